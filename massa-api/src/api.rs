@@ -3,29 +3,42 @@
 use std::net::SocketAddr;
 
 use crate::api_trait::MassaApiServer;
+use crate::operation_status::OperationStatusTracker;
+use crate::public::verify_operations;
 use crate::{APIConfig, ApiServer, ApiV2, StopHandle, API};
 use async_trait::async_trait;
 use jsonrpsee::core::error::SubscriptionClosed;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
+use jsonrpsee::types::error::ErrorObject;
 use jsonrpsee::types::SubscriptionResult;
 use jsonrpsee::SubscriptionSink;
 use massa_consensus_exports::ConsensusChannels;
+use massa_execution_exports::ExecutionChannels;
+use massa_models::api::{OperationInput, OperationStatus, OperationStatusUpdate};
+use massa_models::operation::OperationId;
 use massa_models::version::Version;
+use massa_pool_exports::PoolController;
 use massa_protocol_exports::ProtocolSenders;
+use massa_storage::Storage;
 use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
 use tokio_stream::wrappers::BroadcastStream;
 
 impl API<ApiV2> {
     /// generate a new massa API
     pub fn new(
         consensus_channels: ConsensusChannels,
+        execution_channels: ExecutionChannels,
         protocol_senders: ProtocolSenders,
+        storage: Storage,
         api_settings: APIConfig,
         version: Version,
     ) -> Self {
         API(ApiV2 {
             consensus_channels,
+            execution_channels,
             protocol_senders,
+            storage,
             api_settings,
             version,
         })
@@ -39,7 +52,7 @@ impl ApiServer for API<ApiV2> {
         url: &SocketAddr,
         api_config: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+        crate::serve(self.into_rpc(), url, api_config, None).await
     }
 }
 
@@ -69,6 +82,108 @@ impl MassaApiServer for API<ApiV2> {
         broadcast_via_ws(self.0.protocol_senders.operation_sender.clone(), sink);
         Ok(())
     }
+
+    fn subscribe_new_slot_execution_outputs(&self, sink: SubscriptionSink) -> SubscriptionResult {
+        broadcast_via_ws(
+            self.0
+                .execution_channels
+                .slot_execution_output_sender
+                .clone(),
+            sink,
+        );
+        Ok(())
+    }
+
+    fn subscribe_execution_reorgs(&self, sink: SubscriptionSink) -> SubscriptionResult {
+        broadcast_via_ws(
+            self.0.execution_channels.execution_reorg_sender.clone(),
+            sink,
+        );
+        Ok(())
+    }
+
+    fn send_operations_and_wait(
+        &self,
+        mut sink: SubscriptionSink,
+        ops: Vec<OperationInput>,
+        wait_for_final: bool,
+    ) -> SubscriptionResult {
+        let operations = match verify_operations(ops, &self.0.api_settings) {
+            Ok(operations) => operations,
+            Err(err) => {
+                sink.reject(err)?;
+                return Ok(());
+            }
+        };
+
+        // subscribe before submitting the operations so that no status change is missed
+        let mut block_rx = self.0.consensus_channels.block_sender.subscribe();
+        let mut execution_rx = self
+            .0
+            .execution_channels
+            .slot_execution_output_sender
+            .subscribe();
+        let mut tracker = OperationStatusTracker::new(
+            &operations,
+            self.0.api_settings.thread_count,
+            wait_for_final,
+        );
+        let operation_ids: Vec<OperationId> = operations.iter().map(|op| op.id).collect();
+        let mut to_send = self.0.storage.clone_without_refs();
+        to_send.store_operations(operations);
+        let mut pool_sender = self.0.consensus_channels.pool_command_sender.clone();
+        let mut protocol_sender = self.0.consensus_channels.protocol_command_sender.clone();
+        sink.accept()?;
+
+        tokio::spawn(async move {
+            pool_sender.add_operations(to_send.clone());
+            let propagation =
+                tokio::task::spawn_blocking(move || protocol_sender.propagate_operations(to_send))
+                    .await;
+            if !matches!(propagation, Ok(Ok(()))) {
+                // same code as `ApiError::InternalServerError`
+                sink.close(SubscriptionClosed::Failed(ErrorObject::owned(
+                    -32001,
+                    "failed to propagate operations",
+                    None::<()>,
+                )));
+                return;
+            }
+
+            let mut updates: Vec<OperationStatusUpdate> = operation_ids
+                .into_iter()
+                .map(|operation_id| OperationStatusUpdate {
+                    operation_id,
+                    status: OperationStatus::Pending,
+                })
+                .collect();
+            loop {
+                for update in updates.iter() {
+                    if !matches!(sink.send(update), Ok(true)) {
+                        // the client unsubscribed or the connection is closed
+                        return;
+                    }
+                }
+                if tracker.is_done() {
+                    sink.close(SubscriptionClosed::Success);
+                    return;
+                }
+                updates = tokio::select! {
+                    block = block_rx.recv() => match block {
+                        Ok(block) => tracker.on_block(&block),
+                        Err(RecvError::Lagged(_)) => Vec::new(),
+                        Err(RecvError::Closed) => return,
+                    },
+                    output = execution_rx.recv() => match output {
+                        Ok(output) => tracker.on_slot_execution(&output),
+                        Err(RecvError::Lagged(_)) => Vec::new(),
+                        Err(RecvError::Closed) => return,
+                    },
+                };
+            }
+        });
+        Ok(())
+    }
 }
 
 /// Brodcast the stream(sender) content via a WebSocket
@@ -2,6 +2,7 @@
 //! Json RPC API for a massa-node
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
+use massa_models::api::OperationInput;
 use massa_models::version::Version;
 
 /// Exposed API methods
@@ -42,4 +43,31 @@ pub trait MassaApi {
 		item = Operation
 	)]
     fn subscribe_new_operations(&self);
+
+    /// New outputs of candidate and final slot executions.
+    #[subscription(
+        name = "subscribe_new_slot_execution_outputs" => "new_slot_execution_outputs",
+        unsubscribe = "unsubscribe_new_slot_execution_outputs",
+        item = SlotExecutionOutput
+    )]
+    fn subscribe_new_slot_execution_outputs(&self);
+
+    /// Candidate slot executions cancelled by blockclique changes, with the affected operations.
+    #[subscription(
+        name = "subscribe_execution_reorgs" => "execution_reorgs",
+        unsubscribe = "unsubscribe_execution_reorgs",
+        item = ExecutionReorg
+    )]
+    fn subscribe_execution_reorgs(&self);
+
+    /// Submits operations and notifies the status changes of each of them:
+    /// pending, included in a block, executed speculatively, then final or expired.
+    /// The subscription ends once all the operations are final or expired,
+    /// or once they are executed speculatively if `wait_for_final` is false.
+    #[subscription(
+        name = "send_operations_and_wait" => "operation_status",
+        unsubscribe = "unsubscribe_send_operations_and_wait",
+        item = OperationStatusUpdate
+    )]
+    fn send_operations_and_wait(&self, ops: Vec<OperationInput>, wait_for_final: bool);
 }
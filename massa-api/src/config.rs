@@ -1,6 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_time::MassaTime;
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 
@@ -14,12 +15,16 @@ pub struct APIConfig {
     pub draw_lookahead_period_count: u64,
     /// bind for the private API
     pub bind_private: SocketAddr,
+    /// bearer token required by the private API, if any
+    pub private_auth_token: Option<String>,
     /// bind for the public API
     pub bind_public: SocketAddr,
     /// bind for the Massa API
     pub bind_api: SocketAddr,
     /// max argument count
     pub max_arguments: u64,
+    /// max number of cycles of a selection simulation
+    pub max_simulated_cycles: u64,
     /// openrpc specification path
     pub openrpc_spec_path: PathBuf,
     /// bootstrap whitelist path
@@ -46,6 +51,12 @@ pub struct APIConfig {
     pub enable_http: bool,
     /// whether to enable WS.
     pub enable_ws: bool,
+    /// maximum number of controller requests processed at the same time
+    pub max_in_flight_requests: usize,
+    /// default timeout of a request
+    pub request_timeout: MassaTime,
+    /// timeouts overriding `request_timeout` for specific methods
+    pub method_timeouts: HashMap<String, MassaTime>,
     /// max datastore value length
     pub max_datastore_value_length: u64,
     /// max op datastore entry
@@ -66,4 +77,8 @@ pub struct APIConfig {
     pub t0: MassaTime,
     /// periods per cycle
     pub periods_per_cycle: u64,
+    /// chain id
+    pub chain_id: u64,
+    /// time at which the node started
+    pub node_start_time: MassaTime,
 }
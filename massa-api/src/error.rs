@@ -52,6 +52,10 @@ pub enum ApiError {
     BadRequest(String),
     /// Internal server error: {0}
     InternalServerError(String),
+    /// Too many requests in flight, retry later
+    TooManyRequests,
+    /// Request timed out: {0}
+    Timeout(String),
 }
 
 impl From<ApiError> for JsonRpseeError {
@@ -75,8 +79,18 @@ impl From<ApiError> for JsonRpseeError {
             ApiError::MissingCommandSender(_) => -32017,
             ApiError::MissingConfig(_) => -32018,
             ApiError::WrongAPI => -32019,
+            ApiError::TooManyRequests => -32020,
+            ApiError::Timeout(_) => -32021,
         };
 
-        CallError::Custom(ErrorObject::owned(code, err.to_string(), None::<()>)).into()
+        // execution errors carry their machine-readable code
+        let data = match &err {
+            ApiError::ExecutionError(execution_error) => {
+                Some(serde_json::json!({ "execution_error_code": execution_error.code() }))
+            }
+            _ => None,
+        };
+
+        CallError::Custom(ErrorObject::owned(code, err.to_string(), data)).into()
     }
 }
@@ -5,27 +5,34 @@
 #![warn(unused_crate_dependencies)]
 use crate::api_trait::MassaApiServer;
 use crate::error::ApiError::WrongAPI;
+use crate::worker::ApiWorker;
 use hyper::Method;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use jsonrpsee::proc_macros::rpc;
 use jsonrpsee::server::{AllowHosts, ServerBuilder, ServerHandle};
 use jsonrpsee::RpcModule;
 use massa_consensus_exports::{ConsensusChannels, ConsensusController};
-use massa_execution_exports::ExecutionController;
+use massa_execution_exports::{ExecutionChannels, ExecutionController};
+use massa_factory_exports::FactoryController;
+use massa_final_state::FinalState;
+use massa_logging::LogFilterHandle;
 use massa_models::api::{
-    AddressInfo, BlockInfo, BlockSummary, DatastoreEntryInput, DatastoreEntryOutput,
-    EndorsementInfo, EventFilter, NodeStatus, OperationInfo, OperationInput,
-    ReadOnlyBytecodeExecution, ReadOnlyCall, TimeInterval,
+    AddressHistoryRequest, AddressInfo, BlockInfo, BlockSummary, DatastoreEntryInput,
+    DatastoreEntryOutput, EndorsementInfo, EventFilter, LedgerEntryProofInput, NodeStatus,
+    OperationInfo, OperationInput, ReadOnlyBytecodeExecution, ReadOnlyCall, RollCompoundingMode,
+    SelectionSimulation, SimulatedDraws, SnapshotRequest, StakersPage, StakersRequest,
+    TimeInterval, TransfersPage, TransfersRequest,
 };
 use massa_models::clique::Clique;
 use massa_models::composite::PubkeySig;
 use massa_models::execution::ExecuteReadOnlyResponse;
+use massa_models::ledger_proof::FinalLedgerEntryProof;
 use massa_models::node::NodeId;
 use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::{
-    address::Address,
+    address::{Address, AddressHistoryEntry},
     block::{Block, BlockId},
     endorsement::EndorsementId,
     slot::Slot,
@@ -41,6 +48,7 @@ use parking_lot::RwLock;
 use serde_json::Value;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
+use tower_http::auth::RequireAuthorizationLayer;
 use tower_http::cors::{Any, CorsLayer};
 
 use tokio::sync::mpsc;
@@ -50,8 +58,10 @@ mod api;
 mod api_trait;
 mod config;
 mod error;
+mod operation_status;
 mod private;
 mod public;
+mod worker;
 pub use config::APIConfig;
 
 /// Public API component
@@ -78,6 +88,8 @@ pub struct Public {
     pub network_command_sender: NetworkCommandSender,
     /// our node id
     pub node_id: NodeId,
+    /// limits in-flight controller requests and their duration
+    worker: ApiWorker,
 }
 
 /// Private API content
@@ -92,14 +104,26 @@ pub struct Private {
     pub stop_node_channel: mpsc::Sender<()>,
     /// User wallet
     pub node_wallet: Arc<RwLock<Wallet>>,
+    /// link to the factory component
+    pub factory_controller: Box<dyn FactoryController>,
+    /// handle changing the log filter
+    pub log_filter_handle: LogFilterHandle,
+    /// final state, used to export snapshots
+    pub final_state: Arc<RwLock<FinalState>>,
+    /// limits in-flight controller requests and their duration
+    worker: ApiWorker,
 }
 
 /// API v2 content
 pub struct ApiV2 {
     /// link(channels) to the consensus component
     pub consensus_channels: ConsensusChannels,
+    /// link(channels) to the execution component
+    pub execution_channels: ExecutionChannels,
     /// link(channels) to the protocol component
     pub protocol_senders: ProtocolSenders,
+    /// Massa storage
+    pub storage: Storage,
     /// API settings
     pub api_settings: APIConfig,
     /// node version
@@ -131,10 +155,14 @@ pub trait ApiServer: MassaApiServer {
     ) -> Result<StopHandle, JsonRpseeError>;
 }
 
+/// Starts a JSON-RPC server for `api`.
+///
+/// If `auth_token` is set, every request must carry it as an `Authorization: Bearer` header.
 async fn serve<T>(
     api: RpcModule<T>,
     url: &SocketAddr,
     api_config: &APIConfig,
+    auth_token: Option<&str>,
 ) -> Result<StopHandle, JsonRpseeError> {
     let allowed_hosts = if api_config.allow_hosts.is_empty() {
         AllowHosts::Any
@@ -168,9 +196,11 @@ async fn serve<T>(
         .allow_methods([Method::POST, Method::OPTIONS])
         // Allow requests from any origin
         .allow_origin(Any)
-        .allow_headers([hyper::header::CONTENT_TYPE]);
+        .allow_headers([hyper::header::CONTENT_TYPE, hyper::header::AUTHORIZATION]);
 
-    let middleware = tower::ServiceBuilder::new().layer(cors);
+    let middleware = tower::ServiceBuilder::new()
+        .layer(cors)
+        .option_layer(auth_token.map(RequireAuthorizationLayer::bearer));
 
     let server = server_builder
         .set_middleware(middleware)
@@ -241,6 +271,50 @@ pub trait MassaRpc {
     #[method(name = "get_staking_addresses")]
     async fn get_staking_addresses(&self) -> RpcResult<PreHashSet<Address>>;
 
+    /// Resume block and endorsement production on a node in standby mode.
+    /// No confirmation to expect.
+    #[method(name = "node_staking_activate")]
+    async fn node_staking_activate(&self) -> RpcResult<()>;
+
+    /// Put the node in standby mode: it keeps in sync but withholds block and endorsement production.
+    /// No confirmation to expect.
+    #[method(name = "node_staking_standby")]
+    async fn node_staking_standby(&self) -> RpcResult<()>;
+
+    /// Notify a node in standby mode that the primary node is alive.
+    /// No confirmation to expect.
+    #[method(name = "node_staking_heartbeat")]
+    async fn node_staking_heartbeat(&self) -> RpcResult<()>;
+
+    /// Replace the log filter by comma-separated `target=level` directives, e.g. `massa_bootstrap=debug,info`.
+    /// No confirmation to expect.
+    #[method(name = "node_set_log_filter")]
+    async fn node_set_log_filter(&self, arg: String) -> RpcResult<()>;
+
+    /// Log one `massa_trace` event out of the given rate, or none if the rate is 0.
+    /// No confirmation to expect.
+    #[method(name = "node_set_trace_sampling")]
+    async fn node_set_trace_sampling(&self, arg: u64) -> RpcResult<()>;
+
+    /// Set how the deferred credits paid to the staking addresses are converted into rolls at the start of each cycle.
+    /// No confirmation to expect.
+    #[method(name = "node_set_roll_compounding")]
+    async fn node_set_roll_compounding(&self, arg: RollCompoundingMode) -> RpcResult<()>;
+
+    /// Write a snapshot of the final state to the given file of the node machine at the given final slot.
+    /// The snapshot is written once the slot is final.
+    #[method(name = "node_export_snapshot")]
+    async fn node_export_snapshot(&self, arg: SnapshotRequest) -> RpcResult<()>;
+
+    /// Write the peer database to the given file of the node machine.
+    #[method(name = "node_export_peers")]
+    async fn node_export_peers(&self, arg: String) -> RpcResult<()>;
+
+    /// Merge the peers of the given file of the node machine with the peer database.
+    /// Returns the number of previously unknown peers.
+    #[method(name = "node_import_peers")]
+    async fn node_import_peers(&self, arg: String) -> RpcResult<usize>;
+
     /// Bans given IP address(es).
     /// No confirmation to expect.
     #[method(name = "node_ban_by_ip")]
@@ -318,6 +392,30 @@ pub trait MassaRpc {
     #[method(name = "get_stakers")]
     async fn get_stakers(&self) -> RpcResult<Vec<(Address, u64)>>;
 
+    /// Returns a page of the stakers of a cycle, sorted by decreasing active roll count,
+    /// with their active rolls, candidate rolls and production stats for that cycle.
+    #[method(name = "get_cycle_stakers")]
+    async fn get_cycle_stakers(&self, arg: StakersRequest) -> RpcResult<StakersPage>;
+
+    /// Returns the balance and roll changes of an address in the recent final slots,
+    /// optionally restricted to a slot range.
+    #[method(name = "get_address_history")]
+    async fn get_address_history(
+        &self,
+        arg: AddressHistoryRequest,
+    ) -> RpcResult<Vec<AddressHistoryEntry>>;
+
+    /// Returns a page of the coins sent and received by an address through the operations
+    /// of the recent final slots, newest first, including the failed transfers.
+    #[method(name = "get_transactions_by_address")]
+    async fn get_transactions_by_address(&self, arg: TransfersRequest) -> RpcResult<TransfersPage>;
+
+    /// Runs the selector over a hypothetical roll distribution and seed for a number of cycles.
+    /// Returns the draw counts and frequencies of each address having rolls.
+    #[method(name = "simulate_selections")]
+    async fn simulate_selections(&self, arg: SelectionSimulation)
+        -> RpcResult<Vec<SimulatedDraws>>;
+
     /// Returns operation(s) information associated to a given list of operation(s) ID(s).
     #[method(name = "get_operations")]
     async fn get_operations(&self, arg: Vec<OperationId>) -> RpcResult<Vec<OperationInfo>>;
@@ -347,6 +445,14 @@ pub trait MassaRpc {
         arg: Vec<DatastoreEntryInput>,
     ) -> RpcResult<Vec<DatastoreEntryOutput>>;
 
+    /// Get proofs of final ledger entries (balances or datastore entries)
+    /// against the latest final state hash.
+    #[method(name = "get_ledger_entry_proof")]
+    async fn get_ledger_entry_proof(
+        &self,
+        arg: Vec<LedgerEntryProofInput>,
+    ) -> RpcResult<Vec<FinalLedgerEntryProof>>;
+
     /// Get addresses.
     #[method(name = "get_addresses")]
     async fn get_addresses(&self, arg: Vec<Address>) -> RpcResult<Vec<AddressInfo>>;
@@ -0,0 +1,198 @@
+//! Copyright (c) 2022 MASSA LABS <info@massa.net>
+//! Follows the status of submitted operations through the block and slot execution broadcasts
+
+use massa_models::{
+    api::{OperationStatus, OperationStatusUpdate},
+    block::Block,
+    execution::{SlotExecutionOutput, SlotExecutionStatus},
+    operation::{OperationId, WrappedOperation},
+    prehash::PreHashMap,
+};
+
+/// Status of the submitted operations that did not reach their last status yet
+pub(crate) struct OperationStatusTracker {
+    /// thread and last valid period of each followed operation
+    followed: PreHashMap<OperationId, (u8, u64)>,
+    /// if false, the operations stop being followed once executed speculatively
+    wait_for_final: bool,
+}
+
+impl OperationStatusTracker {
+    /// Starts following the given operations
+    pub fn new(operations: &[WrappedOperation], thread_count: u8, wait_for_final: bool) -> Self {
+        OperationStatusTracker {
+            followed: operations
+                .iter()
+                .map(|op| {
+                    (
+                        op.id,
+                        (
+                            op.creator_address.get_thread(thread_count),
+                            op.content.expire_period,
+                        ),
+                    )
+                })
+                .collect(),
+            wait_for_final,
+        }
+    }
+
+    /// Returns true once all the operations reached their last status
+    pub fn is_done(&self) -> bool {
+        self.followed.is_empty()
+    }
+
+    /// Status changes of the followed operations included in a new block
+    pub fn on_block(&self, block: &Block) -> Vec<OperationStatusUpdate> {
+        block
+            .operations
+            .iter()
+            .filter(|op_id| self.followed.contains_key(op_id))
+            .map(|op_id| OperationStatusUpdate {
+                operation_id: *op_id,
+                status: OperationStatus::InBlock {
+                    block_id: block.header.id,
+                    slot: block.header.content.slot,
+                },
+            })
+            .collect()
+    }
+
+    /// Status changes of the followed operations caused by a slot execution.
+    /// The operations reaching their last status stop being followed.
+    pub fn on_slot_execution(
+        &mut self,
+        output: &SlotExecutionOutput,
+    ) -> Vec<OperationStatusUpdate> {
+        let mut updates = Vec::new();
+        if let Some(block_id) = output.block_id {
+            for op_id in output.executed_ops.iter() {
+                if !self.followed.contains_key(op_id) {
+                    continue;
+                }
+                let status = match output.status {
+                    SlotExecutionStatus::Candidate => OperationStatus::CandidateExecuted {
+                        block_id,
+                        slot: output.slot,
+                    },
+                    SlotExecutionStatus::Final => OperationStatus::Final {
+                        block_id,
+                        slot: output.slot,
+                    },
+                };
+                if output.status == SlotExecutionStatus::Final || !self.wait_for_final {
+                    self.followed.remove(op_id);
+                }
+                updates.push(OperationStatusUpdate {
+                    operation_id: *op_id,
+                    status,
+                });
+            }
+        }
+
+        // a final slot past the validity period of an operation in its thread
+        // means it can not be executed anymore
+        if output.status == SlotExecutionStatus::Final {
+            let expired: Vec<OperationId> = self
+                .followed
+                .iter()
+                .filter(|(_, (thread, expire_period))| {
+                    *thread == output.slot.thread && output.slot.period > *expire_period
+                })
+                .map(|(op_id, _)| *op_id)
+                .collect();
+            for op_id in expired {
+                self.followed.remove(&op_id);
+                updates.push(OperationStatusUpdate {
+                    operation_id: op_id,
+                    status: OperationStatus::Expired,
+                });
+            }
+        }
+        updates
+    }
+}
+
+#[test]
+fn test_operation_status_tracker() {
+    use massa_models::{
+        address::Address,
+        amount::Amount,
+        block::BlockId,
+        operation::{Operation, OperationSerializer, OperationType},
+        slot::Slot,
+        wrapped::WrappedContent,
+    };
+    use massa_signature::KeyPair;
+    use std::collections::VecDeque;
+
+    let keypair = KeyPair::generate();
+    let new_op = |expire_period| {
+        Operation::new_wrapped(
+            Operation {
+                fee: Amount::zero(),
+                expire_period,
+                op: OperationType::Transaction {
+                    recipient_address: Address::from_public_key(&keypair.get_public_key()),
+                    amount: Amount::zero(),
+                },
+            },
+            OperationSerializer::new(),
+            &keypair,
+        )
+        .unwrap()
+    };
+    let (executed, expiring) = (new_op(10), new_op(3));
+    let thread = executed.creator_address.get_thread(32);
+    let block_id = BlockId(massa_hash::Hash::compute_from(b"block"));
+    let output = |period, status, executed_ops| SlotExecutionOutput {
+        slot: Slot::new(period, thread),
+        block_id: Some(block_id),
+        status,
+        ledger_changes: Vec::new(),
+        roll_changes: Default::default(),
+        executed_ops,
+        gas_used: 0,
+        events: VecDeque::new(),
+    };
+
+    let mut tracker = OperationStatusTracker::new(&[executed.clone(), expiring.clone()], 32, true);
+    let updates = tracker.on_slot_execution(&output(
+        2,
+        SlotExecutionStatus::Candidate,
+        vec![executed.id],
+    ));
+    assert_eq!(
+        updates[0].status,
+        OperationStatus::CandidateExecuted {
+            block_id,
+            slot: Slot::new(2, thread)
+        }
+    );
+    assert!(!tracker.is_done());
+
+    // the final execution of the slot settles the executed operation,
+    // and a later final slot in the thread settles the expired one
+    let updates =
+        tracker.on_slot_execution(&output(2, SlotExecutionStatus::Final, vec![executed.id]));
+    assert_eq!(updates.len(), 1);
+    assert!(matches!(updates[0].status, OperationStatus::Final { .. }));
+    let updates = tracker.on_slot_execution(&output(4, SlotExecutionStatus::Final, vec![]));
+    assert_eq!(
+        updates,
+        vec![OperationStatusUpdate {
+            operation_id: expiring.id,
+            status: OperationStatus::Expired
+        }]
+    );
+    assert!(tracker.is_done());
+
+    // without waiting for finality, the speculative execution is the last status
+    let mut tracker = OperationStatusTracker::new(&[executed.clone()], 32, false);
+    tracker.on_slot_execution(&output(
+        2,
+        SlotExecutionStatus::Candidate,
+        vec![executed.id],
+    ));
+    assert!(tracker.is_done());
+}
@@ -2,25 +2,32 @@
 
 use crate::config::APIConfig;
 use crate::error::ApiError;
+use crate::worker::ApiWorker;
 use crate::{MassaRpcServer, Private, RpcServer, StopHandle, Value, API};
 
 use async_trait::async_trait;
 use itertools::Itertools;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use massa_execution_exports::ExecutionController;
+use massa_factory_exports::FactoryController;
+use massa_final_state::FinalState;
+use massa_logging::{set_massa_trace_sampling, LogFilterHandle};
 use massa_models::api::{
-    AddressInfo, BlockInfo, BlockSummary, DatastoreEntryInput, DatastoreEntryOutput,
-    EndorsementInfo, EventFilter, ListType, NodeStatus, OperationInfo, OperationInput,
-    ReadOnlyBytecodeExecution, ReadOnlyCall, ScrudOperation, TimeInterval,
+    AddressHistoryRequest, AddressInfo, BlockInfo, BlockSummary, DatastoreEntryInput,
+    DatastoreEntryOutput, EndorsementInfo, EventFilter, LedgerEntryProofInput, ListType,
+    NodeStatus, OperationInfo, OperationInput, ReadOnlyBytecodeExecution, ReadOnlyCall,
+    RollCompoundingMode, ScrudOperation, SelectionSimulation, SimulatedDraws, SnapshotRequest,
+    StakersPage, StakersRequest, TimeInterval, TransfersPage, TransfersRequest,
 };
 use massa_models::clique::Clique;
 use massa_models::composite::PubkeySig;
 use massa_models::execution::ExecuteReadOnlyResponse;
+use massa_models::ledger_proof::FinalLedgerEntryProof;
 use massa_models::node::NodeId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::{
-    address::Address,
+    address::{Address, AddressHistoryEntry},
     block::{Block, BlockId},
     endorsement::EndorsementId,
     operation::OperationId,
@@ -46,15 +53,22 @@ impl API<Private> {
         execution_controller: Box<dyn ExecutionController>,
         api_settings: APIConfig,
         node_wallet: Arc<RwLock<Wallet>>,
+        factory_controller: Box<dyn FactoryController>,
+        log_filter_handle: LogFilterHandle,
+        final_state: Arc<RwLock<FinalState>>,
     ) -> (Self, mpsc::Receiver<()>) {
         let (stop_node_channel, rx) = mpsc::channel(1);
         (
             API(Private {
                 network_command_sender,
                 execution_controller,
+                worker: ApiWorker::new(&api_settings),
                 api_settings,
                 stop_node_channel,
                 node_wallet,
+                factory_controller,
+                log_filter_handle,
+                final_state,
             }),
             rx,
         )
@@ -68,7 +82,13 @@ impl RpcServer for API<Private> {
         url: &SocketAddr,
         settings: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, settings).await
+        crate::serve(
+            self.into_rpc(),
+            url,
+            settings,
+            settings.private_auth_token.as_deref(),
+        )
+        .await
     }
 }
 
@@ -133,6 +153,86 @@ impl MassaRpcServer for API<Private> {
         Ok(w_wallet.get_wallet_address_list())
     }
 
+    async fn node_staking_activate(&self) -> RpcResult<()> {
+        self.0.factory_controller.activate();
+        Ok(())
+    }
+
+    async fn node_staking_standby(&self) -> RpcResult<()> {
+        self.0.factory_controller.standby();
+        Ok(())
+    }
+
+    async fn node_staking_heartbeat(&self) -> RpcResult<()> {
+        self.0.factory_controller.heartbeat();
+        Ok(())
+    }
+
+    async fn node_set_log_filter(&self, directives: String) -> RpcResult<()> {
+        self.0
+            .log_filter_handle
+            .set_filter(&directives)
+            .map_err(|e| ApiError::BadRequest(e).into())
+    }
+
+    async fn node_set_trace_sampling(&self, rate: u64) -> RpcResult<()> {
+        set_massa_trace_sampling(rate);
+        Ok(())
+    }
+
+    async fn node_set_roll_compounding(&self, mode: RollCompoundingMode) -> RpcResult<()> {
+        self.0.factory_controller.set_roll_compounding(mode);
+        Ok(())
+    }
+
+    async fn node_export_snapshot(&self, request: SnapshotRequest) -> RpcResult<()> {
+        let final_state = self.0.final_state.clone();
+        // the export reads the whole final state if the slot is already final: do not block the API runtime
+        self.0
+            .worker
+            .run_blocking("node_export_snapshot", move || {
+                let path = PathBuf::from(request.path);
+                // the readers of the final state are not blocked while the snapshot is written
+                let read_final_state = final_state.read();
+                if request.slot == read_final_state.slot {
+                    return read_final_state.export_snapshot(&path);
+                }
+                drop(read_final_state);
+                // the write lock is taken to queue the request, unless the slot became final meanwhile
+                final_state.write().request_snapshot(request.slot, path)
+            })
+            .await?
+            .map_err(|e| ApiError::InternalServerError(e.to_string()).into())
+    }
+
+    async fn node_export_peers(&self, path: String) -> RpcResult<()> {
+        let network_command_sender = self.0.network_command_sender.clone();
+        self.0
+            .worker
+            .run("node_export_peers", async {
+                network_command_sender
+                    .export_peers(PathBuf::from(path))
+                    .await
+                    .map_err(ApiError::NetworkError)
+            })
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn node_import_peers(&self, path: String) -> RpcResult<usize> {
+        let network_command_sender = self.0.network_command_sender.clone();
+        self.0
+            .worker
+            .run("node_import_peers", async {
+                network_command_sender
+                    .import_peers(PathBuf::from(path))
+                    .await
+                    .map_err(ApiError::NetworkError)
+            })
+            .await
+            .map_err(|e| e.into())
+    }
+
     async fn node_ban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
         let network_command_sender = self.0.network_command_sender.clone();
         network_command_sender
@@ -177,6 +277,25 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<(Address, u64)>>()
     }
 
+    async fn get_cycle_stakers(&self, _: StakersRequest) -> RpcResult<StakersPage> {
+        crate::wrong_api::<StakersPage>()
+    }
+
+    async fn get_address_history(
+        &self,
+        _: AddressHistoryRequest,
+    ) -> RpcResult<Vec<AddressHistoryEntry>> {
+        crate::wrong_api::<Vec<AddressHistoryEntry>>()
+    }
+
+    async fn get_transactions_by_address(&self, _: TransfersRequest) -> RpcResult<TransfersPage> {
+        crate::wrong_api::<TransfersPage>()
+    }
+
+    async fn simulate_selections(&self, _: SelectionSimulation) -> RpcResult<Vec<SimulatedDraws>> {
+        crate::wrong_api::<Vec<SimulatedDraws>>()
+    }
+
     async fn get_operations(&self, _: Vec<OperationId>) -> RpcResult<Vec<OperationInfo>> {
         crate::wrong_api::<Vec<OperationInfo>>()
     }
@@ -204,6 +323,13 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api()
     }
 
+    async fn get_ledger_entry_proof(
+        &self,
+        _: Vec<LedgerEntryProofInput>,
+    ) -> RpcResult<Vec<FinalLedgerEntryProof>> {
+        crate::wrong_api()
+    }
+
     async fn get_addresses(&self, _: Vec<Address>) -> RpcResult<Vec<AddressInfo>> {
         crate::wrong_api::<Vec<AddressInfo>>()
     }
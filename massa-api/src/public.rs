@@ -3,6 +3,7 @@
 
 use crate::config::APIConfig;
 use crate::error::ApiError;
+use crate::worker::ApiWorker;
 use crate::{MassaRpcServer, Public, RpcServer, StopHandle, Value, API};
 use async_trait::async_trait;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
@@ -12,10 +13,13 @@ use massa_execution_exports::{
     ExecutionController, ExecutionStackElement, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
 };
 use massa_models::api::{
-    BlockGraphStatus, DatastoreEntryInput, DatastoreEntryOutput, OperationInput,
-    ReadOnlyBytecodeExecution, ReadOnlyCall, SlotAmount,
+    AddressHistoryRequest, BlockGraphStatus, DatastoreEntryInput, DatastoreEntryOutput,
+    LedgerEntryProofInput, OperationInput, ReadOnlyBytecodeExecution, ReadOnlyCall,
+    RollCompoundingMode, SelectionSimulation, SimulatedDraws, SlotAmount, SnapshotRequest,
+    StakerInfo, StakersPage, StakersRequest, TransfersPage, TransfersRequest,
 };
 use massa_models::execution::ReadOnlyResult;
+use massa_models::ledger_proof::FinalLedgerEntryProof;
 use massa_models::operation::OperationDeserializer;
 use massa_models::wrapped::WrappedDeserializer;
 use massa_models::{
@@ -29,7 +33,7 @@ use massa_serialization::{DeserializeError, Deserializer};
 use itertools::{izip, Itertools};
 use massa_models::datastore::DatastoreDeserializer;
 use massa_models::{
-    address::Address,
+    address::{Address, AddressHistoryEntry},
     api::{
         AddressInfo, BlockInfo, BlockInfoContent, BlockSummary, EndorsementInfo, EventFilter,
         NodeStatus, OperationInfo, TimeInterval,
@@ -52,7 +56,7 @@ use massa_network_exports::{NetworkCommandSender, NetworkConfig};
 use massa_pool_exports::PoolController;
 use massa_signature::KeyPair;
 use massa_storage::Storage;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SystemClock};
 use std::collections::BTreeMap;
 use std::net::{IpAddr, SocketAddr};
 
@@ -73,6 +77,7 @@ impl API<Public> {
     ) -> Self {
         API(Public {
             consensus_controller,
+            worker: ApiWorker::new(&api_settings),
             api_settings,
             pool_command_sender,
             network_settings,
@@ -85,6 +90,21 @@ impl API<Public> {
             storage,
         })
     }
+
+    /// Returns the cycle of the latest block slot at the current time
+    fn get_current_cycle(&self) -> Result<u64, ApiError> {
+        let cfg = &self.0.api_settings;
+        let now = MassaTime::now()?;
+        let latest_block_slot = get_latest_block_slot_at_timestamp(
+            cfg.thread_count,
+            cfg.t0,
+            cfg.genesis_timestamp,
+            now,
+        )?;
+        Ok(latest_block_slot
+            .unwrap_or_else(|| Slot::new(0, 0))
+            .get_cycle(cfg.periods_per_cycle))
+    }
 }
 
 #[async_trait]
@@ -94,7 +114,7 @@ impl RpcServer for API<Public> {
         url: &SocketAddr,
         api_config: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+        crate::serve(self.into_rpc(), url, api_config, None).await
     }
 }
 
@@ -173,7 +193,14 @@ impl MassaRpcServer for API<Public> {
             };
 
             // run
-            let result = self.0.execution_controller.execute_readonly_request(req);
+            let execution_controller = self.0.execution_controller.clone();
+            let result = self
+                .0
+                .worker
+                .run_blocking("execute_read_only_bytecode", move || {
+                    execution_controller.execute_readonly_request(req)
+                })
+                .await?;
 
             // map result
             let result = ExecuteReadOnlyResponse {
@@ -185,6 +212,14 @@ impl MassaRpcServer for API<Public> {
                     |res| ReadOnlyResult::Ok(res.call_result.clone()),
                 ),
                 gas_cost: result.as_ref().map_or_else(|_| 0, |v| v.gas_cost),
+                error_code: result.as_ref().err().map(|err| err.code().to_string()),
+                call_trace: result
+                    .as_ref()
+                    .map_or_else(|_| Default::default(), |v| v.call_trace.clone()),
+                ledger_changes: result.as_ref().map_or_else(
+                    |_| Default::default(),
+                    |v| v.out.state_changes.ledger_changes.summarize(),
+                ),
                 output_events: result
                     .map_or_else(|_| Default::default(), |mut v| v.out.events.take()),
             };
@@ -248,7 +283,14 @@ impl MassaRpcServer for API<Public> {
             };
 
             // run
-            let result = self.0.execution_controller.execute_readonly_request(req);
+            let execution_controller = self.0.execution_controller.clone();
+            let result = self
+                .0
+                .worker
+                .run_blocking("execute_read_only_call", move || {
+                    execution_controller.execute_readonly_request(req)
+                })
+                .await?;
 
             // map result
             let result = ExecuteReadOnlyResponse {
@@ -260,6 +302,14 @@ impl MassaRpcServer for API<Public> {
                     |res| ReadOnlyResult::Ok(res.call_result.clone()),
                 ),
                 gas_cost: result.as_ref().map_or_else(|_| 0, |v| v.gas_cost),
+                error_code: result.as_ref().err().map(|err| err.code().to_string()),
+                call_trace: result
+                    .as_ref()
+                    .map_or_else(|_| Default::default(), |v| v.call_trace.clone()),
+                ledger_changes: result.as_ref().map_or_else(
+                    |_| Default::default(),
+                    |v| v.out.state_changes.ledger_changes.summarize(),
+                ),
                 output_events: result
                     .map_or_else(|_| Default::default(), |mut v| v.out.events.take()),
             };
@@ -279,6 +329,42 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<PreHashSet<Address>>()
     }
 
+    async fn node_staking_activate(&self) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_staking_standby(&self) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_staking_heartbeat(&self) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_set_log_filter(&self, _: String) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_set_trace_sampling(&self, _: u64) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_set_roll_compounding(&self, _: RollCompoundingMode) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_export_snapshot(&self, _: SnapshotRequest) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_export_peers(&self, _: String) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_import_peers(&self, _: String) -> RpcResult<usize> {
+        crate::wrong_api::<usize>()
+    }
+
     async fn node_ban_by_ip(&self, _: Vec<IpAddr>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
@@ -328,10 +414,16 @@ impl MassaRpcServer for API<Public> {
             Err(e) => return Err(ApiError::ConsensusError(e).into()),
         };
 
-        let (network_stats_result, peers_result) = tokio::join!(
-            network_command_sender.get_network_stats(),
-            network_command_sender.get_peers()
-        );
+        let (network_stats_result, peers_result) = self
+            .0
+            .worker
+            .run("get_status", async {
+                Ok(tokio::join!(
+                    network_command_sender.get_network_stats(),
+                    network_command_sender.get_peers()
+                ))
+            })
+            .await?;
 
         let network_stats = match network_stats_result {
             Ok(network_stats) => network_stats,
@@ -348,6 +440,8 @@ impl MassaRpcServer for API<Public> {
             pool_command_sender.get_endorsement_count(),
         );
 
+        let operation_latency_stats = self.0.storage.read_operations().get_latency_stats().clone();
+
         let next_slot_result = last_slot
             .unwrap_or_else(|| Slot::new(0, 0))
             .get_next_slot(api_settings.thread_count);
@@ -371,11 +465,14 @@ impl MassaRpcServer for API<Public> {
             node_id,
             node_ip: network_config.routable_ip,
             version,
+            chain_id: api_settings.chain_id,
             current_time: now,
+            uptime: now.saturating_sub(api_settings.node_start_time),
             connected_nodes,
             last_slot,
             next_slot,
             execution_stats,
+            operation_latency_stats,
             consensus_stats,
             network_stats,
             pool_stats,
@@ -393,26 +490,7 @@ impl MassaRpcServer for API<Public> {
 
     async fn get_stakers(&self) -> RpcResult<Vec<(Address, u64)>> {
         let execution_controller = self.0.execution_controller.clone();
-        let cfg = self.0.api_settings.clone();
-
-        let now = match MassaTime::now() {
-            Ok(now) => now,
-            Err(e) => return Err(ApiError::TimeError(e).into()),
-        };
-
-        let latest_block_slot_at_timestamp_result = get_latest_block_slot_at_timestamp(
-            cfg.thread_count,
-            cfg.t0,
-            cfg.genesis_timestamp,
-            now,
-        );
-
-        let curr_cycle = match latest_block_slot_at_timestamp_result {
-            Ok(curr_cycle) => curr_cycle
-                .unwrap_or_else(|| Slot::new(0, 0))
-                .get_cycle(cfg.periods_per_cycle),
-            Err(e) => return Err(ApiError::ModelsError(e).into()),
-        };
+        let curr_cycle = self.get_current_cycle()?;
 
         let mut staker_vec = execution_controller
             .get_cycle_active_rolls(curr_cycle)
@@ -423,6 +501,179 @@ impl MassaRpcServer for API<Public> {
         Ok(staker_vec)
     }
 
+    async fn get_cycle_stakers(&self, request: StakersRequest) -> RpcResult<StakersPage> {
+        if request.limit > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest(format!(
+                "too many stakers requested (max {})",
+                self.0.api_settings.max_arguments
+            ))
+            .into());
+        }
+        let cycle = match request.cycle {
+            Some(cycle) => cycle,
+            None => self.get_current_cycle()?,
+        };
+
+        // sort by decreasing active roll count, then by address for a stable pagination
+        let mut active_rolls: Vec<(Address, u64)> = self
+            .0
+            .execution_controller
+            .get_cycle_active_rolls(cycle)
+            .into_iter()
+            .collect();
+        active_rolls.sort_by(|(_, rolls_a), (_, rolls_b)| rolls_b.cmp(rolls_a));
+        let total_count = active_rolls.len() as u64;
+        let page: Vec<(Address, u64)> = active_rolls
+            .into_iter()
+            .skip(request.offset.try_into().unwrap_or(usize::MAX))
+            .take(request.limit as usize)
+            .collect();
+
+        // candidate rolls and production stats of the page stakers
+        let addresses: Vec<Address> = page.iter().map(|(address, _)| *address).collect();
+        let execution_controller = self.0.execution_controller.clone();
+        let infos = self
+            .0
+            .worker
+            .run_blocking("get_cycle_stakers", move || {
+                execution_controller.get_addresses_infos(&addresses)
+            })
+            .await?;
+        let stakers = page
+            .into_iter()
+            .zip(infos)
+            .map(|((address, active_rolls), info)| {
+                let (ok_count, nok_count) = info
+                    .cycle_infos
+                    .iter()
+                    .find(|cycle_info| cycle_info.cycle == cycle)
+                    .map_or((0, 0), |cycle_info| {
+                        (cycle_info.ok_count, cycle_info.nok_count)
+                    });
+                StakerInfo {
+                    address,
+                    active_rolls,
+                    candidate_rolls: info.candidate_roll_count,
+                    ok_count,
+                    nok_count,
+                }
+            })
+            .collect();
+
+        Ok(StakersPage {
+            cycle,
+            total_count,
+            stakers,
+        })
+    }
+
+    async fn get_address_history(
+        &self,
+        request: AddressHistoryRequest,
+    ) -> RpcResult<Vec<AddressHistoryEntry>> {
+        if let (Some(from_slot), Some(to_slot)) = (request.from_slot, request.to_slot) {
+            if from_slot > to_slot {
+                return Err(ApiError::BadRequest(
+                    "from_slot must not be after to_slot".to_string(),
+                )
+                .into());
+            }
+        }
+        let execution_controller = self.0.execution_controller.clone();
+        Ok(self
+            .0
+            .worker
+            .run_blocking("get_address_history", move || {
+                execution_controller.get_address_history(
+                    &request.address,
+                    request.from_slot,
+                    request.to_slot,
+                )
+            })
+            .await?)
+    }
+
+    async fn get_transactions_by_address(
+        &self,
+        request: TransfersRequest,
+    ) -> RpcResult<TransfersPage> {
+        if request.limit > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest(format!(
+                "too many transfers requested (max {})",
+                self.0.api_settings.max_arguments
+            ))
+            .into());
+        }
+        let execution_controller = self.0.execution_controller.clone();
+        let (total_count, transfers) = self
+            .0
+            .worker
+            .run_blocking("get_transactions_by_address", move || {
+                execution_controller.get_transactions_by_address(
+                    &request.address,
+                    request.offset.try_into().unwrap_or(usize::MAX),
+                    request.limit as usize,
+                )
+            })
+            .await?;
+        Ok(TransfersPage {
+            total_count: total_count as u64,
+            transfers,
+        })
+    }
+
+    async fn simulate_selections(
+        &self,
+        simulation: SelectionSimulation,
+    ) -> RpcResult<Vec<SimulatedDraws>> {
+        check_selection_simulation(
+            &simulation,
+            self.0.api_settings.max_arguments,
+            self.0.api_settings.max_simulated_cycles,
+        )?;
+
+        let rolls: BTreeMap<Address, u64> = simulation.rolls.into_iter().collect();
+        let selector_controller = self.0.selector_controller.clone();
+        let simulated_rolls = rolls.clone();
+        let draw_counts = self
+            .0
+            .worker
+            .run_blocking("simulate_selections", move || {
+                selector_controller.simulate_draws(
+                    simulated_rolls,
+                    simulation.seed,
+                    simulation.cycle_count,
+                )
+            })
+            .await?
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+        // every simulated slot has one block producer and `endorsement_count` endorsers
+        let total_block_draws: u64 = draw_counts.values().map(|c| c.block_draws).sum();
+        let total_endorsement_draws: u64 = draw_counts.values().map(|c| c.endorsement_draws).sum();
+        let frequency = |draws: u64, total: u64| {
+            if total == 0 {
+                0.0
+            } else {
+                draws as f64 / total as f64
+            }
+        };
+        Ok(draw_counts
+            .into_iter()
+            .map(|(address, counts)| SimulatedDraws {
+                address,
+                roll_count: rolls.get(&address).copied().unwrap_or_default(),
+                block_draws: counts.block_draws,
+                block_draw_frequency: frequency(counts.block_draws, total_block_draws),
+                endorsement_draws: counts.endorsement_draws,
+                endorsement_draw_frequency: frequency(
+                    counts.endorsement_draws,
+                    total_endorsement_draws,
+                ),
+            })
+            .collect())
+    }
+
     async fn get_operations(&self, ops: Vec<OperationId>) -> RpcResult<Vec<OperationInfo>> {
         // get the operations and the list of blocks that contain them from storage
         let storage_info: Vec<(WrappedOperation, PreHashSet<BlockId>)> = {
@@ -662,10 +913,14 @@ impl MassaRpcServer for API<Public> {
             Err(e) => return Err(ApiError::ModelsError(e).into()),
         };
 
-        let graph = match consensus_controller.get_block_graph_status(start_slot, end_slot) {
-            Ok(graph) => graph,
-            Err(e) => return Err(ApiError::ConsensusError(e).into()),
-        };
+        let graph = self
+            .0
+            .worker
+            .run_blocking("get_graph_interval", move || {
+                consensus_controller.get_block_graph_status(start_slot, end_slot)
+            })
+            .await?
+            .map_err(ApiError::ConsensusError)?;
 
         let mut res = Vec::with_capacity(graph.active_blocks.len());
         let blockclique = graph
@@ -705,13 +960,18 @@ impl MassaRpcServer for API<Public> {
         entries: Vec<DatastoreEntryInput>,
     ) -> RpcResult<Vec<DatastoreEntryOutput>> {
         let execution_controller = self.0.execution_controller.clone();
-        Ok(execution_controller
-            .get_final_and_active_data_entry(
-                entries
-                    .into_iter()
-                    .map(|input| (input.address, input.key))
-                    .collect::<Vec<_>>(),
-            )
+        Ok(self
+            .0
+            .worker
+            .run_blocking("get_datastore_entries", move || {
+                execution_controller.get_final_and_active_data_entry(
+                    entries
+                        .into_iter()
+                        .map(|input| (input.address, input.key))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .await?
             .into_iter()
             .map(|output| DatastoreEntryOutput {
                 final_value: output.0,
@@ -720,6 +980,28 @@ impl MassaRpcServer for API<Public> {
             .collect())
     }
 
+    async fn get_ledger_entry_proof(
+        &self,
+        entries: Vec<LedgerEntryProofInput>,
+    ) -> RpcResult<Vec<FinalLedgerEntryProof>> {
+        if entries.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        let execution_controller = self.0.execution_controller.clone();
+        Ok(self
+            .0
+            .worker
+            .run_blocking("get_ledger_entry_proof", move || {
+                execution_controller.get_final_ledger_entry_proofs(
+                    entries
+                        .into_iter()
+                        .map(|input| (input.address, input.key))
+                        .collect(),
+                )
+            })
+            .await?)
+    }
+
     async fn get_addresses(&self, addresses: Vec<Address>) -> RpcResult<Vec<AddressInfo>> {
         // get info from storage about which blocks the addresses have created
         let created_blocks: Vec<PreHashSet<BlockId>> = {
@@ -769,6 +1051,7 @@ impl MassaRpcServer for API<Public> {
                 self.0.api_settings.thread_count,
                 self.0.api_settings.t0,
                 self.0.api_settings.genesis_timestamp,
+                &SystemClock,
             )
             .expect("could not get latest current slot")
             .unwrap_or_else(|| Slot::new(0, 0));
@@ -856,59 +1139,19 @@ impl MassaRpcServer for API<Public> {
     async fn send_operations(&self, ops: Vec<OperationInput>) -> RpcResult<Vec<OperationId>> {
         let mut cmd_sender = self.0.pool_command_sender.clone();
         let mut protocol_sender = self.0.protocol_command_sender.clone();
-        let api_cfg = self.0.api_settings.clone();
         let mut to_send = self.0.storage.clone_without_refs();
 
-        if ops.len() as u64 > api_cfg.max_arguments {
-            return Err(ApiError::BadRequest("too many arguments".into()).into());
-        }
-        let operation_deserializer = WrappedDeserializer::new(OperationDeserializer::new(
-            api_cfg.max_datastore_value_length,
-            api_cfg.max_function_name_length,
-            api_cfg.max_parameter_size,
-            api_cfg.max_op_datastore_entry_count,
-            api_cfg.max_op_datastore_key_length,
-            api_cfg.max_op_datastore_value_length,
-        ));
-        let verified_ops = ops
-            .into_iter()
-            .map(|op_input| {
-                let mut op_serialized = Vec::new();
-                op_serialized.extend(op_input.signature.to_bytes());
-                op_serialized.extend(op_input.creator_public_key.to_bytes());
-                op_serialized.extend(op_input.serialized_content);
-                let (rest, op): (&[u8], WrappedOperation) = operation_deserializer
-                    .deserialize::<DeserializeError>(&op_serialized)
-                    .map_err(|err| {
-                        ApiError::ModelsError(ModelsError::DeserializeError(err.to_string()))
-                    })?;
-                if rest.is_empty() {
-                    Ok(op)
-                } else {
-                    Err(ApiError::ModelsError(ModelsError::DeserializeError(
-                        "There is data left after operation deserialization".to_owned(),
-                    ))
-                    .into())
-                }
-            })
-            .map(|op| match op {
-                Ok(operation) => {
-                    let _verify_signature = match operation.verify_signature() {
-                        Ok(()) => (),
-                        Err(e) => return Err(ApiError::ModelsError(e).into()),
-                    };
-                    Ok(operation)
-                }
-                Err(e) => Err(e),
-            })
-            .collect::<RpcResult<Vec<WrappedOperation>>>()?;
+        let verified_ops = verify_operations(ops, &self.0.api_settings)?;
         to_send.store_operations(verified_ops.clone());
         let ids: Vec<OperationId> = verified_ops.iter().map(|op| op.id).collect();
         cmd_sender.add_operations(to_send.clone());
 
-        tokio::task::spawn_blocking(move || protocol_sender.propagate_operations(to_send))
-            .await
-            .map_err(|err| ApiError::InternalServerError(err.to_string()))?
+        self.0
+            .worker
+            .run_blocking("send_operations", move || {
+                protocol_sender.propagate_operations(to_send)
+            })
+            .await?
             .map_err(|err| {
                 ApiError::InternalServerError(format!("Failed to propagate operations: {}", err))
             })?;
@@ -925,12 +1168,15 @@ impl MassaRpcServer for API<Public> {
         &self,
         filter: EventFilter,
     ) -> RpcResult<Vec<SCOutputEvent>> {
+        let execution_controller = self.0.execution_controller.clone();
         let events = self
             .0
-            .execution_controller
-            .get_filtered_sc_output_event(filter);
+            .worker
+            .run_blocking("get_filtered_sc_output_event", move || {
+                execution_controller.get_filtered_sc_output_event(filter)
+            })
+            .await?;
 
-        // TODO: get rid of the async part
         Ok(events)
     }
 
@@ -997,3 +1243,95 @@ impl MassaRpcServer for API<Public> {
         openrpc
     }
 }
+
+/// Checks that a selection simulation has at most `max_arguments` roll counts
+/// and `max_simulated_cycles` cycles
+fn check_selection_simulation(
+    simulation: &SelectionSimulation,
+    max_arguments: u64,
+    max_simulated_cycles: u64,
+) -> Result<(), ApiError> {
+    if simulation.rolls.len() as u64 > max_arguments {
+        return Err(ApiError::BadRequest("too many arguments".into()));
+    }
+    if simulation.cycle_count > max_simulated_cycles {
+        return Err(ApiError::BadRequest("too many simulated cycles".into()));
+    }
+    Ok(())
+}
+
+/// Deserializes the submitted operations and checks their signature
+pub(crate) fn verify_operations(
+    ops: Vec<OperationInput>,
+    api_cfg: &APIConfig,
+) -> RpcResult<Vec<WrappedOperation>> {
+    if ops.len() as u64 > api_cfg.max_arguments {
+        return Err(ApiError::BadRequest("too many arguments".into()).into());
+    }
+    let operation_deserializer = WrappedDeserializer::new(OperationDeserializer::new(
+        api_cfg.max_datastore_value_length,
+        api_cfg.max_function_name_length,
+        api_cfg.max_parameter_size,
+        api_cfg.max_op_datastore_entry_count,
+        api_cfg.max_op_datastore_key_length,
+        api_cfg.max_op_datastore_value_length,
+    ));
+    ops.into_iter()
+        .map(|op_input| {
+            let mut op_serialized = Vec::new();
+            op_serialized.extend(op_input.signature.to_bytes());
+            op_serialized.extend(op_input.creator_public_key.to_bytes());
+            op_serialized.extend(op_input.serialized_content);
+            let (rest, op): (&[u8], WrappedOperation) = operation_deserializer
+                .deserialize::<DeserializeError>(&op_serialized)
+                .map_err(|err| {
+                    ApiError::ModelsError(ModelsError::DeserializeError(err.to_string()))
+                })?;
+            if rest.is_empty() {
+                Ok(op)
+            } else {
+                Err(ApiError::ModelsError(ModelsError::DeserializeError(
+                    "There is data left after operation deserialization".to_owned(),
+                ))
+                .into())
+            }
+        })
+        .map(|op| match op {
+            Ok(operation) => {
+                let _verify_signature = match operation.verify_signature() {
+                    Ok(()) => (),
+                    Err(e) => return Err(ApiError::ModelsError(e).into()),
+                };
+                Ok(operation)
+            }
+            Err(e) => Err(e),
+        })
+        .collect::<RpcResult<Vec<WrappedOperation>>>()
+}
+
+#[test]
+fn test_check_selection_simulation() {
+    let simulation = |roll_owners: usize, cycle_count: u64| SelectionSimulation {
+        rolls: (0..roll_owners)
+            .map(|_| {
+                (
+                    Address::from_public_key(&KeyPair::generate().get_public_key()),
+                    1,
+                )
+            })
+            .collect(),
+        seed: massa_hash::Hash::compute_from(b"seed"),
+        cycle_count,
+    };
+    assert!(check_selection_simulation(&simulation(3, 2), 3, 2).is_ok());
+    // too many roll counts
+    assert!(matches!(
+        check_selection_simulation(&simulation(4, 2), 3, 2),
+        Err(ApiError::BadRequest(_))
+    ));
+    // too many cycles
+    assert!(matches!(
+        check_selection_simulation(&simulation(3, 3), 3, 2),
+        Err(ApiError::BadRequest(_))
+    ));
+}
@@ -0,0 +1,173 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! API worker: mediates between the JSON-RPC handlers and the node controllers.
+//!
+//! Every controller call made through the worker holds one of a bounded number of
+//! in-flight slots and is bounded in time, so that a slow or stuck controller
+//! results in an error response instead of a request hanging forever.
+
+use crate::config::APIConfig;
+use crate::error::ApiError;
+use massa_time::MassaTime;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::warn;
+
+/// Limits the number of in-flight controller requests and their duration
+#[derive(Clone)]
+pub(crate) struct ApiWorker {
+    /// one permit per allowed in-flight request
+    in_flight: Arc<Semaphore>,
+    /// timeout applied to methods without a specific timeout
+    default_timeout: MassaTime,
+    /// per-method timeouts
+    method_timeouts: Arc<HashMap<String, MassaTime>>,
+}
+
+impl ApiWorker {
+    /// Creates a new API worker from the API configuration
+    pub(crate) fn new(cfg: &APIConfig) -> Self {
+        ApiWorker {
+            in_flight: Arc::new(Semaphore::new(cfg.max_in_flight_requests)),
+            default_timeout: cfg.request_timeout,
+            method_timeouts: Arc::new(cfg.method_timeouts.clone()),
+        }
+    }
+
+    /// Returns the timeout applied to `method`
+    fn timeout_for(&self, method: &str) -> MassaTime {
+        self.method_timeouts
+            .get(method)
+            .copied()
+            .unwrap_or(self.default_timeout)
+    }
+
+    /// Takes an in-flight slot, released when the returned permit is dropped
+    fn acquire(&self) -> Result<OwnedSemaphorePermit, ApiError> {
+        self.in_flight
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| ApiError::TooManyRequests)
+    }
+
+    /// Runs a future on behalf of `method`, within the in-flight and time limits
+    pub(crate) async fn run<T, F>(&self, method: &'static str, fut: F) -> Result<T, ApiError>
+    where
+        F: Future<Output = Result<T, ApiError>>,
+    {
+        let _permit = self.acquire()?;
+        self.with_timeout(method, fut).await
+    }
+
+    /// Runs a future on behalf of `method`, within its time limit
+    async fn with_timeout<T, F>(&self, method: &'static str, fut: F) -> Result<T, ApiError>
+    where
+        F: Future<Output = Result<T, ApiError>>,
+    {
+        let timeout = self.timeout_for(method);
+        match tokio::time::timeout(timeout.to_duration(), fut).await {
+            Ok(res) => res,
+            Err(_) => {
+                warn!("API request {} timed out after {}", method, timeout);
+                Err(ApiError::Timeout(method.to_string()))
+            }
+        }
+    }
+
+    /// Runs a blocking controller call on behalf of `method`, within the in-flight and time limits.
+    ///
+    /// The call is moved to the blocking thread pool so that it does not stall the async runtime.
+    /// If it times out, its result is discarded once it eventually completes,
+    /// and its in-flight slot is only released at that point.
+    pub(crate) async fn run_blocking<T, F>(&self, method: &'static str, f: F) -> Result<T, ApiError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let permit = self.acquire()?;
+        self.with_timeout(method, async move {
+            tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                f()
+            })
+            .await
+            .map_err(|err| ApiError::InternalServerError(err.to_string()))
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn worker(max_in_flight_requests: usize, request_timeout: u64) -> ApiWorker {
+        ApiWorker {
+            in_flight: Arc::new(Semaphore::new(max_in_flight_requests)),
+            default_timeout: MassaTime::from_millis(request_timeout),
+            method_timeouts: Arc::new(HashMap::from([(
+                "slow_method".to_string(),
+                MassaTime::from_millis(1000),
+            )])),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_blocking_returns_result() {
+        let worker = worker(1, 1000);
+        let res = worker.run_blocking("method", || 42).await.unwrap();
+        assert_eq!(res, 42);
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let worker = worker(1, 10);
+        let res = worker
+            .run_blocking("method", || std::thread::sleep(Duration::from_millis(200)))
+            .await;
+        assert!(matches!(res, Err(ApiError::Timeout(method)) if method == "method"));
+
+        // the timed out call keeps its in-flight slot until it completes
+        assert_eq!(worker.in_flight.available_permits(), 0);
+        while worker.in_flight.available_permits() == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        // the per-method timeout takes precedence over the default one
+        worker
+            .run_blocking("slow_method", || {
+                std::thread::sleep(Duration::from_millis(50))
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_too_many_requests() {
+        let worker = worker(1, 1000);
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let busy_worker = worker.clone();
+        let busy = tokio::spawn(async move {
+            busy_worker
+                .run("method", async move {
+                    rx.await.ok();
+                    Ok(())
+                })
+                .await
+        });
+        tokio::task::yield_now().await;
+        while worker.in_flight.available_permits() > 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let res = worker.run_blocking("method", || ()).await;
+        assert!(matches!(res, Err(ApiError::TooManyRequests)));
+
+        tx.send(()).unwrap();
+        busy.await.unwrap().unwrap();
+        worker.run_blocking("method", || ()).await.unwrap();
+    }
+}
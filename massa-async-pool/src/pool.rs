@@ -11,7 +11,7 @@ use crate::{
 };
 use massa_hash::{Hash, HASH_SIZE_BYTES};
 use massa_ledger_exports::LedgerChanges;
-use massa_models::{slot::Slot, streaming_step::StreamingStep};
+use massa_models::{amount::Amount, slot::Slot, streaming_step::StreamingStep};
 use massa_serialization::{
     Deserializer, SerializeError, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
 };
@@ -174,6 +174,15 @@ impl AsyncPool {
             .collect()
     }
 
+    /// Get the sum of the coins held by the messages of the pool
+    pub fn get_total_coins(&self) -> Amount {
+        self.messages
+            .values()
+            .fold(Amount::zero(), |total, message| {
+                total.saturating_add(message.coins)
+            })
+    }
+
     /// Get a part of the async pool.
     /// Used for bootstrap.
     ///
@@ -343,7 +352,11 @@ impl Deserializer<BTreeMap<AsyncMessageId, AsyncMessage>> for AsyncPoolDeseriali
 #[test]
 fn test_take_batch() {
     use massa_hash::Hash;
-    use massa_models::{address::Address, amount::Amount, slot::Slot};
+    use massa_models::{
+        address::{Address, AddressCategory},
+        amount::Amount,
+        slot::Slot,
+    };
     use std::str::FromStr;
 
     let config = AsyncPoolConfig {
@@ -353,7 +366,7 @@ fn test_take_batch() {
         bootstrap_part_size: 100,
     };
     let mut pool = AsyncPool::new(config);
-    let address = Address(Hash::compute_from(b"abc"));
+    let address = Address::new(AddressCategory::UserAddress, Hash::compute_from(b"abc"));
     for i in 1..10 {
         let message = AsyncMessage::new_with_hash(
             Slot::new(0, 0),
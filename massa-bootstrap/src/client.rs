@@ -1,29 +1,215 @@
+use futures::future::join_all;
 use humantime::format_duration;
-use std::{net::SocketAddr, sync::Arc, time::Duration};
+use std::{collections::BTreeMap, net::SocketAddr, sync::Arc, time::Duration};
 
-use massa_final_state::FinalState;
+use massa_async_pool::{AsyncMessage, AsyncMessageId};
+use massa_consensus_exports::bootstrapable_graph::BootstrapableGraph;
+use massa_final_state::{FinalState, StateChanges};
+use massa_hash::Hash;
 use massa_logging::massa_trace;
-use massa_models::{streaming_step::StreamingStep, version::Version};
+use massa_models::{
+    block::BlockId, operation::OperationId, prehash::PreHashSet, slot::Slot,
+    streaming_step::StreamingStep, version::Version,
+};
+use massa_pos_exports::{CycleHistoryCursor, CycleInfo, DeferredCredits, PosError};
 use massa_signature::PublicKey;
 use massa_time::MassaTime;
 use parking_lot::RwLock;
-use rand::{
-    prelude::{SliceRandom, StdRng},
-    SeedableRng,
-};
-use tokio::time::sleep;
+use tokio::{sync::mpsc, time::sleep};
 use tracing::{debug, info, warn};
 
 use crate::{
     client_binder::BootstrapClientBinder,
+    cursor::{
+        compact_bootstrap_journal, load_bootstrap_cursor, remove_bootstrap_cursor,
+        save_bootstrap_cursor, save_cycle_history_reset, serialize_journal_part, JournalEntry,
+    },
     error::BootstrapError,
     messages::{BootstrapClientMessage, BootstrapServerMessage},
+    server_list::BootstrapServerList,
     BootstrapConfig, Establisher, GlobalBootstrapState,
 };
 
+/// Final state streaming steps reached after applying a bootstrap part
+struct FinalStateSteps {
+    ledger: StreamingStep<Vec<u8>>,
+    pool: StreamingStep<AsyncMessageId>,
+    cycle: StreamingStep<CycleHistoryCursor>,
+    credits: StreamingStep<Slot>,
+    ops: StreamingStep<Slot>,
+}
+
+/// Applies the final state content of a bootstrap part and returns the reached streaming steps.
+///
+/// This is CPU and disk intensive, so it is meant to run on a blocking thread.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn apply_final_state_part(
+    final_state: &RwLock<FinalState>,
+    slot: Slot,
+    ledger_part: Vec<u8>,
+    async_pool_part: BTreeMap<AsyncMessageId, AsyncMessage>,
+    pos_cycle_part: Option<CycleInfo>,
+    pos_credits_part: DeferredCredits,
+    exec_ops_part: BTreeMap<Slot, PreHashSet<OperationId>>,
+    final_state_changes: Vec<(Slot, StateChanges)>,
+) -> Result<FinalStateSteps, BootstrapError> {
+    let mut write_final_state = final_state.write();
+    let ledger = write_final_state.ledger.set_ledger_part(ledger_part)?;
+    let pool = write_final_state.async_pool.set_pool_part(async_pool_part);
+    let cycle = write_final_state
+        .pos_state
+        .set_cycle_history_part(pos_cycle_part)
+        .map_err(|err| match err {
+            PosError::UnexpectedCycleHistoryPart(received, expected) => {
+                BootstrapError::InconsistentCycleHistoryPart(received, expected)
+            }
+            err => err.into(),
+        })?;
+    let credits = write_final_state
+        .pos_state
+        .set_deferred_credits_part(pos_credits_part);
+    let ops = write_final_state
+        .executed_ops
+        .set_executed_ops_part(exec_ops_part);
+    for (changes_slot, changes) in final_state_changes.iter() {
+        write_final_state
+            .ledger
+            .apply_changes(changes.ledger_changes.clone(), *changes_slot);
+        write_final_state
+            .async_pool
+            .apply_changes_unchecked(&changes.async_pool_changes);
+        if !changes.pos_changes.is_empty() {
+            write_final_state.pos_state.apply_changes(
+                changes.pos_changes.clone(),
+                *changes_slot,
+                false,
+            )?;
+        }
+        if !changes.executed_ops_changes.is_empty() {
+            write_final_state
+                .executed_ops
+                .apply_changes(changes.executed_ops_changes.clone(), *changes_slot);
+        }
+    }
+    write_final_state.slot = slot;
+    Ok(FinalStateSteps {
+        ledger,
+        pool,
+        cycle,
+        credits,
+        ops,
+    })
+}
+
+/// Applies the consensus content of a bootstrap part and returns the reached streaming step.
+fn apply_consensus_part(
+    graph: &mut Option<BootstrapableGraph>,
+    consensus_part: BootstrapableGraph,
+    consensus_outdated_ids: PreHashSet<BlockId>,
+) -> StreamingStep<PreHashSet<BlockId>> {
+    if let Some(graph) = graph.as_mut() {
+        // Extend the final blocks with the received part
+        graph.final_blocks.extend(consensus_part.final_blocks);
+        // Remove every outdated block
+        graph
+            .final_blocks
+            .retain(|block_export| !consensus_outdated_ids.contains(&block_export.block.id));
+    } else {
+        *graph = Some(consensus_part);
+    }
+    StreamingStep::Ongoing(
+        // Note that this unwrap call is safe because of the above conditional statement
+        graph
+            .as_ref()
+            .unwrap()
+            .final_blocks
+            .iter()
+            .map(|b_export| b_export.block.id)
+            .collect(),
+    )
+}
+
+/// Rebuilds the in-memory final state components and the consensus graph of an interrupted
+/// bootstrap by applying its journaled parts in order. Their ledger content is already on disk.
+pub(crate) fn replay_bootstrap_journal(
+    global_bootstrap_state: &mut GlobalBootstrapState,
+    journal: Vec<JournalEntry>,
+) -> Result<(), BootstrapError> {
+    for entry in journal {
+        match entry {
+            JournalEntry::Part(BootstrapServerMessage::BootstrapPart {
+                slot,
+                ledger_part,
+                async_pool_part,
+                pos_cycle_part,
+                pos_credits_part,
+                exec_ops_part,
+                final_state_changes,
+                consensus_part,
+                consensus_outdated_ids,
+            }) => {
+                apply_final_state_part(
+                    &global_bootstrap_state.final_state,
+                    slot,
+                    ledger_part,
+                    async_pool_part,
+                    pos_cycle_part,
+                    pos_credits_part,
+                    exec_ops_part,
+                    final_state_changes,
+                )?;
+                apply_consensus_part(
+                    &mut global_bootstrap_state.graph,
+                    consensus_part,
+                    consensus_outdated_ids,
+                );
+            }
+            JournalEntry::Part(other) => {
+                return Err(BootstrapError::UnexpectedServerMessage(other))
+            }
+            JournalEntry::CycleHistoryReset => global_bootstrap_state
+                .final_state
+                .write()
+                .pos_state
+                .cycle_history
+                .clear(),
+            JournalEntry::State {
+                slot,
+                async_pool,
+                cycle_history,
+                deferred_credits,
+                executed_ops,
+                graph,
+            } => {
+                let mut final_state = global_bootstrap_state.final_state.write();
+                final_state.async_pool.set_pool_part(async_pool);
+                for cycle_info in cycle_history {
+                    final_state
+                        .pos_state
+                        .set_cycle_history_part(Some(cycle_info))?;
+                }
+                final_state
+                    .pos_state
+                    .set_deferred_credits_part(deferred_credits);
+                final_state.executed_ops.set_executed_ops_part(executed_ops);
+                final_state.slot = slot;
+                if !graph.final_blocks.is_empty() {
+                    global_bootstrap_state.graph = Some(graph);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 /// This function will send the starting point to receive a stream of the ledger and will receive and process each part until receive a `BootstrapServerMessage::FinalStateFinished` message from the server.
 /// `next_bootstrap_message` passed as parameter must be `BootstrapClientMessage::AskFinalStatePart` enum variant.
 /// `next_bootstrap_message` will be updated after receiving each part so that in case of connection lost we can restart from the last message we processed.
+///
+/// The parts are downloaded while the previous ones are applied on a blocking thread.
+/// At most `max_pending_bootstrap_parts` received parts wait to be applied:
+/// once this queue is full, the download pauses, which slows the server down through the connection flow control.
+/// Errors sent by the server are handled without waiting for the queued parts to be applied.
 async fn stream_final_state_and_consensus(
     cfg: &BootstrapConfig,
     client: &mut BootstrapClientBinder,
@@ -45,141 +231,203 @@ async fn stream_final_state_and_consensus(
             Ok(Err(e)) => Err(e),
             Ok(Ok(_)) => Ok(()),
         }?;
-        loop {
-            let msg = match tokio::time::timeout(cfg.read_timeout.into(), client.next()).await {
-                Err(_) => {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::TimedOut,
-                        "final state bootstrap read timed out",
-                    )
-                    .into());
-                }
-                Ok(Err(e)) => return Err(e),
-                Ok(Ok(msg)) => msg,
-            };
-            match msg {
-                BootstrapServerMessage::BootstrapPart {
-                    slot,
-                    ledger_part,
-                    async_pool_part,
-                    pos_cycle_part,
-                    pos_credits_part,
-                    exec_ops_part,
-                    final_state_changes,
-                    consensus_part,
-                    consensus_outdated_ids,
-                } => {
-                    // Set final state
-                    let mut write_final_state = global_bootstrap_state.final_state.write();
-                    let last_ledger_step = write_final_state.ledger.set_ledger_part(ledger_part)?;
-                    let last_pool_step =
-                        write_final_state.async_pool.set_pool_part(async_pool_part);
-                    let last_cycle_step = write_final_state
-                        .pos_state
-                        .set_cycle_history_part(pos_cycle_part);
-                    let last_credits_step = write_final_state
-                        .pos_state
-                        .set_deferred_credits_part(pos_credits_part);
-                    let last_ops_step = write_final_state
-                        .executed_ops
-                        .set_executed_ops_part(exec_ops_part);
-                    for (changes_slot, changes) in final_state_changes.iter() {
-                        write_final_state
-                            .ledger
-                            .apply_changes(changes.ledger_changes.clone(), *changes_slot);
-                        write_final_state
-                            .async_pool
-                            .apply_changes_unchecked(&changes.async_pool_changes);
-                        if !changes.pos_changes.is_empty() {
-                            write_final_state.pos_state.apply_changes(
-                                changes.pos_changes.clone(),
-                                *changes_slot,
-                                false,
-                            )?;
+
+        let (part_tx, mut part_rx) =
+            mpsc::channel::<BootstrapServerMessage>(cfg.max_pending_bootstrap_parts.max(1));
+
+        // download the parts and queue them
+        let receive_parts = async move {
+            loop {
+                let msg = tokio::select! {
+                    res = tokio::time::timeout(cfg.read_timeout.into(), client.next()) => match res {
+                        Err(_) => {
+                            return Err(std::io::Error::new(
+                                std::io::ErrorKind::TimedOut,
+                                "final state bootstrap read timed out",
+                            )
+                            .into());
                         }
-                        if !changes.executed_ops_changes.is_empty() {
-                            write_final_state
-                                .executed_ops
-                                .apply_changes(changes.executed_ops_changes.clone(), *changes_slot);
+                        Ok(Err(e)) => return Err(e),
+                        Ok(Ok(msg)) => msg,
+                    },
+                    // the parts are not applied anymore: stop downloading them
+                    _ = part_tx.closed() => return Ok(()),
+                };
+                match msg {
+                    BootstrapServerMessage::BootstrapPart { .. } => {
+                        if part_tx.send(msg).await.is_err() {
+                            return Ok(());
                         }
                     }
-                    write_final_state.slot = slot;
-
-                    // Set consensus blocks
-                    if let Some(graph) = global_bootstrap_state.graph.as_mut() {
-                        // Extend the final blocks with the received part
-                        graph.final_blocks.extend(consensus_part.final_blocks);
-                        // Remove every outdated block
-                        graph.final_blocks.retain(|block_export| {
-                            !consensus_outdated_ids.contains(&block_export.block.id)
-                        });
-                    } else {
-                        global_bootstrap_state.graph = Some(consensus_part);
+                    BootstrapServerMessage::BootstrapFinished { .. }
+                    | BootstrapServerMessage::SlotTooOld => {
+                        let _ = part_tx.send(msg).await;
+                        return Ok(());
+                    }
+                    BootstrapServerMessage::BootstrapError { error } => {
+                        return Err(
+                            std::io::Error::new(std::io::ErrorKind::InvalidData, error).into()
+                        )
+                    }
+                    _ => {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "unexpected message",
+                        )
+                        .into())
                     }
-                    let last_consensus_step = StreamingStep::Ongoing(
-                        // Note that this unwrap call is safe because of the above conditional statement
-                        global_bootstrap_state
-                            .graph
-                            .as_ref()
-                            .unwrap()
-                            .final_blocks
-                            .iter()
-                            .map(|b_export| b_export.block.id)
-                            .collect(),
-                    );
-
-                    // Set new message in case of disconnection
-                    *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
-                        last_slot: Some(slot),
-                        last_ledger_step,
-                        last_pool_step,
-                        last_cycle_step,
-                        last_credits_step,
-                        last_ops_step,
-                        last_consensus_step,
-                    };
-
-                    // Logs for an easier diagnostic if needed
-                    debug!(
-                        "client final state bootstrap cursors: {:?}",
-                        next_bootstrap_message
-                    );
-                    debug!(
-                        "client final state slot changes length: {}",
-                        final_state_changes.len()
-                    );
-                }
-                BootstrapServerMessage::BootstrapFinished => {
-                    info!("State bootstrap complete");
-                    // Set next bootstrap message
-                    *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPeers;
-                    return Ok(());
-                }
-                BootstrapServerMessage::SlotTooOld => {
-                    info!("Slot is too old retry bootstrap from scratch");
-                    *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
-                        last_slot: None,
-                        last_ledger_step: StreamingStep::Started,
-                        last_pool_step: StreamingStep::Started,
-                        last_cycle_step: StreamingStep::Started,
-                        last_credits_step: StreamingStep::Started,
-                        last_ops_step: StreamingStep::Started,
-                        last_consensus_step: StreamingStep::Started,
-                    };
-                    return Err(BootstrapError::GeneralError(String::from("Slot too old")));
-                }
-                BootstrapServerMessage::BootstrapError { error } => {
-                    return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, error).into())
                 }
-                _ => {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::InvalidData,
-                        "unexpected message",
-                    )
-                    .into())
+            }
+        };
+
+        // apply the queued parts in order
+        let apply_parts = async {
+            while let Some(mut msg) = part_rx.recv().await {
+                // the part is journaled without its ledger content, which is stored on disk
+                let journaled_part = if !global_bootstrap_state.journal_failed
+                    && matches!(msg, BootstrapServerMessage::BootstrapPart { .. })
+                {
+                    Some(serialize_journal_part(&mut msg)?)
+                } else {
+                    None
+                };
+                match msg {
+                    BootstrapServerMessage::BootstrapPart {
+                        slot,
+                        ledger_part,
+                        async_pool_part,
+                        pos_cycle_part,
+                        pos_credits_part,
+                        exec_ops_part,
+                        final_state_changes,
+                        consensus_part,
+                        consensus_outdated_ids,
+                    } => {
+                        let final_state_changes_len = final_state_changes.len();
+                        let finished_streams = count_finished_streams(next_bootstrap_message);
+
+                        // Set final state
+                        let final_state = global_bootstrap_state.final_state.clone();
+                        let steps = tokio::task::spawn_blocking(move || {
+                            apply_final_state_part(
+                                &final_state,
+                                slot,
+                                ledger_part,
+                                async_pool_part,
+                                pos_cycle_part,
+                                pos_credits_part,
+                                exec_ops_part,
+                                final_state_changes,
+                            )
+                        })
+                        .await
+                        .map_err(|err| {
+                            BootstrapError::GeneralError(format!(
+                                "could not apply bootstrap part: {}",
+                                err
+                            ))
+                        })??;
+
+                        // Set consensus blocks
+                        let last_consensus_step = apply_consensus_part(
+                            &mut global_bootstrap_state.graph,
+                            consensus_part,
+                            consensus_outdated_ids,
+                        );
+
+                        // Set new message in case of disconnection
+                        *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
+                            last_slot: Some(slot),
+                            last_ledger_step: steps.ledger,
+                            last_pool_step: steps.pool,
+                            last_cycle_step: steps.cycle,
+                            last_credits_step: steps.credits,
+                            last_ops_step: steps.ops,
+                            last_consensus_step,
+                        };
+
+                        // Journal the part and the cursor so that an interrupted bootstrap can be resumed,
+                        // or compact the journal once a stream completes so that it does not keep every part
+                        if let Some(journaled_part) = journaled_part {
+                            let res = if count_finished_streams(next_bootstrap_message)
+                                > finished_streams
+                            {
+                                compact_bootstrap_journal(
+                                    cfg,
+                                    next_bootstrap_message,
+                                    global_bootstrap_state,
+                                )
+                            } else {
+                                save_bootstrap_cursor(cfg, next_bootstrap_message, &journaled_part)
+                            };
+                            if let Err(err) = res {
+                                warn!("could not save the bootstrap cursor, an interrupted bootstrap will restart from scratch: {}", err);
+                                global_bootstrap_state.journal_failed = true;
+                                remove_bootstrap_cursor(&cfg.bootstrap_cursor_path)?;
+                            }
+                        }
+
+                        // Logs for an easier diagnostic if needed
+                        debug!(
+                            "client final state bootstrap cursors: {:?}",
+                            next_bootstrap_message
+                        );
+                        debug!(
+                            "client final state slot changes length: {}",
+                            final_state_changes_len
+                        );
+                    }
+                    BootstrapServerMessage::BootstrapFinished {
+                        slot,
+                        final_state_hash,
+                    } => {
+                        // check that the bootstrapped state matches the fingerprint of the server
+                        {
+                            let mut final_state = global_bootstrap_state.final_state.write();
+                            // a mismatching state cannot be repaired in place: the disk ledger
+                            // has to be cleared, which only happens when the node restarts
+                            // without a bootstrap cursor
+                            if final_state.slot != slot {
+                                let bootstrapped_slot = final_state.slot;
+                                drop(final_state);
+                                restart_bootstrap_from_scratch(cfg, next_bootstrap_message)?;
+                                return Err(BootstrapError::FinalStateSlotMismatch(
+                                    bootstrapped_slot,
+                                    slot,
+                                ));
+                            }
+                            if final_state.get_state_hash() != final_state_hash {
+                                drop(final_state);
+                                restart_bootstrap_from_scratch(cfg, next_bootstrap_message)?;
+                                return Err(BootstrapError::FinalStateHashMismatch(slot));
+                            }
+                            final_state.final_state_hash = final_state_hash;
+                        }
+                        info!(
+                            "State bootstrap complete, final state hash: {}",
+                            final_state_hash
+                        );
+                        remove_bootstrap_cursor(&cfg.bootstrap_cursor_path)?;
+                        // Set next bootstrap message
+                        *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPeers;
+                        return Ok(());
+                    }
+                    BootstrapServerMessage::SlotTooOld => {
+                        info!("Slot is too old retry bootstrap from scratch");
+                        restart_bootstrap_from_scratch(cfg, next_bootstrap_message)?;
+                        return Err(BootstrapError::GeneralError(String::from("Slot too old")));
+                    }
+                    // only parts and end of stream messages are queued
+                    other => return Err(BootstrapError::UnexpectedServerMessage(other)),
                 }
             }
-        }
+            // the download was interrupted, its error is returned below
+            Ok(())
+        };
+
+        let (apply_res, receive_res) = tokio::join!(apply_parts, receive_parts);
+        apply_res?;
+        receive_res
     } else {
         Err(BootstrapError::GeneralError(format!(
             "Try to stream the final state but the message to send to the server was {:#?}",
@@ -188,17 +436,90 @@ async fn stream_final_state_and_consensus(
     }
 }
 
-/// Gets the state from a bootstrap server (internal private function)
-/// needs to be CANCELLABLE
-async fn bootstrap_from_server(
+/// Counts the streams marked as finished by the cursor held by `next_bootstrap_message`.
+fn count_finished_streams(next_bootstrap_message: &BootstrapClientMessage) -> usize {
+    let BootstrapClientMessage::AskBootstrapPart {
+        last_ledger_step,
+        last_pool_step,
+        last_cycle_step,
+        last_credits_step,
+        last_ops_step,
+        last_consensus_step,
+        ..
+    } = next_bootstrap_message
+    else {
+        return 0;
+    };
+    [
+        last_ledger_step.finished(),
+        last_pool_step.finished(),
+        last_cycle_step.finished(),
+        last_credits_step.finished(),
+        last_ops_step.finished(),
+        last_consensus_step.finished(),
+    ]
+    .into_iter()
+    .filter(|finished| *finished)
+    .count()
+}
+
+/// Drops the bootstrap cursor and makes the next attempt ask for the whole state again.
+fn restart_bootstrap_from_scratch(
+    cfg: &BootstrapConfig,
+    next_bootstrap_message: &mut BootstrapClientMessage,
+) -> Result<(), BootstrapError> {
+    remove_bootstrap_cursor(&cfg.bootstrap_cursor_path)?;
+    *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
+        last_slot: None,
+        last_ledger_step: StreamingStep::Started,
+        last_pool_step: StreamingStep::Started,
+        last_cycle_step: StreamingStep::Started,
+        last_credits_step: StreamingStep::Started,
+        last_ops_step: StreamingStep::Started,
+        last_consensus_step: StreamingStep::Started,
+    };
+    Ok(())
+}
+
+/// Drops the cycle history received so far and restarts its stream from the beginning.
+///
+/// Used when a server sent a cycle history part that does not follow the received ones,
+/// so that the inconsistent history is not kept when bootstrapping from another server.
+pub(crate) fn reset_cycle_history(
     cfg: &BootstrapConfig,
-    client: &mut BootstrapClientBinder,
     next_bootstrap_message: &mut BootstrapClientMessage,
     global_bootstrap_state: &mut GlobalBootstrapState,
+) {
+    if !global_bootstrap_state.journal_failed {
+        if let Err(err) = save_cycle_history_reset(cfg) {
+            warn!("could not save the bootstrap cursor, an interrupted bootstrap will restart from scratch: {}", err);
+            global_bootstrap_state.journal_failed = true;
+            if let Err(err) = remove_bootstrap_cursor(&cfg.bootstrap_cursor_path) {
+                warn!("could not remove the bootstrap cursor: {}", err);
+            }
+        }
+    }
+    global_bootstrap_state
+        .final_state
+        .write()
+        .pos_state
+        .cycle_history
+        .clear();
+    if let BootstrapClientMessage::AskBootstrapPart {
+        last_cycle_step, ..
+    } = next_bootstrap_message
+    {
+        *last_cycle_step = StreamingStep::Started;
+    }
+}
+
+/// Reads the error the server may send at connection, then performs the handshake
+/// and checks the ping and the clock delta with the server.
+async fn handshake_and_sync_clock(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
     our_version: Version,
 ) -> Result<(), BootstrapError> {
-    massa_trace!("bootstrap.lib.bootstrap_from_server", {});
-
     // read error (if sent by the server)
     // client.next() is not cancel-safe but we drop the whole client object if cancelled => it's OK
     match tokio::time::timeout(cfg.read_error_timeout.into(), client.next()).await {
@@ -294,6 +615,37 @@ async fn bootstrap_from_server(
         return Err(BootstrapError::ClockError(message));
     }
 
+    Ok(())
+}
+
+/// Gets the state from a bootstrap server (internal private function)
+/// needs to be CANCELLABLE
+#[allow(clippy::too_many_arguments)]
+async fn bootstrap_from_server(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    establisher: &mut Establisher,
+    secondary_servers: &[(SocketAddr, PublicKey)],
+    next_bootstrap_message: &mut BootstrapClientMessage,
+    global_bootstrap_state: &mut GlobalBootstrapState,
+    our_version: Version,
+) -> Result<(), BootstrapError> {
+    massa_trace!("bootstrap.lib.bootstrap_from_server", {});
+
+    handshake_and_sync_clock(cfg, client, our_version).await?;
+
+    // download the complete PoS cycles from the secondary servers
+    bootstrap_cycles_from_secondaries(
+        cfg,
+        client,
+        establisher,
+        secondary_servers,
+        next_bootstrap_message,
+        global_bootstrap_state,
+        our_version,
+    )
+    .await?;
+
     let write_timeout: std::time::Duration = cfg.write_timeout.into();
     // Loop to ask data to the server depending on the last message we sent
     loop {
@@ -339,8 +691,13 @@ async fn bootstrap_from_server(
                 }?;
                 break;
             }
-            BootstrapClientMessage::BootstrapError { error: _ } => {
-                panic!("The next message to send shouldn't be BootstrapError");
+            BootstrapClientMessage::BootstrapError { error: _ }
+            | BootstrapClientMessage::AskCycleHashes
+            | BootstrapClientMessage::AskCompleteCycle { .. } => {
+                panic!(
+                    "The next message to send shouldn't be {:#?}",
+                    next_bootstrap_message
+                );
             }
         };
     }
@@ -348,6 +705,281 @@ async fn bootstrap_from_server(
     Ok(())
 }
 
+/// Downloads the complete PoS cycles from the `secondary_servers` in parallel and merges
+/// the ones matching the hashes provided by the primary server `client` into the final state.
+///
+/// Complete cycles do not change anymore, so every up-to-date server holds the same version of them.
+/// The ledger, async pool, deferred credits, executed operations and consensus graph change at every slot:
+/// a secondary server cannot provide parts matching the primary server ones, so they are only streamed by the primary server.
+///
+/// Does nothing if the cycle history stream already started.
+/// The cycles that could not be downloaded or verified are streamed by the primary server afterwards.
+async fn bootstrap_cycles_from_secondaries(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    establisher: &mut Establisher,
+    secondary_servers: &[(SocketAddr, PublicKey)],
+    next_bootstrap_message: &mut BootstrapClientMessage,
+    global_bootstrap_state: &mut GlobalBootstrapState,
+    our_version: Version,
+) -> Result<(), BootstrapError> {
+    let last_cycle_step = match next_bootstrap_message {
+        BootstrapClientMessage::AskBootstrapPart {
+            last_cycle_step, ..
+        } if !secondary_servers.is_empty() && matches!(last_cycle_step, StreamingStep::Started) => {
+            last_cycle_step
+        }
+        _ => return Ok(()),
+    };
+
+    // get the hashes of the complete cycles from the primary server
+    let hashes = match send_client_message(
+        &BootstrapClientMessage::AskCycleHashes,
+        client,
+        cfg.write_timeout.into(),
+        cfg.read_timeout.into(),
+        "ask cycle hashes timed out",
+    )
+    .await?
+    {
+        BootstrapServerMessage::CycleHashes { hashes } => hashes,
+        BootstrapServerMessage::BootstrapError { error } => {
+            return Err(BootstrapError::ReceivedError(error))
+        }
+        other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+    };
+    if hashes.is_empty() {
+        return Ok(());
+    }
+
+    // connect to the secondary servers
+    let mut secondaries = Vec::new();
+    for (addr, pub_key) in secondary_servers.iter().take(hashes.len()) {
+        match connect_to_server(establisher, cfg, addr, pub_key).await {
+            Ok(secondary) => secondaries.push((*addr, secondary)),
+            Err(e) => warn!(
+                "Error while connecting to secondary bootstrap server {}: {}",
+                addr, e
+            ),
+        }
+    }
+    if secondaries.is_empty() {
+        return Ok(());
+    }
+
+    // share the cycles between the secondary servers and download them in parallel
+    let secondary_count = secondaries.len();
+    let downloads = secondaries
+        .into_iter()
+        .enumerate()
+        .map(|(index, (addr, mut secondary))| {
+            let expected_hashes: Vec<(u64, Hash)> = hashes
+                .iter()
+                .skip(index)
+                .step_by(secondary_count)
+                .copied()
+                .collect();
+            async move {
+                match fetch_cycles_from_secondary(
+                    cfg,
+                    &mut secondary,
+                    &expected_hashes,
+                    our_version,
+                )
+                .await
+                {
+                    Ok(cycles) => cycles,
+                    Err(e) => {
+                        warn!(
+                            "Error while downloading cycles from secondary bootstrap server {}: {}",
+                            addr, e
+                        );
+                        let _ = tokio::time::timeout(
+                            cfg.write_error_timeout.into(),
+                            secondary.send(&BootstrapClientMessage::BootstrapError {
+                                error: e.to_string(),
+                            }),
+                        )
+                        .await;
+                        Vec::new()
+                    }
+                }
+            }
+        });
+    // the primary server drops the connection if it does not hear from us within its read timeout
+    let mut received_cycles: BTreeMap<u64, CycleInfo> =
+        match tokio::time::timeout(cfg.read_timeout.into(), join_all(downloads)).await {
+            Ok(results) => results
+                .into_iter()
+                .flatten()
+                .map(|cycle_info| (cycle_info.cycle, cycle_info))
+                .collect(),
+            Err(_) => {
+                warn!("cycle download from secondary bootstrap servers timed out");
+                return Ok(());
+            }
+        };
+
+    // merge the verified cycles following each other from the first one,
+    // the primary server will stream the next cycles
+    let mut write_final_state = global_bootstrap_state.final_state.write();
+    let mut merged_count = 0;
+    for (cycle, _) in hashes.iter() {
+        let cycle_info = match received_cycles.remove(cycle) {
+            Some(cycle_info) => cycle_info,
+            None => break,
+        };
+        *last_cycle_step = write_final_state
+            .pos_state
+            .set_cycle_history_part(Some(cycle_info))
+            .map_err(|err| match err {
+                PosError::UnexpectedCycleHistoryPart(received, expected) => {
+                    BootstrapError::InconsistentCycleHistoryPart(received, expected)
+                }
+                err => err.into(),
+            })?;
+        merged_count += 1;
+    }
+    info!(
+        "{} complete cycles out of {} downloaded from {} secondary bootstrap servers",
+        merged_count,
+        hashes.len(),
+        secondary_count
+    );
+    Ok(())
+}
+
+/// Adds a part of a complete cycle received from a secondary bootstrap server to `cycle_info`,
+/// the parts received so far for the cycle of `cursor`.
+///
+/// The global hash of `cycle_info` is not maintained, it is computed once the cycle is received.
+///
+/// # Returns
+/// The cursor to ask the next part with, or `None` once the cycle was entirely received
+pub(crate) fn add_complete_cycle_part(
+    cfg: &BootstrapConfig,
+    cycle_info: &mut Option<CycleInfo>,
+    cursor: CycleHistoryCursor,
+    cycle_part: CycleInfo,
+) -> Result<Option<CycleHistoryCursor>, BootstrapError> {
+    // a part only holds addresses after the cursor, so that the stream always moves forward
+    let part_addresses = cycle_part
+        .roll_counts
+        .keys()
+        .chain(cycle_part.production_stats.keys());
+    let mut addresses_after_cursor = true;
+    let mut last_address = cursor.last_address;
+    for address in part_addresses {
+        addresses_after_cursor &= cursor.last_address.map_or(true, |last| *address > last);
+        last_address = last_address.max(Some(*address));
+    }
+    if cycle_part.cycle != cursor.cycle || !cycle_part.complete || !addresses_after_cursor {
+        return Err(BootstrapError::UnverifiedCycle(cursor.cycle));
+    }
+    let is_last_part = last_address == cursor.last_address;
+    match cycle_info {
+        Some(info) => {
+            info.roll_counts.extend(cycle_part.roll_counts);
+            info.production_stats.extend(cycle_part.production_stats);
+        }
+        None => *cycle_info = Some(cycle_part),
+    }
+    let info = cycle_info.as_ref().expect("cycle parts should be set here");
+    if info.roll_counts.len() as u64 > cfg.max_rolls_length
+        || info.production_stats.len() as u64 > cfg.max_production_stats_length
+    {
+        return Err(BootstrapError::UnverifiedCycle(cursor.cycle));
+    }
+    if is_last_part {
+        return Ok(None);
+    }
+    Ok(Some(CycleHistoryCursor {
+        cycle: cursor.cycle,
+        last_address,
+    }))
+}
+
+/// Downloads the cycles of `expected_hashes` from a secondary bootstrap server
+/// and checks them against their hash.
+async fn fetch_cycles_from_secondary(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    expected_hashes: &[(u64, Hash)],
+    our_version: Version,
+) -> Result<Vec<CycleInfo>, BootstrapError> {
+    handshake_and_sync_clock(cfg, client, our_version).await?;
+
+    let write_timeout: std::time::Duration = cfg.write_timeout.into();
+    let mut cycles = Vec::with_capacity(expected_hashes.len());
+    for (cycle, hash) in expected_hashes {
+        // the cycle is streamed in parts bounded like the ones of the primary server stream
+        let mut cycle_info: Option<CycleInfo> = None;
+        let mut cursor = Some(CycleHistoryCursor {
+            cycle: *cycle,
+            last_address: None,
+        });
+        while let Some(current_cursor) = cursor {
+            let cycle_part = match send_client_message(
+                &BootstrapClientMessage::AskCompleteCycle {
+                    cursor: current_cursor,
+                },
+                client,
+                write_timeout,
+                cfg.read_timeout.into(),
+                "ask complete cycle timed out",
+            )
+            .await?
+            {
+                BootstrapServerMessage::CompleteCyclePart {
+                    cycle_part: Some(cycle_part),
+                } => cycle_part,
+                BootstrapServerMessage::CompleteCyclePart { cycle_part: None } => {
+                    return Err(BootstrapError::GeneralError(format!(
+                        "complete cycle {} not available",
+                        cycle
+                    )))
+                }
+                BootstrapServerMessage::BootstrapError { error } => {
+                    return Err(BootstrapError::ReceivedError(error))
+                }
+                other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+            };
+            cursor = add_complete_cycle_part(cfg, &mut cycle_info, current_cursor, cycle_part)?;
+        }
+        let received = cycle_info.ok_or(BootstrapError::UnverifiedCycle(*cycle))?;
+        // recompute the cycle global hash from the whole received content
+        let mut cycle_info = CycleInfo::new_with_hash(
+            received.cycle,
+            received.complete,
+            received.roll_counts,
+            received.rng_seed,
+            received.production_stats,
+        );
+        cycle_info.final_state_hash_snapshot = received.final_state_hash_snapshot;
+        if cycle_info.get_bootstrap_hash() != *hash {
+            return Err(BootstrapError::UnverifiedCycle(*cycle));
+        }
+        cycles.push(cycle_info);
+    }
+
+    // end the session with the secondary server
+    match tokio::time::timeout(
+        write_timeout,
+        client.send(&BootstrapClientMessage::BootstrapSuccess),
+    )
+    .await
+    {
+        Err(_) => Err(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            "send bootstrap success timed out",
+        )
+        .into()),
+        Ok(Err(e)) => Err(e),
+        Ok(Ok(_)) => Ok(()),
+    }?;
+    Ok(cycles)
+}
+
 async fn send_client_message(
     message_to_send: &BootstrapClientMessage,
     client: &mut BootstrapClientBinder,
@@ -422,32 +1054,54 @@ pub async fn get_state(
     // if we are before genesis, do not bootstrap
     if now < genesis_timestamp {
         massa_trace!("bootstrap.lib.get_state.init_from_scratch", {});
-        // init final state
-        {
-            let mut final_state_guard = final_state.write();
-            // load ledger from initial ledger file
-            final_state_guard
-                .ledger
-                .load_initial_ledger()
-                .map_err(|err| {
-                    BootstrapError::GeneralError(format!("could not load initial ledger: {}", err))
-                })?;
-            // create the initial cycle of PoS cycle_history
-            final_state_guard.pos_state.create_initial_cycle();
-        }
+        // init final state from the initial ledger and rolls, or from the configured snapshot
+        final_state.write().load_initial_state().map_err(|err| {
+            BootstrapError::GeneralError(format!("could not load initial state: {}", err))
+        })?;
         return Ok(GlobalBootstrapState::new(final_state));
     }
     // we are after genesis => bootstrap
+    // a snapshot can only be used as the initial state of a network that did not start yet
+    if let Some(snapshot_path) = final_state.read().get_initial_snapshot_path() {
+        return Err(BootstrapError::GeneralError(format!(
+            "initial snapshot {:?} can only be loaded before genesis, remove it from the configuration to bootstrap",
+            snapshot_path
+        )));
+    }
     massa_trace!("bootstrap.lib.get_state.init_from_others", {});
-    if bootstrap_config.bootstrap_list.is_empty() {
+    let mut server_list = BootstrapServerList::new(bootstrap_config);
+    if server_list.is_empty() {
         return Err(BootstrapError::GeneralError(
             "no bootstrap nodes found in list".into(),
         ));
     }
-    let mut shuffled_list = bootstrap_config.bootstrap_list.clone();
-    shuffled_list.shuffle(&mut StdRng::from_entropy());
-    let mut next_bootstrap_message: BootstrapClientMessage =
-        BootstrapClientMessage::AskBootstrapPart {
+    // resume from the journal of an interrupted bootstrap if there is one
+    let mut global_bootstrap_state = GlobalBootstrapState::new(final_state.clone());
+    let resumed_bootstrap_message =
+        match load_bootstrap_cursor(bootstrap_config).and_then(|resumed| {
+            resumed
+                .map(|(msg, journal)| {
+                    replay_bootstrap_journal(&mut global_bootstrap_state, journal).map(|_| msg)
+                })
+                .transpose()
+        }) {
+            Ok(msg) => msg,
+            Err(err) => {
+                // the disk ledger may be partially filled: it has to be cleared by a restart
+                remove_bootstrap_cursor(&bootstrap_config.bootstrap_cursor_path)?;
+                return Err(BootstrapError::GeneralError(format!(
+                    "could not resume bootstrap, please restart your node: {}",
+                    err
+                )));
+            }
+        };
+    let mut next_bootstrap_message: BootstrapClientMessage = match resumed_bootstrap_message {
+        Some(msg) => {
+            info!("Resuming interrupted bootstrap");
+            debug!("resumed bootstrap cursors: {:?}", msg);
+            msg
+        }
+        None => BootstrapClientMessage::AskBootstrapPart {
             last_slot: None,
             last_ledger_step: StreamingStep::Started,
             last_pool_step: StreamingStep::Started,
@@ -455,39 +1109,64 @@ pub async fn get_state(
             last_credits_step: StreamingStep::Started,
             last_ops_step: StreamingStep::Started,
             last_consensus_step: StreamingStep::Started,
-        };
-    let mut global_bootstrap_state = GlobalBootstrapState::new(final_state.clone());
+        },
+    };
     loop {
-        for (addr, pub_key) in shuffled_list.iter() {
-            if let Some(end) = end_timestamp {
-                if MassaTime::now().expect("could not get now time") > end {
-                    panic!("This episode has come to an end, please get the latest testnet node version to continue");
-                }
+        if let Some(end) = end_timestamp {
+            if MassaTime::now().expect("could not get now time") > end {
+                panic!("This episode has come to an end, please get the latest testnet node version to continue");
             }
-            info!("Start bootstrapping from {}", addr);
-            match connect_to_server(&mut establisher, bootstrap_config, addr, pub_key).await {
-                Ok(mut client) => {
-                    match bootstrap_from_server(bootstrap_config, &mut client, &mut next_bootstrap_message, &mut global_bootstrap_state,version)
-                    .await  // cancellable
-                    {
-                        Err(BootstrapError::ReceivedError(error)) => warn!("Error received from bootstrap server: {}", error),
-                        Err(e) => {
-                            warn!("Error while bootstrapping: {}", e);
-                            // We allow unused result because we don't care if an error is thrown when sending the error message to the server we will close the socket anyway.
-                            let _ = tokio::time::timeout(bootstrap_config.write_error_timeout.into(), client.send(&BootstrapClientMessage::BootstrapError { error: e.to_string() })).await;
-                        }
-                        Ok(()) => {
-                            return Ok(global_bootstrap_state)
-                        }
+        }
+        // resolve the servers again before each attempt as their DNS records may have changed
+        let candidates = server_list.candidates().await;
+        let Some((addr, pub_key)) = candidates.first() else {
+            warn!(
+                "No bootstrap server could be resolved, retrying in {}.",
+                format_duration(bootstrap_config.retry_delay.to_duration()).to_string()
+            );
+            sleep(bootstrap_config.retry_delay.into()).await;
+            continue;
+        };
+        info!("Start bootstrapping from {}", addr);
+        let secondary_servers: Vec<(SocketAddr, PublicKey)> = candidates
+            .iter()
+            .skip(1)
+            .take(bootstrap_config.secondary_bootstrap_server_count)
+            .cloned()
+            .collect();
+        match connect_to_server(&mut establisher, bootstrap_config, addr, pub_key).await {
+            Ok(mut client) => {
+                match bootstrap_from_server(bootstrap_config, &mut client, &mut establisher, &secondary_servers, &mut next_bootstrap_message, &mut global_bootstrap_state,version)
+                .await  // cancellable
+                {
+                    Err(BootstrapError::ReceivedError(error)) => warn!("Error received from bootstrap server: {}", error),
+                    Err(e @ BootstrapError::InconsistentCycleHistoryPart(..)) => {
+                        warn!("Error while bootstrapping: {}, dropping the received cycle history", e);
+                        reset_cycle_history(bootstrap_config, &mut next_bootstrap_message, &mut global_bootstrap_state);
+                        let _ = tokio::time::timeout(bootstrap_config.write_error_timeout.into(), client.send(&BootstrapClientMessage::BootstrapError { error: e.to_string() })).await;
+                    }
+                    Err(e @ (BootstrapError::FinalStateHashMismatch(..) | BootstrapError::FinalStateSlotMismatch(..))) => {
+                        // the bootstrapped state is corrupted and cannot be cleared while the node runs
+                        let _ = tokio::time::timeout(bootstrap_config.write_error_timeout.into(), client.send(&BootstrapClientMessage::BootstrapError { error: e.to_string() })).await;
+                        return Err(e);
+                    }
+                    Err(e) => {
+                        warn!("Error while bootstrapping: {}", e);
+                        // We allow unused result because we don't care if an error is thrown when sending the error message to the server we will close the socket anyway.
+                        let _ = tokio::time::timeout(bootstrap_config.write_error_timeout.into(), client.send(&BootstrapClientMessage::BootstrapError { error: e.to_string() })).await;
+                    }
+                    Ok(()) => {
+                        return Ok(global_bootstrap_state)
                     }
                 }
-                Err(e) => {
-                    warn!("Error while connecting to bootstrap server: {}", e);
-                }
-            };
+            }
+            Err(e) => {
+                warn!("Error while connecting to bootstrap server: {}", e);
+            }
+        };
 
-            info!("Bootstrap from server {} failed. Your node will try to bootstrap from another server in {}.", addr, format_duration(bootstrap_config.retry_delay.to_duration()).to_string());
-            sleep(bootstrap_config.retry_delay.into()).await;
-        }
+        server_list.record_failure(*addr);
+        info!("Bootstrap from server {} failed. Your node will try to bootstrap from another server in {}.", addr, format_duration(bootstrap_config.retry_delay.to_duration()).to_string());
+        sleep(bootstrap_config.retry_delay.into()).await;
     }
 }
@@ -4,12 +4,14 @@ use crate::error::BootstrapError;
 use crate::establisher::types::Duplex;
 use crate::messages::{
     BootstrapClientMessage, BootstrapClientMessageSerializer, BootstrapServerMessage,
-    BootstrapServerMessageDeserializer,
+    BootstrapServerMessageDeserializer, BOOTSTRAP_MESSAGE_FORMAT_VERSION,
 };
 use async_speed_limit::clock::StandardClock;
 use async_speed_limit::{Limiter, Resource};
 use massa_hash::{Hash, HASH_SIZE_BYTES};
-use massa_models::serialization::{DeserializeMinBEInt, SerializeMinBEInt};
+use massa_models::serialization::{
+    DeserializeMinBEInt, SerializeMinBEInt, VersionedDeserializer, VersionedSerializer,
+};
 use massa_models::version::{Version, VersionSerializer};
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_signature::{PublicKey, Signature, SIGNATURE_SIZE_BYTES};
@@ -154,28 +156,29 @@ impl BootstrapClientBinder {
         };
 
         // read message, check signature and check signature of the message sent just before then deserialize it
-        let message_deserializer = BootstrapServerMessageDeserializer::new(
-            self.thread_count,
-            self.endorsement_count,
-            self.max_advertise_length,
-            self.max_bootstrap_blocks,
-            self.max_operations_per_block,
-            self.max_bootstrap_final_state_parts_size,
-            self.max_async_pool_changes,
-            self.max_async_pool_length,
-            self.max_async_message_data,
-            self.max_ledger_changes_count,
-            self.max_datastore_key_length,
-            self.max_datastore_value_length,
-            self.max_datastore_entry_count,
-            self.max_bootstrap_error_length,
-            self.max_changes_slot_count,
-            self.max_rolls_length,
-            self.max_production_stats_length,
-            self.max_credits_length,
-            self.max_executed_ops_length,
-            self.max_ops_changes_length,
-        );
+        let message_deserializer =
+            VersionedDeserializer::new(BootstrapServerMessageDeserializer::new(
+                self.thread_count,
+                self.endorsement_count,
+                self.max_advertise_length,
+                self.max_bootstrap_blocks,
+                self.max_operations_per_block,
+                self.max_bootstrap_final_state_parts_size,
+                self.max_async_pool_changes,
+                self.max_async_pool_length,
+                self.max_async_message_data,
+                self.max_ledger_changes_count,
+                self.max_datastore_key_length,
+                self.max_datastore_value_length,
+                self.max_datastore_entry_count,
+                self.max_bootstrap_error_length,
+                self.max_changes_slot_count,
+                self.max_rolls_length,
+                self.max_production_stats_length,
+                self.max_credits_length,
+                self.max_executed_ops_length,
+                self.max_ops_changes_length,
+            ));
         let message = {
             if let Some(prev_message) = self.prev_message {
                 self.prev_message = Some(Hash::compute_from(&sig.to_bytes()));
@@ -209,7 +212,10 @@ impl BootstrapClientBinder {
     /// Send a message to the bootstrap server
     pub async fn send(&mut self, msg: &BootstrapClientMessage) -> Result<(), BootstrapError> {
         let mut msg_bytes = Vec::new();
-        let message_serializer = BootstrapClientMessageSerializer::new();
+        let message_serializer = VersionedSerializer::new(
+            BOOTSTRAP_MESSAGE_FORMAT_VERSION,
+            BootstrapClientMessageSerializer::new(),
+        );
         message_serializer.serialize(msg, &mut msg_bytes)?;
         let msg_len: u32 = msg_bytes.len().try_into().map_err(|e| {
             BootstrapError::GeneralError(format!("bootstrap message too large to encode: {}", e))
@@ -0,0 +1,421 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Persistence of the client bootstrap streaming cursor.
+//!
+//! After each received bootstrap part, the client appends a record to a journal on disk holding
+//! the composite `StreamingStep` cursor (ledger, async pool, cycle history, deferred credits,
+//! executed ops and consensus) reached after that part, along with the part itself.
+//! If the node stops while bootstrapping, the next start resumes the stream from the last cursor
+//! instead of downloading the whole final state again.
+//!
+//! Only the ledger is stored on disk, the other final state components and the consensus graph
+//! are kept in memory and are lost on restart. The journaled parts are therefore stripped of
+//! their ledger content only, and are replayed on resume to rebuild the in-memory components
+//! before every component continues its stream from its own cursor.
+//!
+//! When one of the streams completes, the journal is compacted: it is replaced by a single
+//! record holding the in-memory components received so far, so that it does not keep every part.
+
+use massa_async_pool::{AsyncMessage, AsyncMessageId, AsyncPoolDeserializer, AsyncPoolSerializer};
+use massa_consensus_exports::bootstrapable_graph::{
+    BootstrapableGraph, BootstrapableGraphDeserializer, BootstrapableGraphSerializer,
+};
+use massa_executed_ops::{ExecutedOpsDeserializer, ExecutedOpsSerializer};
+use massa_models::operation::OperationId;
+use massa_models::prehash::PreHashSet;
+use massa_models::serialization::{VersionedDeserializer, VersionedSerializer};
+use massa_models::slot::{Slot, SlotDeserializer, SlotSerializer};
+use massa_models::streaming_step::StreamingStep;
+use massa_pos_exports::{
+    CycleInfo, CycleInfoDeserializer, CycleInfoSerializer, DeferredCredits,
+    DeferredCreditsDeserializer, DeferredCreditsSerializer,
+};
+use massa_serialization::{
+    DeserializeError, Deserializer, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
+};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::ops::Bound::{Excluded, Included};
+use std::path::Path;
+
+use crate::{
+    error::BootstrapError,
+    messages::{
+        BootstrapClientMessage, BootstrapClientMessageDeserializer,
+        BootstrapClientMessageSerializer, BootstrapServerMessage,
+        BootstrapServerMessageDeserializer, BootstrapServerMessageSerializer,
+        BOOTSTRAP_MESSAGE_FORMAT_VERSION,
+    },
+    BootstrapConfig, GlobalBootstrapState,
+};
+
+/// Journal record holding a bootstrap part and the cursor reached after it
+const PART_RECORD: u8 = 0;
+/// Journal record noting that the cycle history received so far was dropped
+const CYCLE_HISTORY_RESET_RECORD: u8 = 1;
+/// Journal record holding the state received so far and the cursor reached, superseding the
+/// records before it
+const STATE_RECORD: u8 = 2;
+/// Size of a record header: record kind then payload length
+const RECORD_HEADER_SIZE: usize = 1 + 8;
+
+/// Entry of the bootstrap journal, to be replayed in order on resume
+#[derive(Debug)]
+pub(crate) enum JournalEntry {
+    /// bootstrap part received from the server, without its ledger content which is on disk
+    Part(BootstrapServerMessage),
+    /// the cycle history received so far was dropped
+    CycleHistoryReset,
+    /// in-memory components received before the journal was compacted
+    State {
+        /// final state slot
+        slot: Slot,
+        /// async pool messages
+        async_pool: BTreeMap<AsyncMessageId, AsyncMessage>,
+        /// PoS cycle history
+        cycle_history: Vec<CycleInfo>,
+        /// PoS deferred credits
+        deferred_credits: DeferredCredits,
+        /// executed operations
+        executed_ops: BTreeMap<Slot, PreHashSet<OperationId>>,
+        /// consensus final blocks
+        graph: BootstrapableGraph,
+    },
+}
+
+/// Serializes a `BootstrapServerMessage::BootstrapPart` to be journaled, without its ledger content.
+///
+/// The ledger content is left in `part` once serialized.
+pub(crate) fn serialize_journal_part(
+    part: &mut BootstrapServerMessage,
+) -> Result<Vec<u8>, BootstrapError> {
+    let BootstrapServerMessage::BootstrapPart { ledger_part, .. } = part else {
+        return Err(BootstrapError::GeneralError(format!(
+            "Try to journal a bootstrap part but the message was {:#?}",
+            part
+        )));
+    };
+    let ledger_part = std::mem::take(ledger_part);
+    let mut bytes = Vec::new();
+    let res = VersionedSerializer::new(
+        BOOTSTRAP_MESSAGE_FORMAT_VERSION,
+        BootstrapServerMessageSerializer::new(),
+    )
+    .serialize(part, &mut bytes);
+    if let BootstrapServerMessage::BootstrapPart {
+        ledger_part: stripped_ledger_part,
+        ..
+    } = part
+    {
+        *stripped_ledger_part = ledger_part;
+    }
+    res?;
+    Ok(bytes)
+}
+
+/// Builds a journal record: kind, payload length then payload.
+fn encode_record(kind: u8, payload: &[u8]) -> Vec<u8> {
+    let mut record = Vec::with_capacity(RECORD_HEADER_SIZE + payload.len());
+    record.push(kind);
+    record.extend((payload.len() as u64).to_be_bytes());
+    record.extend(payload);
+    record
+}
+
+/// Appends a record to the journal at `cfg.bootstrap_cursor_path`, creating it if needed.
+fn append_record(cfg: &BootstrapConfig, kind: u8, payload: &[u8]) -> Result<(), BootstrapError> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&cfg.bootstrap_cursor_path)?;
+    file.write_all(&encode_record(kind, payload))?;
+    file.sync_data()?;
+    Ok(())
+}
+
+/// Serializes the streaming cursor held by `next_bootstrap_message`.
+fn serialize_cursor(
+    next_bootstrap_message: &BootstrapClientMessage,
+) -> Result<Vec<u8>, BootstrapError> {
+    if !matches!(
+        next_bootstrap_message,
+        BootstrapClientMessage::AskBootstrapPart { .. }
+    ) {
+        return Err(BootstrapError::GeneralError(format!(
+            "Try to save a bootstrap cursor but the message was {:#?}",
+            next_bootstrap_message
+        )));
+    }
+    let mut payload = Vec::new();
+    VersionedSerializer::new(
+        BOOTSTRAP_MESSAGE_FORMAT_VERSION,
+        BootstrapClientMessageSerializer::new(),
+    )
+    .serialize(next_bootstrap_message, &mut payload)?;
+    Ok(payload)
+}
+
+/// Journals a bootstrap part serialized by `serialize_journal_part`, along with the streaming
+/// cursor held by `next_bootstrap_message` once that part is applied.
+pub(crate) fn save_bootstrap_cursor(
+    cfg: &BootstrapConfig,
+    next_bootstrap_message: &BootstrapClientMessage,
+    journaled_part: &[u8],
+) -> Result<(), BootstrapError> {
+    let mut payload = serialize_cursor(next_bootstrap_message)?;
+    payload.extend_from_slice(journaled_part);
+    append_record(cfg, PART_RECORD, &payload)
+}
+
+/// Replaces the journal by a single record holding the in-memory components received so far,
+/// along with the streaming cursor held by `next_bootstrap_message`.
+///
+/// The new journal is written next to the current one then renamed over it,
+/// so that a crash during the compaction keeps the current journal.
+pub(crate) fn compact_bootstrap_journal(
+    cfg: &BootstrapConfig,
+    next_bootstrap_message: &BootstrapClientMessage,
+    global_bootstrap_state: &GlobalBootstrapState,
+) -> Result<(), BootstrapError> {
+    let mut payload = serialize_cursor(next_bootstrap_message)?;
+    {
+        let final_state = global_bootstrap_state.final_state.read();
+        SlotSerializer::new().serialize(&final_state.slot, &mut payload)?;
+
+        let mut async_pool = BTreeMap::new();
+        let mut cursor = StreamingStep::Started;
+        loop {
+            let (part, new_cursor) = final_state.async_pool.get_pool_part(cursor);
+            if part.is_empty() {
+                break;
+            }
+            async_pool.extend(part);
+            cursor = new_cursor;
+        }
+        AsyncPoolSerializer::new().serialize(&async_pool, &mut payload)?;
+
+        U64VarIntSerializer::new().serialize(
+            &(final_state.pos_state.cycle_history.len() as u64),
+            &mut payload,
+        )?;
+        let cycle_info_serializer = CycleInfoSerializer::new();
+        for cycle_info in final_state.pos_state.cycle_history.iter() {
+            cycle_info_serializer.serialize(cycle_info, &mut payload)?;
+        }
+        DeferredCreditsSerializer::new()
+            .serialize(&final_state.pos_state.deferred_credits, &mut payload)?;
+        ExecutedOpsSerializer::new()
+            .serialize(&final_state.executed_ops.sorted_ops, &mut payload)?;
+    }
+    let empty_graph = BootstrapableGraph {
+        final_blocks: Vec::new(),
+    };
+    BootstrapableGraphSerializer::new().serialize(
+        global_bootstrap_state
+            .graph
+            .as_ref()
+            .unwrap_or(&empty_graph),
+        &mut payload,
+    )?;
+
+    let compacted_path = cfg.bootstrap_cursor_path.with_extension("compacted");
+    let mut file = std::fs::File::create(&compacted_path)?;
+    file.write_all(&encode_record(STATE_RECORD, &payload))?;
+    file.sync_data()?;
+    drop(file);
+    std::fs::rename(&compacted_path, &cfg.bootstrap_cursor_path)?;
+    Ok(())
+}
+
+/// Journals that the cycle history received so far was dropped.
+pub(crate) fn save_cycle_history_reset(cfg: &BootstrapConfig) -> Result<(), BootstrapError> {
+    if !cfg.bootstrap_cursor_path.exists() {
+        // nothing was journaled yet, so there is no cycle history to drop on resume
+        return Ok(());
+    }
+    append_record(cfg, CYCLE_HISTORY_RESET_RECORD, &[])
+}
+
+/// Loads the journal persisted at `cfg.bootstrap_cursor_path`, if any.
+///
+/// Returns the message to send to the server to resume the bootstrap, and the journal entries
+/// to replay beforehand, or `Ok(None)` if there is no bootstrap to resume.
+/// A record truncated by a crash during its write is dropped from the journal.
+pub(crate) fn load_bootstrap_cursor(
+    cfg: &BootstrapConfig,
+) -> Result<Option<(BootstrapClientMessage, Vec<JournalEntry>)>, BootstrapError> {
+    if !cfg.bootstrap_cursor_path.exists() {
+        return Ok(None);
+    }
+    let bytes = std::fs::read(&cfg.bootstrap_cursor_path)?;
+    let cursor_deserializer = VersionedDeserializer::new(BootstrapClientMessageDeserializer::new(
+        cfg.thread_count,
+        cfg.max_datastore_key_length,
+        cfg.consensus_bootstrap_part_size,
+    ));
+    let part_deserializer = VersionedDeserializer::new(BootstrapServerMessageDeserializer::new(
+        cfg.thread_count,
+        cfg.endorsement_count,
+        cfg.max_advertise_length,
+        cfg.max_bootstrap_blocks_length,
+        cfg.max_operations_per_block,
+        cfg.max_bootstrap_final_state_parts_size,
+        cfg.max_async_pool_changes,
+        cfg.max_async_pool_length,
+        cfg.max_async_message_data,
+        cfg.max_ledger_changes_count,
+        cfg.max_datastore_key_length,
+        cfg.max_datastore_value_length,
+        cfg.max_datastore_entry_count,
+        cfg.max_bootstrap_error_length,
+        cfg.max_changes_slot_count,
+        cfg.max_rolls_length,
+        cfg.max_production_stats_length,
+        cfg.max_credits_length,
+        cfg.max_executed_ops_length,
+        cfg.max_ops_changes_length,
+    ));
+    let slot_deserializer = SlotDeserializer::new(
+        (Included(0), Included(u64::MAX)),
+        (Included(0), Excluded(cfg.thread_count)),
+    );
+    let async_pool_deserializer = AsyncPoolDeserializer::new(
+        cfg.thread_count,
+        cfg.max_async_pool_length,
+        cfg.max_async_message_data,
+        cfg.max_datastore_key_length as u32,
+    );
+    let cycle_count_deserializer = U64VarIntDeserializer::new(Included(0), Included(u64::MAX));
+    let cycle_info_deserializer = CycleInfoDeserializer::new(u64::MAX, u64::MAX);
+    let deferred_credits_deserializer =
+        DeferredCreditsDeserializer::new(cfg.thread_count, u64::MAX);
+    let executed_ops_deserializer =
+        ExecutedOpsDeserializer::new(cfg.thread_count, u64::MAX, u64::MAX);
+    let graph_deserializer = BootstrapableGraphDeserializer::new(
+        cfg.thread_count,
+        cfg.endorsement_count,
+        cfg.max_bootstrap_blocks_length,
+        cfg.max_operations_per_block,
+    );
+    let invalid =
+        |err: String| BootstrapError::GeneralError(format!("invalid bootstrap cursor: {}", err));
+
+    let mut cursor = None;
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    while bytes.len() - offset >= RECORD_HEADER_SIZE {
+        let kind = bytes[offset];
+        let mut len_bytes = [0u8; 8];
+        len_bytes.copy_from_slice(&bytes[offset + 1..offset + RECORD_HEADER_SIZE]);
+        let Ok(len) = usize::try_from(u64::from_be_bytes(len_bytes)) else {
+            break;
+        };
+        let payload_start = offset + RECORD_HEADER_SIZE;
+        let Some(payload) = payload_start
+            .checked_add(len)
+            .and_then(|payload_end| bytes.get(payload_start..payload_end))
+        else {
+            break;
+        };
+        match kind {
+            PART_RECORD => {
+                let (rest, message) = cursor_deserializer
+                    .deserialize::<DeserializeError>(payload)
+                    .map_err(|err| invalid(err.to_string()))?;
+                let (rest, part) = part_deserializer
+                    .deserialize::<DeserializeError>(rest)
+                    .map_err(|err| invalid(err.to_string()))?;
+                if !rest.is_empty() {
+                    return Err(invalid("trailing bytes".to_string()));
+                }
+                match message {
+                    BootstrapClientMessage::AskBootstrapPart { .. } => cursor = Some(message),
+                    other => return Err(BootstrapError::UnexpectedClientMessage(other)),
+                }
+                entries.push(JournalEntry::Part(part));
+            }
+            STATE_RECORD => {
+                let (rest, message) = cursor_deserializer
+                    .deserialize::<DeserializeError>(payload)
+                    .map_err(|err| invalid(err.to_string()))?;
+                let (rest, slot) = slot_deserializer
+                    .deserialize::<DeserializeError>(rest)
+                    .map_err(|err| invalid(err.to_string()))?;
+                let (rest, async_pool) = async_pool_deserializer
+                    .deserialize::<DeserializeError>(rest)
+                    .map_err(|err| invalid(err.to_string()))?;
+                let (mut rest, cycle_count) = cycle_count_deserializer
+                    .deserialize::<DeserializeError>(rest)
+                    .map_err(|err| invalid(err.to_string()))?;
+                let mut cycle_history = Vec::new();
+                for _ in 0..cycle_count {
+                    let (new_rest, cycle_info) = cycle_info_deserializer
+                        .deserialize::<DeserializeError>(rest)
+                        .map_err(|err| invalid(err.to_string()))?;
+                    rest = new_rest;
+                    cycle_history.push(cycle_info);
+                }
+                let (rest, deferred_credits) = deferred_credits_deserializer
+                    .deserialize::<DeserializeError>(rest)
+                    .map_err(|err| invalid(err.to_string()))?;
+                let (rest, executed_ops) = executed_ops_deserializer
+                    .deserialize::<DeserializeError>(rest)
+                    .map_err(|err| invalid(err.to_string()))?;
+                let (rest, graph) = graph_deserializer
+                    .deserialize::<DeserializeError>(rest)
+                    .map_err(|err| invalid(err.to_string()))?;
+                if !rest.is_empty() {
+                    return Err(invalid("trailing bytes".to_string()));
+                }
+                match message {
+                    BootstrapClientMessage::AskBootstrapPart { .. } => cursor = Some(message),
+                    other => return Err(BootstrapError::UnexpectedClientMessage(other)),
+                }
+                // the state supersedes the records before it
+                entries.clear();
+                entries.push(JournalEntry::State {
+                    slot,
+                    async_pool,
+                    cycle_history,
+                    deferred_credits,
+                    executed_ops,
+                    graph,
+                });
+            }
+            CYCLE_HISTORY_RESET_RECORD => {
+                if let Some(BootstrapClientMessage::AskBootstrapPart {
+                    last_cycle_step, ..
+                }) = cursor.as_mut()
+                {
+                    *last_cycle_step = StreamingStep::Started;
+                }
+                entries.push(JournalEntry::CycleHistoryReset);
+            }
+            other => return Err(invalid(format!("unknown record kind {}", other))),
+        }
+        offset = payload_start + len;
+    }
+    if offset < bytes.len() {
+        // drop the truncated record so that the next records are appended after the valid ones
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&cfg.bootstrap_cursor_path)?
+            .set_len(offset as u64)?;
+    }
+    match cursor {
+        Some(message) => Ok(Some((message, entries))),
+        // the disk ledger may have been written without its cursor being journaled
+        None => Err(invalid("no bootstrap part to resume from".to_string())),
+    }
+}
+
+/// Removes the streaming cursor persisted at `path`, if any,
+/// along with a compacted journal left by an interrupted compaction.
+pub(crate) fn remove_bootstrap_cursor(path: &Path) -> Result<(), BootstrapError> {
+    for path in [path.to_path_buf(), path.with_extension("compacted")] {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
@@ -5,6 +5,7 @@ use displaydoc::Display;
 use massa_consensus_exports::error::ConsensusError;
 use massa_final_state::FinalStateError;
 use massa_hash::MassaHashError;
+use massa_models::slot::Slot;
 use massa_network_exports::NetworkError;
 use massa_pos_exports::PosError;
 use massa_serialization::SerializeError;
@@ -50,6 +51,16 @@ pub enum BootstrapError {
     IncompatibleVersionError(String),
     /// Received error: {0}
     ReceivedError(String),
+    /// bootstrap server resolution error: {0}
+    ResolutionError(String),
     /// clock error: {0}
     ClockError(String),
+    /// inconsistent cycle history part received from server: received cycle {0} but expected cycle {1}
+    InconsistentCycleHistoryPart(u64, u64),
+    /// cycle {0} received from a secondary bootstrap server does not match the hash provided by the primary server
+    UnverifiedCycle(u64),
+    /// final state hash mismatch with the bootstrap server at slot {0}, please restart your node to bootstrap from scratch
+    FinalStateHashMismatch(Slot),
+    /// bootstrapped final state is at slot {0} but the bootstrap server finished at slot {1}, please restart your node to bootstrap from scratch
+    FinalStateSlotMismatch(Slot, Slot),
 }
@@ -22,11 +22,13 @@ use std::sync::Arc;
 
 mod client;
 mod client_binder;
+mod cursor;
 mod error;
 mod establisher;
 mod messages;
 mod server;
 mod server_binder;
+mod server_list;
 mod settings;
 mod tools;
 pub use client::get_state;
@@ -34,6 +36,7 @@ pub use establisher::types;
 pub use messages::{
     BootstrapClientMessage, BootstrapClientMessageDeserializer, BootstrapClientMessageSerializer,
     BootstrapServerMessage, BootstrapServerMessageDeserializer, BootstrapServerMessageSerializer,
+    BOOTSTRAP_MESSAGE_FORMAT_VERSION,
 };
 pub use server::{start_bootstrap_server, BootstrapManager};
 pub use settings::BootstrapConfig;
@@ -51,6 +54,9 @@ pub struct GlobalBootstrapState {
 
     /// list of network peers
     pub peers: Option<BootstrapPeers>,
+
+    /// the bootstrap journal could not be written: it was removed and is not written anymore
+    journal_failed: bool,
 }
 
 impl GlobalBootstrapState {
@@ -59,6 +65,7 @@ impl GlobalBootstrapState {
             final_state,
             graph: None,
             peers: None,
+            journal_failed: false,
         }
     }
 }
@@ -9,12 +9,15 @@ use massa_consensus_exports::bootstrapable_graph::{
 };
 use massa_executed_ops::{ExecutedOpsDeserializer, ExecutedOpsSerializer};
 use massa_final_state::{StateChanges, StateChangesDeserializer, StateChangesSerializer};
+use massa_hash::{Hash, HashDeserializer, HashSerializer};
 use massa_ledger_exports::{KeyDeserializer, KeySerializer};
 use massa_models::block::{BlockId, BlockIdDeserializer, BlockIdSerializer};
+use massa_models::config::MAX_BOOTSTRAP_POS_CYCLES;
 use massa_models::operation::OperationId;
 use massa_models::prehash::PreHashSet;
 use massa_models::serialization::{
-    PreHashSetDeserializer, PreHashSetSerializer, VecU8Deserializer, VecU8Serializer,
+    FormatVersion, PreHashSetDeserializer, PreHashSetSerializer, VecU8Deserializer,
+    VecU8Serializer, VersionDispatch,
 };
 use massa_models::slot::{Slot, SlotDeserializer, SlotSerializer};
 use massa_models::streaming_step::{
@@ -23,8 +26,9 @@ use massa_models::streaming_step::{
 use massa_models::version::{Version, VersionDeserializer, VersionSerializer};
 use massa_network_exports::{BootstrapPeers, BootstrapPeersDeserializer, BootstrapPeersSerializer};
 use massa_pos_exports::{
-    CycleInfo, CycleInfoDeserializer, CycleInfoSerializer, DeferredCredits,
-    DeferredCreditsDeserializer, DeferredCreditsSerializer,
+    CycleHistoryCursor, CycleHistoryCursorDeserializer, CycleHistoryCursorSerializer, CycleInfo,
+    CycleInfoDeserializer, CycleInfoSerializer, DeferredCredits, DeferredCreditsDeserializer,
+    DeferredCreditsSerializer,
 };
 use massa_serialization::{
     Deserializer, OptionDeserializer, OptionSerializer, SerializeError, Serializer,
@@ -43,6 +47,13 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 use std::collections::BTreeMap;
 use std::convert::TryInto;
 use std::ops::Bound::{Excluded, Included};
+use std::ops::RangeInclusive;
+
+/// Current format version of the bootstrap messages, prefixed to every message on the wire
+/// and to the persisted bootstrap cursor.
+///
+/// Version 0 serialized the addresses without their category: it is not supported anymore.
+pub const BOOTSTRAP_MESSAGE_FORMAT_VERSION: FormatVersion = 1;
 
 /// Messages used during bootstrap by server
 #[derive(Debug, Clone)]
@@ -82,7 +93,12 @@ pub enum BootstrapServerMessage {
         consensus_outdated_ids: PreHashSet<BlockId>,
     },
     /// Message sent when the final state and consensus bootstrap are finished
-    BootstrapFinished,
+    BootstrapFinished {
+        /// Slot of the server final state when the bootstrap finished
+        slot: Slot,
+        /// Hash of the server final state at `slot`
+        final_state_hash: Hash,
+    },
     /// Slot sent to get state changes is too old
     SlotTooOld,
     /// Bootstrap error
@@ -90,6 +106,17 @@ pub enum BootstrapServerMessage {
         /// Error message
         error: String,
     },
+    /// Bootstrap hashes of the complete Proof of Stake cycles
+    CycleHashes {
+        /// Cycle numbers and their bootstrap hash
+        hashes: Vec<(u64, Hash)>,
+    },
+    /// Part of a complete Proof of Stake cycle
+    CompleteCyclePart {
+        /// The requested cycle part, without any address once the cycle was entirely sent,
+        /// `None` if the server does not hold the cycle as complete
+        cycle_part: Option<CycleInfo>,
+    },
 }
 
 #[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
@@ -101,6 +128,8 @@ enum MessageServerTypeId {
     FinalStateFinished = 3u32,
     SlotTooOld = 4u32,
     BootstrapError = 5u32,
+    CycleHashes = 6u32,
+    CompleteCyclePart = 7u32,
 }
 
 /// Serializer for `BootstrapServerMessage`
@@ -119,6 +148,7 @@ pub struct BootstrapServerMessageSerializer {
     opt_pos_cycle_serializer: OptionSerializer<CycleInfo, CycleInfoSerializer>,
     pos_credits_serializer: DeferredCreditsSerializer,
     exec_ops_serializer: ExecutedOpsSerializer,
+    hash_serializer: HashSerializer,
 }
 
 impl Default for BootstrapServerMessageSerializer {
@@ -145,6 +175,7 @@ impl BootstrapServerMessageSerializer {
             opt_pos_cycle_serializer: OptionSerializer::new(CycleInfoSerializer::new()),
             pos_credits_serializer: DeferredCreditsSerializer::new(),
             exec_ops_serializer: ExecutedOpsSerializer::new(),
+            hash_serializer: HashSerializer::new(),
         }
     }
 }
@@ -231,9 +262,14 @@ impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
                 self.block_id_set_serializer
                     .serialize(consensus_outdated_ids, buffer)?;
             }
-            BootstrapServerMessage::BootstrapFinished => {
+            BootstrapServerMessage::BootstrapFinished {
+                slot,
+                final_state_hash,
+            } => {
                 self.u32_serializer
                     .serialize(&u32::from(MessageServerTypeId::FinalStateFinished), buffer)?;
+                self.slot_serializer.serialize(slot, buffer)?;
+                self.hash_serializer.serialize(final_state_hash, buffer)?;
             }
             BootstrapServerMessage::SlotTooOld => {
                 self.u32_serializer
@@ -250,6 +286,22 @@ impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
                 )?;
                 buffer.extend(error.as_bytes())
             }
+            BootstrapServerMessage::CycleHashes { hashes } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::CycleHashes), buffer)?;
+                self.u64_serializer
+                    .serialize(&(hashes.len() as u64), buffer)?;
+                for (cycle, hash) in hashes {
+                    self.u64_serializer.serialize(cycle, buffer)?;
+                    self.hash_serializer.serialize(hash, buffer)?;
+                }
+            }
+            BootstrapServerMessage::CompleteCyclePart { cycle_part } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::CompleteCyclePart), buffer)?;
+                self.opt_pos_cycle_serializer
+                    .serialize(cycle_part, buffer)?;
+            }
         }
         Ok(())
     }
@@ -272,6 +324,9 @@ pub struct BootstrapServerMessageDeserializer {
     opt_pos_cycle_deserializer: OptionDeserializer<CycleInfo, CycleInfoDeserializer>,
     pos_credits_deserializer: DeferredCreditsDeserializer,
     exec_ops_deserializer: ExecutedOpsDeserializer,
+    length_cycle_hashes: U64VarIntDeserializer,
+    cycle_deserializer: U64VarIntDeserializer,
+    hash_deserializer: HashDeserializer,
 }
 
 impl BootstrapServerMessageDeserializer {
@@ -366,6 +421,12 @@ impl BootstrapServerMessageDeserializer {
                 max_executed_ops_length,
                 max_operations_per_block as u64,
             ),
+            length_cycle_hashes: U64VarIntDeserializer::new(
+                Included(0),
+                Included(MAX_BOOTSTRAP_POS_CYCLES as u64),
+            ),
+            cycle_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+            hash_deserializer: HashDeserializer::new(),
         }
     }
 }
@@ -502,9 +563,21 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
                     },
                 )
                 .parse(input),
-                MessageServerTypeId::FinalStateFinished => {
-                    Ok((input, BootstrapServerMessage::BootstrapFinished))
-                }
+                MessageServerTypeId::FinalStateFinished => tuple((
+                    context("Failed slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    }),
+                    context("Failed final_state_hash deserialization", |input| {
+                        self.hash_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(
+                    |(slot, final_state_hash)| BootstrapServerMessage::BootstrapFinished {
+                        slot,
+                        final_state_hash,
+                    },
+                )
+                .parse(input),
                 MessageServerTypeId::SlotTooOld => Ok((input, BootstrapServerMessage::SlotTooOld)),
                 MessageServerTypeId::BootstrapError => context(
                     "Failed BootstrapError deserialization",
@@ -516,12 +589,53 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
                     error: String::from_utf8_lossy(error).into_owned(),
                 })
                 .parse(input),
+                MessageServerTypeId::CycleHashes => context(
+                    "Failed cycle hashes deserialization",
+                    length_count(
+                        context("Failed length deserialization", |input| {
+                            self.length_cycle_hashes.deserialize(input)
+                        }),
+                        tuple((
+                            |input| self.cycle_deserializer.deserialize(input),
+                            |input| self.hash_deserializer.deserialize(input),
+                        )),
+                    ),
+                )
+                .map(|hashes| BootstrapServerMessage::CycleHashes { hashes })
+                .parse(input),
+                MessageServerTypeId::CompleteCyclePart => {
+                    context("Failed complete cycle part deserialization", |input| {
+                        self.opt_pos_cycle_deserializer.deserialize(input)
+                    })
+                    .map(|cycle_part| BootstrapServerMessage::CompleteCyclePart { cycle_part })
+                    .parse(input)
+                }
             }
         })
         .parse(buffer)
     }
 }
 
+impl VersionDispatch<BootstrapServerMessage> for BootstrapServerMessageDeserializer {
+    fn supported_versions(&self) -> RangeInclusive<FormatVersion> {
+        BOOTSTRAP_MESSAGE_FORMAT_VERSION..=BOOTSTRAP_MESSAGE_FORMAT_VERSION
+    }
+
+    fn deserialize_version<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        version: FormatVersion,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], BootstrapServerMessage, E> {
+        match version {
+            BOOTSTRAP_MESSAGE_FORMAT_VERSION => self.deserialize(buffer),
+            _ => Err(nom::Err::Error(ParseError::from_error_kind(
+                buffer,
+                nom::error::ErrorKind::Verify,
+            ))),
+        }
+    }
+}
+
 /// Messages used during bootstrap by client
 #[derive(Debug, Clone)]
 pub enum BootstrapClientMessage {
@@ -535,8 +649,8 @@ pub enum BootstrapClientMessage {
         last_ledger_step: StreamingStep<Vec<u8>>,
         /// Last received async message id
         last_pool_step: StreamingStep<AsyncMessageId>,
-        /// Last received Proof of Stake cycle
-        last_cycle_step: StreamingStep<u64>,
+        /// Last received Proof of Stake cycle part
+        last_cycle_step: StreamingStep<CycleHistoryCursor>,
         /// Last received Proof of Stake credits slot
         last_credits_step: StreamingStep<Slot>,
         /// Last received executed operation associated slot
@@ -551,6 +665,13 @@ pub enum BootstrapClientMessage {
     },
     /// Bootstrap succeed
     BootstrapSuccess,
+    /// Ask for the bootstrap hashes of the complete Proof of Stake cycles
+    AskCycleHashes,
+    /// Ask for a part of a complete Proof of Stake cycle
+    AskCompleteCycle {
+        /// Requested cycle and last address received for it, `None` to ask for the first part
+        cursor: CycleHistoryCursor,
+    },
 }
 
 #[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
@@ -560,6 +681,8 @@ enum MessageClientTypeId {
     AskFinalStatePart = 1u32,
     BootstrapError = 2u32,
     BootstrapSuccess = 3u32,
+    AskCycleHashes = 4u32,
+    AskCompleteCycle = 5u32,
 }
 
 /// Serializer for `BootstrapClientMessage`
@@ -568,7 +691,9 @@ pub struct BootstrapClientMessageSerializer {
     slot_serializer: SlotSerializer,
     ledger_step_serializer: StreamingStepSerializer<Vec<u8>, KeySerializer>,
     pool_step_serializer: StreamingStepSerializer<AsyncMessageId, AsyncMessageIdSerializer>,
-    cycle_step_serializer: StreamingStepSerializer<u64, U64VarIntSerializer>,
+    cycle_step_serializer:
+        StreamingStepSerializer<CycleHistoryCursor, CycleHistoryCursorSerializer>,
+    cycle_cursor_serializer: CycleHistoryCursorSerializer,
     slot_step_serializer: StreamingStepSerializer<Slot, SlotSerializer>,
     block_ids_step_serializer: StreamingStepSerializer<
         PreHashSet<BlockId>,
@@ -584,7 +709,8 @@ impl BootstrapClientMessageSerializer {
             slot_serializer: SlotSerializer::new(),
             ledger_step_serializer: StreamingStepSerializer::new(KeySerializer::new()),
             pool_step_serializer: StreamingStepSerializer::new(AsyncMessageIdSerializer::new()),
-            cycle_step_serializer: StreamingStepSerializer::new(U64VarIntSerializer::new()),
+            cycle_step_serializer: StreamingStepSerializer::new(CycleHistoryCursorSerializer::new()),
+            cycle_cursor_serializer: CycleHistoryCursorSerializer::new(),
             slot_step_serializer: StreamingStepSerializer::new(SlotSerializer::new()),
             block_ids_step_serializer: StreamingStepSerializer::new(PreHashSetSerializer::new(
                 BlockIdSerializer::new(),
@@ -664,6 +790,15 @@ impl Serializer<BootstrapClientMessage> for BootstrapClientMessageSerializer {
                 self.u32_serializer
                     .serialize(&u32::from(MessageClientTypeId::BootstrapSuccess), buffer)?;
             }
+            BootstrapClientMessage::AskCycleHashes => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskCycleHashes), buffer)?;
+            }
+            BootstrapClientMessage::AskCompleteCycle { cursor } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskCompleteCycle), buffer)?;
+                self.cycle_cursor_serializer.serialize(cursor, buffer)?;
+            }
         }
         Ok(())
     }
@@ -676,7 +811,9 @@ pub struct BootstrapClientMessageDeserializer {
     slot_deserializer: SlotDeserializer,
     ledger_step_deserializer: StreamingStepDeserializer<Vec<u8>, KeyDeserializer>,
     pool_step_deserializer: StreamingStepDeserializer<AsyncMessageId, AsyncMessageIdDeserializer>,
-    cycle_step_deserializer: StreamingStepDeserializer<u64, U64VarIntDeserializer>,
+    cycle_step_deserializer:
+        StreamingStepDeserializer<CycleHistoryCursor, CycleHistoryCursorDeserializer>,
+    cycle_cursor_deserializer: CycleHistoryCursorDeserializer,
     slot_step_deserializer: StreamingStepDeserializer<Slot, SlotDeserializer>,
     block_ids_step_deserializer: StreamingStepDeserializer<
         PreHashSet<BlockId>,
@@ -704,10 +841,10 @@ impl BootstrapClientMessageDeserializer {
             pool_step_deserializer: StreamingStepDeserializer::new(
                 AsyncMessageIdDeserializer::new(thread_count),
             ),
-            cycle_step_deserializer: StreamingStepDeserializer::new(U64VarIntDeserializer::new(
-                Included(0),
-                Included(u64::MAX),
-            )),
+            cycle_step_deserializer: StreamingStepDeserializer::new(
+                CycleHistoryCursorDeserializer::new(),
+            ),
+            cycle_cursor_deserializer: CycleHistoryCursorDeserializer::new(),
             slot_step_deserializer: StreamingStepDeserializer::new(SlotDeserializer::new(
                 (Included(0), Included(u64::MAX)),
                 (Included(0), Excluded(thread_count)),
@@ -840,8 +977,38 @@ impl Deserializer<BootstrapClientMessage> for BootstrapClientMessageDeserializer
                 MessageClientTypeId::BootstrapSuccess => {
                     Ok((input, BootstrapClientMessage::BootstrapSuccess))
                 }
+                MessageClientTypeId::AskCycleHashes => {
+                    Ok((input, BootstrapClientMessage::AskCycleHashes))
+                }
+                MessageClientTypeId::AskCompleteCycle => {
+                    context("Failed cycle cursor deserialization", |input| {
+                        self.cycle_cursor_deserializer.deserialize(input)
+                    })
+                    .map(|cursor| BootstrapClientMessage::AskCompleteCycle { cursor })
+                    .parse(input)
+                }
             }
         })
         .parse(buffer)
     }
 }
+
+impl VersionDispatch<BootstrapClientMessage> for BootstrapClientMessageDeserializer {
+    fn supported_versions(&self) -> RangeInclusive<FormatVersion> {
+        BOOTSTRAP_MESSAGE_FORMAT_VERSION..=BOOTSTRAP_MESSAGE_FORMAT_VERSION
+    }
+
+    fn deserialize_version<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        version: FormatVersion,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], BootstrapClientMessage, E> {
+        match version {
+            BOOTSTRAP_MESSAGE_FORMAT_VERSION => self.deserialize(buffer),
+            _ => Err(nom::Err::Error(ParseError::from_error_kind(
+                buffer,
+                nom::error::ErrorKind::Verify,
+            ))),
+        }
+    }
+}
@@ -10,6 +10,7 @@ use massa_models::{
     version::Version,
 };
 use massa_network_exports::NetworkCommandSender;
+use massa_pos_exports::CycleHistoryCursor;
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use parking_lot::RwLock;
@@ -302,7 +303,7 @@ pub async fn stream_bootstrap_information(
     mut last_slot: Option<Slot>,
     mut last_ledger_step: StreamingStep<Vec<u8>>,
     mut last_pool_step: StreamingStep<AsyncMessageId>,
-    mut last_cycle_step: StreamingStep<u64>,
+    mut last_cycle_step: StreamingStep<CycleHistoryCursor>,
     mut last_credits_step: StreamingStep<Slot>,
     mut last_ops_step: StreamingStep<Slot>,
     mut last_consensus_step: StreamingStep<PreHashSet<BlockId>>,
@@ -316,6 +317,7 @@ pub async fn stream_bootstrap_information(
         }
 
         let current_slot;
+        let current_state_hash;
         let ledger_part;
         let async_pool_part;
         let pos_cycle_part;
@@ -344,7 +346,7 @@ pub async fn stream_bootstrap_information(
 
             let (credits_data, new_credits_step) = final_state_read
                 .pos_state
-                .get_deferred_credits_part(last_credits_step);
+                .get_deferred_credits_part(last_credits_step, final_state_read.slot);
             pos_credits_part = credits_data;
 
             let (ops_data, new_ops_step) = final_state_read
@@ -385,6 +387,7 @@ pub async fn stream_bootstrap_information(
             last_ops_step = new_ops_step;
             last_slot = Some(final_state_read.slot);
             current_slot = final_state_read.slot;
+            current_state_hash = final_state_read.final_state_hash;
         }
 
         if slot_too_old {
@@ -457,7 +460,10 @@ pub async fn stream_bootstrap_information(
         {
             match tokio::time::timeout(
                 write_timeout,
-                server.send(BootstrapServerMessage::BootstrapFinished),
+                server.send(BootstrapServerMessage::BootstrapFinished {
+                    slot: current_slot,
+                    final_state_hash: current_state_hash,
+                }),
             )
             .await
             {
@@ -610,6 +616,40 @@ async fn manage_bootstrap(
                     )
                     .await?;
                 }
+                BootstrapClientMessage::AskCycleHashes => {
+                    let hashes = final_state.read().pos_state.get_complete_cycle_hashes();
+                    match tokio::time::timeout(
+                        write_timeout,
+                        server.send(BootstrapServerMessage::CycleHashes { hashes }),
+                    )
+                    .await
+                    {
+                        Err(_) => Err(std::io::Error::new(
+                            std::io::ErrorKind::TimedOut,
+                            "bootstrap cycle hashes send timed out",
+                        )
+                        .into()),
+                        Ok(Err(e)) => Err(e),
+                        Ok(Ok(_)) => Ok(()),
+                    }?;
+                }
+                BootstrapClientMessage::AskCompleteCycle { cursor } => {
+                    let cycle_part = final_state.read().pos_state.get_complete_cycle_part(cursor);
+                    match tokio::time::timeout(
+                        write_timeout,
+                        server.send(BootstrapServerMessage::CompleteCyclePart { cycle_part }),
+                    )
+                    .await
+                    {
+                        Err(_) => Err(std::io::Error::new(
+                            std::io::ErrorKind::TimedOut,
+                            "bootstrap complete cycle send timed out",
+                        )
+                        .into()),
+                        Ok(Err(e)) => Err(e),
+                        Ok(Ok(_)) => Ok(()),
+                    }?;
+                }
                 BootstrapClientMessage::BootstrapSuccess => break Ok(()),
                 BootstrapClientMessage::BootstrapError { error } => {
                     break Err(BootstrapError::ReceivedError(error));
@@ -4,13 +4,15 @@ use crate::error::BootstrapError;
 use crate::establisher::types::Duplex;
 use crate::messages::{
     BootstrapClientMessage, BootstrapClientMessageDeserializer, BootstrapServerMessage,
-    BootstrapServerMessageSerializer,
+    BootstrapServerMessageSerializer, BOOTSTRAP_MESSAGE_FORMAT_VERSION,
 };
 use async_speed_limit::clock::StandardClock;
 use async_speed_limit::{Limiter, Resource};
 use massa_hash::Hash;
 use massa_hash::HASH_SIZE_BYTES;
-use massa_models::serialization::{DeserializeMinBEInt, SerializeMinBEInt};
+use massa_models::serialization::{
+    DeserializeMinBEInt, SerializeMinBEInt, VersionedDeserializer, VersionedSerializer,
+};
 use massa_models::version::{Version, VersionDeserializer, VersionSerializer};
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_signature::KeyPair;
@@ -99,7 +101,11 @@ impl BootstrapServerBinder {
     pub async fn send(&mut self, msg: BootstrapServerMessage) -> Result<(), BootstrapError> {
         // serialize message
         let mut msg_bytes = Vec::new();
-        BootstrapServerMessageSerializer::new().serialize(&msg, &mut msg_bytes)?;
+        VersionedSerializer::new(
+            BOOTSTRAP_MESSAGE_FORMAT_VERSION,
+            BootstrapServerMessageSerializer::new(),
+        )
+        .serialize(&msg, &mut msg_bytes)?;
         let msg_len: u32 = msg_bytes.len().try_into().map_err(|e| {
             BootstrapError::GeneralError(format!("bootstrap message too large to encode: {}", e))
         })?;
@@ -183,11 +189,11 @@ impl BootstrapServerBinder {
         }
 
         // deserialize message
-        let (_, msg) = BootstrapClientMessageDeserializer::new(
+        let (_, msg) = VersionedDeserializer::new(BootstrapClientMessageDeserializer::new(
             self.thread_count,
             self.max_datastore_key_length,
             self.consensus_bootstrap_part_size,
-        )
+        ))
         .deserialize::<DeserializeError>(&msg_bytes)
         .map_err(|err| BootstrapError::GeneralError(format!("{}", err)))?;
 
@@ -0,0 +1,180 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Bootstrap servers the client can connect to.
+//!
+//! The list combines the configured IP addresses with DNS names that are resolved again before
+//! each bootstrap attempt, so that the servers can move without the configuration being edited.
+
+use crate::error::BootstrapError;
+use crate::BootstrapConfig;
+use massa_signature::PublicKey;
+use rand::{
+    prelude::{SliceRandom, StdRng},
+    SeedableRng,
+};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use tracing::warn;
+use trust_dns_resolver::TokioAsyncResolver;
+
+/// Bootstrap servers along with the number of failed attempts of each endpoint
+pub(crate) struct BootstrapServerList {
+    /// servers configured with their IP address
+    static_servers: Vec<(SocketAddr, PublicKey)>,
+    /// servers configured with a DNS name
+    dns_servers: Vec<(String, PublicKey)>,
+    /// number of failed bootstrap attempts per endpoint
+    failures: HashMap<SocketAddr, u64>,
+    /// DNS resolver, only built if DNS names are configured
+    resolver: Option<TokioAsyncResolver>,
+}
+
+impl BootstrapServerList {
+    /// Creates the list of the servers of the bootstrap configuration
+    pub(crate) fn new(config: &BootstrapConfig) -> Self {
+        let resolver = if config.bootstrap_dns_list.is_empty() {
+            None
+        } else {
+            match TokioAsyncResolver::tokio_from_system_conf() {
+                Ok(resolver) => Some(resolver),
+                Err(err) => {
+                    warn!("could not create the bootstrap DNS resolver: {}", err);
+                    None
+                }
+            }
+        };
+        BootstrapServerList {
+            static_servers: config.bootstrap_list.clone(),
+            dns_servers: config.bootstrap_dns_list.clone(),
+            failures: HashMap::new(),
+            resolver,
+        }
+    }
+
+    /// Returns true if no server is configured
+    pub(crate) fn is_empty(&self) -> bool {
+        self.static_servers.is_empty() && self.dns_servers.is_empty()
+    }
+
+    /// Resolves the configured servers into endpoints.
+    ///
+    /// The endpoints are shuffled and then sorted by increasing number of failures,
+    /// so that the servers that never failed are tried first, in a random order.
+    /// Names that cannot be resolved are skipped.
+    pub(crate) async fn candidates(&self) -> Vec<(SocketAddr, PublicKey)> {
+        let mut candidates = self.static_servers.clone();
+        if let Some(resolver) = &self.resolver {
+            for (name, pub_key) in self.dns_servers.iter() {
+                match resolve(resolver, name).await {
+                    Ok(addrs) => candidates.extend(addrs.into_iter().map(|addr| (addr, *pub_key))),
+                    Err(err) => warn!("could not resolve bootstrap server {}: {}", name, err),
+                }
+            }
+        }
+
+        let mut seen = HashSet::new();
+        candidates.retain(|(addr, _)| seen.insert(*addr));
+        candidates.shuffle(&mut StdRng::from_entropy());
+        candidates.sort_by_key(|(addr, _)| self.failures.get(addr).copied().unwrap_or_default());
+        candidates
+    }
+
+    /// Records a failed bootstrap attempt on `addr`
+    pub(crate) fn record_failure(&mut self, addr: SocketAddr) {
+        *self.failures.entry(addr).or_default() += 1;
+    }
+}
+
+/// Resolves a bootstrap server name into endpoints.
+///
+/// A `host:port` name is resolved through its A/AAAA records,
+/// any other name (e.g. `_massa-bootstrap._tcp.example.com`) through its SRV records.
+async fn resolve(
+    resolver: &TokioAsyncResolver,
+    name: &str,
+) -> Result<Vec<SocketAddr>, BootstrapError> {
+    if let Some((host, port)) = name.rsplit_once(':') && let Ok(port) = port.parse::<u16>() {
+        let ips = resolver
+            .lookup_ip(host)
+            .await
+            .map_err(|err| BootstrapError::ResolutionError(err.to_string()))?;
+        return Ok(ips.iter().map(|ip| SocketAddr::new(ip, port)).collect());
+    }
+
+    let records = resolver
+        .srv_lookup(name)
+        .await
+        .map_err(|err| BootstrapError::ResolutionError(err.to_string()))?;
+    let mut addrs = Vec::new();
+    for record in records.iter() {
+        let target = record.target().to_utf8();
+        match resolver.lookup_ip(target.as_str()).await {
+            Ok(ips) => addrs.extend(ips.iter().map(|ip| SocketAddr::new(ip, record.port()))),
+            Err(err) => warn!("could not resolve bootstrap server {}: {}", target, err),
+        }
+    }
+    Ok(addrs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+
+    fn server_list(addrs: &[&str]) -> BootstrapServerList {
+        let pub_key = KeyPair::generate().get_public_key();
+        BootstrapServerList {
+            static_servers: addrs
+                .iter()
+                .map(|addr| (addr.parse().unwrap(), pub_key))
+                .collect(),
+            dns_servers: Vec::new(),
+            failures: HashMap::new(),
+            resolver: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_candidates_without_failures() {
+        let list = server_list(&["127.0.0.1:1", "127.0.0.1:2", "127.0.0.1:1"]);
+        assert!(!list.is_empty());
+        let mut addrs: Vec<SocketAddr> = list
+            .candidates()
+            .await
+            .into_iter()
+            .map(|(addr, _)| addr)
+            .collect();
+        addrs.sort();
+        assert_eq!(
+            addrs,
+            vec![
+                "127.0.0.1:1".parse::<SocketAddr>().unwrap(),
+                "127.0.0.1:2".parse().unwrap()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_servers_are_tried_last() {
+        let mut list = server_list(&["127.0.0.1:1", "127.0.0.1:2", "127.0.0.1:3"]);
+        list.record_failure("127.0.0.1:1".parse().unwrap());
+        list.record_failure("127.0.0.1:1".parse().unwrap());
+        list.record_failure("127.0.0.1:2".parse().unwrap());
+        for _ in 0..10 {
+            let addrs: Vec<SocketAddr> = list
+                .candidates()
+                .await
+                .into_iter()
+                .map(|(addr, _)| addr)
+                .collect();
+            assert_eq!(
+                addrs,
+                vec![
+                    "127.0.0.1:3".parse::<SocketAddr>().unwrap(),
+                    "127.0.0.1:2".parse().unwrap(),
+                    "127.0.0.1:1".parse().unwrap()
+                ]
+            );
+        }
+    }
+}
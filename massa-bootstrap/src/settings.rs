@@ -10,10 +10,19 @@ use std::{net::SocketAddr, path::PathBuf};
 pub struct BootstrapConfig {
     /// Ip address of our bootstrap nodes and their public key.
     pub bootstrap_list: Vec<(SocketAddr, PublicKey)>,
+    /// DNS names of bootstrap nodes and their public key, resolved before each bootstrap attempt.
+    /// `host:port` names are resolved through their A/AAAA records, other names through their SRV records.
+    pub bootstrap_dns_list: Vec<(String, PublicKey)>,
     /// Path to the bootstrap whitelist file. This whitelist define IPs that can bootstrap on your node.
     pub bootstrap_whitelist_path: PathBuf,
     /// Path to the bootstrap blacklist file. This whitelist define IPs that will not be able to bootstrap on your node. This list is optional.
     pub bootstrap_blacklist_path: PathBuf,
+    /// Path to the journal where the client persists the received bootstrap parts and its streaming cursor, to resume an interrupted bootstrap.
+    pub bootstrap_cursor_path: PathBuf,
+    /// Number of secondary bootstrap servers the complete Proof of Stake cycles are downloaded from in parallel, 0 to download everything from a single server.
+    pub secondary_bootstrap_server_count: usize,
+    /// Maximum number of received bootstrap parts waiting to be applied while the next ones are downloaded.
+    pub max_pending_bootstrap_parts: usize,
     /// Port to listen if we choose to allow other nodes to use us as bootstrap node.
     pub bind: Option<SocketAddr>,
     /// connection timeout
@@ -0,0 +1,141 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use crate::messages::{
+    BootstrapClientMessageDeserializer, BootstrapClientMessageSerializer,
+    BootstrapServerMessageDeserializer, BootstrapServerMessageSerializer,
+    BOOTSTRAP_MESSAGE_FORMAT_VERSION,
+};
+use massa_models::config::{
+    CONSENSUS_BOOTSTRAP_PART_SIZE, ENDORSEMENT_COUNT, MAX_ADVERTISE_LENGTH, MAX_ASYNC_MESSAGE_DATA,
+    MAX_ASYNC_POOL_LENGTH, MAX_BOOTSTRAP_ASYNC_POOL_CHANGES, MAX_BOOTSTRAP_BLOCKS,
+    MAX_BOOTSTRAP_ERROR_LENGTH, MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE, MAX_DATASTORE_ENTRY_COUNT,
+    MAX_DATASTORE_KEY_LENGTH, MAX_DATASTORE_VALUE_LENGTH, MAX_DEFERRED_CREDITS_LENGTH,
+    MAX_EXECUTED_OPS_CHANGES_LENGTH, MAX_EXECUTED_OPS_LENGTH, MAX_LEDGER_CHANGES_COUNT,
+    MAX_OPERATIONS_PER_BLOCK, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, THREAD_COUNT,
+};
+use massa_models::serialization::{VersionedDeserializer, VersionedSerializer};
+use massa_models::test_exports::{assert_golden_vectors, GoldenVector};
+use massa_serialization::{DeserializeError, Deserializer};
+use serial_test::serial;
+
+/// Bootstrap server messages serialized in format version 1
+fn server_message_vectors() -> Vec<GoldenVector> {
+    vec![
+        GoldenVector::new("server SlotTooOld", 1, vec![1, 4]),
+        GoldenVector::new("server BootstrapError", 1, vec![1, 5, 3, b'e', b'r', b'r']),
+        GoldenVector::new("server CycleHashes (empty)", 1, vec![1, 6, 0]),
+        GoldenVector::new("server CompleteCyclePart (none)", 1, vec![1, 7, b'0']),
+    ]
+}
+
+/// Bootstrap client messages serialized in format version 1
+fn client_message_vectors() -> Vec<GoldenVector> {
+    vec![
+        GoldenVector::new("client AskBootstrapPeers", 1, vec![1, 0]),
+        GoldenVector::new("client BootstrapError", 1, vec![1, 2, 3, b'e', b'r', b'r']),
+        GoldenVector::new("client BootstrapSuccess", 1, vec![1, 3]),
+        GoldenVector::new("client AskCycleHashes", 1, vec![1, 4]),
+        GoldenVector::new("client AskCompleteCycle", 1, vec![1, 5, 200, 1, b'0']),
+    ]
+}
+
+/// Bootstrap messages serialized in format version 0.
+///
+/// Version 0 serialized the addresses without their category byte. Their category cannot be
+/// recovered, so version 0 is refused as a whole, even for the messages without address.
+fn refused_version_0_vectors() -> Vec<GoldenVector> {
+    vec![
+        GoldenVector::new("client AskBootstrapPeers", 0, vec![0, 0]),
+        GoldenVector::new("client BootstrapSuccess", 0, vec![0, 3]),
+        GoldenVector::new("client AskCompleteCycle", 0, vec![0, 5, 200, 1, b'0']),
+    ]
+}
+
+#[test]
+#[serial]
+fn test_bootstrap_server_message_golden_vectors() {
+    let serializer = VersionedSerializer::new(
+        BOOTSTRAP_MESSAGE_FORMAT_VERSION,
+        BootstrapServerMessageSerializer::new(),
+    );
+    let deserializer = VersionedDeserializer::new(BootstrapServerMessageDeserializer::new(
+        THREAD_COUNT,
+        ENDORSEMENT_COUNT,
+        MAX_ADVERTISE_LENGTH,
+        MAX_BOOTSTRAP_BLOCKS,
+        MAX_OPERATIONS_PER_BLOCK,
+        MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE,
+        MAX_BOOTSTRAP_ASYNC_POOL_CHANGES,
+        MAX_ASYNC_POOL_LENGTH,
+        MAX_ASYNC_MESSAGE_DATA,
+        MAX_LEDGER_CHANGES_COUNT,
+        MAX_DATASTORE_KEY_LENGTH,
+        MAX_DATASTORE_VALUE_LENGTH,
+        MAX_DATASTORE_ENTRY_COUNT,
+        MAX_BOOTSTRAP_ERROR_LENGTH,
+        1000,
+        MAX_ROLLS_COUNT_LENGTH,
+        MAX_PRODUCTION_STATS_LENGTH,
+        MAX_DEFERRED_CREDITS_LENGTH,
+        MAX_EXECUTED_OPS_LENGTH,
+        MAX_EXECUTED_OPS_CHANGES_LENGTH,
+    ));
+    assert_golden_vectors(
+        &serializer,
+        &deserializer,
+        BOOTSTRAP_MESSAGE_FORMAT_VERSION,
+        &server_message_vectors(),
+    );
+}
+
+#[test]
+#[serial]
+fn test_bootstrap_client_message_golden_vectors() {
+    let serializer = VersionedSerializer::new(
+        BOOTSTRAP_MESSAGE_FORMAT_VERSION,
+        BootstrapClientMessageSerializer::new(),
+    );
+    let deserializer = VersionedDeserializer::new(BootstrapClientMessageDeserializer::new(
+        THREAD_COUNT,
+        MAX_DATASTORE_KEY_LENGTH,
+        CONSENSUS_BOOTSTRAP_PART_SIZE,
+    ));
+    assert_golden_vectors(
+        &serializer,
+        &deserializer,
+        BOOTSTRAP_MESSAGE_FORMAT_VERSION,
+        &client_message_vectors(),
+    );
+}
+
+#[test]
+#[serial]
+fn test_bootstrap_message_unknown_format_version() {
+    let deserializer = VersionedDeserializer::new(BootstrapClientMessageDeserializer::new(
+        THREAD_COUNT,
+        MAX_DATASTORE_KEY_LENGTH,
+        CONSENSUS_BOOTSTRAP_PART_SIZE,
+    ));
+    assert!(deserializer
+        .deserialize::<DeserializeError>(&[2, 0])
+        .is_err());
+}
+
+#[test]
+#[serial]
+fn test_bootstrap_message_format_version_0_refused() {
+    let deserializer = VersionedDeserializer::new(BootstrapClientMessageDeserializer::new(
+        THREAD_COUNT,
+        MAX_DATASTORE_KEY_LENGTH,
+        CONSENSUS_BOOTSTRAP_PART_SIZE,
+    ));
+    for vector in refused_version_0_vectors() {
+        assert!(
+            deserializer
+                .deserialize::<DeserializeError>(&vector.bytes)
+                .is_err(),
+            "golden vector {} of format version 0 was decoded",
+            vector.name
+        );
+    }
+}
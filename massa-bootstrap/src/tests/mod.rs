@@ -1,6 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 mod binders;
+mod golden_vectors;
 pub mod mock_establisher;
 mod scenarios;
 pub mod tools;
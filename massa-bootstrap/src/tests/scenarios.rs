@@ -3,47 +3,69 @@
 use super::{
     mock_establisher,
     tools::{
-        bridge_mock_streams, get_boot_state, get_peers, get_random_final_state_bootstrap,
-        get_random_ledger_changes, wait_network_command,
+        bridge_mock_streams, get_boot_state, get_dummy_block_id, get_peers,
+        get_random_final_state_bootstrap, get_random_ledger_changes, wait_network_command,
     },
 };
+use crate::client::{
+    add_complete_cycle_part, apply_final_state_part, replay_bootstrap_journal, reset_cycle_history,
+};
+use crate::cursor::{
+    compact_bootstrap_journal, load_bootstrap_cursor, remove_bootstrap_cursor,
+    save_bootstrap_cursor, save_cycle_history_reset, serialize_journal_part, JournalEntry,
+};
+use crate::error::BootstrapError;
 use crate::tests::tools::{
-    get_random_async_pool_changes, get_random_executed_ops_changes, get_random_pos_changes,
+    get_random_address, get_random_async_pool_changes, get_random_executed_ops_changes,
+    get_random_pos_changes,
 };
-use crate::BootstrapConfig;
 use crate::{
     get_state, start_bootstrap_server,
     tests::tools::{assert_eq_bootstrap_graph, get_bootstrap_config},
 };
-use massa_async_pool::AsyncPoolConfig;
+use crate::{
+    BootstrapClientMessage, BootstrapConfig, BootstrapServerMessage, GlobalBootstrapState,
+};
+use bitvec::vec::BitVec;
+use massa_async_pool::{test_exports::assert_eq_async_pool_bootstrap_state, AsyncPoolConfig};
 use massa_consensus_exports::{
     bootstrapable_graph::BootstrapableGraph,
     test_exports::{MockConsensusController, MockConsensusControllerMessage},
 };
 use massa_executed_ops::ExecutedOpsConfig;
 use massa_final_state::{
+    read_trace,
     test_exports::{assert_eq_final_state, assert_eq_final_state_hash},
-    FinalState, FinalStateConfig, StateChanges,
+    ExecutionTrace, FinalState, FinalStateConfig, SlotTrace, StateChanges, TraceReplayOutcome,
+    TraceWriter,
 };
 use massa_hash::{Hash, HASH_SIZE_BYTES};
-use massa_ledger_exports::LedgerConfig;
-use massa_models::{address::Address, slot::Slot, streaming_step::StreamingStep, version::Version};
+use massa_ledger_exports::{LedgerConfig, BALANCE_IDENT};
+use massa_ledger_worker::FinalLedger;
+use massa_models::{
+    address::Address, amount::Amount, slot::Slot, streaming_step::StreamingStep, version::Version,
+};
 use massa_models::{
     config::{
-        MAX_ASYNC_MESSAGE_DATA, MAX_ASYNC_POOL_LENGTH, MAX_DATASTORE_KEY_LENGTH, POS_SAVED_CYCLES,
+        GenesisBuilder, GenesisLedgerEntry, MAX_ASYNC_MESSAGE_DATA, MAX_ASYNC_POOL_LENGTH,
+        MAX_DATASTORE_KEY_LENGTH, MAX_LEDGER_VALUE_LENGTH, POS_SAVED_CYCLES,
     },
-    prehash::PreHashSet,
+    prehash::{PreHashMap, PreHashSet},
 };
 use massa_network_exports::{NetworkCommand, NetworkCommandSender};
 use massa_pos_exports::{
-    test_exports::assert_eq_pos_selection, PoSConfig, PoSFinalState, SelectorConfig,
+    test_exports::{assert_eq_pos_selection, assert_eq_pos_state},
+    CycleHistoryCursor, CycleInfo, DeferredCredits, PoSConfig, PoSFinalState, ProductionStats,
+    SelectorConfig,
 };
 use massa_pos_worker::start_selector_worker;
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use parking_lot::RwLock;
 use serial_test::serial;
-use std::{path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    collections::BTreeMap, io::Write, path::PathBuf, str::FromStr, sync::Arc, time::Duration,
+};
 use tempfile::TempDir;
 use tokio::sync::mpsc;
 
@@ -75,6 +97,7 @@ async fn test_bootstrap_server() {
             initial_ledger_path: "".into(),
             disk_ledger_path: temp_dir.path().to_path_buf(),
             max_key_length: MAX_DATASTORE_KEY_LENGTH,
+            max_value_length: MAX_LEDGER_VALUE_LENGTH,
             max_ledger_part_size: 100_000,
         },
         async_pool_config: AsyncPoolConfig {
@@ -88,6 +111,8 @@ async fn test_bootstrap_server() {
             thread_count,
             cycle_history_length: POS_SAVED_CYCLES,
             credits_bootstrap_part_size: 100,
+            credits_bootstrap_part_size_bytes: 1_000_000,
+            cycle_info_bootstrap_part_size: 40,
         },
         executed_ops_config: ExecutedOpsConfig {
             thread_count,
@@ -96,6 +121,8 @@ async fn test_bootstrap_server() {
         final_history_length: 100,
         initial_seed_string: "".into(),
         initial_rolls_path: "".into(),
+        initial_deferred_credits_path: None,
+        initial_snapshot_path: None,
         thread_count,
         periods_per_cycle,
     };
@@ -352,3 +379,664 @@ async fn test_bootstrap_server() {
     server_selector_manager.stop();
     client_selector_manager.stop();
 }
+
+#[test]
+#[serial]
+fn test_unexpected_cycle_history_part() {
+    let (bootstrap_config, _): &(BootstrapConfig, KeyPair) = &BOOTSTRAP_CONFIG_KEYPAIR;
+    let rolls_path = PathBuf::from_str("../massa-node/base_config/initial_rolls.json").unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let config = FinalStateConfig {
+        ledger_config: LedgerConfig {
+            disk_ledger_path: temp_dir.path().join("ledger"),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (mut selector_manager, selector_controller) =
+        start_selector_worker(SelectorConfig::default())
+            .expect("could not start selector controller");
+    let final_state = Arc::new(RwLock::new(FinalState::create_final_state(
+        PoSFinalState::new(
+            config.pos_config.clone(),
+            "",
+            &rolls_path,
+            selector_controller,
+            Hash::from_bytes(&[0; HASH_SIZE_BYTES]),
+        )
+        .unwrap(),
+        config,
+    )));
+    let cycle_part = |cycle: u64| {
+        Some(CycleInfo::new_with_hash(
+            cycle,
+            false,
+            BTreeMap::from([(get_random_address(), 1)]),
+            BitVec::default(),
+            PreHashMap::default(),
+        ))
+    };
+    let apply_cycle_part = |cycle: u64| {
+        apply_final_state_part(
+            &final_state,
+            Slot::new(1, 0),
+            Vec::new(),
+            BTreeMap::new(),
+            cycle_part(cycle),
+            DeferredCredits::default(),
+            BTreeMap::new(),
+            Vec::new(),
+        )
+    };
+    let cycles = || {
+        final_state
+            .read()
+            .pos_state
+            .cycle_history
+            .iter()
+            .map(|cycle_info| cycle_info.cycle)
+            .collect::<Vec<_>>()
+    };
+
+    // a part that does not follow the received cycles is refused, leaving the history untouched
+    apply_cycle_part(2).unwrap();
+    assert!(matches!(
+        apply_cycle_part(5),
+        Err(BootstrapError::InconsistentCycleHistoryPart(5, 3))
+    ));
+    assert_eq!(cycles(), vec![2]);
+
+    // the received history is dropped and its stream restarts, so that another server can send it
+    let mut next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
+        last_slot: Some(Slot::new(1, 0)),
+        last_ledger_step: StreamingStep::Started,
+        last_pool_step: StreamingStep::Started,
+        last_cycle_step: StreamingStep::Ongoing(CycleHistoryCursor {
+            cycle: 2,
+            last_address: None,
+        }),
+        last_credits_step: StreamingStep::Started,
+        last_ops_step: StreamingStep::Started,
+        last_consensus_step: StreamingStep::Started,
+    };
+    let mut global_bootstrap_state = GlobalBootstrapState::new(final_state.clone());
+    reset_cycle_history(
+        bootstrap_config,
+        &mut next_bootstrap_message,
+        &mut global_bootstrap_state,
+    );
+    assert!(cycles().is_empty());
+    match next_bootstrap_message {
+        BootstrapClientMessage::AskBootstrapPart {
+            last_cycle_step, ..
+        } => assert_eq!(last_cycle_step, StreamingStep::Started),
+        other => panic!("unexpected bootstrap message: {:?}", other),
+    }
+    apply_cycle_part(5).unwrap();
+    assert_eq!(cycles(), vec![5]);
+
+    selector_manager.stop();
+}
+
+#[test]
+#[serial]
+fn test_complete_cycle_parts() {
+    let (bootstrap_config, _): &(BootstrapConfig, KeyPair) = &BOOTSTRAP_CONFIG_KEYPAIR;
+    let rolls_path = PathBuf::from_str("../massa-node/base_config/initial_rolls.json").unwrap();
+    let pos_config = PoSConfig {
+        cycle_info_bootstrap_part_size: 2,
+        ..FinalStateConfig::default().pos_config
+    };
+    let (mut selector_manager, selector_controller) =
+        start_selector_worker(SelectorConfig::default())
+            .expect("could not start selector controller");
+    let mut pos_state = PoSFinalState::new(
+        pos_config,
+        "",
+        &rolls_path,
+        selector_controller,
+        Hash::from_bytes(&[0; HASH_SIZE_BYTES]),
+    )
+    .unwrap();
+
+    // a complete cycle of 5 addresses, 2 of them having production stats only
+    let addresses: Vec<Address> = (0..5).map(|_| get_random_address()).collect();
+    let mut cycle_info = CycleInfo::new_with_hash(
+        3,
+        true,
+        addresses[..3].iter().map(|addr| (*addr, 1)).collect(),
+        BitVec::default(),
+        addresses[2..]
+            .iter()
+            .map(|addr| {
+                (
+                    *addr,
+                    ProductionStats {
+                        block_success_count: 1,
+                        block_failure_count: 0,
+                    },
+                )
+            })
+            .collect(),
+    );
+    cycle_info.final_state_hash_snapshot = Some(Hash::compute_from(b"snapshot"));
+    let expected_hash = cycle_info.get_bootstrap_hash();
+    pos_state.cycle_history.clear();
+    pos_state.cycle_history.push_back(cycle_info);
+    pos_state.cycle_history.push_back(CycleInfo::new_with_hash(
+        4,
+        false,
+        BTreeMap::new(),
+        BitVec::default(),
+        PreHashMap::default(),
+    ));
+
+    // the server streams the cycle in parts of at most 2 addresses, ended by an empty part,
+    // which the client assembles back
+    let mut received: Option<CycleInfo> = None;
+    let mut cursor = Some(CycleHistoryCursor {
+        cycle: 3,
+        last_address: None,
+    });
+    let mut part_count = 0;
+    while let Some(current_cursor) = cursor {
+        let part = pos_state
+            .get_complete_cycle_part(current_cursor)
+            .expect("the cycle should be available");
+        let mut part_addresses: Vec<Address> = part
+            .roll_counts
+            .keys()
+            .chain(part.production_stats.keys())
+            .copied()
+            .collect();
+        part_addresses.sort_unstable();
+        part_addresses.dedup();
+        assert!(part_addresses.len() <= 2);
+        cursor =
+            add_complete_cycle_part(bootstrap_config, &mut received, current_cursor, part).unwrap();
+        part_count += 1;
+    }
+    assert_eq!(part_count, 4);
+    let received = received.unwrap();
+    let mut assembled = CycleInfo::new_with_hash(
+        received.cycle,
+        received.complete,
+        received.roll_counts,
+        received.rng_seed,
+        received.production_stats,
+    );
+    assembled.final_state_hash_snapshot = received.final_state_hash_snapshot;
+    assert_eq!(assembled.get_bootstrap_hash(), expected_hash);
+
+    // incomplete and unknown cycles are not served
+    for cycle in [2, 4, 5] {
+        assert!(pos_state
+            .get_complete_cycle_part(CycleHistoryCursor {
+                cycle,
+                last_address: None,
+            })
+            .is_none());
+    }
+
+    // a part that does not move the stream forward is refused
+    let first_part = pos_state
+        .get_complete_cycle_part(CycleHistoryCursor {
+            cycle: 3,
+            last_address: None,
+        })
+        .unwrap();
+    let mut sorted_addresses = addresses.clone();
+    sorted_addresses.sort_unstable();
+    let mut received = None;
+    assert!(matches!(
+        add_complete_cycle_part(
+            bootstrap_config,
+            &mut received,
+            CycleHistoryCursor {
+                cycle: 3,
+                last_address: Some(sorted_addresses[1]),
+            },
+            first_part,
+        ),
+        Err(BootstrapError::UnverifiedCycle(3))
+    ));
+
+    selector_manager.stop();
+}
+
+#[test]
+#[serial]
+fn test_genesis_initial_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let address = get_random_address();
+    let credit_slot = Slot::new(3, 1);
+    let genesis = GenesisBuilder::new(2)
+        .with_ledger_entry(
+            address,
+            GenesisLedgerEntry {
+                balance: Amount::from_str("1000").unwrap(),
+                ..Default::default()
+            },
+        )
+        .with_rolls(address, 10)
+        .with_deferred_credit(credit_slot, address, Amount::from_str("50").unwrap())
+        .build(&KeyPair::generate())
+        .unwrap();
+    let ledger_path = temp_dir.path().join("initial_ledger.json");
+    let rolls_path = temp_dir.path().join("initial_rolls.json");
+    let credits_path = temp_dir.path().join("initial_deferred_credits.json");
+    genesis
+        .write_initial_files(&ledger_path, &rolls_path, &credits_path)
+        .unwrap();
+
+    // a node starting from the written files loads the whole genesis
+    let ledger_config = LedgerConfig {
+        initial_ledger_path: ledger_path,
+        disk_ledger_path: temp_dir.path().join("ledger"),
+        ..Default::default()
+    };
+    let config = FinalStateConfig {
+        ledger_config: ledger_config.clone(),
+        initial_rolls_path: rolls_path,
+        initial_deferred_credits_path: Some(credits_path),
+        ..Default::default()
+    };
+    let (mut selector_manager, selector_controller) =
+        start_selector_worker(SelectorConfig::default())
+            .expect("could not start selector controller");
+    let mut final_state = FinalState::new(
+        config,
+        Box::new(FinalLedger::new(ledger_config)),
+        selector_controller,
+    )
+    .unwrap();
+    final_state.load_initial_state().unwrap();
+    assert_eq!(
+        final_state.ledger.get_balance(&address),
+        Some(Amount::from_str("1000").unwrap())
+    );
+    assert_eq!(final_state.pos_state.initial_rolls, genesis.rolls);
+    assert_eq!(
+        final_state
+            .pos_state
+            .deferred_credits
+            .get_address_deferred_credit_for_slot(&address, &credit_slot),
+        Some(Amount::from_str("50").unwrap())
+    );
+
+    selector_manager.stop();
+}
+
+#[test]
+#[serial]
+fn test_final_state_snapshot() {
+    let rolls_path = PathBuf::from_str("../massa-node/base_config/initial_rolls.json").unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let snapshot_path = temp_dir.path().join("snapshot.bin");
+    let config = FinalStateConfig {
+        ledger_config: LedgerConfig {
+            disk_ledger_path: temp_dir.path().join("ledger"),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (mut selector_manager, selector_controller) =
+        start_selector_worker(SelectorConfig::default())
+            .expect("could not start selector controller");
+    let new_pos_state = || {
+        PoSFinalState::new(
+            config.pos_config.clone(),
+            "",
+            &rolls_path,
+            selector_controller.clone(),
+            Hash::from_bytes(&[0; HASH_SIZE_BYTES]),
+        )
+        .unwrap()
+    };
+
+    // export a random final state at its current final slot
+    let mut exported_state = get_random_final_state_bootstrap(new_pos_state(), config.clone());
+    exported_state.slot = Slot::new(1, 0);
+    exported_state.compute_state_hash_at_slot(exported_state.slot);
+    let slot = exported_state.slot;
+    let next_slot = slot.get_next_slot(config.thread_count).unwrap();
+    exported_state
+        .request_snapshot(next_slot, snapshot_path.clone())
+        .unwrap();
+    assert!(
+        !snapshot_path.exists(),
+        "the snapshot should be written once its slot is final"
+    );
+    exported_state
+        .request_snapshot(slot, snapshot_path.clone())
+        .unwrap();
+    assert!(snapshot_path.exists());
+    exported_state
+        .request_snapshot(Slot::new(0, 0), temp_dir.path().join("old.bin"))
+        .expect_err("snapshots at past slots should be refused");
+
+    // a fresh final state loads the same content back
+    let mut imported_state = FinalState::create_final_state(new_pos_state(), config);
+    imported_state.import_snapshot(&snapshot_path).unwrap();
+    assert_eq_final_state(&exported_state, &imported_state);
+    assert_eq_final_state_hash(&exported_state, &imported_state);
+    assert_eq!(
+        exported_state.final_state_hash,
+        imported_state.final_state_hash
+    );
+
+    selector_manager.stop();
+}
+
+#[test]
+#[serial]
+fn test_final_state_trace_replay() {
+    let rolls_path = PathBuf::from_str("../massa-node/base_config/initial_rolls.json").unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let snapshot_path = temp_dir.path().join("snapshot.bin");
+    let config = FinalStateConfig {
+        ledger_config: LedgerConfig {
+            disk_ledger_path: temp_dir.path().join("ledger"),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (mut selector_manager, selector_controller) =
+        start_selector_worker(SelectorConfig::default())
+            .expect("could not start selector controller");
+    let new_pos_state = || {
+        PoSFinalState::new(
+            config.pos_config.clone(),
+            "",
+            &rolls_path,
+            selector_controller.clone(),
+            Hash::from_bytes(&[0; HASH_SIZE_BYTES]),
+        )
+        .unwrap()
+    };
+    let mut exported_state = get_random_final_state_bootstrap(new_pos_state(), config.clone());
+    exported_state.compute_state_hash_at_slot(exported_state.slot);
+    exported_state.export_snapshot(&snapshot_path).unwrap();
+
+    // record the trace of the snapshot slot, and a tampered copy of it
+    let trace = SlotTrace {
+        slot: exported_state.slot,
+        block_id: Some(get_dummy_block_id("traced")),
+        execution: ExecutionTrace {
+            executed_ops: get_random_executed_ops_changes(5).into_keys().collect(),
+            executed_messages: Vec::new(),
+            gas_used: 1_000_000,
+        },
+        state_changes: StateChanges {
+            pos_changes: get_random_pos_changes(10),
+            ledger_changes: get_random_ledger_changes(10),
+            async_pool_changes: get_random_async_pool_changes(10),
+            executed_ops_changes: get_random_executed_ops_changes(10),
+        },
+        hashes: exported_state.get_state_hashes(),
+    };
+    let trace_path = temp_dir.path().join("trace.bin");
+    TraceWriter::open(&trace_path)
+        .unwrap()
+        .append(&trace)
+        .unwrap();
+    let mut tampered_trace = trace.clone();
+    tampered_trace.hashes.ledger = Hash::compute_from(b"tampered");
+    let tampered_trace_path = temp_dir.path().join("tampered_trace.bin");
+    TraceWriter::open(&tampered_trace_path)
+        .unwrap()
+        .append(&tampered_trace)
+        .unwrap();
+
+    // the trace is read back as recorded
+    let read_traces = read_trace(&trace_path, config.thread_count).unwrap();
+    assert_eq!(read_traces.len(), 1);
+    assert_eq!(read_traces[0].slot, trace.slot);
+    assert_eq!(read_traces[0].block_id, trace.block_id);
+    assert_eq!(read_traces[0].execution, trace.execution);
+    assert_eq!(read_traces[0].hashes, trace.hashes);
+
+    // replaying on top of the snapshot matches the recorded hashes
+    let mut imported_state = FinalState::create_final_state(new_pos_state(), config.clone());
+    imported_state.import_snapshot(&snapshot_path).unwrap();
+    match imported_state.replay_trace(&trace_path).unwrap() {
+        TraceReplayOutcome::Matched {
+            replayed_slots,
+            last_slot,
+        } => {
+            assert_eq!(replayed_slots, 0);
+            assert_eq!(last_slot, exported_state.slot);
+        }
+        outcome => panic!("unexpected replay outcome: {:?}", outcome),
+    }
+
+    // the tampered component is reported
+    match imported_state.replay_trace(&tampered_trace_path).unwrap() {
+        TraceReplayOutcome::Diverged {
+            slot, components, ..
+        } => {
+            assert_eq!(slot, exported_state.slot);
+            assert_eq!(components, vec!["ledger"]);
+        }
+        outcome => panic!("unexpected replay outcome: {:?}", outcome),
+    }
+
+    selector_manager.stop();
+}
+
+#[test]
+#[serial]
+fn test_bootstrap_cursor_resume() {
+    let (bootstrap_config, _): &(BootstrapConfig, KeyPair) = &BOOTSTRAP_CONFIG_KEYPAIR;
+
+    // no cursor persisted yet
+    assert!(load_bootstrap_cursor(bootstrap_config).unwrap().is_none());
+
+    // journal two parts, then drop the received cycle history
+    let part = |slot: Slot| BootstrapServerMessage::BootstrapPart {
+        slot,
+        ledger_part: vec![1, 2, 3],
+        async_pool_part: BTreeMap::new(),
+        pos_cycle_part: None,
+        pos_credits_part: DeferredCredits::default(),
+        exec_ops_part: BTreeMap::from([(slot, PreHashSet::default())]),
+        final_state_changes: Vec::new(),
+        consensus_part: BootstrapableGraph {
+            final_blocks: Vec::new(),
+        },
+        consensus_outdated_ids: PreHashSet::default(),
+    };
+    let last_ledger_step =
+        StreamingStep::Ongoing([&get_random_address().to_bytes()[..], &[BALANCE_IDENT]].concat());
+    let cursor = |last_slot: Slot| BootstrapClientMessage::AskBootstrapPart {
+        last_slot: Some(last_slot),
+        last_ledger_step: last_ledger_step.clone(),
+        last_pool_step: StreamingStep::Finished(None),
+        last_cycle_step: StreamingStep::Ongoing(CycleHistoryCursor {
+            cycle: 3,
+            last_address: Some(get_random_address()),
+        }),
+        last_credits_step: StreamingStep::Ongoing(Slot::new(4, 0)),
+        last_ops_step: StreamingStep::Ongoing(last_slot),
+        last_consensus_step: StreamingStep::Finished(None),
+    };
+    for last_slot in [Slot::new(5, 0), Slot::new(5, 1)] {
+        let mut journaled_part = part(last_slot);
+        let bytes = serialize_journal_part(&mut journaled_part).unwrap();
+        // the ledger content is left in the part
+        match journaled_part {
+            BootstrapServerMessage::BootstrapPart { ledger_part, .. } => {
+                assert_eq!(ledger_part, vec![1, 2, 3])
+            }
+            other => panic!("unexpected journaled part: {:?}", other),
+        }
+        save_bootstrap_cursor(bootstrap_config, &cursor(last_slot), &bytes).unwrap();
+    }
+    save_cycle_history_reset(bootstrap_config).unwrap();
+
+    // a record truncated by a crash is dropped
+    let journal_len = std::fs::metadata(&bootstrap_config.bootstrap_cursor_path)
+        .unwrap()
+        .len();
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(&bootstrap_config.bootstrap_cursor_path)
+        .unwrap()
+        .write_all(&[0, 0, 0])
+        .unwrap();
+
+    // every component resumes from its cursor, the cycle history restarts as it was dropped
+    let (resumed_message, journal) = load_bootstrap_cursor(bootstrap_config).unwrap().unwrap();
+    match (resumed_message, cursor(Slot::new(5, 1))) {
+        (
+            BootstrapClientMessage::AskBootstrapPart {
+                last_slot,
+                last_ledger_step,
+                last_pool_step,
+                last_cycle_step,
+                last_credits_step,
+                last_ops_step,
+                last_consensus_step,
+            },
+            BootstrapClientMessage::AskBootstrapPart {
+                last_slot: expected_slot,
+                last_ledger_step: expected_ledger_step,
+                last_pool_step: expected_pool_step,
+                last_credits_step: expected_credits_step,
+                last_ops_step: expected_ops_step,
+                last_consensus_step: expected_consensus_step,
+                ..
+            },
+        ) => {
+            assert_eq!(last_slot, expected_slot);
+            assert_eq!(last_ledger_step, expected_ledger_step);
+            assert_eq!(last_pool_step, expected_pool_step);
+            assert_eq!(last_cycle_step, StreamingStep::Started);
+            assert_eq!(last_credits_step, expected_credits_step);
+            assert_eq!(last_ops_step, expected_ops_step);
+            assert_eq!(last_consensus_step, expected_consensus_step);
+        }
+        other => panic!("unexpected resumed bootstrap message: {:?}", other),
+    }
+    assert_eq!(
+        std::fs::metadata(&bootstrap_config.bootstrap_cursor_path)
+            .unwrap()
+            .len(),
+        journal_len
+    );
+
+    // the journaled parts are replayed in order, without their ledger content
+    assert_eq!(journal.len(), 3);
+    for (entry, expected_slot) in journal[..2].iter().zip([Slot::new(5, 0), Slot::new(5, 1)]) {
+        match entry {
+            JournalEntry::Part(BootstrapServerMessage::BootstrapPart {
+                slot,
+                ledger_part,
+                exec_ops_part,
+                ..
+            }) => {
+                assert_eq!(*slot, expected_slot);
+                assert!(ledger_part.is_empty());
+                assert!(exec_ops_part.contains_key(&expected_slot));
+            }
+            other => panic!("unexpected journal entry: {:?}", other),
+        }
+    }
+    assert!(matches!(journal[2], JournalEntry::CycleHistoryReset));
+
+    remove_bootstrap_cursor(&bootstrap_config.bootstrap_cursor_path).unwrap();
+    assert!(load_bootstrap_cursor(bootstrap_config).unwrap().is_none());
+}
+
+#[test]
+#[serial]
+fn test_bootstrap_journal_compaction() {
+    let (bootstrap_config, _): &(BootstrapConfig, KeyPair) = &BOOTSTRAP_CONFIG_KEYPAIR;
+    let rolls_path = PathBuf::from_str("../massa-node/base_config/initial_rolls.json").unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let config = FinalStateConfig {
+        ledger_config: LedgerConfig {
+            disk_ledger_path: temp_dir.path().join("ledger"),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (mut selector_manager, selector_controller) =
+        start_selector_worker(SelectorConfig::default())
+            .expect("could not start selector controller");
+    let new_pos_state = || {
+        PoSFinalState::new(
+            config.pos_config.clone(),
+            "",
+            &rolls_path,
+            selector_controller.clone(),
+            Hash::from_bytes(&[0; HASH_SIZE_BYTES]),
+        )
+        .unwrap()
+    };
+
+    // the state received so far, with a part journaled before the compaction
+    let mut received_state = get_random_final_state_bootstrap(new_pos_state(), config.clone());
+    received_state.slot = Slot::new(1, 0);
+    let received_state = GlobalBootstrapState::new(Arc::new(RwLock::new(received_state)));
+    let part = |slot: Slot| BootstrapServerMessage::BootstrapPart {
+        slot,
+        ledger_part: Vec::new(),
+        async_pool_part: BTreeMap::new(),
+        pos_cycle_part: None,
+        pos_credits_part: DeferredCredits::default(),
+        exec_ops_part: BTreeMap::new(),
+        final_state_changes: Vec::new(),
+        consensus_part: BootstrapableGraph {
+            final_blocks: Vec::new(),
+        },
+        consensus_outdated_ids: PreHashSet::default(),
+    };
+    let cursor = |last_slot: Slot| BootstrapClientMessage::AskBootstrapPart {
+        last_slot: Some(last_slot),
+        last_ledger_step: StreamingStep::Started,
+        last_pool_step: StreamingStep::Finished(None),
+        last_cycle_step: StreamingStep::Finished(None),
+        last_credits_step: StreamingStep::Finished(None),
+        last_ops_step: StreamingStep::Finished(None),
+        last_consensus_step: StreamingStep::Started,
+    };
+    let bytes = serialize_journal_part(&mut part(Slot::new(0, 1))).unwrap();
+    save_bootstrap_cursor(bootstrap_config, &cursor(Slot::new(0, 1)), &bytes).unwrap();
+
+    // the compaction replaces the journaled parts, the next parts are appended after it
+    compact_bootstrap_journal(bootstrap_config, &cursor(Slot::new(1, 0)), &received_state).unwrap();
+    let bytes = serialize_journal_part(&mut part(Slot::new(1, 1))).unwrap();
+    save_bootstrap_cursor(bootstrap_config, &cursor(Slot::new(1, 1)), &bytes).unwrap();
+    let (resumed_message, journal) = load_bootstrap_cursor(bootstrap_config).unwrap().unwrap();
+    match resumed_message {
+        BootstrapClientMessage::AskBootstrapPart { last_slot, .. } => {
+            assert_eq!(last_slot, Some(Slot::new(1, 1)))
+        }
+        other => panic!("unexpected resumed bootstrap message: {:?}", other),
+    }
+    assert_eq!(journal.len(), 2);
+    assert!(matches!(journal[0], JournalEntry::State { .. }));
+    assert!(matches!(journal[1], JournalEntry::Part(_)));
+
+    // a fresh state rebuilds the in-memory components from the compacted journal
+    let mut resumed_state = GlobalBootstrapState::new(Arc::new(RwLock::new(
+        FinalState::create_final_state(new_pos_state(), config),
+    )));
+    replay_bootstrap_journal(&mut resumed_state, journal).unwrap();
+    {
+        let received = received_state.final_state.read();
+        let resumed = resumed_state.final_state.read();
+        assert_eq!(resumed.slot, Slot::new(1, 1));
+        assert_eq_async_pool_bootstrap_state(&received.async_pool, &resumed.async_pool);
+        assert_eq_pos_state(&received.pos_state, &resumed.pos_state);
+        assert_eq!(
+            received.executed_ops.sorted_ops,
+            resumed.executed_ops.sorted_ops
+        );
+    }
+
+    remove_bootstrap_cursor(&bootstrap_config.bootstrap_cursor_path).unwrap();
+    assert!(load_bootstrap_cursor(bootstrap_config).unwrap().is_none());
+    selector_manager.stop();
+}
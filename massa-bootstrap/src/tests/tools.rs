@@ -29,7 +29,7 @@ use massa_models::config::{
     MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, PERIODS_PER_CYCLE, THREAD_COUNT,
 };
 use massa_models::{
-    address::Address,
+    address::{Address, AddressCategory},
     amount::Amount,
     block::BlockSerializer,
     block::{Block, BlockHeader, BlockHeaderSerializer, BlockId},
@@ -54,6 +54,7 @@ use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
 };
+use tempfile::NamedTempFile;
 use tokio::io::AsyncReadExt;
 use tokio::io::AsyncWriteExt;
 use tokio::{sync::mpsc::Receiver, time::sleep};
@@ -134,6 +135,9 @@ fn get_random_pos_cycles_info(
 }
 
 /// generates random PoS deferred credits
+///
+/// note: credits are set after the slots reached by the tests
+/// so that they are not skipped by the bootstrap as already settled
 fn get_random_deferred_credits(r_limit: u64) -> DeferredCredits {
     let mut deferred_credits = DeferredCredits::default();
 
@@ -144,7 +148,7 @@ fn get_random_deferred_credits(r_limit: u64) -> DeferredCredits {
         }
         deferred_credits.credits.insert(
             Slot {
-                period: i,
+                period: i + 100,
                 thread: 0,
             },
             credits,
@@ -235,7 +239,10 @@ pub fn get_random_final_state_bootstrap(
         sorted_ledger.insert(get_random_address(), get_random_ledger_entry());
     }
     // insert the last possible address to prevent the last cursor to move when testing the changes
-    sorted_ledger.insert(Address::from_bytes(&[255; 32]), get_random_ledger_entry());
+    sorted_ledger.insert(
+        Address::new(AddressCategory::SCAddress, Hash::from_bytes(&[255; 32])),
+        get_random_ledger_entry(),
+    );
 
     let slot = Slot::new(0, 0);
     let final_ledger = create_final_ledger(config.ledger_config.clone(), sorted_ledger);
@@ -283,12 +290,19 @@ pub fn get_bootstrap_config(bootstrap_public_key: PublicKey) -> BootstrapConfig
         read_error_timeout: 200.into(),
         write_error_timeout: 200.into(),
         bootstrap_list: vec![(SocketAddr::new(BASE_BOOTSTRAP_IP, 16), bootstrap_public_key)],
+        bootstrap_dns_list: Vec::new(),
         bootstrap_whitelist_path: PathBuf::from(
             "../massa-node/base_config/bootstrap_whitelist.json",
         ),
         bootstrap_blacklist_path: PathBuf::from(
             "../massa-node/base_config/bootstrap_blacklist.json",
         ),
+        bootstrap_cursor_path: NamedTempFile::new()
+            .expect("cannot create temp file")
+            .path()
+            .to_path_buf(),
+        secondary_bootstrap_server_count: 0,
+        max_pending_bootstrap_parts: 2,
         max_clock_delta: MassaTime::from_millis(1000),
         cache_duration: 10000.into(),
         max_simultaneous_bootstraps: 2,
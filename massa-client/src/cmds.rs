@@ -3,10 +3,14 @@
 use crate::repl::Output;
 use anyhow::{anyhow, bail, Error, Result};
 use console::style;
+use massa_hash::Hash;
 use massa_models::api::{
-    AddressInfo, CompactAddressInfo, DatastoreEntryInput, EventFilter, OperationInput,
+    AddressHistoryRequest, AddressInfo, CompactAddressInfo, DatastoreEntryInput, EventFilter,
+    LedgerEntryProofInput, RollCompoundingMode, SnapshotRequest,
+};
+use massa_models::api::{
+    ReadOnlyBytecodeExecution, ReadOnlyCall, SelectionSimulation, StakersRequest, TransfersRequest,
 };
-use massa_models::api::{ReadOnlyBytecodeExecution, ReadOnlyCall};
 use massa_models::node::NodeId;
 use massa_models::prehash::PreHashMap;
 use massa_models::timeslots::get_current_latest_block_slot;
@@ -15,13 +19,13 @@ use massa_models::{
     amount::Amount,
     block::BlockId,
     endorsement::EndorsementId,
-    operation::{Operation, OperationId, OperationType},
+    operation::{OperationId, OperationType},
     slot::Slot,
 };
 use massa_sdk::Client;
 use massa_signature::KeyPair;
-use massa_time::MassaTime;
-use massa_wallet::Wallet;
+use massa_time::{MassaTime, SystemClock};
+use massa_wallet::{to_operation_input, Wallet};
 use serde::Serialize;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Write as _;
@@ -91,6 +95,66 @@ pub enum Command {
     )]
     node_add_staking_secret_keys,
 
+    #[strum(
+        ascii_case_insensitive,
+        message = "resume block and endorsement production on a node in standby mode"
+    )]
+    node_staking_activate,
+
+    #[strum(
+        ascii_case_insensitive,
+        message = "keep the node in sync but withhold block and endorsement production"
+    )]
+    node_staking_standby,
+
+    #[strum(
+        ascii_case_insensitive,
+        message = "notify a node in standby mode that the primary node is alive"
+    )]
+    node_staking_heartbeat,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Directives"),
+        message = "replace the node log filter by comma-separated target=level directives, e.g. massa_bootstrap=debug,info"
+    )]
+    node_set_log_filter,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Rate"),
+        message = "log one massa trace event out of Rate, or none if Rate is 0"
+    )]
+    node_set_trace_sampling,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "(disabled, dry_run or enabled)"),
+        message = "set how the deferred credits of the staking addresses are converted into rolls at the start of each cycle"
+    )]
+    node_set_roll_compounding,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Slot Path"),
+        message = "write a snapshot of the final state at final slot Slot to Path on the node machine"
+    )]
+    node_export_snapshot,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Path"),
+        message = "write the peer database to Path on the node machine"
+    )]
+    node_export_peers,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Path"),
+        message = "merge the peers of the file at Path on the node machine with the peer database"
+    )]
+    node_import_peers,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address discord_id"),
@@ -139,6 +203,13 @@ pub enum Command {
     )]
     get_datastore_entry,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address [Key]"),
+        message = "get a proof of the final balance of an address, or of one of its datastore entries if a key is given (key must be UTF-8)"
+    )]
+    get_ledger_entry_proof,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "BlockId"),
@@ -169,6 +240,34 @@ pub enum Command {
     )]
     get_filtered_sc_output_event,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Offset Limit [Cycle]"),
+        message = "show a page of the stakers of a cycle (current cycle by default) with their rolls and production stats"
+    )]
+    get_cycle_stakers,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address [FromSlot] [ToSlot]"),
+        message = "show the balance and roll changes of an address in the recent final slots (slots given as period,thread)"
+    )]
+    get_address_history,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address Offset Limit"),
+        message = "show a page of the coins sent and received by an address in the recent final slots, newest first"
+    )]
+    get_transactions_by_address,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Seed CycleCount Address1 RollCount1 Address2 RollCount2 ..."),
+        message = "simulate the selections of a roll distribution for a number of cycles (the seed is any text)"
+    )]
+    simulate_selections,
+
     #[strum(
         ascii_case_insensitive,
         message = "show wallet info (keys, addresses, balances ...)"
@@ -493,6 +592,154 @@ impl Command {
                 }
             }
 
+            Command::node_staking_activate => {
+                match client.private.node_staking_activate().await {
+                    Ok(()) => {
+                        if !json {
+                            println!("Block and endorsement production activated!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_staking_standby => {
+                match client.private.node_staking_standby().await {
+                    Ok(()) => {
+                        if !json {
+                            println!("Node is now in standby mode!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_staking_heartbeat => {
+                match client.private.node_staking_heartbeat().await {
+                    Ok(()) => {
+                        if !json {
+                            println!("Heartbeat successfully sent!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_set_log_filter => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                match client
+                    .private
+                    .node_set_log_filter(parameters[0].clone())
+                    .await
+                {
+                    Ok(()) => {
+                        if !json {
+                            println!("Log filter successfully updated!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_set_trace_sampling => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let rate = parameters[0].parse::<u64>()?;
+                match client.private.node_set_trace_sampling(rate).await {
+                    Ok(()) => {
+                        if !json {
+                            println!("Trace sampling successfully updated!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_set_roll_compounding => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let mode = parameters[0].parse::<RollCompoundingMode>()?;
+                match client.private.node_set_roll_compounding(mode).await {
+                    Ok(()) => {
+                        if !json {
+                            println!("Roll compounding mode successfully updated!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_export_snapshot => {
+                if parameters.len() != 2 {
+                    bail!("wrong number of parameters");
+                }
+                let request = SnapshotRequest {
+                    slot: parameters[0].parse::<Slot>()?,
+                    path: parameters[1].clone(),
+                };
+                let slot = request.slot;
+                match client.private.node_export_snapshot(request).await {
+                    Ok(()) => {
+                        if !json {
+                            println!(
+                                "Snapshot of the final state will be written once slot {} is final!",
+                                slot
+                            )
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_export_peers => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                match client
+                    .private
+                    .node_export_peers(parameters[0].clone())
+                    .await
+                {
+                    Ok(()) => {
+                        if !json {
+                            println!("Peers written!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_import_peers => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                match client
+                    .private
+                    .node_import_peers(parameters[0].clone())
+                    .await
+                {
+                    Ok(new_peer_count) => {
+                        if !json {
+                            println!("{} new peer(s) imported!", new_peer_count)
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
             Command::node_remove_staking_addresses => {
                 let addresses = parse_vec::<Address>(parameters)?;
                 match client.private.remove_staking_addresses(addresses).await {
@@ -582,6 +829,22 @@ impl Command {
                 }
             }
 
+            Command::get_ledger_entry_proof => {
+                if parameters.is_empty() || parameters.len() > 2 {
+                    bail!("invalid number of parameters");
+                }
+                let address = parameters[0].parse::<Address>()?;
+                let key = parameters.get(1).map(|key| key.as_bytes().to_vec());
+                match client
+                    .public
+                    .get_ledger_entry_proof(vec![LedgerEntryProofInput { address, key }])
+                    .await
+                {
+                    Ok(result) => Ok(Box::new(result)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::get_blocks => {
                 if parameters.is_empty() {
                     bail!("wrong param numbers, expecting at least one block id")
@@ -609,6 +872,84 @@ impl Command {
                 }
             }
 
+            Command::get_cycle_stakers => {
+                if parameters.len() != 2 && parameters.len() != 3 {
+                    bail!("wrong number of parameters");
+                }
+                let request = StakersRequest {
+                    offset: parameters[0].parse::<u64>()?,
+                    limit: parameters[1].parse::<u64>()?,
+                    cycle: parameters
+                        .get(2)
+                        .map(|cycle| cycle.parse::<u64>())
+                        .transpose()?,
+                };
+                match client.public.get_cycle_stakers(request).await {
+                    Ok(stakers_page) => Ok(Box::new(stakers_page)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::get_address_history => {
+                if parameters.is_empty() || parameters.len() > 3 {
+                    bail!("wrong number of parameters");
+                }
+                let request = AddressHistoryRequest {
+                    address: parameters[0].parse::<Address>()?,
+                    from_slot: parameters
+                        .get(1)
+                        .map(|slot| slot.parse::<Slot>())
+                        .transpose()?,
+                    to_slot: parameters
+                        .get(2)
+                        .map(|slot| slot.parse::<Slot>())
+                        .transpose()?,
+                };
+                match client.public.get_address_history(request).await {
+                    Ok(entries) => Ok(Box::new(entries)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::get_transactions_by_address => {
+                if parameters.len() != 3 {
+                    bail!("wrong number of parameters");
+                }
+                let request = TransfersRequest {
+                    address: parameters[0].parse::<Address>()?,
+                    offset: parameters[1].parse::<u64>()?,
+                    limit: parameters[2].parse::<u64>()?,
+                };
+                match client.public.get_transactions_by_address(request).await {
+                    Ok(transfers_page) => Ok(Box::new(transfers_page)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::simulate_selections => {
+                if parameters.len() < 4 || parameters.len() % 2 != 0 {
+                    bail!("wrong number of parameters");
+                }
+                let seed = Hash::compute_from(parameters[0].as_bytes());
+                let cycle_count = parameters[1].parse::<u64>()?;
+                let mut rolls = Vec::with_capacity((parameters.len() - 2) / 2);
+                for pair in parameters[2..].chunks(2) {
+                    rolls.push((pair[0].parse::<Address>()?, pair[1].parse::<u64>()?));
+                }
+                match client
+                    .public
+                    .simulate_selections(SelectionSimulation {
+                        rolls,
+                        seed,
+                        cycle_count,
+                    })
+                    .await
+                {
+                    Ok(simulated_draws) => Ok(Box::new(simulated_draws)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::get_filtered_sc_output_event => {
                 let p_list: [&str; 7] = [
                     "start",
@@ -660,8 +1001,7 @@ impl Command {
             }
 
             Command::wallet_generate_secret_key => {
-                let key = KeyPair::generate();
-                let ad = wallet.add_keypairs(vec![key])?[0];
+                let ad = wallet.generate_keypair()?;
                 if json {
                     Ok(Box::new(ad.to_string()))
                 } else {
@@ -711,9 +1051,9 @@ impl Command {
                 let fee = parameters[2].parse::<Amount>()?;
 
                 if !json {
-                    let roll_price = match client.public.get_status().await {
+                    let roll_price = match client.public.get_config().await {
                         Err(e) => bail!("RpcError: {}", e),
-                        Ok(status) => status.config.roll_price,
+                        Ok(config) => config.roll_price,
                     };
                     match roll_price
                         .checked_mul_u64(roll_count)
@@ -877,8 +1217,8 @@ impl Command {
                 };
                 let data = get_file_as_byte_vec(&path).await?;
                 if !json {
-                    let max_block_size = match client.public.get_status().await {
-                        Ok(node_status) => node_status.config.max_block_size,
+                    let max_block_size = match client.public.get_config().await {
+                        Ok(config) => config.max_block_size,
                         Err(e) => bail!("RpcError: {}", e),
                     };
                     if data.len() > max_block_size as usize {
@@ -937,6 +1277,18 @@ impl Command {
                             client_warning!("the total amount hit the limit overflow, operation will be rejected");
                         }
                     }
+                    let config = match client.public.get_config().await {
+                        Ok(config) => config,
+                        Err(e) => bail!("RpcError: {}", e),
+                    };
+                    if target_func.len() > config.max_function_name_length as usize {
+                        client_warning!("function name length exceeded the maximum length, operation will be rejected");
+                    }
+                    if param.len() > config.max_parameters_size as usize {
+                        client_warning!(
+                            "parameters size exceeded the maximum size, operation will be rejected"
+                        );
+                    }
                 };
                 send_operation(
                     client,
@@ -1213,35 +1565,30 @@ async fn send_operation(
     addr: Address,
     json: bool,
 ) -> Result<Box<dyn Output>> {
-    let cfg = match client.public.get_status().await {
-        Ok(node_status) => node_status,
+    let cfg = match client.public.get_config().await {
+        Ok(config) => config,
         Err(e) => rpc_error!(e),
-    }
-    .config;
+    };
 
-    let slot = get_current_latest_block_slot(cfg.thread_count, cfg.t0, cfg.genesis_timestamp)?
-        .unwrap_or_else(|| Slot::new(0, 0));
-    let mut expire_period = slot.period + cfg.operation_validity_periods;
+    let slot = get_current_latest_block_slot(
+        cfg.thread_count,
+        cfg.t0,
+        cfg.genesis_timestamp,
+        &SystemClock,
+    )?
+    .unwrap_or_else(|| Slot::new(0, 0));
+    let mut expire_period = slot.skip_periods(cfg.operation_validity_periods)?.period;
     if slot.thread >= addr.get_thread(cfg.thread_count) {
         expire_period += 1;
     };
 
-    let op = wallet.create_operation(
-        Operation {
-            fee,
-            expire_period,
-            op,
-        },
-        addr,
-    )?;
+    let op = wallet
+        .operation_builder(addr, fee, expire_period)
+        .build(op)?;
 
     match client
         .public
-        .send_operations(vec![OperationInput {
-            creator_public_key: op.creator_public_key,
-            serialized_content: op.serialized_data,
-            signature: op.signature,
-        }])
+        .send_operations(vec![to_operation_input(op)])
         .await
     {
         Ok(operation_ids) => {
@@ -35,6 +35,9 @@ struct Args {
     /// Address to listen on
     #[structopt(long)]
     ip: Option<IpAddr>,
+    /// Token authenticating the requests to the private API
+    #[structopt(long)]
+    private_auth_token: Option<String>,
     /// Command that client would execute (non-interactive mode)
     #[structopt(name = "COMMAND", default_value = "help")]
     command: Command,
@@ -103,6 +106,10 @@ async fn run(args: Args) -> Result<()> {
         id_kind: SETTINGS.http.id_kind.clone(),
         max_log_length: SETTINGS.http.max_log_length,
         headers: SETTINGS.http.headers.clone(),
+        private_auth_token: args
+            .private_auth_token
+            .clone()
+            .or_else(|| SETTINGS.default_node.private_auth_token.clone()),
     };
 
     // TODO: move settings loading in another crate ... see #1277
@@ -7,12 +7,17 @@ use console::style;
 use erased_serde::{Serialize, Serializer};
 use massa_models::api::{
     AddressInfo, BlockInfo, DatastoreEntryOutput, EndorsementInfo, NodeStatus, OperationInfo,
+    SimulatedDraws, StakersPage, TransfersPage,
 };
 use massa_models::composite::PubkeySig;
 use massa_models::execution::ExecuteReadOnlyResponse;
+use massa_models::ledger_proof::FinalLedgerEntryProof;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
-use massa_models::{address::Address, operation::OperationId};
+use massa_models::{
+    address::{Address, AddressHistoryEntry},
+    operation::OperationId,
+};
 use massa_sdk::Client;
 use massa_wallet::Wallet;
 use rustyline::completion::{Completer, FilenameCompleter, Pair};
@@ -273,6 +278,14 @@ impl Output for Vec<DatastoreEntryOutput> {
     }
 }
 
+impl Output for Vec<FinalLedgerEntryProof> {
+    fn pretty_print(&self) {
+        for proof in self {
+            println!("{}", proof);
+        }
+    }
+}
+
 impl Output for Vec<EndorsementInfo> {
     fn pretty_print(&self) {
         for endorsement_info in self {
@@ -281,6 +294,34 @@ impl Output for Vec<EndorsementInfo> {
     }
 }
 
+impl Output for Vec<SimulatedDraws> {
+    fn pretty_print(&self) {
+        for simulated_draws in self {
+            println!("{}", simulated_draws);
+        }
+    }
+}
+
+impl Output for StakersPage {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl Output for TransfersPage {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl Output for Vec<AddressHistoryEntry> {
+    fn pretty_print(&self) {
+        for entry in self {
+            println!("{}", entry);
+        }
+    }
+}
+
 impl Output for Vec<IpAddr> {
     fn pretty_print(&self) {
         for ips in self {
@@ -5,8 +5,13 @@ use massa_models::{
     prehash::{PreHashMap, PreHashSet},
     slot::Slot,
 };
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
 
-use crate::block_status::{DiscardReason, ExportCompiledBlock};
+use crate::{
+    block_status::{DiscardReason, ExportCompiledBlock},
+    error::ConsensusError,
+};
 
 /// Bootstrap compatible version of the block graph
 #[derive(Debug, Clone)]
@@ -27,3 +32,177 @@ pub struct BlockGraphExport {
     /// List of maximal cliques of compatible blocks.
     pub max_cliques: Vec<Clique>,
 }
+
+/// Format of a rendered block graph export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GraphExportFormat {
+    /// GraphViz DOT digraph, each block pointing to its parents
+    Dot,
+    /// JSON list of blocks with their parents, clique membership and status
+    Json,
+}
+
+/// Status of a block in a rendered graph export
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GraphBlockStatus {
+    /// active and not yet final
+    Active,
+    /// active and final
+    Final,
+    /// discarded for the given reason
+    Discarded(DiscardReason),
+}
+
+/// Block of a rendered graph export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphBlock {
+    /// block id
+    pub id: BlockId,
+    /// block slot
+    pub slot: Slot,
+    /// block creator address
+    pub creator: Address,
+    /// parents of the block, one per thread
+    pub parents: Vec<BlockId>,
+    /// indexes in `BlockGraphExport::max_cliques` of the cliques containing the block
+    pub cliques: Vec<usize>,
+    /// true if the block belongs to the blockclique
+    pub in_blockclique: bool,
+    /// block status
+    pub status: GraphBlockStatus,
+}
+
+impl BlockGraphExport {
+    /// Lists the active and discarded blocks of the export, sorted by slot then by id
+    pub fn graph_blocks(&self) -> Vec<GraphBlock> {
+        let clique_indexes = |block_id: &BlockId| -> Vec<usize> {
+            self.max_cliques
+                .iter()
+                .enumerate()
+                .filter(|(_, clique)| clique.block_ids.contains(block_id))
+                .map(|(index, _)| index)
+                .collect()
+        };
+        let mut blocks: Vec<GraphBlock> =
+            Vec::with_capacity(self.active_blocks.len() + self.discarded_blocks.len());
+        for (id, block) in self.active_blocks.iter() {
+            let cliques = clique_indexes(id);
+            blocks.push(GraphBlock {
+                id: *id,
+                slot: block.header.content.slot,
+                creator: block.header.creator_address,
+                parents: block.header.content.parents.clone(),
+                in_blockclique: cliques
+                    .iter()
+                    .any(|index| self.max_cliques[*index].is_blockclique),
+                cliques,
+                status: if block.is_final {
+                    GraphBlockStatus::Final
+                } else {
+                    GraphBlockStatus::Active
+                },
+            });
+        }
+        for (id, (reason, (slot, creator, parents))) in self.discarded_blocks.iter() {
+            blocks.push(GraphBlock {
+                id: *id,
+                slot: *slot,
+                creator: *creator,
+                parents: parents.clone(),
+                cliques: Vec::new(),
+                in_blockclique: false,
+                status: GraphBlockStatus::Discarded(reason.clone()),
+            });
+        }
+        blocks.sort_unstable_by_key(|block| (block.slot, block.id));
+        blocks
+    }
+
+    /// Renders the export as a GraphViz DOT digraph.
+    /// Final blocks are blue, blockclique blocks are green and discarded blocks are grey and dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph BlockGraph {\n    rankdir=LR;\n    node [shape=box];\n");
+        for block in self.graph_blocks() {
+            let (color, style, extra_label) = match &block.status {
+                GraphBlockStatus::Final => ("lightblue", "filled", String::new()),
+                GraphBlockStatus::Active if block.in_blockclique => {
+                    ("palegreen", "filled", String::new())
+                }
+                GraphBlockStatus::Active => ("white", "filled", String::new()),
+                GraphBlockStatus::Discarded(reason) => (
+                    "lightgrey",
+                    "filled,dashed",
+                    format!("\\n{:?}", reason).replace('"', "'"),
+                ),
+            };
+            // writing to a String cannot fail
+            let _ = writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\\n{}{}\", fillcolor=\"{}\", style=\"{}\"];",
+                block.id, block.id, block.slot, extra_label, color, style
+            );
+            for parent in block.parents.iter() {
+                let _ = writeln!(dot, "    \"{}\" -> \"{}\";", block.id, parent);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the export as a JSON list of `GraphBlock`
+    pub fn to_json(&self) -> Result<String, ConsensusError> {
+        Ok(serde_json::to_string_pretty(&self.graph_blocks())?)
+    }
+
+    /// Renders the export in the given format
+    pub fn render(&self, format: GraphExportFormat) -> Result<String, ConsensusError> {
+        match format {
+            GraphExportFormat::Dot => Ok(self.to_dot()),
+            GraphExportFormat::Json => self.to_json(),
+        }
+    }
+}
+
+#[test]
+fn test_render_graph_export() {
+    use massa_hash::Hash;
+    use std::str::FromStr;
+
+    let creator =
+        Address::from_str("A12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let genesis_id = BlockId(Hash::compute_from(b"genesis"));
+    let discarded_id = BlockId(Hash::compute_from(b"discarded"));
+    let export = BlockGraphExport {
+        genesis_blocks: vec![genesis_id],
+        active_blocks: Default::default(),
+        discarded_blocks: [(
+            discarded_id,
+            (
+                DiscardReason::Stale,
+                (Slot::new(1, 0), creator, vec![genesis_id]),
+            ),
+        )]
+        .into_iter()
+        .collect(),
+        best_parents: vec![(genesis_id, 0)],
+        latest_final_blocks_periods: vec![(genesis_id, 0)],
+        gi_head: Default::default(),
+        max_cliques: Vec::new(),
+    };
+
+    let dot = export.render(GraphExportFormat::Dot).unwrap();
+    assert!(dot.starts_with("digraph BlockGraph {"));
+    assert!(dot.contains(&format!("\"{}\" -> \"{}\";", discarded_id, genesis_id)));
+    assert!(dot.contains("style=\"filled,dashed\""));
+
+    let json = export.render(GraphExportFormat::Json).unwrap();
+    let blocks: Vec<GraphBlock> = serde_json::from_str(&json).unwrap();
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].id, discarded_id);
+    assert_eq!(blocks[0].parents, vec![genesis_id]);
+    assert_eq!(
+        blocks[0].status,
+        GraphBlockStatus::Discarded(DiscardReason::Stale)
+    );
+}
@@ -3,12 +3,15 @@ use massa_models::block::{Block, BlockHeader, FilledBlock};
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::ProtocolCommandSender;
+use massa_time::MassaClock;
+use std::sync::Arc;
 
 use crate::events::ConsensusEvent;
 
 /// Contains a reference to the pool, selector and execution controller
 /// Contains a channel to send info to protocol
 /// Contains channels to send info to api
+/// Contains the clock giving the current time to the worker
 #[derive(Clone)]
 pub struct ConsensusChannels {
     pub execution_controller: Box<dyn ExecutionController>,
@@ -19,4 +22,5 @@ pub struct ConsensusChannels {
     pub block_sender: tokio::sync::broadcast::Sender<Block>,
     pub block_header_sender: tokio::sync::broadcast::Sender<BlockHeader>,
     pub filled_block_sender: tokio::sync::broadcast::Sender<FilledBlock>,
+    pub clock: Arc<dyn MassaClock>,
 }
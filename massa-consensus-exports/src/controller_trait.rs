@@ -1,4 +1,4 @@
-use crate::block_graph_export::BlockGraphExport;
+use crate::block_graph_export::{BlockGraphExport, GraphExportFormat};
 use crate::{bootstrapable_graph::BootstrapableGraph, error::ConsensusError};
 use massa_models::prehash::PreHashSet;
 use massa_models::streaming_step::StreamingStep;
@@ -28,6 +28,25 @@ pub trait ConsensusController: Send + Sync {
         end_slot: Option<Slot>,
     ) -> Result<BlockGraphExport, ConsensusError>;
 
+    /// Render a part of the graph to visualize forks, in DOT or JSON format
+    ///
+    /// # Arguments
+    /// * `format`: the output format
+    /// * `start_slot`: the slot to start the export from, if None, the export starts from the genesis
+    /// * `end_slot`: the slot to end the export at (excluded), if None, the export ends at the current slot
+    ///
+    /// # Returns
+    /// The rendered export of the graph
+    fn export_graph(
+        &self,
+        format: GraphExportFormat,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> Result<String, ConsensusError> {
+        self.get_block_graph_status(start_slot, end_slot)?
+            .render(format)
+    }
+
     /// Get statuses of a list of blocks
     ///
     /// # Arguments
@@ -37,6 +56,18 @@ pub trait ConsensusController: Send + Sync {
     /// The statuses of the blocks sorted by the order of the input list
     fn get_block_statuses(&self, ids: &[BlockId]) -> Vec<BlockGraphStatus>;
 
+    /// Check whether blocks are known by the graph (incoming, waiting, active or discarded).
+    /// Unlike `get_block_statuses`, does not wait for the consensus worker to release its state.
+    ///
+    /// # Arguments
+    /// * `block_ids`: the list of block ids to check
+    ///
+    /// # Returns
+    /// Whether each block is known, sorted by the order of the input list
+    fn are_blocks_known(&self, ids: &[BlockId]) -> Vec<bool> {
+        vec![false; ids.len()]
+    }
+
     /// Get all the cliques of the graph
     ///
     /// # Returns
@@ -130,7 +161,7 @@ impl Clone for Box<dyn ConsensusController> {
 }
 
 /// Consensus manager used to stop the consensus thread
-pub trait ConsensusManager {
+pub trait ConsensusManager: Send {
     /// Stop the consensus thread
     /// Note that we do not take self by value to consume it
     /// because it is not allowed to move out of Box<dyn ConsensusManager>
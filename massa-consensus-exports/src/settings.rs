@@ -44,6 +44,9 @@ pub struct ConsensusConfig {
     pub stats_timespan: MassaTime,
     /// channel size
     pub channel_size: usize,
+    /// maximum number of queued block commands processed in a row before the consensus state
+    /// is handed over to its readers (API, factories) and slot ticks are checked
+    pub max_commands_per_batch: usize,
     /// size of a consensus bootstrap streaming part
     pub bootstrap_part_size: u64,
     /// whether broadcast is enabled
@@ -54,4 +57,6 @@ pub struct ConsensusConfig {
     pub broadcast_blocks_capacity: usize,
     /// filled blocks sender(channel) capacity
     pub broadcast_filled_blocks_capacity: usize,
+    /// diagnostics mode: check the consistency of the block graph at each slot
+    pub check_graph_invariants: bool,
 }
@@ -32,11 +32,13 @@ impl Default for ConsensusConfig {
             end_timestamp: None,
             stats_timespan: MassaTime::from_millis(60000),
             channel_size: CHANNEL_SIZE,
+            max_commands_per_batch: 32,
             bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
             broadcast_enabled: true,
             broadcast_blocks_headers_capacity: 128,
             broadcast_blocks_capacity: 128,
             broadcast_filled_blocks_capacity: 128,
+            check_graph_invariants: true,
         }
     }
 }
@@ -33,15 +33,26 @@ pub struct ConsensusControllerImpl {
     command_sender: SyncSender<ConsensusCommand>,
     channels: ConsensusChannels,
     shared_state: Arc<RwLock<ConsensusState>>,
+    known_blocks: Arc<RwLock<PreHashSet<BlockId>>>,
     bootstrap_part_size: u64,
     broadcast_enabled: bool,
 }
 
+/// Counts a command that could not be queued because the consensus worker is overflowed
+fn record_dropped_command(command: &str) {
+    massa_metrics::inc_counter(
+        "massa_consensus_commands_dropped_total",
+        &[("command", &command)],
+        1,
+    );
+}
+
 impl ConsensusControllerImpl {
     pub fn new(
         command_sender: SyncSender<ConsensusCommand>,
         channels: ConsensusChannels,
         shared_state: Arc<RwLock<ConsensusState>>,
+        known_blocks: Arc<RwLock<PreHashSet<BlockId>>>,
         bootstrap_part_size: u64,
         broadcast_enabled: bool,
     ) -> Self {
@@ -49,6 +60,7 @@ impl ConsensusControllerImpl {
             command_sender,
             channels,
             shared_state,
+            known_blocks,
             bootstrap_part_size,
             broadcast_enabled,
         }
@@ -88,6 +100,18 @@ impl ConsensusController for ConsensusControllerImpl {
             .collect()
     }
 
+    /// Check whether blocks are known by the graph, without locking the consensus state
+    ///
+    /// # Arguments:
+    /// * `ids`: the block ids to check
+    ///
+    /// # Returns:
+    /// A vector of booleans sorted by the order of the block ids
+    fn are_blocks_known(&self, ids: &[BlockId]) -> Vec<bool> {
+        let known_blocks = self.known_blocks.read();
+        ids.iter().map(|id| known_blocks.contains(id)).collect()
+    }
+
     /// Get all the cliques possible in the block graph.
     ///
     /// # Returns:
@@ -267,6 +291,7 @@ impl ConsensusController for ConsensusControllerImpl {
                 created,
             ))
         {
+            record_dropped_command("register_block");
             warn!("error trying to register a block: {}", err);
         }
     }
@@ -282,6 +307,7 @@ impl ConsensusController for ConsensusControllerImpl {
             .command_sender
             .try_send(ConsensusCommand::RegisterBlockHeader(block_id, header))
         {
+            record_dropped_command("register_block_header");
             warn!("error trying to register a block header: {}", err);
         }
     }
@@ -291,6 +317,7 @@ impl ConsensusController for ConsensusControllerImpl {
             .command_sender
             .try_send(ConsensusCommand::MarkInvalidBlock(block_id, header))
         {
+            record_dropped_command("mark_invalid_block");
             warn!("error trying to mark block as invalid: {}", err);
         }
     }
@@ -7,7 +7,7 @@ use massa_consensus_exports::{
 use massa_logging::massa_trace;
 use massa_models::{block::BlockId, clique::Clique, prehash::PreHashSet, slot::Slot};
 
-use super::ConsensusState;
+use super::{stats::record_discarded_block, ConsensusState};
 
 impl ConsensusState {
     pub fn insert_parents_descendants(
@@ -149,6 +149,7 @@ impl ConsensusState {
                 "hash": block_id
             });
 
+            record_discarded_block(&DiscardReason::Stale);
             // mark as stale
             self.new_stale_blocks
                 .insert(*block_id, (active_block.creator_address, active_block.slot));
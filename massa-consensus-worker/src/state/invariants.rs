@@ -0,0 +1,179 @@
+use massa_consensus_exports::{block_status::BlockStatus, error::ConsensusError};
+use massa_models::{block::BlockId, prehash::PreHashSet};
+
+use super::ConsensusState;
+
+impl ConsensusState {
+    /// Checks the consistency of the block graph:
+    /// * every block status is referenced by the index of its kind, and every index entry has a status of that kind
+    /// * non-final active blocks have active parents, and are after the latest final block of their thread
+    /// * the incompatibility graph is symmetric and has exactly one entry per non-final active block
+    /// * there is exactly one blockclique, and cliques only contain compatible non-final active blocks
+    /// * the latest final blocks are final active blocks
+    ///
+    /// # Returns
+    /// An error listing every violated invariant
+    pub fn check_invariants(&self) -> Result<(), ConsensusError> {
+        let mut violations: Vec<String> = Vec::new();
+
+        // index coherence
+        let indexes: [(&str, &PreHashSet<BlockId>); 5] = [
+            ("active", &self.active_index),
+            ("incoming", &self.incoming_index),
+            ("waiting for slot", &self.waiting_for_slot_index),
+            (
+                "waiting for dependencies",
+                &self.waiting_for_dependencies_index,
+            ),
+            ("discarded", &self.discarded_index),
+        ];
+        for (block_id, status) in self.block_statuses.iter() {
+            let kind = match status {
+                BlockStatus::Active { .. } => "active",
+                BlockStatus::Incoming(_) => "incoming",
+                BlockStatus::WaitingForSlot(_) => "waiting for slot",
+                BlockStatus::WaitingForDependencies { .. } => "waiting for dependencies",
+                BlockStatus::Discarded { .. } => "discarded",
+            };
+            for (index_kind, index) in indexes.iter() {
+                if (*index_kind == kind) != index.contains(block_id) {
+                    violations.push(format!(
+                        "{} block {} is {} the {} index",
+                        kind,
+                        block_id,
+                        if index.contains(block_id) {
+                            "in"
+                        } else {
+                            "missing from"
+                        },
+                        index_kind
+                    ));
+                }
+            }
+        }
+        for (index_kind, index) in indexes.iter() {
+            for block_id in index.iter() {
+                if !self.block_statuses.contains_key(block_id) {
+                    violations.push(format!(
+                        "block {} of the {} index has no status",
+                        block_id, index_kind
+                    ));
+                }
+            }
+        }
+
+        // active blocks
+        let mut non_final_ids: PreHashSet<BlockId> = PreHashSet::default();
+        for block_id in self.active_index.iter() {
+            let Some((a_block, _storage)) = self.get_full_active_block(block_id) else {
+                continue;
+            };
+            if a_block.is_final {
+                continue;
+            }
+            non_final_ids.insert(*block_id);
+            for (parent_id, _period) in a_block.parents.iter() {
+                if self.get_full_active_block(parent_id).is_none() {
+                    violations.push(format!(
+                        "parent {} of non-final block {} is not active",
+                        parent_id, block_id
+                    ));
+                }
+            }
+            if let Some((_, final_period)) = self
+                .latest_final_blocks_periods
+                .get(a_block.slot.thread as usize)
+                && a_block.slot.period <= *final_period
+            {
+                violations.push(format!(
+                    "non-final block {} at slot {} is not after the latest final period {} of its thread",
+                    block_id, a_block.slot, final_period
+                ));
+            }
+        }
+
+        // incompatibility graph
+        for (block_id, incomps) in self.gi_head.iter() {
+            if !non_final_ids.contains(block_id) {
+                violations.push(format!(
+                    "block {} of the incompatibility graph is not a non-final active block",
+                    block_id
+                ));
+            }
+            for incomp_id in incomps.iter() {
+                if !self
+                    .gi_head
+                    .get(incomp_id)
+                    .map_or(false, |other| other.contains(block_id))
+                {
+                    violations.push(format!(
+                        "incompatibility between {} and {} is not symmetric",
+                        block_id, incomp_id
+                    ));
+                }
+            }
+        }
+        for block_id in non_final_ids.iter() {
+            if !self.gi_head.contains_key(block_id) {
+                violations.push(format!(
+                    "non-final block {} is missing from the incompatibility graph",
+                    block_id
+                ));
+            }
+        }
+
+        // cliques
+        let blockclique_count = self
+            .max_cliques
+            .iter()
+            .filter(|clique| clique.is_blockclique)
+            .count();
+        if blockclique_count != 1 {
+            violations.push(format!(
+                "{} cliques are marked as blockclique",
+                blockclique_count
+            ));
+        }
+        for (clique_i, clique) in self.max_cliques.iter().enumerate() {
+            for block_id in clique.block_ids.iter() {
+                if !non_final_ids.contains(block_id) {
+                    violations.push(format!(
+                        "block {} of clique {} is not a non-final active block",
+                        block_id, clique_i
+                    ));
+                }
+                if let Some(incomps) = self.gi_head.get(block_id)
+                    && let Some(incomp_id) = incomps.intersection(&clique.block_ids).next()
+                {
+                    violations.push(format!(
+                        "clique {} contains incompatible blocks {} and {}",
+                        clique_i, block_id, incomp_id
+                    ));
+                }
+            }
+        }
+
+        // latest final blocks
+        for (thread, (block_id, period)) in self.latest_final_blocks_periods.iter().enumerate() {
+            match self.get_full_active_block(block_id) {
+                Some((a_block, _storage))
+                    if a_block.is_final
+                        && a_block.slot.thread as usize == thread
+                        && a_block.slot.period == *period => {}
+                _ => violations.push(format!(
+                    "latest final block {} of thread {} is not a final active block at period {}",
+                    block_id, thread, period
+                )),
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ConsensusError::ContainerInconsistency(format!(
+                "block graph invariants violated: {}",
+                violations.join("; ")
+            )))
+        }
+    }
+}
@@ -20,9 +20,12 @@ use massa_models::{
 };
 use massa_storage::Storage;
 use massa_time::MassaTime;
+use parking_lot::RwLock;
+use std::sync::Arc;
 use tracing::debug;
 
 mod graph;
+mod invariants;
 mod process;
 mod process_commands;
 mod prune;
@@ -55,6 +58,8 @@ pub struct ConsensusState {
     pub best_parents: Vec<(BlockId, u64)>,
     /// Every block we know about
     pub block_statuses: PreHashMap<BlockId, BlockStatus>,
+    /// Ids of `block_statuses`, readable by the controller without locking the whole state
+    pub known_blocks: Arc<RwLock<PreHashSet<BlockId>>>,
     /// Ids of incoming blocks/headers
     pub incoming_index: PreHashSet<BlockId>,
     /// Used to limit the number of waiting and discarded blocks
@@ -92,6 +97,14 @@ pub struct ConsensusState {
 }
 
 impl ConsensusState {
+    /// Update `known_blocks` with the current content of `block_statuses`.
+    /// Called by the worker each time it releases the state after changing it.
+    pub fn sync_known_blocks(&self) {
+        let mut known_blocks = self.known_blocks.write();
+        known_blocks.retain(|block_id| self.block_statuses.contains_key(block_id));
+        known_blocks.extend(self.block_statuses.keys().copied());
+    }
+
     /// Get a full active block
     pub fn get_full_active_block(&self, block_id: &BlockId) -> Option<(&ActiveBlock, &Storage)> {
         match self.block_statuses.get(block_id) {
@@ -23,7 +23,7 @@ use tracing::log::{debug, info};
 
 use crate::state::verifications::HeaderCheckOutcome;
 
-use super::ConsensusState;
+use super::{stats::record_discarded_block, ConsensusState};
 
 impl ConsensusState {
     /// Acknowledge a set of items recursively and process them
@@ -176,6 +176,7 @@ impl ConsensusState {
                             self.new_stale_blocks
                                 .insert(block_id, (header.creator_address, header.content.slot));
                         }
+                        record_discarded_block(&reason);
                         // discard
                         self.block_statuses.insert(
                             block_id,
@@ -304,6 +305,7 @@ impl ConsensusState {
                                 ),
                             );
                         }
+                        record_discarded_block(&reason);
                         // add to discard
                         self.block_statuses.insert(
                             block_id,
@@ -514,6 +516,7 @@ impl ConsensusState {
             },
         );
         self.active_index.insert(add_block_id);
+        massa_metrics::inc_counter("massa_consensus_blocks_acknowledged_total", &[], 1);
 
         // add as child to parents
         // add as descendant to ancestors. Note: descendants are never removed.
@@ -13,7 +13,7 @@ use massa_storage::Storage;
 use massa_time::MassaTime;
 use tracing::debug;
 
-use super::ConsensusState;
+use super::{stats::record_discarded_block, ConsensusState};
 
 impl ConsensusState {
     /// Register a block header in the graph. Ignore genesis hashes.
@@ -169,6 +169,7 @@ impl ConsensusState {
         self.maybe_note_attack_attempt(&reason, block_id);
         massa_trace!("consensus.block_graph.process.invalid_block", {"block_id": block_id, "reason": reason});
 
+        record_discarded_block(&reason);
         // add to discard
         self.block_statuses.insert(
             *block_id,
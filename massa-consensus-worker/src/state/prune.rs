@@ -11,7 +11,7 @@ use massa_models::{
 };
 use tracing::debug;
 
-use super::ConsensusState;
+use super::{stats::record_discarded_block, ConsensusState};
 
 impl ConsensusState {
     /// prune active blocks and return final blocks, return discarded final blocks
@@ -86,6 +86,7 @@ impl ConsensusState {
 
             massa_trace!("consensus.block_graph.prune_active", {"hash": discard_active_h, "reason": DiscardReason::Final});
 
+            record_discarded_block(&DiscardReason::Final);
             // mark as final
             self.block_statuses.insert(
                 discard_active_h,
@@ -304,6 +305,7 @@ impl ConsensusState {
                         self.new_stale_blocks
                             .insert(block_id, (header.creator_address, header.content.slot));
                     }
+                    record_discarded_block(&reason);
                     // transition to Discarded only if there is a reason
                     self.block_statuses.insert(
                         block_id,
@@ -1,5 +1,5 @@
 use super::ConsensusState;
-use massa_consensus_exports::error::ConsensusError;
+use massa_consensus_exports::{block_status::DiscardReason, error::ConsensusError};
 use massa_models::stats::ConsensusStats;
 use massa_time::MassaTime;
 use std::cmp::max;
@@ -10,6 +10,20 @@ use tracing::log::warn;
 #[cfg(not(feature = "sandbox"))]
 use massa_consensus_exports::events::ConsensusEvent;
 
+/// Counts a discarded block in the metrics, by discard reason
+pub(crate) fn record_discarded_block(reason: &DiscardReason) {
+    let reason = match reason {
+        DiscardReason::Invalid(_) => "invalid",
+        DiscardReason::Stale => "stale",
+        DiscardReason::Final => "final",
+    };
+    massa_metrics::inc_counter(
+        "massa_consensus_blocks_discarded_total",
+        &[("reason", &reason)],
+        1,
+    );
+}
+
 impl ConsensusState {
     /// Calculate and return stats about consensus
     pub fn get_stats(&self) -> Result<ConsensusStats, ConsensusError> {
@@ -65,6 +79,11 @@ impl ConsensusState {
         }
         // prune stats
         self.prune_stats()?;
+        massa_metrics::set_gauge(
+            "massa_consensus_cliques",
+            &[],
+            self.get_clique_count() as f64,
+        );
         Ok(())
     }
 
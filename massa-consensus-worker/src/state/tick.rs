@@ -44,6 +44,11 @@ impl ConsensusState {
         // take care of block db changes
         self.block_db_changed()?;
 
+        // diagnostics mode: report any inconsistency of the graph
+        if self.config.check_graph_invariants {
+            self.check_invariants()?;
+        }
+
         Ok(())
     }
 }
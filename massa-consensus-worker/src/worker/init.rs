@@ -2,15 +2,13 @@ use massa_consensus_exports::{
     block_status::BlockStatus, bootstrapable_graph::BootstrapableGraph, error::ConsensusError,
     ConsensusConfig,
 };
-use massa_hash::Hash;
 use massa_models::{
     active_block::ActiveBlock,
     address::Address,
-    block::{Block, BlockHeader, BlockHeaderSerializer, BlockId, BlockSerializer, WrappedBlock},
+    block::{BlockId, WrappedBlock},
     prehash::PreHashMap,
     slot::Slot,
-    timeslots::{get_block_slot_timestamp, get_latest_block_slot_at_timestamp},
-    wrapped::WrappedContent,
+    timeslots::{get_block_slot_timestamp, get_latest_block_slot_at_timestamp, SlotScheduler},
 };
 use massa_storage::Storage;
 use massa_time::MassaTime;
@@ -19,7 +17,7 @@ use std::{
     collections::{HashMap, VecDeque},
     sync::{mpsc, Arc},
 };
-use tracing::log::info;
+use tracing::log::{info, warn};
 
 use crate::{commands::ConsensusCommand, state::ConsensusState};
 
@@ -37,25 +35,9 @@ pub fn create_genesis_block(
     cfg: &ConsensusConfig,
     thread_number: u8,
 ) -> Result<WrappedBlock, ConsensusError> {
-    let keypair = &cfg.genesis_key;
-    let header = BlockHeader::new_wrapped(
-        BlockHeader {
-            slot: Slot::new(0, thread_number),
-            parents: Vec::new(),
-            operation_merkle_root: Hash::compute_from(&Vec::new()),
-            endorsements: Vec::new(),
-        },
-        BlockHeaderSerializer::new(),
-        keypair,
-    )?;
-
-    Ok(Block::new_wrapped(
-        Block {
-            header,
-            operations: Default::default(),
-        },
-        BlockSerializer::new(),
-        keypair,
+    Ok(massa_models::config::create_genesis_block(
+        &cfg.genesis_key,
+        thread_number,
     )?)
 }
 
@@ -79,7 +61,8 @@ impl ConsensusWorker {
         init_graph: Option<BootstrapableGraph>,
         storage: Storage,
     ) -> Result<Self, ConsensusError> {
-        let now = MassaTime::now().expect("Couldn't init timer consensus");
+        let clock = shared_state.read().channels.clock.clone();
+        let now = clock.now().expect("Couldn't init timer consensus");
         let previous_slot = get_latest_block_slot_at_timestamp(
             config.thread_count,
             config.t0,
@@ -116,16 +99,14 @@ impl ConsensusWorker {
             );
         }
 
-        let next_slot = previous_slot.map_or(Ok(Slot::new(0u64, 0u8)), |s| {
-            s.get_next_slot(config.thread_count)
-        })?;
-        let next_instant = get_block_slot_timestamp(
+        let mut slot_scheduler = SlotScheduler::new(
             config.thread_count,
             config.t0,
             config.genesis_timestamp,
-            next_slot,
-        )?
-        .estimate_instant()?;
+            MassaTime::from(0),
+            previous_slot,
+        );
+        let (next_slot, next_timestamp) = slot_scheduler.next_slot_at(now)?;
 
         info!(
             "Started node at time {}, cycle {}, period {}, thread {}",
@@ -168,7 +149,9 @@ impl ConsensusWorker {
             shared_state,
             previous_slot,
             next_slot,
-            next_instant,
+            next_timestamp,
+            slot_scheduler,
+            clock,
         };
 
         if let Some(BootstrapableGraph { final_blocks }) = init_graph {
@@ -212,6 +195,11 @@ impl ConsensusWorker {
             }
 
             res_consensus.claim_parent_refs()?;
+
+            // report inconsistencies of the bootstrapped graph
+            if let Err(err) = res_consensus.shared_state.read().check_invariants() {
+                warn!("bootstrapped graph: {}", err);
+            }
         } else {
             {
                 let mut write_shared_state = res_consensus.shared_state.write();
@@ -254,6 +242,7 @@ impl ConsensusWorker {
                 .collect();
             write_shared_state.prev_blockclique =
                 notify_blockclique.iter().map(|(k, v)| (*v, *k)).collect();
+            write_shared_state.sync_known_blocks();
             write_shared_state
                 .channels
                 .execution_controller
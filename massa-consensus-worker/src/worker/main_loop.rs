@@ -1,14 +1,13 @@
-use std::{sync::mpsc, time::Instant};
+use std::sync::mpsc;
 
 use massa_consensus_exports::{error::ConsensusError, events::ConsensusEvent};
-use massa_models::{
-    slot::Slot,
-    timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
-};
 use massa_time::MassaTime;
+use parking_lot::RwLockWriteGuard;
+use tracing::info_span;
 use tracing::log::{info, warn};
 
 use crate::commands::ConsensusCommand;
+use crate::state::ConsensusState;
 
 use super::ConsensusWorker;
 
@@ -19,21 +18,40 @@ enum WaitingStatus {
 }
 
 impl ConsensusWorker {
-    /// Execute a command received from the controller also run an update of the graph after processing the command.
+    /// Execute a command received from the controller.
     ///
     /// # Arguments:
+    /// * `write_shared_state`: the locked consensus state
     /// * `command`: the command to execute
     ///
     /// # Returns:
-    /// An error if the command failed
-    fn manage_command(&mut self, command: ConsensusCommand) -> Result<(), ConsensusError> {
-        let mut write_shared_state = self.shared_state.write();
+    /// Whether the graph needs to be updated, or an error if the command failed
+    fn manage_command(
+        &self,
+        write_shared_state: &mut ConsensusState,
+        command: ConsensusCommand,
+    ) -> Result<bool, ConsensusError> {
         match command {
             ConsensusCommand::RegisterBlockHeader(block_id, header) => {
+                let _header_span = info_span!(
+                    "block_header",
+                    period = header.content.slot.period,
+                    thread = header.content.slot.thread,
+                    block_id = %block_id
+                )
+                .entered();
                 write_shared_state.register_block_header(block_id, header, self.previous_slot)?;
-                write_shared_state.block_db_changed()
+                Ok(true)
             }
             ConsensusCommand::RegisterBlock(block_id, slot, block_storage, created) => {
+                let _block_span = info_span!(
+                    "block",
+                    period = slot.period,
+                    thread = slot.thread,
+                    block_id = %block_id,
+                    created
+                )
+                .entered();
                 write_shared_state.register_block(
                     block_id,
                     slot,
@@ -41,84 +59,91 @@ impl ConsensusWorker {
                     block_storage,
                     created,
                 )?;
-                write_shared_state.block_db_changed()
+                Ok(true)
             }
             ConsensusCommand::MarkInvalidBlock(block_id, header) => {
                 write_shared_state.mark_invalid_block(&block_id, header);
-                Ok(())
+                Ok(false)
             }
         }
     }
 
-    /// Wait and interrupt or wait until an instant or a stop signal
+    /// Execute a command and the following queued ones, up to `max_commands_per_batch` commands,
+    /// under a single lock of the consensus state, then run an update of the graph for the whole batch.
+    /// The lock is released fairly so that pending readers (API, factories) are served before the next batch.
     ///
-    /// # Return value
-    /// Returns the error of the process of the command if any.
-    /// Returns true if we reached the instant.
-    /// Returns false if we were interrupted by a command.
-    fn wait_slot_or_command(&mut self, deadline: Instant) -> WaitingStatus {
-        match self.command_receiver.recv_deadline(deadline) {
-            // message received => manage it
-            Ok(command) => {
-                if let Err(err) = self.manage_command(command) {
-                    warn!("Error in consensus: {}", err);
-                }
-                WaitingStatus::Interrupted
+    /// # Arguments:
+    /// * `first_command`: the command that was received
+    fn manage_commands(&self, first_command: ConsensusCommand) {
+        let mut write_shared_state = self.shared_state.write();
+        let mut graph_changed = false;
+        let mut processed_count: usize = 0;
+        let mut next_command = Some(first_command);
+        while let Some(command) = next_command {
+            match self.manage_command(&mut write_shared_state, command) {
+                Ok(changed) => graph_changed |= changed,
+                Err(err) => warn!("Error in consensus: {}", err),
             }
-            // timeout => continue main loop
-            Err(mpsc::RecvTimeoutError::Timeout) => WaitingStatus::Ended,
-            // channel disconnected (sender dropped) => quit main loop
-            Err(mpsc::RecvTimeoutError::Disconnected) => WaitingStatus::Disconnected,
+            processed_count += 1;
+            next_command = if processed_count < self.config.max_commands_per_batch {
+                self.command_receiver.try_recv().ok()
+            } else {
+                None
+            };
         }
-    }
-
-    /// Gets the next slot and the instant when it will happen.
-    /// Slots can be skipped if we waited too much in-between.
-    /// Extra safety against double-production caused by clock adjustments (this is the role of the `previous_slot` parameter).
-    fn get_next_slot(&self, previous_slot: Option<Slot>) -> (Slot, Instant) {
-        // get current absolute time
-        let now = MassaTime::now().expect("could not get current time");
-
-        // get closest slot according to the current absolute time
-        let mut next_slot = get_closest_slot_to_timestamp(
-            self.config.thread_count,
-            self.config.t0,
-            self.config.genesis_timestamp,
-            now,
+        if graph_changed {
+            if let Err(err) = write_shared_state.block_db_changed() {
+                warn!("Error in consensus: {}", err);
+            }
+        }
+        write_shared_state.sync_known_blocks();
+        RwLockWriteGuard::unlock_fair(write_shared_state);
+        massa_metrics::set_gauge(
+            "massa_consensus_command_batch_size",
+            &[],
+            processed_count as f64,
         );
+    }
 
-        // protection against double-production on unexpected system clock adjustment
-        if let Some(prev_slot) = previous_slot {
-            if next_slot <= prev_slot {
-                next_slot = prev_slot
-                    .get_next_slot(self.config.thread_count)
-                    .expect("could not compute next slot");
+    /// Wait and interrupt or wait until a timestamp of the worker clock or a stop signal
+    ///
+    /// # Return value
+    /// Returns the error of the process of the command if any.
+    /// Returns true if we reached the timestamp.
+    /// Returns false if we were interrupted by a command.
+    fn wait_slot_or_command(&mut self, deadline: MassaTime) -> WaitingStatus {
+        loop {
+            // a flood of commands must not delay the slot ticks
+            let timeout = self
+                .clock
+                .duration_until(deadline)
+                .expect("could not read the clock");
+            if timeout.is_zero() {
+                return WaitingStatus::Ended;
+            }
+            match self.command_receiver.recv_timeout(timeout) {
+                // message received => manage it along with the queued ones
+                Ok(command) => {
+                    self.manage_commands(command);
+                    return WaitingStatus::Interrupted;
+                }
+                // timeout => check the deadline again, the clock may not have reached it yet
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                // channel disconnected (sender dropped) => quit main loop
+                Err(mpsc::RecvTimeoutError::Disconnected) => return WaitingStatus::Disconnected,
             }
         }
-
-        // get the timestamp of the target slot
-        let next_instant = get_block_slot_timestamp(
-            self.config.thread_count,
-            self.config.t0,
-            self.config.genesis_timestamp,
-            next_slot,
-        )
-        .expect("could not get block slot timestamp")
-        .estimate_instant()
-        .expect("could not estimate block slot instant");
-
-        (next_slot, next_instant)
     }
 
     /// Runs in loop forever. This loop must stop every slot to perform operations on stats and graph
     /// but can be stopped anytime by a command received.
     pub fn run(&mut self) {
-        let mut last_prune = Instant::now();
+        let mut last_prune = self.clock.now().expect("could not read the clock");
         loop {
-            match self.wait_slot_or_command(self.next_instant) {
+            match self.wait_slot_or_command(self.next_timestamp) {
                 WaitingStatus::Ended => {
                     if let Some(end) = self.config.end_timestamp {
-                        if self.next_instant > end.estimate_instant().unwrap() {
+                        if self.next_timestamp > end {
                             info!("This episode has come to an end, please get the latest testnet node version to continue");
                             let _ = self
                                 .shared_state
@@ -140,23 +165,33 @@ impl ConsensusWorker {
                     if previous_cycle < Some(observed_cycle) {
                         info!("Started cycle {}", observed_cycle);
                     }
+                    let _slot_span = info_span!(
+                        "slot",
+                        period = self.next_slot.period,
+                        thread = self.next_slot.thread
+                    )
+                    .entered();
                     {
                         let mut write_shared_state = self.shared_state.write();
                         if let Err(err) = write_shared_state.slot_tick(self.next_slot) {
                             warn!("Error while processing block tick: {}", err);
                         }
+                        write_shared_state.sync_known_blocks();
                     };
-                    if last_prune.elapsed().as_millis()
-                        > self.config.block_db_prune_interval.to_millis() as u128
-                    {
-                        self.shared_state
-                            .write()
-                            .prune()
-                            .expect("Error while pruning");
-                        last_prune = Instant::now();
+                    let now = self.clock.now().expect("could not read the clock");
+                    if now.saturating_sub(last_prune) > self.config.block_db_prune_interval {
+                        let mut write_shared_state = self.shared_state.write();
+                        write_shared_state.prune().expect("Error while pruning");
+                        write_shared_state.sync_known_blocks();
+                        last_prune = now;
                     }
                     self.previous_slot = Some(self.next_slot);
-                    (self.next_slot, self.next_instant) = self.get_next_slot(Some(self.next_slot));
+                    let (next_slot, next_timestamp) = self
+                        .slot_scheduler
+                        .next_slot()
+                        .expect("could not schedule next slot");
+                    self.next_slot = next_slot;
+                    self.next_timestamp = next_timestamp;
                 }
                 WaitingStatus::Disconnected => {
                     break;
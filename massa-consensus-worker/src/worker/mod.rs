@@ -4,15 +4,14 @@ use massa_consensus_exports::{
 };
 use massa_models::block::BlockId;
 use massa_models::clique::Clique;
-use massa_models::config::CHANNEL_SIZE;
 use massa_models::prehash::PreHashSet;
 use massa_models::slot::Slot;
+use massa_models::timeslots::SlotScheduler;
 use massa_storage::Storage;
-use massa_time::MassaTime;
+use massa_time::{MassaClock, MassaTime};
 use parking_lot::RwLock;
 use std::sync::{mpsc, Arc};
 use std::thread;
-use std::time::Instant;
 
 use crate::commands::ConsensusCommand;
 use crate::controller::ConsensusControllerImpl;
@@ -31,8 +30,12 @@ pub struct ConsensusWorker {
     previous_slot: Option<Slot>,
     /// Next slot
     next_slot: Slot,
-    /// Next slot instant
-    next_instant: Instant,
+    /// Next slot timestamp
+    next_timestamp: MassaTime,
+    /// Schedules the slots to tick
+    slot_scheduler: SlotScheduler,
+    /// Source of the current time
+    clock: Arc<dyn MassaClock>,
 }
 
 mod init;
@@ -55,11 +58,12 @@ pub fn start_consensus_worker(
     init_graph: Option<BootstrapableGraph>,
     storage: Storage,
 ) -> (Box<dyn ConsensusController>, Box<dyn ConsensusManager>) {
-    let (tx, rx) = mpsc::sync_channel(CHANNEL_SIZE);
+    let (tx, rx) = mpsc::sync_channel(config.channel_size);
     // desync detection timespan
     let bootstrap_part_size = config.bootstrap_part_size;
     let stats_desync_detection_timespan =
         config.t0.checked_mul(config.periods_per_cycle * 2).unwrap();
+    let known_blocks = Arc::new(RwLock::new(PreHashSet::<BlockId>::default()));
     let shared_state = Arc::new(RwLock::new(ConsensusState {
         storage: storage.clone(),
         config: config.clone(),
@@ -83,13 +87,14 @@ pub fn start_consensus_worker(
         latest_final_blocks_periods: Default::default(),
         best_parents: Default::default(),
         block_statuses: Default::default(),
+        known_blocks: known_blocks.clone(),
         genesis_hashes: Default::default(),
         gi_head: Default::default(),
         final_block_stats: Default::default(),
         stale_block_stats: Default::default(),
         protocol_blocks: Default::default(),
         wishlist: Default::default(),
-        launch_time: MassaTime::now().unwrap(),
+        launch_time: channels.clock.now().unwrap(),
         stats_desync_detection_timespan,
         stats_history_timespan: std::cmp::max(
             stats_desync_detection_timespan,
@@ -115,6 +120,7 @@ pub fn start_consensus_worker(
         tx,
         channels,
         shared_state,
+        known_blocks,
         bootstrap_part_size,
         config.broadcast_enabled,
     );
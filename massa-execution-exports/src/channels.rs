@@ -0,0 +1,14 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! This file exports the channels used by the execution worker to broadcast its outputs
+
+use massa_models::execution::{ExecutionReorg, SlotExecutionOutput};
+
+/// Contains channels used to broadcast the execution outputs to the APIs
+#[derive(Clone)]
+pub struct ExecutionChannels {
+    /// Broadcast sender(channel) for the outputs of executed and finalized slots
+    pub slot_execution_output_sender: tokio::sync::broadcast::Sender<SlotExecutionOutput>,
+    /// Broadcast sender(channel) for the candidate slot executions cancelled by blockclique changes
+    pub execution_reorg_sender: tokio::sync::broadcast::Sender<ExecutionReorg>,
+}
@@ -4,11 +4,14 @@
 
 use crate::types::ReadOnlyExecutionRequest;
 use crate::ExecutionError;
-use crate::{ExecutionAddressInfo, ReadOnlyExecutionOutput};
-use massa_models::address::Address;
+use crate::{
+    DatastoreEntryDiff, ExecutionAddressBytecode, ExecutionAddressInfo, ReadOnlyExecutionOutput,
+};
+use massa_models::address::{Address, AddressHistoryEntry, AddressTransfer};
 use massa_models::amount::Amount;
 use massa_models::api::EventFilter;
 use massa_models::block::BlockId;
+use massa_models::ledger_proof::FinalLedgerEntryProof;
 use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashMap;
@@ -61,6 +64,41 @@ pub trait ExecutionController: Send + Sync {
         input: Vec<(Address, Vec<u8>)>,
     ) -> Vec<(Option<Vec<u8>>, Option<Vec<u8>>)>;
 
+    /// Get a page of the final and candidate datastore keys of an address, in ascending order.
+    /// Only the keys starting with `prefix` and strictly greater than `start_key` (if set) are returned,
+    /// so that the last key of a page can be used as the `start_key` of the next one.
+    ///
+    /// # Return value
+    /// * `(final_keys, candidate_keys)`, each holding at most `limit` keys
+    fn get_ledger_datastore_keys(
+        &self,
+        address: &Address,
+        prefix: &[u8],
+        start_key: Option<&[u8]>,
+        limit: usize,
+    ) -> (Vec<Vec<u8>>, Vec<Vec<u8>>);
+
+    /// Get the datastore entries of an address whose candidate value differs from the final one,
+    /// in ascending key order. Shows the datastore changes that are not final yet.
+    fn get_datastore_diff(&self, address: &Address) -> Vec<DatastoreEntryDiff>;
+
+    /// Get the proofs of final ledger entries against the final state hash,
+    /// allowing light clients to verify them without running a node.
+    /// An entry is the balance of an address if its datastore key is `None`.
+    fn get_final_ledger_entry_proofs(
+        &self,
+        input: Vec<(Address, Option<Vec<u8>>)>,
+    ) -> Vec<FinalLedgerEntryProof>;
+
+    /// Get the final and candidate bytecode deployed at an address, with its hash and size
+    fn get_bytecode(&self, address: &Address) -> ExecutionAddressBytecode;
+
+    /// Check whether a batch of addresses have a non-empty bytecode deployed
+    ///
+    /// # Return value
+    /// * `(final_has_bytecode, candidate_has_bytecode)` for each address
+    fn has_bytecode(&self, addresses: &[Address]) -> Vec<(bool, bool)>;
+
     /// Returns for a given cycle the stakers taken into account
     /// by the selector. That correspond to the `roll_counts` in `cycle - 3`.
     ///
@@ -90,6 +128,25 @@ pub trait ExecutionController: Send + Sync {
     /// Gets information about a batch of addresses
     fn get_addresses_infos(&self, addresses: &[Address]) -> Vec<ExecutionAddressInfo>;
 
+    /// Gets the balance and roll changes of an address in the recent final slots,
+    /// optionally restricted to the slots between `from` and `to` (both included)
+    fn get_address_history(
+        &self,
+        address: &Address,
+        from: Option<Slot>,
+        to: Option<Slot>,
+    ) -> Vec<AddressHistoryEntry>;
+
+    /// Gets a page of the coins sent and received by an address through the operations
+    /// of the recent final slots, newest first.
+    /// Also returns the total number of transfers kept for that address.
+    fn get_transactions_by_address(
+        &self,
+        address: &Address,
+        offset: usize,
+        limit: usize,
+    ) -> (usize, Vec<AddressTransfer>);
+
     /// Get execution statistics
     fn get_stats(&self) -> ExecutionStats;
 
@@ -107,7 +164,7 @@ impl Clone for Box<dyn ExecutionController> {
 }
 
 /// Execution manager used to stop the execution thread
-pub trait ExecutionManager {
+pub trait ExecutionManager: Send {
     /// Stop the execution thread
     /// Note that we do not take self by value to consume it
     /// because it is not allowed to move out of Box<dyn ExecutionManager>
@@ -44,4 +44,106 @@ pub enum ExecutionError {
 
     /// Include operation error: {0}
     IncludeOperationError(String),
+
+    /// Insufficient balance: {0}
+    InsufficientBalance(String),
+
+    /// Gas exhausted: {0}
+    GasExhausted(String),
+
+    /// VM error: {0}
+    VMError(String),
+
+    /// Bytecode missing: {0}
+    BytecodeMissing(String),
+
+    /// Target not found: {0}
+    TargetNotFound(String),
+
+    /// Coins overflow: {0}
+    CoinsOverflow(String),
+
+    /// Datastore limit exceeded: {0}
+    DatastoreLimit(String),
+
+    /// Wrong address category: {0}
+    AddressCategoryError(String),
+}
+
+/// Message of the errors returned by the VM when its gas metering interrupts an execution.
+///
+/// The VM reports every error as an untyped message, so executions running out of gas are recognized
+/// by this message. `test_out_of_gas_error_code` in the execution worker fails if the VM rewords it.
+const VM_OUT_OF_GAS_MESSAGE: &str = "Not enough gas";
+
+impl ExecutionError {
+    /// Stable machine-readable code of the error category,
+    /// exposed in error events and API errors so that clients do not have to parse messages
+    pub fn code(&self) -> &'static str {
+        match self {
+            ExecutionError::ChannelError(_) => "channel_error",
+            ExecutionError::RuntimeError(_) => "runtime_error",
+            ExecutionError::MassaHashError(_) => "hash_error",
+            ExecutionError::ModelsError(_) => "models_error",
+            ExecutionError::RollBuyError(_) => "roll_buy_error",
+            ExecutionError::RollSellError(_) => "roll_sell_error",
+            ExecutionError::TransactionError(_) => "transaction_error",
+            ExecutionError::BlockGasError(_) => "block_gas_error",
+            ExecutionError::InvalidSlotRange => "invalid_slot_range",
+            ExecutionError::NotEnoughGas(_) => "not_enough_gas",
+            ExecutionError::TooMuchGas(_) => "too_much_gas",
+            ExecutionError::IncludeOperationError(_) => "include_operation_error",
+            ExecutionError::InsufficientBalance(_) => "insufficient_balance",
+            ExecutionError::GasExhausted(_) => "gas_exhausted",
+            ExecutionError::VMError(_) => "vm_error",
+            ExecutionError::BytecodeMissing(_) => "bytecode_missing",
+            ExecutionError::TargetNotFound(_) => "target_not_found",
+            ExecutionError::CoinsOverflow(_) => "coins_overflow",
+            ExecutionError::DatastoreLimit(_) => "datastore_limit",
+            ExecutionError::AddressCategoryError(_) => "address_category_error",
+        }
+    }
+
+    /// Prefixes the error message with some context, keeping the error category.
+    /// Errors without a message become runtime errors.
+    pub fn with_context(self, context: impl std::fmt::Display) -> Self {
+        let prefix = |msg: String| format!("{}: {}", context, msg);
+        match self {
+            ExecutionError::ChannelError(msg) => ExecutionError::ChannelError(prefix(msg)),
+            ExecutionError::RuntimeError(msg) => ExecutionError::RuntimeError(prefix(msg)),
+            ExecutionError::RollBuyError(msg) => ExecutionError::RollBuyError(prefix(msg)),
+            ExecutionError::RollSellError(msg) => ExecutionError::RollSellError(prefix(msg)),
+            ExecutionError::TransactionError(msg) => ExecutionError::TransactionError(prefix(msg)),
+            ExecutionError::BlockGasError(msg) => ExecutionError::BlockGasError(prefix(msg)),
+            ExecutionError::NotEnoughGas(msg) => ExecutionError::NotEnoughGas(prefix(msg)),
+            ExecutionError::TooMuchGas(msg) => ExecutionError::TooMuchGas(prefix(msg)),
+            ExecutionError::IncludeOperationError(msg) => {
+                ExecutionError::IncludeOperationError(prefix(msg))
+            }
+            ExecutionError::InsufficientBalance(msg) => {
+                ExecutionError::InsufficientBalance(prefix(msg))
+            }
+            ExecutionError::GasExhausted(msg) => ExecutionError::GasExhausted(prefix(msg)),
+            ExecutionError::VMError(msg) => ExecutionError::VMError(prefix(msg)),
+            ExecutionError::BytecodeMissing(msg) => ExecutionError::BytecodeMissing(prefix(msg)),
+            ExecutionError::TargetNotFound(msg) => ExecutionError::TargetNotFound(prefix(msg)),
+            ExecutionError::CoinsOverflow(msg) => ExecutionError::CoinsOverflow(prefix(msg)),
+            ExecutionError::DatastoreLimit(msg) => ExecutionError::DatastoreLimit(prefix(msg)),
+            ExecutionError::AddressCategoryError(msg) => {
+                ExecutionError::AddressCategoryError(prefix(msg))
+            }
+            other => ExecutionError::RuntimeError(prefix(other.to_string())),
+        }
+    }
+
+    /// Categorizes an error returned by the VM
+    pub fn from_vm_error(context: impl std::fmt::Display, err: impl std::fmt::Display) -> Self {
+        let err = err.to_string();
+        let msg = format!("{}: {}", context, err);
+        if err.contains(VM_OUT_OF_GAS_MESSAGE) {
+            ExecutionError::GasExhausted(msg)
+        } else {
+            ExecutionError::VMError(msg)
+        }
+    }
 }
@@ -44,20 +44,23 @@
 
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
+mod channels;
 mod controller_traits;
 mod error;
 mod event_store;
 mod settings;
 mod types;
 
+pub use channels::ExecutionChannels;
 pub use controller_traits::{ExecutionController, ExecutionManager};
 pub use error::ExecutionError;
-pub use event_store::EventStore;
+pub use event_store::{event_matches_filter, EventStore};
 pub use massa_sc_runtime::GasCosts;
 pub use settings::{ExecutionConfig, StorageCostsConstants};
 pub use types::{
-    ExecutionAddressInfo, ExecutionOutput, ExecutionStackElement, ReadOnlyCallRequest,
-    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    BytecodeInfo, DatastoreEntryDiff, ExecutionAddressBytecode, ExecutionAddressInfo,
+    ExecutionOutput, ExecutionStackElement, ReadOnlyCallRequest, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, Transfer,
 };
 
 #[cfg(any(feature = "testing", feature = "gas_calibration"))]
@@ -6,6 +6,7 @@ use massa_models::amount::Amount;
 use massa_sc_runtime::GasCosts;
 use massa_time::MassaTime;
 use num::rational::Ratio;
+use std::path::PathBuf;
 
 /// Storage cost constants
 #[derive(Debug, Clone, Copy)]
@@ -25,6 +26,12 @@ pub struct ExecutionConfig {
     pub readonly_queue_length: usize,
     /// maximum number of SC output events kept in cache
     pub max_final_events: usize,
+    /// number of periods during which final SC output events are kept
+    pub final_events_retention_periods: u64,
+    /// number of final slots for which the balance and roll changes of each address are kept
+    pub max_address_history_slots: usize,
+    /// number of final slots for which the coins sent and received by each address are kept
+    pub max_transfer_history_slots: usize,
     /// maximum available gas for asynchronous messages execution
     pub max_async_gas: u64,
     /// maximum gas per block
@@ -63,4 +70,10 @@ pub struct ExecutionConfig {
     pub max_read_only_gas: u64,
     /// Gas costs
     pub gas_costs: GasCosts,
+    /// optional file to which the traces of the final slots are appended
+    pub trace_path: Option<PathBuf>,
+    /// whether slot execution outputs are broadcast
+    pub broadcast_enabled: bool,
+    /// slot execution outputs sender(channel) capacity
+    pub broadcast_slot_execution_output_capacity: usize,
 }
@@ -23,6 +23,9 @@ impl Default for ExecutionConfig {
         Self {
             readonly_queue_length: 100,
             max_final_events: 1000,
+            final_events_retention_periods: 1000,
+            max_address_history_slots: 1000,
+            max_transfer_history_slots: 1000,
             max_async_gas: MAX_ASYNC_GAS,
             thread_count: THREAD_COUNT,
             roll_price: ROLL_PRICE,
@@ -55,6 +58,9 @@ impl Default for ExecutionConfig {
                 .into(),
             )
             .unwrap(),
+            trace_path: None,
+            broadcast_enabled: false,
+            broadcast_slot_execution_output_capacity: 5000,
         }
     }
 }
@@ -3,15 +3,17 @@
 //! This file defines utilities to mock the crate for testing purposes
 
 use crate::{
-    ExecutionAddressInfo, ExecutionController, ExecutionError, ReadOnlyExecutionOutput,
-    ReadOnlyExecutionRequest,
+    DatastoreEntryDiff, ExecutionAddressBytecode, ExecutionAddressInfo, ExecutionController,
+    ExecutionError, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
 };
+use massa_hash::{Hash, HASH_SIZE_BYTES};
 use massa_ledger_exports::LedgerEntry;
 use massa_models::{
-    address::Address,
+    address::{Address, AddressHistoryEntry, AddressTransfer},
     amount::Amount,
     api::EventFilter,
     block::BlockId,
+    ledger_proof::FinalLedgerEntryProof,
     operation::OperationId,
     output_event::SCOutputEvent,
     prehash::{PreHashMap, PreHashSet},
@@ -83,6 +85,13 @@ pub enum MockExecutionControllerMessage {
         /// response channel
         response_tx: mpsc::Sender<Vec<(Option<Amount>, Option<Amount>)>>,
     },
+    /// Get final and candidate bytecode of an address
+    GetBytecode {
+        /// address to get
+        address: Address,
+        /// response channel
+        response_tx: mpsc::Sender<ExecutionAddressBytecode>,
+    },
 }
 
 /// A mocked execution controller that will intercept calls on its methods
@@ -121,6 +130,8 @@ impl ExecutionController for MockExecutionController {
             final_block_count: 0,
             final_executed_operations_count: 0,
             active_cursor: Slot::new(0, 0),
+            final_cursor: Slot::new(0, 0),
+            final_state_hash: Hash::from_bytes(&[0; HASH_SIZE_BYTES]),
         }
     }
 
@@ -170,6 +181,48 @@ impl ExecutionController for MockExecutionController {
             .unwrap()
     }
 
+    fn get_bytecode(&self, address: &Address) -> ExecutionAddressBytecode {
+        let (response_tx, response_rx) = mpsc::channel();
+        if let Err(err) = self
+            .0
+            .lock()
+            .send(MockExecutionControllerMessage::GetBytecode {
+                address: *address,
+                response_tx,
+            })
+        {
+            println!("mock error {err}");
+        }
+        response_rx
+            .recv_timeout(Duration::from_millis(100))
+            .unwrap()
+    }
+
+    fn has_bytecode(&self, addresses: &[Address]) -> Vec<(bool, bool)> {
+        vec![(false, false); addresses.len()]
+    }
+
+    fn get_datastore_diff(&self, _address: &Address) -> Vec<DatastoreEntryDiff> {
+        Vec::default()
+    }
+
+    fn get_ledger_datastore_keys(
+        &self,
+        _address: &Address,
+        _prefix: &[u8],
+        _start_key: Option<&[u8]>,
+        _limit: usize,
+    ) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        (Vec::default(), Vec::default())
+    }
+
+    fn get_final_ledger_entry_proofs(
+        &self,
+        _input: Vec<(Address, Option<Vec<u8>>)>,
+    ) -> Vec<FinalLedgerEntryProof> {
+        Vec::default()
+    }
+
     fn get_final_and_active_data_entry(
         &self,
         _: Vec<(Address, Vec<u8>)>,
@@ -185,6 +238,24 @@ impl ExecutionController for MockExecutionController {
         BTreeMap::default()
     }
 
+    fn get_address_history(
+        &self,
+        _address: &Address,
+        _from: Option<Slot>,
+        _to: Option<Slot>,
+    ) -> Vec<AddressHistoryEntry> {
+        Vec::default()
+    }
+
+    fn get_transactions_by_address(
+        &self,
+        _address: &Address,
+        _offset: usize,
+        _limit: usize,
+    ) -> (usize, Vec<AddressTransfer>) {
+        (0, Vec::default())
+    }
+
     fn execute_readonly_request(
         &self,
         req: ReadOnlyExecutionRequest,
@@ -3,10 +3,16 @@
 //! This file exports useful types used to interact with the execution worker
 
 use crate::event_store::EventStore;
-use massa_final_state::StateChanges;
+use massa_final_state::{ExecutionTrace, StateChanges};
+use massa_hash::Hash;
 use massa_models::datastore::Datastore;
 use massa_models::{
-    address::Address, address::ExecutionAddressCycleInfo, amount::Amount, block::BlockId,
+    address::Address,
+    address::ExecutionAddressCycleInfo,
+    amount::Amount,
+    block::BlockId,
+    execution::{ReadOnlyCallTraceEntry, SlotExecutionOutput, SlotExecutionStatus},
+    operation::OperationId,
     slot::Slot,
 };
 use std::collections::{BTreeMap, BTreeSet};
@@ -36,6 +42,63 @@ pub struct ExecutionAddressInfo {
     pub cycle_infos: Vec<ExecutionAddressCycleInfo>,
 }
 
+/// Bytecode deployed at an address, along with its metadata
+#[derive(Clone, Debug)]
+pub struct BytecodeInfo {
+    /// the deployed bytecode
+    pub bytecode: Vec<u8>,
+    /// hash of the bytecode
+    pub hash: Hash,
+    /// size of the bytecode in bytes
+    pub size: u64,
+}
+
+impl BytecodeInfo {
+    /// Wraps a bytecode and computes its metadata
+    pub fn new(bytecode: Vec<u8>) -> Self {
+        BytecodeInfo {
+            hash: Hash::compute_from(&bytecode),
+            size: bytecode.len() as u64,
+            bytecode,
+        }
+    }
+}
+
+/// Final and candidate bytecode of an address
+#[derive(Clone, Debug)]
+pub struct ExecutionAddressBytecode {
+    /// final bytecode, `None` if the address does not exist in the final ledger
+    pub final_bytecode: Option<BytecodeInfo>,
+    /// candidate bytecode, `None` if the address does not exist in the candidate ledger
+    pub candidate_bytecode: Option<BytecodeInfo>,
+}
+
+/// Datastore entry of an address whose final and candidate values differ
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DatastoreEntryDiff {
+    /// datastore key
+    pub key: Vec<u8>,
+    /// final value, `None` if the entry does not exist in the final ledger
+    pub final_value: Option<Vec<u8>>,
+    /// candidate value, `None` if the entry does not exist in the candidate ledger
+    pub candidate_value: Option<Vec<u8>>,
+}
+
+/// Coins transferred by an operation from its sender to another address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    /// id of the operation
+    pub operation_id: OperationId,
+    /// sender of the coins
+    pub from: Address,
+    /// recipient of the coins
+    pub to: Address,
+    /// transferred amount
+    pub amount: Amount,
+    /// false if the execution of the operation failed and its effects were reverted
+    pub success: bool,
+}
+
 /// structure describing the output of a single execution
 #[derive(Debug, Clone)]
 pub struct ExecutionOutput {
@@ -47,6 +110,32 @@ pub struct ExecutionOutput {
     pub state_changes: StateChanges,
     /// events emitted by the execution step
     pub events: EventStore,
+    /// operations and asynchronous messages executed during the execution step
+    pub execution_trace: ExecutionTrace,
+    /// coins transferred by the operations executed during the execution step
+    pub transfers: Vec<Transfer>,
+}
+
+impl ExecutionOutput {
+    /// Summarizes the output for the subscribers of the slot execution outputs
+    pub fn summarize(&self, status: SlotExecutionStatus) -> SlotExecutionOutput {
+        SlotExecutionOutput {
+            slot: self.slot,
+            block_id: self.block_id,
+            status,
+            ledger_changes: self.state_changes.ledger_changes.summarize(),
+            roll_changes: self
+                .state_changes
+                .pos_changes
+                .roll_changes
+                .iter()
+                .map(|(address, roll_count)| (*address, *roll_count))
+                .collect(),
+            executed_ops: self.execution_trace.executed_ops.clone(),
+            gas_used: self.execution_trace.gas_used,
+            events: self.events.0.clone(),
+        }
+    }
 }
 
 /// structure describing the output of a read only execution
@@ -58,6 +147,8 @@ pub struct ReadOnlyExecutionOutput {
     pub gas_cost: u64,
     /// Returned value from the module call
     pub call_result: Vec<u8>,
+    /// Calls made between smart contracts, in call order
+    pub call_trace: Vec<ReadOnlyCallTraceEntry>,
 }
 
 /// structure describing different types of read-only execution request
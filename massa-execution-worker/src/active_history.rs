@@ -33,12 +33,12 @@ pub enum SlotIndexPosition {
 }
 
 impl ActiveHistory {
-    /// Remove `slot` and the slots after it from history
-    pub fn truncate_from(&mut self, slot: &Slot, thread_count: u8) {
+    /// Remove `slot` and the slots after it from history, and return the removed outputs
+    pub fn truncate_from(&mut self, slot: &Slot, thread_count: u8) -> Vec<ExecutionOutput> {
         match self.get_slot_index(slot, thread_count) {
-            SlotIndexPosition::Past => self.0.clear(),
-            SlotIndexPosition::Found(index) => self.0.truncate(index),
-            _ => {}
+            SlotIndexPosition::Past => self.0.drain(..).collect(),
+            SlotIndexPosition::Found(index) => self.0.drain(index..).collect(),
+            _ => Vec::new(),
         }
     }
 
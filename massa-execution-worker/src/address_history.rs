@@ -0,0 +1,123 @@
+//! Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! This module indexes the balance and roll changes of each address in the recent final slots.
+//!
+//! It allows wallets to display a transaction history without relying on an external indexer.
+//! Only the last `max_slots` final slots are kept: older history is dropped.
+
+use massa_execution_exports::ExecutionOutput;
+use massa_ledger_exports::{LedgerEntryUpdate, SetOrKeep, SetUpdateOrDelete};
+use massa_models::{
+    address::{Address, AddressHistoryEntry},
+    amount::Amount,
+    prehash::PreHashMap,
+    slot::Slot,
+};
+use std::collections::VecDeque;
+
+/// Balance and roll changes of the addresses in recent final slots
+pub(crate) struct AddressHistory {
+    /// changes indexed by address for each final slot, oldest at the front
+    slots: VecDeque<(Slot, PreHashMap<Address, AddressHistoryEntry>)>,
+    /// maximum number of slots kept
+    max_slots: usize,
+}
+
+impl AddressHistory {
+    /// Creates an empty `AddressHistory` keeping at most `max_slots` slots
+    pub fn new(max_slots: usize) -> Self {
+        AddressHistory {
+            slots: VecDeque::new(),
+            max_slots,
+        }
+    }
+
+    /// Records the balance and roll changes of a final execution output
+    pub fn register_final_output(&mut self, exec_out: &ExecutionOutput) {
+        if self.max_slots == 0 {
+            return;
+        }
+        let mut changes: PreHashMap<Address, AddressHistoryEntry> = PreHashMap::default();
+        for (addr, change) in exec_out.state_changes.ledger_changes.0.iter() {
+            let balance = match change {
+                SetUpdateOrDelete::Set(entry) => entry.balance,
+                SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                    balance: SetOrKeep::Set(balance),
+                    ..
+                }) => *balance,
+                SetUpdateOrDelete::Update(_) => continue,
+                SetUpdateOrDelete::Delete => Amount::zero(),
+            };
+            changes
+                .entry(*addr)
+                .or_insert_with(|| AddressHistoryEntry::new(exec_out.slot))
+                .balance = Some(balance);
+        }
+        for (addr, roll_count) in exec_out.state_changes.pos_changes.roll_changes.iter() {
+            changes
+                .entry(*addr)
+                .or_insert_with(|| AddressHistoryEntry::new(exec_out.slot))
+                .roll_count = Some(*roll_count);
+        }
+        self.slots.push_back((exec_out.slot, changes));
+        while self.slots.len() > self.max_slots {
+            self.slots.pop_front();
+        }
+    }
+
+    /// Gets the changes of an address between `from` and `to` (both included), oldest first
+    pub fn get_address_history(
+        &self,
+        address: &Address,
+        from: Option<Slot>,
+        to: Option<Slot>,
+    ) -> Vec<AddressHistoryEntry> {
+        self.slots
+            .iter()
+            .filter(|(slot, _)| from.map_or(true, |from| *slot >= from))
+            .take_while(|(slot, _)| to.map_or(true, |to| *slot <= to))
+            .filter_map(|(_, changes)| changes.get(address).cloned())
+            .collect()
+    }
+}
+
+#[test]
+fn test_address_history_retention() {
+    use massa_ledger_exports::LedgerEntry;
+    use std::str::FromStr;
+
+    let address =
+        Address::from_str("A12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let mut history = AddressHistory::new(2);
+    for period in 1..=3 {
+        let mut exec_out = ExecutionOutput {
+            slot: Slot::new(period, 0),
+            block_id: None,
+            state_changes: Default::default(),
+            events: Default::default(),
+            execution_trace: Default::default(),
+            transfers: Default::default(),
+        };
+        exec_out.state_changes.ledger_changes.0.insert(
+            address,
+            SetUpdateOrDelete::Set(LedgerEntry {
+                balance: Amount::from_str(&period.to_string()).unwrap(),
+                ..Default::default()
+            }),
+        );
+        history.register_final_output(&exec_out);
+    }
+
+    // only the last two slots are kept
+    let entries = history.get_address_history(&address, None, None);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].slot, Slot::new(2, 0));
+    assert_eq!(entries[1].balance, Some(Amount::from_str("3").unwrap()));
+    assert_eq!(entries[1].roll_count, None);
+
+    // slot bounds are included
+    let entries = history.get_address_history(&address, Some(Slot::new(3, 0)), None);
+    assert_eq!(entries.len(), 1);
+    let entries = history.get_address_history(&address, None, Some(Slot::new(2, 0)));
+    assert_eq!(entries.len(), 1);
+}
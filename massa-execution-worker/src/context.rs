@@ -14,15 +14,16 @@ use crate::{active_history::ActiveHistory, speculative_roll_state::SpeculativeRo
 use massa_async_pool::{AsyncMessage, AsyncMessageId};
 use massa_executed_ops::ExecutedOpsChanges;
 use massa_execution_exports::{
-    EventStore, ExecutionConfig, ExecutionError, ExecutionOutput, ExecutionStackElement,
+    EventStore, ExecutionConfig, ExecutionError, ExecutionOutput, ExecutionStackElement, Transfer,
 };
 use massa_final_state::{FinalState, StateChanges};
 use massa_ledger_exports::LedgerChanges;
 use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::{
-    address::Address,
+    address::{Address, AddressCategory},
     amount::Amount,
     block::BlockId,
+    execution::ReadOnlyCallTraceEntry,
     operation::OperationId,
     output_event::{EventExecutionContext, SCOutputEvent},
     slot::Slot,
@@ -115,9 +116,15 @@ pub struct ExecutionContext {
     /// True if it's a read-only context
     pub read_only: bool,
 
+    /// calls made between smart contracts, only recorded in read-only contexts
+    pub call_trace: Vec<ReadOnlyCallTraceEntry>,
+
     /// generated events during this execution, with multiple indexes
     pub events: EventStore,
 
+    /// coins transferred by the operations executed so far in the slot
+    pub transfers: Vec<Transfer>,
+
     /// Unsafe random state (can be predicted and manipulated)
     pub unsafe_rng: Xoshiro256PlusPlus,
 
@@ -170,7 +177,9 @@ impl ExecutionContext {
             opt_block_id: Default::default(),
             stack: Default::default(),
             read_only: Default::default(),
+            call_trace: Default::default(),
             events: Default::default(),
+            transfers: Default::default(),
             unsafe_rng: Xoshiro256PlusPlus::from_seed([0u8; 32]),
             creator_address: Default::default(),
             origin_operation_id: Default::default(),
@@ -224,10 +233,11 @@ impl ExecutionContext {
 
         // Emit the error event.
         // Note that the context event counter is properly handled by event_emit (see doc).
-        self.event_emit(self.event_create(
-            serde_json::json!({ "massa_execution_error": format!("{}", error) }).to_string(),
-            true,
-        ));
+        let error_data = serde_json::json!({
+            "massa_execution_error": format!("{}", error),
+            "massa_execution_error_code": error.code(),
+        });
+        self.event_emit(self.event_create(error_data.to_string(), true));
     }
 
     /// Create a new `ExecutionContext` for read-only execution
@@ -380,15 +390,8 @@ impl ExecutionContext {
 
     /// Creates a new smart contract address with initial bytecode, and returns this address
     pub fn create_new_sc_address(&mut self, bytecode: Vec<u8>) -> Result<Address, ExecutionError> {
-        // TODO: collision problem:
-        //  prefix addresses to know if they are SCs or normal,
-        //  otherwise people can already create new accounts by sending coins to the right hash
-        //  they won't have ownership over it but this can still be unexpected
-        //  to have initial extra coins when an address is created
-        //  It may also induce that for read-only calls.
-        //  https://github.com/massalabs/massa/issues/2331
-
         // deterministically generate a new unique smart contract address
+        // (SC addresses have their own category so they cannot collide with user addresses)
 
         // create a seed from the current slot
         let mut data: Vec<u8> = self.slot.to_bytes_key().to_vec();
@@ -402,7 +405,10 @@ impl ExecutionContext {
             data.push(1u8);
         }
         // hash the seed to get a unique address
-        let address = Address(massa_hash::Hash::compute_from(&data));
+        let address = Address::new(
+            AddressCategory::SCAddress,
+            massa_hash::Hash::compute_from(&data),
+        );
 
         // add this address with its bytecode to the speculative ledger
         self.speculative_ledger.create_new_sc_address(
@@ -724,6 +730,8 @@ impl ExecutionContext {
             block_id: std::mem::take(&mut self.opt_block_id),
             state_changes,
             events: std::mem::take(&mut self.events),
+            execution_trace: Default::default(),
+            transfers: std::mem::take(&mut self.transfers),
         }
     }
 
@@ -6,14 +6,20 @@
 use crate::execution::ExecutionState;
 use crate::request_queue::{RequestQueue, RequestWithResponseSender};
 use massa_execution_exports::{
-    ExecutionAddressInfo, ExecutionConfig, ExecutionController, ExecutionError, ExecutionManager,
+    BytecodeInfo, DatastoreEntryDiff, ExecutionAddressBytecode, ExecutionAddressInfo,
+    ExecutionConfig, ExecutionController, ExecutionError, ExecutionManager,
     ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
 };
 use massa_models::api::EventFilter;
+use massa_models::ledger_proof::FinalLedgerEntryProof;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::stats::ExecutionStats;
-use massa_models::{address::Address, amount::Amount, operation::OperationId};
+use massa_models::{
+    address::{Address, AddressHistoryEntry, AddressTransfer},
+    amount::Amount,
+    operation::OperationId,
+};
 use massa_models::{block::BlockId, slot::Slot};
 use massa_storage::Storage;
 use parking_lot::{Condvar, Mutex, RwLock};
@@ -169,6 +175,60 @@ impl ExecutionController for ExecutionControllerImpl {
         result
     }
 
+    /// Get the final and candidate bytecode deployed at an address, with its hash and size
+    fn get_bytecode(&self, address: &Address) -> ExecutionAddressBytecode {
+        let (final_bytecode, candidate_bytecode) = self
+            .execution_state
+            .read()
+            .get_final_and_candidate_bytecode(address);
+        ExecutionAddressBytecode {
+            final_bytecode: final_bytecode.map(BytecodeInfo::new),
+            candidate_bytecode: candidate_bytecode.map(BytecodeInfo::new),
+        }
+    }
+
+    /// Get the datastore entries of an address whose candidate value differs from the final one
+    fn get_datastore_diff(&self, address: &Address) -> Vec<DatastoreEntryDiff> {
+        self.execution_state.read().get_datastore_diff(address)
+    }
+
+    /// Get the proofs of final ledger entries against the final state hash
+    fn get_final_ledger_entry_proofs(
+        &self,
+        input: Vec<(Address, Option<Vec<u8>>)>,
+    ) -> Vec<FinalLedgerEntryProof> {
+        self.execution_state
+            .read()
+            .get_final_ledger_entry_proofs(input)
+    }
+
+    /// Get a page of the final and candidate datastore keys of an address
+    fn get_ledger_datastore_keys(
+        &self,
+        address: &Address,
+        prefix: &[u8],
+        start_key: Option<&[u8]>,
+        limit: usize,
+    ) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        self.execution_state
+            .read()
+            .get_final_and_candidate_datastore_keys_page(address, prefix, start_key, limit)
+    }
+
+    /// Check whether a batch of addresses have a non-empty final and candidate bytecode
+    fn has_bytecode(&self, addresses: &[Address]) -> Vec<(bool, bool)> {
+        let lock = self.execution_state.read();
+        let mut result = Vec::with_capacity(addresses.len());
+        for addr in addresses {
+            let (final_bytecode, candidate_bytecode) = lock.get_final_and_candidate_bytecode(addr);
+            result.push((
+                final_bytecode.map_or(false, |bytecode| !bytecode.is_empty()),
+                candidate_bytecode.map_or(false, |bytecode| !bytecode.is_empty()),
+            ));
+        }
+        result
+    }
+
     /// Return the active rolls distribution for the given `cycle`
     fn get_cycle_active_rolls(&self, cycle: u64) -> BTreeMap<Address, u64> {
         self.execution_state.read().get_cycle_active_rolls(cycle)
@@ -251,6 +311,30 @@ impl ExecutionController for ExecutionControllerImpl {
         res
     }
 
+    /// Gets the balance and roll changes of an address in the recent final slots
+    fn get_address_history(
+        &self,
+        address: &Address,
+        from: Option<Slot>,
+        to: Option<Slot>,
+    ) -> Vec<AddressHistoryEntry> {
+        self.execution_state
+            .read()
+            .get_address_history(address, from, to)
+    }
+
+    /// Gets a page of the coins sent and received by an address in the recent final slots
+    fn get_transactions_by_address(
+        &self,
+        address: &Address,
+        offset: usize,
+        limit: usize,
+    ) -> (usize, Vec<AddressTransfer>) {
+        self.execution_state
+            .read()
+            .get_transactions_by_address(address, offset, limit)
+    }
+
     /// Get execution statistics
     fn get_stats(&self) -> ExecutionStats {
         self.execution_state.read().get_stats()
@@ -9,21 +9,28 @@
 //! * the output of the execution is extracted from the context
 
 use crate::active_history::{ActiveHistory, HistorySearchResult};
+use crate::address_history::AddressHistory;
 use crate::context::ExecutionContext;
+use crate::final_event_store::FinalEventStore;
 use crate::interface_impl::InterfaceImpl;
 use crate::stats::ExecutionStatsCounter;
+use crate::supply_checker::TotalSupplyChecker;
+use crate::transfer_history::TransferHistory;
 use massa_async_pool::AsyncMessage;
 use massa_execution_exports::{
-    EventStore, ExecutionConfig, ExecutionError, ExecutionOutput, ExecutionStackElement,
-    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    DatastoreEntryDiff, ExecutionChannels, ExecutionConfig, ExecutionError, ExecutionOutput,
+    ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget, Transfer,
 };
-use massa_final_state::FinalState;
+use massa_final_state::{ExecutionTrace, FinalState, SlotTrace, TraceWriter};
 use massa_ledger_exports::{SetOrDelete, SetUpdateOrDelete};
-use massa_models::address::ExecutionAddressCycleInfo;
+use massa_models::address::{AddressHistoryEntry, AddressTransfer, ExecutionAddressCycleInfo};
 use massa_models::api::EventFilter;
-use massa_models::output_event::SCOutputEvent;
+use massa_models::execution::{ExecutionReorg, ReorgAffectedOperation, SlotExecutionStatus};
+use massa_models::ledger_proof::FinalLedgerEntryProof;
+use massa_models::output_event::{EventExecutionContext, SCOutputEvent};
 use massa_models::prehash::PreHashSet;
-use massa_models::stats::ExecutionStats;
+use massa_models::stats::{ExecutionStats, OperationStage};
 use massa_models::{
     address::Address,
     block::BlockId,
@@ -34,8 +41,9 @@ use massa_pos_exports::SelectorController;
 use massa_sc_runtime::Interface;
 use massa_storage::Storage;
 use parking_lot::{Mutex, RwLock};
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::sync::Arc;
+use std::time::Instant;
 use tracing::{debug, info, warn};
 
 /// Used to acquire a lock on the execution context
@@ -45,6 +53,20 @@ macro_rules! context_guard {
     };
 }
 
+/// Records the time it took for the operations of an executed block to reach the given stages since their reception
+fn record_operation_latencies(exec_target: Option<&(BlockId, Storage)>, stages: &[OperationStage]) {
+    let Some((block_id, block_store)) = exec_target else {
+        return;
+    };
+    let op_ids = match block_store.read_blocks().get(block_id) {
+        Some(block) => block.content.operations.clone(),
+        None => return,
+    };
+    for stage in stages {
+        block_store.record_operation_stage(&op_ids, *stage);
+    }
+}
+
 /// Structure holding consistent speculative and final execution states,
 /// and allowing access to them.
 pub(crate) struct ExecutionState {
@@ -60,8 +82,12 @@ pub(crate) struct ExecutionState {
     pub active_cursor: Slot,
     // a cursor pointing to the highest executed final slot
     pub final_cursor: Slot,
-    // store containing execution events that became final
-    final_events: EventStore,
+    // indexed store containing execution events that became final
+    final_events: FinalEventStore,
+    // balance and roll changes of the addresses in recent final slots
+    address_history: AddressHistory,
+    // coins sent and received by the addresses in recent final slots
+    transfer_history: TransferHistory,
     // final state with atomic R/W access
     final_state: Arc<RwLock<FinalState>>,
     // execution context (see documentation in context.rs)
@@ -70,6 +96,12 @@ pub(crate) struct ExecutionState {
     execution_interface: Box<dyn Interface>,
     // execution statistics
     stats_counter: ExecutionStatsCounter,
+    // total supply invariant checker, run at each cycle boundary
+    supply_checker: TotalSupplyChecker,
+    // writer of the final slot traces, if enabled
+    trace_writer: Option<TraceWriter>,
+    // channels used to broadcast the slot execution outputs
+    channels: ExecutionChannels,
 }
 
 impl ExecutionState {
@@ -78,10 +110,15 @@ impl ExecutionState {
     /// # Arguments
     /// * `config`: execution configuration
     /// * `final_state`: atomic access to the final state
+    /// * `channels`: channels used to broadcast the slot execution outputs
     ///
     /// # returns
     /// A new `ExecutionState`
-    pub fn new(config: ExecutionConfig, final_state: Arc<RwLock<FinalState>>) -> ExecutionState {
+    pub fn new(
+        config: ExecutionConfig,
+        final_state: Arc<RwLock<FinalState>>,
+        channels: ExecutionChannels,
+    ) -> ExecutionState {
         // Get the slot at the output of which the final state is attached.
         // This should be among the latest final slots.
         let last_final_slot = final_state.read().slot;
@@ -102,6 +139,18 @@ impl ExecutionState {
             execution_context.clone(),
         ));
 
+        // open the trace file if enabled
+        let trace_writer = config.trace_path.as_ref().and_then(|path| {
+            TraceWriter::open(path)
+                .map_err(|err| {
+                    warn!(
+                        "could not open the execution trace, tracing disabled: {}",
+                        err
+                    )
+                })
+                .ok()
+        });
+
         // build the execution state
         ExecutionState {
             final_state,
@@ -110,18 +159,31 @@ impl ExecutionState {
             // empty execution output history: it is not recovered through bootstrap
             active_history,
             // empty final event store: it is not recovered through bootstrap
-            final_events: Default::default(),
+            final_events: FinalEventStore::new(
+                config.max_final_events,
+                config.final_events_retention_periods,
+            ),
+            // empty address history: it is not recovered through bootstrap
+            address_history: AddressHistory::new(config.max_address_history_slots),
+            transfer_history: TransferHistory::new(config.max_transfer_history_slots),
             // no active slots executed yet: set active_cursor to the last final block
             active_cursor: last_final_slot,
             final_cursor: last_final_slot,
             stats_counter: ExecutionStatsCounter::new(config.stats_time_window_duration),
+            supply_checker: TotalSupplyChecker::new(),
+            trace_writer,
+            channels,
             config,
         }
     }
 
     /// Get execution statistics
     pub fn get_stats(&self) -> ExecutionStats {
-        self.stats_counter.get_stats(self.active_cursor)
+        self.stats_counter.get_stats(
+            self.active_cursor,
+            self.final_cursor,
+            self.final_state.read().final_state_hash,
+        )
     }
 
     /// Applies the output of an execution to the final execution state.
@@ -134,18 +196,53 @@ impl ExecutionState {
             panic!("attempting to apply a final execution output at or before the current final_cursor");
         }
 
+        if self.config.broadcast_enabled {
+            let _slot_execution_output_receivers_count = self
+                .channels
+                .slot_execution_output_sender
+                .send(exec_out.summarize(SlotExecutionStatus::Final));
+        }
+
         // count stats
         if exec_out.block_id.is_some() {
             self.stats_counter.register_final_blocks(1);
             self.stats_counter.register_final_executed_operations(
                 exec_out.state_changes.executed_ops_changes.len(),
             );
+            self.supply_checker
+                .register_block_reward(self.config.block_reward);
         }
 
+        // index the balance and roll changes of the addresses
+        self.address_history.register_final_output(&exec_out);
+
+        // index the coins sent and received by the addresses
+        self.transfer_history.register_final_output(&exec_out);
+
+        // keep a copy of the state changes for the trace
+        let traced_changes = self
+            .trace_writer
+            .as_ref()
+            .map(|_| exec_out.state_changes.clone());
+
         // apply state changes to the final ledger
         self.final_state
             .write()
-            .finalize(exec_out.slot, exec_out.state_changes);
+            .finalize(exec_out.slot, std::mem::take(&mut exec_out.state_changes));
+
+        // append the trace of the slot, along with the resulting final state hashes
+        if let (Some(writer), Some(state_changes)) = (self.trace_writer.as_mut(), traced_changes) {
+            let trace = SlotTrace {
+                slot: exec_out.slot,
+                block_id: exec_out.block_id,
+                execution: std::mem::take(&mut exec_out.execution_trace),
+                state_changes,
+                hashes: self.final_state.read().get_state_hashes(),
+            };
+            if let Err(err) = writer.append(&trace) {
+                warn!("could not trace final slot {}: {}", exec_out.slot, err);
+            }
+        }
 
         // update the final ledger's slot
         self.final_cursor = exec_out.slot;
@@ -156,10 +253,64 @@ impl ExecutionState {
             self.active_cursor = self.final_cursor;
         }
 
+        // check the total supply at the end of each cycle
+        if exec_out
+            .slot
+            .is_last_of_cycle(self.config.periods_per_cycle, self.config.thread_count)
+        {
+            self.check_total_supply(&mut exec_out);
+        }
+
         // append generated events to the final event store
         exec_out.events.finalize();
-        self.final_events.extend(exec_out.events);
-        self.final_events.prune(self.config.max_final_events);
+        self.final_events.extend(exec_out.events, exec_out.slot);
+    }
+
+    /// Gets the total supply of the final state:
+    /// ledger balances, deferred credits, value of the rolls and coins held by asynchronous messages
+    fn get_final_total_supply(&self) -> Amount {
+        let final_state = self.final_state.read();
+        let rolls_value = self
+            .config
+            .roll_price
+            .checked_mul_u64(final_state.pos_state.get_total_rolls())
+            .unwrap_or(Amount::MAX);
+        final_state
+            .ledger
+            .get_total_balance()
+            .saturating_add(final_state.pos_state.deferred_credits.get_total_amount())
+            .saturating_add(rolls_value)
+            .saturating_add(final_state.async_pool.get_total_coins())
+    }
+
+    /// Checks that the final total supply did not grow more than the emission schedule allows
+    /// since the last check, and emits an error event in `exec_out` if it did.
+    fn check_total_supply(&mut self, exec_out: &mut ExecutionOutput) {
+        let total_supply = self.get_final_total_supply();
+        debug!(
+            "total supply at the end of slot {}: {}",
+            exec_out.slot, total_supply
+        );
+        if let Some(expected_total_supply) = self.supply_checker.check(total_supply) {
+            let message = format!(
+                "total supply mismatch at the end of slot {}: expected at most {} but found {}",
+                exec_out.slot, expected_total_supply, total_supply
+            );
+            warn!("{}", message);
+            exec_out.events.push(SCOutputEvent {
+                context: EventExecutionContext {
+                    slot: exec_out.slot,
+                    block: exec_out.block_id,
+                    read_only: false,
+                    index_in_slot: exec_out.events.0.len() as u64,
+                    call_stack: VecDeque::new(),
+                    origin_operation_id: None,
+                    is_final: true,
+                    is_error: true,
+                },
+                data: message,
+            });
+        }
     }
 
     /// Applies an execution output to the active (non-final) state
@@ -303,23 +454,47 @@ impl ExecutionState {
             }
         };
 
+        // coins sent by the operation to another address, if any
+        let transferred = match &operation.content.op {
+            OperationType::Transaction {
+                recipient_address,
+                amount,
+            } => Some((*recipient_address, *amount)),
+            OperationType::CallSC {
+                target_addr, coins, ..
+            } if !coins.is_zero() => Some((*target_addr, *coins)),
+            _ => None,
+        };
+
         {
             // lock execution context
             let mut context = context_guard!(self);
 
             // check execution results
+            let success = execution_result.is_ok();
             match execution_result {
                 Ok(_) => {}
                 Err(err) => {
                     // an error occurred: emit error event and reset context to snapshot
-                    let err = ExecutionError::RuntimeError(format!(
-                        "runtime error when executing operation {}: {}",
-                        operation_id, &err
+                    let err = err.with_context(format!(
+                        "runtime error when executing operation {}",
+                        operation_id
                     ));
                     debug!("{}", &err);
                     context.reset_to_snapshot(context_snapshot, err);
                 }
             }
+
+            // record the transfer after the reset so that failed transfers are kept too
+            if let Some((to, amount)) = transferred {
+                context.transfers.push(Transfer {
+                    operation_id,
+                    from: sender_addr,
+                    to,
+                    amount,
+                    success,
+                });
+            }
         }
 
         Ok(())
@@ -354,6 +529,14 @@ impl ExecutionState {
             operation_datastore: None,
         }];
 
+        // only user addresses can stake
+        if !seller_addr.is_user() {
+            return Err(ExecutionError::AddressCategoryError(format!(
+                "{} failed to sell {} rolls: only user addresses can hold rolls",
+                seller_addr, roll_count
+            )));
+        }
+
         // try to sell the rolls
         if let Err(err) = context.try_sell_rolls(&seller_addr, *roll_count) {
             return Err(ExecutionError::RollSellError(format!(
@@ -393,6 +576,14 @@ impl ExecutionState {
             operation_datastore: None,
         }];
 
+        // only user addresses can stake
+        if !buyer_addr.is_user() {
+            return Err(ExecutionError::AddressCategoryError(format!(
+                "{} failed to buy {} rolls: only user addresses can hold rolls",
+                buyer_addr, roll_count
+            )));
+        }
+
         // compute the amount of coins to spend
         let spend_coins = match self.config.roll_price.checked_mul_u64(*roll_count) {
             Some(v) => v,
@@ -406,10 +597,11 @@ impl ExecutionState {
 
         // spend `roll_price` * `roll_count` coins from the buyer
         if let Err(err) = context.transfer_coins(Some(buyer_addr), None, spend_coins, false) {
-            return Err(ExecutionError::RollBuyError(format!(
-                "{} failed to buy {} rolls: {}",
-                buyer_addr, roll_count, err
-            )));
+            let msg = format!("{} failed to buy {} rolls", buyer_addr, roll_count);
+            return Err(match err {
+                ExecutionError::InsufficientBalance(_) => err.with_context(msg),
+                err => ExecutionError::RollBuyError(format!("{}: {}", msg, err)),
+            });
         }
 
         // add rolls to the buyer withing the context
@@ -455,10 +647,14 @@ impl ExecutionState {
         if let Err(err) =
             context.transfer_coins(Some(sender_addr), Some(*recipient_address), *amount, false)
         {
-            return Err(ExecutionError::TransactionError(format!(
-                "transfer of {} coins from {} to {} failed: {}",
-                amount, sender_addr, recipient_address, err
-            )));
+            let msg = format!(
+                "transfer of {} coins from {} to {} failed",
+                amount, sender_addr, recipient_address
+            );
+            return Err(match err {
+                ExecutionError::InsufficientBalance(_) => err.with_context(msg),
+                err => ExecutionError::TransactionError(format!("{}: {}", msg, err)),
+            });
         }
 
         Ok(())
@@ -512,10 +708,10 @@ impl ExecutionState {
             Ok(_response) => {}
             Err(err) => {
                 // there was an error during bytecode execution
-                return Err(ExecutionError::RuntimeError(format!(
-                    "bytecode execution error: {}",
-                    err
-                )));
+                return Err(ExecutionError::from_vm_error(
+                    "bytecode execution error",
+                    err,
+                ));
             }
         }
 
@@ -525,6 +721,10 @@ impl ExecutionState {
     /// Execute an operation of type `CallSC`
     /// Will panic if called with another operation type
     ///
+    /// Fails with `AddressCategoryError` if the target is not a smart contract address,
+    /// and with `TargetNotFound` if the target holds no bytecode or an empty one,
+    /// before any coin is transferred.
+    ///
     /// # Arguments
     /// * `operation`: the `WrappedOperation` to process, must be an `CallSC`
     /// * `block_creator_addr`: address of the block creator
@@ -571,19 +771,38 @@ impl ExecutionState {
                 },
             ];
 
+            // only smart contracts can be called
+            if !target_addr.is_sc() {
+                return Err(ExecutionError::AddressCategoryError(format!(
+                    "target address {} is not a smart contract address",
+                    target_addr
+                )));
+            }
+
+            // Load bytecode, the target must be a smart contract with bytecode
+            bytecode = match context.get_bytecode(&target_addr) {
+                Some(bytecode) if !bytecode.is_empty() => bytecode,
+                _ => {
+                    return Err(ExecutionError::TargetNotFound(format!(
+                        "no smart contract bytecode found at target address {}",
+                        target_addr
+                    )))
+                }
+            };
+
             // Debit the sender's balance with the coins to transfer
             if let Err(err) = context.transfer_coins(Some(sender_addr), None, coins, false) {
-                return Err(ExecutionError::RuntimeError(format!(
-                    "failed to debit operation sender {} with {} operation coins: {}",
-                    sender_addr, coins, err
+                return Err(err.with_context(format!(
+                    "failed to debit operation sender {} with {} operation coins",
+                    sender_addr, coins
                 )));
             }
 
             // Credit the operation target with coins.
             if let Err(err) = context.transfer_coins(None, Some(target_addr), coins, false) {
-                return Err(ExecutionError::RuntimeError(format!(
-                    "failed to credit operation target {} with {} operation coins: {}",
-                    target_addr, coins, err
+                return Err(err.with_context(format!(
+                    "failed to credit operation target {} with {} operation coins",
+                    target_addr, coins
                 )));
             }
 
@@ -591,9 +810,6 @@ impl ExecutionState {
             if target_func.is_empty() {
                 return Ok(());
             }
-
-            // Load bytecode. Assume empty bytecode if not found.
-            bytecode = context.get_bytecode(&target_addr).unwrap_or_default();
         }
 
         // run the VM on the bytecode loaded from the target address
@@ -608,10 +824,10 @@ impl ExecutionState {
             Ok(_response) => {}
             Err(err) => {
                 // there was an error during bytecode execution
-                return Err(ExecutionError::RuntimeError(format!(
-                    "bytecode execution error: {}",
-                    err
-                )));
+                return Err(ExecutionError::from_vm_error(
+                    "bytecode execution error",
+                    err,
+                ));
             }
         }
 
@@ -657,7 +873,7 @@ impl ExecutionState {
                 Some(bc) => bc,
                 bc => {
                     let err = if bc.is_none() {
-                        ExecutionError::RuntimeError("no target bytecode found".into())
+                        ExecutionError::BytecodeMissing("no target bytecode found".into())
                     } else {
                         ExecutionError::RuntimeError(
                             "message data does not convert to utf-8".into(),
@@ -674,10 +890,7 @@ impl ExecutionState {
                 context.transfer_coins(None, Some(message.destination), message.coins, false)
             {
                 // coin crediting failed: reset context to snapshot and reimburse sender
-                let err = ExecutionError::RuntimeError(format!(
-                    "could not credit coins to target of async execution: {}",
-                    err
-                ));
+                let err = err.with_context("could not credit coins to target of async execution");
                 context.reset_to_snapshot(context_snapshot, err.clone());
                 context.cancel_async_message(&message);
                 return Err(err);
@@ -696,10 +909,7 @@ impl ExecutionState {
             self.config.gas_costs.clone(),
         ) {
             // execution failed: reset context to snapshot and reimburse sender
-            let err = ExecutionError::RuntimeError(format!(
-                "async message runtime execution error: {}",
-                err
-            ));
+            let err = ExecutionError::from_vm_error("async message runtime execution error", err);
             let mut context = context_guard!(self);
             context.reset_to_snapshot(context_snapshot, err.clone());
             context.cancel_async_message(&message);
@@ -725,6 +935,8 @@ impl ExecutionState {
         exec_target: Option<&(BlockId, Storage)>,
         selector: Box<dyn SelectorController>,
     ) -> ExecutionOutput {
+        let execution_start = Instant::now();
+
         // Create a new execution context for the whole active slot
         let mut execution_context = ExecutionContext::active_slot(
             self.config.clone(),
@@ -740,9 +952,14 @@ impl ExecutionState {
         // Apply the created execution context for slot execution
         *context_guard!(self) = execution_context;
 
+        // Executed operations and asynchronous messages, in execution order
+        let mut execution_trace = ExecutionTrace::default();
+
         // Try executing asynchronous messages.
         // Effects are cancelled on failure and the sender is reimbursed.
         for (opt_bytecode, message) in messages {
+            execution_trace.executed_messages.push(message.compute_id());
+            execution_trace.gas_used = execution_trace.gas_used.saturating_add(message.max_gas);
             if let Err(err) = self.execute_async_message(message, opt_bytecode) {
                 debug!("failed executing async message: {}", err);
             }
@@ -806,19 +1023,32 @@ impl ExecutionState {
             // Try executing the operations of this block in the order in which they appear in the block.
             // Errors are logged but do not interrupt the execution of the slot.
             for operation in operations.into_iter() {
-                if let Err(err) = self.execute_operation(
+                match self.execute_operation(
                     &operation,
                     stored_block.content.header.content.slot,
                     &mut remaining_block_gas,
                     &mut block_credits,
                 ) {
-                    debug!(
+                    Ok(()) => execution_trace.executed_ops.push(operation.id),
+                    Err(err) => debug!(
                         "failed executing operation {} in block {}: {}",
                         operation.id, block_id, err
-                    );
+                    ),
                 }
             }
 
+            let block_gas_used = self
+                .config
+                .max_gas_per_block
+                .saturating_sub(remaining_block_gas);
+            execution_trace.gas_used = execution_trace.gas_used.saturating_add(block_gas_used);
+            massa_metrics::observe_histogram(
+                "massa_execution_block_gas_used",
+                &[],
+                massa_metrics::GAS_BUCKETS,
+                block_gas_used as f64,
+            );
+
             // Get block creator address
             let block_creator_addr = stored_block.creator_address;
 
@@ -892,7 +1122,39 @@ impl ExecutionState {
         }
 
         // Finish slot and return the execution output
-        context_guard!(self).settle_slot()
+        let mut exec_out = context_guard!(self).settle_slot();
+        exec_out.execution_trace = execution_trace;
+        massa_metrics::observe_histogram(
+            "massa_execution_slot_duration_seconds",
+            &[],
+            massa_metrics::DURATION_BUCKETS,
+            execution_start.elapsed().as_secs_f64(),
+        );
+        exec_out
+    }
+
+    /// Notify the subscribers of the execution reorgs that candidate slot executions were cancelled
+    fn notify_reorg(&self, cancelled_outputs: &[ExecutionOutput]) {
+        if !self.config.broadcast_enabled || cancelled_outputs.is_empty() {
+            return;
+        }
+        let reorg = ExecutionReorg {
+            cancelled_slots: cancelled_outputs.iter().map(|output| output.slot).collect(),
+            affected_operations: cancelled_outputs
+                .iter()
+                .flat_map(|output| {
+                    output
+                        .execution_trace
+                        .executed_ops
+                        .iter()
+                        .map(|operation_id| ReorgAffectedOperation {
+                            operation_id: *operation_id,
+                            slot: output.slot,
+                        })
+                })
+                .collect(),
+        };
+        let _execution_reorg_receivers_count = self.channels.execution_reorg_sender.send(reorg);
     }
 
     /// Execute a candidate slot
@@ -921,17 +1183,28 @@ impl ExecutionState {
                 "execute_candidate_slot: truncating down from slot {}",
                 self.active_cursor
             );
-            self.active_history
+            let cancelled_outputs = self
+                .active_history
                 .write()
                 .truncate_from(slot, self.config.thread_count);
+            self.notify_reorg(&cancelled_outputs);
             self.active_cursor = slot
                 .get_prev_slot(self.config.thread_count)
                 .expect("overflow when iterating on slots");
         }
 
+        record_operation_latencies(exec_target, &[OperationStage::BlockInclusion]);
+
         let exec_out = self.execute_slot(slot, exec_target, selector);
         debug!("execute_candidate_slot: execution finished");
 
+        if self.config.broadcast_enabled {
+            let _slot_execution_output_receivers_count = self
+                .channels
+                .slot_execution_output_sender
+                .send(exec_out.summarize(SlotExecutionStatus::Candidate));
+        }
+
         // apply execution output to active state
         self.apply_active_execution_output(exec_out);
         debug!("execute_candidate_slot: execution state applied");
@@ -958,8 +1231,17 @@ impl ExecutionState {
             return;
         }
 
+        record_operation_latencies(
+            exec_target,
+            &[
+                OperationStage::BlockInclusion,
+                OperationStage::FinalExecution,
+            ],
+        );
+
         // check if the final slot execution result is already cached at the front of the speculative execution history
         let first_exec_output = self.active_history.write().0.pop_front();
+        let mut cancelled_outputs = Vec::new();
         if let Some(exec_out) = first_exec_output {
             if &exec_out.slot == slot && exec_out.block_id == target_id {
                 // speculative execution front result matches what we want to compute
@@ -975,6 +1257,7 @@ impl ExecutionState {
                     "speculative execution cache mismatch (final slot={}/block={:?}, front speculative slot={}/block={:?}). Resetting the cache.",
                     slot, target_id, exec_out.slot, exec_out.block_id
                 );
+                cancelled_outputs.push(exec_out);
             }
         } else {
             // cache entry absent
@@ -985,7 +1268,8 @@ impl ExecutionState {
         }
 
         // truncate the whole execution queue
-        self.active_history.write().0.clear();
+        cancelled_outputs.extend(self.active_history.write().0.drain(..));
+        self.notify_reorg(&cancelled_outputs);
         self.active_cursor = self.final_cursor;
 
         // execute slot
@@ -1052,7 +1336,7 @@ impl ExecutionState {
                     &*self.execution_interface,
                     self.config.gas_costs.clone(),
                 )
-                .map_err(|err| ExecutionError::RuntimeError(err.to_string()))?
+                .map_err(|err| ExecutionError::from_vm_error("bytecode execution error", err))?
             }
             ReadOnlyExecutionTarget::FunctionCall {
                 target_addr,
@@ -1076,16 +1360,19 @@ impl ExecutionState {
                     &*self.execution_interface,
                     self.config.gas_costs.clone(),
                 )
-                .map_err(|err| ExecutionError::RuntimeError(err.to_string()))?
+                .map_err(|err| ExecutionError::from_vm_error("bytecode execution error", err))?
             }
         };
 
         // return the execution output
-        let execution_output = context_guard!(self).settle_slot();
+        let mut context = context_guard!(self);
+        let call_trace = std::mem::take(&mut context.call_trace);
+        let execution_output = context.settle_slot();
         Ok(ReadOnlyExecutionOutput {
             out: execution_output,
             gas_cost: req.max_gas.saturating_sub(exec_response.remaining_gas),
             call_result: exec_response.ret,
+            call_trace,
         })
     }
 
@@ -1106,6 +1393,23 @@ impl ExecutionState {
         )
     }
 
+    /// Gets the bytecode of an address both at the latest final and active executed slots
+    pub fn get_final_and_candidate_bytecode(
+        &self,
+        address: &Address,
+    ) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+        let final_bytecode = self.final_state.read().ledger.get_bytecode(address);
+        let search_result = self.active_history.read().fetch_bytecode(address);
+        (
+            final_bytecode.clone(),
+            match search_result {
+                HistorySearchResult::Present(active_bytecode) => Some(active_bytecode),
+                HistorySearchResult::NoInfo => final_bytecode,
+                HistorySearchResult::Absent => None,
+            },
+        )
+    }
+
     /// Gets roll counts both at the latest final and active executed slots
     pub fn get_final_and_candidate_rolls(&self, address: &Address) -> (u64, u64) {
         let final_rolls = self.final_state.read().pos_state.get_rolls_for(address);
@@ -1117,6 +1421,28 @@ impl ExecutionState {
         (final_rolls, active_rolls)
     }
 
+    /// Gets the balance and roll changes of an address in the recent final slots
+    /// between `from` and `to` (both included)
+    pub fn get_address_history(
+        &self,
+        address: &Address,
+        from: Option<Slot>,
+        to: Option<Slot>,
+    ) -> Vec<AddressHistoryEntry> {
+        self.address_history.get_address_history(address, from, to)
+    }
+
+    /// Gets a page of the coins sent and received by an address in the recent final slots,
+    /// newest first, along with the total number of transfers kept for that address
+    pub fn get_transactions_by_address(
+        &self,
+        address: &Address,
+        offset: usize,
+        limit: usize,
+    ) -> (usize, Vec<AddressTransfer>) {
+        self.transfer_history.get_transfers(address, offset, limit)
+    }
+
     /// Gets a data entry both at the latest final and active executed slots
     pub fn get_final_and_active_data_entry(
         &self,
@@ -1184,6 +1510,179 @@ impl ExecutionState {
         (final_keys, candidate_keys)
     }
 
+    /// Get the datastore entries of an address whose candidate value differs from the final one,
+    /// in ascending key order
+    pub fn get_datastore_diff(&self, addr: &Address) -> Vec<DatastoreEntryDiff> {
+        // list the keys changed by the active history along with their candidate value
+        let mut entry_replaced = false;
+        let mut changes: BTreeMap<Vec<u8>, HistorySearchResult<Vec<u8>>> = {
+            let active_history = self.active_history.read();
+            let mut changed_keys: BTreeSet<Vec<u8>> = BTreeSet::new();
+            for output in &active_history.0 {
+                match output.state_changes.ledger_changes.get(addr) {
+                    None => (),
+                    Some(SetUpdateOrDelete::Set(new_ledger_entry)) => {
+                        changed_keys.extend(new_ledger_entry.datastore.keys().cloned());
+                        entry_replaced = true;
+                    }
+                    Some(SetUpdateOrDelete::Update(entry_updates)) => {
+                        changed_keys.extend(entry_updates.datastore.keys().cloned());
+                    }
+                    Some(SetUpdateOrDelete::Delete) => entry_replaced = true,
+                }
+            }
+            changed_keys
+                .into_iter()
+                .map(|key| {
+                    let candidate = active_history.fetch_active_history_data_entry(addr, &key);
+                    (key, candidate)
+                })
+                .collect()
+        };
+
+        let final_state = self.final_state.read();
+        // the final keys not set again after the entry was replaced are absent from the candidate entry
+        if entry_replaced {
+            for key in final_state
+                .ledger
+                .get_datastore_keys(addr)
+                .unwrap_or_default()
+            {
+                changes.entry(key).or_insert(HistorySearchResult::Absent);
+            }
+        }
+        changes
+            .into_iter()
+            .filter_map(|(key, candidate)| {
+                let final_value = final_state.ledger.get_data_entry(addr, &key);
+                let candidate_value = match candidate {
+                    HistorySearchResult::Present(active_entry) => Some(active_entry),
+                    HistorySearchResult::NoInfo => final_value.clone(),
+                    HistorySearchResult::Absent => None,
+                };
+                (final_value != candidate_value).then_some(DatastoreEntryDiff {
+                    key,
+                    final_value,
+                    candidate_value,
+                })
+            })
+            .collect()
+    }
+
+    /// Get the proofs of final ledger entries against the final state hash.
+    /// An entry is the balance of an address if its datastore key is `None`.
+    pub fn get_final_ledger_entry_proofs(
+        &self,
+        input: Vec<(Address, Option<Vec<u8>>)>,
+    ) -> Vec<FinalLedgerEntryProof> {
+        let final_state = self.final_state.read();
+        input
+            .into_iter()
+            .map(|(address, datastore_key)| {
+                final_state.get_ledger_entry_proof(address, datastore_key)
+            })
+            .collect()
+    }
+
+    /// Get a page of the final and active datastore keys of the given address starting with `prefix`,
+    /// in ascending order and strictly after `start_key` if set.
+    ///
+    /// # Returns
+    /// At most `limit` final keys and `limit` candidate keys
+    pub fn get_final_and_candidate_datastore_keys_page(
+        &self,
+        addr: &Address,
+        prefix: &[u8],
+        start_key: Option<&[u8]>,
+        limit: usize,
+    ) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        let in_page_range = |key: &Vec<u8>| {
+            key.starts_with(prefix)
+                && start_key.map_or(true, |start_key| key.as_slice() > start_key)
+        };
+
+        // Every active deletion can remove a key of the final page:
+        // fetch as many more final keys so that the candidate page is still complete after applying the history.
+        // Keys after the last fetched final key are then dropped when truncating the candidate page.
+        let deletion_count: usize = self
+            .active_history
+            .read()
+            .0
+            .iter()
+            .map(
+                |output| match output.state_changes.ledger_changes.get(addr) {
+                    Some(SetUpdateOrDelete::Update(entry_updates)) => entry_updates
+                        .datastore
+                        .iter()
+                        .filter(|(ds_key, ds_update)| {
+                            matches!(ds_update, SetOrDelete::Delete) && in_page_range(ds_key)
+                        })
+                        .count(),
+                    _ => 0,
+                },
+            )
+            .sum();
+        let final_state = self.final_state.read();
+        let final_keys = final_state
+            .ledger
+            .get_datastore_keys_page(addr, prefix, start_key, limit)
+            .unwrap_or_default();
+        let mut candidate_keys: BTreeSet<Vec<u8>> = if deletion_count == 0 {
+            final_keys.iter().cloned().collect()
+        } else {
+            final_state
+                .ledger
+                .get_datastore_keys_page(
+                    addr,
+                    prefix,
+                    start_key,
+                    limit.saturating_add(deletion_count),
+                )
+                .unwrap_or_default()
+                .into_iter()
+                .collect()
+        };
+        drop(final_state);
+
+        // traverse the history from oldest to newest, applying additions and deletions within the page range
+        for output in &self.active_history.read().0 {
+            match output.state_changes.ledger_changes.get(addr) {
+                // address absent from the changes
+                None => (),
+
+                // address ledger entry being reset to an absolute new list of keys
+                Some(SetUpdateOrDelete::Set(new_ledger_entry)) => {
+                    candidate_keys = new_ledger_entry
+                        .datastore
+                        .keys()
+                        .filter(|ds_key| in_page_range(ds_key))
+                        .cloned()
+                        .collect();
+                }
+
+                // address ledger entry being updated
+                Some(SetUpdateOrDelete::Update(entry_updates)) => {
+                    for (ds_key, ds_update) in &entry_updates.datastore {
+                        if !in_page_range(ds_key) {
+                            continue;
+                        }
+                        match ds_update {
+                            SetOrDelete::Set(_) => candidate_keys.insert(ds_key.clone()),
+                            SetOrDelete::Delete => candidate_keys.remove(ds_key),
+                        };
+                    }
+                }
+
+                // address ledger entry being deleted
+                Some(SetUpdateOrDelete::Delete) => {
+                    candidate_keys.clear();
+                }
+            }
+        }
+
+        (final_keys, candidate_keys.into_iter().take(limit).collect())
+    }
+
     /// Returns for a given cycle the stakers taken into account
     /// by the selector. That correspond to the `roll_counts` in `cycle - 3`.
     ///
@@ -1213,11 +1712,7 @@ impl ExecutionState {
     /// * event state (final, candidate or both)
     pub fn get_filtered_sc_output_event(&self, filter: EventFilter) -> Vec<SCOutputEvent> {
         match filter.is_final {
-            Some(true) => self
-                .final_events
-                .get_filtered_sc_output_events(&filter)
-                .into_iter()
-                .collect(),
+            Some(true) => self.final_events.get_filtered_sc_output_events(&filter),
             Some(false) => self
                 .active_history
                 .read()
@@ -0,0 +1,248 @@
+//! Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! This module stores the events generated by final executions over a bounded retention window.
+//!
+//! Events are ordered by slot and by index in their slot, so that query results are deterministic and can be paginated.
+//! Secondary indexes by emitter address, original caller address, original operation id and error status
+//! avoid scanning the whole store for the most selective `EventFilter` criteria.
+
+use massa_execution_exports::{event_matches_filter, EventStore};
+use massa_models::{
+    address::Address,
+    api::EventFilter,
+    operation::OperationId,
+    output_event::SCOutputEvent,
+    prehash::{PreHashMap, PreHashed},
+    slot::Slot,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::Hash;
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+
+/// Position of an event: slot and index in the slot
+type EventKey = (Slot, u64);
+
+/// Events generated by final executions, with secondary indexes
+pub(crate) struct FinalEventStore {
+    /// events sorted by position
+    events: BTreeMap<EventKey, SCOutputEvent>,
+    /// positions of the events by emitter address
+    by_emitter: PreHashMap<Address, BTreeSet<EventKey>>,
+    /// positions of the events by original caller address
+    by_caller: PreHashMap<Address, BTreeSet<EventKey>>,
+    /// positions of the events by original operation id
+    by_operation: PreHashMap<OperationId, BTreeSet<EventKey>>,
+    /// positions of the events generated by failed executions
+    errors: BTreeSet<EventKey>,
+    /// maximum number of events kept
+    max_events: usize,
+    /// number of periods during which events are kept
+    retention_periods: u64,
+}
+
+/// Adds an event position to an index
+fn index_insert<K: Eq + Hash + PreHashed>(
+    index: &mut PreHashMap<K, BTreeSet<EventKey>>,
+    id: K,
+    key: EventKey,
+) {
+    index.entry(id).or_default().insert(key);
+}
+
+/// Removes an event position from an index, dropping empty entries
+fn index_remove<K: Eq + Hash + PreHashed>(
+    index: &mut PreHashMap<K, BTreeSet<EventKey>>,
+    id: &K,
+    key: &EventKey,
+) {
+    if let Some(keys) = index.get_mut(id) {
+        keys.remove(key);
+        if keys.is_empty() {
+            index.remove(id);
+        }
+    }
+}
+
+impl FinalEventStore {
+    /// Creates an empty store
+    ///
+    /// # Arguments
+    /// * `max_events`: maximum number of events kept
+    /// * `retention_periods`: number of periods during which events are kept
+    pub fn new(max_events: usize, retention_periods: u64) -> Self {
+        FinalEventStore {
+            events: Default::default(),
+            by_emitter: Default::default(),
+            by_caller: Default::default(),
+            by_operation: Default::default(),
+            errors: Default::default(),
+            max_events,
+            retention_periods,
+        }
+    }
+
+    /// Adds final events and drops the events that fell out of the retention window
+    ///
+    /// # Arguments
+    /// * `events`: the final events to add
+    /// * `final_slot`: latest final slot
+    pub fn extend(&mut self, events: EventStore, final_slot: Slot) {
+        for event in events.0 {
+            let key = (event.context.slot, event.context.index_in_slot);
+            if let Some(emitter) = event.context.call_stack.front() {
+                index_insert(&mut self.by_emitter, *emitter, key);
+            }
+            if let Some(caller) = event.context.call_stack.back() {
+                index_insert(&mut self.by_caller, *caller, key);
+            }
+            if let Some(op_id) = event.context.origin_operation_id {
+                index_insert(&mut self.by_operation, op_id, key);
+            }
+            if event.context.is_error {
+                self.errors.insert(key);
+            }
+            self.events.insert(key, event);
+        }
+        self.prune(final_slot);
+    }
+
+    /// Drops the oldest events until the store fits in its size and retention bounds
+    fn prune(&mut self, final_slot: Slot) {
+        let min_period = final_slot.period.saturating_sub(self.retention_periods);
+        while let Some((key, _)) = self.events.first_key_value() {
+            if self.events.len() <= self.max_events && key.0.period >= min_period {
+                break;
+            }
+            let (key, event) = self.events.pop_first().expect("store should not be empty");
+            if let Some(emitter) = event.context.call_stack.front() {
+                index_remove(&mut self.by_emitter, emitter, &key);
+            }
+            if let Some(caller) = event.context.call_stack.back() {
+                index_remove(&mut self.by_caller, caller, &key);
+            }
+            if let Some(op_id) = &event.context.origin_operation_id {
+                index_remove(&mut self.by_operation, op_id, &key);
+            }
+            self.errors.remove(&key);
+        }
+    }
+
+    /// Gets the events matching a filter, sorted by slot and index in the slot
+    pub fn get_filtered_sc_output_events(&self, filter: &EventFilter) -> Vec<SCOutputEvent> {
+        // positions delimited by the slot range of the filter
+        let range: (Bound<EventKey>, Bound<EventKey>) = (
+            filter.start.map_or(Unbounded, |start| Included((start, 0))),
+            filter.end.map_or(Unbounded, |end| Excluded((end, 0))),
+        );
+        if let (Some(start), Some(end)) = (filter.start, filter.end)
+            && start >= end
+        {
+            return Vec::new();
+        }
+
+        // use the most selective index available
+        let indexed_keys = if let Some(op_id) = &filter.original_operation_id {
+            Some(self.by_operation.get(op_id))
+        } else if let Some(emitter) = &filter.emitter_address {
+            Some(self.by_emitter.get(emitter))
+        } else if let Some(caller) = &filter.original_caller_address {
+            Some(self.by_caller.get(caller))
+        } else if filter.is_error == Some(true) {
+            Some(Some(&self.errors))
+        } else {
+            None
+        };
+
+        let matches = |event: &&SCOutputEvent| event_matches_filter(event, filter);
+        match indexed_keys {
+            Some(Some(keys)) => keys
+                .range(range)
+                .filter_map(|key| self.events.get(key))
+                .filter(matches)
+                .cloned()
+                .collect(),
+            Some(None) => Vec::new(),
+            None => self
+                .events
+                .range(range)
+                .map(|(_, event)| event)
+                .filter(matches)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+#[test]
+fn test_final_event_store() {
+    use massa_models::output_event::EventExecutionContext;
+    use std::collections::VecDeque;
+    use std::str::FromStr;
+
+    let emitter =
+        Address::from_str("A12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let mut store = FinalEventStore::new(100, 5);
+    for period in 1..=10 {
+        let mut events = EventStore::default();
+        for index_in_slot in 0..2 {
+            events.push(SCOutputEvent {
+                context: EventExecutionContext {
+                    slot: Slot::new(period, 0),
+                    block: None,
+                    read_only: false,
+                    index_in_slot,
+                    call_stack: if index_in_slot == 0 {
+                        VecDeque::from([emitter])
+                    } else {
+                        VecDeque::new()
+                    },
+                    origin_operation_id: None,
+                    is_final: true,
+                    is_error: index_in_slot == 1,
+                },
+                data: format!("{}-{}", period, index_in_slot),
+            });
+        }
+        store.extend(events, Slot::new(period, 0));
+    }
+
+    // only the events of the last 5 periods are kept
+    let all = store.get_filtered_sc_output_events(&EventFilter::default());
+    assert_eq!(all.len(), 12);
+    assert_eq!(all[0].data, "5-0");
+    assert_eq!(all[11].data, "10-1");
+
+    // indexed queries are sorted and respect the slot range
+    let by_emitter = store.get_filtered_sc_output_events(&EventFilter {
+        emitter_address: Some(emitter),
+        start: Some(Slot::new(7, 0)),
+        ..Default::default()
+    });
+    assert_eq!(
+        by_emitter
+            .iter()
+            .map(|e| e.data.as_str())
+            .collect::<Vec<_>>(),
+        ["7-0", "8-0", "9-0", "10-0"]
+    );
+    let errors = store.get_filtered_sc_output_events(&EventFilter {
+        is_error: Some(true),
+        end: Some(Slot::new(6, 0)),
+        ..Default::default()
+    });
+    assert_eq!(
+        errors.iter().map(|e| e.data.as_str()).collect::<Vec<_>>(),
+        ["5-1"]
+    );
+
+    // the size bound also applies
+    let mut store = FinalEventStore::new(3, 100);
+    store.extend(EventStore(all.into_iter().collect()), Slot::new(10, 0));
+    assert_eq!(
+        store
+            .get_filtered_sc_output_events(&EventFilter::default())
+            .len(),
+        3
+    );
+    assert!(store.errors.len() <= 3);
+}
@@ -12,7 +12,11 @@ use massa_execution_exports::ExecutionConfig;
 use massa_execution_exports::ExecutionStackElement;
 use massa_models::config::MAX_DATASTORE_KEY_LENGTH;
 use massa_models::{
-    address::Address, amount::Amount, slot::Slot, timeslots::get_block_slot_timestamp,
+    address::{Address, AddressCategory},
+    amount::Amount,
+    execution::ReadOnlyCallTraceEntry,
+    slot::Slot,
+    timeslots::get_block_slot_timestamp,
 };
 use massa_sc_runtime::{Interface, InterfaceClone};
 use parking_lot::Mutex;
@@ -32,6 +36,21 @@ macro_rules! context_guard {
     };
 }
 
+/// Parses an address given by a smart contract.
+///
+/// Addresses formatted before address categories existed parse as user addresses.
+/// They designate the smart contract with the same hash if it exists, so that deployed bytecode keeps working.
+fn parse_address(context: &ExecutionContext, address: &str) -> Result<Address> {
+    let parsed = Address::from_str(address)?;
+    if parsed.to_string() != address {
+        let sc_address = Address::new(AddressCategory::SCAddress, parsed.hash);
+        if context.get_bytecode(&sc_address).is_some() {
+            return Ok(sc_address);
+        }
+    }
+    Ok(parsed)
+}
+
 /// an implementation of the Interface trait (see massa-sc-runtime crate)
 #[derive(Clone)]
 pub struct InterfaceImpl {
@@ -51,6 +70,11 @@ impl InterfaceImpl {
         InterfaceImpl { config, context }
     }
 
+    /// parses an address given by the smart contract (see `parse_address`)
+    fn parse_address(&self, address: &str) -> Result<Address> {
+        parse_address(&context_guard!(self), address)
+    }
+
     #[cfg(any(feature = "gas_calibration", feature = "benchmarking"))]
     /// Used to create an default interface to run SC in a test environment
     pub fn new_default(
@@ -116,7 +140,7 @@ impl Interface for InterfaceImpl {
     /// The target bytecode or an error
     fn init_call(&self, address: &str, raw_coins: u64) -> Result<Vec<u8>> {
         // get target address
-        let to_address = massa_models::address::Address::from_str(address)?;
+        let to_address = self.parse_address(address)?;
 
         // write-lock context
         let mut context = context_guard!(self);
@@ -146,6 +170,17 @@ impl Interface for InterfaceImpl {
             );
         }
 
+        // trace the call to allow debugging read-only executions
+        if context.read_only {
+            let depth = context.stack.len();
+            context.call_trace.push(ReadOnlyCallTraceEntry {
+                depth,
+                caller: from_address,
+                target: to_address,
+                coins,
+            });
+        }
+
         // push a new call stack element on top of the current call stack
         context.stack.push(ExecutionStackElement {
             address: to_address,
@@ -190,7 +225,7 @@ impl Interface for InterfaceImpl {
     /// The raw representation (no decimal factor) of the balance of the address,
     /// or zero if the address is not found in the ledger.
     fn get_balance_for(&self, address: &str) -> Result<u64> {
-        let address = massa_models::address::Address::from_str(address)?;
+        let address = self.parse_address(address)?;
         Ok(context_guard!(self)
             .get_balance(&address)
             .unwrap_or_default()
@@ -230,7 +265,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// A list of keys (keys are byte arrays)
     fn get_keys_for(&self, address: &str) -> Result<BTreeSet<Vec<u8>>> {
-        let addr = &Address::from_str(address)?;
+        let addr = &self.parse_address(address)?;
         let context = context_guard!(self);
         match context.get_keys(addr) {
             Some(value) => Ok(value),
@@ -247,7 +282,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The datastore value matching the provided key, if found, otherwise an error.
     fn raw_get_data_for(&self, address: &str, key: &[u8]) -> Result<Vec<u8>> {
-        let addr = &massa_models::address::Address::from_str(address)?;
+        let addr = &self.parse_address(address)?;
         let context = context_guard!(self);
         match context.get_data_entry(addr, key) {
             Some(value) => Ok(value),
@@ -264,7 +299,7 @@ impl Interface for InterfaceImpl {
     /// * key: string key of the datastore entry to set
     /// * value: new value to set
     fn raw_set_data_for(&self, address: &str, key: &[u8], value: &[u8]) -> Result<()> {
-        let addr = massa_models::address::Address::from_str(address)?;
+        let addr = self.parse_address(address)?;
         let mut context = context_guard!(self);
         context.set_data_entry(&addr, key.to_vec(), value.to_vec())?;
         Ok(())
@@ -278,7 +313,7 @@ impl Interface for InterfaceImpl {
     /// * key: string key of the datastore entry
     /// * value: value to append
     fn raw_append_data_for(&self, address: &str, key: &[u8], value: &[u8]) -> Result<()> {
-        let addr = massa_models::address::Address::from_str(address)?;
+        let addr = self.parse_address(address)?;
         context_guard!(self).append_data_entry(&addr, key.to_vec(), value.to_vec())?;
         Ok(())
     }
@@ -290,7 +325,7 @@ impl Interface for InterfaceImpl {
     /// * address: string representation of the address
     /// * key: string key of the datastore entry to delete
     fn raw_delete_data_for(&self, address: &str, key: &[u8]) -> Result<()> {
-        let addr = &massa_models::address::Address::from_str(address)?;
+        let addr = &self.parse_address(address)?;
         context_guard!(self).delete_data_entry(addr, key)?;
         Ok(())
     }
@@ -304,7 +339,7 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// true if the address exists and has the entry matching the provided key in its datastore, otherwise false
     fn has_data_for(&self, address: &str, key: &[u8]) -> Result<bool> {
-        let addr = massa_models::address::Address::from_str(address)?;
+        let addr = self.parse_address(address)?;
         let context = context_guard!(self);
         Ok(context.has_data_entry(&addr, key))
     }
@@ -412,7 +447,7 @@ impl Interface for InterfaceImpl {
     /// Returns bytecode of the target address
     fn raw_get_bytecode_for(&self, address: &str) -> Result<Vec<u8>> {
         let context = context_guard!(self);
-        let address = Address::from_str(address)?;
+        let address = parse_address(&context, address)?;
         match context.get_bytecode(&address) {
             Some(bytecode) => Ok(bytecode),
             _ => bail!("bytecode not found"),
@@ -533,7 +568,7 @@ impl Interface for InterfaceImpl {
     /// * `to_address`: string representation of the address to which the coins are sent
     /// * `raw_amount`: raw representation (no decimal factor) of the amount of coins to transfer
     fn transfer_coins(&self, to_address: &str, raw_amount: u64) -> Result<()> {
-        let to_address = massa_models::address::Address::from_str(to_address)?;
+        let to_address = self.parse_address(to_address)?;
         let amount = massa_models::amount::Amount::from_raw(raw_amount);
         let mut context = context_guard!(self);
         let from_address = context.get_current_address()?;
@@ -553,8 +588,8 @@ impl Interface for InterfaceImpl {
         to_address: &str,
         raw_amount: u64,
     ) -> Result<()> {
-        let from_address = massa_models::address::Address::from_str(from_address)?;
-        let to_address = massa_models::address::Address::from_str(to_address)?;
+        let from_address = self.parse_address(from_address)?;
+        let to_address = self.parse_address(to_address)?;
         let amount = massa_models::amount::Amount::from_raw(raw_amount);
         let mut context = context_guard!(self);
         context.transfer_coins(Some(from_address), Some(to_address), amount, true)?;
@@ -679,11 +714,26 @@ impl Interface for InterfaceImpl {
         let fee = Amount::from_raw(raw_fee);
         execution_context.transfer_coins(Some(sender), None, coins, true)?;
         execution_context.transfer_coins(Some(sender), None, fee, true)?;
+        let destination = parse_address(&execution_context, target_address)?;
+        let trigger = filter
+            .map(|(addr, key)| {
+                let datastore_key = key.map(|k| k.to_vec());
+                if let Some(ref k) = datastore_key {
+                    if k.len() > MAX_DATASTORE_KEY_LENGTH as usize {
+                        bail!("datastore key is too long")
+                    }
+                }
+                Ok::<AsyncMessageTrigger, _>(AsyncMessageTrigger {
+                    address: parse_address(&execution_context, addr)?,
+                    datastore_key,
+                })
+            })
+            .transpose()?;
         execution_context.push_new_message(AsyncMessage::new_with_hash(
             emission_slot,
             emission_index,
             sender,
-            Address::from_str(target_address)?,
+            destination,
             target_handler.to_string(),
             max_gas,
             fee,
@@ -691,20 +741,7 @@ impl Interface for InterfaceImpl {
             Slot::new(validity_start.0, validity_start.1),
             Slot::new(validity_end.0, validity_end.1),
             data.to_vec(),
-            filter
-                .map(|(addr, key)| {
-                    let datastore_key = key.map(|k| k.to_vec());
-                    if let Some(ref k) = datastore_key {
-                        if k.len() > MAX_DATASTORE_KEY_LENGTH as usize {
-                            bail!("datastore key is too long")
-                        }
-                    }
-                    Ok::<AsyncMessageTrigger, _>(AsyncMessageTrigger {
-                        address: Address::from_str(addr)?,
-                        datastore_key,
-                    })
-                })
-                .transpose()?,
+            trigger,
         ));
         execution_context.created_message_index += 1;
         Ok(())
@@ -735,7 +772,7 @@ impl Interface for InterfaceImpl {
     /// Sets the bytecode of an arbitrary address.
     /// Fails if the address does not exist of if the context doesn't have write access rights on it.
     fn raw_set_bytecode_for(&self, address: &str, bytecode: &[u8]) -> Result<()> {
-        let address = massa_models::address::Address::from_str(address)?;
+        let address = self.parse_address(address)?;
         let mut execution_context = context_guard!(self);
         match execution_context.set_bytecode(&address, bytecode.to_vec()) {
             Ok(()) => Ok(()),
@@ -73,6 +73,15 @@
 //! This module contains the implementation of a generic finite-size execution request queue.
 //! It handles requests that come with an MPSC to send back the result of their execution once it's done.
 //!
+//! ## `address_history.rs`
+//! Indexes the balance and roll changes of each address in the recent final slots.
+//!
+//! ## `transfer_history.rs`
+//! Indexes the coins sent and received by each address through the operations of the recent final slots.
+//!
+//! ## `final_event_store.rs`
+//! Stores the final execution events over a bounded retention window, indexed for slot range queries.
+//!
 //! ## `stats.rs`
 //! Defines a structure that gathers execution statistics.
 
@@ -84,9 +93,11 @@
 #![feature(option_get_or_insert_default)]
 
 mod active_history;
+mod address_history;
 mod context;
 mod controller;
 mod execution;
+mod final_event_store;
 mod interface_impl;
 mod request_queue;
 mod slot_sequencer;
@@ -95,6 +106,8 @@ mod speculative_executed_ops;
 mod speculative_ledger;
 mod speculative_roll_state;
 mod stats;
+mod supply_checker;
+mod transfer_history;
 mod worker;
 
 pub use worker::start_execution_worker;
@@ -5,12 +5,7 @@
 use std::collections::{HashMap, VecDeque};
 
 use massa_execution_exports::ExecutionConfig;
-use massa_models::{
-    block::BlockId,
-    prehash::PreHashMap,
-    slot::Slot,
-    timeslots::{get_block_slot_timestamp, get_latest_block_slot_at_timestamp},
-};
+use massa_models::{block::BlockId, prehash::PreHashMap, slot::Slot, timeslots::SlotScheduler};
 use massa_storage::Storage;
 use massa_time::MassaTime;
 
@@ -60,6 +55,9 @@ pub struct SlotSequencer {
 
     /// candidate slot execution cursor
     latest_executed_candidate_slot: Slot,
+
+    /// slot timing, shifted by `config.cursor_delay`
+    slot_scheduler: SlotScheduler,
 }
 
 impl SlotSequencer {
@@ -76,6 +74,13 @@ impl SlotSequencer {
             latest_sce_final_slot: final_cursor,
             latest_executed_final_slot: final_cursor,
             latest_executed_candidate_slot: final_cursor,
+            slot_scheduler: SlotScheduler::new(
+                config.thread_count,
+                config.t0,
+                config.genesis_timestamp,
+                config.cursor_delay,
+                None,
+            ),
             config,
         }
     }
@@ -187,17 +192,10 @@ impl SlotSequencer {
     /// Note that this time cursor is shifted by `self.config.cursor_delay`
     /// to avoid computing speculative slots that are too recent, and therefore subject to frequent re-writes.
     fn get_time_cursor(&self) -> Slot {
-        let shifted_now = MassaTime::now()
-            .expect("could not get current time")
-            .saturating_sub(self.config.cursor_delay);
-        get_latest_block_slot_at_timestamp(
-            self.config.thread_count,
-            self.config.t0,
-            self.config.genesis_timestamp,
-            shifted_now,
-        )
-        .expect("could not get latest block slot at shifted execution time")
-        .unwrap_or_else(|| Slot::new(0, 0))
+        self.slot_scheduler
+            .get_latest_slot_at(MassaTime::now().expect("could not get current time"))
+            .expect("could not get latest block slot at shifted execution time")
+            .unwrap_or_else(|| Slot::new(0, 0))
     }
 
     /// Notify the sequencer of incoming changes: CSS-finalized blocks and changes in the blockclique.
@@ -743,13 +741,8 @@ impl SlotSequencer {
             .expect("slot overflow in slot deadline computation");
 
         // Return the timestamp of that slot, shifted by the cursor delay.
-        get_block_slot_timestamp(
-            self.config.thread_count,
-            self.config.t0,
-            self.config.genesis_timestamp,
-            next_slot,
-        )
-        .expect("could not compute slot timestamp")
-        .saturating_add(self.config.cursor_delay)
+        self.slot_scheduler
+            .get_slot_timestamp(next_slot)
+            .expect("could not compute slot timestamp")
     }
 }
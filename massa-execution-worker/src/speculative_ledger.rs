@@ -150,7 +150,7 @@ impl SpeculativeLedger {
                 .ok_or_else(|| ExecutionError::RuntimeError("source addr not found".to_string()))?
                 .checked_sub(amount)
                 .ok_or_else(|| {
-                    ExecutionError::RuntimeError("insufficient from_addr balance".into())
+                    ExecutionError::InsufficientBalance("insufficient from_addr balance".into())
                 })?;
             changes.set_balance(from_addr, new_balance);
         }
@@ -163,7 +163,7 @@ impl SpeculativeLedger {
                 // if `to_addr` exists we increase the balance
                 (Some(old_balance), _) => {
                     let new_balance = old_balance.checked_add(amount).ok_or_else(|| {
-                        ExecutionError::RuntimeError("overflow in to_addr balance".into())
+                        ExecutionError::CoinsOverflow("overflow in to_addr balance".into())
                     })?;
                     changes.set_balance(to_addr, new_balance);
                 }
@@ -184,7 +184,7 @@ impl SpeculativeLedger {
                                 })?,
                         );
                     } else {
-                        return Err(ExecutionError::RuntimeError(
+                        return Err(ExecutionError::InsufficientBalance(
                             "insufficient amount to create receiver address".to_string(),
                         ));
                     }
@@ -467,7 +467,7 @@ impl SpeculativeLedger {
         self.storage_costs_constants
             .ledger_cost_per_byte
             .checked_mul_u64(value.len().try_into().map_err(|_| {
-                ExecutionError::RuntimeError("value in datastore is too big".to_string())
+                ExecutionError::DatastoreLimit("value in datastore is too big".to_string())
             })?)
             .ok_or_else(|| {
                 ExecutionError::RuntimeError(
@@ -503,14 +503,14 @@ impl SpeculativeLedger {
         // check key correctness
         let key_length = key.len();
         if key_length == 0 || key_length > self.max_datastore_key_length as usize {
-            return Err(ExecutionError::RuntimeError(format!(
+            return Err(ExecutionError::DatastoreLimit(format!(
                 "key length is {}, but it must be in [0..={}]",
                 key_length, self.max_datastore_key_length
             )));
         }
 
         if value.len() > self.max_datastore_value_size as usize {
-            return Err(ExecutionError::RuntimeError(format!(
+            return Err(ExecutionError::DatastoreLimit(format!(
                 "value length is {}, but it must be in [0..={}]",
                 value.len(),
                 self.max_datastore_value_size
@@ -1,5 +1,6 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_hash::Hash;
 use massa_models::slot::Slot;
 use massa_models::stats::ExecutionStats;
 use massa_time::MassaTime;
@@ -63,7 +64,12 @@ impl ExecutionStatsCounter {
     }
 
     /// get statistics
-    pub fn get_stats(&self, active_cursor: Slot) -> ExecutionStats {
+    pub fn get_stats(
+        &self,
+        active_cursor: Slot,
+        final_cursor: Slot,
+        final_state_hash: Hash,
+    ) -> ExecutionStats {
         let current_time = MassaTime::now().expect("could not get current time");
         let start_time = current_time.saturating_sub(self.time_window_duration);
         let map_func = |pair: &(usize, MassaTime)| -> usize {
@@ -80,6 +86,8 @@ impl ExecutionStatsCounter {
             time_window_start: start_time,
             time_window_end: current_time,
             active_cursor,
+            final_cursor,
+            final_state_hash,
         }
     }
 }
@@ -0,0 +1,73 @@
+//! Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! This module checks at each cycle boundary that no coins were created out of the emission schedule.
+//!
+//! The total supply is the sum of the ledger balances, the deferred credits, the value of the rolls
+//! and the coins held by the asynchronous messages.
+//! Between two checks, it can only grow by the block rewards of the produced blocks.
+//! It can decrease as coins are burned, for example to pay for storage costs.
+
+use massa_models::amount::Amount;
+
+/// Total supply invariant checker
+pub struct TotalSupplyChecker {
+    /// total supply measured at the last check, `None` until the first check
+    last_total_supply: Option<Amount>,
+    /// coins minted as block rewards since the last check
+    minted_since_last_check: Amount,
+}
+
+impl TotalSupplyChecker {
+    /// create a new `TotalSupplyChecker`
+    pub fn new() -> Self {
+        TotalSupplyChecker {
+            last_total_supply: None,
+            minted_since_last_check: Amount::zero(),
+        }
+    }
+
+    /// register the reward minted for a final block
+    pub fn register_block_reward(&mut self, block_reward: Amount) {
+        self.minted_since_last_check = self.minted_since_last_check.saturating_add(block_reward);
+    }
+
+    /// Compares the measured `total_supply` with the supply expected from the last check
+    /// and the block rewards minted since then, then starts a new check period.
+    ///
+    /// # Returns
+    /// The expected total supply if the measured one exceeds it
+    pub fn check(&mut self, total_supply: Amount) -> Option<Amount> {
+        let expected_total_supply = self.last_total_supply.map(|last_total_supply| {
+            last_total_supply.saturating_add(self.minted_since_last_check)
+        });
+        self.last_total_supply = Some(total_supply);
+        self.minted_since_last_check = Amount::zero();
+        match expected_total_supply {
+            Some(expected) if total_supply > expected => Some(expected),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_total_supply_checker() {
+    let mut checker = TotalSupplyChecker::new();
+    let reward = Amount::from_raw(100);
+
+    // the first check only sets the reference supply
+    checker.register_block_reward(reward);
+    assert_eq!(checker.check(Amount::from_raw(1_000)), None);
+
+    // minted rewards and burned coins are accepted
+    checker.register_block_reward(reward);
+    checker.register_block_reward(reward);
+    assert_eq!(checker.check(Amount::from_raw(1_200)), None);
+    assert_eq!(checker.check(Amount::from_raw(1_150)), None);
+
+    // coins created out of the emission schedule are reported
+    checker.register_block_reward(reward);
+    assert_eq!(
+        checker.check(Amount::from_raw(1_300)),
+        Some(Amount::from_raw(1_250))
+    );
+}
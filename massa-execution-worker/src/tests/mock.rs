@@ -26,7 +26,7 @@ use massa_models::{
 };
 
 #[cfg(feature = "testing")]
-use massa_execution_exports::ExecutionError;
+use massa_execution_exports::{ExecutionChannels, ExecutionError};
 
 #[cfg(feature = "testing")]
 use massa_hash::Hash;
@@ -121,6 +121,8 @@ pub fn get_sample_state() -> Result<(Arc<RwLock<FinalState>>, NamedTempFile, Tem
         final_history_length: 128,
         thread_count: THREAD_COUNT,
         initial_rolls_path: rolls_file.path().to_path_buf(),
+        initial_deferred_credits_path: None,
+        initial_snapshot_path: None,
         initial_seed_string: "".to_string(),
         periods_per_cycle: 10,
     };
@@ -136,6 +138,14 @@ pub fn get_sample_state() -> Result<(Arc<RwLock<FinalState>>, NamedTempFile, Tem
 /// creator.
 ///
 /// Return a result that should be unwrapped in the root `#[test]` routine.
+/// Get execution channels whose outputs are not listened to
+pub fn get_execution_channels() -> ExecutionChannels {
+    ExecutionChannels {
+        slot_execution_output_sender: tokio::sync::broadcast::channel(5000).0,
+        execution_reorg_sender: tokio::sync::broadcast::channel(5000).0,
+    }
+}
+
 #[cfg(feature = "testing")]
 pub fn create_block(
     creator_keypair: KeyPair,
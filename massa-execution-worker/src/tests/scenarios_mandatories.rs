@@ -1,14 +1,23 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use crate::start_execution_worker;
-use crate::tests::mock::{create_block, get_random_address_full, get_sample_state};
+use crate::tests::mock::{
+    create_block, get_execution_channels, get_random_address_full, get_sample_state,
+};
 use massa_execution_exports::{
     ExecutionConfig, ExecutionController, ExecutionError, ReadOnlyExecutionRequest,
     ReadOnlyExecutionTarget,
 };
+use massa_hash::Hash;
+use massa_ledger_exports::LedgerChanges;
 use massa_models::config::{LEDGER_ENTRY_BASE_SIZE, LEDGER_ENTRY_DATASTORE_BASE_SIZE};
+use massa_models::execution::{ReorgAffectedOperation, SlotExecutionStatus};
 use massa_models::prehash::PreHashMap;
-use massa_models::{address::Address, amount::Amount, slot::Slot};
+use massa_models::{
+    address::{Address, AddressCategory},
+    amount::Amount,
+    slot::Slot,
+};
 use massa_models::{
     api::EventFilter,
     block::BlockId,
@@ -33,6 +42,7 @@ fn test_execution_shutdown() {
         ExecutionConfig::default(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     manager.stop();
 }
@@ -45,6 +55,7 @@ fn test_sending_command() {
         ExecutionConfig::default(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     controller.update_blockclique_status(
         Default::default(),
@@ -62,6 +73,7 @@ fn test_readonly_execution() {
         ExecutionConfig::default(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     let mut res = controller
         .execute_readonly_request(ReadOnlyExecutionRequest {
@@ -79,6 +91,31 @@ fn test_readonly_execution() {
     manager.stop();
 }
 
+#[test]
+#[serial]
+fn test_out_of_gas_error_code() {
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+    let (mut manager, controller) = start_execution_worker(
+        ExecutionConfig::default(),
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
+    );
+    // the VM runs out of gas: the error must be categorized from the message of the VM
+    let err = controller
+        .execute_readonly_request(ReadOnlyExecutionRequest {
+            max_gas: 1,
+            call_stack: vec![],
+            target: ReadOnlyExecutionTarget::BytecodeExecution(
+                include_bytes!("./wasm/event_test.wasm").to_vec(),
+            ),
+        })
+        .expect_err("readonly execution should run out of gas");
+    assert_eq!(err.code(), "gas_exhausted", "unexpected error: {}", err);
+
+    manager.stop();
+}
+
 /// Feeds the execution worker with genesis blocks to start it
 fn init_execution_worker(
     config: &ExecutionConfig,
@@ -130,6 +167,7 @@ fn test_nested_call_gas_usage() {
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -274,6 +312,7 @@ fn send_and_receive_async_message() {
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -352,6 +391,7 @@ fn local_execution() {
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -453,6 +493,7 @@ fn sc_deployment() {
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -496,6 +537,29 @@ fn sc_deployment() {
     assert_eq!(events[1].data, "constructor exists and will be called");
     assert_eq!(events[2].data, "constructor called by deployer");
 
+    // check the bytecode deployed at the created smart contract address
+    let sc_address = *events[2].context.call_stack.back().unwrap();
+    let sc_bytecode = controller.get_bytecode(&sc_address);
+    let final_bytecode = sc_bytecode
+        .final_bytecode
+        .expect("the smart contract should be in the final ledger");
+    assert!(!final_bytecode.bytecode.is_empty());
+    assert_eq!(final_bytecode.size, final_bytecode.bytecode.len() as u64);
+    assert_eq!(
+        final_bytecode.hash,
+        Hash::compute_from(&final_bytecode.bytecode)
+    );
+    assert_eq!(
+        sc_bytecode.candidate_bytecode.unwrap().hash,
+        final_bytecode.hash
+    );
+    // the deployer is a regular address and has no bytecode
+    let deployer_address = Address::from_public_key(&keypair.get_public_key());
+    assert_eq!(
+        controller.has_bytecode(&[sc_address, deployer_address]),
+        vec![(true, true), (false, false)]
+    );
+
     // stop the execution controller
     manager.stop();
 }
@@ -537,6 +601,7 @@ fn send_and_receive_async_message_with_trigger() {
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -676,6 +741,7 @@ pub fn send_and_receive_transaction() {
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -754,6 +820,7 @@ pub fn roll_buy() {
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -827,6 +894,7 @@ pub fn roll_sell() {
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -939,6 +1007,7 @@ fn sc_execution_error() {
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -978,6 +1047,9 @@ fn sc_execution_error() {
         .data
         .contains("runtime error when executing operation"));
     assert!(events[1].data.contains("address parsing error"));
+    assert!(events[1]
+        .data
+        .contains(r#""massa_execution_error_code":"vm_error""#));
     // stop the execution controller
     manager.stop();
 }
@@ -1002,6 +1074,7 @@ fn sc_datastore() {
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -1059,6 +1132,7 @@ fn set_bytecode_error() {
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -1120,6 +1194,7 @@ fn datastore_manipulations() {
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -1195,6 +1270,65 @@ fn datastore_manipulations() {
     manager.stop();
 }
 
+/// Checks that the datastore entries written by a candidate execution are listed
+/// by `get_datastore_diff` until they become final
+#[test]
+#[serial]
+fn datastore_diff() {
+    let exec_cfg = ExecutionConfig {
+        t0: 100.into(),
+        max_async_gas: 100_000,
+        cursor_delay: 0.into(),
+        ..ExecutionConfig::default()
+    };
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+    let mut storage = Storage::create_root();
+    let (mut manager, controller) = start_execution_worker(
+        exec_cfg.clone(),
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
+    );
+    init_execution_worker(&exec_cfg, &storage, controller.clone());
+
+    // keypair associated to thread 0
+    let keypair = KeyPair::from_str("S1JJeHiZv1C1zZN5GLFcbz6EXYiccmUPLkYuDFA3kayjxP39kFQ").unwrap();
+    let address = Address::from_public_key(&keypair.get_public_key());
+    let bytecode = include_bytes!("./wasm/datastore_manipulations.wasm");
+    let operation = create_execute_sc_operation(&keypair, bytecode, BTreeMap::default()).unwrap();
+    storage.store_operations(vec![operation.clone()]);
+    let block = create_block(KeyPair::generate(), vec![operation], Slot::new(1, 0)).unwrap();
+    storage.store_block(block.clone());
+    let slot = block.content.header.content.slot;
+    let block_store: PreHashMap<BlockId, Storage> =
+        vec![(block.id, storage.clone())].into_iter().collect();
+
+    // execute the block as a candidate
+    let blockclique_blocks: HashMap<Slot, BlockId> = vec![(slot, block.id)].into_iter().collect();
+    controller.update_blockclique_status(
+        Default::default(),
+        Some(blockclique_blocks),
+        block_store.clone(),
+    );
+    std::thread::sleep(Duration::from_millis(1000));
+    let diff = controller.get_datastore_diff(&address);
+    let key: Vec<u8> = vec![1, 0, 4, 255];
+    let entry = diff
+        .iter()
+        .find(|entry| entry.key == key)
+        .expect("the written entry should differ from the final state");
+    assert_eq!(entry.final_value, None);
+    assert!(entry.candidate_value.is_some());
+
+    // once final, the entry does not differ anymore
+    let finalized_blocks: HashMap<Slot, BlockId> = vec![(slot, block.id)].into_iter().collect();
+    controller.update_blockclique_status(finalized_blocks, None, block_store);
+    std::thread::sleep(Duration::from_millis(1000));
+    assert!(controller.get_datastore_diff(&address).is_empty());
+
+    manager.stop();
+}
+
 /// This test checks causes a history rewrite in slot sequencing and ensures that emitted events match
 #[test]
 #[serial]
@@ -1204,14 +1338,18 @@ fn events_from_switching_blockclique() {
     let exec_cfg = ExecutionConfig {
         t0: 100.into(),
         cursor_delay: 0.into(),
+        broadcast_enabled: true,
         ..ExecutionConfig::default()
     };
     let storage: Storage = Storage::create_root();
     let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+    let channels = get_execution_channels();
+    let mut execution_reorg_receiver = channels.execution_reorg_sender.subscribe();
     let (mut manager, controller) = start_execution_worker(
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        channels,
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -1220,7 +1358,7 @@ fn events_from_switching_blockclique() {
     let mut blockclique_blocks: HashMap<Slot, BlockId> = HashMap::new();
 
     // create blockclique block at slot (1,1)
-    {
+    let switched_operation_id = {
         let blockclique_block_slot = Slot::new(1, 1);
         let keypair =
             KeyPair::from_str("S1kEBGgxHFBdsNC4HtRHhsZsB5irAtYHEmuAKATkfiomYmj58tm").unwrap();
@@ -1232,9 +1370,11 @@ fn events_from_switching_blockclique() {
         blockclique_blocks.insert(blockclique_block_slot, blockclique_block.id);
         let mut blockclique_block_storage = storage.clone_without_refs();
         blockclique_block_storage.store_block(blockclique_block.clone());
+        let operation_id = operation.id;
         blockclique_block_storage.store_operations(vec![operation]);
         block_storage.insert(blockclique_block.id, blockclique_block_storage);
-    }
+        operation_id
+    };
     // notify execution about blockclique change
     controller.update_blockclique_status(
         Default::default(),
@@ -1274,6 +1414,86 @@ fn events_from_switching_blockclique() {
     assert_eq!(events[0].context.slot, Slot::new(1, 0), "Wrong event slot");
     assert_eq!(events[1].context.slot, Slot::new(1, 1), "Wrong event slot");
 
+    // the candidate execution of slot (1,1) was cancelled by the insertion of the block at (1,0)
+    let mut affected_operations = Vec::new();
+    while let Ok(reorg) = execution_reorg_receiver.try_recv() {
+        affected_operations.extend(reorg.affected_operations);
+    }
+    assert_eq!(
+        affected_operations,
+        vec![ReorgAffectedOperation {
+            operation_id: switched_operation_id,
+            slot: Slot::new(1, 1)
+        }]
+    );
+
+    manager.stop();
+}
+
+#[test]
+#[serial]
+fn slot_execution_outputs_broadcast() {
+    // Send a block executing `event_test.wasm` in the blockclique, then finalize it.
+    // We check that its output is broadcast once as candidate, then once as final.
+    let exec_cfg = ExecutionConfig {
+        t0: 100.into(),
+        cursor_delay: 0.into(),
+        broadcast_enabled: true,
+        ..ExecutionConfig::default()
+    };
+    let storage: Storage = Storage::create_root();
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+    let channels = get_execution_channels();
+    let mut slot_execution_output_receiver = channels.slot_execution_output_sender.subscribe();
+    let (mut manager, controller) = start_execution_worker(
+        exec_cfg.clone(),
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+        channels,
+    );
+    // initialize the execution system with genesis blocks
+    init_execution_worker(&exec_cfg, &storage, controller.clone());
+
+    // create a block at slot (1,0)
+    let slot = Slot::new(1, 0);
+    let keypair = KeyPair::from_str("S1JJeHiZv1C1zZN5GLFcbz6EXYiccmUPLkYuDFA3kayjxP39kFQ").unwrap();
+    let event_test_data = include_bytes!("./wasm/event_test.wasm");
+    let operation =
+        create_execute_sc_operation(&keypair, event_test_data, BTreeMap::default()).unwrap();
+    let block = create_block(keypair, vec![operation.clone()], slot).unwrap();
+    let mut block_store = storage.clone_without_refs();
+    block_store.store_block(block.clone());
+    block_store.store_operations(vec![operation.clone()]);
+    let mut block_storage: PreHashMap<BlockId, Storage> = Default::default();
+    block_storage.insert(block.id, block_store);
+    let blocks: HashMap<Slot, BlockId> = HashMap::from([(slot, block.id)]);
+
+    // send it in the blockclique, then finalize it
+    controller.update_blockclique_status(
+        Default::default(),
+        Some(blocks.clone()),
+        block_storage.clone(),
+    );
+    std::thread::sleep(Duration::from_millis(1000));
+    controller.update_blockclique_status(blocks, None, block_storage);
+    std::thread::sleep(Duration::from_millis(100));
+
+    let mut outputs = Vec::new();
+    while let Ok(output) = slot_execution_output_receiver.try_recv() {
+        if output.slot == slot {
+            outputs.push(output);
+        }
+    }
+    assert_eq!(outputs.len(), 2, "wrong slot execution output count");
+    assert_eq!(outputs[0].status, SlotExecutionStatus::Candidate);
+    assert_eq!(outputs[1].status, SlotExecutionStatus::Final);
+    for output in outputs {
+        assert_eq!(output.block_id, Some(block.id));
+        assert_eq!(output.executed_ops, vec![operation.id]);
+        assert!(output.gas_used > 0);
+        assert_eq!(output.events.len(), 1, "wrong event count");
+    }
+
     manager.stop();
 }
 
@@ -1350,6 +1570,7 @@ fn sc_builtins() {
         exec_cfg.clone(),
         sample_state.clone(),
         sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
     );
     // initialize the execution system with genesis blocks
     init_execution_worker(&exec_cfg, &storage, controller.clone());
@@ -1399,3 +1620,79 @@ fn sc_builtins() {
     // stop the execution controller
     manager.stop();
 }
+
+#[test]
+#[serial]
+fn call_sc_invalid_targets() {
+    // setup the period duration
+    let exec_cfg = ExecutionConfig {
+        t0: 100.into(),
+        cursor_delay: 0.into(),
+        ..ExecutionConfig::default()
+    };
+    // get a sample final state
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+
+    // init the storage
+    let mut storage = Storage::create_root();
+    // start the execution worker
+    let (mut manager, controller) = start_execution_worker(
+        exec_cfg.clone(),
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+        get_execution_channels(),
+    );
+    // initialize the execution system with genesis blocks
+    init_execution_worker(&exec_cfg, &storage, controller.clone());
+    // keypair associated to thread 0
+    let keypair = KeyPair::from_str("S1JJeHiZv1C1zZN5GLFcbz6EXYiccmUPLkYuDFA3kayjxP39kFQ").unwrap();
+    // create a smart contract address without bytecode
+    let empty_sc_address = Address::new(AddressCategory::SCAddress, Hash::compute_from(b"empty"));
+    let mut ledger_changes = LedgerChanges::default();
+    ledger_changes.create_address(&empty_sc_address);
+    {
+        let mut final_state = sample_state.write();
+        let slot = final_state.slot;
+        final_state.ledger.apply_changes(ledger_changes, slot);
+    }
+    // call a user address, then a smart contract address that does not exist, then one with an empty bytecode
+    let targets = [
+        Address::from_public_key(&KeyPair::generate().get_public_key()),
+        Address::new(AddressCategory::SCAddress, Hash::compute_from(b"missing")),
+        empty_sc_address,
+    ];
+    let operations: Vec<WrappedOperation> = targets
+        .iter()
+        .map(|target| {
+            create_call_sc_operation(
+                &keypair,
+                100_000,
+                Amount::from_str("0").unwrap(),
+                *target,
+                "main".to_string(),
+                Vec::new(),
+            )
+            .unwrap()
+        })
+        .collect();
+    storage.store_operations(operations.clone());
+    let block = create_block(KeyPair::generate(), operations, Slot::new(1, 0)).unwrap();
+    // store the block in storage
+    storage.store_block(block.clone());
+    // set our block as a final block
+    let mut finalized_blocks: HashMap<Slot, BlockId> = Default::default();
+    finalized_blocks.insert(block.content.header.content.slot, block.id);
+    let mut block_storage: PreHashMap<BlockId, Storage> = Default::default();
+    block_storage.insert(block.id, storage.clone());
+    controller.update_blockclique_status(finalized_blocks, Default::default(), block_storage);
+    std::thread::sleep(Duration::from_millis(10));
+
+    // retrieve the events emitted by the execution errors
+    let events = controller.get_filtered_sc_output_event(EventFilter::default());
+    assert_eq!(events.len(), 3, "Three events were expected");
+    assert!(events[0].data.contains("address_category_error"));
+    assert!(events[1].data.contains("target_not_found"));
+    assert!(events[2].data.contains("target_not_found"));
+    // stop the execution controller
+    manager.stop();
+}
@@ -5,7 +5,7 @@ use std::collections::{BTreeMap, VecDeque};
 
 use massa_final_state::StateChanges;
 use massa_hash::Hash;
-use massa_models::address::Address;
+use massa_models::address::{Address, AddressCategory};
 use massa_models::amount::Amount;
 use massa_models::prehash::{CapacityAllocator, PreHashMap};
 use massa_pos_exports::{DeferredCredits, PoSChanges};
@@ -17,8 +17,14 @@ fn test_active_history_deferred_credits() {
     let slot1 = Slot::new(2, 2);
     let slot2 = Slot::new(4, 11);
 
-    let addr1 = Address(Hash::compute_from("A1".as_bytes()));
-    let addr2 = Address(Hash::compute_from("A2".as_bytes()));
+    let addr1 = Address::new(
+        AddressCategory::UserAddress,
+        Hash::compute_from("A1".as_bytes()),
+    );
+    let addr2 = Address::new(
+        AddressCategory::UserAddress,
+        Hash::compute_from("A2".as_bytes()),
+    );
 
     let amount_a1_s1 = Amount::from_raw(500);
     let amount_a2_s1 = Amount::from_raw(2702);
@@ -50,6 +56,8 @@ fn test_active_history_deferred_credits() {
             executed_ops_changes: Default::default(),
         },
         events: Default::default(),
+        execution_trace: Default::default(),
+        transfers: Default::default(),
     };
 
     let active_history = ActiveHistory {
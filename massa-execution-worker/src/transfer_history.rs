@@ -0,0 +1,158 @@
+//! Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! This module indexes the coins sent and received by each address through operations
+//! executed in the recent final slots.
+//!
+//! Failed transfers are indexed too, so that wallets can show them to their users.
+//! Only the transfers of the last `max_slots` final slots are kept: older transfers are dropped.
+
+use massa_execution_exports::ExecutionOutput;
+use massa_models::{
+    address::{Address, AddressTransfer},
+    prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
+};
+use std::collections::VecDeque;
+
+/// Transfers of the addresses in recent final slots
+pub(crate) struct TransferHistory {
+    /// transfers of each address, oldest at the front
+    transfers: PreHashMap<Address, VecDeque<AddressTransfer>>,
+    /// final slots kept, oldest at the front, with the addresses having transfers at that slot
+    slots: VecDeque<(Slot, PreHashSet<Address>)>,
+    /// maximum number of slots kept
+    max_slots: usize,
+}
+
+impl TransferHistory {
+    /// Creates an empty `TransferHistory` keeping at most `max_slots` slots
+    pub fn new(max_slots: usize) -> Self {
+        TransferHistory {
+            transfers: PreHashMap::default(),
+            slots: VecDeque::new(),
+            max_slots,
+        }
+    }
+
+    /// Records the transfers of a final execution output
+    pub fn register_final_output(&mut self, exec_out: &ExecutionOutput) {
+        if self.max_slots == 0 {
+            return;
+        }
+        let mut addresses = PreHashSet::default();
+        for transfer in exec_out.transfers.iter() {
+            let sent = AddressTransfer {
+                slot: exec_out.slot,
+                operation_id: transfer.operation_id,
+                counterpart: transfer.to,
+                amount: transfer.amount,
+                received: false,
+                success: transfer.success,
+            };
+            self.transfers
+                .entry(transfer.from)
+                .or_default()
+                .push_back(sent.clone());
+            addresses.insert(transfer.from);
+            // a transfer to oneself is only listed once
+            if transfer.to != transfer.from {
+                self.transfers
+                    .entry(transfer.to)
+                    .or_default()
+                    .push_back(AddressTransfer {
+                        counterpart: transfer.from,
+                        received: true,
+                        ..sent
+                    });
+                addresses.insert(transfer.to);
+            }
+        }
+        self.slots.push_back((exec_out.slot, addresses));
+        while self.slots.len() > self.max_slots {
+            let Some((slot, addresses)) = self.slots.pop_front() else {
+                break;
+            };
+            for address in addresses {
+                if let Some(transfers) = self.transfers.get_mut(&address) {
+                    while transfers
+                        .front()
+                        .map_or(false, |transfer| transfer.slot <= slot)
+                    {
+                        transfers.pop_front();
+                    }
+                    if transfers.is_empty() {
+                        self.transfers.remove(&address);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Gets a page of the transfers of an address, newest first,
+    /// along with the total number of transfers kept for that address
+    pub fn get_transfers(
+        &self,
+        address: &Address,
+        offset: usize,
+        limit: usize,
+    ) -> (usize, Vec<AddressTransfer>) {
+        match self.transfers.get(address) {
+            Some(transfers) => (
+                transfers.len(),
+                transfers
+                    .iter()
+                    .rev()
+                    .skip(offset)
+                    .take(limit)
+                    .cloned()
+                    .collect(),
+            ),
+            None => (0, Vec::new()),
+        }
+    }
+}
+
+#[test]
+fn test_transfer_history_retention() {
+    use massa_execution_exports::Transfer;
+    use massa_models::{amount::Amount, operation::OperationId};
+    use std::str::FromStr;
+
+    let sender = Address::from_str("A12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let recipient =
+        Address::from_str("A12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+    let mut history = TransferHistory::new(2);
+    for period in 1..=3u64 {
+        let exec_out = ExecutionOutput {
+            slot: Slot::new(period, 0),
+            block_id: None,
+            state_changes: Default::default(),
+            events: Default::default(),
+            execution_trace: Default::default(),
+            transfers: vec![Transfer {
+                operation_id: OperationId::from_bytes(&[period as u8; 32]),
+                from: sender,
+                to: recipient,
+                amount: Amount::from_str(&period.to_string()).unwrap(),
+                success: period != 2,
+            }],
+        };
+        history.register_final_output(&exec_out);
+    }
+
+    // only the last two slots are kept, newest first
+    let (total_count, transfers) = history.get_transfers(&sender, 0, 10);
+    assert_eq!(total_count, 2);
+    assert_eq!(transfers[0].slot, Slot::new(3, 0));
+    assert_eq!(transfers[0].counterpart, recipient);
+    assert!(!transfers[0].received);
+    assert!(!transfers[1].success);
+
+    // both sides of the transfer are indexed
+    let (total_count, transfers) = history.get_transfers(&recipient, 1, 10);
+    assert_eq!(total_count, 2);
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].slot, Slot::new(2, 0));
+    assert_eq!(transfers[0].counterpart, sender);
+    assert!(transfers[0].received);
+}
@@ -10,7 +10,7 @@ use crate::execution::ExecutionState;
 use crate::request_queue::RequestQueue;
 use crate::slot_sequencer::SlotSequencer;
 use massa_execution_exports::{
-    ExecutionConfig, ExecutionController, ExecutionError, ExecutionManager,
+    ExecutionChannels, ExecutionConfig, ExecutionController, ExecutionError, ExecutionManager,
     ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
 };
 use massa_final_state::FinalState;
@@ -22,7 +22,7 @@ use massa_time::MassaTime;
 use parking_lot::{Condvar, Mutex, RwLock};
 use std::sync::Arc;
 use std::thread;
-use tracing::debug;
+use tracing::{debug, info_span};
 
 /// Structure gathering all elements needed by the execution thread
 pub(crate) struct ExecutionThread {
@@ -184,6 +184,14 @@ impl ExecutionThread {
             // ask the slot sequencer for a task to be executed in priority (final is higher priority than candidate)
             let run_result = self.slot_sequencer.run_task_with(
                 |is_final: bool, slot: &Slot, content: Option<&(BlockId, Storage)>| {
+                    let _slot_span = info_span!(
+                        "slot_execution",
+                        period = slot.period,
+                        thread = slot.thread,
+                        block_id = ?content.map(|(block_id, _)| block_id),
+                        is_final
+                    )
+                    .entered();
                     if is_final {
                         self.execution_state.write().execute_final_slot(
                             slot,
@@ -228,6 +236,8 @@ impl ExecutionThread {
 /// # parameters
 /// * `config`: execution configuration
 /// * `final_state`: a thread-safe shared access to the final state for reading and writing
+/// * `selector`: access to the PoS selector
+/// * `channels`: channels used to broadcast the slot execution outputs
 ///
 /// # Returns
 /// A pair `(execution_manager, execution_controller)` where:
@@ -237,11 +247,13 @@ pub fn start_execution_worker(
     config: ExecutionConfig,
     final_state: Arc<RwLock<FinalState>>,
     selector: Box<dyn SelectorController>,
+    channels: ExecutionChannels,
 ) -> (Box<dyn ExecutionManager>, Box<dyn ExecutionController>) {
     // create an execution state
     let execution_state = Arc::new(RwLock::new(ExecutionState::new(
         config.clone(),
         final_state,
+        channels,
     )));
 
     // define the input data interface
@@ -2,6 +2,7 @@
 
 //! This file defines the factory settings
 
+use massa_models::{amount::Amount, api::RollCompoundingMode};
 use massa_time::MassaTime;
 
 /// Structure defining the settings of the factory
@@ -19,9 +20,33 @@ pub struct FactoryConfig {
     /// initial delay before starting production, to avoid double-production on node restart
     pub initial_delay: MassaTime,
 
+    /// how long before its slot the block body is packed; parents and endorsements are selected at the slot
+    pub block_preparation_time: MassaTime,
+
     /// maximal block size in bytes
     pub max_block_size: u64,
 
     /// maximal block gas
     pub max_block_gas: u64,
+
+    /// start in standby mode: keep in sync but withhold block and endorsement production until activation
+    pub standby: bool,
+
+    /// in standby mode, production is activated if the primary node did not send a heartbeat for that long
+    pub standby_heartbeat_timeout: MassaTime,
+
+    /// number of periods per cycle
+    pub periods_per_cycle: u64,
+
+    /// cost (in coins) of a single roll
+    pub roll_price: Amount,
+
+    /// operation validity periods
+    pub operation_validity_periods: u64,
+
+    /// conversion of the deferred credits of the staking addresses into rolls at the start of each cycle
+    pub roll_compounding: RollCompoundingMode,
+
+    /// fee of the roll purchases sent by the roll compounding
+    pub roll_compounding_fee: Amount,
 }
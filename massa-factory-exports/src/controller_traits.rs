@@ -3,8 +3,43 @@
 //! This module exports generic traits representing interfaces for interacting
 //! with the factory worker.
 
+use massa_models::api::RollCompoundingMode;
+
+/// interface that communicates with the factory workers
+pub trait FactoryController: Send + Sync {
+    /// Resumes block and endorsement production (leaves standby mode)
+    fn activate(&self);
+
+    /// Withholds block and endorsement production while the node keeps in sync (enters standby mode)
+    fn standby(&self);
+
+    /// Notifies that the primary node is alive.
+    ///
+    /// In standby mode, once a first heartbeat was received,
+    /// production is activated if no heartbeat is received during `standby_heartbeat_timeout`.
+    fn heartbeat(&self);
+
+    /// Returns `true` if block and endorsement production is withheld
+    fn is_standby(&self) -> bool;
+
+    /// Sets how the deferred credits of the staking addresses are converted into rolls at the start of each cycle
+    fn set_roll_compounding(&self, mode: RollCompoundingMode);
+
+    /// Returns a boxed clone of self.
+    /// Useful to allow cloning `Box<dyn FactoryController>`.
+    fn clone_box(&self) -> Box<dyn FactoryController>;
+}
+
+/// Allow cloning `Box<dyn FactoryController>`
+/// Uses `FactoryController::clone_box` internally
+impl Clone for Box<dyn FactoryController> {
+    fn clone(&self) -> Box<dyn FactoryController> {
+        self.clone_box()
+    }
+}
+
 /// Factory manager used to stop the factory thread
-pub trait FactoryManager {
+pub trait FactoryManager: Send {
     /// Stop the factory thread
     /// Note that we do not take self by value to consume it
     /// because it is not allowed to move out of Box<dyn FactoryManager>
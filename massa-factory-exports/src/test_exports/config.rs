@@ -1,6 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use crate::FactoryConfig;
+use massa_models::{amount::Amount, api::RollCompoundingMode};
 use massa_time::MassaTime;
 
 impl Default for FactoryConfig {
@@ -11,8 +12,16 @@ impl Default for FactoryConfig {
             genesis_timestamp: MassaTime::now().expect("failed to get current time"),
             t0: T0,
             initial_delay: MassaTime::from(0),
+            block_preparation_time: MassaTime::from(0),
             max_block_size: MAX_BLOCK_SIZE as u64,
             max_block_gas: MAX_GAS_PER_BLOCK,
+            standby: false,
+            standby_heartbeat_timeout: MassaTime::from(32000),
+            periods_per_cycle: PERIODS_PER_CYCLE,
+            roll_price: ROLL_PRICE,
+            operation_validity_periods: OPERATION_VALIDITY_PERIODS,
+            roll_compounding: RollCompoundingMode::Disabled,
+            roll_compounding_fee: Amount::zero(),
         }
     }
 }
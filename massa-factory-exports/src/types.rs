@@ -1,4 +1,5 @@
 use massa_consensus_exports::ConsensusController;
+use massa_execution_exports::ExecutionController;
 use massa_models::block::Block;
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
@@ -18,6 +19,8 @@ pub struct FactoryChannels {
     pub consensus: Box<dyn ConsensusController>,
     /// pool controller
     pub pool: Box<dyn PoolController>,
+    /// execution controller, to get the deferred credits of the staking addresses
+    pub execution: Box<dyn ExecutionController>,
     /// protocol controller
     pub protocol: ProtocolCommandSender,
     /// storage instance
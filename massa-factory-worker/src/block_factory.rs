@@ -1,15 +1,19 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::controller::FactoryControllerImpl;
 use massa_factory_exports::{FactoryChannels, FactoryConfig};
 use massa_hash::Hash;
 use massa_models::{
+    address::Address,
     block::{Block, BlockHeader, BlockHeaderSerializer, BlockId, BlockSerializer, WrappedHeader},
     endorsement::WrappedEndorsement,
+    operation::OperationId,
     prehash::PreHashSet,
     slot::Slot,
     timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
     wrapped::WrappedContent,
 };
+use massa_storage::Storage;
 use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
@@ -20,12 +24,25 @@ use std::{
 };
 use tracing::{info, warn};
 
+/// Block body packed ahead of its slot, waiting for parents and endorsements
+struct PreparedBlock {
+    /// address of the drawn block producer
+    producer_addr: Address,
+    /// ids of the operations to include in the block
+    op_ids: Vec<OperationId>,
+    /// hash of the included operations
+    operation_merkle_root: Hash,
+    /// storage holding references to the included operations
+    storage: Storage,
+}
+
 /// Structure gathering all elements needed by the factory thread
 pub(crate) struct BlockFactoryWorker {
     cfg: FactoryConfig,
     wallet: Arc<RwLock<Wallet>>,
     channels: FactoryChannels,
     factory_receiver: mpsc::Receiver<()>,
+    controller: FactoryControllerImpl,
 }
 
 impl BlockFactoryWorker {
@@ -36,6 +53,7 @@ impl BlockFactoryWorker {
         wallet: Arc<RwLock<Wallet>>,
         channels: FactoryChannels,
         factory_receiver: mpsc::Receiver<()>,
+        controller: FactoryControllerImpl,
     ) -> thread::JoinHandle<()> {
         thread::Builder::new()
             .name("block-factory".into())
@@ -45,6 +63,7 @@ impl BlockFactoryWorker {
                     wallet,
                     channels,
                     factory_receiver,
+                    controller,
                 };
                 this.run();
             })
@@ -116,35 +135,69 @@ impl BlockFactoryWorker {
         }
     }
 
-    /// Process a slot: produce a block at that slot if one of the managed keys is drawn.
-    fn process_slot(&mut self, slot: Slot) {
+    /// Prepare the block body of a slot ahead of time if one of the managed keys is drawn.
+    ///
+    /// Operations only depend on the slot, so they can be packed before the best parents are known.
+    fn prepare_block(&mut self, slot: Slot) -> Option<PreparedBlock> {
         // get block producer address for that slot
-        let block_producer_addr = match self.channels.selector.get_producer(slot) {
+        let producer_addr = match self.channels.selector.get_producer(slot) {
             Ok(addr) => addr,
             Err(err) => {
                 warn!(
                     "block factory could not get selector draws for slot {}: {}",
                     slot, err
                 );
-                return;
+                return None;
             }
         };
 
         // check if the block producer address is handled by the wallet
-        let block_producer_keypair_ref = self.wallet.read();
-        let block_producer_keypair = if let Some(kp) =
-            block_producer_keypair_ref.find_associated_keypair(&block_producer_addr)
+        if self
+            .wallet
+            .read()
+            .find_associated_keypair(&producer_addr)
+            .is_none()
         {
-            // the selected block producer is managed locally => continue to attempt block production
-            kp
-        } else {
             // the selected block producer is not managed locally => quit
-            return;
-        };
+            return None;
+        }
+
+        // gather operations and compute global operations hash
+        let (op_ids, storage) = self.channels.pool.get_block_operations(&slot);
+        let operation_merkle_root = Hash::compute_from(
+            &op_ids
+                .iter()
+                .flat_map(|op_id| *op_id.to_bytes())
+                .collect::<Vec<u8>>(),
+        );
+
+        Some(PreparedBlock {
+            producer_addr,
+            op_ids,
+            operation_merkle_root,
+            storage,
+        })
+    }
+
+    /// Finalize a prepared block at its slot: select parents and endorsements, sign and send it to consensus.
+    fn finalize_block(&mut self, slot: Slot, prepared: PreparedBlock) {
+        let PreparedBlock {
+            producer_addr,
+            op_ids,
+            operation_merkle_root,
+            storage: mut block_storage,
+        } = prepared;
+
+        // the keypair may have been removed from the wallet since the preparation
+        let block_producer_keypair_ref = self.wallet.read();
+        let block_producer_keypair =
+            match block_producer_keypair_ref.find_associated_keypair(&producer_addr) {
+                Some(kp) => kp,
+                None => return,
+            };
+
         // get best parents and their periods
         let parents: Vec<(BlockId, u64)> = self.channels.consensus.get_best_parents(); // Vec<(parent_id, parent_period)>
-                                                                                       // generate the local storage object
-        let mut block_storage = self.channels.storage.clone_without_refs();
 
         // claim block parents in local storage
         {
@@ -186,22 +239,12 @@ impl BlockFactoryWorker {
         };
         block_storage.extend(endo_storage);
 
-        // gather operations and compute global operations hash
-        let (op_ids, op_storage) = self.channels.pool.get_block_operations(&slot);
-        block_storage.extend(op_storage);
-        let global_operations_hash = Hash::compute_from(
-            &op_ids
-                .iter()
-                .flat_map(|op_id| *op_id.to_bytes())
-                .collect::<Vec<u8>>(),
-        );
-
         // create header
         let header: WrappedHeader = BlockHeader::new_wrapped::<BlockHeaderSerializer, BlockId>(
             BlockHeader {
                 slot,
                 parents: parents.into_iter().map(|(id, _period)| id).collect(),
-                operation_merkle_root: global_operations_hash,
+                operation_merkle_root,
                 endorsements,
             },
             BlockHeaderSerializer::new(), // TODO reuse self.block_header_serializer
@@ -226,7 +269,7 @@ impl BlockFactoryWorker {
         // log block creation
         info!(
             "block {} created at slot {} by address {}",
-            block_id, slot, block_producer_addr
+            block_id, slot, producer_addr
         );
 
         // send full block to consensus
@@ -242,13 +285,32 @@ impl BlockFactoryWorker {
             // get next slot
             let (slot, block_instant) = self.get_next_slot(prev_slot);
 
+            // wait until it is time to prepare the block of that slot
+            let preparation_instant = block_instant
+                .checked_sub(self.cfg.block_preparation_time.to_duration())
+                .unwrap_or(block_instant);
+            if !self.interruptible_wait_until(preparation_instant) {
+                break;
+            }
+
+            // pack the block body ahead of the slot, unless production is withheld in standby mode
+            let prepared = if self.controller.is_producing() {
+                self.prepare_block(slot)
+            } else {
+                None
+            };
+
             // wait until slot
             if !self.interruptible_wait_until(block_instant) {
                 break;
             }
 
-            // process slot
-            self.process_slot(slot);
+            // finalize and send the block
+            if let Some(prepared) = prepared {
+                if self.controller.is_producing() {
+                    self.finalize_block(slot, prepared);
+                }
+            }
 
             // update previous slot
             prev_slot = Some(slot);
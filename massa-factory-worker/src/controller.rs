@@ -0,0 +1,140 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! This module implements a factory controller.
+//! See `massa-factory-exports/controller_traits.rs` for functional details.
+
+use massa_factory_exports::FactoryController;
+use massa_models::api::RollCompoundingMode;
+use massa_time::MassaTime;
+use parking_lot::RwLock;
+use std::{sync::Arc, time::Instant};
+use tracing::{info, warn};
+
+/// Standby status shared between the controller and the factory workers
+struct StandbyStatus {
+    /// block and endorsement production is withheld
+    standby: bool,
+    /// instant of the last heartbeat received from the primary node, `None` if none was received yet
+    last_heartbeat: Option<Instant>,
+}
+
+/// Implementation of the factory controller
+#[derive(Clone)]
+pub struct FactoryControllerImpl {
+    /// shared standby status
+    status: Arc<RwLock<StandbyStatus>>,
+    /// in standby mode, production is activated if no heartbeat was received for that long
+    heartbeat_timeout: MassaTime,
+    /// shared roll compounding mode
+    roll_compounding: Arc<RwLock<RollCompoundingMode>>,
+}
+
+impl FactoryControllerImpl {
+    /// Creates a new factory controller
+    pub(crate) fn new(
+        standby: bool,
+        heartbeat_timeout: MassaTime,
+        roll_compounding: RollCompoundingMode,
+    ) -> Self {
+        FactoryControllerImpl {
+            status: Arc::new(RwLock::new(StandbyStatus {
+                standby,
+                last_heartbeat: None,
+            })),
+            heartbeat_timeout,
+            roll_compounding: Arc::new(RwLock::new(roll_compounding)),
+        }
+    }
+
+    /// Returns the current roll compounding mode
+    pub(crate) fn roll_compounding(&self) -> RollCompoundingMode {
+        *self.roll_compounding.read()
+    }
+
+    /// Returns `true` if blocks and endorsements can be produced.
+    ///
+    /// In standby mode, production is activated if the primary node
+    /// sent a heartbeat before but did not send one for `heartbeat_timeout`.
+    pub(crate) fn is_producing(&self) -> bool {
+        {
+            let status = self.status.read();
+            if !status.standby {
+                return true;
+            }
+            let Some(last_heartbeat) = status.last_heartbeat else {
+                // no heartbeat received yet: wait for an explicit activation
+                return false;
+            };
+            if last_heartbeat.elapsed() < self.heartbeat_timeout.to_duration() {
+                return false;
+            }
+        }
+        let mut status = self.status.write();
+        if status.standby {
+            warn!(
+                "no heartbeat received from the primary node for {}: activating block and endorsement production",
+                self.heartbeat_timeout
+            );
+            status.standby = false;
+        }
+        true
+    }
+}
+
+impl FactoryController for FactoryControllerImpl {
+    fn activate(&self) {
+        let mut status = self.status.write();
+        if status.standby {
+            info!("activating block and endorsement production");
+            status.standby = false;
+        }
+    }
+
+    fn standby(&self) {
+        let mut status = self.status.write();
+        if !status.standby {
+            info!("entering standby mode: withholding block and endorsement production");
+            status.standby = true;
+            // a new heartbeat is required before the node can activate itself again
+            status.last_heartbeat = None;
+        }
+    }
+
+    fn heartbeat(&self) {
+        self.status.write().last_heartbeat = Some(Instant::now());
+    }
+
+    fn is_standby(&self) -> bool {
+        self.status.read().standby
+    }
+
+    fn set_roll_compounding(&self, mode: RollCompoundingMode) {
+        info!("setting roll compounding mode to {}", mode);
+        *self.roll_compounding.write() = mode;
+    }
+
+    fn clone_box(&self) -> Box<dyn FactoryController> {
+        Box::new(self.clone())
+    }
+}
+
+#[test]
+fn test_standby_heartbeat_timeout() {
+    let controller = FactoryControllerImpl::new(
+        true,
+        MassaTime::from_millis(0),
+        RollCompoundingMode::Disabled,
+    );
+
+    // without heartbeat, the node waits for an explicit activation
+    assert!(!controller.is_producing());
+
+    // once the primary node stopped sending heartbeats, production is activated
+    controller.heartbeat();
+    assert!(controller.is_producing());
+    assert!(!controller.is_standby());
+
+    // entering standby again requires a new heartbeat before self-activation
+    controller.standby();
+    assert!(!controller.is_producing());
+}
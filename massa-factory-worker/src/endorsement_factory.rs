@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::controller::FactoryControllerImpl;
 use massa_factory_exports::{FactoryChannels, FactoryConfig};
 use massa_models::{
     block::BlockId,
@@ -25,6 +26,7 @@ pub(crate) struct EndorsementFactoryWorker {
     wallet: Arc<RwLock<Wallet>>,
     channels: FactoryChannels,
     factory_receiver: mpsc::Receiver<()>,
+    controller: FactoryControllerImpl,
     half_t0: MassaTime,
     endorsement_serializer: EndorsementSerializer,
 }
@@ -37,6 +39,7 @@ impl EndorsementFactoryWorker {
         wallet: Arc<RwLock<Wallet>>,
         channels: FactoryChannels,
         factory_receiver: mpsc::Receiver<()>,
+        controller: FactoryControllerImpl,
     ) -> thread::JoinHandle<()> {
         thread::Builder::new()
             .name("endorsement-factory".into())
@@ -50,6 +53,7 @@ impl EndorsementFactoryWorker {
                     wallet,
                     channels,
                     factory_receiver,
+                    controller,
                     endorsement_serializer: EndorsementSerializer::new(),
                 };
                 this.run();
@@ -213,8 +217,10 @@ impl EndorsementFactoryWorker {
                 break;
             }
 
-            // process slot
-            self.process_slot(slot);
+            // process slot, unless production is withheld in standby mode
+            if self.controller.is_producing() {
+                self.process_slot(slot);
+            }
 
             // update previous slot
             prev_slot = Some(slot);
@@ -3,8 +3,10 @@
 #![feature(deadline_api)]
 
 mod block_factory;
+mod controller;
 mod endorsement_factory;
 mod manager;
+mod roll_compounding;
 mod run;
 
 pub use run::start_factory;
@@ -16,6 +16,9 @@ pub struct FactoryManagerImpl {
 
     /// endorsement worker message sender and join handle
     pub(crate) endorsement_worker: Option<(mpsc::Sender<()>, JoinHandle<()>)>,
+
+    /// roll compounding worker message sender and join handle
+    pub(crate) roll_compounding_worker: Option<(mpsc::Sender<()>, JoinHandle<()>)>,
 }
 
 impl FactoryManager for FactoryManagerImpl {
@@ -34,6 +37,12 @@ impl FactoryManager for FactoryManagerImpl {
                 warn!("endorsement factory worker panicked: {:?}", err);
             }
         }
+        if let Some((chan_tx, join_handle)) = self.roll_compounding_worker.take() {
+            std::mem::drop(chan_tx);
+            if let Err(err) = join_handle.join() {
+                warn!("roll compounding worker panicked: {:?}", err);
+            }
+        }
         info!("factory stopped");
     }
 }
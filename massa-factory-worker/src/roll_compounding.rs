@@ -0,0 +1,343 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use crate::controller::FactoryControllerImpl;
+use massa_factory_exports::{FactoryChannels, FactoryConfig};
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    api::RollCompoundingMode,
+    operation::OperationId,
+    prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
+    timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
+};
+use massa_time::MassaTime;
+use massa_wallet::Wallet;
+use parking_lot::RwLock;
+use std::{
+    collections::BTreeMap,
+    sync::{mpsc, Arc},
+    thread,
+    time::Instant,
+};
+use tracing::{info, warn};
+
+/// Structure gathering all elements needed by the roll compounding thread
+pub(crate) struct RollCompoundingWorker {
+    cfg: FactoryConfig,
+    wallet: Arc<RwLock<Wallet>>,
+    channels: FactoryChannels,
+    factory_receiver: mpsc::Receiver<()>,
+    controller: FactoryControllerImpl,
+    /// deferred credits of the staking addresses that were not paid yet at the previous cycle start
+    pending_credits: PreHashMap<Address, BTreeMap<Slot, Amount>>,
+    /// paid deferred credits of the staking addresses that were not converted into rolls yet
+    accumulated_credits: PreHashMap<Address, Amount>,
+    /// roll purchases sent to the pool that may still be included, by buying address
+    pending_roll_buys: PreHashMap<Address, PendingRollBuy>,
+}
+
+/// Roll purchase sent to the pool, whose cost is deducted from the accumulated credits once included
+struct PendingRollBuy {
+    /// id of the `RollBuy` operation
+    op_id: OperationId,
+    /// number of rolls bought
+    roll_count: u64,
+    /// last period at which the operation can be included
+    expire_period: u64,
+}
+
+impl RollCompoundingWorker {
+    /// Creates the `RollCompoundingWorker` structure to gather all data and references
+    /// needed by the roll compounding thread.
+    pub(crate) fn spawn(
+        cfg: FactoryConfig,
+        wallet: Arc<RwLock<Wallet>>,
+        channels: FactoryChannels,
+        factory_receiver: mpsc::Receiver<()>,
+        controller: FactoryControllerImpl,
+    ) -> thread::JoinHandle<()> {
+        thread::Builder::new()
+            .name("roll-compounding".into())
+            .spawn(|| {
+                let mut this = Self {
+                    cfg,
+                    wallet,
+                    channels,
+                    factory_receiver,
+                    controller,
+                    pending_credits: Default::default(),
+                    accumulated_credits: Default::default(),
+                    pending_roll_buys: Default::default(),
+                };
+                this.run();
+            })
+            .expect("failed to spawn thread : roll-compounding")
+    }
+
+    /// Gets the next cycle and the instant when its first slot will happen.
+    /// The `previous_cycle` parameter protects against processing a cycle twice on clock adjustments.
+    fn get_next_cycle(&self, previous_cycle: Option<u64>) -> (u64, Instant) {
+        // get current absolute time
+        let now = MassaTime::now().expect("could not get current time");
+
+        // get the cycle following the one of the closest slot
+        let mut next_cycle = get_closest_slot_to_timestamp(
+            self.cfg.thread_count,
+            self.cfg.t0,
+            self.cfg.genesis_timestamp,
+            now,
+        )
+        .get_cycle(self.cfg.periods_per_cycle)
+        .saturating_add(1);
+        if let Some(prev_cycle) = previous_cycle {
+            if next_cycle <= prev_cycle {
+                next_cycle = prev_cycle.saturating_add(1);
+            }
+        }
+
+        // get the timestamp of the first slot of that cycle
+        let next_instant = get_block_slot_timestamp(
+            self.cfg.thread_count,
+            self.cfg.t0,
+            self.cfg.genesis_timestamp,
+            self.get_cycle_start(next_cycle),
+        )
+        .expect("could not get cycle start timestamp")
+        .estimate_instant()
+        .expect("could not estimate cycle start instant");
+
+        (next_cycle, next_instant)
+    }
+
+    /// Gets the first slot of a cycle
+    fn get_cycle_start(&self, cycle: u64) -> Slot {
+        Slot::new_first_of_cycle(cycle, self.cfg.periods_per_cycle)
+            .expect("could not compute cycle start slot")
+    }
+
+    /// Wait and interrupt or wait until an instant or a stop signal
+    ///
+    /// # Return value
+    /// Returns `true` if the instant was reached, otherwise `false` if there was an interruption.
+    fn interruptible_wait_until(&self, deadline: Instant) -> bool {
+        match self.factory_receiver.recv_deadline(deadline) {
+            // message received => quit main loop
+            Ok(()) => false,
+            // timeout => continue main loop
+            Err(mpsc::RecvTimeoutError::Timeout) => true,
+            // channel disconnected (sender dropped) => quit main loop
+            Err(mpsc::RecvTimeoutError::Disconnected) => false,
+        }
+    }
+
+    /// Gets the instant after which none of the pending roll purchases can be included anymore
+    fn get_roll_buys_settlement_instant(&self) -> Option<Instant> {
+        self.pending_roll_buys
+            .iter()
+            .map(|(address, buy)| {
+                let slot = Slot::new(
+                    buy.expire_period.saturating_add(1),
+                    address.get_thread(self.cfg.thread_count),
+                );
+                get_block_slot_timestamp(
+                    self.cfg.thread_count,
+                    self.cfg.t0,
+                    self.cfg.genesis_timestamp,
+                    slot,
+                )
+                .expect("could not get roll purchase expiry timestamp")
+                .estimate_instant()
+                .expect("could not estimate roll purchase expiry instant")
+            })
+            .max()
+    }
+
+    /// Settles the pending roll purchases that can no longer be included:
+    /// the cost of the included ones is deducted from the accumulated credits,
+    /// while the credits of the expired ones are kept for the next purchase.
+    ///
+    /// Executed operations are only tracked until their expiry slot is final,
+    /// so this must be called shortly after the purchases expire.
+    fn settle_roll_buys(&mut self) {
+        let now = MassaTime::now().expect("could not get current time");
+        let current_period = get_closest_slot_to_timestamp(
+            self.cfg.thread_count,
+            self.cfg.t0,
+            self.cfg.genesis_timestamp,
+            now,
+        )
+        .period;
+        let settled: Vec<Address> = self
+            .pending_roll_buys
+            .iter()
+            .filter(|(_, buy)| buy.expire_period < current_period)
+            .map(|(address, _)| *address)
+            .collect();
+        for address in settled {
+            let buy = self
+                .pending_roll_buys
+                .remove(&address)
+                .expect("pending roll purchase should exist");
+            let ops: PreHashSet<OperationId> = std::iter::once(buy.op_id).collect();
+            let included = self
+                .channels
+                .execution
+                .unexecuted_ops_among(&ops, address.get_thread(self.cfg.thread_count))
+                .is_empty();
+            if included {
+                let accumulated = self.accumulated_credits.entry(address).or_default();
+                *accumulated = accumulated
+                    .saturating_sub(self.cfg.roll_price.saturating_mul_u64(buy.roll_count));
+            } else {
+                info!(
+                    "roll compounding: roll purchase {} of address {} expired without being included",
+                    buy.op_id, address
+                );
+            }
+        }
+    }
+
+    /// Process a cycle start: buy rolls with the deferred credits paid to the staking addresses since the previous cycle start.
+    fn process_cycle(&mut self, cycle: u64, mode: RollCompoundingMode) {
+        let addresses: Vec<Address> = self
+            .wallet
+            .read()
+            .get_wallet_address_list()
+            .into_iter()
+            .collect();
+        if addresses.is_empty() || self.cfg.roll_price.is_zero() {
+            return;
+        }
+        let infos = self.channels.execution.get_addresses_infos(&addresses);
+        let expire_period = self
+            .get_cycle_start(cycle)
+            .period
+            .saturating_add(self.cfg.operation_validity_periods);
+
+        let mut roll_buys = Vec::new();
+        {
+            let wallet = self.wallet.read();
+            for (address, info) in addresses.into_iter().zip(infos) {
+                // the credits that were pending at the previous cycle start and are not anymore were paid
+                let previous_credits = self
+                    .pending_credits
+                    .insert(address, info.future_deferred_credits.clone())
+                    .unwrap_or_default();
+                let paid_credits = previous_credits
+                    .iter()
+                    .filter(|(slot, _)| !info.future_deferred_credits.contains_key(slot))
+                    .fold(Amount::zero(), |acc, (_, amount)| {
+                        acc.saturating_add(*amount)
+                    });
+                let accumulated = self.accumulated_credits.entry(address).or_default();
+                *accumulated = accumulated.saturating_add(paid_credits);
+
+                // wait for the previous purchase to be settled before spending the credits again
+                if self.pending_roll_buys.contains_key(&address) {
+                    continue;
+                }
+
+                // do not spend more than the available balance
+                let roll_count = std::cmp::min(
+                    accumulated
+                        .checked_div(self.cfg.roll_price)
+                        .unwrap_or_default(),
+                    info.candidate_balance
+                        .saturating_sub(self.cfg.roll_compounding_fee)
+                        .checked_div(self.cfg.roll_price)
+                        .unwrap_or_default(),
+                );
+                if roll_count == 0 {
+                    continue;
+                }
+
+                if mode == RollCompoundingMode::DryRun {
+                    info!(
+                        "roll compounding (dry run): address {} would buy {} roll(s) at cycle {}",
+                        address, roll_count, cycle
+                    );
+                    continue;
+                }
+                match wallet
+                    .operation_builder(address, self.cfg.roll_compounding_fee, expire_period)
+                    .roll_buy(roll_count)
+                {
+                    Ok(op) => {
+                        info!(
+                            "roll compounding: address {} buys {} roll(s) at cycle {} with operation {}",
+                            address, roll_count, cycle, op.id
+                        );
+                        self.pending_roll_buys.insert(
+                            address,
+                            PendingRollBuy {
+                                op_id: op.id,
+                                roll_count,
+                                expire_period,
+                            },
+                        );
+                        roll_buys.push(op);
+                    }
+                    Err(err) => warn!(
+                        "roll compounding could not create a roll purchase for address {}: {}",
+                        address, err
+                    ),
+                }
+            }
+        }
+
+        // quit if there is nothing to send
+        if roll_buys.is_empty() {
+            return;
+        }
+
+        // store operations
+        let mut op_storage = self.channels.storage.clone_without_refs();
+        op_storage.store_operations(roll_buys);
+
+        // send operations to pool for listing and propagation
+        self.channels.pool.add_operations(op_storage.clone());
+
+        if let Err(err) = self.channels.protocol.propagate_operations(op_storage) {
+            warn!("could not propagate roll purchases to protocol: {}", err);
+        }
+    }
+
+    /// main run loop of the roll compounding thread
+    fn run(&mut self) {
+        let mut prev_cycle = None;
+        loop {
+            // get next cycle
+            let (cycle, cycle_instant) = self.get_next_cycle(prev_cycle);
+
+            // settle the pending roll purchases if they expire before the start of the cycle
+            if let Some(settlement_instant) = self.get_roll_buys_settlement_instant() {
+                if settlement_instant < cycle_instant {
+                    if !self.interruptible_wait_until(settlement_instant) {
+                        break;
+                    }
+                    self.settle_roll_buys();
+                }
+            }
+
+            // wait until the start of the cycle
+            if !self.interruptible_wait_until(cycle_instant) {
+                break;
+            }
+
+            // process cycle, forgetting the tracked credits while disabled
+            self.settle_roll_buys();
+            match self.controller.roll_compounding() {
+                RollCompoundingMode::Disabled => {
+                    self.pending_credits.clear();
+                    self.accumulated_credits.clear();
+                    self.pending_roll_buys.clear();
+                }
+                mode => self.process_cycle(cycle, mode),
+            }
+
+            // update previous cycle
+            prev_cycle = Some(cycle);
+        }
+    }
+}
@@ -4,10 +4,11 @@ use parking_lot::RwLock;
 use std::sync::{mpsc, Arc};
 
 use crate::{
-    block_factory::BlockFactoryWorker, endorsement_factory::EndorsementFactoryWorker,
-    manager::FactoryManagerImpl,
+    block_factory::BlockFactoryWorker, controller::FactoryControllerImpl,
+    endorsement_factory::EndorsementFactoryWorker, manager::FactoryManagerImpl,
+    roll_compounding::RollCompoundingWorker,
 };
-use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
+use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryController, FactoryManager};
 use massa_wallet::Wallet;
 
 /// Start factory
@@ -18,35 +19,62 @@ use massa_wallet::Wallet;
 /// * `channels`: channels to communicate with other modules
 ///
 /// # Return value
-/// Returns a factory manager allowing to stop the workers cleanly.
+/// Returns a factory manager allowing to stop the workers cleanly,
+/// and a factory controller allowing to switch production on and off.
 pub fn start_factory(
     cfg: FactoryConfig,
     wallet: Arc<RwLock<Wallet>>,
     channels: FactoryChannels,
-) -> Box<dyn FactoryManager> {
+) -> (Box<dyn FactoryManager>, Box<dyn FactoryController>) {
+    // create the controller shared by the workers
+    let controller = FactoryControllerImpl::new(
+        cfg.standby,
+        cfg.standby_heartbeat_timeout,
+        cfg.roll_compounding,
+    );
+
     // create block factory channel
     let (block_worker_tx, block_worker_rx) = mpsc::channel::<()>();
 
     // create endorsement factory channel
     let (endorsement_worker_tx, endorsement_worker_rx) = mpsc::channel::<()>();
 
+    // create roll compounding channel
+    let (roll_compounding_worker_tx, roll_compounding_worker_rx) = mpsc::channel::<()>();
+
     // start block factory worker
     let block_worker_handle = BlockFactoryWorker::spawn(
         cfg.clone(),
         wallet.clone(),
         channels.clone(),
         block_worker_rx,
+        controller.clone(),
     );
 
     // start endorsement factory worker
-    let endorsement_worker_handle =
-        EndorsementFactoryWorker::spawn(cfg, wallet, channels, endorsement_worker_rx);
+    let endorsement_worker_handle = EndorsementFactoryWorker::spawn(
+        cfg.clone(),
+        wallet.clone(),
+        channels.clone(),
+        endorsement_worker_rx,
+        controller.clone(),
+    );
+
+    // start roll compounding worker
+    let roll_compounding_worker_handle = RollCompoundingWorker::spawn(
+        cfg,
+        wallet,
+        channels,
+        roll_compounding_worker_rx,
+        controller.clone(),
+    );
 
     // create factory manager
     let manager = FactoryManagerImpl {
         block_worker: Some((block_worker_tx, block_worker_handle)),
         endorsement_worker: Some((endorsement_worker_tx, endorsement_worker_handle)),
+        roll_compounding_worker: Some((roll_compounding_worker_tx, roll_compounding_worker_handle)),
     };
 
-    Box::new(manager)
+    (Box::new(manager), Box::new(controller))
 }
@@ -8,6 +8,7 @@ use std::{
     time::Duration,
 };
 
+use massa_execution_exports::test_exports::MockExecutionController;
 use massa_factory_exports::{
     test_exports::create_empty_block, FactoryChannels, FactoryConfig, FactoryManager,
 };
@@ -58,6 +59,8 @@ impl TestFactory {
         let (consensus_controller, consensus_event_receiver) =
             MockConsensusController::new_with_receiver();
         let (pool_controller, pool_receiver) = MockPoolController::new_with_receiver();
+        let (execution_controller, _execution_receiver) =
+            MockExecutionController::new_with_receiver();
         let mut storage = Storage::create_root();
         let mut factory_config = FactoryConfig::default();
         let (_protocol_controller, protocol_command_sender) = MockProtocolController::new();
@@ -78,13 +81,14 @@ impl TestFactory {
             .genesis_timestamp
             .checked_sub(factory_config.t0)
             .unwrap();
-        let factory_manager = start_factory(
+        let (factory_manager, _factory_controller) = start_factory(
             factory_config.clone(),
             Arc::new(RwLock::new(create_test_wallet(Some(accounts)))),
             FactoryChannels {
                 selector: selector_controller.clone(),
                 consensus: consensus_controller,
                 pool: pool_controller.clone(),
+                execution: execution_controller,
                 protocol: protocol_command_sender,
                 storage: storage.clone_without_refs(),
             },
@@ -151,6 +155,26 @@ impl TestFactory {
                 _ => panic!("unexpected message"),
             }
         }
+        self.pool_receiver
+            .wait_command(MassaTime::from_millis(100), |command| match command {
+                MockPoolControllerMessage::GetBlockOperations {
+                    slot: _,
+                    response_tx,
+                } => {
+                    if let Some(operations) = &operations {
+                        let ids = operations.iter().map(|op| op.id).collect();
+                        let mut storage = self.storage.clone_without_refs();
+                        storage.store_operations(operations.clone());
+                        response_tx.send((ids, storage.clone())).unwrap();
+                        Some(())
+                    } else {
+                        response_tx.send((vec![], Storage::create_root())).unwrap();
+                        Some(())
+                    }
+                }
+                _ => panic!("unexpected message"),
+            })
+            .unwrap();
         self.consensus_event_receiver
             .wait_command(MassaTime::from_millis(100), |command| {
                 if let MockConsensusControllerMessage::GetBestParents { response_tx } = command {
@@ -183,26 +207,6 @@ impl TestFactory {
             })
             .unwrap();
 
-        self.pool_receiver
-            .wait_command(MassaTime::from_millis(100), |command| match command {
-                MockPoolControllerMessage::GetBlockOperations {
-                    slot: _,
-                    response_tx,
-                } => {
-                    if let Some(operations) = &operations {
-                        let ids = operations.iter().map(|op| op.id).collect();
-                        let mut storage = self.storage.clone_without_refs();
-                        storage.store_operations(operations.clone());
-                        response_tx.send((ids, storage.clone())).unwrap();
-                        Some(())
-                    } else {
-                        response_tx.send((vec![], Storage::create_root())).unwrap();
-                        Some(())
-                    }
-                }
-                _ => panic!("unexpected message"),
-            })
-            .unwrap();
         self.consensus_event_receiver
             .wait_command(MassaTime::from_millis(100), |command| {
                 if let MockConsensusControllerMessage::RegisterBlock {
@@ -29,4 +29,8 @@ pub struct FinalStateConfig {
     pub initial_seed_string: String,
     /// initial rolls file path
     pub initial_rolls_path: PathBuf,
+    /// initial deferred credits file path, if any
+    pub initial_deferred_credits_path: Option<PathBuf>,
+    /// final state snapshot to start from instead of the initial ledger and rolls, if any
+    pub initial_snapshot_path: Option<PathBuf>,
 }
@@ -15,4 +15,8 @@ pub enum FinalStateError {
     LedgerError(String),
     /// PoS error: {0}
     PosError(String),
+    /// snapshot error: {0}
+    SnapshotError(String),
+    /// trace error: {0}
+    TraceError(String),
 }
@@ -10,9 +10,13 @@ use massa_async_pool::{AsyncMessageId, AsyncPool, AsyncPoolChanges, Change};
 use massa_executed_ops::ExecutedOps;
 use massa_hash::{Hash, HASH_SIZE_BYTES};
 use massa_ledger_exports::{get_address_from_key, LedgerChanges, LedgerController};
-use massa_models::{slot::Slot, streaming_step::StreamingStep};
-use massa_pos_exports::{DeferredCredits, PoSFinalState, SelectorController};
-use std::collections::VecDeque;
+use massa_models::{
+    address::Address, config::GenesisDeferredCredit, ledger_proof::FinalLedgerEntryProof,
+    slot::Slot, streaming_step::StreamingStep,
+};
+use massa_pos_exports::{CycleHistoryCursor, DeferredCredits, PoSFinalState, SelectorController};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
 use tracing::{debug, info};
 
 /// Represents a final state `(ledger, async pool, executed_ops and the state of the PoS)`
@@ -34,6 +38,8 @@ pub struct FinalState {
     pub changes_history: VecDeque<(Slot, StateChanges)>,
     /// hash of the final state, it is computed on finality
     pub final_state_hash: Hash,
+    /// paths of the snapshots to write once their slot is final, by slot
+    pub(crate) snapshot_requests: BTreeMap<Slot, Vec<PathBuf>>,
 }
 
 const FINAL_STATE_HASH_INITIAL_BYTES: &[u8; 32] = &[0; HASH_SIZE_BYTES];
@@ -77,26 +83,80 @@ impl FinalState {
             executed_ops,
             changes_history: Default::default(), // no changes in history
             final_state_hash: Hash::from_bytes(FINAL_STATE_HASH_INITIAL_BYTES),
+            snapshot_requests: Default::default(),
         })
     }
 
-    /// Compute the current state hash.
+    /// Loads the state of a node starting from scratch.
     ///
-    /// Used when finalizing a slot.
-    /// Slot information is only used for logging.
-    pub fn compute_state_hash_at_slot(&mut self, slot: Slot) {
+    /// If a snapshot is configured, the whole state is loaded from it.
+    /// Otherwise the initial ledger and deferred credits are loaded and the initial PoS cycle is created.
+    pub fn load_initial_state(&mut self) -> Result<(), FinalStateError> {
+        if let Some(snapshot_path) = self.config.initial_snapshot_path.clone() {
+            return self.import_snapshot(&snapshot_path);
+        }
+        self.ledger
+            .load_initial_ledger()
+            .map_err(|err| FinalStateError::LedgerError(err.to_string()))?;
+        if let Some(credits_path) = self.config.initial_deferred_credits_path.clone() {
+            self.load_initial_deferred_credits(&credits_path)?;
+        }
+        self.pos_state.create_initial_cycle();
+        Ok(())
+    }
+
+    /// Loads the initial deferred credits from a JSON list of `GenesisDeferredCredit`
+    fn load_initial_deferred_credits(&mut self, path: &Path) -> Result<(), FinalStateError> {
+        let initial_credits: Vec<GenesisDeferredCredit> = std::fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|content| serde_json::from_str(&content).map_err(|err| err.to_string()))
+            .map_err(|err| {
+                FinalStateError::PosError(format!(
+                    "could not load the initial deferred credits {:?}: {}",
+                    path, err
+                ))
+            })?;
+        let mut credits = DeferredCredits::default();
+        for credit in initial_credits {
+            let amount = credits
+                .get_address_deferred_credit_for_slot(&credit.address, &credit.slot)
+                .unwrap_or_default()
+                .checked_add(credit.amount)
+                .ok_or_else(|| {
+                    FinalStateError::PosError(format!(
+                        "overflow in the initial deferred credits of {} at slot {}",
+                        credit.address, credit.slot
+                    ))
+                })?;
+            credits.insert(credit.address, credit.slot, amount);
+        }
+        self.pos_state.set_deferred_credits_part(credits);
+        Ok(())
+    }
+
+    /// Gets the snapshot to load as the initial state of the node, if one is configured.
+    pub fn get_initial_snapshot_path(&self) -> Option<&Path> {
+        self.config.initial_snapshot_path.as_deref()
+    }
+
+    /// Concatenation of the hashes of the final state components preceding the ledger Merkle root
+    /// in the computation of the final state hash.
+    fn get_state_hash_prefix(&self) -> Vec<u8> {
         // 1. init hash concatenation with the ledger hash
         let ledger_hash = self.ledger.get_ledger_hash();
         let mut hash_concat: Vec<u8> = ledger_hash.to_bytes().to_vec();
-        debug!("ledger hash at slot {}: {}", slot, ledger_hash);
+        debug!("ledger hash at slot {}: {}", self.slot, ledger_hash);
         // 2. async_pool hash
         hash_concat.extend(self.async_pool.hash.to_bytes());
-        debug!("async_pool hash at slot {}: {}", slot, self.async_pool.hash);
+        debug!(
+            "async_pool hash at slot {}: {}",
+            self.slot, self.async_pool.hash
+        );
         // 3. pos deferred_credit hash
         hash_concat.extend(self.pos_state.deferred_credits.hash.to_bytes());
         debug!(
             "deferred_credit hash at slot {}: {}",
-            slot, self.pos_state.deferred_credits.hash
+            self.slot, self.pos_state.deferred_credits.hash
         );
         // 4. pos cycle history hashes, skip the bootstrap safety cycle if there is one
         let n = (self.pos_state.cycle_history.len() == self.config.pos_config.cycle_history_length)
@@ -105,17 +165,64 @@ impl FinalState {
             hash_concat.extend(cycle_info.cycle_global_hash.to_bytes());
             debug!(
                 "cycle ({}) hash at slot {}: {}",
-                cycle_info.cycle, slot, cycle_info.cycle_global_hash
+                cycle_info.cycle, self.slot, cycle_info.cycle_global_hash
             );
         }
         // 5. executed operations hash
         hash_concat.extend(self.executed_ops.hash.to_bytes());
         debug!(
             "executed_ops hash at slot {}: {}",
-            slot, self.executed_ops.hash
+            self.slot, self.executed_ops.hash
         );
-        // 6. compute and save final state hash
-        self.final_state_hash = Hash::compute_from(&hash_concat);
+        hash_concat
+    }
+
+    /// Compute the current state hash by combining the incrementally maintained hashes
+    /// of the ledger, async pool, PoS state and executed operations.
+    pub fn get_state_hash(&self) -> Hash {
+        let mut hash_concat = self.get_state_hash_prefix();
+        // 6. ledger Merkle root
+        let ledger_root = self.ledger.get_ledger_root();
+        hash_concat.extend(ledger_root.to_bytes());
+        debug!("ledger root at slot {}: {}", self.slot, ledger_root);
+        // 7. compute final state hash
+        Hash::compute_from(&hash_concat)
+    }
+
+    /// Get the proof of the balance (if `datastore_key` is `None`) or of a datastore entry of an address
+    /// against the current state hash.
+    pub fn get_ledger_entry_proof(
+        &self,
+        address: Address,
+        datastore_key: Option<Vec<u8>>,
+    ) -> FinalLedgerEntryProof {
+        let state_hash_prefix = self.get_state_hash_prefix();
+        let ledger_proof = self
+            .ledger
+            .get_entry_proof(&address, datastore_key.as_deref());
+        let final_state_hash = Hash::compute_from(
+            &[
+                state_hash_prefix.as_slice(),
+                self.ledger.get_ledger_root().to_bytes(),
+            ]
+            .concat(),
+        );
+        FinalLedgerEntryProof {
+            address,
+            datastore_key,
+            slot: self.slot,
+            final_state_hash,
+            state_hash_prefix,
+            ledger_proof,
+        }
+    }
+
+    /// Compute and save the current state hash.
+    ///
+    /// Used when finalizing a slot.
+    /// Slot information is only used for logging.
+    pub fn compute_state_hash_at_slot(&mut self, slot: Slot) {
+        self.final_state_hash = self.get_state_hash();
         info!(
             "final_state hash at slot {}: {}",
             slot, self.final_state_hash
@@ -176,6 +283,9 @@ impl FinalState {
         let cycle = slot.get_cycle(self.config.periods_per_cycle);
         self.pos_state
             .feed_cycle_state_hash(cycle, self.final_state_hash);
+
+        // write the snapshots requested at this slot
+        self.export_requested_snapshots();
     }
 
     /// Used for bootstrap.
@@ -196,7 +306,7 @@ impl FinalState {
         slot: Slot,
         ledger_step: StreamingStep<Vec<u8>>,
         pool_step: StreamingStep<AsyncMessageId>,
-        cycle_step: StreamingStep<u64>,
+        cycle_step: StreamingStep<CycleHistoryCursor>,
         credits_step: StreamingStep<Slot>,
         ops_step: StreamingStep<Slot>,
     ) -> Result<Vec<(Slot, StateChanges)>, FinalStateError> {
@@ -281,7 +391,8 @@ impl FinalState {
                 _ => (),
             }
 
-            // Get PoS deferred credits changes that concern credits <= credits_step
+            // Get PoS deferred credits changes that concern credits >= credits_step
+            // note: deferred credits are streamed from the newest slot to the oldest one
             match credits_step {
                 StreamingStep::Ongoing(cursor_slot) => {
                     let deferred_credits = DeferredCredits {
@@ -291,7 +402,7 @@ impl FinalState {
                             .credits
                             .iter()
                             .filter_map(|(credits_slot, credits)| {
-                                if *credits_slot <= cursor_slot {
+                                if *credits_slot >= cursor_slot {
                                     Some((*credits_slot, credits.clone()))
                                 } else {
                                     None
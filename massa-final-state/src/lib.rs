@@ -25,6 +25,10 @@
 //! Defines a structure to list and prune previously executed operations.
 //! Used to detect operation reuse.
 //!
+//! ## `snapshot.rs`
+//! Exports the final state to a versioned snapshot file and loads it back,
+//! at a given final slot, for offline analysis or to start a network before its genesis.
+//!
 //! ## `bootstrap.rs`
 //! Provides serializable structures and tools for bootstrapping the final state.
 //!
@@ -42,12 +46,18 @@
 mod config;
 mod error;
 mod final_state;
+mod snapshot;
 mod state_changes;
+mod trace;
 
 pub use config::FinalStateConfig;
 pub use error::FinalStateError;
 pub use final_state::FinalState;
 pub use state_changes::{StateChanges, StateChangesDeserializer, StateChangesSerializer};
+pub use trace::{
+    read_trace, ExecutionTrace, FinalStateHashes, SlotTrace, SlotTraceDeserializer,
+    SlotTraceSerializer, TraceReplayOutcome, TraceWriter,
+};
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,367 @@
+//! Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Final state snapshots.
+//!
+//! A snapshot is a versioned file containing the final ledger, the asynchronous pool,
+//! the proof-of-stake final state (cycle history, deferred credits, initial rolls)
+//! and the executed operations at a given final slot.
+//! It is written once the requested slot is final, streaming the components to the file part by part.
+//! It can be used for offline analysis or to start a network from it before genesis.
+
+use crate::{error::FinalStateError, final_state::FinalState};
+use massa_async_pool::{AsyncPoolDeserializer, AsyncPoolSerializer};
+use massa_executed_ops::{ExecutedOpsDeserializer, ExecutedOpsSerializer};
+use massa_models::{
+    address::{Address, AddressDeserializer, AddressSerializer},
+    serialization::{VecU8Deserializer, VecU8Serializer},
+    slot::{Slot, SlotDeserializer, SlotSerializer},
+    streaming_step::StreamingStep,
+};
+use massa_pos_exports::{
+    CycleInfoDeserializer, CycleInfoSerializer, DeferredCreditsDeserializer,
+    DeferredCreditsSerializer,
+};
+use massa_serialization::{
+    DeserializeError, Deserializer, SerializeError, Serializer, U32VarIntDeserializer,
+    U32VarIntSerializer, U64VarIntDeserializer, U64VarIntSerializer,
+};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::ops::Bound::{Excluded, Included};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Bytes starting every snapshot file
+const SNAPSHOT_MAGIC: &[u8] = b"MASSA_SNAPSHOT";
+
+/// Version of the snapshot format, to be incremented on every format change
+const SNAPSHOT_VERSION: u32 = 1;
+
+fn ser_error(err: SerializeError) -> FinalStateError {
+    FinalStateError::SnapshotError(format!("serialization error: {}", err))
+}
+
+fn deser_error(section: &str, err: nom::Err<DeserializeError>) -> FinalStateError {
+    FinalStateError::SnapshotError(format!("could not deserialize {}: {}", section, err))
+}
+
+/// Writes serialized values to a snapshot file as they come,
+/// so that the snapshot is never held in memory as a whole.
+struct SnapshotWriter<'a> {
+    /// path of the snapshot file, for error messages
+    path: &'a Path,
+    /// buffered snapshot file
+    file: BufWriter<File>,
+    /// serialization buffer, reused across values
+    buffer: Vec<u8>,
+}
+
+impl<'a> SnapshotWriter<'a> {
+    /// Creates the snapshot file, replacing any existing one
+    fn create(path: &'a Path) -> Result<Self, FinalStateError> {
+        let file = File::create(path).map_err(|err| write_error(path, err))?;
+        Ok(SnapshotWriter {
+            path,
+            file: BufWriter::new(file),
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Appends raw bytes to the file
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), FinalStateError> {
+        self.file
+            .write_all(bytes)
+            .map_err(|err| write_error(self.path, err))
+    }
+
+    /// Serializes a value and appends it to the file
+    fn write<T>(
+        &mut self,
+        serializer: &impl Serializer<T>,
+        value: &T,
+    ) -> Result<(), FinalStateError> {
+        self.buffer.clear();
+        serializer
+            .serialize(value, &mut self.buffer)
+            .map_err(ser_error)?;
+        self.file
+            .write_all(&self.buffer)
+            .map_err(|err| write_error(self.path, err))
+    }
+
+    /// Flushes the file to disk
+    fn finish(mut self) -> Result<(), FinalStateError> {
+        self.file
+            .flush()
+            .and_then(|_| self.file.get_ref().sync_all())
+            .map_err(|err| write_error(self.path, err))
+    }
+}
+
+fn write_error(path: &Path, err: std::io::Error) -> FinalStateError {
+    FinalStateError::SnapshotError(format!("could not write {:?}: {}", path, err))
+}
+
+impl FinalState {
+    /// Requests a snapshot of the final state to be written to a file at a given final slot.
+    ///
+    /// The snapshot is written right away if `slot` is the current final slot,
+    /// and by `finalize` once `slot` is final otherwise.
+    pub fn request_snapshot(&mut self, slot: Slot, path: PathBuf) -> Result<(), FinalStateError> {
+        if slot < self.slot {
+            return Err(FinalStateError::SnapshotError(format!(
+                "slot {} is older than the current final slot {}",
+                slot, self.slot
+            )));
+        }
+        if slot == self.slot {
+            return self.export_snapshot(&path);
+        }
+        self.snapshot_requests.entry(slot).or_default().push(path);
+        info!("final state snapshot requested at slot {}", slot);
+        Ok(())
+    }
+
+    /// Writes the snapshots requested at the current final slot.
+    pub(crate) fn export_requested_snapshots(&mut self) {
+        let Some(paths) = self.snapshot_requests.remove(&self.slot) else {
+            return;
+        };
+        for path in paths {
+            if let Err(err) = self.export_snapshot(&path) {
+                warn!(
+                    "could not write the final state snapshot requested at slot {}: {}",
+                    self.slot, err
+                );
+            }
+        }
+    }
+
+    /// Writes a snapshot of the final state at the current final slot to a file.
+    ///
+    /// The components are streamed to the file part by part.
+    pub fn export_snapshot(&self, path: &Path) -> Result<(), FinalStateError> {
+        let u64_serializer = U64VarIntSerializer::new();
+        let mut writer = SnapshotWriter::create(path)?;
+
+        // header
+        writer.write_bytes(SNAPSHOT_MAGIC)?;
+        writer.write(&U32VarIntSerializer::new(), &SNAPSHOT_VERSION)?;
+        writer.write(&SlotSerializer::new(), &self.slot)?;
+
+        // ledger, as a list of parts ended by an empty one
+        let part_serializer = VecU8Serializer::new();
+        let mut cursor = StreamingStep::Started;
+        loop {
+            let (part, new_cursor) = self
+                .ledger
+                .get_ledger_part(cursor)
+                .map_err(|err| FinalStateError::LedgerError(err.to_string()))?;
+            writer.write(&part_serializer, &part)?;
+            if part.is_empty() {
+                break;
+            }
+            cursor = new_cursor;
+        }
+
+        // async pool, as a list of parts ended by an empty one
+        let async_pool_serializer = AsyncPoolSerializer::new();
+        let mut cursor = StreamingStep::Started;
+        loop {
+            let (part, new_cursor) = self.async_pool.get_pool_part(cursor);
+            writer.write(&async_pool_serializer, &part)?;
+            if part.is_empty() {
+                break;
+            }
+            cursor = new_cursor;
+        }
+
+        // cycle history
+        writer.write(
+            &u64_serializer,
+            &(self.pos_state.cycle_history.len() as u64),
+        )?;
+        let cycle_info_serializer = CycleInfoSerializer::new();
+        for cycle_info in self.pos_state.cycle_history.iter() {
+            writer.write(&cycle_info_serializer, cycle_info)?;
+        }
+
+        // deferred credits
+        writer.write(
+            &DeferredCreditsSerializer::new(),
+            &self.pos_state.deferred_credits,
+        )?;
+
+        // initial rolls
+        writer.write(
+            &u64_serializer,
+            &(self.pos_state.initial_rolls.len() as u64),
+        )?;
+        let address_serializer = AddressSerializer::new();
+        for (address, roll_count) in self.pos_state.initial_rolls.iter() {
+            writer.write(&address_serializer, address)?;
+            writer.write(&u64_serializer, roll_count)?;
+        }
+
+        // executed operations, as a list of parts ended by an empty one
+        let executed_ops_serializer = ExecutedOpsSerializer::new();
+        let mut cursor = StreamingStep::Started;
+        loop {
+            let (part, new_cursor) = self.executed_ops.get_executed_ops_part(cursor);
+            writer.write(&executed_ops_serializer, &part)?;
+            if part.is_empty() {
+                break;
+            }
+            cursor = new_cursor;
+        }
+
+        writer.finish()?;
+        info!(
+            "final state snapshot at slot {} written to {:?}",
+            self.slot, path
+        );
+        Ok(())
+    }
+
+    /// Loads the final state from a snapshot file.
+    ///
+    /// Must be called on a freshly created final state,
+    /// in place of loading the initial ledger and creating the initial cycle.
+    pub fn import_snapshot(&mut self, path: &Path) -> Result<(), FinalStateError> {
+        let data = std::fs::read(path).map_err(|err| {
+            FinalStateError::SnapshotError(format!("could not read {:?}: {}", path, err))
+        })?;
+
+        // header
+        let rest = data.strip_prefix(SNAPSHOT_MAGIC).ok_or_else(|| {
+            FinalStateError::SnapshotError(format!("{:?} is not a snapshot file", path))
+        })?;
+        let (rest, version) = U32VarIntDeserializer::new(Included(0), Included(u32::MAX))
+            .deserialize::<DeserializeError>(rest)
+            .map_err(|err| deser_error("version", err))?;
+        if version != SNAPSHOT_VERSION {
+            return Err(FinalStateError::SnapshotError(format!(
+                "unsupported snapshot version {} (expected {})",
+                version, SNAPSHOT_VERSION
+            )));
+        }
+        let (mut rest, slot) = SlotDeserializer::new(
+            (Included(u64::MIN), Included(u64::MAX)),
+            (Included(0), Excluded(self.config.thread_count)),
+        )
+        .deserialize::<DeserializeError>(rest)
+        .map_err(|err| deser_error("slot", err))?;
+
+        // ledger
+        let part_deserializer = VecU8Deserializer::new(Included(0), Included(u64::MAX));
+        loop {
+            let (new_rest, part) = part_deserializer
+                .deserialize::<DeserializeError>(rest)
+                .map_err(|err| deser_error("ledger part", err))?;
+            rest = new_rest;
+            if part.is_empty() {
+                break;
+            }
+            self.ledger
+                .set_ledger_part(part)
+                .map_err(|err| FinalStateError::LedgerError(err.to_string()))?;
+        }
+
+        // async pool
+        let async_pool_deserializer = AsyncPoolDeserializer::new(
+            self.config.async_pool_config.thread_count,
+            self.config.async_pool_config.max_length,
+            self.config.async_pool_config.max_async_message_data,
+            self.config.ledger_config.max_key_length as u32,
+        );
+        loop {
+            let (new_rest, part) = async_pool_deserializer
+                .deserialize::<DeserializeError>(rest)
+                .map_err(|err| deser_error("async pool part", err))?;
+            rest = new_rest;
+            if part.is_empty() {
+                break;
+            }
+            self.async_pool.set_pool_part(part);
+        }
+
+        // cycle history
+        let u64_deserializer = U64VarIntDeserializer::new(Included(u64::MIN), Included(u64::MAX));
+        let (mut rest, cycle_count) = u64_deserializer
+            .deserialize::<DeserializeError>(rest)
+            .map_err(|err| deser_error("cycle history length", err))?;
+        let cycle_info_deserializer = CycleInfoDeserializer::new(u64::MAX, u64::MAX);
+        let mut cycle_history = VecDeque::new();
+        for _ in 0..cycle_count {
+            let (new_rest, cycle_info) = cycle_info_deserializer
+                .deserialize::<DeserializeError>(rest)
+                .map_err(|err| deser_error("cycle history", err))?;
+            rest = new_rest;
+            cycle_history.push_back(cycle_info);
+        }
+        for cycle_info in cycle_history {
+            self.pos_state
+                .set_cycle_history_part(Some(cycle_info))
+                .map_err(|err| FinalStateError::PosError(err.to_string()))?;
+        }
+
+        // deferred credits
+        let (rest, deferred_credits) =
+            DeferredCreditsDeserializer::new(self.config.pos_config.thread_count, u64::MAX)
+                .deserialize::<DeserializeError>(rest)
+                .map_err(|err| deser_error("deferred credits", err))?;
+        self.pos_state.set_deferred_credits_part(deferred_credits);
+
+        // initial rolls
+        let (mut rest, rolls_count) = u64_deserializer
+            .deserialize::<DeserializeError>(rest)
+            .map_err(|err| deser_error("initial rolls length", err))?;
+        let address_deserializer = AddressDeserializer::new();
+        let mut initial_rolls: BTreeMap<Address, u64> = BTreeMap::new();
+        for _ in 0..rolls_count {
+            let (new_rest, address) = address_deserializer
+                .deserialize::<DeserializeError>(rest)
+                .map_err(|err| deser_error("initial rolls", err))?;
+            let (new_rest, roll_count) = u64_deserializer
+                .deserialize::<DeserializeError>(new_rest)
+                .map_err(|err| deser_error("initial rolls", err))?;
+            rest = new_rest;
+            initial_rolls.insert(address, roll_count);
+        }
+        self.pos_state.initial_rolls = initial_rolls;
+
+        // executed operations
+        let executed_ops_deserializer = ExecutedOpsDeserializer::new(
+            self.config.executed_ops_config.thread_count,
+            u64::MAX,
+            u64::MAX,
+        );
+        loop {
+            let (new_rest, part) = executed_ops_deserializer
+                .deserialize::<DeserializeError>(rest)
+                .map_err(|err| deser_error("executed operations part", err))?;
+            rest = new_rest;
+            if part.is_empty() {
+                break;
+            }
+            self.executed_ops.set_executed_ops_part(part);
+        }
+
+        if !rest.is_empty() {
+            return Err(FinalStateError::SnapshotError(format!(
+                "{} unexpected trailing bytes in {:?}",
+                rest.len(),
+                path
+            )));
+        }
+
+        self.slot = slot;
+        self.compute_state_hash_at_slot(slot);
+        info!(
+            "final state loaded from snapshot {:?} at slot {}",
+            path, slot
+        );
+        Ok(())
+    }
+}
@@ -12,8 +12,9 @@ use massa_ledger_exports::LedgerConfig;
 use massa_ledger_worker::FinalLedger;
 use massa_models::{
     config::{
-        DEFERRED_CREDITS_BOOTSTRAP_PART_SIZE, EXECUTED_OPS_BOOTSTRAP_PART_SIZE, PERIODS_PER_CYCLE,
-        POS_SAVED_CYCLES, THREAD_COUNT,
+        CYCLE_INFO_BOOTSTRAP_PART_SIZE, DEFERRED_CREDITS_BOOTSTRAP_PART_SIZE,
+        DEFERRED_CREDITS_PART_SIZE_MESSAGE_BYTES, EXECUTED_OPS_BOOTSTRAP_PART_SIZE,
+        PERIODS_PER_CYCLE, POS_SAVED_CYCLES, THREAD_COUNT,
     },
     slot::Slot,
 };
@@ -31,6 +32,7 @@ impl FinalState {
             changes_history: Default::default(),
             config,
             final_state_hash: Hash::from_bytes(&[0; HASH_SIZE_BYTES]),
+            snapshot_requests: Default::default(),
         }
     }
 }
@@ -50,11 +52,15 @@ impl Default for FinalStateConfig {
                 thread_count: THREAD_COUNT,
                 cycle_history_length: POS_SAVED_CYCLES,
                 credits_bootstrap_part_size: DEFERRED_CREDITS_BOOTSTRAP_PART_SIZE,
+                credits_bootstrap_part_size_bytes: DEFERRED_CREDITS_PART_SIZE_MESSAGE_BYTES,
+                cycle_info_bootstrap_part_size: CYCLE_INFO_BOOTSTRAP_PART_SIZE,
             },
             final_history_length: 10,
             thread_count: 2,
             periods_per_cycle: 100,
             initial_rolls_path: PathBuf::new(),
+            initial_deferred_credits_path: None,
+            initial_snapshot_path: None,
             initial_seed_string: "".to_string(),
         }
     }
@@ -0,0 +1,480 @@
+//! Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Final slot execution traces.
+//!
+//! A trace is an append-only file recording, for every final slot, the operations
+//! and asynchronous messages executed in order, the gas they used,
+//! the resulting state changes and the hashes of the final state components.
+//!
+//! Replaying a trace on top of a snapshot taken at an earlier slot (see `snapshot.rs`)
+//! applies the recorded state changes again, and reports the first slot
+//! at which the recomputed hashes differ from the recorded ones.
+
+use crate::{
+    error::FinalStateError,
+    final_state::FinalState,
+    state_changes::{StateChanges, StateChangesDeserializer, StateChangesSerializer},
+};
+use massa_async_pool::{AsyncMessageId, AsyncMessageIdDeserializer, AsyncMessageIdSerializer};
+use massa_hash::{Hash, HashDeserializer, HashSerializer};
+use massa_models::{
+    block::{BlockId, BlockIdDeserializer, BlockIdSerializer},
+    operation::{OperationId, OperationIdDeserializer, OperationIdSerializer},
+    serialization::{VecU8Deserializer, VecU8Serializer},
+    slot::{Slot, SlotDeserializer, SlotSerializer},
+};
+use massa_serialization::{
+    DeserializeError, Deserializer, OptionDeserializer, OptionSerializer, SerializeError,
+    Serializer, U32VarIntDeserializer, U32VarIntSerializer, U64VarIntDeserializer,
+    U64VarIntSerializer,
+};
+use nom::{
+    error::{context, ContextError, ParseError},
+    multi::length_count,
+    sequence::tuple,
+    IResult, Parser,
+};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::ops::Bound::{Excluded, Included};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Bytes starting every trace file
+const TRACE_MAGIC: &[u8] = b"MASSA_TRACE";
+
+/// Version of the trace format, to be incremented on every format change
+const TRACE_VERSION: u32 = 0;
+
+/// Operations and asynchronous messages executed at a slot
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionTrace {
+    /// IDs of the executed operations, in execution order
+    pub executed_ops: Vec<OperationId>,
+    /// IDs of the executed asynchronous messages, in execution order
+    pub executed_messages: Vec<AsyncMessageId>,
+    /// gas used by the executed operations and asynchronous messages
+    pub gas_used: u64,
+}
+
+/// Hashes of the final state components
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FinalStateHashes {
+    /// ledger hash
+    pub ledger: Hash,
+    /// asynchronous pool hash
+    pub async_pool: Hash,
+    /// deferred credits hash
+    pub deferred_credits: Hash,
+    /// executed operations hash
+    pub executed_ops: Hash,
+    /// final state hash, also covering the cycle history
+    pub final_state: Hash,
+}
+
+impl FinalStateHashes {
+    /// Lists the components whose hash differs between `self` and `other`
+    pub fn diff(&self, other: &FinalStateHashes) -> Vec<&'static str> {
+        let mut components = Vec::new();
+        if self.ledger != other.ledger {
+            components.push("ledger");
+        }
+        if self.async_pool != other.async_pool {
+            components.push("async_pool");
+        }
+        if self.deferred_credits != other.deferred_credits {
+            components.push("deferred_credits");
+        }
+        if self.executed_ops != other.executed_ops {
+            components.push("executed_ops");
+        }
+        if self.final_state != other.final_state {
+            components.push("final_state");
+        }
+        components
+    }
+}
+
+/// Record of the execution of a final slot
+#[derive(Debug, Clone)]
+pub struct SlotTrace {
+    /// executed slot
+    pub slot: Slot,
+    /// block executed at that slot, `None` on misses
+    pub block_id: Option<BlockId>,
+    /// executed operations and asynchronous messages
+    pub execution: ExecutionTrace,
+    /// state changes applied to the final state
+    pub state_changes: StateChanges,
+    /// hashes of the final state once the changes were applied
+    pub hashes: FinalStateHashes,
+}
+
+/// Serializer for `SlotTrace`
+pub struct SlotTraceSerializer {
+    slot_serializer: SlotSerializer,
+    block_id_serializer: OptionSerializer<BlockId, BlockIdSerializer>,
+    u64_serializer: U64VarIntSerializer,
+    op_id_serializer: OperationIdSerializer,
+    message_id_serializer: AsyncMessageIdSerializer,
+    state_changes_serializer: StateChangesSerializer,
+    hash_serializer: HashSerializer,
+}
+
+impl SlotTraceSerializer {
+    /// Creates a new `SlotTraceSerializer`
+    pub fn new() -> Self {
+        Self {
+            slot_serializer: SlotSerializer::new(),
+            block_id_serializer: OptionSerializer::new(BlockIdSerializer::new()),
+            u64_serializer: U64VarIntSerializer::new(),
+            op_id_serializer: OperationIdSerializer::new(),
+            message_id_serializer: AsyncMessageIdSerializer::new(),
+            state_changes_serializer: StateChangesSerializer::new(),
+            hash_serializer: HashSerializer::new(),
+        }
+    }
+}
+
+impl Default for SlotTraceSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer<SlotTrace> for SlotTraceSerializer {
+    fn serialize(&self, value: &SlotTrace, buffer: &mut Vec<u8>) -> Result<(), SerializeError> {
+        self.slot_serializer.serialize(&value.slot, buffer)?;
+        self.block_id_serializer
+            .serialize(&value.block_id, buffer)?;
+        self.u64_serializer
+            .serialize(&(value.execution.executed_ops.len() as u64), buffer)?;
+        for op_id in value.execution.executed_ops.iter() {
+            self.op_id_serializer.serialize(op_id, buffer)?;
+        }
+        self.u64_serializer
+            .serialize(&(value.execution.executed_messages.len() as u64), buffer)?;
+        for message_id in value.execution.executed_messages.iter() {
+            self.message_id_serializer.serialize(message_id, buffer)?;
+        }
+        self.u64_serializer
+            .serialize(&value.execution.gas_used, buffer)?;
+        self.state_changes_serializer
+            .serialize(&value.state_changes, buffer)?;
+        for hash in [
+            &value.hashes.ledger,
+            &value.hashes.async_pool,
+            &value.hashes.deferred_credits,
+            &value.hashes.executed_ops,
+            &value.hashes.final_state,
+        ] {
+            self.hash_serializer.serialize(hash, buffer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Deserializer for `SlotTrace`
+///
+/// Traces are written locally by the node itself, so lengths are not bounded.
+pub struct SlotTraceDeserializer {
+    slot_deserializer: SlotDeserializer,
+    block_id_deserializer: OptionDeserializer<BlockId, BlockIdDeserializer>,
+    u64_deserializer: U64VarIntDeserializer,
+    op_id_deserializer: OperationIdDeserializer,
+    message_id_deserializer: AsyncMessageIdDeserializer,
+    state_changes_deserializer: StateChangesDeserializer,
+    hash_deserializer: HashDeserializer,
+}
+
+impl SlotTraceDeserializer {
+    /// Creates a new `SlotTraceDeserializer`
+    pub fn new(thread_count: u8) -> Self {
+        Self {
+            slot_deserializer: SlotDeserializer::new(
+                (Included(u64::MIN), Included(u64::MAX)),
+                (Included(0), Excluded(thread_count)),
+            ),
+            block_id_deserializer: OptionDeserializer::new(BlockIdDeserializer::new()),
+            u64_deserializer: U64VarIntDeserializer::new(Included(u64::MIN), Included(u64::MAX)),
+            op_id_deserializer: OperationIdDeserializer::new(),
+            message_id_deserializer: AsyncMessageIdDeserializer::new(thread_count),
+            state_changes_deserializer: StateChangesDeserializer::new(
+                thread_count,
+                u64::MAX,
+                u64::MAX,
+                u64::MAX,
+                u8::MAX,
+                u64::MAX,
+                u64::MAX,
+                u64::MAX,
+                u64::MAX,
+                u64::MAX,
+                u64::MAX,
+            ),
+            hash_deserializer: HashDeserializer::new(),
+        }
+    }
+}
+
+impl Deserializer<SlotTrace> for SlotTraceDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], SlotTrace, E> {
+        context(
+            "Failed SlotTrace deserialization",
+            tuple((
+                context("Failed slot deserialization", |input| {
+                    self.slot_deserializer.deserialize(input)
+                }),
+                context("Failed block_id deserialization", |input| {
+                    self.block_id_deserializer.deserialize(input)
+                }),
+                context(
+                    "Failed executed_ops deserialization",
+                    length_count(
+                        |input| self.u64_deserializer.deserialize(input),
+                        |input| self.op_id_deserializer.deserialize(input),
+                    ),
+                ),
+                context(
+                    "Failed executed_messages deserialization",
+                    length_count(
+                        |input| self.u64_deserializer.deserialize(input),
+                        |input| self.message_id_deserializer.deserialize(input),
+                    ),
+                ),
+                context("Failed gas_used deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context("Failed state_changes deserialization", |input| {
+                    self.state_changes_deserializer.deserialize(input)
+                }),
+                context(
+                    "Failed hashes deserialization",
+                    tuple((
+                        |input| self.hash_deserializer.deserialize(input),
+                        |input| self.hash_deserializer.deserialize(input),
+                        |input| self.hash_deserializer.deserialize(input),
+                        |input| self.hash_deserializer.deserialize(input),
+                        |input| self.hash_deserializer.deserialize(input),
+                    )),
+                ),
+            )),
+        )
+        .map(
+            |(
+                slot,
+                block_id,
+                executed_ops,
+                executed_messages,
+                gas_used,
+                state_changes,
+                (ledger, async_pool, deferred_credits, executed_ops_hash, final_state),
+            )| SlotTrace {
+                slot,
+                block_id,
+                execution: ExecutionTrace {
+                    executed_ops,
+                    executed_messages,
+                    gas_used,
+                },
+                state_changes,
+                hashes: FinalStateHashes {
+                    ledger,
+                    async_pool,
+                    deferred_credits,
+                    executed_ops: executed_ops_hash,
+                    final_state,
+                },
+            },
+        )
+        .parse(buffer)
+    }
+}
+
+fn trace_error(path: &Path, err: impl std::fmt::Display) -> FinalStateError {
+    FinalStateError::TraceError(format!("{:?}: {}", path, err))
+}
+
+/// Appends slot traces to a trace file
+pub struct TraceWriter {
+    path: PathBuf,
+    file: File,
+    record_serializer: VecU8Serializer,
+    trace_serializer: SlotTraceSerializer,
+}
+
+impl TraceWriter {
+    /// Opens a trace file for appending, writing the header if the file is new
+    pub fn open(path: &Path) -> Result<Self, FinalStateError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|err| trace_error(path, err))?;
+        let is_new = file.metadata().map_err(|err| trace_error(path, err))?.len() == 0;
+        if is_new {
+            let mut header = TRACE_MAGIC.to_vec();
+            U32VarIntSerializer::new()
+                .serialize(&TRACE_VERSION, &mut header)
+                .map_err(|err| trace_error(path, err))?;
+            file.write_all(&header)
+                .map_err(|err| trace_error(path, err))?;
+        }
+        Ok(TraceWriter {
+            path: path.to_path_buf(),
+            file,
+            record_serializer: VecU8Serializer::new(),
+            trace_serializer: SlotTraceSerializer::new(),
+        })
+    }
+
+    /// Appends the trace of a slot.
+    /// Each trace is written at once, as a length-prefixed record.
+    pub fn append(&mut self, trace: &SlotTrace) -> Result<(), FinalStateError> {
+        let mut data = Vec::new();
+        self.trace_serializer
+            .serialize(trace, &mut data)
+            .map_err(|err| trace_error(&self.path, err))?;
+        let mut record = Vec::with_capacity(data.len() + 10);
+        self.record_serializer
+            .serialize(&data, &mut record)
+            .map_err(|err| trace_error(&self.path, err))?;
+        self.file
+            .write_all(&record)
+            .map_err(|err| trace_error(&self.path, err))
+    }
+}
+
+/// Reads all the slot traces of a trace file, in recording order
+pub fn read_trace(path: &Path, thread_count: u8) -> Result<Vec<SlotTrace>, FinalStateError> {
+    let data = std::fs::read(path).map_err(|err| trace_error(path, err))?;
+
+    // header
+    let rest = data
+        .strip_prefix(TRACE_MAGIC)
+        .ok_or_else(|| trace_error(path, "not a trace file"))?;
+    let (mut rest, version) = U32VarIntDeserializer::new(Included(0), Included(u32::MAX))
+        .deserialize::<DeserializeError>(rest)
+        .map_err(|err| trace_error(path, err))?;
+    if version != TRACE_VERSION {
+        return Err(trace_error(
+            path,
+            format!(
+                "unsupported trace version {} (expected {})",
+                version, TRACE_VERSION
+            ),
+        ));
+    }
+
+    // records
+    let record_deserializer = VecU8Deserializer::new(Included(0), Included(u64::MAX));
+    let trace_deserializer = SlotTraceDeserializer::new(thread_count);
+    let mut traces = Vec::new();
+    while !rest.is_empty() {
+        let (new_rest, record) = record_deserializer
+            .deserialize::<DeserializeError>(rest)
+            .map_err(|err| trace_error(path, format!("truncated record: {}", err)))?;
+        rest = new_rest;
+        let (trailing, trace) = trace_deserializer
+            .deserialize::<DeserializeError>(&record)
+            .map_err(|err| trace_error(path, err))?;
+        if !trailing.is_empty() {
+            return Err(trace_error(
+                path,
+                format!("{} unexpected trailing bytes in a record", trailing.len()),
+            ));
+        }
+        traces.push(trace);
+    }
+    Ok(traces)
+}
+
+/// Outcome of a trace replay
+#[derive(Debug, Clone)]
+pub enum TraceReplayOutcome {
+    /// all the traced slots were replayed and the hashes always matched
+    Matched {
+        /// number of replayed slots
+        replayed_slots: u64,
+        /// final slot reached by the replay
+        last_slot: Slot,
+    },
+    /// the hashes of the final state differ from the recorded ones
+    Diverged {
+        /// first slot at which the hashes differ
+        slot: Slot,
+        /// names of the components whose hash differs
+        components: Vec<&'static str>,
+        /// recorded hashes
+        expected: FinalStateHashes,
+        /// recomputed hashes
+        actual: FinalStateHashes,
+    },
+}
+
+impl FinalState {
+    /// Gets the hashes of the final state components
+    pub fn get_state_hashes(&self) -> FinalStateHashes {
+        FinalStateHashes {
+            ledger: self.ledger.get_ledger_hash(),
+            async_pool: self.async_pool.hash,
+            deferred_credits: self.pos_state.deferred_credits.hash,
+            executed_ops: self.executed_ops.hash,
+            final_state: self.final_state_hash,
+        }
+    }
+
+    /// Replays a trace on top of the current final state, usually just loaded from a snapshot.
+    ///
+    /// Traces of slots before the current one are skipped,
+    /// the trace of the current slot is checked against the current state,
+    /// and the traces of the following slots are applied one after the other.
+    /// Stops at the first slot at which the recomputed hashes differ from the recorded ones.
+    pub fn replay_trace(&mut self, path: &Path) -> Result<TraceReplayOutcome, FinalStateError> {
+        let traces = read_trace(path, self.config.thread_count)?;
+        let mut replayed_slots = 0;
+        for trace in traces {
+            if trace.slot < self.slot {
+                continue;
+            }
+            if trace.slot > self.slot {
+                let next_slot = self
+                    .slot
+                    .get_next_slot(self.config.thread_count)
+                    .map_err(|err| FinalStateError::InvalidSlot(err.to_string()))?;
+                if trace.slot != next_slot {
+                    return Err(trace_error(
+                        path,
+                        format!(
+                            "missing slots in the trace: expected slot {} but found slot {}",
+                            next_slot, trace.slot
+                        ),
+                    ));
+                }
+                self.finalize(trace.slot, trace.state_changes);
+                replayed_slots += 1;
+            }
+            let actual = self.get_state_hashes();
+            let components = trace.hashes.diff(&actual);
+            if !components.is_empty() {
+                return Ok(TraceReplayOutcome::Diverged {
+                    slot: trace.slot,
+                    components,
+                    expected: trace.hashes,
+                    actual,
+                });
+            }
+        }
+        info!(
+            "replayed {} slots of trace {:?} up to slot {}",
+            replayed_slots, path, self.slot
+        );
+        Ok(TraceReplayOutcome::Matched {
+            replayed_slots,
+            last_slot: self.slot,
+        })
+    }
+}
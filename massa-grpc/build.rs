@@ -0,0 +1,10 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tonic_build::configure()
+        .build_client(true)
+        .build_server(true)
+        .compile(&["proto/massa/api/v1/api.proto"], &["proto"])?;
+    println!("cargo:rerun-if-changed=proto");
+    Ok(())
+}
@@ -0,0 +1,26 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use massa_time::MassaTime;
+use serde::Deserialize;
+use std::net::SocketAddr;
+
+/// gRPC API settings
+#[derive(Debug, Deserialize, Clone)]
+pub struct GrpcConfig {
+    /// whether to enable the gRPC API
+    pub enabled: bool,
+    /// bind for the gRPC API
+    pub bind: SocketAddr,
+    /// maximum size in bytes of a decoded request
+    pub max_decoding_message_size: usize,
+    /// maximum size in bytes of an encoded response
+    pub max_encoding_message_size: usize,
+    /// maximum number of concurrent requests per connection
+    pub concurrency_limit_per_connection: usize,
+    /// timeout of a request
+    pub timeout: MassaTime,
+    /// max argument count
+    pub max_arguments: u64,
+    /// thread count
+    pub thread_count: u8,
+}
@@ -0,0 +1,32 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use displaydoc::Display;
+use massa_models::error::ModelsError;
+use tonic::Status;
+
+/// Errors of the gRPC API
+#[non_exhaustive]
+#[derive(Display, thiserror::Error, Debug)]
+pub enum GrpcError {
+    /// Invalid argument: {0}
+    InvalidArgument(String),
+    /// Models error: {0}
+    ModelsError(#[from] ModelsError),
+    /// IO error: {0}
+    IoError(#[from] std::io::Error),
+    /// Transport error: {0}
+    TransportError(#[from] tonic::transport::Error),
+}
+
+impl From<GrpcError> for Status {
+    fn from(err: GrpcError) -> Self {
+        match err {
+            GrpcError::InvalidArgument(_) | GrpcError::ModelsError(_) => {
+                Status::invalid_argument(err.to_string())
+            }
+            GrpcError::IoError(_) | GrpcError::TransportError(_) => {
+                Status::internal(err.to_string())
+            }
+        }
+    }
+}
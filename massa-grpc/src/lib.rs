@@ -0,0 +1,41 @@
+//! Copyright (c) 2022 MASSA LABS <info@massa.net>
+//! gRPC API for a massa-node
+//!
+//! Exposes the node's query and subscription surface with protobuf schemas
+//! (see `proto/massa/api/v1/api.proto`). Subscriptions are server-streaming calls,
+//! so that indexers do not have to poll the JSON-RPC API.
+#![warn(missing_docs)]
+#![warn(unused_crate_dependencies)]
+
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+
+mod config;
+mod error;
+mod models;
+mod service;
+
+pub use config::GrpcConfig;
+pub use error::GrpcError;
+pub use service::MassaGrpcService;
+
+/// Code generated from the protobuf schemas
+#[allow(missing_docs)]
+pub mod proto {
+    tonic::include_proto!("massa.api.v1");
+}
+
+/// Used to stop the gRPC API
+pub struct StopHandle {
+    stop_cmd_sender: oneshot::Sender<()>,
+}
+
+impl StopHandle {
+    /// stop the gRPC API gracefully
+    pub fn stop(self) {
+        match self.stop_cmd_sender.send(()) {
+            Ok(_) => info!("gRPC API finished cleanly"),
+            Err(_) => warn!("gRPC API was already stopped"),
+        }
+    }
+}
@@ -0,0 +1,317 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Conversions between the node models and their protobuf counterparts
+
+use crate::error::GrpcError;
+use crate::proto;
+use massa_execution_exports::ExecutionAddressInfo;
+use massa_models::address::Address;
+use massa_models::block::{Block, BlockHeader, FilledBlock, WrappedBlock, WrappedHeader};
+use massa_models::endorsement::WrappedEndorsement;
+use massa_models::execution::{ReadOnlyLedgerChange, SlotExecutionOutput, SlotExecutionStatus};
+use massa_models::operation::{Operation, OperationType, WrappedOperation};
+use massa_models::output_event::SCOutputEvent;
+use massa_models::slot::Slot;
+use std::str::FromStr;
+
+impl From<Slot> for proto::Slot {
+    fn from(slot: Slot) -> Self {
+        proto::Slot {
+            period: slot.period,
+            thread: slot.thread as u32,
+        }
+    }
+}
+
+impl From<WrappedEndorsement> for proto::SignedEndorsement {
+    fn from(endorsement: WrappedEndorsement) -> Self {
+        proto::SignedEndorsement {
+            id: endorsement.id.to_string(),
+            content: Some(proto::Endorsement {
+                slot: Some(endorsement.content.slot.into()),
+                index: endorsement.content.index,
+                endorsed_block: endorsement.content.endorsed_block.to_string(),
+            }),
+            signature: endorsement.signature.to_string(),
+            creator_public_key: endorsement.creator_public_key.to_string(),
+            creator_address: endorsement.creator_address.to_string(),
+        }
+    }
+}
+
+impl From<BlockHeader> for proto::BlockHeader {
+    fn from(header: BlockHeader) -> Self {
+        proto::BlockHeader {
+            slot: Some(header.slot.into()),
+            parents: header.parents.iter().map(|id| id.to_string()).collect(),
+            operation_merkle_root: header.operation_merkle_root.to_string(),
+            endorsements: header.endorsements.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<WrappedHeader> for proto::SignedBlockHeader {
+    fn from(header: WrappedHeader) -> Self {
+        proto::SignedBlockHeader {
+            id: header.id.to_string(),
+            signature: header.signature.to_string(),
+            creator_public_key: header.creator_public_key.to_string(),
+            creator_address: header.creator_address.to_string(),
+            content: Some(header.content.into()),
+        }
+    }
+}
+
+impl From<Block> for proto::Block {
+    fn from(block: Block) -> Self {
+        proto::Block {
+            header: Some(block.header.into()),
+            operations: block.operations.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+}
+
+impl From<WrappedBlock> for proto::SignedBlock {
+    fn from(block: WrappedBlock) -> Self {
+        proto::SignedBlock {
+            id: block.id.to_string(),
+            signature: block.signature.to_string(),
+            creator_public_key: block.creator_public_key.to_string(),
+            creator_address: block.creator_address.to_string(),
+            content: Some(block.content.into()),
+        }
+    }
+}
+
+impl From<OperationType> for proto::OperationType {
+    fn from(op: OperationType) -> Self {
+        let op_type = match op {
+            OperationType::Transaction {
+                recipient_address,
+                amount,
+            } => proto::operation_type::Type::Transaction(proto::Transaction {
+                recipient_address: recipient_address.to_string(),
+                amount: amount.to_string(),
+            }),
+            OperationType::RollBuy { roll_count } => {
+                proto::operation_type::Type::RollBuy(proto::RollBuy { roll_count })
+            }
+            OperationType::RollSell { roll_count } => {
+                proto::operation_type::Type::RollSell(proto::RollSell { roll_count })
+            }
+            OperationType::ExecuteSC { data, max_gas, .. } => {
+                proto::operation_type::Type::ExecuteSc(proto::ExecuteSc { data, max_gas })
+            }
+            OperationType::CallSC {
+                target_addr,
+                target_func,
+                param,
+                max_gas,
+                coins,
+            } => proto::operation_type::Type::CallSc(proto::CallSc {
+                target_addr: target_addr.to_string(),
+                target_func,
+                param,
+                max_gas,
+                coins: coins.to_string(),
+            }),
+        };
+        proto::OperationType {
+            r#type: Some(op_type),
+        }
+    }
+}
+
+impl From<Operation> for proto::Operation {
+    fn from(operation: Operation) -> Self {
+        proto::Operation {
+            fee: operation.fee.to_string(),
+            expire_period: operation.expire_period,
+            op: Some(operation.op.into()),
+        }
+    }
+}
+
+impl From<WrappedOperation> for proto::SignedOperation {
+    fn from(operation: WrappedOperation) -> Self {
+        proto::SignedOperation {
+            id: operation.id.to_string(),
+            signature: operation.signature.to_string(),
+            creator_public_key: operation.creator_public_key.to_string(),
+            creator_address: operation.creator_address.to_string(),
+            content: Some(operation.content.into()),
+        }
+    }
+}
+
+impl From<FilledBlock> for proto::FilledBlock {
+    fn from(block: FilledBlock) -> Self {
+        proto::FilledBlock {
+            header: Some(block.header.into()),
+            operations: block
+                .operations
+                .into_iter()
+                .map(|(operation_id, operation)| proto::FilledOperationEntry {
+                    operation_id: operation_id.to_string(),
+                    operation: operation.map(Into::into),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<SCOutputEvent> for proto::ScExecutionEvent {
+    fn from(event: SCOutputEvent) -> Self {
+        let context = event.context;
+        proto::ScExecutionEvent {
+            context: Some(proto::ScExecutionEventContext {
+                slot: Some(context.slot.into()),
+                block: context.block.map(|id| id.to_string()),
+                read_only: context.read_only,
+                index_in_slot: context.index_in_slot,
+                call_stack: context
+                    .call_stack
+                    .iter()
+                    .map(|address| address.to_string())
+                    .collect(),
+                origin_operation_id: context.origin_operation_id.map(|id| id.to_string()),
+                is_final: context.is_final,
+                is_error: context.is_error,
+            }),
+            data: event.data,
+        }
+    }
+}
+
+impl From<SlotExecutionStatus> for proto::SlotExecutionStatus {
+    fn from(status: SlotExecutionStatus) -> Self {
+        match status {
+            SlotExecutionStatus::Candidate => proto::SlotExecutionStatus::Candidate,
+            SlotExecutionStatus::Final => proto::SlotExecutionStatus::Final,
+        }
+    }
+}
+
+impl From<ReadOnlyLedgerChange> for proto::LedgerChangeSummary {
+    fn from(change: ReadOnlyLedgerChange) -> Self {
+        proto::LedgerChangeSummary {
+            address: change.address.to_string(),
+            deleted: change.deleted,
+            balance: change.balance.map(|balance| balance.to_string()),
+            bytecode_changed: change.bytecode_changed,
+            datastore_set_keys: change.datastore_set_keys,
+            datastore_deleted_keys: change.datastore_deleted_keys,
+        }
+    }
+}
+
+impl From<SlotExecutionOutput> for proto::SlotExecutionOutput {
+    fn from(output: SlotExecutionOutput) -> Self {
+        proto::SlotExecutionOutput {
+            slot: Some(output.slot.into()),
+            block_id: output.block_id.map(|id| id.to_string()),
+            status: proto::SlotExecutionStatus::from(output.status).into(),
+            ledger_changes: output.ledger_changes.into_iter().map(Into::into).collect(),
+            roll_changes: output
+                .roll_changes
+                .into_iter()
+                .map(|(address, roll_count)| proto::RollChange {
+                    address: address.to_string(),
+                    roll_count,
+                })
+                .collect(),
+            executed_ops: output
+                .executed_ops
+                .iter()
+                .map(|id| id.to_string())
+                .collect(),
+            gas_used: output.gas_used,
+            events: output.events.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Builds the protobuf address info of `address`
+pub(crate) fn to_address_info(address: Address, info: ExecutionAddressInfo) -> proto::AddressInfo {
+    proto::AddressInfo {
+        address: address.to_string(),
+        final_balance: info.final_balance.to_string(),
+        candidate_balance: info.candidate_balance.to_string(),
+        final_roll_count: info.final_roll_count,
+        candidate_roll_count: info.candidate_roll_count,
+    }
+}
+
+/// Converts a protobuf slot, checking its thread against `thread_count`
+pub(crate) fn to_slot(slot: proto::Slot, thread_count: u8) -> Result<Slot, GrpcError> {
+    match u8::try_from(slot.thread) {
+        Ok(thread) if thread < thread_count => Ok(Slot::new(slot.period, thread)),
+        _ => Err(GrpcError::InvalidArgument(format!(
+            "invalid thread {} in slot",
+            slot.thread
+        ))),
+    }
+}
+
+/// Parses the string representation of an id or an address
+pub(crate) fn parse<T: FromStr>(value: &str) -> Result<T, GrpcError> {
+    T::from_str(value).map_err(|_| GrpcError::InvalidArgument(format!("invalid {}", value)))
+}
+
+/// Parses the string representations of ids or addresses
+pub(crate) fn parse_all<T: FromStr>(values: &[String]) -> Result<Vec<T>, GrpcError> {
+    values.iter().map(|value| parse(value)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_models::block::BlockId;
+
+    #[test]
+    fn test_to_slot() {
+        let slot = to_slot(
+            proto::Slot {
+                period: 10,
+                thread: 3,
+            },
+            32,
+        )
+        .unwrap();
+        assert_eq!(slot, Slot::new(10, 3));
+        assert_eq!(
+            proto::Slot::from(slot),
+            proto::Slot {
+                period: 10,
+                thread: 3
+            }
+        );
+
+        assert!(to_slot(
+            proto::Slot {
+                period: 10,
+                thread: 32
+            },
+            32
+        )
+        .is_err());
+        assert!(to_slot(
+            proto::Slot {
+                period: 10,
+                thread: 300
+            },
+            32
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let ids: Vec<BlockId> = parse_all(&[]).unwrap();
+        assert!(ids.is_empty());
+        assert!(matches!(
+            parse_all::<BlockId>(&["invalid".to_string()]),
+            Err(GrpcError::InvalidArgument(_))
+        ));
+    }
+}
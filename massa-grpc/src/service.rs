@@ -0,0 +1,292 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use crate::config::GrpcConfig;
+use crate::error::GrpcError;
+use crate::models::{parse, parse_all, to_address_info, to_slot};
+use crate::proto;
+use crate::proto::massa_service_server::{MassaService, MassaServiceServer};
+use crate::StopHandle;
+use massa_consensus_exports::ConsensusChannels;
+use massa_execution_exports::{ExecutionChannels, ExecutionController};
+use massa_models::address::Address;
+use massa_models::api::EventFilter;
+use massa_models::block::BlockId;
+use massa_models::execution::SlotExecutionStatus;
+use massa_models::operation::OperationId;
+use massa_models::version::Version;
+use massa_protocol_exports::ProtocolSenders;
+use massa_storage::Storage;
+use std::pin::Pin;
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, oneshot};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::{BroadcastStream, TcpListenerStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+use tracing::warn;
+
+/// Stream of subscription messages sent to a client
+type SubscriptionStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send + 'static>>;
+
+/// gRPC API content
+pub struct MassaGrpcService {
+    /// link(channels) to the consensus component
+    pub consensus_channels: ConsensusChannels,
+    /// link to the execution component
+    pub execution_controller: Box<dyn ExecutionController>,
+    /// link(channels) to the execution component
+    pub execution_channels: ExecutionChannels,
+    /// link(channels) to the protocol component
+    pub protocol_senders: ProtocolSenders,
+    /// Massa storage
+    pub storage: Storage,
+    /// gRPC settings
+    pub grpc_config: GrpcConfig,
+    /// node version
+    pub version: Version,
+}
+
+impl MassaGrpcService {
+    /// Start the gRPC API
+    pub async fn serve(self, config: &GrpcConfig) -> Result<StopHandle, GrpcError> {
+        let service = MassaServiceServer::new(self)
+            .max_decoding_message_size(config.max_decoding_message_size)
+            .max_encoding_message_size(config.max_encoding_message_size);
+
+        // bind before spawning the server so that binding errors are reported to the caller
+        let listener = TcpListener::bind(config.bind).await?;
+
+        let (stop_cmd_sender, stop_cmd_receiver) = oneshot::channel();
+        let router = Server::builder()
+            .concurrency_limit_per_connection(config.concurrency_limit_per_connection)
+            .timeout(config.timeout.to_duration())
+            .add_service(service);
+        tokio::spawn(async move {
+            let shutdown_signal = async {
+                let _ = stop_cmd_receiver.await;
+            };
+            if let Err(err) = router
+                .serve_with_incoming_shutdown(TcpListenerStream::new(listener), shutdown_signal)
+                .await
+            {
+                warn!("gRPC API stopped with an error: {}", err);
+            }
+        });
+
+        Ok(StopHandle { stop_cmd_sender })
+    }
+
+    /// Checks that a request does not carry too many arguments
+    fn check_arguments_count(&self, count: usize) -> Result<(), GrpcError> {
+        if count as u64 > self.grpc_config.max_arguments {
+            return Err(GrpcError::InvalidArgument("too many arguments".into()));
+        }
+        Ok(())
+    }
+}
+
+/// Streams the content broadcast by `sender`, mapped by `map`.
+///
+/// If the client is too slow and messages are dropped, the stream ends with a `DATA_LOSS` status
+/// so that the client knows it has to resubscribe and catch up.
+fn broadcast_stream<T, U, F>(sender: &broadcast::Sender<T>, mut map: F) -> SubscriptionStream<U>
+where
+    T: Clone + Send + 'static,
+    U: Send + 'static,
+    F: FnMut(T) -> Option<U> + Send + 'static,
+{
+    let stream = BroadcastStream::new(sender.subscribe()).filter_map(move |item| match item {
+        Ok(content) => map(content).map(Ok),
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => Some(Err(Status::data_loss(format!(
+            "{} messages were skipped, the client is too slow",
+            skipped
+        )))),
+    });
+    Box::pin(stream)
+}
+
+#[tonic::async_trait]
+impl MassaService for MassaGrpcService {
+    type NewBlocksStream = SubscriptionStream<proto::NewBlocksResponse>;
+    type NewBlocksHeadersStream = SubscriptionStream<proto::NewBlocksHeadersResponse>;
+    type NewFilledBlocksStream = SubscriptionStream<proto::NewFilledBlocksResponse>;
+    type NewOperationsStream = SubscriptionStream<proto::NewOperationsResponse>;
+    type NewSlotExecutionOutputsStream = SubscriptionStream<proto::NewSlotExecutionOutputsResponse>;
+
+    async fn get_version(
+        &self,
+        _request: Request<proto::GetVersionRequest>,
+    ) -> Result<Response<proto::GetVersionResponse>, Status> {
+        Ok(Response::new(proto::GetVersionResponse {
+            version: self.version.to_string(),
+        }))
+    }
+
+    async fn get_blocks(
+        &self,
+        request: Request<proto::GetBlocksRequest>,
+    ) -> Result<Response<proto::GetBlocksResponse>, Status> {
+        let request = request.into_inner();
+        self.check_arguments_count(request.ids.len())?;
+        let ids: Vec<BlockId> = parse_all(&request.ids)?;
+
+        let blocks = {
+            let read_blocks = self.storage.read_blocks();
+            ids.iter()
+                .filter_map(|id| read_blocks.get(id).cloned())
+                .map(Into::into)
+                .collect()
+        };
+        Ok(Response::new(proto::GetBlocksResponse { blocks }))
+    }
+
+    async fn get_operations(
+        &self,
+        request: Request<proto::GetOperationsRequest>,
+    ) -> Result<Response<proto::GetOperationsResponse>, Status> {
+        let request = request.into_inner();
+        self.check_arguments_count(request.ids.len())?;
+        let ids: Vec<OperationId> = parse_all(&request.ids)?;
+
+        let operations = {
+            let read_operations = self.storage.read_operations();
+            ids.iter()
+                .filter_map(|id| read_operations.get(id).cloned())
+                .map(Into::into)
+                .collect()
+        };
+        Ok(Response::new(proto::GetOperationsResponse { operations }))
+    }
+
+    async fn get_addresses(
+        &self,
+        request: Request<proto::GetAddressesRequest>,
+    ) -> Result<Response<proto::GetAddressesResponse>, Status> {
+        let request = request.into_inner();
+        self.check_arguments_count(request.addresses.len())?;
+        let addresses: Vec<Address> = parse_all(&request.addresses)?;
+
+        let infos = self.execution_controller.get_addresses_infos(&addresses);
+        Ok(Response::new(proto::GetAddressesResponse {
+            addresses: addresses
+                .into_iter()
+                .zip(infos)
+                .map(|(address, info)| to_address_info(address, info))
+                .collect(),
+        }))
+    }
+
+    async fn get_sc_execution_events(
+        &self,
+        request: Request<proto::GetScExecutionEventsRequest>,
+    ) -> Result<Response<proto::GetScExecutionEventsResponse>, Status> {
+        let request = request.into_inner();
+        let thread_count = self.grpc_config.thread_count;
+        let filter = EventFilter {
+            start: request
+                .start
+                .map(|slot| to_slot(slot, thread_count))
+                .transpose()?,
+            end: request
+                .end
+                .map(|slot| to_slot(slot, thread_count))
+                .transpose()?,
+            emitter_address: request
+                .emitter_address
+                .map(|address| parse(&address))
+                .transpose()?,
+            original_caller_address: request
+                .original_caller_address
+                .map(|address| parse(&address))
+                .transpose()?,
+            original_operation_id: request
+                .original_operation_id
+                .map(|id| parse(&id))
+                .transpose()?,
+            is_final: request.is_final,
+            is_error: request.is_error,
+        };
+
+        let events = self
+            .execution_controller
+            .get_filtered_sc_output_event(filter);
+        Ok(Response::new(proto::GetScExecutionEventsResponse {
+            events: events.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn new_blocks(
+        &self,
+        _request: Request<proto::NewBlocksRequest>,
+    ) -> Result<Response<Self::NewBlocksStream>, Status> {
+        Ok(Response::new(broadcast_stream(
+            &self.consensus_channels.block_sender,
+            |block| {
+                Some(proto::NewBlocksResponse {
+                    block: Some(block.into()),
+                })
+            },
+        )))
+    }
+
+    async fn new_blocks_headers(
+        &self,
+        _request: Request<proto::NewBlocksHeadersRequest>,
+    ) -> Result<Response<Self::NewBlocksHeadersStream>, Status> {
+        Ok(Response::new(broadcast_stream(
+            &self.consensus_channels.block_header_sender,
+            |header| {
+                Some(proto::NewBlocksHeadersResponse {
+                    block_header: Some(header.into()),
+                })
+            },
+        )))
+    }
+
+    async fn new_filled_blocks(
+        &self,
+        _request: Request<proto::NewFilledBlocksRequest>,
+    ) -> Result<Response<Self::NewFilledBlocksStream>, Status> {
+        Ok(Response::new(broadcast_stream(
+            &self.consensus_channels.filled_block_sender,
+            |filled_block| {
+                Some(proto::NewFilledBlocksResponse {
+                    filled_block: Some(filled_block.into()),
+                })
+            },
+        )))
+    }
+
+    async fn new_operations(
+        &self,
+        _request: Request<proto::NewOperationsRequest>,
+    ) -> Result<Response<Self::NewOperationsStream>, Status> {
+        Ok(Response::new(broadcast_stream(
+            &self.protocol_senders.operation_sender,
+            |operation| {
+                Some(proto::NewOperationsResponse {
+                    operation: Some(operation.into()),
+                })
+            },
+        )))
+    }
+
+    async fn new_slot_execution_outputs(
+        &self,
+        request: Request<proto::NewSlotExecutionOutputsRequest>,
+    ) -> Result<Response<Self::NewSlotExecutionOutputsStream>, Status> {
+        let final_only = request.into_inner().final_only;
+        Ok(Response::new(broadcast_stream(
+            &self.execution_channels.slot_execution_output_sender,
+            move |output| {
+                if final_only && output.status != SlotExecutionStatus::Final {
+                    return None;
+                }
+                Some(proto::NewSlotExecutionOutputsResponse {
+                    output: Some(output.into()),
+                })
+            },
+        )))
+    }
+}
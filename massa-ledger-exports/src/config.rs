@@ -15,6 +15,8 @@ pub struct LedgerConfig {
     pub disk_ledger_path: PathBuf,
     /// max key length
     pub max_key_length: u8,
+    /// max value length
+    pub max_value_length: u64,
     /// max ledger part size
     pub max_ledger_part_size: u64,
 }
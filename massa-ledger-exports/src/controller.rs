@@ -1,6 +1,7 @@
 use massa_hash::Hash;
 use massa_models::{
-    address::Address, amount::Amount, error::ModelsError, slot::Slot, streaming_step::StreamingStep,
+    address::Address, amount::Amount, error::ModelsError, ledger_proof::LedgerEntryProof,
+    slot::Slot, streaming_step::StreamingStep,
 };
 use std::collections::BTreeSet;
 use std::fmt::Debug;
@@ -58,9 +59,37 @@ pub trait LedgerController: Send + Sync + Debug {
     /// A `BTreeSet` of the datastore keys
     fn get_datastore_keys(&self, addr: &Address) -> Option<BTreeSet<Vec<u8>>>;
 
+    /// Get the datastore keys of a given address starting with `prefix`, in ascending order.
+    /// If `start_key` is set, only the keys strictly greater than it are returned,
+    /// so that the last key of a page can be used to get the next one.
+    ///
+    /// # Returns
+    /// At most `limit` keys, or None if the ledger entry was not found
+    fn get_datastore_keys_page(
+        &self,
+        addr: &Address,
+        prefix: &[u8],
+        start_key: Option<&[u8]>,
+        limit: usize,
+    ) -> Option<Vec<Vec<u8>>>;
+
     /// Get the current disk ledger hash
     fn get_ledger_hash(&self) -> Hash;
 
+    /// Get the root of the ledger Merkle tree
+    fn get_ledger_root(&self) -> Hash;
+
+    /// Get the Merkle proof of the balance (if `datastore_key` is `None`) or of a datastore entry of an address.
+    ///
+    /// # Returns
+    /// An inclusion proof if the entry exists, otherwise an exclusion proof
+    fn get_entry_proof(&self, addr: &Address, datastore_key: Option<&[u8]>) -> LedgerEntryProof;
+
+    /// Get the sum of the balances of every ledger entry
+    ///
+    /// The sum is maintained by each write, so this does not iterate over the ledger.
+    fn get_total_balance(&self) -> Amount;
+
     /// Get a part of the ledger
     /// Used for bootstrap
     /// Return: Tuple with data and last key
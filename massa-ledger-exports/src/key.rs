@@ -1,3 +1,4 @@
+pub use massa_models::ledger_proof::{BALANCE_IDENT, BYTECODE_IDENT, DATASTORE_IDENT};
 use massa_models::{
     address::{Address, AddressDeserializer, ADDRESS_SIZE_BYTES},
     serialization::{VecU8Deserializer, VecU8Serializer},
@@ -6,10 +7,6 @@ use massa_serialization::{DeserializeError, Deserializer, SerializeError, Serial
 use nom::error::{ContextError, ParseError};
 use std::ops::Bound::Included;
 
-pub const BALANCE_IDENT: u8 = 0u8;
-pub const BYTECODE_IDENT: u8 = 1u8;
-pub const DATASTORE_IDENT: u8 = 2u8;
-
 /// Balance key formatting macro
 #[macro_export]
 macro_rules! balance_key {
@@ -10,6 +10,7 @@ use crate::types::{
 };
 use massa_models::address::{Address, AddressDeserializer};
 use massa_models::amount::{Amount, AmountDeserializer, AmountSerializer};
+use massa_models::execution::ReadOnlyLedgerChange;
 use massa_models::prehash::PreHashMap;
 use massa_models::serialization::{VecU8Deserializer, VecU8Serializer};
 use massa_serialization::{
@@ -515,6 +516,47 @@ impl Applicable<LedgerChanges> for LedgerChanges {
 }
 
 impl LedgerChanges {
+    /// Summarizes the changes of each address, sorted by address
+    pub fn summarize(&self) -> Vec<ReadOnlyLedgerChange> {
+        let mut summary: Vec<ReadOnlyLedgerChange> = self
+            .0
+            .iter()
+            .map(|(address, change)| {
+                let mut res = ReadOnlyLedgerChange {
+                    address: *address,
+                    deleted: false,
+                    balance: None,
+                    bytecode_changed: false,
+                    datastore_set_keys: Vec::new(),
+                    datastore_deleted_keys: Vec::new(),
+                };
+                match change {
+                    SetUpdateOrDelete::Set(entry) => {
+                        res.balance = Some(entry.balance);
+                        res.bytecode_changed = !entry.bytecode.is_empty();
+                        res.datastore_set_keys = entry.datastore.keys().cloned().collect();
+                    }
+                    SetUpdateOrDelete::Update(update) => {
+                        if let SetOrKeep::Set(balance) = update.balance {
+                            res.balance = Some(balance);
+                        }
+                        res.bytecode_changed = matches!(update.bytecode, SetOrKeep::Set(_));
+                        for (key, value_update) in update.datastore.iter() {
+                            match value_update {
+                                SetOrDelete::Set(_) => res.datastore_set_keys.push(key.clone()),
+                                SetOrDelete::Delete => res.datastore_deleted_keys.push(key.clone()),
+                            }
+                        }
+                    }
+                    SetUpdateOrDelete::Delete => res.deleted = true,
+                }
+                res
+            })
+            .collect();
+        summary.sort_unstable_by_key(|change| change.address);
+        summary
+    }
+
     /// Get an item from the `LedgerChanges`
     pub fn get(
         &self,
@@ -5,12 +5,14 @@
 use crate::ledger_db::{LedgerDB, LedgerSubEntry};
 use massa_hash::Hash;
 use massa_ledger_exports::{
-    LedgerChanges, LedgerConfig, LedgerController, LedgerEntry, LedgerError,
+    balance_key, data_key, LedgerChanges, LedgerConfig, LedgerController, LedgerEntry, LedgerError,
+    BALANCE_IDENT, DATASTORE_IDENT,
 };
 use massa_models::{
     address::Address,
     amount::{Amount, AmountDeserializer},
     error::ModelsError,
+    ledger_proof::LedgerEntryProof,
     slot::Slot,
     streaming_step::StreamingStep,
 };
@@ -39,6 +41,7 @@ impl FinalLedger {
             config.disk_ledger_path.clone(),
             config.thread_count,
             config.max_key_length,
+            config.max_value_length,
             config.max_ledger_part_size,
         );
 
@@ -159,11 +162,51 @@ impl LedgerController for FinalLedger {
         }
     }
 
+    /// Get the datastore keys of a given address starting with `prefix`, in ascending order.
+    ///
+    /// # Returns
+    /// At most `limit` keys strictly greater than `start_key` if set
+    fn get_datastore_keys_page(
+        &self,
+        addr: &Address,
+        prefix: &[u8],
+        start_key: Option<&[u8]>,
+        limit: usize,
+    ) -> Option<Vec<Vec<u8>>> {
+        match self.entry_exists(addr) {
+            true => Some(
+                self.sorted_ledger
+                    .get_datastore_keys_page(addr, prefix, start_key, limit),
+            ),
+            false => None,
+        }
+    }
+
     /// Get the current disk ledger hash
     fn get_ledger_hash(&self) -> Hash {
         self.sorted_ledger.get_ledger_hash()
     }
 
+    /// Get the root of the ledger Merkle tree
+    fn get_ledger_root(&self) -> Hash {
+        self.sorted_ledger.get_merkle_root()
+    }
+
+    /// Get the Merkle proof of the balance (if `datastore_key` is `None`) or of a datastore entry of an address
+    fn get_entry_proof(&self, addr: &Address, datastore_key: Option<&[u8]>) -> LedgerEntryProof {
+        match datastore_key {
+            Some(key) => self.sorted_ledger.get_merkle_proof(&data_key!(addr, key)),
+            None => self.sorted_ledger.get_merkle_proof(&balance_key!(addr)),
+        }
+    }
+
+    /// Get the sum of the balances of every ledger entry
+    ///
+    /// The sum is maintained by each write, so this does not iterate over the ledger.
+    fn get_total_balance(&self) -> Amount {
+        self.sorted_ledger.get_total_balance()
+    }
+
     /// Get a part of the disk ledger.
     ///
     /// Solely used by the bootstrap.
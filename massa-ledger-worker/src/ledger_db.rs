@@ -6,13 +6,17 @@ use massa_hash::{Hash, HASH_SIZE_BYTES};
 use massa_ledger_exports::*;
 use massa_models::{
     address::{Address, ADDRESS_SIZE_BYTES},
-    amount::AmountSerializer,
+    amount::{Amount, AmountDeserializer, AmountSerializer},
     error::ModelsError,
+    ledger_proof::{
+        ledger_merkle_empty_hash, ledger_merkle_leaf_hash, ledger_merkle_node_hash,
+        ledger_merkle_path, ledger_merkle_path_bit, LedgerEntryProof, LEDGER_MERKLE_DEPTH,
+    },
     serialization::{VecU8Deserializer, VecU8Serializer},
     slot::{Slot, SlotSerializer},
     streaming_step::StreamingStep,
 };
-use massa_serialization::{Deserializer, Serializer, U64VarIntSerializer};
+use massa_serialization::{DeserializeError, Deserializer, Serializer, U64VarIntSerializer};
 use nom::multi::many0;
 use nom::sequence::tuple;
 use rocksdb::{
@@ -28,19 +32,27 @@ use std::{
     convert::TryInto,
 };
 
-#[cfg(feature = "testing")]
-use massa_models::amount::{Amount, AmountDeserializer};
-
 const LEDGER_CF: &str = "ledger";
 const METADATA_CF: &str = "metadata";
+const MERKLE_CF: &str = "merkle";
 const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
 const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
 const CF_ERROR: &str = "critical: rocksdb column family operation failed";
 const LEDGER_HASH_ERROR: &str = "critical: saved ledger hash is corrupted";
+const MERKLE_NODE_ERROR: &str = "critical: saved ledger merkle node is corrupted";
 const KEY_LEN_SER_ERROR: &str = "critical: key length serialization failed";
+const BALANCE_DESER_ERROR: &str = "critical: balance deserialization failed";
 const SLOT_KEY: &[u8; 1] = b"s";
 const LEDGER_HASH_KEY: &[u8; 1] = b"h";
+const TOTAL_BALANCE_KEY: &[u8; 1] = b"b";
+const FORMAT_VERSION_KEY: &[u8; 1] = b"v";
+/// Version of the layout of the ledger keys, to be incremented on every layout change.
+/// Version 1 prefixes the addresses of the keys with their category.
+const LEDGER_FORMAT_VERSION: u8 = 1;
+const FORMAT_VERSION_ERROR: &str = "critical: the disk ledger was written with an incompatible key format, delete it and bootstrap again";
 const LEDGER_HASH_INITIAL_BYTES: &[u8; 32] = &[0; HASH_SIZE_BYTES];
+/// Number of ledger keys inserted per batch when rebuilding the ledger Merkle tree
+const MERKLE_REBUILD_BATCH_SIZE: usize = 10_000;
 
 /// Ledger sub entry enum
 pub enum LedgerSubEntry {
@@ -62,8 +74,8 @@ pub(crate) struct LedgerDB {
     slot_serializer: SlotSerializer,
     len_serializer: U64VarIntSerializer,
     max_datastore_key_length: u8,
+    max_value_length: u64,
     ledger_part_size_message_bytes: u64,
-    #[cfg(feature = "testing")]
     amount_deserializer: AmountDeserializer,
 }
 
@@ -96,6 +108,50 @@ fn test_end_prefix() {
     assert_eq!(end_prefix(&[5, 6, 255]), Some(vec![5, 7]));
 }
 
+/// Returns true if `key` is the balance key of a ledger entry
+fn is_balance_key(key: &[u8]) -> bool {
+    key.len() == ADDRESS_SIZE_BYTES + 1 && key[ADDRESS_SIZE_BYTES] == BALANCE_IDENT
+}
+
+/// Key of the ledger Merkle tree node located at `depth` on the path `path`.
+/// It is made of the depth followed by the path bits above the node, the other bits being zeroed.
+fn merkle_node_key(path: &[u8; HASH_SIZE_BYTES], depth: usize) -> Vec<u8> {
+    let mut prefix = *path;
+    for (index, byte) in prefix.iter_mut().enumerate() {
+        let start = index * 8;
+        if depth <= start {
+            *byte = 0;
+        } else if depth < start + 8 {
+            *byte &= 0xff << (start + 8 - depth);
+        }
+    }
+    [&(depth as u16).to_be_bytes()[..], &prefix].concat()
+}
+
+/// Path of the sibling of the node located at `depth` (at least 1) on the path `path`
+fn merkle_sibling_path(path: &Hash, depth: usize) -> [u8; HASH_SIZE_BYTES] {
+    let mut sibling = *path.to_bytes();
+    let bit = depth - 1;
+    sibling[bit / 8] ^= 0x80 >> (bit % 8);
+    sibling
+}
+
+/// Keys of the siblings of the nodes on the path `path`, from the leaves to the root
+fn merkle_sibling_keys(path: &Hash) -> Vec<Vec<u8>> {
+    (1..=LEDGER_MERKLE_DEPTH)
+        .rev()
+        .map(|depth| merkle_node_key(&merkle_sibling_path(path, depth), depth))
+        .collect()
+}
+
+/// Ledger Merkle tree node stored as `bytes`, absent nodes being empty
+fn merkle_node_from_bytes(bytes: Option<Vec<u8>>) -> Hash {
+    match bytes {
+        Some(bytes) => Hash::from_bytes(&bytes.try_into().expect(MERKLE_NODE_ERROR)),
+        None => ledger_merkle_empty_hash(),
+    }
+}
+
 /// Batch containing write operations to perform on disk and cache for the ledger hash computing
 pub struct LedgerBatch {
     // Rocksdb write batch
@@ -104,6 +160,14 @@ pub struct LedgerBatch {
     ledger_hash: Hash,
     // Added entry hashes in the current batch
     aeh_list: BTreeMap<Vec<u8>, Hash>,
+    // New ledger Merkle tree leaf hashes in the current batch
+    merkle_leaves: BTreeMap<Vec<u8>, Hash>,
+    // Balances written in the current batch, by balance key
+    balances: BTreeMap<Vec<u8>, Amount>,
+    // Sum of the balances written in the current batch
+    balance_added: Amount,
+    // Sum of the balances overwritten or deleted in the current batch
+    balance_removed: Amount,
 }
 
 impl LedgerBatch {
@@ -112,6 +176,10 @@ impl LedgerBatch {
             write_batch: WriteBatch::default(),
             ledger_hash,
             aeh_list: BTreeMap::new(),
+            merkle_leaves: BTreeMap::new(),
+            balances: BTreeMap::new(),
+            balance_added: Amount::zero(),
+            balance_removed: Amount::zero(),
         }
     }
 }
@@ -125,6 +193,7 @@ impl LedgerDB {
         path: PathBuf,
         thread_count: u8,
         max_datastore_key_length: u8,
+        max_value_length: u64,
         ledger_part_size_message_bytes: u64,
     ) -> Self {
         let mut db_opts = Options::default();
@@ -137,23 +206,130 @@ impl LedgerDB {
             vec![
                 ColumnFamilyDescriptor::new(LEDGER_CF, Options::default()),
                 ColumnFamilyDescriptor::new(METADATA_CF, Options::default()),
+                ColumnFamilyDescriptor::new(MERKLE_CF, Options::default()),
             ],
         )
         .expect(OPEN_ERROR);
 
-        LedgerDB {
+        let ledger_db = LedgerDB {
             db,
             thread_count,
             amount_serializer: AmountSerializer::new(),
             slot_serializer: SlotSerializer::new(),
             len_serializer: U64VarIntSerializer::new(),
             max_datastore_key_length,
+            max_value_length,
             ledger_part_size_message_bytes,
-            #[cfg(feature = "testing")]
             amount_deserializer: AmountDeserializer::new(
                 Bound::Included(Amount::MIN),
                 Bound::Included(Amount::MAX),
             ),
+        };
+        ledger_db.check_format_version();
+        ledger_db.rebuild_merkle_tree_if_missing();
+        ledger_db.compute_total_balance_if_missing();
+        ledger_db
+    }
+
+    /// Compute the total balance of a ledger that was created before it was maintained
+    fn compute_total_balance_if_missing(&self) {
+        let handle = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        if self
+            .db
+            .get_cf(handle, TOTAL_BALANCE_KEY)
+            .expect(CRUD_ERROR)
+            .is_some()
+        {
+            return;
+        }
+        let ledger_handle = self.db.cf_handle(LEDGER_CF).expect(CF_ERROR);
+        let mut total_balance = Amount::zero();
+        for (key, entry) in self
+            .db
+            .iterator_cf(ledger_handle, IteratorMode::Start)
+            .flatten()
+        {
+            if is_balance_key(&key) {
+                let (_, amount) = self
+                    .amount_deserializer
+                    .deserialize::<DeserializeError>(&entry)
+                    .expect(BALANCE_DESER_ERROR);
+                total_balance = total_balance.saturating_add(amount);
+            }
+        }
+        let mut bytes = Vec::new();
+        // Amount serialization never fails
+        self.amount_serializer
+            .serialize(&total_balance, &mut bytes)
+            .unwrap();
+        self.db
+            .put_cf(handle, TOTAL_BALANCE_KEY, bytes)
+            .expect(CRUD_ERROR);
+    }
+
+    /// Check that a non-empty ledger was written with the current key format,
+    /// and record the format of an empty one.
+    ///
+    /// Ledgers written before the format was recorded used keys without address category,
+    /// which cannot be converted: they are refused.
+    fn check_format_version(&self) {
+        let handle = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        let ledger_handle = self.db.cf_handle(LEDGER_CF).expect(CF_ERROR);
+        match self
+            .db
+            .get_cf(handle, FORMAT_VERSION_KEY)
+            .expect(CRUD_ERROR)
+        {
+            Some(version) => {
+                if version.as_slice() != [LEDGER_FORMAT_VERSION] {
+                    panic!("{}", FORMAT_VERSION_ERROR);
+                }
+            }
+            None => {
+                if self
+                    .db
+                    .iterator_cf(ledger_handle, IteratorMode::Start)
+                    .next()
+                    .is_some()
+                {
+                    panic!("{}", FORMAT_VERSION_ERROR);
+                }
+                self.db
+                    .put_cf(handle, FORMAT_VERSION_KEY, [LEDGER_FORMAT_VERSION])
+                    .expect(CRUD_ERROR);
+            }
+        }
+    }
+
+    /// Build the ledger Merkle tree of a ledger that was created before it existed
+    fn rebuild_merkle_tree_if_missing(&self) {
+        let ledger_handle = self.db.cf_handle(LEDGER_CF).expect(CF_ERROR);
+        let merkle_handle = self.db.cf_handle(MERKLE_CF).expect(CF_ERROR);
+        if self
+            .db
+            .iterator_cf(merkle_handle, IteratorMode::Start)
+            .next()
+            .is_some()
+        {
+            return;
+        }
+        let mut batch = LedgerBatch::new(self.get_ledger_hash());
+        for (key, value) in self
+            .db
+            .iterator_cf(ledger_handle, IteratorMode::Start)
+            .flatten()
+        {
+            batch
+                .merkle_leaves
+                .insert(key.to_vec(), ledger_merkle_leaf_hash(&key, &value));
+            if batch.merkle_leaves.len() >= MERKLE_REBUILD_BATCH_SIZE {
+                let ledger_hash = batch.ledger_hash;
+                self.write_batch(batch);
+                batch = LedgerBatch::new(ledger_hash);
+            }
+        }
+        if !batch.merkle_leaves.is_empty() {
+            self.write_batch(batch);
         }
     }
 
@@ -212,13 +388,117 @@ impl LedgerDB {
 
     /// Apply the given operation batch to the disk ledger
     fn write_batch(&self, mut batch: LedgerBatch) {
+        self.update_merkle_tree(&mut batch);
         let handle = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
         batch
             .write_batch
             .put_cf(handle, LEDGER_HASH_KEY, batch.ledger_hash.to_bytes());
+        if !batch.balances.is_empty() {
+            let total_balance = self
+                .get_total_balance()
+                .saturating_add(batch.balance_added)
+                .saturating_sub(batch.balance_removed);
+            let mut bytes = Vec::new();
+            // Amount serialization never fails
+            self.amount_serializer
+                .serialize(&total_balance, &mut bytes)
+                .unwrap();
+            batch.write_batch.put_cf(handle, TOTAL_BALANCE_KEY, bytes);
+        }
         self.db.write(batch.write_batch).expect(CRUD_ERROR);
     }
 
+    /// Get a ledger Merkle tree node
+    fn get_merkle_node(&self, node_key: &[u8]) -> Hash {
+        let handle = self.db.cf_handle(MERKLE_CF).expect(CF_ERROR);
+        merkle_node_from_bytes(self.db.get_cf(handle, node_key).expect(CRUD_ERROR))
+    }
+
+    /// Get a batch of ledger Merkle tree nodes with a single read
+    fn get_merkle_nodes<'a>(&self, node_keys: impl IntoIterator<Item = &'a [u8]>) -> Vec<Hash> {
+        let handle = self.db.cf_handle(MERKLE_CF).expect(CF_ERROR);
+        self.db
+            .multi_get_cf(node_keys.into_iter().map(|node_key| (handle, node_key)))
+            .into_iter()
+            .map(|bytes| merkle_node_from_bytes(bytes.expect(CRUD_ERROR)))
+            .collect()
+    }
+
+    /// Recompute the ledger Merkle tree nodes on the path of every leaf modified by the batch.
+    ///
+    /// The siblings of each path are read in a single batch, and are kept along with
+    /// the recomputed nodes for the next paths, which share their upper nodes.
+    fn update_merkle_tree(&self, batch: &mut LedgerBatch) {
+        let handle = self.db.cf_handle(MERKLE_CF).expect(CF_ERROR);
+        let mut updated_nodes: HashMap<Vec<u8>, Hash> = HashMap::new();
+        let mut read_nodes: HashMap<Vec<u8>, Hash> = HashMap::new();
+        for (key, leaf_hash) in std::mem::take(&mut batch.merkle_leaves) {
+            let path = ledger_merkle_path(&key);
+            let sibling_keys = merkle_sibling_keys(&path);
+
+            // read the siblings that were neither updated nor read before
+            let unknown_keys: Vec<&[u8]> = sibling_keys
+                .iter()
+                .filter(|node_key| {
+                    !updated_nodes.contains_key(*node_key) && !read_nodes.contains_key(*node_key)
+                })
+                .map(|node_key| node_key.as_slice())
+                .collect();
+            let unknown_nodes = self.get_merkle_nodes(unknown_keys.iter().copied());
+            for (node_key, hash) in unknown_keys.into_iter().zip(unknown_nodes) {
+                read_nodes.insert(node_key.to_vec(), hash);
+            }
+
+            let mut node = leaf_hash;
+            for (depth, sibling_key) in (1..=LEDGER_MERKLE_DEPTH).rev().zip(&sibling_keys) {
+                updated_nodes.insert(merkle_node_key(path.to_bytes(), depth), node);
+                let sibling = *updated_nodes
+                    .get(sibling_key)
+                    .or_else(|| read_nodes.get(sibling_key))
+                    .expect("the sibling node should have been read");
+                node = if ledger_merkle_path_bit(&path, depth - 1) {
+                    ledger_merkle_node_hash(&sibling, &node)
+                } else {
+                    ledger_merkle_node_hash(&node, &sibling)
+                };
+            }
+            updated_nodes.insert(merkle_node_key(path.to_bytes(), 0), node);
+        }
+        // only the non-empty nodes are stored
+        for (node_key, hash) in updated_nodes {
+            if hash == ledger_merkle_empty_hash() {
+                batch.write_batch.delete_cf(handle, node_key);
+            } else {
+                batch.write_batch.put_cf(handle, node_key, hash.to_bytes());
+            }
+        }
+    }
+
+    /// Get the root of the ledger Merkle tree
+    pub fn get_merkle_root(&self) -> Hash {
+        self.get_merkle_node(&merkle_node_key(&[0; HASH_SIZE_BYTES], 0))
+    }
+
+    /// Get the inclusion (or exclusion) proof of a raw ledger key in the ledger Merkle tree
+    pub fn get_merkle_proof(&self, key: &[u8]) -> LedgerEntryProof {
+        let handle = self.db.cf_handle(LEDGER_CF).expect(CF_ERROR);
+        let path = ledger_merkle_path(key);
+        let empty_hash = ledger_merkle_empty_hash();
+        let sibling_keys = merkle_sibling_keys(&path);
+        let siblings = self
+            .get_merkle_nodes(sibling_keys.iter().map(|node_key| node_key.as_slice()))
+            .into_iter()
+            .enumerate()
+            .filter(|(_, sibling)| *sibling != empty_hash)
+            .map(|(height, sibling)| (height as u8, sibling))
+            .collect();
+        LedgerEntryProof {
+            key: key.to_vec(),
+            value: self.db.get_cf(handle, key).expect(CRUD_ERROR),
+            siblings,
+        }
+    }
+
     /// Set the disk ledger slot metadata
     ///
     /// # Arguments
@@ -255,6 +535,60 @@ impl LedgerDB {
         }
     }
 
+    /// Get the sum of the balances of every ledger entry.
+    ///
+    /// The sum is maintained by each write, so this does not iterate over the ledger.
+    pub fn get_total_balance(&self) -> Amount {
+        let handle = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        match self.db.get_cf(handle, TOTAL_BALANCE_KEY).expect(CRUD_ERROR) {
+            Some(bytes) => {
+                self.amount_deserializer
+                    .deserialize::<DeserializeError>(&bytes)
+                    .expect(BALANCE_DESER_ERROR)
+                    .1
+            }
+            None => Amount::zero(),
+        }
+    }
+
+    /// Internal function to keep track of the balances written by a batch, to update the total balance.
+    /// `value` is the new value of `key`, `None` if it is deleted.
+    fn note_balance_write(
+        &self,
+        handle: &ColumnFamily,
+        batch: &mut LedgerBatch,
+        key: &[u8],
+        value: Option<&[u8]>,
+    ) {
+        if !is_balance_key(key) {
+            return;
+        }
+        let prev_balance = match batch.balances.get(key) {
+            Some(prev_balance) => Some(*prev_balance),
+            None => self.db.get_cf(handle, key).expect(CRUD_ERROR).map(|bytes| {
+                self.amount_deserializer
+                    .deserialize::<DeserializeError>(&bytes)
+                    .expect(BALANCE_DESER_ERROR)
+                    .1
+            }),
+        };
+        if let Some(prev_balance) = prev_balance {
+            batch.balance_removed = batch.balance_removed.saturating_add(prev_balance);
+        }
+        let balance = match value {
+            Some(bytes) => {
+                let (_, balance) = self
+                    .amount_deserializer
+                    .deserialize::<DeserializeError>(bytes)
+                    .expect(BALANCE_DESER_ERROR);
+                batch.balance_added = batch.balance_added.saturating_add(balance);
+                balance
+            }
+            None => Amount::zero(),
+        };
+        batch.balances.insert(key.to_vec(), balance);
+    }
+
     /// Internal function to put a key & value and perform the ledger hash XORs
     fn put_entry_value(
         &self,
@@ -270,6 +604,8 @@ impl LedgerDB {
         let hash = Hash::compute_from(&[&len_bytes, key, value].concat());
         batch.ledger_hash ^= hash;
         batch.aeh_list.insert(key.to_vec(), hash);
+        batch.merkle_leaves.insert(key.to_vec(), hash);
+        self.note_balance_write(handle, batch, key, Some(value));
         batch.write_batch.put_cf(handle, key, value);
     }
 
@@ -347,6 +683,42 @@ impl LedgerDB {
             .collect()
     }
 
+    /// Get the datastore keys of a given address starting with `prefix`, in ascending order.
+    ///
+    /// # Arguments
+    /// * `prefix`: prefix of the returned keys
+    /// * `start_key`: if set, only the keys strictly greater than it are returned
+    /// * `limit`: maximum number of returned keys
+    pub fn get_datastore_keys_page(
+        &self,
+        addr: &Address,
+        prefix: &[u8],
+        start_key: Option<&[u8]>,
+        limit: usize,
+    ) -> Vec<Vec<u8>> {
+        let handle = self.db.cf_handle(LEDGER_CF).expect(CF_ERROR);
+
+        let range_start = data_key!(addr, prefix);
+        let mut opt = ReadOptions::default();
+        if let Some(range_end) = end_prefix(&range_start) {
+            opt.set_iterate_upper_bound(range_end);
+        }
+
+        // skip the keys before the start key if it is in the prefix range
+        let from = match start_key {
+            Some(start_key) if start_key > prefix => data_key!(addr, start_key),
+            _ => range_start,
+        };
+
+        self.db
+            .iterator_cf_opt(handle, opt, IteratorMode::From(&from, Direction::Forward))
+            .flatten()
+            .map(|(key, _)| key.split_at(ADDRESS_SIZE_BYTES + 1).1.to_vec())
+            .filter(|key| start_key.map_or(true, |start_key| key.as_slice() > start_key))
+            .take(limit)
+            .collect()
+    }
+
     /// Internal function to update a key & value and perform the ledger hash XORs
     fn update_key_value(
         &self,
@@ -367,6 +739,8 @@ impl LedgerDB {
         let hash = Hash::compute_from(&[&len_bytes, key, value].concat());
         batch.ledger_hash ^= hash;
         batch.aeh_list.insert(key.to_vec(), hash);
+        batch.merkle_leaves.insert(key.to_vec(), hash);
+        self.note_balance_write(handle, batch, key, Some(value));
         batch.write_batch.put_cf(handle, key, value);
     }
 
@@ -413,13 +787,20 @@ impl LedgerDB {
     fn delete_key(&self, handle: &ColumnFamily, batch: &mut LedgerBatch, key: &[u8]) {
         if let Some(added_hash) = batch.aeh_list.get(key) {
             batch.ledger_hash ^= *added_hash;
+            batch
+                .merkle_leaves
+                .insert(key.to_vec(), ledger_merkle_empty_hash());
         } else if let Some(prev_bytes) = self.db.get_cf(handle, key).expect(CRUD_ERROR) {
             let mut len_bytes = Vec::new();
             self.len_serializer
                 .serialize(&(key.len() as u64), &mut len_bytes)
                 .expect(KEY_LEN_SER_ERROR);
             batch.ledger_hash ^= Hash::compute_from(&[&len_bytes, key, &prev_bytes].concat());
+            batch
+                .merkle_leaves
+                .insert(key.to_vec(), ledger_merkle_empty_hash());
         }
+        self.note_balance_write(handle, batch, key, None);
         batch.write_batch.delete_cf(handle, key);
     }
 
@@ -518,7 +899,7 @@ impl LedgerDB {
     ) -> Result<StreamingStep<Vec<u8>>, ModelsError> {
         let handle = self.db.cf_handle(LEDGER_CF).expect(CF_ERROR);
         let vec_u8_deserializer =
-            VecU8Deserializer::new(Bound::Included(0), Bound::Excluded(u64::MAX));
+            VecU8Deserializer::new(Bound::Included(0), Bound::Included(self.max_value_length));
         let key_deserializer = KeyDeserializer::new(self.max_datastore_key_length);
         let mut last_key = Rc::new(Vec::new());
         let mut batch = LedgerBatch::new(self.get_ledger_hash());
@@ -561,7 +942,6 @@ impl LedgerDB {
         &self,
     ) -> std::collections::BTreeMap<Address, massa_models::amount::Amount> {
         use massa_models::address::AddressDeserializer;
-        use massa_serialization::DeserializeError;
 
         let handle = self.db.cf_handle(LEDGER_CF).expect(CF_ERROR);
 
@@ -623,12 +1003,19 @@ impl LedgerDB {
 #[cfg(test)]
 mod tests {
     use super::LedgerDB;
-    use crate::ledger_db::{LedgerBatch, LedgerSubEntry, LEDGER_HASH_INITIAL_BYTES};
+    use crate::ledger_db::{
+        LedgerBatch, LedgerSubEntry, FORMAT_VERSION_KEY, LEDGER_HASH_INITIAL_BYTES, METADATA_CF,
+    };
     use massa_hash::Hash;
-    use massa_ledger_exports::{LedgerEntry, LedgerEntryUpdate, SetOrKeep};
+    use massa_ledger_exports::{
+        balance_key, data_key, LedgerEntry, LedgerEntryUpdate, SetOrKeep, BALANCE_IDENT,
+        DATASTORE_IDENT,
+    };
     use massa_models::{
         address::Address,
         amount::{Amount, AmountDeserializer},
+        ledger_proof::{ledger_merkle_empty_hash, FinalLedgerEntryProof},
+        slot::Slot,
         streaming_step::StreamingStep,
     };
     use massa_serialization::{DeserializeError, Deserializer};
@@ -658,7 +1045,7 @@ mod tests {
 
         // write data
         let temp_dir = TempDir::new().unwrap();
-        let mut db = LedgerDB::new(temp_dir.path().to_path_buf(), 32, 255, 1_000_000);
+        let mut db = LedgerDB::new(temp_dir.path().to_path_buf(), 32, 255, 1_000, 1_000_000);
         let mut batch = LedgerBatch::new(Hash::from_bytes(LEDGER_HASH_INITIAL_BYTES));
         db.put_entry(&addr, entry, &mut batch);
         db.update_entry(&addr, entry_update, &mut batch);
@@ -693,6 +1080,7 @@ mod tests {
             Hash::from_bytes(LEDGER_HASH_INITIAL_BYTES),
             db.get_ledger_hash()
         );
+        assert_eq!(db.get_total_balance(), Amount::from_str("21").unwrap());
 
         // delete entry
         let mut batch = LedgerBatch::new(ledger_hash);
@@ -706,6 +1094,7 @@ mod tests {
         );
         assert!(db.get_sub_entry(&addr, LedgerSubEntry::Balance).is_none());
         assert!(db.get_entire_datastore(&addr).is_empty());
+        assert_eq!(db.get_total_balance(), Amount::zero());
     }
 
     #[test]
@@ -716,4 +1105,143 @@ mod tests {
         let res = db.get_ledger_part(StreamingStep::Started).unwrap();
         db.set_ledger_part(&res.0[..]).unwrap();
     }
+
+    #[test]
+    fn test_ledger_part_with_oversized_value() {
+        let addr = Address::from_public_key(&KeyPair::generate().get_public_key());
+        let (mut db, _) = init_test_ledger(addr);
+        let mut batch = LedgerBatch::new(db.get_ledger_hash());
+        let entry = LedgerEntry {
+            datastore: BTreeMap::from([(b"big".to_vec(), vec![0u8; 1_001])]),
+            ..Default::default()
+        };
+        db.put_entry(&addr, entry, &mut batch);
+        db.write_batch(batch);
+
+        let temp_dir = TempDir::new().unwrap();
+        let copy_db = LedgerDB::new(temp_dir.path().to_path_buf(), 32, 255, 1_000, 1_000_000);
+        let (part, _) = db.get_ledger_part(StreamingStep::Started).unwrap();
+        assert!(copy_db.set_ledger_part(&part).is_err());
+    }
+
+    #[test]
+    fn test_datastore_keys_page() {
+        let addr = Address::from_public_key(&KeyPair::generate().get_public_key());
+        let (db, _) = init_test_ledger(addr);
+
+        // pages over the whole datastore
+        assert_eq!(
+            db.get_datastore_keys_page(&addr, b"", None, 2),
+            vec![b"1".to_vec(), b"2".to_vec()]
+        );
+        assert_eq!(
+            db.get_datastore_keys_page(&addr, b"", Some(&b"2"[..]), 2),
+            vec![b"3".to_vec()]
+        );
+        assert!(db
+            .get_datastore_keys_page(&addr, b"", Some(&b"3"[..]), 2)
+            .is_empty());
+
+        // pages restricted to a prefix
+        assert_eq!(
+            db.get_datastore_keys_page(&addr, b"2", None, 10),
+            vec![b"2".to_vec()]
+        );
+        assert_eq!(
+            db.get_datastore_keys_page(&addr, b"2", Some(&b"1"[..]), 10),
+            vec![b"2".to_vec()]
+        );
+        assert!(db
+            .get_datastore_keys_page(&addr, b"2", Some(&b"2"[..]), 10)
+            .is_empty());
+    }
+    #[test]
+    fn test_ledger_merkle_proofs() {
+        let addr = Address::from_public_key(&KeyPair::generate().get_public_key());
+        let (db, _) = init_test_ledger(addr);
+        let root = db.get_merkle_root();
+        assert_ne!(root, ledger_merkle_empty_hash());
+
+        // inclusion proof
+        let proof = db.get_merkle_proof(&data_key!(addr, b"2".to_vec()));
+        assert_eq!(proof.value, Some(b"b".to_vec()));
+        assert_eq!(proof.compute_root(), root);
+
+        // exclusion proof
+        let proof = db.get_merkle_proof(&data_key!(addr, b"4".to_vec()));
+        assert_eq!(proof.value, None);
+        assert_eq!(proof.compute_root(), root);
+
+        // a forged value does not lead to the root
+        let mut forged_proof = db.get_merkle_proof(&balance_key!(addr));
+        forged_proof.value = Some(b"forged".to_vec());
+        assert_ne!(forged_proof.compute_root(), root);
+
+        // a proof is only valid for the entry it was requested for
+        let state_hash_prefix = b"prefix".to_vec();
+        let final_proof = FinalLedgerEntryProof {
+            address: addr,
+            datastore_key: Some(b"2".to_vec()),
+            slot: Slot::new(1, 0),
+            final_state_hash: Hash::compute_from(
+                &[state_hash_prefix.as_slice(), root.to_bytes()].concat(),
+            ),
+            state_hash_prefix,
+            ledger_proof: db.get_merkle_proof(&data_key!(addr, b"2".to_vec())),
+        };
+        assert!(final_proof.verify());
+        let swapped_proof = FinalLedgerEntryProof {
+            datastore_key: Some(b"1".to_vec()),
+            ..final_proof.clone()
+        };
+        assert!(!swapped_proof.verify());
+        let swapped_proof = FinalLedgerEntryProof {
+            datastore_key: None,
+            ..final_proof
+        };
+        assert!(!swapped_proof.verify());
+
+        // the root does not depend on the insertion order
+        let temp_dir = TempDir::new().unwrap();
+        let copy_db = LedgerDB::new(temp_dir.path().to_path_buf(), 32, 255, 1_000, 1_000_000);
+        let mut cursor = StreamingStep::Started;
+        loop {
+            let (part, new_cursor) = db.get_ledger_part(cursor).unwrap();
+            if part.is_empty() {
+                break;
+            }
+            copy_db.set_ledger_part(&part).unwrap();
+            cursor = new_cursor;
+        }
+        assert_eq!(copy_db.get_merkle_root(), root);
+
+        // deleting every key empties the tree
+        let mut batch = LedgerBatch::new(db.get_ledger_hash());
+        db.delete_entry(&addr, &mut batch);
+        db.write_batch(batch);
+        assert_eq!(db.get_merkle_root(), ledger_merkle_empty_hash());
+    }
+
+    #[test]
+    #[should_panic(expected = "incompatible key format")]
+    fn test_ledger_format_version() {
+        let addr = Address::from_public_key(&KeyPair::generate().get_public_key());
+        let temp_dir = TempDir::new().unwrap();
+        let open = || LedgerDB::new(temp_dir.path().to_path_buf(), 32, 255, 1_000, 1_000_000);
+        let mut db = open();
+        let mut batch = LedgerBatch::new(Hash::from_bytes(LEDGER_HASH_INITIAL_BYTES));
+        db.put_entry(&addr, LedgerEntry::default(), &mut batch);
+        db.write_batch(batch);
+        drop(db);
+
+        // a ledger written with the current format is reopened
+        let db = open();
+        assert!(db.get_sub_entry(&addr, LedgerSubEntry::Balance).is_some());
+
+        // a ledger written before the format was recorded is refused
+        let handle = db.db.cf_handle(METADATA_CF).unwrap();
+        db.db.delete_cf(handle, FORMAT_VERSION_KEY).unwrap();
+        drop(db);
+        open();
+    }
 }
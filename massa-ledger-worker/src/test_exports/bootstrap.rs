@@ -18,6 +18,7 @@ pub fn create_final_ledger(
         temp_dir.path().to_path_buf(),
         config.thread_count,
         config.max_key_length,
+        config.max_value_length,
         config.max_ledger_part_size,
     );
     db.load_initial_ledger(initial_ledger);
@@ -5,7 +5,7 @@ use tempfile::TempDir;
 
 use crate::{ledger_db::LedgerDB, FinalLedger};
 use massa_models::config::{
-    LEDGER_PART_SIZE_MESSAGE_BYTES, MAX_DATASTORE_KEY_LENGTH, THREAD_COUNT,
+    LEDGER_PART_SIZE_MESSAGE_BYTES, MAX_DATASTORE_KEY_LENGTH, MAX_LEDGER_VALUE_LENGTH, THREAD_COUNT,
 };
 
 /// Default value of `FinalLedger` used for tests
@@ -16,6 +16,7 @@ impl Default for FinalLedger {
             temp_dir.path().to_path_buf(),
             THREAD_COUNT,
             MAX_DATASTORE_KEY_LENGTH,
+            MAX_LEDGER_VALUE_LENGTH,
             LEDGER_PART_SIZE_MESSAGE_BYTES,
         );
         FinalLedger {
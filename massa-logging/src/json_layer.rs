@@ -0,0 +1,166 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+//! Export of the logs as JSON lines
+//!
+//! Each event is written as one JSON object holding its level, target and fields,
+//! along with the fields of all the spans it happened in, from the outermost one.
+//! Spans carry correlation ids such as the `(period, thread)` of the processed slot,
+//! so that the life of a block can be followed across workers.
+
+use serde_json::{json, Map, Value};
+use std::fmt::Debug;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+/// Fields of a span, stored in its extensions
+struct SpanFields(Map<String, Value>);
+
+/// Collects the fields of a span or event in a JSON map
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .insert(field.name().into(), format!("{:?}", value).into());
+    }
+}
+
+/// Layer writing the logs as JSON lines
+pub struct JsonLogLayer {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl JsonLogLayer {
+    /// Creates a layer writing one JSON object per line to `writer`
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        JsonLogLayer {
+            writer: Mutex::new(Box::new(writer)),
+        }
+    }
+
+    /// Formats an event and the spans it happened in
+    fn format_event<S>(event: &Event<'_>, ctx: &Context<'_, S>) -> Value
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let mut fields = Map::new();
+        event.record(&mut JsonVisitor(&mut fields));
+        let spans: Vec<Value> = ctx
+            .event_scope(event)
+            .map(|scope| {
+                scope
+                    .from_root()
+                    .map(|span| {
+                        let mut span_fields = span
+                            .extensions()
+                            .get::<SpanFields>()
+                            .map(|fields| fields.0.clone())
+                            .unwrap_or_default();
+                        span_fields.insert("name".into(), span.name().into());
+                        Value::Object(span_fields)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default();
+        json!({
+            "timestamp": timestamp,
+            "level": event.metadata().level().to_string(),
+            "target": event.metadata().target(),
+            "fields": fields,
+            "spans": spans,
+        })
+    }
+}
+
+impl<S> Layer<S> for JsonLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut fields = Map::new();
+            attrs.record(&mut JsonVisitor(&mut fields));
+            span.extensions_mut().insert(SpanFields(fields));
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(fields) = span.extensions_mut().get_mut::<SpanFields>() {
+                values.record(&mut JsonVisitor(&mut fields.0));
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let line = Self::format_event(event, &ctx);
+        if let Ok(mut writer) = self.writer.lock() {
+            // logging must not fail the node
+            let _ = writeln!(writer, "{}", line);
+        }
+    }
+}
+
+#[test]
+fn test_json_log_layer() {
+    use std::sync::Arc;
+    use tracing_subscriber::prelude::*;
+
+    /// Writer sharing its output with the test
+    #[derive(Clone, Default)]
+    struct SharedWriter(Arc<Mutex<Vec<u8>>>);
+    impl Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let output = SharedWriter::default();
+    let subscriber = tracing_subscriber::registry().with(JsonLogLayer::new(output.clone()));
+    tracing::subscriber::with_default(subscriber, || {
+        let _slot_span = tracing::info_span!("slot", period = 12u64, thread = 3u64).entered();
+        tracing::info!(block_id = "B1", "block acknowledged");
+    });
+
+    let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+    let line: Value = serde_json::from_str(output.trim()).unwrap();
+    assert_eq!(line["level"], "INFO");
+    assert_eq!(line["fields"]["message"], "block acknowledged");
+    assert_eq!(line["fields"]["block_id"], "B1");
+    assert_eq!(line["spans"][0]["name"], "slot");
+    assert_eq!(line["spans"][0]["period"], 12);
+    assert_eq!(line["spans"][0]["thread"], 3);
+}
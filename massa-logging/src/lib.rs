@@ -2,10 +2,19 @@
 //! Log utilities
 
 #![warn(missing_docs)]
+
+mod json_layer;
+mod log_filter;
+
+pub use json_layer::JsonLogLayer;
+pub use log_filter::{massa_trace_sampled, set_massa_trace_sampling, LogFilterHandle};
+
 #[macro_export]
-/// tracing with some context
+/// tracing with some context, sampled according to `set_massa_trace_sampling`
 macro_rules! massa_trace {
     ($evt:expr, $params:tt) => {
-        tracing::trace!("massa:{}:{}", $evt, serde_json::json!($params));
+        if $crate::massa_trace_sampled() {
+            tracing::trace!("massa:{}:{}", $evt, serde_json::json!($params));
+        }
     };
 }
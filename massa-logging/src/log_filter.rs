@@ -0,0 +1,61 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+//! Runtime control of the logs
+
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing_subscriber::{filter::Targets, reload, Registry};
+
+/// one `massa_trace!` event out of `TRACE_SAMPLING_RATE` is logged, none if 0
+static TRACE_SAMPLING_RATE: AtomicU64 = AtomicU64::new(1);
+/// number of `massa_trace!` events seen while sampling
+static TRACE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Logs one `massa_trace!` event out of `rate`, or disables them if `rate` is 0
+pub fn set_massa_trace_sampling(rate: u64) {
+    TRACE_SAMPLING_RATE.store(rate, Ordering::Relaxed);
+}
+
+/// Returns `true` if the current `massa_trace!` event should be logged
+pub fn massa_trace_sampled() -> bool {
+    match TRACE_SAMPLING_RATE.load(Ordering::Relaxed) {
+        0 => false,
+        1 => true,
+        rate => TRACE_COUNTER.fetch_add(1, Ordering::Relaxed) % rate == 0,
+    }
+}
+
+/// Handle changing the log filter of the node at runtime
+#[derive(Clone)]
+pub struct LogFilterHandle(reload::Handle<Targets, Registry>);
+
+impl LogFilterHandle {
+    /// Creates a new handle from the reload handle of the filter of the log layer
+    pub fn new(handle: reload::Handle<Targets, Registry>) -> Self {
+        LogFilterHandle(handle)
+    }
+
+    /// Replaces the log filter by comma-separated `target=level` directives.
+    /// A directive without target sets the default level.
+    ///
+    /// Example: `massa_bootstrap=debug,massa_protocol_worker=trace,info`
+    pub fn set_filter(&self, directives: &str) -> Result<(), String> {
+        let targets = Targets::from_str(directives)
+            .map_err(|err| format!("invalid log filter directives: {}", err))?;
+        self.0
+            .reload(targets)
+            .map_err(|err| format!("could not reload the log filter: {}", err))
+    }
+}
+
+#[test]
+fn test_massa_trace_sampling() {
+    set_massa_trace_sampling(0);
+    assert!(!massa_trace_sampled());
+
+    set_massa_trace_sampling(3);
+    let sampled = (0..9).filter(|_| massa_trace_sampled()).count();
+    assert_eq!(sampled, 3);
+
+    set_massa_trace_sampling(1);
+    assert!(massa_trace_sampled());
+}
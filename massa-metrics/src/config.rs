@@ -0,0 +1,13 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+/// Metrics configuration
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+pub struct MetricsConfig {
+    /// address on which the Prometheus endpoint listens
+    pub bind: SocketAddr,
+    /// size of the channel feeding the collector, records are dropped when it is full
+    pub channel_size: usize,
+}
@@ -0,0 +1,14 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use displaydoc::Display;
+use thiserror::Error;
+
+/// Metrics error
+#[non_exhaustive]
+#[derive(Display, Error, Debug)]
+pub enum MetricsError {
+    /// the metrics server was already started
+    AlreadyStarted,
+    /// metrics server error: {0}
+    ServerError(#[from] hyper::Error),
+}
@@ -0,0 +1,25 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+//! Prometheus metrics of the node
+//!
+//! Workers record counters, gauges and histograms through the free functions of this crate.
+//! Records are sent to a collector thread through a bounded channel and dropped if the channel is full,
+//! so that recording a metric never blocks a worker.
+//! Recording is a no-op until `start_metrics_server` is called.
+//!
+//! The collected metrics are served in the Prometheus text format on the `/metrics` path.
+
+#![warn(missing_docs)]
+#![warn(unused_crate_dependencies)]
+
+mod config;
+mod error;
+mod recorder;
+mod registry;
+mod server;
+
+pub use config::MetricsConfig;
+pub use error::MetricsError;
+pub use recorder::{
+    inc_counter, observe_histogram, set_gauge, MetricLabels, DURATION_BUCKETS, GAS_BUCKETS,
+};
+pub use server::{start_metrics_server, MetricsManager};
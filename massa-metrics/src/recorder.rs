@@ -0,0 +1,92 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+//! Non-blocking recording of metrics from the workers
+
+use crate::registry::{LabelSet, MetricEvent};
+use crossbeam_channel::Sender;
+use once_cell::sync::OnceCell;
+use std::fmt::Display;
+
+/// channel towards the collector, set once the metrics server is started
+static METRICS_SENDER: OnceCell<Sender<MetricEvent>> = OnceCell::new();
+
+/// Labels of a record, as `(label name, label value)` pairs.
+/// Values are only formatted if metrics are enabled.
+pub type MetricLabels<'a> = &'a [(&'static str, &'a dyn Display)];
+
+/// Histogram buckets for durations, in seconds
+pub const DURATION_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+];
+
+/// Histogram buckets for the gas used by a block
+pub const GAS_BUCKETS: &[f64] = &[1e6, 1e7, 5e7, 1e8, 2.5e8, 5e8, 1e9, 2e9, 3e9, 4e9, 4.3e9];
+
+/// Sets the channel towards the collector.
+/// Returns `false` if it was already set.
+pub(crate) fn install_sender(sender: Sender<MetricEvent>) -> bool {
+    METRICS_SENDER.set(sender).is_ok()
+}
+
+/// Sends a record to the collector if metrics are enabled, dropping it if the channel is full
+fn record(event: impl FnOnce() -> MetricEvent) {
+    if let Some(sender) = METRICS_SENDER.get() {
+        let _ = sender.try_send(event());
+    }
+}
+
+/// Formats the labels of a record
+fn label_set(labels: MetricLabels) -> LabelSet {
+    labels
+        .iter()
+        .map(|(name, value)| (*name, value.to_string()))
+        .collect()
+}
+
+/// Increments a counter
+///
+/// # Arguments
+/// * `name`: metric name, conventionally ending with `_total`
+/// * `labels`: labels of the counter
+/// * `value`: increment
+pub fn inc_counter(name: &'static str, labels: MetricLabels, value: u64) {
+    record(|| MetricEvent::Counter {
+        name,
+        labels: label_set(labels),
+        value,
+    })
+}
+
+/// Sets a gauge
+///
+/// # Arguments
+/// * `name`: metric name
+/// * `labels`: labels of the gauge
+/// * `value`: new value of the gauge
+pub fn set_gauge(name: &'static str, labels: MetricLabels, value: f64) {
+    record(|| MetricEvent::Gauge {
+        name,
+        labels: label_set(labels),
+        value,
+    })
+}
+
+/// Adds an observation to a histogram
+///
+/// # Arguments
+/// * `name`: metric name
+/// * `labels`: labels of the histogram
+/// * `buckets`: sorted upper bounds of the buckets, must be the same for every observation of the histogram
+/// * `value`: observed value
+pub fn observe_histogram(
+    name: &'static str,
+    labels: MetricLabels,
+    buckets: &'static [f64],
+    value: f64,
+) {
+    record(|| MetricEvent::Histogram {
+        name,
+        labels: label_set(labels),
+        buckets,
+        value,
+    })
+}
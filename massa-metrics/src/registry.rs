@@ -0,0 +1,225 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+//! Aggregation of the records and rendering in the Prometheus text format
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Labels of a metric, as `(label name, label value)` pairs
+pub(crate) type LabelSet = Vec<(&'static str, String)>;
+
+/// Record sent by a worker to the collector
+pub(crate) enum MetricEvent {
+    /// counter increment
+    Counter {
+        name: &'static str,
+        labels: LabelSet,
+        value: u64,
+    },
+    /// gauge update
+    Gauge {
+        name: &'static str,
+        labels: LabelSet,
+        value: f64,
+    },
+    /// histogram observation
+    Histogram {
+        name: &'static str,
+        labels: LabelSet,
+        buckets: &'static [f64],
+        value: f64,
+    },
+}
+
+/// Histogram state
+struct Histogram {
+    /// upper bounds of the buckets
+    buckets: &'static [f64],
+    /// cumulative number of observations of each bucket
+    bucket_counts: Vec<u64>,
+    /// sum of the observations
+    sum: f64,
+    /// number of observations
+    count: u64,
+}
+
+/// Current value of every metric, sorted by name and labels
+#[derive(Default)]
+pub(crate) struct Registry {
+    counters: BTreeMap<&'static str, BTreeMap<LabelSet, u64>>,
+    gauges: BTreeMap<&'static str, BTreeMap<LabelSet, f64>>,
+    histograms: BTreeMap<&'static str, BTreeMap<LabelSet, Histogram>>,
+}
+
+/// Formats labels as `{name="value",...}`, escaping the values
+fn format_labels(labels: &LabelSet, extra: Option<(&str, &str)>) -> String {
+    let mut pairs = labels
+        .iter()
+        .map(|(name, value)| (*name, value.as_str()))
+        .chain(extra)
+        .peekable();
+    if pairs.peek().is_none() {
+        return String::new();
+    }
+    let pairs: Vec<String> = pairs
+        .map(|(name, value)| {
+            format!(
+                "{}=\"{}\"",
+                name,
+                value
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n")
+            )
+        })
+        .collect();
+    format!("{{{}}}", pairs.join(","))
+}
+
+impl Registry {
+    /// Applies a record
+    pub fn apply(&mut self, event: MetricEvent) {
+        match event {
+            MetricEvent::Counter {
+                name,
+                labels,
+                value,
+            } => {
+                let counter = self
+                    .counters
+                    .entry(name)
+                    .or_default()
+                    .entry(labels)
+                    .or_default();
+                *counter = counter.saturating_add(value);
+            }
+            MetricEvent::Gauge {
+                name,
+                labels,
+                value,
+            } => {
+                self.gauges.entry(name).or_default().insert(labels, value);
+            }
+            MetricEvent::Histogram {
+                name,
+                labels,
+                buckets,
+                value,
+            } => {
+                let histogram = self
+                    .histograms
+                    .entry(name)
+                    .or_default()
+                    .entry(labels)
+                    .or_insert_with(|| Histogram {
+                        buckets,
+                        bucket_counts: vec![0; buckets.len()],
+                        sum: 0.0,
+                        count: 0,
+                    });
+                for (bound, count) in histogram
+                    .buckets
+                    .iter()
+                    .zip(histogram.bucket_counts.iter_mut())
+                {
+                    if value <= *bound {
+                        *count += 1;
+                    }
+                }
+                histogram.sum += value;
+                histogram.count += 1;
+            }
+        }
+    }
+
+    /// Renders every metric in the Prometheus text format
+    pub fn render(&self) -> String {
+        // writing to a String cannot fail
+        let mut res = String::new();
+        for (name, values) in self.counters.iter() {
+            let _ = writeln!(res, "# TYPE {} counter", name);
+            for (labels, value) in values.iter() {
+                let _ = writeln!(res, "{}{} {}", name, format_labels(labels, None), value);
+            }
+        }
+        for (name, values) in self.gauges.iter() {
+            let _ = writeln!(res, "# TYPE {} gauge", name);
+            for (labels, value) in values.iter() {
+                let _ = writeln!(res, "{}{} {}", name, format_labels(labels, None), value);
+            }
+        }
+        for (name, values) in self.histograms.iter() {
+            let _ = writeln!(res, "# TYPE {} histogram", name);
+            for (labels, histogram) in values.iter() {
+                for (bound, count) in histogram.buckets.iter().zip(histogram.bucket_counts.iter()) {
+                    let _ = writeln!(
+                        res,
+                        "{}_bucket{} {}",
+                        name,
+                        format_labels(labels, Some(("le", &bound.to_string()))),
+                        count
+                    );
+                }
+                let _ = writeln!(
+                    res,
+                    "{}_bucket{} {}",
+                    name,
+                    format_labels(labels, Some(("le", "+Inf"))),
+                    histogram.count
+                );
+                let _ = writeln!(
+                    res,
+                    "{}_sum{} {}",
+                    name,
+                    format_labels(labels, None),
+                    histogram.sum
+                );
+                let _ = writeln!(
+                    res,
+                    "{}_count{} {}",
+                    name,
+                    format_labels(labels, None),
+                    histogram.count
+                );
+            }
+        }
+        res
+    }
+}
+
+#[test]
+fn test_render_metrics() {
+    let mut registry = Registry::default();
+    let labels = vec![("reason", "stale".to_string())];
+    registry.apply(MetricEvent::Counter {
+        name: "massa_blocks_discarded_total",
+        labels: labels.clone(),
+        value: 2,
+    });
+    registry.apply(MetricEvent::Counter {
+        name: "massa_blocks_discarded_total",
+        labels,
+        value: 1,
+    });
+    registry.apply(MetricEvent::Gauge {
+        name: "massa_cliques",
+        labels: Vec::new(),
+        value: 1.0,
+    });
+    for value in [0.5, 2.0] {
+        registry.apply(MetricEvent::Histogram {
+            name: "massa_slot_execution_seconds",
+            labels: Vec::new(),
+            buckets: &[1.0],
+            value,
+        });
+    }
+
+    let rendered = registry.render();
+    assert!(rendered.contains("# TYPE massa_blocks_discarded_total counter\n"));
+    assert!(rendered.contains("massa_blocks_discarded_total{reason=\"stale\"} 3\n"));
+    assert!(rendered.contains("massa_cliques 1\n"));
+    assert!(rendered.contains("massa_slot_execution_seconds_bucket{le=\"1\"} 1\n"));
+    assert!(rendered.contains("massa_slot_execution_seconds_bucket{le=\"+Inf\"} 2\n"));
+    assert!(rendered.contains("massa_slot_execution_seconds_sum 2.5\n"));
+    assert!(rendered.contains("massa_slot_execution_seconds_count 2\n"));
+}
@@ -0,0 +1,89 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+//! Collector thread and Prometheus HTTP endpoint
+
+use crate::{
+    recorder::install_sender,
+    registry::{MetricEvent, Registry},
+    MetricsConfig, MetricsError,
+};
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use parking_lot::RwLock;
+use std::{convert::Infallible, sync::Arc, thread};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Manager of the metrics server
+pub struct MetricsManager {
+    /// handle of the HTTP server task
+    server_handle: JoinHandle<()>,
+}
+
+impl MetricsManager {
+    /// Stops the HTTP server.
+    /// The collector keeps running so that the metrics recorded afterwards are not lost.
+    pub fn stop(self) {
+        self.server_handle.abort();
+    }
+}
+
+/// Answers a Prometheus scrape
+fn serve_metrics(req: Request<Body>, registry: &RwLock<Registry>) -> Response<Body> {
+    let mut response = Response::default();
+    if req.method() != Method::GET || req.uri().path() != "/metrics" {
+        *response.status_mut() = StatusCode::NOT_FOUND;
+        return response;
+    }
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("text/plain; version=0.0.4"),
+    );
+    *response.body_mut() = Body::from(registry.read().render());
+    response
+}
+
+/// Starts the collector thread and the Prometheus endpoint, and enables metrics recording.
+/// Must be called from a tokio runtime, at most once per process.
+pub fn start_metrics_server(config: MetricsConfig) -> Result<MetricsManager, MetricsError> {
+    let server_builder = Server::try_bind(&config.bind)?;
+
+    let (sender, receiver) = crossbeam_channel::bounded::<MetricEvent>(config.channel_size);
+    if !install_sender(sender) {
+        return Err(MetricsError::AlreadyStarted);
+    }
+
+    // collect the records
+    let registry = Arc::new(RwLock::new(Registry::default()));
+    let collector_registry = registry.clone();
+    thread::Builder::new()
+        .name("metrics-collector".into())
+        .spawn(move || {
+            for event in receiver.iter() {
+                collector_registry.write().apply(event);
+            }
+        })
+        .expect("failed to spawn thread : metrics-collector");
+
+    // serve them
+    let make_service = make_service_fn(move |_conn| {
+        let registry = registry.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let response = serve_metrics(req, &registry);
+                async move { Ok::<_, Infallible>(response) }
+            }))
+        }
+    });
+    let server = server_builder.serve(make_service);
+    info!("metrics server listening on {}", config.bind);
+    let server_handle = tokio::spawn(async move {
+        if let Err(err) = server.await {
+            warn!("metrics server error: {}", err);
+        }
+    });
+
+    Ok(MetricsManager { server_handle })
+}
@@ -1,28 +1,83 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::amount::Amount;
 use crate::error::ModelsError;
+use crate::operation::OperationId;
 use crate::prehash::PreHashed;
+use crate::slot::Slot;
 use massa_hash::{Hash, HashDeserializer};
 use massa_serialization::{
     DeserializeError, Deserializer, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
 };
 use massa_signature::PublicKey;
+use nom::bytes::complete::take;
 use nom::error::{context, ContextError, ParseError};
+use nom::sequence::tuple;
 use nom::{IResult, Parser};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
 use serde::{Deserialize, Serialize};
 use std::ops::Bound::Included;
 use std::str::FromStr;
 
-/// Size of a serialized address, in bytes
-pub const ADDRESS_SIZE_BYTES: usize = massa_hash::HASH_SIZE_BYTES;
+/// Size of a serialized address, in bytes: category byte followed by the hash
+pub const ADDRESS_SIZE_BYTES: usize = massa_hash::HASH_SIZE_BYTES + 1;
+
+/// Category of an address
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+    IntoPrimitive,
+    TryFromPrimitive,
+)]
+#[repr(u8)]
+pub enum AddressCategory {
+    /// address derived from a public key
+    UserAddress = 0u8,
+    /// address of a smart contract
+    SCAddress = 1u8,
+}
+
+impl AddressCategory {
+    /// Prefix following `ADDRESS_PREFIX` in the string representation of an address of this category
+    fn prefix(&self) -> char {
+        match self {
+            AddressCategory::UserAddress => ADDRESS_USER_PREFIX,
+            AddressCategory::SCAddress => ADDRESS_SC_PREFIX,
+        }
+    }
+}
 
-/// Derived from a public key
-#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct Address(pub Hash);
+/// Address of a user or a smart contract.
+///
+/// Addresses are ordered by category first, consistently with their binary representation.
+#[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Address {
+    /// category of the address
+    pub category: AddressCategory,
+    /// hash identifying the address within its category
+    pub hash: Hash,
+}
 
 const ADDRESS_PREFIX: char = 'A';
+const ADDRESS_USER_PREFIX: char = 'U';
+const ADDRESS_SC_PREFIX: char = 'S';
 const ADDRESS_VERSION: u64 = 0;
 
+/// Only hash the address hash, as expected by `PreHashed`
+impl std::hash::Hash for Address {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
+    }
+}
+
 impl std::fmt::Display for Address {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let u64_serializer = U64VarIntSerializer::new();
@@ -31,11 +86,12 @@ impl std::fmt::Display for Address {
         u64_serializer
             .serialize(&ADDRESS_VERSION, &mut bytes)
             .map_err(|_| std::fmt::Error)?;
-        bytes.extend(self.0.to_bytes());
+        bytes.extend(self.hash.to_bytes());
         write!(
             f,
-            "{}{}",
+            "{}{}{}",
             ADDRESS_PREFIX,
+            self.category.prefix(),
             bs58::encode(bytes).with_check().into_string()
         )
     }
@@ -52,7 +108,7 @@ impl ::serde::Serialize for Address {
         if s.is_human_readable() {
             s.collect_str(&self.to_string())
         } else {
-            s.serialize_bytes(self.to_bytes())
+            s.serialize_bytes(&self.to_bytes())
         }
     }
 }
@@ -66,7 +122,7 @@ impl<'de> ::serde::Deserialize<'de> for Address {
                 type Value = Address;
 
                 fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                    formatter.write_str("A + base58::encode(version + hash)")
+                    formatter.write_str("A + U or S + base58::encode(version + hash)")
                 }
 
                 fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
@@ -102,7 +158,7 @@ impl<'de> ::serde::Deserialize<'de> for Address {
                 where
                     E: ::serde::de::Error,
                 {
-                    Ok(Address::from_bytes(v.try_into().map_err(E::custom)?))
+                    Address::from_bytes(v.try_into().map_err(E::custom)?).map_err(E::custom)
                 }
             }
 
@@ -113,6 +169,9 @@ impl<'de> ::serde::Deserialize<'de> for Address {
 
 impl FromStr for Address {
     type Err = ModelsError;
+    /// Parses `A` + `U` (user) or `S` (smart contract) + `base58::encode(version + hash)`.
+    /// Addresses without category prefix, formatted before categories existed, are user addresses.
+    ///
     /// ## Example
     /// ```rust
     /// # use massa_signature::{PublicKey, KeyPair, Signature};
@@ -127,25 +186,32 @@ impl FromStr for Address {
     /// assert_eq!(address, res_addr);
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut chars = s.chars();
-        match chars.next() {
-            Some(prefix) if prefix == ADDRESS_PREFIX => {
-                let data = chars.collect::<String>();
-                let decoded_bs58_check = bs58::decode(data)
-                    .with_check(None)
-                    .into_vec()
-                    .map_err(|_| ModelsError::AddressParseError)?;
-                let u64_deserializer = U64VarIntDeserializer::new(Included(0), Included(u64::MAX));
-                let (rest, _version) = u64_deserializer
-                    .deserialize::<DeserializeError>(&decoded_bs58_check[..])
-                    .map_err(|_| ModelsError::AddressParseError)?;
-                Ok(Address(Hash::from_bytes(
-                    rest.try_into()
-                        .map_err(|_| ModelsError::AddressParseError)?,
-                )))
-            }
-            _ => Err(ModelsError::AddressParseError),
-        }
+        let Some(data) = s.strip_prefix(ADDRESS_PREFIX) else {
+            return Err(ModelsError::AddressParseError);
+        };
+        // the base58 encoding of the version always starts with '1', so legacy addresses are not ambiguous
+        let (category, data) = if let Some(data) = data.strip_prefix(ADDRESS_USER_PREFIX) {
+            (AddressCategory::UserAddress, data)
+        } else if let Some(data) = data.strip_prefix(ADDRESS_SC_PREFIX) {
+            (AddressCategory::SCAddress, data)
+        } else {
+            (AddressCategory::UserAddress, data)
+        };
+        let decoded_bs58_check = bs58::decode(data)
+            .with_check(None)
+            .into_vec()
+            .map_err(|_| ModelsError::AddressParseError)?;
+        let u64_deserializer = U64VarIntDeserializer::new(Included(0), Included(u64::MAX));
+        let (rest, _version) = u64_deserializer
+            .deserialize::<DeserializeError>(&decoded_bs58_check[..])
+            .map_err(|_| ModelsError::AddressParseError)?;
+        Ok(Address::new(
+            category,
+            Hash::from_bytes(
+                rest.try_into()
+                    .map_err(|_| ModelsError::AddressParseError)?,
+            ),
+        ))
     }
 }
 
@@ -156,23 +222,53 @@ fn test_address_str_format() {
     let keypair = KeyPair::generate();
     let address = Address::from_public_key(&keypair.get_public_key());
     let a = address.to_string();
+    assert!(a.starts_with("AU"));
     let b = Address::from_str(&a).unwrap();
     assert!(address == b);
+
+    let sc_address = Address::new(AddressCategory::SCAddress, address.hash);
+    let a = sc_address.to_string();
+    assert!(a.starts_with("AS"));
+    let b = Address::from_str(&a).unwrap();
+    assert!(sc_address == b);
+    assert!(sc_address != address);
+
+    // addresses formatted without category are user addresses
+    let legacy = format!("A{}", &address.to_string()[2..]);
+    assert_eq!(Address::from_str(&legacy).unwrap(), address);
 }
 
 impl PreHashed for Address {}
 
 impl Address {
+    /// Creates an address of the given category
+    pub const fn new(category: AddressCategory, hash: Hash) -> Self {
+        Address { category, hash }
+    }
+
     /// Gets the associated thread. Depends on the `thread_count`
     pub fn get_thread(&self, thread_count: u8) -> u8 {
-        (self.to_bytes()[0])
+        (self.hash.to_bytes()[0])
             .checked_shr(8 - thread_count.trailing_zeros())
             .unwrap_or(0)
     }
 
-    /// Computes address associated with given public key
+    /// Computes the user address associated with given public key
     pub fn from_public_key(public_key: &PublicKey) -> Self {
-        Address(Hash::compute_from(public_key.to_bytes()))
+        Address::new(
+            AddressCategory::UserAddress,
+            Hash::compute_from(public_key.to_bytes()),
+        )
+    }
+
+    /// Returns true if the address is derived from a public key
+    pub fn is_user(&self) -> bool {
+        self.category == AddressCategory::UserAddress
+    }
+
+    /// Returns true if the address is the address of a smart contract
+    pub fn is_sc(&self) -> bool {
+        self.category == AddressCategory::SCAddress
     }
 
     /// ## Example
@@ -184,11 +280,14 @@ impl Address {
     /// # let keypair = KeyPair::generate();
     /// # let address = Address::from_public_key(&keypair.get_public_key());
     /// let bytes = address.into_bytes();
-    /// let res_addr = Address::from_bytes(&bytes);
+    /// let res_addr = Address::from_bytes(&bytes).unwrap();
     /// assert_eq!(address, res_addr);
     /// ```
-    pub fn to_bytes(&self) -> &[u8; ADDRESS_SIZE_BYTES] {
-        self.0.to_bytes()
+    pub fn to_bytes(&self) -> [u8; ADDRESS_SIZE_BYTES] {
+        let mut bytes = [0u8; ADDRESS_SIZE_BYTES];
+        bytes[0] = self.category.into();
+        bytes[1..].copy_from_slice(self.hash.to_bytes());
+        bytes
     }
 
     /// ## Example
@@ -200,13 +299,14 @@ impl Address {
     /// # let keypair = KeyPair::generate();
     /// # let address = Address::from_public_key(&keypair.get_public_key());
     /// let bytes = address.into_bytes();
-    /// let res_addr = Address::from_bytes(&bytes);
+    /// let res_addr = Address::from_bytes(&bytes).unwrap();
     /// assert_eq!(address, res_addr);
     /// ```
     pub fn into_bytes(self) -> [u8; ADDRESS_SIZE_BYTES] {
-        self.0.into_bytes()
+        self.to_bytes()
     }
 
+    /// Builds an address from its binary representation, failing on an unknown category
     /// ## Example
     /// ```rust
     /// # use massa_signature::{PublicKey, KeyPair, Signature};
@@ -216,11 +316,16 @@ impl Address {
     /// # let keypair = KeyPair::generate();
     /// # let address = Address::from_public_key(&keypair.get_public_key());
     /// let bytes = address.to_bytes();
-    /// let res_addr = Address::from_bytes(&bytes);
+    /// let res_addr = Address::from_bytes(&bytes).unwrap();
     /// assert_eq!(address, res_addr);
     /// ```
-    pub fn from_bytes(data: &[u8; ADDRESS_SIZE_BYTES]) -> Address {
-        Address(Hash::from_bytes(data))
+    pub fn from_bytes(data: &[u8; ADDRESS_SIZE_BYTES]) -> Result<Address, ModelsError> {
+        let category = AddressCategory::try_from(data[0])
+            .map_err(|_| ModelsError::AddressCategoryError(data[0]))?;
+        Ok(Address::new(
+            category,
+            Hash::from_bytes(data[1..].try_into().expect("address hash size mismatch")),
+        ))
     }
 }
 
@@ -241,7 +346,7 @@ impl Serializer<Address> for AddressSerializer {
         value: &Address,
         buffer: &mut Vec<u8>,
     ) -> Result<(), massa_serialization::SerializeError> {
-        buffer.extend_from_slice(value.to_bytes());
+        buffer.extend_from_slice(&value.to_bytes());
         Ok(())
     }
 }
@@ -268,7 +373,7 @@ impl Deserializer<Address> for AddressDeserializer {
     /// use massa_serialization::{Deserializer, DeserializeError};
     /// use std::str::FromStr;
     ///
-    /// let address = Address::from_str("A12hgh5ULW9o8fJE9muLNXhQENaUUswQbxPyDSq8ridnDGu5gRiJ").unwrap();
+    /// let address = Address::from_str("AU12hgh5ULW9o8fJE9muLNXhQENaUUswQbxPyDSq8ridnDGu5gRiJ").unwrap();
     /// let bytes = address.into_bytes();
     /// let (rest, res_addr) = AddressDeserializer::new().deserialize::<DeserializeError>(&bytes).unwrap();
     /// assert_eq!(address, res_addr);
@@ -278,10 +383,23 @@ impl Deserializer<Address> for AddressDeserializer {
         &self,
         buffer: &'a [u8],
     ) -> IResult<&'a [u8], Address, E> {
-        context("Failed Address deserialization", |input| {
-            self.hash_deserializer.deserialize(input)
-        })
-        .map(Address)
+        context(
+            "Failed Address deserialization",
+            tuple((
+                context("Failed address category deserialization", |input| {
+                    let (rest, category) = take(1usize)(input)?;
+                    let category = AddressCategory::try_from(category[0]).map_err(|_| {
+                        nom::Err::Error(ParseError::from_error_kind(
+                            input,
+                            nom::error::ErrorKind::Verify,
+                        ))
+                    })?;
+                    Ok((rest, category))
+                }),
+                |input| self.hash_deserializer.deserialize(input),
+            )),
+        )
+        .map(|(category, hash)| Address::new(category, hash))
         .parse(buffer)
     }
 }
@@ -300,3 +418,74 @@ pub struct ExecutionAddressCycleInfo {
     /// number of active rolls the address had at that cycle (if still available)
     pub active_rolls: Option<u64>,
 }
+
+/// Balance and roll changes of an address at a given final slot
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressHistoryEntry {
+    /// slot at which the changes happened
+    pub slot: Slot,
+    /// new balance of the address, if it changed during that slot
+    pub balance: Option<Amount>,
+    /// new roll count of the address, if it changed during that slot
+    pub roll_count: Option<u64>,
+}
+
+impl AddressHistoryEntry {
+    /// Creates an entry without changes at the given slot
+    pub fn new(slot: Slot) -> Self {
+        AddressHistoryEntry {
+            slot,
+            balance: None,
+            roll_count: None,
+        }
+    }
+}
+
+impl std::fmt::Display for AddressHistoryEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Slot {}:", self.slot)?;
+        if let Some(balance) = self.balance {
+            write!(f, " balance={}", balance)?;
+        }
+        if let Some(roll_count) = self.roll_count {
+            write!(f, " rolls={}", roll_count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Coins sent or received by an address through an operation executed in a final slot
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressTransfer {
+    /// slot at which the operation was executed
+    pub slot: Slot,
+    /// id of the operation
+    pub operation_id: OperationId,
+    /// address the coins were sent to, or received from
+    pub counterpart: Address,
+    /// transferred amount
+    pub amount: Amount,
+    /// true if the address received the coins, false if it sent them
+    pub received: bool,
+    /// false if the execution of the operation failed and the coins were not transferred
+    pub success: bool,
+}
+
+impl std::fmt::Display for AddressTransfer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (direction, preposition) = if self.received {
+            ("received", "from")
+        } else {
+            ("sent", "to")
+        };
+        write!(
+            f,
+            "Slot {}: {} {} {} {} (operation {})",
+            self.slot, direction, self.amount, preposition, self.counterpart, self.operation_id
+        )?;
+        if !self.success {
+            write!(f, " [failed]")?;
+        }
+        Ok(())
+    }
+}
@@ -153,6 +153,50 @@ impl Amount {
     pub fn checked_div_u64(self, factor: u64) -> Option<Self> {
         self.0.checked_div(factor).map(Amount)
     }
+
+    /// safely divide self by another amount, returning the integer quotient
+    /// (rounded down) or None if the divisor is zero
+    /// ```
+    /// # use massa_models::amount::Amount;
+    /// # use std::str::FromStr;
+    /// let amount_1 : Amount = Amount::from_str("42").unwrap();
+    /// let amount_2 : Amount = Amount::from_str("10").unwrap();
+    /// assert_eq!(amount_1.checked_div(amount_2), Some(4));
+    /// assert_eq!(amount_1.checked_div(Amount::zero()), None);
+    /// ```
+    pub fn checked_div(self, divisor: Amount) -> Option<u64> {
+        self.0.checked_div(divisor.0)
+    }
+
+    /// safely compute the remainder of the division of self by another amount,
+    /// returning None if the divisor is zero
+    /// ```
+    /// # use massa_models::amount::Amount;
+    /// # use std::str::FromStr;
+    /// let amount_1 : Amount = Amount::from_str("42.5").unwrap();
+    /// let amount_2 : Amount = Amount::from_str("10").unwrap();
+    /// let res : Amount = amount_1.checked_rem(amount_2).unwrap();
+    /// assert_eq!(res, Amount::from_str("2.5").unwrap());
+    /// ```
+    pub fn checked_rem(self, divisor: Amount) -> Option<Self> {
+        self.0.checked_rem(divisor.0).map(Amount)
+    }
+
+    /// safely divide self by a `u64`, returning zero if the factor is zero
+    /// ```
+    /// # use massa_models::amount::Amount;
+    /// # use std::str::FromStr;
+    /// let amount_1 : Amount = Amount::from_str("42").unwrap();
+    /// assert_eq!(amount_1.saturating_div_u64(7), Amount::from_str("6").unwrap());
+    /// assert_eq!(amount_1.saturating_div_u64(0), Amount::zero());
+    /// ```
+    #[must_use]
+    pub const fn saturating_div_u64(self, factor: u64) -> Self {
+        match self.0.checked_div(factor) {
+            Some(res) => Amount(res),
+            None => Amount(0),
+        }
+    }
 }
 
 /// display an Amount in decimal string form (like "10.33")
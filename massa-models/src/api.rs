@@ -1,21 +1,22 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::address::ExecutionAddressCycleInfo;
+use crate::address::{AddressTransfer, ExecutionAddressCycleInfo};
 use crate::endorsement::{EndorsementId, WrappedEndorsement};
 use crate::ledger_models::LedgerData;
 use crate::node::NodeId;
 use crate::operation::{OperationId, WrappedOperation};
-use crate::stats::{ConsensusStats, ExecutionStats, NetworkStats};
+use crate::stats::{ConsensusStats, ExecutionStats, NetworkStats, OperationLatencyStats};
 use crate::{
     address::Address, amount::Amount, block::Block, block::BlockId, config::CompactConfig,
     slot::Slot, version::Version,
 };
+use massa_hash::Hash;
 use massa_signature::{PublicKey, Signature};
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::net::IpAddr;
-use strum::Display;
+use strum::{Display, EnumString};
 
 /// operation input
 #[derive(Serialize, Deserialize, Debug)]
@@ -37,8 +38,12 @@ pub struct NodeStatus {
     pub node_ip: Option<IpAddr>,
     /// node version
     pub version: Version,
+    /// chain id
+    pub chain_id: u64,
     /// now
     pub current_time: MassaTime,
+    /// time elapsed since the node started
+    pub uptime: MassaTime,
     /// current cycle
     pub current_cycle: u64,
     /// connected nodes (node id, ip address, true if the connection is outgoing, false if incoming)
@@ -55,6 +60,8 @@ pub struct NodeStatus {
     pub network_stats: NetworkStats,
     /// execution stats
     pub execution_stats: ExecutionStats,
+    /// latencies from operation reception to pool admission, block inclusion and final execution
+    pub operation_latency_stats: OperationLatencyStats,
     /// compact configuration
     pub config: CompactConfig,
 }
@@ -70,10 +77,18 @@ impl std::fmt::Display for NodeStatus {
         writeln!(f)?;
 
         writeln!(f, "Version: {}", self.version)?;
+        writeln!(f, "Chain id: {}", self.chain_id)?;
         writeln!(f, "Config:\n{}", self.config)?;
         writeln!(f)?;
 
         writeln!(f, "Current time: {}", self.current_time.to_utc_string())?;
+        if let Ok((days, hours, mins, secs)) = self.uptime.days_hours_mins_secs() {
+            writeln!(
+                f,
+                "Uptime: {} days, {} hours, {} minutes, {} seconds",
+                days, hours, mins, secs
+            )?;
+        }
         writeln!(f, "Current cycle: {}", self.current_cycle)?;
         if self.last_slot.is_some() {
             writeln!(f, "Last slot: {}", self.last_slot.unwrap())?;
@@ -92,6 +107,8 @@ impl std::fmt::Display for NodeStatus {
 
         writeln!(f, "{}", self.execution_stats)?;
 
+        writeln!(f, "{}", self.operation_latency_stats)?;
+
         writeln!(f, "Connected nodes:")?;
         for (node_id, (ip_addr, is_outgoing)) in &self.connected_nodes {
             writeln!(
@@ -140,6 +157,46 @@ impl std::fmt::Display for OperationInfo {
     }
 }
 
+/// Status of an operation submitted through `send_operations_and_wait`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationStatus {
+    /// accepted in the pool and propagated to the network
+    Pending,
+    /// included in a block
+    InBlock {
+        /// id of the block
+        block_id: BlockId,
+        /// slot of the block
+        slot: Slot,
+    },
+    /// executed speculatively in a block that is not final yet
+    CandidateExecuted {
+        /// id of the block
+        block_id: BlockId,
+        /// slot of the block
+        slot: Slot,
+    },
+    /// executed in a final block
+    Final {
+        /// id of the block
+        block_id: BlockId,
+        /// slot of the block
+        slot: Slot,
+    },
+    /// its validity period ended before it could be executed in a final block
+    Expired,
+}
+
+/// Status change of an operation submitted through `send_operations_and_wait`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OperationStatusUpdate {
+    /// id of the operation
+    pub operation_id: OperationId,
+    /// new status of the operation
+    pub status: OperationStatus,
+}
+
 /// Block status within the graph
 #[derive(Eq, PartialEq, Debug, Deserialize, Serialize)]
 pub enum BlockGraphStatus {
@@ -520,6 +577,15 @@ pub struct DatastoreEntryInput {
     pub key: Vec<u8>,
 }
 
+/// Ledger entry proof query input structure
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct LedgerEntryProofInput {
+    /// associated address of the entry
+    pub address: Address,
+    /// datastore key, `None` to prove the balance of the address
+    pub key: Option<Vec<u8>>,
+}
+
 /// Datastore entry query output structure
 #[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct DatastoreEntryOutput {
@@ -592,6 +658,176 @@ pub struct ReadOnlyCall {
     pub caller_address: Option<Address>,
 }
 
+/// selection simulation request: draws over a hypothetical roll distribution
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct SelectionSimulation {
+    /// roll counts of the simulated stakers
+    pub rolls: Vec<(Address, u64)>,
+    /// seed of the simulation, each simulated cycle uses a seed derived from it
+    pub seed: Hash,
+    /// number of simulated cycles
+    pub cycle_count: u64,
+}
+
+/// draws of an address in a selection simulation
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct SimulatedDraws {
+    /// address
+    pub address: Address,
+    /// simulated roll count
+    pub roll_count: u64,
+    /// number of slots for which the address was drawn to produce the block
+    pub block_draws: u64,
+    /// share of the simulated slots for which the address was drawn to produce the block
+    pub block_draw_frequency: f64,
+    /// number of endorsements the address was drawn to produce
+    pub endorsement_draws: u64,
+    /// share of the simulated endorsements the address was drawn to produce
+    pub endorsement_draw_frequency: f64,
+}
+
+impl std::fmt::Display for SimulatedDraws {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Address: {}", self.address)?;
+        writeln!(f, "\tRoll count: {}", self.roll_count)?;
+        writeln!(
+            f,
+            "\tBlock draws: {} ({:.4}%)",
+            self.block_draws,
+            self.block_draw_frequency * 100.0
+        )?;
+        writeln!(
+            f,
+            "\tEndorsement draws: {} ({:.4}%)",
+            self.endorsement_draws,
+            self.endorsement_draw_frequency * 100.0
+        )?;
+        Ok(())
+    }
+}
+
+/// staker set request: the stakers of a cycle, sorted by decreasing active roll count
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct StakersRequest {
+    /// cycle of the staker set, the current cycle if `None`
+    pub cycle: Option<u64>,
+    /// number of stakers to skip
+    pub offset: u64,
+    /// maximum number of returned stakers
+    pub limit: u64,
+}
+
+/// staker of a cycle
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct StakerInfo {
+    /// address
+    pub address: Address,
+    /// number of rolls taken into account by the selector for the cycle
+    pub active_rolls: u64,
+    /// current candidate number of rolls of the address
+    pub candidate_rolls: u64,
+    /// number of blocks created by the address during the cycle
+    pub ok_count: u64,
+    /// number of blocks missed by the address during the cycle
+    pub nok_count: u64,
+}
+
+impl std::fmt::Display for StakerInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Address: {}", self.address)?;
+        writeln!(f, "\tActive rolls: {}", self.active_rolls)?;
+        writeln!(f, "\tCandidate rolls: {}", self.candidate_rolls)?;
+        writeln!(
+            f,
+            "\tProduced blocks: {}, missed blocks: {}",
+            self.ok_count, self.nok_count
+        )?;
+        Ok(())
+    }
+}
+
+/// page of the staker set of a cycle
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct StakersPage {
+    /// cycle of the staker set
+    pub cycle: u64,
+    /// total number of stakers in the cycle
+    pub total_count: u64,
+    /// stakers of the requested page
+    pub stakers: Vec<StakerInfo>,
+}
+
+impl std::fmt::Display for StakersPage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Cycle {}: {} staker(s) out of {}",
+            self.cycle,
+            self.stakers.len(),
+            self.total_count
+        )?;
+        for staker in &self.stakers {
+            write!(f, "{}", staker)?;
+        }
+        Ok(())
+    }
+}
+
+/// address history request: the balance and roll changes of an address in the recent final slots
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct AddressHistoryRequest {
+    /// address
+    pub address: Address,
+    /// optional first slot of the history (included)
+    pub from_slot: Option<Slot>,
+    /// optional last slot of the history (included)
+    pub to_slot: Option<Slot>,
+}
+
+/// final state snapshot request
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct SnapshotRequest {
+    /// final slot at which the snapshot is taken, the current final slot or a later one
+    pub slot: Slot,
+    /// path of the snapshot file to write on the node machine
+    pub path: String,
+}
+
+/// transfer history request: a page of the coins sent and received by an address in the recent final slots
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct TransfersRequest {
+    /// address
+    pub address: Address,
+    /// number of transfers to skip, newest first
+    pub offset: u64,
+    /// maximum number of returned transfers
+    pub limit: u64,
+}
+
+/// page of the transfers of an address
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct TransfersPage {
+    /// total number of transfers of the address kept by the node
+    pub total_count: u64,
+    /// transfers of the requested page, newest first
+    pub transfers: Vec<AddressTransfer>,
+}
+
+impl std::fmt::Display for TransfersPage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} transfer(s) out of {}",
+            self.transfers.len(),
+            self.total_count
+        )?;
+        for transfer in &self.transfers {
+            writeln!(f, "{}", transfer)?;
+        }
+        Ok(())
+    }
+}
+
 /// SCRUD operations
 #[derive(Display)]
 #[strum(serialize_all = "snake_case")]
@@ -617,3 +853,16 @@ pub enum ListType {
     /// contains allowed entry
     Whitelist,
 }
+
+/// Automatic conversion of the deferred credits of the staking addresses into rolls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RollCompoundingMode {
+    /// deferred credits are left as is
+    Disabled,
+    /// the roll purchases are only logged
+    DryRun,
+    /// the roll purchases are sent
+    Enabled,
+}
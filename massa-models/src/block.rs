@@ -6,6 +6,7 @@ use crate::wrapped::{Id, Wrapped, WrappedContent, WrappedDeserializer, WrappedSe
 use crate::{
     endorsement::{Endorsement, EndorsementDeserializerLW, WrappedEndorsement},
     error::ModelsError,
+    id_hash::BLOCK_ID_HASH_DOMAIN,
     operation::{OperationId, OperationIdsDeserializer, OperationIdsSerializer, WrappedOperation},
     slot::{Slot, SlotDeserializer, SlotSerializer},
 };
@@ -44,6 +45,8 @@ pub struct BlockId(pub Hash);
 impl PreHashed for BlockId {}
 
 impl Id for BlockId {
+    const HASH_DOMAIN: &'static [u8] = BLOCK_ID_HASH_DOMAIN;
+
     fn new(hash: Hash) -> Self {
         BlockId(hash)
     }
@@ -1043,4 +1046,53 @@ mod test {
 
         assert!(res.is_err());
     }
+
+    #[test]
+    #[serial]
+    fn test_block_too_many_operations() {
+        let keypair = KeyPair::generate();
+        let parents = (0..THREAD_COUNT)
+            .map(|i| BlockId(Hash::compute_from(&[i])))
+            .collect();
+
+        // create block header
+        let orig_header = BlockHeader::new_wrapped(
+            BlockHeader {
+                slot: Slot::new(1, 0),
+                parents,
+                operation_merkle_root: Hash::compute_from("mno".as_bytes()),
+                endorsements: vec![],
+            },
+            BlockHeaderSerializer::new(),
+            &keypair,
+        )
+        .unwrap();
+
+        // create a block with 3 operations
+        let orig_block = Block {
+            header: orig_header,
+            operations: (0..3u8)
+                .map(|i| OperationId::from_bytes(Hash::compute_from(&[i]).to_bytes()))
+                .collect(),
+        };
+
+        // serialize block
+        let wrapped_block: WrappedBlock =
+            Block::new_wrapped(orig_block, BlockSerializer::new(), &keypair).unwrap();
+        let mut ser_block = Vec::new();
+        WrappedSerializer::new()
+            .serialize(&wrapped_block, &mut ser_block)
+            .unwrap();
+
+        // the block is read when it does not exceed the operation count limit
+        WrappedDeserializer::new(BlockDeserializer::new(THREAD_COUNT, 3, ENDORSEMENT_COUNT))
+            .deserialize::<DeserializeError>(&ser_block)
+            .unwrap();
+
+        // and rejected otherwise
+        let res: Result<(&[u8], WrappedBlock), _> =
+            WrappedDeserializer::new(BlockDeserializer::new(THREAD_COUNT, 2, ENDORSEMENT_COUNT))
+                .deserialize::<DeserializeError>(&ser_block);
+        assert!(res.is_err());
+    }
 }
@@ -27,6 +27,16 @@ pub struct CompactConfig {
     pub roll_price: Amount,
     /// Max total size of a block
     pub max_block_size: u32,
+    /// Maximum number of key/values in the datastore of a `ExecuteSC` operation
+    pub max_operation_datastore_entry_count: u64,
+    /// Maximum length of an operation datastore key
+    pub max_operation_datastore_key_length: u8,
+    /// Maximum length of an operation datastore value
+    pub max_operation_datastore_value_length: u64,
+    /// Maximum length of the function name in a `CallSC` operation
+    pub max_function_name_length: u16,
+    /// Maximum size of the parameters in a `CallSC` operation
+    pub max_parameters_size: u32,
 }
 
 impl Default for CompactConfig {
@@ -42,6 +52,11 @@ impl Default for CompactConfig {
             block_reward: BLOCK_REWARD,
             roll_price: ROLL_PRICE,
             max_block_size: MAX_BLOCK_SIZE,
+            max_operation_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            max_operation_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            max_operation_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+            max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+            max_parameters_size: MAX_PARAMETERS_SIZE,
         }
     }
 }
@@ -69,6 +84,31 @@ impl Display for CompactConfig {
         writeln!(f, "    Periods per cycle: {}", self.periods_per_cycle)?;
         writeln!(f, "    Roll price: {}", self.roll_price)?;
         writeln!(f, "    Max block size (in bytes): {}", self.max_block_size)?;
+        writeln!(
+            f,
+            "    Max operation datastore entry count: {}",
+            self.max_operation_datastore_entry_count
+        )?;
+        writeln!(
+            f,
+            "    Max operation datastore key length (in bytes): {}",
+            self.max_operation_datastore_key_length
+        )?;
+        writeln!(
+            f,
+            "    Max operation datastore value length (in bytes): {}",
+            self.max_operation_datastore_value_length
+        )?;
+        writeln!(
+            f,
+            "    Max function name length: {}",
+            self.max_function_name_length
+        )?;
+        writeln!(
+            f,
+            "    Max parameters size (in bytes): {}",
+            self.max_parameters_size
+        )?;
         Ok(())
     }
 }
@@ -30,6 +30,8 @@ pub const MAX_ASK_BLOCKS_PER_MESSAGE: u32 = 128;
 pub const MAX_OPERATIONS_PER_MESSAGE: u32 = 1024;
 /// Length of the handshake random signature
 pub const HANDSHAKE_RANDOMNESS_SIZE_BYTES: usize = 32;
+/// Identifier of the chain, exchanged during handshakes so that nodes of different networks do not connect
+pub const CHAIN_ID: u64 = 77658366;
 
 /// Consensus static parameters (defined by protocol used)
 /// Changing one of the following values is considered as a breaking change
@@ -64,7 +66,7 @@ lazy_static::lazy_static! {
         if cfg!(feature = "sandbox") {
             "SAND.0.0"
         } else {
-            "TEST.18.0"
+            "TEST.19.0"
         }
         .parse()
         .unwrap()
@@ -111,8 +113,12 @@ pub const POS_SAVED_CYCLES: usize = 5;
 pub const LEDGER_PART_SIZE_MESSAGE_BYTES: u64 = 1_000_000;
 /// Maximum async messages in a batch of the bootstrap of the async pool
 pub const ASYNC_POOL_BOOTSTRAP_PART_SIZE: u64 = 100;
+/// Maximum addresses of a proof-of-stake cycle info in a bootstrap batch
+pub const CYCLE_INFO_BOOTSTRAP_PART_SIZE: u64 = 1000;
 /// Maximum proof-of-stake deferred credits in a bootstrap batch
 pub const DEFERRED_CREDITS_BOOTSTRAP_PART_SIZE: u64 = 100;
+/// Maximum size batch of data in a part of the proof-of-stake deferred credits
+pub const DEFERRED_CREDITS_PART_SIZE_MESSAGE_BYTES: u64 = 1_000_000;
 /// Maximum executed ops per slot in a bootstrap batch
 pub const EXECUTED_OPS_BOOTSTRAP_PART_SIZE: u64 = 10;
 /// Maximum number of consensus blocks in a bootstrap batch
@@ -135,6 +141,12 @@ pub const MAX_OPERATION_DATASTORE_KEY_LENGTH: u8 = MAX_DATASTORE_KEY_LENGTH;
 pub const MAX_DATASTORE_VALUE_LENGTH: u64 = 10_000_000;
 /// Maximum length of a datastore value
 pub const MAX_BYTECODE_LENGTH: u64 = 10_000_000;
+/// Maximum length of a value of the ledger: a bytecode or a datastore value
+pub const MAX_LEDGER_VALUE_LENGTH: u64 = if MAX_BYTECODE_LENGTH > MAX_DATASTORE_VALUE_LENGTH {
+    MAX_BYTECODE_LENGTH
+} else {
+    MAX_DATASTORE_VALUE_LENGTH
+};
 /// Maximum length of an operation datastore value
 pub const MAX_OPERATION_DATASTORE_VALUE_LENGTH: u64 = 500_000;
 /// Maximum ledger changes in a block
@@ -189,6 +201,8 @@ pub const PROTOCOL_CONTROLLER_CHANNEL_SIZE: usize = 1024;
 pub const PROTOCOL_EVENT_CHANNEL_SIZE: usize = 1024;
 /// Pool controller channel size
 pub const POOL_CONTROLLER_CHANNEL_SIZE: usize = 1024;
+/// Metrics collector channel size
+pub const METRICS_CHANNEL_SIZE: usize = 10_000;
 
 // ***********************
 // Constants used for execution module (injected from ConsensusConfig)
@@ -0,0 +1,447 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Genesis construction.
+//!
+//! A `GenesisBuilder` is filled programmatically or from a TOML/JSON spec file,
+//! and builds the per-thread genesis blocks along with the initial ledger, rolls and deferred credits.
+//! This allows launching private networks without code edits.
+
+use crate::{
+    address::{Address, AddressSerializer},
+    amount::{Amount, AmountSerializer},
+    block::{Block, BlockHeader, BlockHeaderSerializer, BlockSerializer, WrappedBlock},
+    datastore::{Datastore, DatastoreSerializer},
+    error::ModelsError,
+    serialization::VecU8Serializer,
+    slot::{Slot, SlotSerializer},
+    wrapped::WrappedContent,
+};
+use massa_hash::Hash;
+use massa_serialization::{Serializer, U64VarIntSerializer};
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Initial ledger entry of an address.
+/// Its JSON representation is the one of the initial ledger file.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisLedgerEntry {
+    /// initial balance
+    pub balance: Amount,
+    /// initial bytecode
+    #[serde(default)]
+    pub bytecode: Vec<u8>,
+    /// initial datastore
+    #[serde(default)]
+    pub datastore: Datastore,
+}
+
+/// Initial deferred credit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisDeferredCredit {
+    /// slot at which the credit happens
+    pub slot: Slot,
+    /// credited address
+    pub address: Address,
+    /// credited amount
+    pub amount: Amount,
+}
+
+/// Description of the initial state of a network
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisSpec {
+    /// initial ledger entries
+    #[serde(default)]
+    pub ledger: BTreeMap<Address, GenesisLedgerEntry>,
+    /// initial roll counts
+    #[serde(default)]
+    pub rolls: BTreeMap<Address, u64>,
+    /// initial deferred credits
+    #[serde(default)]
+    pub deferred_credits: Vec<GenesisDeferredCredit>,
+}
+
+/// Complete genesis set of a network
+#[derive(Debug, Clone)]
+pub struct Genesis {
+    /// genesis blocks, one per thread in thread order
+    pub blocks: Vec<WrappedBlock>,
+    /// initial ledger entries
+    pub ledger: BTreeMap<Address, GenesisLedgerEntry>,
+    /// initial roll counts (addresses without rolls are omitted)
+    pub rolls: BTreeMap<Address, u64>,
+    /// initial deferred credits, by slot
+    pub deferred_credits: BTreeMap<Slot, BTreeMap<Address, Amount>>,
+    /// deterministic hash of the whole genesis set
+    pub hash: Hash,
+}
+
+/// Creates the genesis block of a thread.
+///
+/// # Arguments
+/// * `keypair`: genesis key
+/// * `thread`: thread of the genesis block
+pub fn create_genesis_block(keypair: &KeyPair, thread: u8) -> Result<WrappedBlock, ModelsError> {
+    let header = BlockHeader::new_wrapped(
+        BlockHeader {
+            slot: Slot::new(0, thread),
+            parents: Vec::new(),
+            operation_merkle_root: Hash::compute_from(&Vec::new()),
+            endorsements: Vec::new(),
+        },
+        BlockHeaderSerializer::new(),
+        keypair,
+    )?;
+
+    Block::new_wrapped(
+        Block {
+            header,
+            operations: Default::default(),
+        },
+        BlockSerializer::new(),
+        keypair,
+    )
+}
+
+/// Computes the hash identifying a chain by its genesis: the ids of the genesis blocks of all threads and the genesis timestamp.
+///
+/// # Arguments
+/// * `keypair`: genesis key
+/// * `thread_count`: number of threads
+/// * `genesis_timestamp`: time at which the chain started
+pub fn compute_genesis_blocks_hash(
+    keypair: &KeyPair,
+    thread_count: u8,
+    genesis_timestamp: MassaTime,
+) -> Result<Hash, ModelsError> {
+    let mut buffer = Vec::new();
+    for thread in 0..thread_count {
+        buffer.extend(create_genesis_block(keypair, thread)?.id.to_bytes());
+    }
+    buffer.extend(genesis_timestamp.to_millis().to_be_bytes());
+    Ok(Hash::compute_from(&buffer))
+}
+
+/// Builds a `Genesis`
+#[derive(Debug, Clone)]
+pub struct GenesisBuilder {
+    /// number of threads
+    thread_count: u8,
+    /// description of the initial state
+    spec: GenesisSpec,
+}
+
+impl GenesisBuilder {
+    /// Creates a builder with an empty initial state
+    pub fn new(thread_count: u8) -> Self {
+        GenesisBuilder {
+            thread_count,
+            spec: GenesisSpec::default(),
+        }
+    }
+
+    /// Creates a builder from a JSON spec
+    pub fn from_json_str(thread_count: u8, spec: &str) -> Result<Self, ModelsError> {
+        let spec = serde_json::from_str(spec).map_err(|err| {
+            ModelsError::GenesisError(format!("could not parse JSON genesis spec: {}", err))
+        })?;
+        Ok(GenesisBuilder { thread_count, spec })
+    }
+
+    /// Creates a builder from a TOML spec
+    pub fn from_toml_str(thread_count: u8, spec: &str) -> Result<Self, ModelsError> {
+        let spec = toml::from_str(spec).map_err(|err| {
+            ModelsError::GenesisError(format!("could not parse TOML genesis spec: {}", err))
+        })?;
+        Ok(GenesisBuilder { thread_count, spec })
+    }
+
+    /// Creates a builder from a spec file, read as TOML if its extension is `.toml` and as JSON otherwise
+    pub fn from_file(thread_count: u8, path: &Path) -> Result<Self, ModelsError> {
+        let spec = std::fs::read_to_string(path).map_err(|err| {
+            ModelsError::GenesisError(format!("could not read {:?}: {}", path, err))
+        })?;
+        if path.extension().map_or(false, |ext| ext == "toml") {
+            Self::from_toml_str(thread_count, &spec)
+        } else {
+            Self::from_json_str(thread_count, &spec)
+        }
+    }
+
+    /// Sets the initial ledger entry of an address
+    pub fn with_ledger_entry(mut self, address: Address, entry: GenesisLedgerEntry) -> Self {
+        self.spec.ledger.insert(address, entry);
+        self
+    }
+
+    /// Sets the initial roll count of an address
+    pub fn with_rolls(mut self, address: Address, roll_count: u64) -> Self {
+        self.spec.rolls.insert(address, roll_count);
+        self
+    }
+
+    /// Adds an initial deferred credit
+    pub fn with_deferred_credit(mut self, slot: Slot, address: Address, amount: Amount) -> Self {
+        self.spec.deferred_credits.push(GenesisDeferredCredit {
+            slot,
+            address,
+            amount,
+        });
+        self
+    }
+
+    /// Checks that the spec describes a valid initial state:
+    /// * there is at least one thread and one roll
+    /// * deferred credits are positive and happen after genesis in an existing thread
+    /// * the total supply does not overflow
+    pub fn validate(&self) -> Result<(), ModelsError> {
+        if self.thread_count == 0 {
+            return Err(ModelsError::GenesisError(
+                "thread count must be positive".into(),
+            ));
+        }
+        if self.spec.rolls.values().all(|roll_count| *roll_count == 0) {
+            return Err(ModelsError::GenesisError(
+                "at least one address must have initial rolls".into(),
+            ));
+        }
+        let mut total_supply = Amount::zero();
+        for entry in self.spec.ledger.values() {
+            total_supply = total_supply
+                .checked_add(entry.balance)
+                .ok_or(ModelsError::AmountOverflowError)?;
+        }
+        for credit in self.spec.deferred_credits.iter() {
+            if credit.slot.thread >= self.thread_count {
+                return Err(ModelsError::GenesisError(format!(
+                    "deferred credit of {} at slot {} is in a non-existing thread",
+                    credit.address, credit.slot
+                )));
+            }
+            if credit.slot.period == 0 {
+                return Err(ModelsError::GenesisError(format!(
+                    "deferred credit of {} at slot {} happens at genesis",
+                    credit.address, credit.slot
+                )));
+            }
+            if credit.amount.is_zero() {
+                return Err(ModelsError::GenesisError(format!(
+                    "deferred credit of {} at slot {} is zero",
+                    credit.address, credit.slot
+                )));
+            }
+            total_supply = total_supply
+                .checked_add(credit.amount)
+                .ok_or(ModelsError::AmountOverflowError)?;
+        }
+        Ok(())
+    }
+
+    /// Validates the spec and builds the genesis set, signing the genesis blocks with `keypair`.
+    /// The result only depends on the spec and the key, and not on the order in which the spec was filled.
+    pub fn build(&self, keypair: &KeyPair) -> Result<Genesis, ModelsError> {
+        self.validate()?;
+
+        let blocks = (0..self.thread_count)
+            .map(|thread| create_genesis_block(keypair, thread))
+            .collect::<Result<Vec<_>, _>>()?;
+        let rolls: BTreeMap<Address, u64> = self
+            .spec
+            .rolls
+            .iter()
+            .filter(|(_, roll_count)| **roll_count > 0)
+            .map(|(address, roll_count)| (*address, *roll_count))
+            .collect();
+        let mut deferred_credits: BTreeMap<Slot, BTreeMap<Address, Amount>> = BTreeMap::new();
+        for credit in self.spec.deferred_credits.iter() {
+            let amount = deferred_credits
+                .entry(credit.slot)
+                .or_default()
+                .entry(credit.address)
+                .or_default();
+            *amount = amount
+                .checked_add(credit.amount)
+                .ok_or(ModelsError::AmountOverflowError)?;
+        }
+
+        let hash = compute_genesis_hash(&blocks, &self.spec.ledger, &rolls, &deferred_credits)?;
+        Ok(Genesis {
+            blocks,
+            ledger: self.spec.ledger.clone(),
+            rolls,
+            deferred_credits,
+            hash,
+        })
+    }
+}
+
+/// Hashes the binary serialization of a genesis set, in the order of its sorted maps
+fn compute_genesis_hash(
+    blocks: &[WrappedBlock],
+    ledger: &BTreeMap<Address, GenesisLedgerEntry>,
+    rolls: &BTreeMap<Address, u64>,
+    deferred_credits: &BTreeMap<Slot, BTreeMap<Address, Amount>>,
+) -> Result<Hash, ModelsError> {
+    let address_serializer = AddressSerializer::new();
+    let amount_serializer = AmountSerializer::new();
+    let u64_serializer = U64VarIntSerializer::new();
+    let vec_u8_serializer = VecU8Serializer::new();
+    let datastore_serializer = DatastoreSerializer::new();
+    let slot_serializer = SlotSerializer::new();
+    let mut buffer = Vec::new();
+
+    for block in blocks {
+        buffer.extend(block.id.to_bytes());
+    }
+    u64_serializer.serialize(&(ledger.len() as u64), &mut buffer)?;
+    for (address, entry) in ledger {
+        address_serializer.serialize(address, &mut buffer)?;
+        amount_serializer.serialize(&entry.balance, &mut buffer)?;
+        vec_u8_serializer.serialize(&entry.bytecode, &mut buffer)?;
+        datastore_serializer.serialize(&entry.datastore, &mut buffer)?;
+    }
+    u64_serializer.serialize(&(rolls.len() as u64), &mut buffer)?;
+    for (address, roll_count) in rolls {
+        address_serializer.serialize(address, &mut buffer)?;
+        u64_serializer.serialize(roll_count, &mut buffer)?;
+    }
+    u64_serializer.serialize(&(deferred_credits.len() as u64), &mut buffer)?;
+    for (slot, credits) in deferred_credits {
+        slot_serializer.serialize(slot, &mut buffer)?;
+        u64_serializer.serialize(&(credits.len() as u64), &mut buffer)?;
+        for (address, amount) in credits {
+            address_serializer.serialize(address, &mut buffer)?;
+            amount_serializer.serialize(amount, &mut buffer)?;
+        }
+    }
+    Ok(Hash::compute_from(&buffer))
+}
+
+impl Genesis {
+    /// Writes the initial ledger, rolls and deferred credits files read by the node at startup
+    pub fn write_initial_files(
+        &self,
+        initial_ledger_path: &Path,
+        initial_rolls_path: &Path,
+        initial_deferred_credits_path: &Path,
+    ) -> Result<(), ModelsError> {
+        // slots cannot be JSON object keys: the deferred credits are written as a list
+        let deferred_credits: Vec<GenesisDeferredCredit> = self
+            .deferred_credits
+            .iter()
+            .flat_map(|(slot, credits)| {
+                credits
+                    .iter()
+                    .map(|(address, amount)| GenesisDeferredCredit {
+                        slot: *slot,
+                        address: *address,
+                        amount: *amount,
+                    })
+            })
+            .collect();
+        for (path, content) in [
+            (
+                initial_ledger_path,
+                serde_json::to_string_pretty(&self.ledger),
+            ),
+            (
+                initial_rolls_path,
+                serde_json::to_string_pretty(&self.rolls),
+            ),
+            (
+                initial_deferred_credits_path,
+                serde_json::to_string_pretty(&deferred_credits),
+            ),
+        ] {
+            let content = content.map_err(|err| ModelsError::SerializeError(err.to_string()))?;
+            std::fs::write(path, content).map_err(|err| {
+                ModelsError::GenesisError(format!("could not write {:?}: {}", path, err))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const SPEC: &str = r#"
+        [ledger.A12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x]
+        balance = "1000"
+
+        [rolls]
+        A12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x = 10
+
+        [[deferred_credits]]
+        slot = { period = 3, thread = 1 }
+        address = "A12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x"
+        amount = "50"
+    "#;
+
+    #[test]
+    fn test_genesis_builder_deterministic() {
+        let keypair = KeyPair::generate();
+        let address =
+            Address::from_str("A12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+
+        let from_toml = GenesisBuilder::from_toml_str(2, SPEC)
+            .unwrap()
+            .build(&keypair)
+            .unwrap();
+        let from_code = GenesisBuilder::new(2)
+            .with_deferred_credit(Slot::new(3, 1), address, Amount::from_str("50").unwrap())
+            .with_rolls(address, 10)
+            .with_ledger_entry(
+                address,
+                GenesisLedgerEntry {
+                    balance: Amount::from_str("1000").unwrap(),
+                    ..Default::default()
+                },
+            )
+            .build(&keypair)
+            .unwrap();
+        assert_eq!(from_toml.blocks.len(), 2);
+        assert_eq!(from_toml.hash, from_code.hash);
+
+        // another key gives other genesis blocks
+        let other = GenesisBuilder::from_toml_str(2, SPEC)
+            .unwrap()
+            .build(&KeyPair::generate())
+            .unwrap();
+        assert_ne!(from_toml.hash, other.hash);
+    }
+
+    #[test]
+    fn test_genesis_builder_validation() {
+        let address =
+            Address::from_str("A12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+
+        // no rolls
+        assert!(GenesisBuilder::new(2).validate().is_err());
+
+        // deferred credit in a non-existing thread
+        assert!(GenesisBuilder::new(2)
+            .with_rolls(address, 1)
+            .with_deferred_credit(Slot::new(1, 2), address, Amount::from_str("1").unwrap())
+            .validate()
+            .is_err());
+
+        // total supply overflow
+        assert!(GenesisBuilder::new(2)
+            .with_rolls(address, 1)
+            .with_ledger_entry(
+                address,
+                GenesisLedgerEntry {
+                    balance: Amount::MAX,
+                    ..Default::default()
+                }
+            )
+            .with_deferred_credit(Slot::new(1, 0), address, Amount::from_str("1").unwrap())
+            .validate()
+            .is_err());
+    }
+}
@@ -66,6 +66,12 @@ pub use constants::*;
 mod compact_config;
 pub use compact_config::CompactConfig;
 
+mod genesis;
+pub use genesis::{
+    compute_genesis_blocks_hash, create_genesis_block, Genesis, GenesisBuilder,
+    GenesisDeferredCredit, GenesisLedgerEntry, GenesisSpec,
+};
+
 // Export tool to read user setting file
 mod massa_settings;
 pub use massa_settings::build_massa_settings;
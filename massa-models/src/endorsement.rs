@@ -3,7 +3,7 @@
 use crate::prehash::PreHashed;
 use crate::slot::{Slot, SlotDeserializer, SlotSerializer};
 use crate::wrapped::{Id, Wrapped, WrappedContent};
-use crate::{block::BlockId, error::ModelsError};
+use crate::{block::BlockId, error::ModelsError, id_hash::ENDORSEMENT_ID_HASH_DOMAIN};
 use massa_hash::{Hash, HashDeserializer};
 use massa_serialization::{
     DeserializeError, Deserializer, SerializeError, Serializer, U32VarIntDeserializer,
@@ -36,6 +36,8 @@ const ENDORSEMENTID_VERSION: u64 = 0;
 impl PreHashed for EndorsementId {}
 
 impl Id for EndorsementId {
+    const HASH_DOMAIN: &'static [u8] = ENDORSEMENT_ID_HASH_DOMAIN;
+
     fn new(hash: Hash) -> Self {
         EndorsementId(hash)
     }
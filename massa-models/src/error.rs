@@ -32,6 +32,8 @@ pub enum ModelsError {
     AmountParseError(String),
     /// address parsing error
     AddressParseError,
+    /// unknown address category: {0}
+    AddressCategoryError(u8),
     /// node id parsing error
     NodeIdParseError,
     /// block id parsing error
@@ -62,6 +64,8 @@ pub enum ModelsError {
     OutdatedBootstrapCursor,
     /// Error raised {0}
     ErrorRaised(String),
+    /// genesis error: {0}
+    GenesisError(String),
 }
 
 impl From<nom::Err<nom::error::Error<&[u8]>>> for ModelsError {
@@ -1,6 +1,12 @@
-use std::{collections::VecDeque, fmt::Display};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Display,
+};
 
-use crate::{output_event::SCOutputEvent, slot::Slot};
+use crate::{
+    address::Address, amount::Amount, block::BlockId, operation::OperationId,
+    output_event::SCOutputEvent, slot::Slot,
+};
 use serde::{Deserialize, Serialize};
 
 /// The result of the read-only execution.
@@ -23,6 +29,131 @@ pub struct ExecuteReadOnlyResponse {
     pub output_events: VecDeque<SCOutputEvent>,
     /// The gas cost for the execution
     pub gas_cost: u64,
+    /// Machine-readable code of the execution error, if the execution failed
+    pub error_code: Option<String>,
+    /// The calls made between smart contracts during the read-only execution, in call order
+    pub call_trace: Vec<ReadOnlyCallTraceEntry>,
+    /// The ledger changes caused by the read-only execution, per address
+    pub ledger_changes: Vec<ReadOnlyLedgerChange>,
+}
+
+/// A call from a smart contract to another one during a read-only execution
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReadOnlyCallTraceEntry {
+    /// depth of the call in the call stack, 1 for a call made by the executed bytecode
+    pub depth: usize,
+    /// calling address
+    pub caller: Address,
+    /// called address
+    pub target: Address,
+    /// coins transferred with the call
+    pub coins: Amount,
+}
+
+impl Display for ReadOnlyCallTraceEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{} -> {} ({} coins)",
+            "  ".repeat(self.depth.saturating_sub(1)),
+            self.caller,
+            self.target,
+            self.coins
+        )
+    }
+}
+
+/// Summary of the ledger changes of an address caused by an execution
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReadOnlyLedgerChange {
+    /// changed address
+    pub address: Address,
+    /// true if the ledger entry of the address was deleted
+    pub deleted: bool,
+    /// new balance, if it changed
+    pub balance: Option<Amount>,
+    /// true if the bytecode changed
+    pub bytecode_changed: bool,
+    /// datastore keys that were set
+    pub datastore_set_keys: Vec<Vec<u8>>,
+    /// datastore keys that were deleted
+    pub datastore_deleted_keys: Vec<Vec<u8>>,
+}
+
+impl Display for ReadOnlyLedgerChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:", self.address)?;
+        if self.deleted {
+            return write!(f, " deleted");
+        }
+        if let Some(balance) = self.balance {
+            write!(f, " balance set to {}", balance)?;
+        }
+        if self.bytecode_changed {
+            write!(f, " bytecode changed")?;
+        }
+        if !self.datastore_set_keys.is_empty() {
+            write!(f, " {} datastore key(s) set", self.datastore_set_keys.len())?;
+        }
+        if !self.datastore_deleted_keys.is_empty() {
+            write!(
+                f,
+                " {} datastore key(s) deleted",
+                self.datastore_deleted_keys.len()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a slot was executed speculatively or as final
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SlotExecutionStatus {
+    /// the slot was executed speculatively, its output can still change
+    Candidate,
+    /// the slot was executed as final
+    Final,
+}
+
+/// Output of a slot execution, published for both candidate and final executions
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SlotExecutionOutput {
+    /// executed slot
+    pub slot: Slot,
+    /// block executed at that slot, `None` on misses
+    pub block_id: Option<BlockId>,
+    /// candidate or final execution
+    pub status: SlotExecutionStatus,
+    /// ledger changes caused by the execution, per address
+    pub ledger_changes: Vec<ReadOnlyLedgerChange>,
+    /// new roll counts of the addresses whose rolls changed
+    pub roll_changes: BTreeMap<Address, u64>,
+    /// IDs of the executed operations, in execution order
+    pub executed_ops: Vec<OperationId>,
+    /// gas used by the executed operations and asynchronous messages
+    pub gas_used: u64,
+    /// events emitted during the execution
+    pub events: VecDeque<SCOutputEvent>,
+}
+
+/// Candidate slot executions cancelled by a blockclique change.
+/// The affected operations are executed again if they are included in the new blockclique,
+/// in which case they appear in the following slot execution outputs.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExecutionReorg {
+    /// cancelled candidate slots, in ascending order
+    pub cancelled_slots: Vec<Slot>,
+    /// operations executed at the cancelled slots, in execution order
+    pub affected_operations: Vec<ReorgAffectedOperation>,
+}
+
+/// Operation whose candidate execution was cancelled by a blockclique change
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ReorgAffectedOperation {
+    /// operation id
+    pub operation_id: OperationId,
+    /// slot of the cancelled execution
+    pub slot: Slot,
 }
 
 impl Display for ExecuteReadOnlyResponse {
@@ -37,12 +168,27 @@ impl Display for ExecuteReadOnlyResponse {
                 ReadOnlyResult::Ok(ret) => format!("success, returned value: {:?}", ret),
             }
         )?;
+        if let Some(error_code) = &self.error_code {
+            writeln!(f, "Error code: {}", error_code)?;
+        }
         if !self.output_events.is_empty() {
             writeln!(f, "Generated events:",)?;
             for event in self.output_events.iter() {
                 writeln!(f, "{}", event)?; // id already displayed in event
             }
         }
+        if !self.call_trace.is_empty() {
+            writeln!(f, "Call trace:")?;
+            for call in self.call_trace.iter() {
+                writeln!(f, "{}", call)?;
+            }
+        }
+        if !self.ledger_changes.is_empty() {
+            writeln!(f, "Ledger changes:")?;
+            for change in self.ledger_changes.iter() {
+                writeln!(f, "{}", change)?;
+            }
+        }
         Ok(())
     }
 }
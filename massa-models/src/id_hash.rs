@@ -0,0 +1,86 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Backends used to compute the hash of block, operation and endorsement ids.
+//!
+//! The backend is selected at build time:
+//! * by default, `Blake3IdHashBackend` hashes the raw id data with `Blake3`, which keeps the current ids
+//! * with the `sha256_ids` feature, `Sha256IdHashBackend` hashes the id data prefixed by its domain with `SHA-256`
+//!
+//! Ids are signed and shared with the rest of the network,
+//! so every node of a network must be built with the same backend.
+
+use massa_hash::Hash;
+
+/// Domain separation prefix of block ids
+pub const BLOCK_ID_HASH_DOMAIN: &[u8] = b"massa_block_id";
+/// Domain separation prefix of operation ids
+pub const OPERATION_ID_HASH_DOMAIN: &[u8] = b"massa_operation_id";
+/// Domain separation prefix of endorsement ids
+pub const ENDORSEMENT_ID_HASH_DOMAIN: &[u8] = b"massa_endorsement_id";
+
+/// A backend computing the hash of an id from its data
+pub trait IdHashBackend {
+    /// Computes the hash of an id of the given `domain` from its `data`
+    fn compute(domain: &[u8], data: &[u8]) -> Hash;
+}
+
+/// Current backend: `Blake3` hash of the raw id data.
+///
+/// The domain is ignored to keep the ids computed before the introduction of the backends.
+pub struct Blake3IdHashBackend;
+
+impl IdHashBackend for Blake3IdHashBackend {
+    fn compute(_domain: &[u8], data: &[u8]) -> Hash {
+        Hash::compute_from(data)
+    }
+}
+
+/// Alternative backend: `SHA-256` hash of the id data prefixed by its domain and the domain length
+#[cfg(feature = "sha256_ids")]
+pub struct Sha256IdHashBackend;
+
+#[cfg(feature = "sha256_ids")]
+impl IdHashBackend for Sha256IdHashBackend {
+    fn compute(domain: &[u8], data: &[u8]) -> Hash {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        // the domain length prevents collisions between a domain and the beginning of the data
+        hasher.update([domain.len() as u8]);
+        hasher.update(domain);
+        hasher.update(data);
+        Hash::from_bytes(&hasher.finalize().into())
+    }
+}
+
+/// Backend selected at build time
+#[cfg(not(feature = "sha256_ids"))]
+pub type SelectedIdHashBackend = Blake3IdHashBackend;
+
+/// Backend selected at build time
+#[cfg(feature = "sha256_ids")]
+pub type SelectedIdHashBackend = Sha256IdHashBackend;
+
+/// Computes the hash of an id of the given `domain` from its `data` with the backend selected at build time
+pub fn compute_id_hash(domain: &[u8], data: &[u8]) -> Hash {
+    SelectedIdHashBackend::compute(domain, data)
+}
+
+#[test]
+fn test_blake3_id_hash_backend_keeps_ids() {
+    let data = b"id data";
+    assert_eq!(
+        Blake3IdHashBackend::compute(BLOCK_ID_HASH_DOMAIN, data),
+        Hash::compute_from(data)
+    );
+}
+
+#[cfg(feature = "sha256_ids")]
+#[test]
+fn test_sha256_id_hash_backend_domains() {
+    let data = b"id data";
+    assert_ne!(
+        Sha256IdHashBackend::compute(BLOCK_ID_HASH_DOMAIN, data),
+        Sha256IdHashBackend::compute(OPERATION_ID_HASH_DOMAIN, data)
+    );
+}
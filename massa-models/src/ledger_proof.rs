@@ -0,0 +1,160 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Sparse Merkle tree commitment of the final ledger.
+//!
+//! Every raw ledger key is placed at the leaf of a binary tree of depth `LEDGER_MERKLE_DEPTH`
+//! following the bits of the hash of the key. Empty subtrees hash to `LEDGER_MERKLE_EMPTY_HASH_BYTES`
+//! so that only the non-empty nodes need to be stored and sent in proofs.
+
+use crate::{address::Address, slot::Slot};
+use massa_hash::{Hash, HASH_SIZE_BYTES};
+use massa_serialization::{Serializer, U64VarIntSerializer};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Identifier of the balance of an address in a raw ledger key
+pub const BALANCE_IDENT: u8 = 0u8;
+/// Identifier of the bytecode of an address in a raw ledger key
+pub const BYTECODE_IDENT: u8 = 1u8;
+/// Identifier of a datastore entry of an address in a raw ledger key
+pub const DATASTORE_IDENT: u8 = 2u8;
+
+/// Depth of the ledger Merkle tree: one level per bit of the key hash
+pub const LEDGER_MERKLE_DEPTH: usize = HASH_SIZE_BYTES * 8;
+
+/// Bytes of the hash of an empty subtree of the ledger Merkle tree
+pub const LEDGER_MERKLE_EMPTY_HASH_BYTES: &[u8; HASH_SIZE_BYTES] = &[0; HASH_SIZE_BYTES];
+
+/// Get the hash of an empty subtree of the ledger Merkle tree
+pub fn ledger_merkle_empty_hash() -> Hash {
+    Hash::from_bytes(LEDGER_MERKLE_EMPTY_HASH_BYTES)
+}
+
+/// Get the path of a raw ledger key in the ledger Merkle tree
+pub fn ledger_merkle_path(key: &[u8]) -> Hash {
+    Hash::compute_from(key)
+}
+
+/// Get the bit of `path` choosing the child at depth `depth` (`false` is left, `true` is right)
+pub fn ledger_merkle_path_bit(path: &Hash, depth: usize) -> bool {
+    path.to_bytes()[depth / 8] & (0x80 >> (depth % 8)) != 0
+}
+
+/// Hash of a ledger Merkle tree leaf containing `value` for the raw ledger key `key`.
+///
+/// This is also the hash of the entry used to compute the ledger hash.
+pub fn ledger_merkle_leaf_hash(key: &[u8], value: &[u8]) -> Hash {
+    let mut len_bytes = Vec::new();
+    // U64VarInt serialization never fails
+    U64VarIntSerializer::new()
+        .serialize(&(key.len() as u64), &mut len_bytes)
+        .unwrap();
+    Hash::compute_from(&[&len_bytes, key, value].concat())
+}
+
+/// Hash of a ledger Merkle tree node given the hashes of its children
+pub fn ledger_merkle_node_hash(left: &Hash, right: &Hash) -> Hash {
+    if left.to_bytes() == LEDGER_MERKLE_EMPTY_HASH_BYTES
+        && right.to_bytes() == LEDGER_MERKLE_EMPTY_HASH_BYTES
+    {
+        ledger_merkle_empty_hash()
+    } else {
+        Hash::compute_from(&[left.to_bytes().as_slice(), right.to_bytes()].concat())
+    }
+}
+
+/// Inclusion (or exclusion if `value` is `None`) proof of a raw ledger key in the ledger Merkle tree
+#[derive(Debug, Deserialize, Clone, Serialize, PartialEq, Eq)]
+pub struct LedgerEntryProof {
+    /// raw ledger key
+    pub key: Vec<u8>,
+    /// value of the key, `None` if it is absent from the ledger
+    pub value: Option<Vec<u8>>,
+    /// non-empty siblings of the nodes on the path of the key, indexed by their height above the leaves
+    pub siblings: Vec<(u8, Hash)>,
+}
+
+impl LedgerEntryProof {
+    /// Compute the root of the ledger Merkle tree implied by the proof
+    pub fn compute_root(&self) -> Hash {
+        let path = ledger_merkle_path(&self.key);
+        let siblings: BTreeMap<u8, Hash> = self.siblings.iter().copied().collect();
+        let mut node = match &self.value {
+            Some(value) => ledger_merkle_leaf_hash(&self.key, value),
+            None => ledger_merkle_empty_hash(),
+        };
+        let empty_hash = ledger_merkle_empty_hash();
+        for depth in (0..LEDGER_MERKLE_DEPTH).rev() {
+            let height = (LEDGER_MERKLE_DEPTH - 1 - depth) as u8;
+            let sibling = siblings.get(&height).unwrap_or(&empty_hash);
+            node = if ledger_merkle_path_bit(&path, depth) {
+                ledger_merkle_node_hash(sibling, &node)
+            } else {
+                ledger_merkle_node_hash(&node, sibling)
+            };
+        }
+        node
+    }
+}
+
+/// Proof of a ledger entry against the final state hash
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct FinalLedgerEntryProof {
+    /// address of the entry
+    pub address: Address,
+    /// datastore key of the entry, `None` for the balance
+    pub datastore_key: Option<Vec<u8>>,
+    /// slot of the final state the proof was generated at
+    pub slot: Slot,
+    /// final state hash at `slot`
+    pub final_state_hash: Hash,
+    /// concatenation of the final state component hashes preceding the ledger Merkle root
+    pub state_hash_prefix: Vec<u8>,
+    /// proof of the entry in the ledger Merkle tree
+    pub ledger_proof: LedgerEntryProof,
+}
+
+impl FinalLedgerEntryProof {
+    /// Get the raw ledger key of the balance or datastore entry of `address` the proof is about
+    pub fn get_entry_key(&self) -> Vec<u8> {
+        match &self.datastore_key {
+            Some(key) => [
+                &self.address.to_bytes()[..],
+                &[DATASTORE_IDENT],
+                key.as_slice(),
+            ]
+            .concat(),
+            None => [&self.address.to_bytes()[..], &[BALANCE_IDENT]].concat(),
+        }
+    }
+
+    /// Check that the ledger proof is about the requested entry and leads to `final_state_hash`
+    pub fn verify(&self) -> bool {
+        if self.ledger_proof.key != self.get_entry_key() {
+            return false;
+        }
+        let ledger_root = self.ledger_proof.compute_root();
+        Hash::compute_from(&[self.state_hash_prefix.as_slice(), ledger_root.to_bytes()].concat())
+            == self.final_state_hash
+    }
+}
+
+impl std::fmt::Display for FinalLedgerEntryProof {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Address: {}", self.address)?;
+        match &self.datastore_key {
+            Some(key) => writeln!(f, "Datastore key: {:?}", key)?,
+            None => writeln!(f, "Balance")?,
+        }
+        writeln!(f, "Value: {:?}", self.ledger_proof.value)?;
+        writeln!(f, "Final state slot: {}", self.slot)?;
+        writeln!(f, "Final state hash: {}", self.final_state_hash)?;
+        writeln!(
+            f,
+            "Proof: {} non-empty sibling(s), {}",
+            self.ledger_proof.siblings.len(),
+            if self.verify() { "valid" } else { "INVALID" }
+        )?;
+        Ok(())
+    }
+}
@@ -33,8 +33,12 @@ pub mod endorsement;
 pub mod error;
 /// execution related structures
 pub mod execution;
+/// id hashing backends
+pub mod id_hash;
 /// ledger related structures
 pub mod ledger_models;
+/// ledger Merkle tree proofs
+pub mod ledger_proof;
 /// node related structure
 pub mod node;
 /// operations
@@ -61,5 +65,5 @@ pub mod version;
 pub mod wrapped;
 
 /// Test utils
-#[cfg(feature = "testing")]
+#[cfg(any(test, feature = "testing"))]
 pub mod test_exports;
@@ -7,6 +7,7 @@ use crate::{
     address::{Address, AddressDeserializer},
     amount::{Amount, AmountDeserializer, AmountSerializer},
     error::ModelsError,
+    id_hash::OPERATION_ID_HASH_DOMAIN,
     serialization::{StringDeserializer, StringSerializer, VecU8Deserializer, VecU8Serializer},
 };
 use massa_hash::{Hash, HashDeserializer};
@@ -125,6 +126,8 @@ impl FromStr for OperationId {
 //       prefix
 impl PreHashed for OperationId {}
 impl Id for OperationId {
+    const HASH_DOMAIN: &'static [u8] = OPERATION_ID_HASH_DOMAIN;
+
     fn new(hash: Hash) -> Self {
         OperationId(hash)
     }
@@ -463,6 +466,19 @@ pub enum OperationType {
     },
 }
 
+impl OperationType {
+    /// Gets the name of the operation type
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            OperationType::Transaction { .. } => "Transaction",
+            OperationType::RollBuy { .. } => "RollBuy",
+            OperationType::RollSell { .. } => "RollSell",
+            OperationType::ExecuteSC { .. } => "ExecuteSC",
+            OperationType::CallSC { .. } => "CallSC",
+        }
+    }
+}
+
 impl std::fmt::Display for OperationType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -18,7 +18,7 @@ use nom::{
 use std::convert::TryInto;
 use std::marker::PhantomData;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
-use std::ops::Bound;
+use std::ops::{Bound, RangeInclusive};
 use Bound::Included;
 
 /// Serialize min big endian integer
@@ -672,6 +672,84 @@ impl Deserializer<BitVec<u8>> for BitVecDeserializer {
     }
 }
 
+/// Version of the binary format of a serialized structure
+pub type FormatVersion = u32;
+
+/// Deserializer able to read every supported format version of a structure.
+///
+/// Used by `VersionedDeserializer` to dispatch on the format version prefix.
+pub trait VersionDispatch<T> {
+    /// Range of the format versions that can be deserialized
+    fn supported_versions(&self) -> RangeInclusive<FormatVersion>;
+
+    /// Deserialize `buffer` following the format of version `version`
+    fn deserialize_version<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        version: FormatVersion,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], T, E>;
+}
+
+/// Serializer prefixing the output of an inner serializer with a format version
+pub struct VersionedSerializer<S> {
+    version: FormatVersion,
+    version_serializer: U32VarIntSerializer,
+    inner: S,
+}
+
+impl<S> VersionedSerializer<S> {
+    /// Creates a serializer writing `inner` output in the format version `version`
+    pub const fn new(version: FormatVersion, inner: S) -> Self {
+        Self {
+            version,
+            version_serializer: U32VarIntSerializer::new(),
+            inner,
+        }
+    }
+}
+
+impl<T, S: Serializer<T>> Serializer<T> for VersionedSerializer<S> {
+    fn serialize(&self, value: &T, buffer: &mut Vec<u8>) -> Result<(), SerializeError> {
+        self.version_serializer.serialize(&self.version, buffer)?;
+        self.inner.serialize(value, buffer)
+    }
+}
+
+/// Deserializer reading a format version prefix and dispatching on it
+pub struct VersionedDeserializer<T, D> {
+    version_deserializer: U32VarIntDeserializer,
+    inner: D,
+    phantom_t: PhantomData<T>,
+}
+
+impl<T, D: VersionDispatch<T>> VersionedDeserializer<T, D> {
+    /// Creates a deserializer accepting the format versions supported by `inner`
+    pub fn new(inner: D) -> Self {
+        let versions = inner.supported_versions();
+        Self {
+            version_deserializer: U32VarIntDeserializer::new(
+                Included(*versions.start()),
+                Included(*versions.end()),
+            ),
+            inner,
+            phantom_t: PhantomData,
+        }
+    }
+}
+
+impl<T, D: VersionDispatch<T>> Deserializer<T> for VersionedDeserializer<T, D> {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], T, E> {
+        let (rest, version) = context("Failed format version deserialization", |input| {
+            self.version_deserializer.deserialize(input)
+        })
+        .parse(buffer)?;
+        self.inner.deserialize_version(version, rest)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -763,4 +841,58 @@ mod tests {
         let res = array_from_slice(&zero.to_be_bytes()).unwrap();
         assert_eq!(zero, u64::from_be_bytes(res));
     }
+
+    /// Reads `u64` values stored as `u32` varints in version 0 and as `u64` varints in version 1
+    struct TestU64Dispatch;
+
+    impl VersionDispatch<u64> for TestU64Dispatch {
+        fn supported_versions(&self) -> RangeInclusive<FormatVersion> {
+            0..=1
+        }
+
+        fn deserialize_version<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+            &self,
+            version: FormatVersion,
+            buffer: &'a [u8],
+        ) -> IResult<&'a [u8], u64, E> {
+            match version {
+                0 => U32VarIntDeserializer::new(Included(0), Included(u32::MAX))
+                    .deserialize(buffer)
+                    .map(|(rest, value)| (rest, value as u64)),
+                _ => {
+                    U64VarIntDeserializer::new(Included(0), Included(u64::MAX)).deserialize(buffer)
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_versioned_serialization() {
+        let deserializer = VersionedDeserializer::new(TestU64Dispatch);
+
+        // current version round trip
+        let mut serialized = Vec::new();
+        VersionedSerializer::new(1, U64VarIntSerializer::new())
+            .serialize(&u64::MAX, &mut serialized)
+            .unwrap();
+        assert_eq!(serialized[0], 1);
+        let (rest, value) = deserializer
+            .deserialize::<DeserializeError>(&serialized)
+            .unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(value, u64::MAX);
+
+        // older version is still readable
+        let (rest, value) = deserializer
+            .deserialize::<DeserializeError>(&[0, 42])
+            .unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(value, 42);
+
+        // unsupported version is rejected
+        assert!(deserializer
+            .deserialize::<DeserializeError>(&[2, 42])
+            .is_err());
+    }
 }
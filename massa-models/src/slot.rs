@@ -174,11 +174,17 @@ impl Slot {
         let period = cycle
             .checked_mul(periods_per_cycle)
             .ok_or(ModelsError::PeriodOverflowError)?
-            .checked_add(periods_per_cycle - 1)
+            .checked_add(
+                periods_per_cycle
+                    .checked_sub(1)
+                    .ok_or(ModelsError::PeriodOverflowError)?,
+            )
             .ok_or(ModelsError::PeriodOverflowError)?;
         Ok(Slot {
             period,
-            thread: thread_count - 1,
+            thread: thread_count
+                .checked_sub(1)
+                .ok_or(ModelsError::ThreadOverflowError)?,
         })
     }
 
@@ -216,6 +222,61 @@ impl Slot {
         self.period / periods_per_cycle
     }
 
+    /// Number of slots in a cycle, saturating on overflow
+    pub const fn slots_per_cycle(periods_per_cycle: u64, thread_count: u8) -> u64 {
+        periods_per_cycle.saturating_mul(thread_count as u64)
+    }
+
+    /// Returns the first slot of the cycle of self
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use massa_models::slot::Slot;
+    /// let slot = Slot::new(130, 7);
+    /// assert_eq!(slot.first_slot_of_cycle(128).unwrap(), Slot::new(128, 0));
+    /// ```
+    pub fn first_slot_of_cycle(&self, periods_per_cycle: u64) -> Result<Slot, ModelsError> {
+        Slot::new_first_of_cycle(self.get_cycle(periods_per_cycle), periods_per_cycle)
+    }
+
+    /// Returns the last slot of the cycle of self
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use massa_models::slot::Slot;
+    /// let slot = Slot::new(130, 7);
+    /// assert_eq!(slot.last_slot_of_cycle(128, 32).unwrap(), Slot::new(255, 31));
+    /// ```
+    pub fn last_slot_of_cycle(
+        &self,
+        periods_per_cycle: u64,
+        thread_count: u8,
+    ) -> Result<Slot, ModelsError> {
+        Slot::new_last_of_cycle(
+            self.get_cycle(periods_per_cycle),
+            periods_per_cycle,
+            thread_count,
+        )
+    }
+
+    /// Returns the slot of the same thread `periods` periods later
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use massa_models::slot::Slot;
+    /// let slot = Slot::new(10, 3);
+    /// assert_eq!(slot.skip_periods(5).unwrap(), Slot::new(15, 3));
+    /// assert!(Slot::new(u64::MAX, 3).skip_periods(1).is_err());
+    /// ```
+    pub fn skip_periods(&self, periods: u64) -> Result<Slot, ModelsError> {
+        Ok(Slot::new(
+            self.period
+                .checked_add(periods)
+                .ok_or(ModelsError::PeriodOverflowError)?,
+            self.thread,
+        ))
+    }
+
     /// check if the slot is last in the cycle
     pub fn is_last_of_cycle(&self, periods_per_cycle: u64, thread_count: u8) -> bool {
         self.period % periods_per_cycle == (periods_per_cycle - 1)
@@ -314,6 +375,14 @@ impl Slot {
     /// Counts the number of slots since the one passed in parameter and until self
     /// If the two slots are equal, the returned value is `0`.
     /// If the passed slot is strictly higher than self, an error is returned
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use massa_models::slot::Slot;
+    /// let slot = Slot::new(11, 1);
+    /// assert_eq!(slot.slots_since(&Slot::new(10, 3), 32).unwrap(), 30);
+    /// assert!(Slot::new(10, 3).slots_since(&slot, 32).is_err());
+    /// ```
     pub fn slots_since(&self, s: &Slot, thread_count: u8) -> Result<u64, ModelsError> {
         // if s > self, return an error
         if s > self {
@@ -321,11 +390,12 @@ impl Slot {
         }
 
         // compute the number of slots from s to self
-        Ok((self.period - s.period)
+        (self.period - s.period)
             .checked_mul(thread_count as u64)
             .ok_or(ModelsError::PeriodOverflowError)?
             .checked_add(self.thread as u64)
             .ok_or(ModelsError::PeriodOverflowError)?
-            .saturating_sub(s.thread as u64))
+            .checked_sub(s.thread as u64)
+            .ok_or(ModelsError::PeriodOverflowError)
     }
 }
@@ -1,8 +1,11 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::node::NodeId;
 use crate::slot::Slot;
+use massa_hash::Hash;
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::Formatter;
 
 /// execution statistics
@@ -18,6 +21,10 @@ pub struct ExecutionStats {
     pub final_executed_operations_count: usize,
     /// active execution cursor slot
     pub active_cursor: Slot,
+    /// final execution cursor slot
+    pub final_cursor: Slot,
+    /// hash of the final state at the final execution cursor slot
+    pub final_state_hash: Hash,
 }
 
 impl std::fmt::Display for ExecutionStats {
@@ -44,6 +51,8 @@ impl std::fmt::Display for ExecutionStats {
             self.final_executed_operations_count
         )?;
         writeln!(f, "\tActive cursor: {}", self.active_cursor)?;
+        writeln!(f, "\tFinal cursor: {}", self.final_cursor)?;
+        writeln!(f, "\tFinal state hash: {}", self.final_state_hash)?;
         Ok(())
     }
 }
@@ -61,6 +70,21 @@ pub struct NetworkStats {
     pub banned_peer_count: u64,
     /// active node count
     pub active_node_count: u64,
+    /// bytes exchanged with all the peers since the node started
+    pub transferred_bytes: TransferredBytes,
+    /// bytes exchanged with each active node
+    pub transferred_bytes_per_node: BTreeMap<NodeId, TransferredBytes>,
+    /// bytes exchanged per message type since the node started
+    pub transferred_bytes_per_message_type: BTreeMap<String, TransferredBytes>,
+}
+
+/// bytes sent to and received from peers
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransferredBytes {
+    /// bytes sent
+    pub sent: u64,
+    /// bytes received
+    pub received: u64,
 }
 
 impl std::fmt::Display for NetworkStats {
@@ -71,6 +95,18 @@ impl std::fmt::Display for NetworkStats {
         writeln!(f, "\tKnown peers: {}", self.known_peer_count)?;
         writeln!(f, "\tBanned peers: {}", self.banned_peer_count)?;
         writeln!(f, "\tActive nodes: {}", self.active_node_count)?;
+        writeln!(
+            f,
+            "\tBytes sent: {} / received: {}",
+            self.transferred_bytes.sent, self.transferred_bytes.received
+        )?;
+        for (message_type, bytes) in &self.transferred_bytes_per_message_type {
+            writeln!(
+                f,
+                "\t\t{}: sent {} / received {}",
+                message_type, bytes.sent, bytes.received
+            )?;
+        }
         Ok(())
     }
 }
@@ -127,3 +163,134 @@ impl std::fmt::Display for PoolStats {
         Ok(())
     }
 }
+
+/// Upper bounds (in milliseconds) of the buckets of a `LatencyHistogram`.
+/// The last bucket has no upper bound.
+pub const LATENCY_BUCKET_BOUNDS: [u64; 10] = [
+    250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 60_000, 120_000, 300_000,
+];
+
+/// Distribution of latencies
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyHistogram {
+    /// number of latencies per bucket, see `LATENCY_BUCKET_BOUNDS`
+    pub bucket_counts: [u64; LATENCY_BUCKET_BOUNDS.len() + 1],
+    /// total number of latencies
+    pub count: u64,
+    /// sum of the latencies
+    pub sum: MassaTime,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        LatencyHistogram {
+            bucket_counts: Default::default(),
+            count: 0,
+            sum: MassaTime::from(0),
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// Adds a latency to the distribution
+    pub fn record(&mut self, latency: MassaTime) {
+        let bucket = LATENCY_BUCKET_BOUNDS
+            .iter()
+            .position(|bound| latency.to_millis() <= *bound)
+            .unwrap_or(LATENCY_BUCKET_BOUNDS.len());
+        self.bucket_counts[bucket] = self.bucket_counts[bucket].saturating_add(1);
+        self.count = self.count.saturating_add(1);
+        self.sum = self.sum.saturating_add(latency);
+    }
+
+    /// Gets the mean latency, if any latency was recorded
+    pub fn mean(&self) -> Option<MassaTime> {
+        self.sum.checked_div_u64(self.count).ok()
+    }
+}
+
+impl std::fmt::Display for LatencyHistogram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(mean) = self.mean() {
+            write!(f, "{} ops, mean {} ms |", self.count, mean.to_millis())?;
+        }
+        for (bound, count) in LATENCY_BUCKET_BOUNDS.iter().zip(self.bucket_counts.iter()) {
+            write!(f, " <={}ms: {}", bound, count)?;
+        }
+        write!(
+            f,
+            " >{}ms: {}",
+            LATENCY_BUCKET_BOUNDS[LATENCY_BUCKET_BOUNDS.len() - 1],
+            self.bucket_counts[LATENCY_BUCKET_BOUNDS.len()]
+        )
+    }
+}
+
+/// Stage reached by an operation after its reception by the node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationStage {
+    /// the operation was admitted in the pool
+    PoolAdmission,
+    /// the operation was included in an executed block
+    BlockInclusion,
+    /// the operation was executed in a final block
+    FinalExecution,
+}
+
+/// Time from the reception of operations (API or gossip) to each stage, by operation type
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OperationLatencyStats {
+    /// latencies until pool admission
+    pub pool_admission: BTreeMap<String, LatencyHistogram>,
+    /// latencies until inclusion in an executed block
+    pub block_inclusion: BTreeMap<String, LatencyHistogram>,
+    /// latencies until execution in a final block
+    pub final_execution: BTreeMap<String, LatencyHistogram>,
+}
+
+impl OperationLatencyStats {
+    /// Records the latency of an operation of type `op_type` to reach `stage`
+    pub fn record(&mut self, stage: OperationStage, op_type: &str, latency: MassaTime) {
+        let histograms = match stage {
+            OperationStage::PoolAdmission => &mut self.pool_admission,
+            OperationStage::BlockInclusion => &mut self.block_inclusion,
+            OperationStage::FinalExecution => &mut self.final_execution,
+        };
+        histograms
+            .entry(op_type.to_string())
+            .or_default()
+            .record(latency);
+    }
+}
+
+impl std::fmt::Display for OperationLatencyStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Operation latency stats:")?;
+        for (stage, histograms) in [
+            ("Pool admission", &self.pool_admission),
+            ("Block inclusion", &self.block_inclusion),
+            ("Final execution", &self.final_execution),
+        ] {
+            writeln!(f, "\t{}:", stage)?;
+            for (op_type, histogram) in histograms {
+                writeln!(f, "\t\t{}: {}", op_type, histogram)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_latency_histogram() {
+    let mut histogram = LatencyHistogram::default();
+    assert_eq!(histogram.mean(), None);
+    histogram.record(MassaTime::from(100));
+    histogram.record(MassaTime::from(250));
+    histogram.record(MassaTime::from(400));
+    histogram.record(MassaTime::from(1_000_000));
+    assert_eq!(histogram.bucket_counts[0], 2);
+    assert_eq!(histogram.bucket_counts[1], 1);
+    assert_eq!(histogram.bucket_counts[LATENCY_BUCKET_BOUNDS.len()], 1);
+    assert_eq!(histogram.count, 4);
+    assert_eq!(histogram.mean(), Some(MassaTime::from(250_187)));
+}
@@ -1,3 +1,7 @@
+mod serialization_corpus;
+mod strategies;
 mod tools;
 
+pub use serialization_corpus::*;
+pub use strategies::*;
 pub use tools::*;
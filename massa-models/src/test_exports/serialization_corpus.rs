@@ -0,0 +1,71 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Golden byte vectors of serialized structures.
+//!
+//! Each vector pins the exact bytes of a value in a given format version. Decoding them in tests
+//! catches any change of a wire or bootstrap format that was not done through a version bump.
+
+use crate::serialization::FormatVersion;
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+
+/// Serialized bytes of a structure in a given format version
+pub struct GoldenVector {
+    /// name of the vector, shown on failure
+    pub name: &'static str,
+    /// format version the bytes were produced with
+    pub version: FormatVersion,
+    /// serialized bytes, including the format version prefix
+    pub bytes: Vec<u8>,
+}
+
+impl GoldenVector {
+    /// Creates a golden vector
+    pub fn new(name: &'static str, version: FormatVersion, bytes: Vec<u8>) -> Self {
+        Self {
+            name,
+            version,
+            bytes,
+        }
+    }
+}
+
+/// Checks that every vector is fully decoded by `deserializer`.
+///
+/// Vectors of `current_version` must also be re-encoded by `serializer` to the exact same bytes,
+/// while vectors of older versions only need to stay readable.
+pub fn assert_golden_vectors<T, S, D>(
+    serializer: &S,
+    deserializer: &D,
+    current_version: FormatVersion,
+    vectors: &[GoldenVector],
+) where
+    S: Serializer<T>,
+    D: Deserializer<T>,
+{
+    for vector in vectors {
+        let (rest, value) = deserializer
+            .deserialize::<DeserializeError>(&vector.bytes)
+            .unwrap_or_else(|err| {
+                panic!("golden vector {} failed to decode: {}", vector.name, err)
+            });
+        assert!(
+            rest.is_empty(),
+            "golden vector {} has {} trailing byte(s)",
+            vector.name,
+            rest.len()
+        );
+        if vector.version == current_version {
+            let mut bytes = Vec::new();
+            serializer
+                .serialize(&value, &mut bytes)
+                .unwrap_or_else(|err| {
+                    panic!("golden vector {} failed to re-encode: {}", vector.name, err)
+                });
+            assert_eq!(
+                bytes, vector.bytes,
+                "golden vector {} is not re-encoded identically",
+                vector.name
+            );
+        }
+    }
+}
@@ -0,0 +1,327 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Proptest strategies generating valid wire structures.
+//!
+//! The generated values respect the limits enforced by the deserializers built with the
+//! default configuration constants, so that serializing then deserializing them must succeed
+//! and give back the same bytes.
+
+use crate::address::Address;
+use crate::amount::Amount;
+use crate::block::{Block, BlockHeader, BlockHeaderSerializer, BlockId, BlockSerializer};
+use crate::block::{WrappedBlock, WrappedHeader};
+use crate::config::{ENDORSEMENT_COUNT, THREAD_COUNT};
+use crate::endorsement::{Endorsement, EndorsementSerializer, WrappedEndorsement};
+use crate::operation::{Operation, OperationSerializer, OperationType, WrappedOperation};
+use crate::slot::Slot;
+use crate::wrapped::WrappedContent;
+use massa_hash::Hash;
+use massa_signature::{KeyPair, SECRET_KEY_BYTES_SIZE};
+use proptest::collection::{btree_map, vec};
+use proptest::prelude::*;
+
+/// Maximum length of the generated byte vectors and strings
+const MAX_GENERATED_LENGTH: usize = 64;
+
+/// Key pair derived from random secret key bytes
+pub fn keypair() -> impl Strategy<Value = KeyPair> {
+    any::<[u8; SECRET_KEY_BYTES_SIZE]>().prop_map(|bytes| {
+        KeyPair::from_bytes(&bytes).expect("any secret key bytes make a valid key pair")
+    })
+}
+
+/// Hash of random bytes
+pub fn hash() -> impl Strategy<Value = Hash> {
+    any::<[u8; 32]>().prop_map(|bytes| Hash::compute_from(&bytes))
+}
+
+/// Block id of a random hash
+pub fn block_id() -> impl Strategy<Value = BlockId> {
+    hash().prop_map(BlockId)
+}
+
+/// Address of a random key pair
+pub fn address() -> impl Strategy<Value = Address> {
+    keypair().prop_map(|keypair| Address::from_public_key(&keypair.get_public_key()))
+}
+
+/// Any amount
+pub fn amount() -> impl Strategy<Value = Amount> {
+    any::<u64>().prop_map(Amount::from_raw)
+}
+
+/// Slot in one of the `THREAD_COUNT` threads
+pub fn slot() -> impl Strategy<Value = Slot> {
+    (any::<u64>(), 0..THREAD_COUNT).prop_map(|(period, thread)| Slot::new(period, thread))
+}
+
+/// Endorsement content
+pub fn endorsement() -> impl Strategy<Value = Endorsement> {
+    (slot(), 0..ENDORSEMENT_COUNT, block_id()).prop_map(|(slot, index, endorsed_block)| {
+        Endorsement {
+            slot,
+            index,
+            endorsed_block,
+        }
+    })
+}
+
+/// Endorsement signed by a random key pair
+pub fn wrapped_endorsement() -> impl Strategy<Value = WrappedEndorsement> {
+    (endorsement(), keypair()).prop_map(|(endorsement, keypair)| {
+        Endorsement::new_wrapped(endorsement, EndorsementSerializer::new(), &keypair)
+            .expect("could not sign endorsement")
+    })
+}
+
+/// Any operation type, with data sizes far below the configured limits
+pub fn operation_type() -> impl Strategy<Value = OperationType> {
+    let bytes = || vec(any::<u8>(), 0..MAX_GENERATED_LENGTH);
+    prop_oneof![
+        (address(), amount()).prop_map(|(recipient_address, amount)| {
+            OperationType::Transaction {
+                recipient_address,
+                amount,
+            }
+        }),
+        any::<u64>().prop_map(|roll_count| OperationType::RollBuy { roll_count }),
+        any::<u64>().prop_map(|roll_count| OperationType::RollSell { roll_count }),
+        (bytes(), any::<u64>(), btree_map(bytes(), bytes(), 0..8)).prop_map(
+            |(data, max_gas, datastore)| OperationType::ExecuteSC {
+                data,
+                max_gas,
+                datastore,
+            }
+        ),
+        (
+            address(),
+            "[a-zA-Z_]{0,64}",
+            bytes(),
+            any::<u64>(),
+            amount()
+        )
+            .prop_map(|(target_addr, target_func, param, max_gas, coins)| {
+                OperationType::CallSC {
+                    target_addr,
+                    target_func,
+                    param,
+                    max_gas,
+                    coins,
+                }
+            }),
+    ]
+}
+
+/// Operation signed by a random key pair
+pub fn wrapped_operation() -> impl Strategy<Value = WrappedOperation> {
+    (amount(), any::<u64>(), operation_type(), keypair()).prop_map(
+        |(fee, expire_period, op, keypair)| {
+            Operation::new_wrapped(
+                Operation {
+                    fee,
+                    expire_period,
+                    op,
+                },
+                OperationSerializer::new(),
+                &keypair,
+            )
+            .expect("could not sign operation")
+        },
+    )
+}
+
+/// Block header signed by a random key pair.
+///
+/// Genesis headers (without parents) have no endorsements. The endorsements of the other headers
+/// target the header slot and its parent in the same thread, as the light serialization
+/// of the endorsements inside a header does not carry these fields.
+pub fn wrapped_header() -> impl Strategy<Value = WrappedHeader> {
+    let genesis = (slot(), hash(), keypair()).prop_map(|(slot, operation_merkle_root, keypair)| {
+        BlockHeader::new_wrapped(
+            BlockHeader {
+                slot,
+                parents: Vec::new(),
+                operation_merkle_root,
+                endorsements: Vec::new(),
+            },
+            BlockHeaderSerializer::new(),
+            &keypair,
+        )
+        .expect("could not sign block header")
+    });
+    let regular = (
+        slot(),
+        vec(block_id(), THREAD_COUNT as usize),
+        hash(),
+        vec(
+            (0..ENDORSEMENT_COUNT, keypair()),
+            0..=ENDORSEMENT_COUNT as usize,
+        ),
+        keypair(),
+    )
+        .prop_map(
+            |(slot, parents, operation_merkle_root, endorsers, keypair)| {
+                let endorsed_block = parents[slot.thread as usize];
+                let endorsements = endorsers
+                    .into_iter()
+                    .map(|(index, endorser)| {
+                        Endorsement::new_wrapped(
+                            Endorsement {
+                                slot,
+                                index,
+                                endorsed_block,
+                            },
+                            EndorsementSerializer::new(),
+                            &endorser,
+                        )
+                        .expect("could not sign endorsement")
+                    })
+                    .collect();
+                BlockHeader::new_wrapped(
+                    BlockHeader {
+                        slot,
+                        parents,
+                        operation_merkle_root,
+                        endorsements,
+                    },
+                    BlockHeaderSerializer::new(),
+                    &keypair,
+                )
+                .expect("could not sign block header")
+            },
+        );
+    prop_oneof![1 => genesis, 4 => regular]
+}
+
+/// Block signed by a random key pair, listing the ids of random operations
+pub fn wrapped_block() -> impl Strategy<Value = WrappedBlock> {
+    (wrapped_header(), vec(wrapped_operation(), 0..8), keypair()).prop_map(
+        |(header, operations, keypair)| {
+            Block::new_wrapped(
+                Block {
+                    header,
+                    operations: operations.into_iter().map(|op| op.id).collect(),
+                },
+                BlockSerializer::new(),
+                &keypair,
+            )
+            .expect("could not sign block")
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::{BlockDeserializer, BlockHeaderDeserializer};
+    use crate::config::{
+        MAX_DATASTORE_VALUE_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_OPERATIONS_PER_BLOCK,
+        MAX_OPERATION_DATASTORE_ENTRY_COUNT, MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE,
+    };
+    use crate::endorsement::EndorsementDeserializer;
+    use crate::operation::OperationDeserializer;
+    use crate::wrapped::{Id, Wrapped, WrappedDeserializer, WrappedSerializer};
+    use massa_serialization::{DeserializeError, Deserializer, Serializer};
+
+    fn operation_deserializer() -> WrappedDeserializer<Operation, OperationDeserializer> {
+        WrappedDeserializer::new(OperationDeserializer::new(
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+            MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        ))
+    }
+
+    fn endorsement_deserializer() -> WrappedDeserializer<Endorsement, EndorsementDeserializer> {
+        WrappedDeserializer::new(EndorsementDeserializer::new(
+            THREAD_COUNT,
+            ENDORSEMENT_COUNT,
+        ))
+    }
+
+    fn header_deserializer() -> WrappedDeserializer<BlockHeader, BlockHeaderDeserializer> {
+        WrappedDeserializer::new(BlockHeaderDeserializer::new(
+            THREAD_COUNT,
+            ENDORSEMENT_COUNT,
+        ))
+    }
+
+    fn block_deserializer() -> WrappedDeserializer<Block, BlockDeserializer> {
+        WrappedDeserializer::new(BlockDeserializer::new(
+            THREAD_COUNT,
+            MAX_OPERATIONS_PER_BLOCK,
+            ENDORSEMENT_COUNT,
+        ))
+    }
+
+    /// Serializes `value`, deserializes it with `deserializer` and checks that the result
+    /// has the same id and is serialized to the same bytes
+    fn assert_round_trip<T, U, D>(value: &Wrapped<T, U>, deserializer: &D)
+    where
+        T: std::fmt::Display + WrappedContent,
+        U: Id + PartialEq + std::fmt::Debug,
+        D: Deserializer<Wrapped<T, U>>,
+    {
+        let mut bytes = Vec::new();
+        WrappedSerializer::new()
+            .serialize(value, &mut bytes)
+            .unwrap();
+        let (rest, deserialized) = deserializer
+            .deserialize::<DeserializeError>(&bytes)
+            .unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(deserialized.id, value.id);
+        let mut bytes_again = Vec::new();
+        WrappedSerializer::new()
+            .serialize(&deserialized, &mut bytes_again)
+            .unwrap();
+        assert_eq!(bytes_again, bytes);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn test_operation_round_trip(operation in wrapped_operation()) {
+            assert_round_trip(&operation, &operation_deserializer());
+        }
+
+        #[test]
+        fn test_endorsement_round_trip(endorsement in wrapped_endorsement()) {
+            assert_round_trip(&endorsement, &endorsement_deserializer());
+        }
+
+        #[test]
+        fn test_header_round_trip(header in wrapped_header()) {
+            assert_round_trip(&header, &header_deserializer());
+        }
+
+        #[test]
+        fn test_block_round_trip(block in wrapped_block()) {
+            assert_round_trip(&block, &block_deserializer());
+        }
+
+        #[test]
+        fn test_deserializers_do_not_panic(bytes in vec(any::<u8>(), 0..512)) {
+            let _ = Deserializer::<WrappedOperation>::deserialize::<DeserializeError>(
+                &operation_deserializer(),
+                &bytes,
+            );
+            let _ = Deserializer::<WrappedEndorsement>::deserialize::<DeserializeError>(
+                &endorsement_deserializer(),
+                &bytes,
+            );
+            let _ = Deserializer::<WrappedHeader>::deserialize::<DeserializeError>(
+                &header_deserializer(),
+                &bytes,
+            );
+            let _ = Deserializer::<WrappedBlock>::deserialize::<DeserializeError>(
+                &block_deserializer(),
+                &bytes,
+            );
+        }
+    }
+}
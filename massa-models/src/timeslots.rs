@@ -2,7 +2,7 @@
 
 //! warning: assumes `thread_count >= 1, t0_millis >= 1, t0_millis % thread_count == 0`
 
-use massa_time::MassaTime;
+use massa_time::{MassaClock, MassaTime};
 use std::convert::TryInto;
 
 use crate::{error::ModelsError, slot::Slot};
@@ -14,15 +14,8 @@ use crate::{error::ModelsError, slot::Slot};
 /// * `b`: ending slot (excluded)
 /// * `thread_count`: number of threads
 pub fn slot_count_in_range(a: Slot, b: Slot, thread_count: u8) -> Result<u64, ModelsError> {
-    b.period
-        .checked_sub(a.period)
-        .ok_or(ModelsError::TimeOverflowError)?
-        .checked_mul(thread_count as u64)
-        .ok_or(ModelsError::TimeOverflowError)?
-        .checked_add(b.thread as u64)
-        .ok_or(ModelsError::TimeOverflowError)?
-        .checked_sub(a.thread as u64)
-        .ok_or(ModelsError::TimeOverflowError)
+    b.slots_since(&a, thread_count)
+        .map_err(|_| ModelsError::TimeOverflowError)
 }
 
 /// Gets timestamp in milliseconds for given slot.
@@ -84,12 +77,14 @@ pub fn get_latest_block_slot_at_timestamp(
 /// * `thread_count`: number of threads.
 /// * `t0`: time in milliseconds between two periods in the same thread.
 /// * `genesis_timestamp`: when the blockclique first started, in milliseconds.
+/// * `clock`: source of the current time.
 pub fn get_current_latest_block_slot(
     thread_count: u8,
     t0: MassaTime,
     genesis_timestamp: MassaTime,
+    clock: &dyn MassaClock,
 ) -> Result<Option<Slot>, ModelsError> {
-    get_latest_block_slot_at_timestamp(thread_count, t0, genesis_timestamp, MassaTime::now()?)
+    get_latest_block_slot_at_timestamp(thread_count, t0, genesis_timestamp, clock.now()?)
 }
 
 /// Turns an `MassaTime` range [start, end) with optional start/end to a `Slot` range [start, end) with optional start/end
@@ -200,6 +195,106 @@ pub fn get_closest_slot_to_timestamp(
     }
 }
 
+/// Schedules the processing of slots as they happen.
+///
+/// Slot timestamps are shifted by a fixed `delay`, and each call to `SlotScheduler::next_slot`
+/// gives the next slot to process along with the time at which it should be processed:
+/// * slots that elapsed while the caller was busy (late wake-up) are skipped
+/// * a slot is never scheduled twice, even if the system clock is adjusted backwards
+#[derive(Debug, Clone)]
+pub struct SlotScheduler {
+    /// number of threads
+    thread_count: u8,
+    /// time between two periods in the same thread
+    t0: MassaTime,
+    /// genesis timestamp
+    genesis_timestamp: MassaTime,
+    /// delay by which slot timestamps are shifted
+    delay: MassaTime,
+    /// latest scheduled slot, if any
+    last_slot: Option<Slot>,
+}
+
+impl SlotScheduler {
+    /// Creates a new `SlotScheduler`
+    ///
+    /// # Arguments
+    /// * `thread_count`: number of threads.
+    /// * `t0`: time in milliseconds between two periods in the same thread.
+    /// * `genesis_timestamp`: when the blockclique first started, in milliseconds.
+    /// * `delay`: delay by which slot timestamps are shifted.
+    /// * `last_slot`: latest slot already processed, if any. Only later slots will be scheduled.
+    pub fn new(
+        thread_count: u8,
+        t0: MassaTime,
+        genesis_timestamp: MassaTime,
+        delay: MassaTime,
+        last_slot: Option<Slot>,
+    ) -> Self {
+        SlotScheduler {
+            thread_count,
+            t0,
+            genesis_timestamp,
+            delay,
+            last_slot,
+        }
+    }
+
+    /// Gets the latest scheduled slot, if any
+    pub fn get_last_slot(&self) -> Option<Slot> {
+        self.last_slot
+    }
+
+    /// Gets the timestamp of a slot, shifted by the delay
+    pub fn get_slot_timestamp(&self, slot: Slot) -> Result<MassaTime, ModelsError> {
+        get_block_slot_timestamp(self.thread_count, self.t0, self.genesis_timestamp, slot)?
+            .checked_add(self.delay)
+            .or(Err(ModelsError::TimeOverflowError))
+    }
+
+    /// Gets the latest slot whose shifted timestamp is before `timestamp` (inclusive), if any
+    pub fn get_latest_slot_at(&self, timestamp: MassaTime) -> Result<Option<Slot>, ModelsError> {
+        get_latest_block_slot_at_timestamp(
+            self.thread_count,
+            self.t0,
+            self.genesis_timestamp,
+            timestamp.saturating_sub(self.delay),
+        )
+    }
+
+    /// Schedules the next slot to process at time `timestamp`.
+    ///
+    /// # Returns
+    /// The scheduled slot and its shifted timestamp
+    pub fn next_slot_at(&mut self, timestamp: MassaTime) -> Result<(Slot, MassaTime), ModelsError> {
+        // take the closest slot to the current time, skipping the slots missed by a late wake-up
+        let mut slot = get_closest_slot_to_timestamp(
+            self.thread_count,
+            self.t0,
+            self.genesis_timestamp,
+            timestamp.saturating_sub(self.delay),
+        );
+
+        // protection against double scheduling on unexpected system clock adjustment
+        if let Some(last_slot) = self.last_slot {
+            if slot <= last_slot {
+                slot = last_slot.get_next_slot(self.thread_count)?;
+            }
+        }
+
+        self.last_slot = Some(slot);
+        Ok((slot, self.get_slot_timestamp(slot)?))
+    }
+
+    /// Schedules the next slot to process according to the current time.
+    ///
+    /// # Returns
+    /// The scheduled slot and its shifted timestamp
+    pub fn next_slot(&mut self) -> Result<(Slot, MassaTime), ModelsError> {
+        self.next_slot_at(MassaTime::now()?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -296,4 +391,45 @@ mod tests {
             get_closest_slot_to_timestamp(thread_count, t0, genesis_timestamp, 150.into());
         assert_eq!(out_slot, Slot::new(1, 2));
     }
+
+    #[test]
+    #[serial]
+    fn test_slot_scheduler() {
+        let thread_count = 3u8;
+        let t0: MassaTime = 30.into();
+        let genesis_timestamp: MassaTime = 100.into();
+        /* slots:   (0, 0)  (0, 1)  (0, 2)  (1, 0)  (1, 1)  (1, 2)  (2, 0)  (2, 1)  (2, 2)
+            time:    100      110     120    130      140    150     160     170     180
+        */
+        let mut scheduler = SlotScheduler::new(thread_count, t0, genesis_timestamp, 5.into(), None);
+
+        // before genesis, the first slot is scheduled
+        assert_eq!(
+            scheduler.next_slot_at(50.into()).unwrap(),
+            (Slot::new(0, 0), 105.into())
+        );
+
+        // slots missed because of a late wake-up are skipped
+        assert_eq!(
+            scheduler.next_slot_at(146.into()).unwrap(),
+            (Slot::new(1, 1), 145.into())
+        );
+
+        // a clock going backwards does not schedule a slot twice
+        assert_eq!(
+            scheduler.next_slot_at(120.into()).unwrap(),
+            (Slot::new(1, 2), 155.into())
+        );
+        assert_eq!(scheduler.get_last_slot(), Some(Slot::new(1, 2)));
+
+        // the delay is taken into account in the latest slot
+        assert_eq!(
+            scheduler.get_latest_slot_at(114.into()).unwrap(),
+            Some(Slot::new(0, 0))
+        );
+        assert_eq!(
+            scheduler.get_latest_slot_at(115.into()).unwrap(),
+            Some(Slot::new(0, 1))
+        );
+    }
 }
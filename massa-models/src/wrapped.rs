@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use crate::{address::Address, error::ModelsError};
+use crate::{address::Address, error::ModelsError, id_hash::compute_id_hash};
 use massa_hash::Hash;
 use massa_serialization::{Deserializer, SerializeError, Serializer};
 use massa_signature::{
@@ -38,6 +38,8 @@ where
 
 /// Used by signed structure
 pub trait Id {
+    /// Domain separation prefix used to hash the id, see `id_hash`
+    const HASH_DOMAIN: &'static [u8];
     /// New id from hash
     fn new(hash: Hash) -> Self;
     /// Get a reference to the underlying hash
@@ -61,7 +63,7 @@ where
         let public_key = keypair.get_public_key();
         hash_data.extend(public_key.to_bytes());
         hash_data.extend(content_serialized.clone());
-        let hash = Hash::compute_from(&hash_data);
+        let hash = compute_id_hash(U::HASH_DOMAIN, &hash_data);
         let creator_address = Address::from_public_key(&public_key);
         Ok(Wrapped {
             signature: keypair.sign(&hash)?,
@@ -137,7 +139,7 @@ where
                 creator_public_key,
                 creator_address,
                 serialized_data: content_serialized.to_vec(),
-                id: U::new(Hash::compute_from(&serialized_full_data)),
+                id: U::new(compute_id_hash(U::HASH_DOMAIN, &serialized_full_data)),
             },
         ))
     }
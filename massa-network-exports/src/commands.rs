@@ -69,7 +69,7 @@
 //! Look at `massa-protocol-worker/src/node-info.rs` to look further how we
 //! remember which node know what.
 
-use crate::{BootstrapPeers, ConnectionClosureReason, Peers};
+use crate::{BootstrapPeers, ConnectionClosureReason, NetworkError, Peers};
 use massa_models::{
     block::{BlockId, WrappedHeader},
     composite::PubkeySig,
@@ -79,7 +79,7 @@ use massa_models::{
     stats::NetworkStats,
 };
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, net::IpAddr};
+use std::{collections::HashMap, net::IpAddr, path::PathBuf};
 use tokio::sync::oneshot;
 
 /// network command
@@ -231,6 +231,20 @@ pub enum NetworkCommand {
     Whitelist(Vec<IpAddr>),
     /// Remove from whitelist a list of `IpAddr`
     RemoveFromWhitelist(Vec<IpAddr>),
+    /// Write the peer database to a file
+    ExportPeers {
+        /// path of the written file
+        path: PathBuf,
+        /// response channel
+        response_tx: oneshot::Sender<Result<(), NetworkError>>,
+    },
+    /// Merge the peers of a file with the peer database
+    ImportPeers {
+        /// path of the imported file
+        path: PathBuf,
+        /// response channel, receiving the number of previously unknown peers
+        response_tx: oneshot::Sender<Result<usize, NetworkError>>,
+    },
 }
 
 /// A node replied with info about a block.
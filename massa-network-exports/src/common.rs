@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_hash::Hash;
 use serde::{Deserialize, Serialize};
 
 /// A unique connection id for a node
@@ -23,4 +24,21 @@ pub enum ConnectionClosureReason {
     Failed,
     /// Connection closed after node ban
     Banned,
+    /// Connection refused during the handshake because the peer runs an incompatible version or another network
+    Incompatible,
+}
+
+/// Identifies the network a node belongs to, checked during handshakes
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NetworkId {
+    /// chain identifier
+    pub chain_id: u64,
+    /// hash of the genesis blocks and genesis timestamp
+    pub genesis_hash: Hash,
+}
+
+impl std::fmt::Display for NetworkId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "chain {} (genesis {})", self.chain_id, self.genesis_hash)
+    }
 }
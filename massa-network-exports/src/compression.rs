@@ -0,0 +1,40 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use serde::Deserialize;
+
+/// Compression algorithms that can be negotiated during the handshake to compress messages on the wire
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    /// `zstd`: better compression ratio
+    Zstd,
+    /// `lz4`: faster compression
+    Lz4,
+}
+
+impl CompressionAlgorithm {
+    /// Flag identifying the algorithm in a compressed message.
+    /// It is also the bit of the algorithm in the compression capabilities exchanged during the handshake.
+    pub fn flag(&self) -> u8 {
+        match self {
+            CompressionAlgorithm::Zstd => 0b01,
+            CompressionAlgorithm::Lz4 => 0b10,
+        }
+    }
+
+    /// Gets the algorithm identified by a message flag, if any
+    pub fn from_flag(flag: u8) -> Option<Self> {
+        match flag {
+            0b01 => Some(CompressionAlgorithm::Zstd),
+            0b10 => Some(CompressionAlgorithm::Lz4),
+            _ => None,
+        }
+    }
+
+    /// Compression capabilities advertised during the handshake for a list of algorithms
+    pub fn capabilities(algorithms: &[CompressionAlgorithm]) -> u8 {
+        algorithms
+            .iter()
+            .fold(0, |capabilities, algorithm| capabilities | algorithm.flag())
+    }
+}
@@ -0,0 +1,23 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use serde::Deserialize;
+
+/// Encryption of the messages exchanged with the peers, negotiated during the handshake
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EncryptionMode {
+    /// messages are never encrypted
+    #[default]
+    Disabled,
+    /// messages are encrypted with the peers supporting encryption, and sent in clear to the others
+    Enabled,
+    /// messages are always encrypted: the peers not supporting encryption are refused
+    Required,
+}
+
+impl EncryptionMode {
+    /// Whether encryption is offered to the peers during the handshake
+    pub fn is_offered(&self) -> bool {
+        !matches!(self, EncryptionMode::Disabled)
+    }
+}
@@ -1,6 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::{peers::PeerType, ConnectionId};
+use crate::{peers::PeerType, ConnectionId, NetworkId};
 use displaydoc::Display;
 use massa_models::error::ModelsError;
 use massa_serialization::SerializeError;
@@ -55,6 +55,8 @@ pub enum NetworkError {
     SerializeError(#[from] SerializeError),
     /// container inconsistency error: {0}
     ContainerInconsistencyError(String),
+    /// seed peers error: {0}
+    SeedPeersError(String),
 }
 
 /// Handshake error type
@@ -74,8 +76,16 @@ pub enum HandshakeErrorType {
     HandshakeInvalidSignature,
     /// Incompatible version
     IncompatibleVersion,
+    /// Peer belongs to another network: {0}
+    NetworkIdMismatch(NetworkId),
+    /// Peer did not announce its network
+    MissingNetworkId,
     /// Outgoing connection returned a bootstrapable peer list: {0:?}
     PeerListReceived(Vec<IpAddr>),
+    /// Peer does not support the encryption we require
+    EncryptionRequired,
+    /// Invalid encryption key
+    InvalidEncryptionKey,
 }
 
 /// return handshake error
@@ -14,17 +14,25 @@ mod types {
 }
 #[cfg(not(feature = "testing"))]
 mod types {
+    use crate::quic::{self, IncomingConnection};
+    use crate::TransportType;
     use massa_time::MassaTime;
+    use quinn::Endpoint;
     use std::{io, net::SocketAddr};
     use tokio::{
+        io::{AsyncRead, AsyncWrite},
         net::{TcpListener, TcpStream},
+        sync::mpsc,
         time::timeout,
     };
 
-    /// read half of the TCP stream
-    pub type ReadHalf = tokio::net::tcp::OwnedReadHalf;
-    /// Write half of the TCP stream
-    pub type WriteHalf = tokio::net::tcp::OwnedWriteHalf;
+    /// Size of the channel of the incoming QUIC connections waiting to be accepted
+    const QUIC_INCOMING_CHANNEL_SIZE: usize = 64;
+
+    /// read half of the TCP stream or QUIC stream
+    pub type ReadHalf = Box<dyn AsyncRead + Send + Unpin>;
+    /// Write half of the TCP stream or QUIC stream
+    pub type WriteHalf = Box<dyn AsyncWrite + Send + Unpin>;
     /// Connection listener
     pub type Listener = DefaultListener;
     /// Connection establisher
@@ -32,37 +40,66 @@ mod types {
 
     /// The listener we are using
     #[derive(Debug)]
-    pub struct DefaultListener(TcpListener);
+    pub struct DefaultListener {
+        tcp: TcpListener,
+        /// connections accepted by the QUIC endpoint, if QUIC is enabled
+        quic_rx: Option<mpsc::Receiver<IncomingConnection>>,
+    }
 
     impl DefaultListener {
         /// Accepts a new incoming connection from this listener.
         pub async fn accept(&mut self) -> io::Result<(ReadHalf, WriteHalf, SocketAddr)> {
             // accept
-            let (sock, mut remote_addr) = self.0.accept().await?;
-            // normalize IP
-            remote_addr.set_ip(remote_addr.ip().to_canonical());
-            // split into read half and write half
-            let (read_half, write_half) = sock.into_split();
-            Ok((read_half, write_half, remote_addr))
+            let Some(quic_rx) = &mut self.quic_rx else {
+                return accept_tcp(&self.tcp).await;
+            };
+            tokio::select! {
+                res = accept_tcp(&self.tcp) => res,
+                Some(connection) = quic_rx.recv() => Ok(connection),
+            }
         }
     }
 
+    /// Accepts a new incoming TCP connection
+    async fn accept_tcp(listener: &TcpListener) -> io::Result<(ReadHalf, WriteHalf, SocketAddr)> {
+        let (sock, mut remote_addr) = listener.accept().await?;
+        // normalize IP
+        remote_addr.set_ip(remote_addr.ip().to_canonical());
+        // split into read half and write half
+        let (read_half, write_half) = sock.into_split();
+        Ok((Box::new(read_half), Box::new(write_half), remote_addr))
+    }
+
     /// Initiates a connection with given timeout in milliseconds
     #[derive(Debug)]
-    pub struct DefaultConnector(MassaTime);
+    pub struct DefaultConnector {
+        timeout_duration: MassaTime,
+        /// QUIC endpoint, if QUIC is enabled
+        quic: Option<Endpoint>,
+    }
 
     impl DefaultConnector {
         /// Tries to connect to a address
         ///
         /// # Argument
         /// * `addr`: `SocketAddr` we are trying to connect to.
-        pub async fn connect(&mut self, addr: SocketAddr) -> io::Result<(ReadHalf, WriteHalf)> {
-            match timeout(self.0.to_duration(), TcpStream::connect(addr)).await {
-                Ok(Ok(sock)) => {
-                    let (reader, writer) = sock.into_split();
-                    Ok((reader, writer))
+        /// * `transport`: transport of the connection, TCP is used if QUIC is not enabled
+        pub async fn connect(
+            &mut self,
+            addr: SocketAddr,
+            transport: TransportType,
+        ) -> io::Result<(ReadHalf, WriteHalf)> {
+            let connection = async {
+                match (transport, &self.quic) {
+                    (TransportType::Quic, Some(endpoint)) => quic::connect(endpoint, addr).await,
+                    _ => {
+                        let (reader, writer) = TcpStream::connect(addr).await?.into_split();
+                        Ok((Box::new(reader) as ReadHalf, Box::new(writer) as WriteHalf))
+                    }
                 }
-                Ok(Err(e)) => Err(e),
+            };
+            match timeout(self.timeout_duration.to_duration(), connection).await {
+                Ok(res) => res,
                 Err(e) => Err(io::Error::new(io::ErrorKind::TimedOut, e)),
             }
         }
@@ -70,20 +107,38 @@ mod types {
 
     /// Establishes a connection
     #[derive(Debug)]
-    pub struct DefaultEstablisher;
+    pub struct DefaultEstablisher {
+        /// QUIC endpoint, created with the listener if QUIC is enabled
+        quic: Option<Endpoint>,
+    }
 
     impl DefaultEstablisher {
         /// Creates an Establisher.
         pub fn new() -> Self {
-            DefaultEstablisher {}
+            DefaultEstablisher { quic: None }
         }
 
         /// Gets the associated listener
         ///
         /// # Argument
         /// * `addr`: `SocketAddr` we want to bind to.
-        pub async fn get_listener(&mut self, addr: SocketAddr) -> io::Result<DefaultListener> {
-            Ok(DefaultListener(TcpListener::bind(addr).await?))
+        /// * `quic_enabled`: also accept QUIC connections on the UDP port of `addr`
+        pub async fn get_listener(
+            &mut self,
+            addr: SocketAddr,
+            quic_enabled: bool,
+        ) -> io::Result<DefaultListener> {
+            let tcp = TcpListener::bind(addr).await?;
+            let quic_rx = if quic_enabled {
+                let endpoint = quic::endpoint(addr)?;
+                let (quic_tx, quic_rx) = mpsc::channel(QUIC_INCOMING_CHANNEL_SIZE);
+                tokio::spawn(quic::accept_loop(endpoint.clone(), quic_tx));
+                self.quic = Some(endpoint);
+                Some(quic_rx)
+            } else {
+                None
+            };
+            Ok(DefaultListener { tcp, quic_rx })
         }
 
         /// Get the connector with associated timeout
@@ -94,7 +149,10 @@ mod types {
             &mut self,
             timeout_duration: MassaTime,
         ) -> io::Result<DefaultConnector> {
-            Ok(DefaultConnector(timeout_duration))
+            Ok(DefaultConnector {
+                timeout_duration,
+                quic: self.quic.clone(),
+            })
         }
     }
 
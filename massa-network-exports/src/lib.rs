@@ -9,7 +9,9 @@ pub use commands::{
     NodeCommand, NodeEvent, NodeEventType,
 };
 
-pub use common::{ConnectionClosureReason, ConnectionId};
+pub use common::{ConnectionClosureReason, ConnectionId, NetworkId};
+pub use compression::CompressionAlgorithm;
+pub use encryption::EncryptionMode;
 pub use error::{HandshakeErrorType, NetworkConnectionErrorType, NetworkError};
 pub use establisher::{Establisher, Listener, ReadHalf, WriteHalf};
 pub use network_controller::{NetworkCommandSender, NetworkEventReceiver, NetworkManager};
@@ -17,14 +19,24 @@ pub use peers::{
     BootstrapPeers, BootstrapPeersDeserializer, BootstrapPeersSerializer, ConnectionCount, Peer,
     PeerInfo, PeerType, Peers,
 };
-pub use settings::NetworkConfig;
+pub use settings::{MessageWriteTimeouts, NetworkConfig};
+pub use transport::TransportType;
 
 mod commands;
 mod common;
+mod compression;
+mod encryption;
 mod error;
 mod establisher;
 mod network_controller;
 mod peers;
+#[cfg(not(feature = "testing"))]
+mod quic;
+mod transport;
+
+// the QUIC transport is replaced by the mock establisher when testing
+#[cfg(feature = "testing")]
+use {quinn as _, rcgen as _, rustls as _};
 
 /// network settings
 pub mod settings;
@@ -16,6 +16,7 @@ use massa_models::{
 use std::{
     collections::{HashMap, VecDeque},
     net::IpAddr,
+    path::PathBuf,
 };
 use tokio::{
     sync::{
@@ -69,6 +70,31 @@ impl NetworkCommandSender {
         Ok(())
     }
 
+    /// write the peer database to a file
+    pub async fn export_peers(&self, path: PathBuf) -> Result<(), NetworkError> {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.0
+            .send(NetworkCommand::ExportPeers { path, response_tx })
+            .await
+            .map_err(|_| NetworkError::ChannelError("could not send ExportPeers command".into()))?;
+        response_rx.await.map_err(|_| {
+            NetworkError::ChannelError("could not send ExportPeers response upstream".into())
+        })?
+    }
+
+    /// merge the peers of a file with the peer database,
+    /// returns the number of previously unknown peers
+    pub async fn import_peers(&self, path: PathBuf) -> Result<usize, NetworkError> {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.0
+            .send(NetworkCommand::ImportPeers { path, response_tx })
+            .await
+            .map_err(|_| NetworkError::ChannelError("could not send ImportPeers command".into()))?;
+        response_rx.await.map_err(|_| {
+            NetworkError::ChannelError("could not send ImportPeers response upstream".into())
+        })?
+    }
+
     /// remove from banned node(s) by id(s)
     pub async fn node_unban_by_ids(&self, ids: Vec<NodeId>) -> Result<(), NetworkError> {
         self.0
@@ -299,6 +325,12 @@ pub struct NetworkManager {
 }
 
 impl NetworkManager {
+    /// Returns true if the network worker task ended.
+    /// Before `stop` is called, this only happens if the worker crashed.
+    pub fn is_finished(&self) -> bool {
+        self.join_handle.is_finished()
+    }
+
     /// stop network
     pub async fn stop(
         self,
@@ -202,8 +202,30 @@ pub struct PeerInfo {
     /// Isn't dump into peer file.
     #[serde(default = "usize::default")]
     pub active_in_connections: usize,
+    /// Number of successful outgoing connection attempts
+    #[serde(default)]
+    pub connection_successes: u64,
+    /// Number of failed outgoing connection attempts
+    #[serde(default)]
+    pub connection_failures: u64,
+    /// Number of failures since the peer was last alive, used to back off retries
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    /// Smoothed time in milliseconds to establish an outgoing connection with that peer
+    #[serde(default)]
+    pub latency: Option<MassaTime>,
+    /// Time in milliseconds when the current outgoing connection attempt started.
+    /// Isn't dump into peer file.
+    #[serde(skip)]
+    pub last_attempt: Option<MassaTime>,
+    /// Whether the peer accepts QUIC connections, as announced during the handshake or set in the peers file
+    #[serde(default)]
+    pub quic: bool,
 }
 
+/// Maximum exponent of the retry backoff: a failing peer is retried at most every `wakeup_interval * 2^MAX_BACKOFF_EXPONENT`
+const MAX_BACKOFF_EXPONENT: u32 = 6;
+
 impl PeerInfo {
     /// Cleans up the `PeerInfo` by normalizing the IP address
     /// and resetting active connection counts.
@@ -215,6 +237,7 @@ impl PeerInfo {
         self.active_out_connection_attempts = 0;
         self.active_out_connections = 0;
         self.active_in_connections = 0;
+        self.last_attempt = None;
     }
 
     /// Returns true if there is at least one connection attempt /
@@ -244,10 +267,17 @@ impl PeerInfo {
             active_in_connections: 0,
             peer_type: Default::default(),
             banned: false,
+            connection_successes: 0,
+            connection_failures: 0,
+            consecutive_failures: 0,
+            latency: None,
+            last_attempt: None,
+            quic: false,
         }
     }
 
-    /// peer is ready to be retried, enough time has elapsed since last failure
+    /// peer is ready to be retried, enough time has elapsed since last failure.
+    /// The waiting time doubles with each consecutive failure, up to `wakeup_interval * 2^MAX_BACKOFF_EXPONENT`.
     pub fn is_peer_ready(&self, wakeup_interval: MassaTime, now: MassaTime) -> bool {
         if let Some(last_failure) = self.last_failure {
             if let Some(last_alive) = self.last_alive {
@@ -255,13 +285,51 @@ impl PeerInfo {
                     return true;
                 }
             }
-            return now
-                .saturating_sub(last_failure)
-                .saturating_sub(wakeup_interval)
+            let backoff = wakeup_interval.saturating_mul(
+                1u64 << self
+                    .consecutive_failures
+                    .saturating_sub(1)
+                    .min(MAX_BACKOFF_EXPONENT),
+            );
+            return now.saturating_sub(last_failure).saturating_sub(backoff)
                 > MassaTime::from_millis(0u64);
         }
         true
     }
+
+    /// Records a successful outgoing connection established at `now`
+    pub fn record_connection_success(&mut self, now: MassaTime) {
+        self.connection_successes = self.connection_successes.saturating_add(1);
+        if let Some(start) = self.last_attempt.take() {
+            let elapsed = now.saturating_sub(start);
+            // exponential moving average giving a weight of 1/4 to the new sample
+            self.latency = Some(match self.latency {
+                Some(latency) => MassaTime::from_millis(
+                    (latency.to_millis().saturating_mul(3) + elapsed.to_millis()) / 4,
+                ),
+                None => elapsed,
+            });
+        }
+    }
+
+    /// Records a failed outgoing connection attempt
+    pub fn record_connection_failure(&mut self) {
+        self.connection_failures = self.connection_failures.saturating_add(1);
+        self.last_attempt = None;
+    }
+
+    /// Score used to rank the peers we try to connect to, higher is better.
+    ///
+    /// It is the smoothed connection success rate, divided by the connection latency in seconds plus one.
+    /// Unknown peers get a success rate of 1/2 and no latency penalty.
+    pub fn score(&self) -> f64 {
+        let success_rate = (self.connection_successes as f64 + 1.0)
+            / ((self.connection_successes + self.connection_failures) as f64 + 2.0);
+        let latency_secs = self
+            .latency
+            .map_or(0.0, |latency| latency.to_millis() as f64 / 1000.0);
+        success_rate / (1.0 + latency_secs)
+    }
 }
 
 /// Connection count for a category
@@ -0,0 +1,132 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! QUIC transport.
+//!
+//! Each connection carries a single bidirectional stream, opened by the connecting node.
+//! The TLS certificates are self-signed and not checked: the nodes authenticate each other
+//! with their keypairs during the handshake.
+
+use crate::establisher::{ReadHalf, WriteHalf};
+use quinn::{ClientConfig, Connecting, Endpoint, ServerConfig, TransportConfig};
+use std::{
+    io,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tokio::{
+    sync::{mpsc, Semaphore},
+    time::timeout,
+};
+
+/// Server name announced in the TLS handshake of the QUIC connections
+const SERVER_NAME: &str = "massa";
+
+/// Interval of the keep-alive packets preventing idle connections from timing out
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Time given to an incoming connection to complete its QUIC handshake and open its stream
+const ACCEPT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum number of incoming connections completing their QUIC handshake at the same time
+const MAX_PENDING_ACCEPTS: usize = 64;
+
+/// Incoming connection: stream halves and remote address
+pub(crate) type IncomingConnection = (ReadHalf, WriteHalf, SocketAddr);
+
+fn other_error<E: std::error::Error + Send + Sync + 'static>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+fn transport_config() -> Arc<TransportConfig> {
+    let mut transport = TransportConfig::default();
+    transport.keep_alive_interval(Some(KEEP_ALIVE_INTERVAL));
+    Arc::new(transport)
+}
+
+/// Creates a QUIC endpoint accepting connections on `addr`, also used to connect to the peers
+pub(crate) fn endpoint(addr: SocketAddr) -> io::Result<Endpoint> {
+    let cert = rcgen::generate_simple_self_signed(vec![SERVER_NAME.into()]).map_err(other_error)?;
+    let key = rustls::PrivateKey(cert.serialize_private_key_der());
+    let cert = rustls::Certificate(cert.serialize_der().map_err(other_error)?);
+    let mut server_config = ServerConfig::with_single_cert(vec![cert], key).map_err(other_error)?;
+    server_config.transport = transport_config();
+    let mut endpoint = Endpoint::server(server_config, addr)?;
+
+    let crypto = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(SkipServerVerification))
+        .with_no_client_auth();
+    let mut client_config = ClientConfig::new(Arc::new(crypto));
+    client_config.transport_config(transport_config());
+    endpoint.set_default_client_config(client_config);
+    Ok(endpoint)
+}
+
+/// Accepts the incoming connections of `endpoint` and sends them to `connection_tx`.
+/// Stops when the endpoint is closed or the receiver dropped.
+///
+/// At most `MAX_PENDING_ACCEPTS` connections are accepted at the same time,
+/// the connections arriving while all the slots are taken are dropped.
+pub(crate) async fn accept_loop(
+    endpoint: Endpoint,
+    connection_tx: mpsc::Sender<IncomingConnection>,
+) {
+    let pending_accepts = Arc::new(Semaphore::new(MAX_PENDING_ACCEPTS));
+    while let Some(connecting) = endpoint.accept().await {
+        if connection_tx.is_closed() {
+            break;
+        }
+        let Ok(permit) = pending_accepts.clone().try_acquire_owned() else {
+            continue;
+        };
+        // the QUIC handshake of a connection must not delay the other ones
+        let connection_tx = connection_tx.clone();
+        tokio::spawn(async move {
+            let _permit = permit;
+            if let Ok(Ok(connection)) = timeout(ACCEPT_TIMEOUT, accept(connecting)).await {
+                let _ = connection_tx.send(connection).await;
+            }
+        });
+    }
+}
+
+/// Completes an incoming connection and waits for the stream opened by the peer
+async fn accept(connecting: Connecting) -> io::Result<IncomingConnection> {
+    let connection = connecting.await.map_err(other_error)?;
+    let mut remote_addr = connection.remote_address();
+    remote_addr.set_ip(remote_addr.ip().to_canonical());
+    let (send, recv) = connection.accept_bi().await.map_err(other_error)?;
+    Ok((Box::new(recv), Box::new(send), remote_addr))
+}
+
+/// Connects to `addr` and opens the stream of the connection
+pub(crate) async fn connect(
+    endpoint: &Endpoint,
+    addr: SocketAddr,
+) -> io::Result<(ReadHalf, WriteHalf)> {
+    let connection = endpoint
+        .connect(addr, SERVER_NAME)
+        .map_err(other_error)?
+        .await
+        .map_err(other_error)?;
+    let (send, recv) = connection.open_bi().await.map_err(other_error)?;
+    Ok((Box::new(recv), Box::new(send)))
+}
+
+/// Accepts any server certificate: the identity of the peer is checked during the handshake
+struct SkipServerVerification;
+
+impl rustls::client::ServerCertVerifier for SkipServerVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
@@ -1,11 +1,15 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use enum_map::EnumMap;
+use massa_signature::PublicKey;
 use massa_time::MassaTime;
 use serde::Deserialize;
 use std::net::{IpAddr, SocketAddr};
 
-use crate::peers::PeerType;
+use crate::{
+    common::NetworkId, compression::CompressionAlgorithm, encryption::EncryptionMode,
+    peers::PeerType,
+};
 
 /// Network configuration
 #[derive(Debug, Deserialize, Clone)]
@@ -14,6 +18,8 @@ pub struct NetworkConfig {
     pub bind: SocketAddr,
     /// Our own IP if it is routable, else None.
     pub routable_ip: Option<IpAddr>,
+    /// If `routable_ip` is None, number of distinct peers that must observe the same IP for us to advertise it as routable (0 to disable detection)
+    pub routable_ip_detection_votes: usize,
     /// Protocol port
     pub protocol_port: u16,
     /// Time interval spent waiting for a response from a peer.
@@ -26,6 +32,8 @@ pub struct NetworkConfig {
     pub initial_peers_file: std::path::PathBuf,
     /// Path to the file containing known peers.
     pub peers_file: std::path::PathBuf,
+    /// HTTPS URLs of signed seed peer lists fetched at startup, with the public key of their signer
+    pub seed_peer_lists: Vec<(String, PublicKey)>,
     /// Path to the file containing our keypair
     pub keypair_file: std::path::PathBuf,
     /// Configuration for `PeerType` connections
@@ -38,8 +46,6 @@ pub struct NetworkConfig {
     pub max_banned_peers: usize,
     /// Peer database is dumped every `peers_file_dump_interval` in milliseconds
     pub peers_file_dump_interval: MassaTime,
-    /// After `message_timeout` milliseconds we are no longer waiting on handshake message
-    pub message_timeout: MassaTime,
     /// Every `ask_peer_list_interval` in milliseconds we ask every one for its advertisable peers list.
     pub ask_peer_list_interval: MassaTime,
     /// Max wait time for sending a Node event.
@@ -58,6 +64,8 @@ pub struct NetworkConfig {
     pub max_bytes_read: f64,
     /// Write limitation for a connection in bytes per seconds
     pub max_bytes_write: f64,
+    /// Max size in bytes of the messages waiting to be written to a node
+    pub max_node_outstanding_bytes: u64,
     /// Max number ids in ask blocks message
     pub max_ask_blocks: u32,
     /// Max operations per block
@@ -72,6 +80,16 @@ pub struct NetworkConfig {
     pub max_endorsements_per_message: u32,
     /// Max message size
     pub max_message_size: u32,
+    /// Compression algorithms offered during handshakes, by order of preference (empty to disable compression)
+    pub message_compression: Vec<CompressionAlgorithm>,
+    /// Messages smaller than this size in bytes are sent uncompressed
+    pub min_compressed_message_size: u32,
+    /// Encryption of the messages exchanged with the peers
+    pub message_encryption: EncryptionMode,
+    /// Accept QUIC connections on the UDP port of `bind`, and connect over QUIC to the peers accepting them
+    pub quic_enabled: bool,
+    /// Network we belong to, peers of other networks are refused during handshakes
+    pub network_id: NetworkId,
     /// Maximum length of a datastore value
     pub max_datastore_value_length: u64,
     /// Maximum entry in an operation datastore
@@ -106,14 +124,29 @@ pub struct PeerTypeConnectionConfig {
     pub max_out_attempts: usize,
 }
 
+/// Write timeouts of the messages sent to the nodes, per class of message.
+/// Part of the protocol configuration.
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct MessageWriteTimeouts {
+    /// Write timeout in milliseconds for handshake-sized control messages (peer lists, asks)
+    pub handshake: MassaTime,
+    /// Write timeout in milliseconds for block header messages
+    pub header: MassaTime,
+    /// Write timeout in milliseconds for block body messages (replies to block asks)
+    pub block: MassaTime,
+    /// Write timeout in milliseconds for bootstrap-sized messages (operation and endorsement batches)
+    pub large: MassaTime,
+}
+
 /// setting tests
 #[cfg(feature = "testing")]
 pub mod tests {
-    use crate::NetworkConfig;
     use crate::{test_exports::tools::get_temp_keypair_file, PeerType};
+    use crate::{MessageWriteTimeouts, NetworkConfig, NetworkId};
     use enum_map::enum_map;
+    use massa_hash::Hash;
     use massa_models::config::{
-        ENDORSEMENT_COUNT, MAX_ADVERTISE_LENGTH, MAX_ASK_BLOCKS_PER_MESSAGE,
+        CHAIN_ID, ENDORSEMENT_COUNT, MAX_ADVERTISE_LENGTH, MAX_ASK_BLOCKS_PER_MESSAGE,
         MAX_DATASTORE_VALUE_LENGTH, MAX_ENDORSEMENTS_PER_MESSAGE, MAX_FUNCTION_NAME_LENGTH,
         MAX_MESSAGE_SIZE, MAX_OPERATIONS_PER_MESSAGE, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
         MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH,
@@ -125,6 +158,25 @@ pub mod tests {
 
     use super::PeerTypeConnectionConfig;
 
+    /// network id shared by all the nodes of the tests
+    pub fn default_testing_network_id() -> NetworkId {
+        NetworkId {
+            chain_id: CHAIN_ID,
+            genesis_hash: Hash::compute_from(b"genesis"),
+        }
+    }
+
+    impl Default for MessageWriteTimeouts {
+        fn default() -> Self {
+            MessageWriteTimeouts {
+                handshake: MassaTime::from_millis(5000u64),
+                header: MassaTime::from_millis(5000u64),
+                block: MassaTime::from_millis(20000u64),
+                large: MassaTime::from_millis(20000u64),
+            }
+        }
+    }
+
     impl Default for NetworkConfig {
         fn default() -> Self {
             let peer_types_config = enum_map! {
@@ -147,6 +199,7 @@ pub mod tests {
             NetworkConfig {
                 bind: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080),
                 routable_ip: Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
+                routable_ip_detection_votes: 0,
                 protocol_port: 0,
                 connect_timeout: MassaTime::from_millis(180_000),
                 wakeup_interval: MassaTime::from_millis(10_000),
@@ -155,19 +208,20 @@ pub mod tests {
                 max_idle_peers: 3,
                 max_banned_peers: 3,
                 peers_file_dump_interval: MassaTime::from_millis(10_000),
-                message_timeout: MassaTime::from_millis(5000u64),
                 ask_peer_list_interval: MassaTime::from_millis(50000u64),
                 keypair_file: std::path::PathBuf::new(),
                 max_send_wait_node_event: MassaTime::from_millis(100),
                 max_send_wait_network_event: MassaTime::from_millis(100),
                 ban_timeout: MassaTime::from_millis(100_000_000),
                 initial_peers_file: std::path::PathBuf::new(),
+                seed_peer_lists: Vec::new(),
                 peer_list_send_timeout: MassaTime::from_millis(500),
                 max_in_connection_overflow: 2,
                 peer_types_config,
                 max_operations_per_message: MAX_OPERATIONS_PER_MESSAGE,
                 max_bytes_read: std::f64::INFINITY,
                 max_bytes_write: std::f64::INFINITY,
+                max_node_outstanding_bytes: 67_108_864,
                 max_ask_blocks: MAX_ASK_BLOCKS_PER_MESSAGE,
                 endorsement_count: ENDORSEMENT_COUNT,
                 max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE,
@@ -175,6 +229,11 @@ pub mod tests {
                 max_peer_advertise_length: MAX_ADVERTISE_LENGTH,
                 thread_count: THREAD_COUNT,
                 max_message_size: MAX_MESSAGE_SIZE,
+                message_compression: Vec::new(),
+                min_compressed_message_size: 1024,
+                message_encryption: EncryptionMode::Disabled,
+                quic_enabled: false,
+                network_id: default_testing_network_id(),
                 max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
                 max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
                 max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
@@ -214,6 +273,7 @@ pub mod tests {
             Self {
                 bind,
                 routable_ip,
+                routable_ip_detection_votes: 0,
                 protocol_port: port,
                 connect_timeout: MassaTime::from_millis(3000),
                 peers_file: peers_file.to_path_buf(),
@@ -222,19 +282,20 @@ pub mod tests {
                 max_idle_peers: 100,
                 max_banned_peers: 100,
                 peers_file_dump_interval: MassaTime::from_millis(30000),
-                message_timeout: MassaTime::from_millis(5000u64),
                 ask_peer_list_interval: MassaTime::from_millis(50000u64),
                 keypair_file: get_temp_keypair_file().path().to_path_buf(),
                 max_send_wait_node_event: MassaTime::from_millis(100),
                 max_send_wait_network_event: MassaTime::from_millis(100),
                 ban_timeout: MassaTime::from_millis(100_000_000),
                 initial_peers_file: peers_file.to_path_buf(),
+                seed_peer_lists: Vec::new(),
                 peer_list_send_timeout: MassaTime::from_millis(50),
                 max_in_connection_overflow: 10,
                 peer_types_config,
                 max_operations_per_message: MAX_OPERATIONS_PER_MESSAGE,
                 max_bytes_read: std::f64::INFINITY,
                 max_bytes_write: std::f64::INFINITY,
+                max_node_outstanding_bytes: 67_108_864,
                 max_ask_blocks: 10,
                 endorsement_count: 8,
                 max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE,
@@ -242,6 +303,11 @@ pub mod tests {
                 max_peer_advertise_length: 128,
                 thread_count: THREAD_COUNT,
                 max_message_size: MAX_MESSAGE_SIZE,
+                message_compression: Vec::new(),
+                min_compressed_message_size: 1024,
+                message_encryption: EncryptionMode::Disabled,
+                quic_enabled: false,
+                network_id: default_testing_network_id(),
                 max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
                 max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
                 max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
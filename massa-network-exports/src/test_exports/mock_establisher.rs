@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::TransportType;
 use massa_time::MassaTime;
 use std::io;
 use std::net::SocketAddr;
@@ -74,8 +75,12 @@ pub struct MockConnector {
 }
 
 impl MockConnector {
-    /// connect
-    pub async fn connect(&mut self, addr: SocketAddr) -> std::io::Result<(ReadHalf, WriteHalf)> {
+    /// connect, whatever the transport
+    pub async fn connect(
+        &mut self,
+        addr: SocketAddr,
+        _transport: TransportType,
+    ) -> std::io::Result<(ReadHalf, WriteHalf)> {
         // task the controller connection if exist.
         let (duplex_controller, duplex_mock) = tokio::io::duplex(MAX_DUPLEX_BUFFER_SIZE);
         let (duplex_mock_read, duplex_mock_write) = tokio::io::split(duplex_mock);
@@ -129,7 +134,11 @@ impl MockEstablisher {
     }
 
     /// get connection listener
-    pub async fn get_listener(&mut self, _addr: SocketAddr) -> io::Result<MockListener> {
+    pub async fn get_listener(
+        &mut self,
+        _addr: SocketAddr,
+        _quic_enabled: bool,
+    ) -> io::Result<MockListener> {
         Ok(MockListener {
             connection_listener_rx: self
                 .connection_listener_rx
@@ -1,4 +1,6 @@
 /// mock establisher
 pub mod mock_establisher;
+/// in-memory network connecting several network controllers
+pub mod simulated_network;
 /// test tools
 pub mod tools;
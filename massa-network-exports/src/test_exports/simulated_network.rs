@@ -0,0 +1,212 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! In-memory network connecting several network controllers.
+//!
+//! Each simulated node is given a `MockEstablisher` by `SimulatedNetwork::add_node`.
+//! The connection attempts of a node are routed to the listener of the node owning the target IP,
+//! and the two duplex streams are piped together so that the controllers talk to each other
+//! as if they were connected through TCP. Links can be cut to simulate network partitions.
+//!
+//! The controllers still use the real clock: the tests relying on this network have to use
+//! real durations instead of `tokio::time::pause`.
+
+use super::mock_establisher::{
+    self, MockEstablisher, MockEstablisherInterface, ReadHalf, WriteHalf,
+};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+/// Connection request sent to the router of the target node: source address and response channel
+type IncomingConnection = (
+    SocketAddr,
+    oneshot::Sender<io::Result<(ReadHalf, WriteHalf)>>,
+);
+
+/// Link between two nodes, identified by their IPs in increasing order
+type Link = (IpAddr, IpAddr);
+
+fn link(a: IpAddr, b: IpAddr) -> Link {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Simulated node
+struct SimulatedNode {
+    /// address the other nodes see the connections from this node coming from
+    addr: SocketAddr,
+    /// sends connection requests to the router of the node
+    incoming_tx: mpsc::Sender<IncomingConnection>,
+}
+
+/// State shared by the routers of the simulated nodes
+#[derive(Default)]
+struct Switchboard {
+    /// simulated nodes by IP
+    nodes: HashMap<IpAddr, SimulatedNode>,
+    /// links on which connections are refused
+    cut_links: HashSet<Link>,
+    /// tasks piping the established connections of each link
+    connections: HashMap<Link, Vec<JoinHandle<()>>>,
+}
+
+/// In-memory network connecting network controllers started with the establishers it provides
+#[derive(Clone, Default)]
+pub struct SimulatedNetwork {
+    switchboard: Arc<Mutex<Switchboard>>,
+}
+
+impl SimulatedNetwork {
+    /// Creates an empty simulated network
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node reachable at `addr` and returns the establisher its network controller has to use.
+    /// Must be called from within a tokio runtime.
+    ///
+    /// # Panics
+    /// If a node with the same IP was already added
+    pub fn add_node(&self, addr: SocketAddr) -> MockEstablisher {
+        let (establisher, interface) = mock_establisher::new();
+        let (incoming_tx, incoming_rx) = mpsc::channel(256);
+        {
+            let mut switchboard = self.switchboard.lock().expect("switchboard poisoned");
+            if switchboard
+                .nodes
+                .insert(addr.ip(), SimulatedNode { addr, incoming_tx })
+                .is_some()
+            {
+                panic!("simulated node {} added twice", addr.ip());
+            }
+        }
+        tokio::spawn(self.clone().route(addr.ip(), interface, incoming_rx));
+        establisher
+    }
+
+    /// Closes the connections between the nodes with IPs `a` and `b` and refuses the new ones
+    pub fn cut_link(&self, a: IpAddr, b: IpAddr) {
+        let mut switchboard = self.switchboard.lock().expect("switchboard poisoned");
+        switchboard.cut_links.insert(link(a, b));
+        for handle in switchboard
+            .connections
+            .remove(&link(a, b))
+            .unwrap_or_default()
+        {
+            handle.abort();
+        }
+    }
+
+    /// Allows again the connections between the nodes with IPs `a` and `b`
+    pub fn restore_link(&self, a: IpAddr, b: IpAddr) {
+        let mut switchboard = self.switchboard.lock().expect("switchboard poisoned");
+        switchboard.cut_links.remove(&link(a, b));
+    }
+
+    /// Routes the outgoing connection attempts of the node with IP `ip`
+    /// and the incoming connections coming from the other nodes.
+    /// Stops when the network controller of the node is dropped.
+    async fn route(
+        self,
+        ip: IpAddr,
+        mut interface: MockEstablisherInterface,
+        mut incoming_rx: mpsc::Receiver<IncomingConnection>,
+    ) {
+        enum Event {
+            Outgoing(io::Result<(ReadHalf, WriteHalf, SocketAddr, oneshot::Sender<bool>)>),
+            Incoming(Option<IncomingConnection>),
+        }
+        loop {
+            let event = tokio::select! {
+                attempt = interface.wait_connection_attempt_from_controller() => {
+                    Event::Outgoing(attempt)
+                }
+                incoming = incoming_rx.recv() => Event::Incoming(incoming),
+            };
+            match event {
+                Event::Outgoing(Ok((read, write, target, accept_tx))) => {
+                    // the target router may be waiting for this one: connect in the background
+                    tokio::spawn(self.clone().connect(ip, (read, write), target, accept_tx));
+                }
+                Event::Outgoing(Err(_)) | Event::Incoming(None) => break,
+                Event::Incoming(Some((source, response_tx))) => {
+                    let _ = response_tx.send(interface.connect_to_controller(&source).await);
+                }
+            }
+        }
+        let mut switchboard = self.switchboard.lock().expect("switchboard poisoned");
+        switchboard.nodes.remove(&ip);
+    }
+
+    /// Connects the node with IP `source` to `target`, piping `outgoing` with the target's stream
+    async fn connect(
+        self,
+        source: IpAddr,
+        outgoing: (ReadHalf, WriteHalf),
+        target: SocketAddr,
+        accept_tx: oneshot::Sender<bool>,
+    ) {
+        let route = {
+            let switchboard = self.switchboard.lock().expect("switchboard poisoned");
+            if switchboard.cut_links.contains(&link(source, target.ip())) {
+                None
+            } else {
+                switchboard
+                    .nodes
+                    .get(&source)
+                    .zip(switchboard.nodes.get(&target.ip()))
+                    .map(|(source, target)| (source.addr, target.incoming_tx.clone()))
+            }
+        };
+        let Some((source_addr, incoming_tx)) = route else {
+            let _ = accept_tx.send(false);
+            return;
+        };
+
+        let (response_tx, response_rx) = oneshot::channel();
+        if incoming_tx.send((source_addr, response_tx)).await.is_err() {
+            let _ = accept_tx.send(false);
+            return;
+        }
+        let Ok(Ok(incoming)) = response_rx.await else {
+            let _ = accept_tx.send(false);
+            return;
+        };
+        if accept_tx.send(true).is_err() {
+            return;
+        }
+
+        let handle = tokio::spawn(pipe(outgoing, incoming));
+        let mut switchboard = self.switchboard.lock().expect("switchboard poisoned");
+        let link = link(source, target.ip());
+        if switchboard.cut_links.contains(&link) {
+            // the link was cut while the connection was being established
+            handle.abort();
+            return;
+        }
+        let connections = switchboard.connections.entry(link).or_default();
+        connections.retain(|handle| !handle.is_finished());
+        connections.push(handle);
+    }
+}
+
+/// Forwards the bytes written on each side of a connection to the other side until both are closed
+async fn pipe(a: (ReadHalf, WriteHalf), b: (ReadHalf, WriteHalf)) {
+    let ((mut a_read, mut a_write), (mut b_read, mut b_write)) = (a, b);
+    let a_to_b = async {
+        let _ = tokio::io::copy(&mut a_read, &mut b_write).await;
+        let _ = b_write.shutdown().await;
+    };
+    let b_to_a = async {
+        let _ = tokio::io::copy(&mut b_read, &mut a_write).await;
+        let _ = a_write.shutdown().await;
+    };
+    tokio::join!(a_to_b, b_to_a);
+}
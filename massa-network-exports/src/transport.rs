@@ -0,0 +1,29 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+/// Transports the connections with the peers can use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportType {
+    /// TCP, accepted by all the nodes
+    Tcp,
+    /// QUIC over UDP, on the same port number as TCP
+    Quic,
+}
+
+impl TransportType {
+    /// Bit of the transport in the transport capabilities exchanged during the handshake
+    pub fn flag(&self) -> u8 {
+        match self {
+            TransportType::Tcp => 0b01,
+            TransportType::Quic => 0b10,
+        }
+    }
+
+    /// Transport capabilities advertised during the handshake
+    pub fn capabilities(quic_enabled: bool) -> u8 {
+        if quic_enabled {
+            TransportType::Tcp.flag() | TransportType::Quic.flag()
+        } else {
+            TransportType::Tcp.flag()
+        }
+    }
+}
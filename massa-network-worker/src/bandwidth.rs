@@ -0,0 +1,123 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Accounting of the bytes exchanged with the peers, per node and per message type.
+//!
+//! The node workers update the counters after each message they send or receive,
+//! and the network worker reads them to answer `NetworkCommand::GetStats`.
+
+use massa_models::node::NodeId;
+use massa_models::stats::TransferredBytes;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Bandwidth stats shared between the network worker and the node workers
+pub(crate) type SharedBandwidthStats = Arc<Mutex<BandwidthStats>>;
+
+/// Bytes exchanged with the peers since the node started
+#[derive(Debug, Default)]
+pub(crate) struct BandwidthStats {
+    /// bytes exchanged with all the peers
+    total: TransferredBytes,
+    /// bytes exchanged with each node, removed when the node disconnects
+    per_node: HashMap<NodeId, TransferredBytes>,
+    /// bytes exchanged per message type
+    per_message_type: HashMap<&'static str, TransferredBytes>,
+}
+
+impl BandwidthStats {
+    /// Notes that a message of type `message_type` of `bytes` bytes was sent to `node_id`
+    pub(crate) fn note_sent(&mut self, node_id: NodeId, message_type: &'static str, bytes: u64) {
+        for counter in [
+            &mut self.total,
+            self.per_node.entry(node_id).or_default(),
+            self.per_message_type.entry(message_type).or_default(),
+        ] {
+            counter.sent = counter.sent.saturating_add(bytes);
+        }
+    }
+
+    /// Notes that a message of type `message_type` of `bytes` bytes was received from `node_id`
+    pub(crate) fn note_received(
+        &mut self,
+        node_id: NodeId,
+        message_type: &'static str,
+        bytes: u64,
+    ) {
+        for counter in [
+            &mut self.total,
+            self.per_node.entry(node_id).or_default(),
+            self.per_message_type.entry(message_type).or_default(),
+        ] {
+            counter.received = counter.received.saturating_add(bytes);
+        }
+    }
+
+    /// Forgets the bytes exchanged with a node that disconnected. They are still counted in the totals.
+    pub(crate) fn remove_node(&mut self, node_id: &NodeId) {
+        self.per_node.remove(node_id);
+    }
+
+    /// Returns the bytes exchanged with all the peers
+    pub(crate) fn total(&self) -> TransferredBytes {
+        self.total
+    }
+
+    /// Returns the bytes exchanged with each connected node
+    pub(crate) fn per_node(&self) -> BTreeMap<NodeId, TransferredBytes> {
+        self.per_node
+            .iter()
+            .map(|(node_id, bytes)| (*node_id, *bytes))
+            .collect()
+    }
+
+    /// Returns the bytes exchanged per message type
+    pub(crate) fn per_message_type(&self) -> BTreeMap<String, TransferredBytes> {
+        self.per_message_type
+            .iter()
+            .map(|(message_type, bytes)| (message_type.to_string(), *bytes))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+
+    #[test]
+    fn test_bandwidth_accounting() {
+        let node_a = NodeId::new(KeyPair::generate().get_public_key());
+        let node_b = NodeId::new(KeyPair::generate().get_public_key());
+        let mut stats = BandwidthStats::default();
+        stats.note_sent(node_a, "block_header", 100);
+        stats.note_received(node_a, "block_header", 50);
+        stats.note_received(node_b, "operations", 10);
+
+        assert_eq!(
+            stats.total(),
+            TransferredBytes {
+                sent: 100,
+                received: 60
+            }
+        );
+        assert_eq!(
+            stats.per_message_type()["block_header"],
+            TransferredBytes {
+                sent: 100,
+                received: 50
+            }
+        );
+        assert_eq!(
+            stats.per_node()[&node_b],
+            TransferredBytes {
+                sent: 0,
+                received: 10
+            }
+        );
+
+        stats.remove_node(&node_a);
+        assert!(!stats.per_node().contains_key(&node_a));
+        assert_eq!(stats.total().sent, 100);
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 //! `Flexbuffer` layer between raw data and our objects.
+use crate::compression::{compress, decompress};
+use crate::encryption::MessageCipher;
 use crate::messages::{MessageDeserializer, MessageSerializer};
 
 use super::messages::Message;
@@ -8,18 +10,31 @@ use massa_models::{
     error::ModelsError,
     serialization::{DeserializeMinBEInt, SerializeMinBEInt},
 };
-use massa_network_exports::{NetworkError, ReadHalf, WriteHalf};
+use massa_network_exports::{CompressionAlgorithm, NetworkError, ReadHalf, WriteHalf};
 use massa_serialization::Serializer;
 use massa_serialization::{DeserializeError, Deserializer};
 use std::convert::TryInto;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tracing::warn;
 
+/// Flag of the messages sent uncompressed once compression is negotiated
+const UNCOMPRESSED_FLAG: u8 = 0;
+
 /// Used to serialize and send data.
 pub struct WriteBinder {
     pub(crate) write_half: WriteHalf,
     message_index: u64,
     max_message_size: u32,
+    /// `true` once both peers agreed on prefixing messages with a compression flag
+    compression_flags: bool,
+    /// algorithm used to compress the messages, if any is supported by the peer
+    compression: Option<CompressionAlgorithm>,
+    /// messages smaller than this size are sent uncompressed
+    min_compressed_message_size: usize,
+    /// cipher of the messages, once both peers agreed on encrypting them
+    cipher: Option<MessageCipher>,
+    /// bytes sent since the last call to `take_transferred_bytes`
+    transferred_bytes: u64,
 }
 
 impl WriteBinder {
@@ -33,33 +48,104 @@ impl WriteBinder {
             write_half,
             message_index: 0,
             max_message_size,
+            compression_flags: false,
+            compression: None,
+            min_compressed_message_size: 0,
+            cipher: None,
+            transferred_bytes: 0,
         }
     }
 
+    /// Prefixes the next messages with a compression flag, and compresses them with `compression` if any.
+    /// Called once the compression capabilities were exchanged during the handshake.
+    ///
+    /// # Argument
+    /// * `compression`: algorithm supported by the peer to compress messages with, if any
+    /// * `min_compressed_message_size`: messages smaller than this size are sent uncompressed
+    pub fn enable_compression(
+        &mut self,
+        compression: Option<CompressionAlgorithm>,
+        min_compressed_message_size: u32,
+    ) {
+        self.compression_flags = true;
+        self.compression = compression;
+        self.min_compressed_message_size = min_compressed_message_size as usize;
+    }
+
+    /// Encrypts the next messages with `cipher`.
+    /// Called once the encryption keys were exchanged during the handshake.
+    pub(crate) fn enable_encryption(&mut self, cipher: MessageCipher) {
+        self.cipher = Some(cipher);
+    }
+
     /// Sends a serialized message.
     ///
     /// # Argument
     /// * `buf`: data to transmit.
     pub async fn send(&mut self, msg: &Message) -> Result<u64, NetworkError> {
         //        massa_trace!("binder.send", { "msg": msg });
+        self.send_serialized(Self::serialize(msg)?).await
+    }
+
+    /// Serializes a message
+    pub(crate) fn serialize(msg: &Message) -> Result<Vec<u8>, NetworkError> {
         let mut buf = Vec::new();
         MessageSerializer::new().serialize(msg, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Sends a message serialized by `serialize`.
+    ///
+    /// # Argument
+    /// * `buf`: serialized message.
+    pub(crate) async fn send_serialized(&mut self, mut buf: Vec<u8>) -> Result<u64, NetworkError> {
+        if self.compression_flags {
+            buf = self.compress(buf)?;
+        }
+        if let Some(cipher) = &mut self.cipher {
+            buf = cipher.encrypt(&buf)?;
+        }
         let msg_size: u32 = buf
             .len()
             .try_into()
             .map_err(|_| NetworkError::GeneralProtocolError("message too long".into()))?;
-        self.write_half
-            .write_all(&msg_size.to_be_bytes_min(self.max_message_size)?[..])
-            .await?;
+        let size_field = msg_size.to_be_bytes_min(self.max_message_size)?;
+        self.write_half.write_all(&size_field[..]).await?;
 
         // send message
         self.write_half.write_all(&buf).await?;
+        self.transferred_bytes += (size_field.len() + buf.len()) as u64;
 
         let res_index = self.message_index;
         self.message_index += 1;
         //        massa_trace!("binder.send END", { "index": res_index });
         Ok(res_index)
     }
+
+    /// Returns the number of bytes sent since the last call, and resets it
+    pub fn take_transferred_bytes(&mut self) -> u64 {
+        std::mem::take(&mut self.transferred_bytes)
+    }
+
+    /// Prefixes a serialized message with its compression flag, compressing it if worth it
+    fn compress(&self, buf: Vec<u8>) -> Result<Vec<u8>, NetworkError> {
+        if let Some(algorithm) = self.compression {
+            if buf.len() >= self.min_compressed_message_size {
+                let compressed = compress(algorithm, &buf)?;
+                // keep the message uncompressed if compression does not reduce its size
+                if compressed.len() < buf.len() {
+                    let mut res = Vec::with_capacity(compressed.len() + 1);
+                    res.push(algorithm.flag());
+                    res.extend(compressed);
+                    return Ok(res);
+                }
+            }
+        }
+        let mut res = Vec::with_capacity(buf.len() + 1);
+        res.push(UNCOMPRESSED_FLAG);
+        res.extend(buf);
+        Ok(res)
+    }
 }
 
 /// Used to receive and deserialize data.
@@ -71,6 +157,14 @@ pub struct ReadBinder {
     msg_size: Option<u32>,
     max_message_size: u32,
     message_deserializer: MessageDeserializer,
+    /// `true` once both peers agreed on prefixing messages with a compression flag
+    compression_flags: bool,
+    /// compression capabilities we advertised: messages compressed with other algorithms are rejected
+    compression_capabilities: u8,
+    /// cipher of the messages, once both peers agreed on encrypting them
+    cipher: Option<MessageCipher>,
+    /// bytes received since the last call to `take_transferred_bytes`
+    transferred_bytes: u64,
 }
 
 impl ReadBinder {
@@ -93,9 +187,54 @@ impl ReadBinder {
             msg_size: None,
             max_message_size,
             message_deserializer,
+            compression_flags: false,
+            compression_capabilities: 0,
+            cipher: None,
+            transferred_bytes: 0,
         }
     }
 
+    /// Expects the next messages to be prefixed by a compression flag.
+    /// Called once the compression capabilities were exchanged during the handshake.
+    ///
+    /// # Argument
+    /// * `compression_capabilities`: compression capabilities we advertised to the peer
+    pub fn enable_compression(&mut self, compression_capabilities: u8) {
+        self.compression_flags = true;
+        self.compression_capabilities = compression_capabilities;
+    }
+
+    /// Expects the next messages to be encrypted with `cipher`.
+    /// Called once the encryption keys were exchanged during the handshake.
+    pub(crate) fn enable_encryption(&mut self, cipher: MessageCipher) {
+        self.cipher = Some(cipher);
+    }
+
+    /// Returns the number of bytes received since the last call, and resets it
+    pub fn take_transferred_bytes(&mut self) -> u64 {
+        std::mem::take(&mut self.transferred_bytes)
+    }
+
+    /// Removes the compression flag of a received message and decompresses it if needed.
+    /// The decompressed message can not exceed `max_message_size`.
+    fn decompress(&self) -> Result<Option<Vec<u8>>, NetworkError> {
+        let (flag, data) = self.buf.split_first().ok_or_else(|| {
+            NetworkError::GeneralProtocolError("missing message compression flag".into())
+        })?;
+        if *flag == UNCOMPRESSED_FLAG {
+            return Ok(None);
+        }
+        let algorithm = CompressionAlgorithm::from_flag(*flag)
+            .filter(|algorithm| self.compression_capabilities & algorithm.flag() != 0)
+            .ok_or_else(|| {
+                NetworkError::GeneralProtocolError(format!(
+                    "unsupported message compression flag {}",
+                    flag
+                ))
+            })?;
+        decompress(algorithm, data, self.max_message_size).map(Some)
+    }
+
     /// Awaits the next incoming message and deserializes it. Asynchronous cancel-safe.
     /// Returns the message, as well as the serialized object in the case of a block.
     ///
@@ -169,15 +308,30 @@ impl ReadBinder {
                 }
             }
         }
+        if let Some(cipher) = &mut self.cipher {
+            self.buf = cipher.decrypt(&self.buf)?;
+        }
+        let decompressed = if self.compression_flags {
+            self.decompress()?
+        } else {
+            None
+        };
+        let serialized = match &decompressed {
+            Some(decompressed) => &decompressed[..],
+            None if self.compression_flags => &self.buf[1..],
+            None => &self.buf[..],
+        };
         let (_, res_msg) = self
             .message_deserializer
-            .deserialize::<DeserializeError>(&self.buf)
+            .deserialize::<DeserializeError>(serialized)
             .map_err(|err| {
                 warn!("error deserializing message: {:?}", err);
                 NetworkError::ModelsError(ModelsError::DeserializeError(err.to_string()))
             })?;
 
         // now the message readout is over, we reset the state to start reading the next message's size field again at the next run
+        self.transferred_bytes += (u32::be_bytes_min_length(self.max_message_size)
+            + self.msg_size.unwrap_or_default() as usize) as u64;
         self.cursor = 0;
         self.msg_size = None;
 
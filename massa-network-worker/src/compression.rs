@@ -0,0 +1,67 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Compression of the messages sent on the wire.
+//!
+//! `lz4` compressed data is prefixed by the decompressed size (u32, little endian),
+//! `zstd` compressed data is a standard `zstd` frame.
+
+use massa_network_exports::{CompressionAlgorithm, NetworkError};
+
+/// Compresses `data` with `algorithm`
+pub(crate) fn compress(
+    algorithm: CompressionAlgorithm,
+    data: &[u8],
+) -> Result<Vec<u8>, NetworkError> {
+    match algorithm {
+        CompressionAlgorithm::Zstd => zstd::bulk::compress(data, 0).map_err(|err| {
+            NetworkError::GeneralProtocolError(format!("zstd compression failed: {}", err))
+        }),
+        CompressionAlgorithm::Lz4 => Ok(lz4_flex::block::compress_prepend_size(data)),
+    }
+}
+
+/// Decompresses `data` with `algorithm`.
+///
+/// Fails if the decompressed data would exceed `max_decompressed_size` bytes,
+/// so that a small malicious message can not make us allocate a lot of memory.
+pub(crate) fn decompress(
+    algorithm: CompressionAlgorithm,
+    data: &[u8],
+    max_decompressed_size: u32,
+) -> Result<Vec<u8>, NetworkError> {
+    match algorithm {
+        CompressionAlgorithm::Zstd => zstd::bulk::decompress(data, max_decompressed_size as usize)
+            .map_err(|err| {
+                NetworkError::GeneralProtocolError(format!("zstd decompression failed: {}", err))
+            }),
+        CompressionAlgorithm::Lz4 => {
+            if data.len() < 4 {
+                return Err(NetworkError::GeneralProtocolError(
+                    "lz4 compressed data too short".into(),
+                ));
+            }
+            // the decompressed size is checked before allocating the output
+            let decompressed_size = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+            if decompressed_size > max_decompressed_size {
+                return Err(NetworkError::GeneralProtocolError(format!(
+                    "lz4 decompressed size {} exceeds the maximum of {}",
+                    decompressed_size, max_decompressed_size
+                )));
+            }
+            lz4_flex::block::decompress(&data[4..], decompressed_size as usize).map_err(|err| {
+                NetworkError::GeneralProtocolError(format!("lz4 decompression failed: {}", err))
+            })
+        }
+    }
+}
+
+#[test]
+fn test_decompressed_size_guard() {
+    let data = vec![0u8; 100_000];
+    for algorithm in [CompressionAlgorithm::Zstd, CompressionAlgorithm::Lz4] {
+        let compressed = compress(algorithm, &data).unwrap();
+        assert!(compressed.len() < data.len());
+        assert_eq!(decompress(algorithm, &compressed, 100_000).unwrap(), data);
+        assert!(decompress(algorithm, &compressed, 99_999).is_err());
+    }
+}
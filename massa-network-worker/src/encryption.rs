@@ -0,0 +1,180 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Encryption of the messages sent on the wire.
+//!
+//! Nodes offering encryption send an ephemeral X25519 public key in their handshake initiation,
+//! and sign it along with the random bytes of the peer so that it can not be replaced on the way.
+//! Both nodes then derive one AES-256-GCM key per direction from the Diffie-Hellman shared secret.
+//! The nonce of a message is its index in the connection: replayed, reordered or dropped messages
+//! fail to decrypt.
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use curve25519_dalek::{constants::X25519_BASEPOINT, montgomery::MontgomeryPoint, scalar::Scalar};
+use massa_hash::Hash;
+use massa_models::config::HANDSHAKE_RANDOMNESS_SIZE_BYTES;
+use massa_network_exports::{HandshakeErrorType, NetworkError};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+/// Size of the ephemeral public keys exchanged during the handshake
+pub(crate) const ENCRYPTION_KEY_SIZE_BYTES: usize = 32;
+
+/// Ephemeral X25519 key pair generated for one handshake
+pub(crate) struct EphemeralKey {
+    secret: Scalar,
+    public: MontgomeryPoint,
+}
+
+impl EphemeralKey {
+    /// Generates a random key pair
+    pub(crate) fn generate() -> Self {
+        let mut bytes = [0u8; 32];
+        StdRng::from_entropy().fill_bytes(&mut bytes);
+        // clamp the scalar as specified by X25519
+        bytes[0] &= 248;
+        bytes[31] &= 127;
+        bytes[31] |= 64;
+        let secret = Scalar::from_bits(bytes);
+        EphemeralKey {
+            public: &X25519_BASEPOINT * &secret,
+            secret,
+        }
+    }
+
+    /// Public key sent to the peer
+    pub(crate) fn public_bytes(&self) -> [u8; ENCRYPTION_KEY_SIZE_BYTES] {
+        self.public.to_bytes()
+    }
+
+    /// Derives the ciphers of a connection from the public key of the peer.
+    /// Returns the cipher of the messages we send, and the cipher of the messages we receive.
+    ///
+    /// # Arguments
+    /// * `other_public`: ephemeral public key of the peer
+    /// * `self_random_bytes`: random bytes we sent during the handshake
+    /// * `other_random_bytes`: random bytes the peer sent during the handshake
+    pub(crate) fn into_ciphers(
+        self,
+        other_public: &[u8; ENCRYPTION_KEY_SIZE_BYTES],
+        self_random_bytes: &[u8; HANDSHAKE_RANDOMNESS_SIZE_BYTES],
+        other_random_bytes: &[u8; HANDSHAKE_RANDOMNESS_SIZE_BYTES],
+    ) -> Result<(MessageCipher, MessageCipher), NetworkError> {
+        let shared_secret = (&MontgomeryPoint(*other_public) * &self.secret).to_bytes();
+        // a low order public key gives a predictable shared secret
+        if shared_secret == [0u8; 32] {
+            return Err(NetworkError::HandshakeError(
+                HandshakeErrorType::InvalidEncryptionKey,
+            ));
+        }
+        Ok((
+            MessageCipher::new(&shared_secret, self_random_bytes, other_random_bytes),
+            MessageCipher::new(&shared_secret, other_random_bytes, self_random_bytes),
+        ))
+    }
+}
+
+/// Cipher of the messages sent in one direction of a connection
+pub(crate) struct MessageCipher {
+    cipher: Aes256Gcm,
+    /// index of the next message, used as nonce
+    message_index: u64,
+}
+
+impl MessageCipher {
+    /// Creates the cipher of the messages sent by the node that generated `sender_random_bytes`
+    fn new(
+        shared_secret: &[u8; 32],
+        sender_random_bytes: &[u8; HANDSHAKE_RANDOMNESS_SIZE_BYTES],
+        receiver_random_bytes: &[u8; HANDSHAKE_RANDOMNESS_SIZE_BYTES],
+    ) -> Self {
+        let key = Hash::compute_from(
+            &[
+                &shared_secret[..],
+                &sender_random_bytes[..],
+                &receiver_random_bytes[..],
+            ]
+            .concat(),
+        );
+        MessageCipher {
+            // does not panic: the hash has the size of an AES-256 key
+            cipher: Aes256Gcm::new_from_slice(key.to_bytes()).expect("invalid AES key size"),
+            message_index: 0,
+        }
+    }
+
+    /// Nonce of the next message
+    fn nonce(&self) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        nonce[4..].copy_from_slice(&self.message_index.to_be_bytes());
+        nonce
+    }
+
+    /// Encrypts the next message sent
+    pub(crate) fn encrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, NetworkError> {
+        let encrypted = self
+            .cipher
+            .encrypt(Nonce::from_slice(&self.nonce()), data)
+            .map_err(|_| NetworkError::GeneralProtocolError("message encryption failed".into()))?;
+        self.message_index += 1;
+        Ok(encrypted)
+    }
+
+    /// Decrypts the next message received.
+    /// Fails if the message was altered, or is not the next message sent by the peer.
+    pub(crate) fn decrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, NetworkError> {
+        let decrypted = self
+            .cipher
+            .decrypt(Nonce::from_slice(&self.nonce()), data)
+            .map_err(|_| NetworkError::GeneralProtocolError("message decryption failed".into()))?;
+        self.message_index += 1;
+        Ok(decrypted)
+    }
+}
+
+#[test]
+fn test_message_encryption() {
+    let (a_random_bytes, b_random_bytes) = ([1u8; 32], [2u8; 32]);
+    let (a_key, b_key) = (EphemeralKey::generate(), EphemeralKey::generate());
+    let (a_public, b_public) = (a_key.public_bytes(), b_key.public_bytes());
+    let (mut a_send, mut a_receive) = a_key
+        .into_ciphers(&b_public, &a_random_bytes, &b_random_bytes)
+        .unwrap();
+    let (mut b_send, mut b_receive) = b_key
+        .into_ciphers(&a_public, &b_random_bytes, &a_random_bytes)
+        .unwrap();
+
+    // the authentication tag is appended to the message
+    let first = a_send.encrypt(b"first").unwrap();
+    assert_eq!(first.len(), 5 + 16);
+
+    // altered messages are rejected
+    let mut altered = first.clone();
+    altered[0] ^= 1;
+    assert!(b_receive.decrypt(&altered).is_err());
+
+    assert_eq!(b_receive.decrypt(&first).unwrap(), b"first");
+    assert_eq!(
+        a_receive
+            .decrypt(&b_send.encrypt(b"reply").unwrap())
+            .unwrap(),
+        b"reply"
+    );
+
+    // replayed messages are rejected
+    assert!(b_receive.decrypt(&first).is_err());
+
+    // the ciphers derived from another key do not match
+    let (_, mut other_receive) = EphemeralKey::generate()
+        .into_ciphers(&a_public, &b_random_bytes, &a_random_bytes)
+        .unwrap();
+    assert!(other_receive
+        .decrypt(&a_send.encrypt(b"second").unwrap())
+        .is_err());
+
+    // low order public keys are refused
+    assert!(EphemeralKey::generate()
+        .into_ciphers(&[0u8; 32], &a_random_bytes, &b_random_bytes)
+        .is_err());
+}
@@ -2,6 +2,7 @@
 
 //! Here are happening handshakes.
 
+use crate::encryption::{EphemeralKey, ENCRYPTION_KEY_SIZE_BYTES};
 use crate::messages::MessageDeserializer;
 
 use super::{
@@ -25,17 +26,21 @@ use massa_models::{
     node::NodeId,
 };
 use massa_network_exports::{
-    throw_handshake_error as throw, ConnectionId, HandshakeErrorType, NetworkError, ReadHalf,
-    WriteHalf,
+    throw_handshake_error as throw, CompressionAlgorithm, ConnectionId, EncryptionMode,
+    HandshakeErrorType, NetworkError, NetworkId, ReadHalf, WriteHalf,
 };
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use rand::{rngs::StdRng, RngCore, SeedableRng};
+use std::net::IpAddr;
 use tokio::{task::JoinHandle, time::timeout};
 use tracing::debug;
 
-/// Type alias for more readability
-pub type HandshakeReturnType = Result<(NodeId, ReadBinder, WriteBinder), NetworkError>;
+/// Type alias for more readability.
+/// On success, contains the node id of the peer, the binders to communicate with it,
+/// the IP the peer observed for us if it told us, and the transports the peer accepts.
+pub type HandshakeReturnType =
+    Result<(NodeId, ReadBinder, WriteBinder, Option<IpAddr>, u8), NetworkError>;
 
 /// Manages handshakes.
 pub struct HandshakeWorker {
@@ -50,6 +55,18 @@ pub struct HandshakeWorker {
     /// After `timeout_duration` milliseconds, the handshake attempt is dropped.
     timeout_duration: MassaTime,
     version: Version,
+    /// Compression algorithms we support, by order of preference.
+    message_compression: Vec<CompressionAlgorithm>,
+    /// Messages smaller than this size are sent uncompressed.
+    min_compressed_message_size: u32,
+    /// Encryption of the messages.
+    message_encryption: EncryptionMode,
+    /// Transports we accept connections on, see `TransportType::capabilities`.
+    transports: u8,
+    /// Network we belong to.
+    network_id: NetworkId,
+    /// IP address of the peer, as we observe it.
+    remote_ip: IpAddr,
 }
 
 impl HandshakeWorker {
@@ -69,6 +86,12 @@ impl HandshakeWorker {
     /// * `timeout_duration`: after `timeout_duration` milliseconds, the handshake attempt is dropped.
     /// * `connection_id`: Node we are trying to connect for debugging
     /// * `version`: Node version used in handshake initialization (check peers compatibility)
+    /// * `message_compression`: compression algorithms we support, by order of preference
+    /// * `min_compressed_message_size`: messages smaller than this size are sent uncompressed
+    /// * `message_encryption`: encryption of the messages, peers not supporting a required encryption are refused
+    /// * `transports`: transports we accept connections on, announced to the peer
+    /// * `network_id`: network we belong to, peers announcing another network are refused
+    /// * `remote_ip`: IP address of the peer, sent to it so that it can detect its routable IP
     #[allow(clippy::too_many_arguments)]
     pub fn spawn(
         socket_reader: ReadHalf,
@@ -80,6 +103,12 @@ impl HandshakeWorker {
         connection_id: ConnectionId,
         max_bytes_read: f64,
         max_bytes_write: f64,
+        message_compression: Vec<CompressionAlgorithm>,
+        min_compressed_message_size: u32,
+        message_encryption: EncryptionMode,
+        transports: u8,
+        network_id: NetworkId,
+        remote_ip: IpAddr,
     ) -> JoinHandle<(ConnectionId, HandshakeReturnType)> {
         debug!("starting handshake with connection_id={}", connection_id);
         massa_trace!("network_worker.new_connection", {
@@ -116,6 +145,12 @@ impl HandshakeWorker {
                     keypair,
                     timeout_duration,
                     version,
+                    message_compression,
+                    min_compressed_message_size,
+                    message_encryption,
+                    transports,
+                    network_id,
+                    remote_ip,
                 }
                 .run()
                 .await,
@@ -131,12 +166,21 @@ impl HandshakeWorker {
         // generate random bytes
         let mut self_random_bytes = [0u8; 32];
         StdRng::from_entropy().fill_bytes(&mut self_random_bytes);
-        let self_random_hash = Hash::compute_from(&self_random_bytes);
+        let self_encryption_key = self
+            .message_encryption
+            .is_offered()
+            .then(EphemeralKey::generate);
+        let self_compression = CompressionAlgorithm::capabilities(&self.message_compression);
         // send handshake init future
         let msg = Message::HandshakeInitiation {
             public_key: self.self_node_id.get_public_key(),
             random_bytes: self_random_bytes,
             version: self.version,
+            compression: self_compression,
+            network_id: Some(self.network_id),
+            observed_ip: Some(self.remote_ip),
+            transports: self.transports,
+            encryption_key: self_encryption_key.as_ref().map(EphemeralKey::public_bytes),
         };
         let send_init_fut = self.writer.send(&msg);
 
@@ -144,7 +188,16 @@ impl HandshakeWorker {
         let recv_init_fut = self.reader.next();
 
         // join send_init_fut and recv_init_fut with a timeout, and match result
-        let (other_node_id, other_random_bytes, other_version) = match timeout(
+        let (
+            other_node_id,
+            other_random_bytes,
+            other_version,
+            other_compression,
+            other_network_id,
+            self_observed_ip,
+            other_transports,
+            other_encryption_key,
+        ) = match timeout(
             self.timeout_duration.to_duration(),
             try_join(send_init_fut, recv_init_fut),
         )
@@ -158,7 +211,21 @@ impl HandshakeWorker {
                     public_key: pk,
                     random_bytes: rb,
                     version,
-                } => (NodeId::new(pk), rb, version),
+                    compression,
+                    network_id,
+                    observed_ip,
+                    transports,
+                    encryption_key,
+                } => (
+                    NodeId::new(pk),
+                    rb,
+                    version,
+                    compression,
+                    network_id,
+                    observed_ip,
+                    transports,
+                    encryption_key,
+                ),
                 Message::PeerList(list) => throw!(PeerListReceived, list),
                 _ => throw!(HandshakeWrongMessage),
             },
@@ -174,8 +241,30 @@ impl HandshakeWorker {
             throw!(IncompatibleVersion)
         }
 
-        // sign their random bytes
-        let other_random_hash = Hash::compute_from(&other_random_bytes);
+        // check that the peer belongs to our network
+        match other_network_id {
+            None => throw!(MissingNetworkId),
+            Some(other_network_id) if other_network_id != self.network_id => {
+                throw!(NetworkIdMismatch, other_network_id)
+            }
+            Some(_) => {}
+        }
+
+        // check that the peer supports encryption if we require it
+        if self.message_encryption == EncryptionMode::Required && other_encryption_key.is_none() {
+            throw!(EncryptionRequired)
+        }
+
+        // messages are encrypted if both nodes sent an encryption key
+        let encryption_keys = self_encryption_key.zip(other_encryption_key);
+
+        // sign their random bytes, along with our encryption key if it is used
+        let other_random_hash = handshake_hash(
+            &other_random_bytes,
+            encryption_keys
+                .as_ref()
+                .map(|(self_key, _)| self_key.public_bytes()),
+        );
         let self_signature = self.keypair.sign(&other_random_hash)?;
 
         // send handshake reply future
@@ -204,6 +293,10 @@ impl HandshakeWorker {
         };
 
         // check their signature
+        let self_random_hash = handshake_hash(
+            &self_random_bytes,
+            encryption_keys.as_ref().map(|(_, other_key)| *other_key),
+        );
         other_node_id
             .get_public_key()
             .verify_signature(&self_random_hash, &other_signature)
@@ -211,6 +304,46 @@ impl HandshakeWorker {
                 NetworkError::HandshakeError(HandshakeErrorType::HandshakeInvalidSignature)
             })?;
 
-        Ok((other_node_id, self.reader, self.writer))
+        // from now on, messages carry a compression flag if both nodes support compression
+        if self_compression != 0 && other_compression != 0 {
+            // compress with our preferred algorithm among the ones supported by the peer
+            let compression = self
+                .message_compression
+                .iter()
+                .find(|algorithm| other_compression & algorithm.flag() != 0)
+                .copied();
+            self.writer
+                .enable_compression(compression, self.min_compressed_message_size);
+            self.reader.enable_compression(self_compression);
+        }
+
+        // from now on, messages are encrypted if both nodes offered encryption
+        if let Some((self_key, other_key)) = encryption_keys {
+            let (send_cipher, receive_cipher) =
+                self_key.into_ciphers(&other_key, &self_random_bytes, &other_random_bytes)?;
+            self.writer.enable_encryption(send_cipher);
+            self.reader.enable_encryption(receive_cipher);
+        }
+
+        Ok((
+            other_node_id,
+            self.reader,
+            self.writer,
+            self_observed_ip,
+            other_transports,
+        ))
+    }
+}
+
+/// Hash a node signs to prove its identity during the handshake: the random bytes sent by the peer,
+/// followed by the encryption key of the node if both nodes offered encryption,
+/// so that the encryption keys can not be replaced by a third party.
+fn handshake_hash(
+    random_bytes: &[u8],
+    encryption_key: Option<[u8; ENCRYPTION_KEY_SIZE_BYTES]>,
+) -> Hash {
+    match encryption_key {
+        Some(encryption_key) => Hash::compute_from(&[random_bytes, &encryption_key[..]].concat()),
+        None => Hash::compute_from(random_bytes),
     }
 }
@@ -14,15 +14,19 @@ use crate::{
 use massa_logging::massa_trace;
 use massa_models::{node::NodeId, version::Version};
 use massa_network_exports::{
-    BootstrapPeers, Establisher, NetworkCommand, NetworkCommandSender, NetworkConfig, NetworkError,
-    NetworkEvent, NetworkEventReceiver, NetworkManagementCommand, NetworkManager,
+    BootstrapPeers, Establisher, MessageWriteTimeouts, NetworkCommand, NetworkCommandSender,
+    NetworkConfig, NetworkError, NetworkEvent, NetworkEventReceiver, NetworkManagementCommand,
+    NetworkManager,
 };
 use massa_signature::KeyPair;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
 //pub use establisher::Establisher;
+mod bandwidth;
 mod binders;
+mod compression;
+mod encryption;
 mod handshake_worker;
 mod messages;
 mod network_cmd_impl;
@@ -30,6 +34,9 @@ mod network_event;
 mod network_worker;
 mod node_worker;
 mod peer_info_database;
+mod public_ip;
+mod seed_peers;
+mod send_queue;
 
 #[cfg(test)]
 pub mod tests;
@@ -38,8 +45,10 @@ pub mod tests;
 ///
 /// # Arguments
 /// * `cfg`: network configuration
+/// * `write_timeouts`: write timeouts of the messages sent to the nodes, from the protocol configuration
 pub async fn start_network_controller(
     network_settings: &NetworkConfig,
+    write_timeouts: MessageWriteTimeouts,
     mut establisher: Establisher,
     initial_peers: Option<BootstrapPeers>,
     version: Version,
@@ -90,7 +99,9 @@ pub async fn start_network_controller(
     massa_trace!("self_node_id", { "node_id": self_node_id });
 
     // create listener
-    let listener = establisher.get_listener(network_settings.bind).await?;
+    let listener = establisher
+        .get_listener(network_settings.bind, network_settings.quic_enabled)
+        .await?;
 
     debug!("Loading peer database");
     // load peer info database
@@ -112,6 +123,7 @@ pub async fn start_network_controller(
     let join_handle = tokio::spawn(async move {
         let res = NetworkWorker::new(
             cfg_copy,
+            write_timeouts,
             keypair_cloned,
             listener,
             establisher,
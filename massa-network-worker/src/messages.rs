@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::encryption::ENCRYPTION_KEY_SIZE_BYTES;
 use massa_hash::HashDeserializer;
 use massa_models::{
     block::{BlockHeader, BlockHeaderDeserializer, BlockId, WrappedHeader},
@@ -15,15 +16,17 @@ use massa_models::{
     version::{Version, VersionDeserializer, VersionSerializer},
     wrapped::{WrappedDeserializer, WrappedSerializer},
 };
-use massa_network_exports::{AskForBlocksInfo, BlockInfoReply};
+use massa_network_exports::{AskForBlocksInfo, BlockInfoReply, NetworkId};
 use massa_serialization::{
     Deserializer, SerializeError, Serializer, U32VarIntDeserializer, U32VarIntSerializer,
 };
 use massa_signature::{PublicKey, PublicKeyDeserializer, Signature, SignatureDeserializer};
 use nom::{
     bytes::complete::take,
+    combinator::opt,
     error::{context, ContextError, ParseError},
     multi::length_count,
+    number::complete::{be_u64, be_u8},
     sequence::tuple,
     IResult, Parser,
 };
@@ -45,6 +48,22 @@ pub enum Message {
         /// let us know their public key.
         random_bytes: [u8; HANDSHAKE_RANDOMNESS_SIZE_BYTES],
         version: Version,
+        /// Compression algorithms we support, see `CompressionAlgorithm::capabilities`.
+        /// Sent after the version so that nodes ignoring it can still read the handshake.
+        compression: u8,
+        /// Network we belong to.
+        /// Optional so that the handshake of nodes ignoring it can still be read,
+        /// but handshakes without it are refused.
+        network_id: Option<NetworkId>,
+        /// IP address we observe for the peer, used by the peer to detect its routable IP.
+        /// Sent only along with `network_id`.
+        observed_ip: Option<IpAddr>,
+        /// Transports we accept connections on, see `TransportType::capabilities`.
+        /// Sent only along with `observed_ip`.
+        transports: u8,
+        /// Ephemeral public key used to derive the encryption keys, if we offer encryption.
+        /// Sent last, and only along with `transports`.
+        encryption_key: Option<[u8; ENCRYPTION_KEY_SIZE_BYTES]>,
     },
     /// Reply to a handshake initiation message.
     HandshakeReply {
@@ -74,6 +93,25 @@ pub enum Message {
     Endorsements(Vec<WrappedEndorsement>),
 }
 
+impl Message {
+    /// Name of the message type, used to label metrics
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Message::HandshakeInitiation { .. } => "handshake_initiation",
+            Message::HandshakeReply { .. } => "handshake_reply",
+            Message::BlockHeader(_) => "block_header",
+            Message::AskForBlocks(_) => "ask_for_blocks",
+            Message::ReplyForBlocks(_) => "reply_for_blocks",
+            Message::AskPeerList => "ask_peer_list",
+            Message::PeerList(_) => "peer_list",
+            Message::OperationsAnnouncement(_) => "operations_announcement",
+            Message::AskForOperations(_) => "ask_for_operations",
+            Message::Operations(_) => "operations",
+            Message::Endorsements(_) => "endorsements",
+        }
+    }
+}
+
 #[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
 #[repr(u32)]
 pub(crate) enum MessageTypeId {
@@ -140,12 +178,29 @@ impl Serializer<Message> for MessageSerializer {
                 public_key,
                 random_bytes,
                 version,
+                compression,
+                network_id,
+                observed_ip,
+                transports,
+                encryption_key,
             } => {
                 self.u32_serializer
                     .serialize(&(MessageTypeId::HandshakeInitiation as u32), buffer)?;
                 buffer.extend(public_key.to_bytes());
                 buffer.extend(random_bytes);
                 self.version_serializer.serialize(version, buffer)?;
+                buffer.push(*compression);
+                if let Some(network_id) = network_id {
+                    buffer.extend(network_id.chain_id.to_be_bytes());
+                    buffer.extend(network_id.genesis_hash.to_bytes());
+                    if let Some(observed_ip) = observed_ip {
+                        self.ip_addr_serializer.serialize(observed_ip, buffer)?;
+                        buffer.push(*transports);
+                        if let Some(encryption_key) = encryption_key {
+                            buffer.extend(encryption_key);
+                        }
+                    }
+                }
             }
             Message::HandshakeReply { signature } => {
                 self.u32_serializer
@@ -353,15 +408,56 @@ impl Deserializer<Message> for MessageDeserializer {
                         context("Failed version deserialization", |input| {
                             self.version_deserializer.deserialize(input)
                         }),
+                        // absent if the peer does not support compression
+                        context("Failed compression deserialization", opt(be_u8)),
+                        // absent if the peer does not announce its network
+                        context(
+                            "Failed network_id deserialization",
+                            opt(tuple((be_u64, |input| {
+                                self.hash_deserializer.deserialize(input)
+                            }))),
+                        ),
+                        // absent if the peer does not tell us our IP
+                        context(
+                            "Failed observed_ip deserialization",
+                            opt(|input| self.ip_addr_deserializer.deserialize(input)),
+                        ),
+                        // absent if the peer does not announce its transports
+                        context("Failed transports deserialization", opt(be_u8)),
+                        // absent if the peer does not offer encryption
+                        context(
+                            "Failed encryption_key deserialization",
+                            opt(take(ENCRYPTION_KEY_SIZE_BYTES)),
+                        ),
                     ))
-                    .map(|(public_key, random_bytes, version)| {
-                        // Unwrap safety: we checked above that we took enough bytes
-                        Message::HandshakeInitiation {
+                    .map(
+                        |(
                             public_key,
-                            random_bytes: array_from_slice(random_bytes).unwrap(),
+                            random_bytes,
                             version,
-                        }
-                    }),
+                            compression,
+                            network_id,
+                            observed_ip,
+                            transports,
+                            encryption_key,
+                        )| {
+                            // Unwrap safety: we checked above that we took enough bytes
+                            Message::HandshakeInitiation {
+                                public_key,
+                                random_bytes: array_from_slice(random_bytes).unwrap(),
+                                version,
+                                compression: compression.unwrap_or_default(),
+                                network_id: network_id.map(|(chain_id, genesis_hash)| NetworkId {
+                                    chain_id,
+                                    genesis_hash,
+                                }),
+                                observed_ip,
+                                transports: transports.unwrap_or_default(),
+                                encryption_key: encryption_key
+                                    .map(|key| array_from_slice(key).unwrap()),
+                            }
+                        },
+                    ),
                 )
                 .parse(input),
                 MessageTypeId::HandshakeReply => {
@@ -536,6 +632,7 @@ impl Deserializer<Message> for MessageDeserializer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use massa_hash::{Hash, HASH_SIZE_BYTES};
     use massa_models::config::{
         ENDORSEMENT_COUNT, MAX_ADVERTISE_LENGTH, MAX_ASK_BLOCKS_PER_MESSAGE,
         MAX_DATASTORE_VALUE_LENGTH, MAX_ENDORSEMENTS_PER_MESSAGE, MAX_FUNCTION_NAME_LENGTH,
@@ -575,6 +672,14 @@ mod tests {
             public_key: keypair.get_public_key(),
             random_bytes,
             version: Version::from_str("TEST.1.10").unwrap(),
+            compression: 0b11,
+            network_id: Some(NetworkId {
+                chain_id: 1,
+                genesis_hash: Hash::compute_from(b"genesis"),
+            }),
+            observed_ip: Some("169.202.0.11".parse().unwrap()),
+            transports: 0b11,
+            encryption_key: Some([7u8; ENCRYPTION_KEY_SIZE_BYTES]),
         };
         let mut ser = Vec::new();
         message_serializer.serialize(&msg, &mut ser).unwrap();
@@ -587,18 +692,98 @@ mod tests {
                     public_key: pk1,
                     random_bytes: rb1,
                     version: v1,
+                    compression: c1,
+                    network_id: n1,
+                    observed_ip: o1,
+                    transports: t1,
+                    encryption_key: e1,
                 },
                 Message::HandshakeInitiation {
                     public_key,
                     random_bytes,
                     version,
+                    compression,
+                    network_id,
+                    observed_ip,
+                    transports,
+                    encryption_key,
                 },
             ) => {
                 assert_eq!(pk1, public_key);
                 assert_eq!(rb1, random_bytes);
                 assert_eq!(v1, version);
+                assert_eq!(c1, compression);
+                assert_eq!(n1, network_id);
+                assert_eq!(o1, observed_ip);
+                assert_eq!(t1, transports);
+                assert_eq!(e1, encryption_key);
+            }
+            _ => panic!("unexpected message"),
+        }
+
+        // a handshake without encryption key is still accepted
+        ser.truncate(ser.len() - ENCRYPTION_KEY_SIZE_BYTES);
+        match message_deserializer
+            .deserialize::<DeserializeError>(&ser)
+            .unwrap()
+            .1
+        {
+            Message::HandshakeInitiation {
+                transports,
+                encryption_key,
+                ..
+            } => {
+                assert_eq!(transports, 0b11);
+                assert_eq!(encryption_key, None);
+            }
+            _ => panic!("unexpected message"),
+        }
+
+        // a handshake without transports is still accepted
+        ser.pop();
+        match message_deserializer
+            .deserialize::<DeserializeError>(&ser)
+            .unwrap()
+            .1
+        {
+            Message::HandshakeInitiation {
+                observed_ip,
+                transports,
+                ..
+            } => {
+                assert!(observed_ip.is_some());
+                assert_eq!(transports, 0);
+            }
+            _ => panic!("unexpected message"),
+        }
+
+        // a handshake without network id nor observed IP can still be read
+        ser.truncate(ser.len() - 5 - 8 - HASH_SIZE_BYTES);
+        match message_deserializer
+            .deserialize::<DeserializeError>(&ser)
+            .unwrap()
+            .1
+        {
+            Message::HandshakeInitiation {
+                compression,
+                network_id,
+                ..
+            } => {
+                assert_eq!(compression, 0b11);
+                assert_eq!(network_id, None);
             }
             _ => panic!("unexpected message"),
         }
+
+        // a handshake without compression capabilities is still accepted
+        ser.pop();
+        match message_deserializer
+            .deserialize::<DeserializeError>(&ser)
+            .unwrap()
+            .1
+        {
+            Message::HandshakeInitiation { compression, .. } => assert_eq!(compression, 0),
+            _ => panic!("unexpected message"),
+        }
     }
 }
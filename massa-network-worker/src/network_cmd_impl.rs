@@ -38,6 +38,7 @@ use massa_network_exports::{
 use std::{
     collections::{HashMap, HashSet},
     net::IpAddr,
+    path::PathBuf,
 };
 use tokio::sync::oneshot;
 use tracing::warn;
@@ -324,10 +325,33 @@ pub async fn on_remove_from_whitelist_cmd(
     worker.peer_info_db.remove_from_whitelist(ips).await
 }
 
+pub async fn on_export_peers_cmd(
+    worker: &mut NetworkWorker,
+    path: PathBuf,
+    response_tx: oneshot::Sender<Result<(), NetworkError>>,
+) {
+    let res = worker.peer_info_db.export_peers(&path).await;
+    if response_tx.send(res).is_err() {
+        warn!("network: could not send ExportPeers response upstream");
+    }
+}
+
+pub async fn on_import_peers_cmd(
+    worker: &mut NetworkWorker,
+    path: PathBuf,
+    response_tx: oneshot::Sender<Result<usize, NetworkError>>,
+) {
+    let res = worker.peer_info_db.import_peers(&path).await;
+    if response_tx.send(res).is_err() {
+        warn!("network: could not send ImportPeers response upstream");
+    }
+}
+
 pub async fn on_get_stats_cmd(
     worker: &mut NetworkWorker,
     response_tx: oneshot::Sender<NetworkStats>,
 ) {
+    let bandwidth_stats = worker.bandwidth_stats.lock();
     let res = NetworkStats {
         in_connection_count: worker.peer_info_db.get_in_connection_count(),
         out_connection_count: worker.peer_info_db.get_out_connection_count(),
@@ -339,7 +363,11 @@ pub async fn on_get_stats_cmd(
             .filter(|(_, p)| p.banned)
             .fold(0, |acc, _| acc + 1),
         active_node_count: worker.active_nodes.len() as u64,
+        transferred_bytes: bandwidth_stats.total(),
+        transferred_bytes_per_node: bandwidth_stats.per_node(),
+        transferred_bytes_per_message_type: bandwidth_stats.per_message_type(),
     };
+    drop(bandwidth_stats);
     if response_tx.send(res).is_err() {
         warn!("network: could not send NodeSignMessage response upstream");
     }
@@ -5,18 +5,21 @@ use super::{
     handshake_worker::HandshakeReturnType, node_worker::NodeWorker, peer_info_database::*,
 };
 use crate::{
+    bandwidth::SharedBandwidthStats,
     binders::{ReadBinder, WriteBinder},
     handshake_worker::HandshakeWorker,
     messages::{Message, MessageDeserializer},
     network_event::EventSender,
+    public_ip::PublicIpVoter,
 };
 use futures::{stream::FuturesUnordered, StreamExt};
 use massa_logging::massa_trace;
 use massa_models::{node::NodeId, version::Version};
 use massa_network_exports::{
     ConnectionClosureReason, ConnectionId, Establisher, HandshakeErrorType, Listener,
-    NetworkCommand, NetworkConfig, NetworkConnectionErrorType, NetworkError, NetworkEvent,
-    NetworkManagementCommand, NodeCommand, NodeEvent, NodeEventType, ReadHalf, WriteHalf,
+    MessageWriteTimeouts, NetworkCommand, NetworkConfig, NetworkConnectionErrorType, NetworkError,
+    NetworkEvent, NetworkManagementCommand, NodeCommand, NodeEvent, NodeEventType, ReadHalf,
+    TransportType, WriteHalf,
 };
 use massa_signature::KeyPair;
 use std::{
@@ -25,7 +28,7 @@ use std::{
 };
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
-use tracing::{debug, trace, warn};
+use tracing::{debug, info, trace, warn};
 
 /// Real job is done by network worker
 pub struct NetworkWorker {
@@ -64,6 +67,12 @@ pub struct NetworkWorker {
     version: Version,
     /// Event sender
     pub(crate) event: EventSender,
+    /// Elects our routable IP from the IPs observed by our peers
+    public_ip_voter: PublicIpVoter,
+    /// Bytes exchanged with the peers, updated by the node workers
+    pub(crate) bandwidth_stats: SharedBandwidthStats,
+    /// Write timeouts of the messages sent to the nodes
+    write_timeouts: MessageWriteTimeouts,
 }
 
 pub struct NetworkWorkerChannels {
@@ -77,6 +86,7 @@ impl NetworkWorker {
     ///
     /// # Arguments
     /// * `cfg`: Network configuration.
+    /// * `write_timeouts`: Write timeouts of the messages sent to the nodes.
     /// * `listener`: Listener part of the establisher.
     /// * `establisher`: The connection establisher.
     /// * `peer_info_db`: Database with peer information.
@@ -86,6 +96,7 @@ impl NetworkWorker {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         cfg: NetworkConfig,
+        write_timeouts: MessageWriteTimeouts,
         keypair: KeyPair,
         listener: Listener,
         establisher: Establisher,
@@ -102,6 +113,12 @@ impl NetworkWorker {
         let (node_event_tx, node_event_rx) =
             mpsc::channel::<NodeEvent>(cfg.node_event_channel_size);
         let max_wait_event = cfg.max_send_wait_network_event.to_duration();
+        // detect our routable IP only if it is not configured
+        let public_ip_voter = PublicIpVoter::new(if cfg.routable_ip.is_none() {
+            cfg.routable_ip_detection_votes
+        } else {
+            0
+        });
         NetworkWorker {
             cfg,
             self_node_id,
@@ -120,6 +137,9 @@ impl NetworkWorker {
             node_worker_handles: FuturesUnordered::new(),
             active_connections: HashMap::new(),
             version,
+            public_ip_voter,
+            bandwidth_stats: Default::default(),
+            write_timeouts,
         }
     }
 
@@ -146,8 +166,13 @@ impl NetworkWorker {
                         .get_connector(self.cfg.connect_timeout)
                         .await?;
                     let addr = SocketAddr::new(ip, self.cfg.protocol_port);
+                    // connect over QUIC to the peers known to accept it
+                    let transport = match self.peer_info_db.get_peers().get(&ip) {
+                        Some(peer) if self.cfg.quic_enabled && peer.quic => TransportType::Quic,
+                        _ => TransportType::Tcp,
+                    };
                     out_connecting_futures.push(async move {
-                        match connector.connect(addr).await {
+                        match connector.connect(addr, transport).await {
                             Ok((reader, writer)) => (addr.ip(), Ok((reader, writer))),
                             Err(e) => (addr.ip(), Err(e)),
                         }
@@ -236,6 +261,7 @@ impl NetworkWorker {
                         .active_nodes
                         .remove(&node_id) {
                         massa_trace!("protocol channel closed", {"node_id": node_id});
+                        self.bandwidth_stats.lock().remove_node(&node_id);
                         self.connection_closed(connection_id, reason).await?;
                     }
 
@@ -327,7 +353,7 @@ impl NetworkWorker {
         });
         match outcome {
             // a handshake finished, and succeeded
-            Ok((new_node_id, socket_reader, socket_writer)) => {
+            Ok((new_node_id, socket_reader, socket_writer, observed_ip, transports)) => {
                 debug!(
                     "handshake with connection_id={} succeeded => node_id={}",
                     new_connection_id, new_node_id
@@ -380,23 +406,37 @@ impl NetworkWorker {
                                 .ok_or_else(|| {
                                     NetworkError::ActiveConnectionMissing(new_connection_id)
                                 })?;
+                        self.peer_info_db
+                            .set_peer_quic(ip, transports & TransportType::Quic.flag() != 0)?;
                         self.peer_info_db.peer_alive(ip)?;
 
+                        // take into account the IP the peer observed for us
+                        if let Some(observed_ip) = observed_ip {
+                            if let Some(elected_ip) = self.public_ip_voter.vote(*ip, observed_ip) {
+                                info!("detected routable IP {} from peer observations", elected_ip);
+                                self.peer_info_db.network_settings.routable_ip = Some(elected_ip);
+                            }
+                        }
+
                         // spawn node_controller_fn
                         let (node_command_tx, node_command_rx) =
                             mpsc::channel::<NodeCommand>(self.cfg.node_command_channel_size);
                         let node_event_tx_clone = self.event.clone_node_sender();
                         let cfg_copy = self.cfg.clone();
                         let node_worker_command_tx = node_command_tx.clone();
+                        let bandwidth_stats = self.bandwidth_stats.clone();
+                        let write_timeouts = self.write_timeouts;
                         let node_fn_handle = tokio::spawn(async move {
                             let res = NodeWorker::new(
                                 cfg_copy,
+                                write_timeouts,
                                 new_node_id,
                                 socket_reader,
                                 socket_writer,
                                 node_worker_command_tx,
                                 node_command_rx,
                                 node_event_tx_clone,
+                                bandwidth_stats,
                             )
                             .run_loop()
                             .await;
@@ -447,8 +487,15 @@ impl NetworkWorker {
                     "err": err.to_string()
                 });
                 self.running_handshakes.remove(&new_connection_id);
-                self.connection_closed(new_connection_id, ConnectionClosureReason::Failed)
-                    .await?;
+                let reason = match err {
+                    NetworkError::HandshakeError(
+                        HandshakeErrorType::IncompatibleVersion
+                        | HandshakeErrorType::NetworkIdMismatch(_)
+                        | HandshakeErrorType::MissingNetworkId,
+                    ) => ConnectionClosureReason::Incompatible,
+                    _ => ConnectionClosureReason::Failed,
+                };
+                self.connection_closed(new_connection_id, reason).await?;
             }
         };
         Ok(())
@@ -474,7 +521,7 @@ impl NetworkWorker {
         });
         match reason {
             ConnectionClosureReason::Normal => {}
-            ConnectionClosureReason::Failed => {
+            ConnectionClosureReason::Failed | ConnectionClosureReason::Incompatible => {
                 self.peer_info_db.peer_failed(&ip)?;
             }
             ConnectionClosureReason::Banned => {
@@ -546,6 +593,12 @@ impl NetworkWorker {
             NetworkCommand::RemoveFromWhitelist(ips) => {
                 on_remove_from_whitelist_cmd(self, ips).await?
             }
+            NetworkCommand::ExportPeers { path, response_tx } => {
+                on_export_peers_cmd(self, path, response_tx).await
+            }
+            NetworkCommand::ImportPeers { path, response_tx } => {
+                on_import_peers_cmd(self, path, response_tx).await
+            }
         };
         Ok(())
     }
@@ -764,6 +817,10 @@ impl NetworkWorker {
         reader: ReadHalf,
         writer: WriteHalf,
     ) -> Result<(), NetworkError> {
+        let (remote_ip, _) = *self
+            .active_connections
+            .get(&connection_id)
+            .ok_or(NetworkError::ActiveConnectionMissing(connection_id))?;
         if !self.running_handshakes.insert(connection_id) {
             return Err(NetworkError::HandshakeError(
                 HandshakeErrorType::HandshakeIdAlreadyExist(format!("{}", connection_id)),
@@ -779,6 +836,12 @@ impl NetworkWorker {
             connection_id,
             self.cfg.max_bytes_read,
             self.cfg.max_bytes_write,
+            self.cfg.message_compression.clone(),
+            self.cfg.min_compressed_message_size,
+            self.cfg.message_encryption,
+            TransportType::capabilities(self.cfg.quic_enabled),
+            self.cfg.network_id,
+            remote_ip,
         ));
         Ok(())
     }
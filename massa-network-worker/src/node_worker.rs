@@ -1,14 +1,17 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use super::{
+    bandwidth::SharedBandwidthStats,
     binders::{ReadBinder, WriteBinder},
     messages::Message,
+    send_queue::{PushResult, QueuedMessage, SendLane, SendQueue},
 };
 use itertools::Itertools;
 use massa_logging::massa_trace;
 use massa_models::{node::NodeId, wrapped::Id};
 use massa_network_exports::{
-    ConnectionClosureReason, NetworkConfig, NetworkError, NodeCommand, NodeEvent, NodeEventType,
+    ConnectionClosureReason, MessageWriteTimeouts, NetworkConfig, NetworkError, NodeCommand,
+    NodeEvent, NodeEventType,
 };
 use massa_time::MassaTime;
 use tokio::{
@@ -18,11 +21,30 @@ use tokio::{
 };
 use tracing::{debug, trace, warn};
 
+/// Returns the write timeout matching the class of `msg`.
+fn write_timeout(timeouts: &MessageWriteTimeouts, msg: &Message) -> MassaTime {
+    match msg {
+        Message::HandshakeInitiation { .. }
+        | Message::HandshakeReply { .. }
+        | Message::AskPeerList
+        | Message::PeerList(_)
+        | Message::AskForBlocks(_) => timeouts.handshake,
+        Message::BlockHeader(_) => timeouts.header,
+        Message::ReplyForBlocks(_) => timeouts.block,
+        Message::OperationsAnnouncement(_)
+        | Message::AskForOperations(_)
+        | Message::Operations(_)
+        | Message::Endorsements(_) => timeouts.large,
+    }
+}
+
 /// Manages connections
 /// One worker per node.
 pub struct NodeWorker {
     /// Protocol configuration.
     cfg: NetworkConfig,
+    /// Write timeouts of the messages, per class of message.
+    write_timeouts: MessageWriteTimeouts,
     /// Node id associated to that worker.
     node_id: NodeId,
     /// Reader for incoming data.
@@ -35,6 +57,8 @@ pub struct NodeWorker {
     node_command_rx: mpsc::Receiver<NodeCommand>,
     /// Channel to send node events.
     node_event_tx: mpsc::Sender<NodeEvent>,
+    /// Bytes exchanged with the peers, shared with the network worker.
+    bandwidth_stats: SharedBandwidthStats,
 }
 
 impl NodeWorker {
@@ -42,29 +66,36 @@ impl NodeWorker {
     ///
     /// # Arguments
     /// * `cfg`: Network configuration.
+    /// * `write_timeouts`: Write timeouts of the messages, per class of message.
     /// * `node_id`: Node id associated to that worker.
     /// * `socket_reader`: Reader for incoming data.
     /// * `socket_writer`: Writer for sending data.
     /// * `node_command_rx`: Channel to receive node commands.
     /// * `node_event_tx`: Channel to send node events.
+    /// * `bandwidth_stats`: Bytes exchanged with the peers, shared with the network worker.
     /// * `storage`: Shared storage.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         cfg: NetworkConfig,
+        write_timeouts: MessageWriteTimeouts,
         node_id: NodeId,
         socket_reader: ReadBinder,
         socket_writer: WriteBinder,
         node_command_tx: mpsc::Sender<NodeCommand>,
         node_command_rx: mpsc::Receiver<NodeCommand>,
         node_event_tx: mpsc::Sender<NodeEvent>,
+        bandwidth_stats: SharedBandwidthStats,
     ) -> NodeWorker {
         NodeWorker {
             cfg,
+            write_timeouts,
             node_id,
             socket_reader,
             socket_writer_opt: Some(socket_writer),
             node_command_tx,
             node_command_rx,
             node_event_tx,
+            bandwidth_stats,
         }
     }
 
@@ -76,15 +107,19 @@ impl NodeWorker {
             )
         })?;
 
+        let write_timeouts = self.write_timeouts;
+        let writer_bandwidth_stats = self.bandwidth_stats.clone();
         let node_writer_handle = tokio::spawn(async move {
             node_writer_handle(
                 &mut socket_writer,
                 &mut self.node_command_rx,
-                self.cfg.message_timeout,
+                write_timeouts,
+                writer_bandwidth_stats,
                 self.node_id,
                 self.cfg.max_ask_blocks,
                 self.cfg.max_operations_per_message,
                 self.cfg.max_endorsements_per_message,
+                self.cfg.max_node_outstanding_bytes,
             )
             .await
         });
@@ -95,6 +130,7 @@ impl NodeWorker {
             node_reader_handle(
                 &mut self.socket_reader,
                 &mut self.node_event_tx,
+                self.bandwidth_stats,
                 self.node_id,
                 self.cfg.max_send_wait_node_event,
             )
@@ -184,130 +220,185 @@ impl NodeWorker {
     }
 }
 
-/// Handle incoming node command, convert to message(s) and write that to socket
+/// Handle incoming node command, convert to message(s) and write that to socket.
+/// The messages are written by order of priority, see `SendQueue`.
+#[allow(clippy::too_many_arguments)]
 async fn node_writer_handle(
     socket_writer: &mut WriteBinder,
     node_command_rx: &mut mpsc::Receiver<NodeCommand>,
-    write_timeout: MassaTime,
+    write_timeouts: MessageWriteTimeouts,
+    bandwidth_stats: SharedBandwidthStats,
     node_id: NodeId,
     max_ask_blocks: u32,
     max_operations_per_message: u32,
     max_endorsements_per_message: u32,
+    max_outstanding_bytes: u64,
 ) -> ConnectionClosureReason {
     let mut exit_reason = ConnectionClosureReason::Normal;
+    let mut send_queue = SendQueue::new(max_outstanding_bytes);
 
     'writer_loop: loop {
-        let messages_: Option<Vec<Message>> = match node_command_rx.recv().await {
-            Some(NodeCommand::Close(r)) => {
-                exit_reason = r;
-                None
-            }
-            Some(NodeCommand::SendPeerList(ip_vec)) => {
-                massa_trace!("node_worker.run_loop. send Message::PeerList", {"peerlist": ip_vec, "node": node_id});
-                Some(vec![Message::PeerList(ip_vec)])
-            }
-            Some(NodeCommand::SendBlockHeader(header)) => {
-                massa_trace!("node_worker.run_loop. send Message::BlockHeader", {"hash": header.id, "node": node_id});
-                Some(vec![Message::BlockHeader(header)])
-            }
-            Some(NodeCommand::AskForBlocks(list)) => {
-                // cut hash list on sub list if exceed max_ask_blocks_per_message
-                massa_trace!("node_worker.run_loop. send Message::AskForBlocks", {"hashlist": list, "node": node_id});
-                let messages = list
-                    .chunks(max_ask_blocks as usize)
-                    .map(|to_send| Message::AskForBlocks(to_send.to_vec()))
-                    .collect();
-                Some(messages)
-            }
-            Some(NodeCommand::ReplyForBlocks(list)) => {
-                // cut hash list on sub list if exceed max_ask_blocks_per_message
-                massa_trace!("node_worker.run_loop. send Message::ReplyForBlocks", {"hashlist": list, "node": node_id});
-                let messages = list
-                    .chunks(max_ask_blocks as usize)
-                    .map(|to_send| Message::ReplyForBlocks(to_send.to_vec()))
-                    .collect();
-                Some(messages)
-            }
-            Some(NodeCommand::SendOperations(operations)) => {
-                massa_trace!("node_worker.run_loop. send Message::SendOperations", {"node": node_id, "operations": operations});
-                let messages = operations
-                    .chunks(max_operations_per_message as usize)
-                    .map(|to_send| Message::Operations(to_send.to_vec()))
-                    .collect();
-                Some(messages)
-            }
-            Some(NodeCommand::SendOperationAnnouncements(operation_prefix_ids)) => {
-                massa_trace!("node_worker.run_loop. send Message::OperationsAnnouncement", {"node": node_id, "operation_ids": operation_prefix_ids});
-                let messages = operation_prefix_ids
-                    .into_iter()
-                    .chunks(max_operations_per_message as usize)
-                    .into_iter()
-                    .map(|chunk| chunk.collect())
-                    .map(Message::OperationsAnnouncement)
-                    .collect();
-                Some(messages)
-            }
-            Some(NodeCommand::AskForOperations(operation_prefix_ids)) => {
-                massa_trace!(
-                    "node_worker.run_loop. send Message::AskForOperations",
-                    {"node": node_id, "operation_ids": operation_prefix_ids}
-                );
-                let messages = operation_prefix_ids
-                    .into_iter()
-                    .chunks(max_operations_per_message as usize)
-                    .into_iter()
-                    .map(|chunk| chunk.collect())
-                    .map(Message::AskForOperations)
-                    .collect();
-                Some(messages)
-            }
-            Some(NodeCommand::SendEndorsements(endorsements)) => {
-                massa_trace!("node_worker.run_loop. send Message::SendEndorsements", {"node": node_id, "endorsements": endorsements});
-                // cut endorsement list if it exceed max_endorsements_per_message
-                let messages = endorsements
-                    .chunks(max_endorsements_per_message as usize)
-                    .map(|endos| Message::Endorsements(endos.to_vec()))
-                    .collect();
-                Some(messages)
-            }
-            Some(NodeCommand::AskPeerList) => Some(vec![Message::AskPeerList]),
-            None => {
-                // Note: this should never happen,
-                // since it implies the network worker dropped its node command sender
-                // before having shut-down the node and joined on its handle.
-                exit_reason = ConnectionClosureReason::Failed;
-                break 'writer_loop;
-            }
-        };
-
-        if messages_.is_none() {
-            break;
-        }
-        // safe to unwrap here
-        let messages = messages_.unwrap();
-
-        for msg in messages.iter() {
-            match timeout(write_timeout.to_duration(), socket_writer.send(msg)).await {
-                Err(err) => {
-                    massa_trace!("node_worker.run_loop.loop.writer_command_rx.recv.send.timeout", {
-                        "node": node_id,
-                    });
-                    debug!("Node data writing timed out: {}", err);
+        // wait for a command only if no message is waiting to be written,
+        // then queue the messages of all the commands received meanwhile
+        let mut next_command = if send_queue.is_empty() {
+            match node_command_rx.recv().await {
+                Some(command) => Some(command),
+                None => {
+                    // Note: this should never happen,
+                    // since it implies the network worker dropped its node command sender
+                    // before having shut-down the node and joined on its handle.
                     exit_reason = ConnectionClosureReason::Failed;
                     break 'writer_loop;
                 }
-                Ok(Err(err)) => {
-                    massa_trace!("node_worker.run_loop.loop.writer_command_rx.recv.send.error", {
-                        "node": node_id, "err":  format!("{}", err),
-                    });
-                    debug!("Node data writing error: {:?}", err);
-                    exit_reason = ConnectionClosureReason::Failed;
+            }
+        } else {
+            node_command_rx.try_recv().ok()
+        };
+        while let Some(command) = next_command {
+            let messages: Vec<Message> = match command {
+                NodeCommand::Close(r) => {
+                    exit_reason = r;
                     break 'writer_loop;
                 }
-                Ok(Ok(id)) => {
-                    massa_trace!("node_worker.run_loop.loop.writer_command_rx.recv.send.ok", {
-                                    "node": node_id, "msg_id": id});
+                NodeCommand::SendPeerList(ip_vec) => {
+                    massa_trace!("node_worker.run_loop. send Message::PeerList", {"peerlist": ip_vec, "node": node_id});
+                    vec![Message::PeerList(ip_vec)]
+                }
+                NodeCommand::SendBlockHeader(header) => {
+                    massa_trace!("node_worker.run_loop. send Message::BlockHeader", {"hash": header.id, "node": node_id});
+                    vec![Message::BlockHeader(header)]
+                }
+                NodeCommand::AskForBlocks(list) => {
+                    // cut hash list on sub list if exceed max_ask_blocks_per_message
+                    massa_trace!("node_worker.run_loop. send Message::AskForBlocks", {"hashlist": list, "node": node_id});
+                    list.chunks(max_ask_blocks as usize)
+                        .map(|to_send| Message::AskForBlocks(to_send.to_vec()))
+                        .collect()
                 }
+                NodeCommand::ReplyForBlocks(list) => {
+                    // cut hash list on sub list if exceed max_ask_blocks_per_message
+                    massa_trace!("node_worker.run_loop. send Message::ReplyForBlocks", {"hashlist": list, "node": node_id});
+                    list.chunks(max_ask_blocks as usize)
+                        .map(|to_send| Message::ReplyForBlocks(to_send.to_vec()))
+                        .collect()
+                }
+                NodeCommand::SendOperations(operations) => {
+                    massa_trace!("node_worker.run_loop. send Message::SendOperations", {"node": node_id, "operations": operations});
+                    operations
+                        .chunks(max_operations_per_message as usize)
+                        .map(|to_send| Message::Operations(to_send.to_vec()))
+                        .collect()
+                }
+                NodeCommand::SendOperationAnnouncements(operation_prefix_ids) => {
+                    massa_trace!("node_worker.run_loop. send Message::OperationsAnnouncement", {"node": node_id, "operation_ids": operation_prefix_ids});
+                    operation_prefix_ids
+                        .into_iter()
+                        .chunks(max_operations_per_message as usize)
+                        .into_iter()
+                        .map(|chunk| chunk.collect())
+                        .map(Message::OperationsAnnouncement)
+                        .collect()
+                }
+                NodeCommand::AskForOperations(operation_prefix_ids) => {
+                    massa_trace!(
+                        "node_worker.run_loop. send Message::AskForOperations",
+                        {"node": node_id, "operation_ids": operation_prefix_ids}
+                    );
+                    operation_prefix_ids
+                        .into_iter()
+                        .chunks(max_operations_per_message as usize)
+                        .into_iter()
+                        .map(|chunk| chunk.collect())
+                        .map(Message::AskForOperations)
+                        .collect()
+                }
+                NodeCommand::SendEndorsements(endorsements) => {
+                    massa_trace!("node_worker.run_loop. send Message::SendEndorsements", {"node": node_id, "endorsements": endorsements});
+                    // cut endorsement list if it exceed max_endorsements_per_message
+                    endorsements
+                        .chunks(max_endorsements_per_message as usize)
+                        .map(|endos| Message::Endorsements(endos.to_vec()))
+                        .collect()
+                }
+                NodeCommand::AskPeerList => vec![Message::AskPeerList],
+            };
+
+            for msg in messages.iter() {
+                let bytes = match WriteBinder::serialize(msg) {
+                    Ok(bytes) => bytes,
+                    Err(err) => {
+                        debug!("Node message serialization error: {:?}", err);
+                        exit_reason = ConnectionClosureReason::Failed;
+                        break 'writer_loop;
+                    }
+                };
+                let queued = QueuedMessage {
+                    type_name: msg.type_name(),
+                    write_timeout: write_timeout(&write_timeouts, msg),
+                    bytes,
+                };
+                let dropped = match send_queue.push(SendLane::of(msg), queued) {
+                    PushResult::Queued(evicted) => evicted,
+                    PushResult::Dropped => 1,
+                    PushResult::BudgetExceeded => {
+                        debug!(
+                            "Node {} does not read the consensus messages fast enough, closing the connection",
+                            node_id
+                        );
+                        exit_reason = ConnectionClosureReason::Failed;
+                        break 'writer_loop;
+                    }
+                };
+                if dropped > 0 {
+                    massa_trace!("node_worker.run_loop.writer.dropped", {"node": node_id, "count": dropped});
+                    massa_metrics::inc_counter(
+                        "massa_network_messages_dropped_total",
+                        &[],
+                        dropped as u64,
+                    );
+                }
+            }
+            next_command = node_command_rx.try_recv().ok();
+        }
+
+        let Some(queued) = send_queue.pop() else {
+            continue;
+        };
+        match timeout(
+            queued.write_timeout.to_duration(),
+            socket_writer.send_serialized(queued.bytes),
+        )
+        .await
+        {
+            Err(err) => {
+                massa_trace!("node_worker.run_loop.loop.writer_command_rx.recv.send.timeout", {
+                    "node": node_id,
+                });
+                debug!("Node data writing timed out: {}", err);
+                exit_reason = ConnectionClosureReason::Failed;
+                break 'writer_loop;
+            }
+            Ok(Err(err)) => {
+                massa_trace!("node_worker.run_loop.loop.writer_command_rx.recv.send.error", {
+                    "node": node_id, "err":  format!("{}", err),
+                });
+                debug!("Node data writing error: {:?}", err);
+                exit_reason = ConnectionClosureReason::Failed;
+                break 'writer_loop;
+            }
+            Ok(Ok(id)) => {
+                massa_trace!("node_worker.run_loop.loop.writer_command_rx.recv.send.ok", {
+                                "node": node_id, "msg_id": id});
+                massa_metrics::inc_counter(
+                    "massa_network_messages_sent_total",
+                    &[("type", &queued.type_name)],
+                    1,
+                );
+                let sent_bytes = socket_writer.take_transferred_bytes();
+                massa_metrics::inc_counter("massa_network_sent_bytes_total", &[], sent_bytes);
+                bandwidth_stats
+                    .lock()
+                    .note_sent(node_id, queued.type_name, sent_bytes);
             }
         }
     }
@@ -320,6 +411,7 @@ async fn node_writer_handle(
 async fn node_reader_handle(
     socket_reader: &mut ReadBinder,
     node_event_tx: &mut Sender<NodeEvent>,
+    bandwidth_stats: SharedBandwidthStats,
     node_id: NodeId,
     max_send_wait: MassaTime,
 ) -> ConnectionClosureReason {
@@ -331,6 +423,20 @@ async fn node_reader_handle(
                 massa_trace!("node_worker.run_loop. receive self.socket_reader.next()", {
                     "index": index
                 });
+                massa_metrics::inc_counter(
+                    "massa_network_messages_received_total",
+                    &[("type", &msg.type_name())],
+                    1,
+                );
+                let received_bytes = socket_reader.take_transferred_bytes();
+                massa_metrics::inc_counter(
+                    "massa_network_received_bytes_total",
+                    &[],
+                    received_bytes,
+                );
+                bandwidth_stats
+                    .lock()
+                    .note_received(node_id, msg.type_name(), received_bytes);
                 match msg {
                     Message::BlockHeader(header) => {
                         massa_trace!(
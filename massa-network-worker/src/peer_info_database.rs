@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::seed_peers::fetch_all_seed_peers;
 use enum_map::EnumMap;
 use itertools::Itertools;
 use massa_logging::massa_trace;
@@ -13,7 +14,7 @@ use massa_network_exports::PeerType;
 use massa_time::MassaTime;
 use serde_json::json;
 use std::cmp::Reverse;
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, HashMap};
 use std::net::IpAddr;
 use std::path::Path;
 use tokio::sync::watch;
@@ -56,6 +57,10 @@ async fn dump_peers(
                 "last_alive": peer.last_alive,
                 "last_failure": peer.last_failure,
                 "advertised": peer.advertised,
+                "connection_successes": peer.connection_successes,
+                "connection_failures": peer.connection_failures,
+                "consecutive_failures": peer.consecutive_failures,
+                "latency": peer.latency,
             })
         })
         .collect();
@@ -197,8 +202,9 @@ impl PeerInfoDatabase {
             );
         }
 
-        // cleanup
-        cleanup_peers(cfg, &mut peers, None, cfg.ban_timeout)?;
+        // cleanup, adding the peers of the seed lists
+        let seed_peers = fetch_all_seed_peers(cfg).await;
+        cleanup_peers(cfg, &mut peers, Some(&seed_peers), cfg.ban_timeout)?;
 
         // setup saver
         let peers_file = cfg.peers_file.clone();
@@ -326,6 +332,43 @@ impl PeerInfoDatabase {
         self.request_dump()
     }
 
+    /// Writes the peer database to a file, in the format of the peers file.
+    ///
+    /// # Argument
+    /// `file_path`: path of the written file
+    pub async fn export_peers(&self, file_path: &Path) -> Result<(), NetworkError> {
+        dump_peers(&self.peers, file_path).await
+    }
+
+    /// Merges the peers of a file in the format of the peers file with our peers.
+    /// Already known peers are left untouched. A dump is requested afterwards.
+    ///
+    /// # Argument
+    /// `file_path`: path of the file to import
+    ///
+    /// # Returns
+    /// The number of previously unknown peers found in the file
+    pub async fn import_peers(&mut self, file_path: &Path) -> Result<usize, NetworkError> {
+        let imported_peers =
+            serde_json::from_str::<Vec<PeerInfo>>(&tokio::fs::read_to_string(file_path).await?)?;
+        let mut new_peer_count = 0;
+        for mut peer in imported_peers {
+            peer.cleanup();
+            if let Entry::Vacant(entry) = self.peers.entry(peer.ip) {
+                entry.insert(peer);
+                new_peer_count += 1;
+            }
+        }
+        cleanup_peers(
+            &self.network_settings,
+            &mut self.peers,
+            None,
+            self.network_settings.ban_timeout,
+        )?;
+        self.request_dump()?;
+        Ok(new_peer_count)
+    }
+
     ////////////////////////////////
     // high level peer management //
     ////////////////////////////////
@@ -433,6 +476,7 @@ impl PeerInfoDatabase {
                 // Can unwrap because we checked above that there is a peer.
                 let peer = self.peers.get_mut(&ip).unwrap();
                 peer.active_out_connection_attempts += 1;
+                peer.last_attempt = Some(MassaTime::now()?);
                 Ok(peer.peer_type)
             } else {
                 Err(NetworkError::PeerConnectionError(
@@ -442,6 +486,7 @@ impl PeerInfoDatabase {
         } else if self.can_try_new_out_connection(Default::default()) {
             let mut peer = PeerInfo::new(ip, false);
             peer.active_out_connection_attempts += 1;
+            peer.last_attempt = Some(MassaTime::now()?);
             self.peers.insert(ip, peer);
             Ok(peer.peer_type)
         } else {
@@ -453,39 +498,48 @@ impl PeerInfoDatabase {
         self.update()
     }
 
-    /// Sets the peer status as alive.
+    /// Sets the peer status as alive and resets its retry backoff.
     /// Requests a subsequent dump.
     ///
     /// # Argument
     /// * ip : ip address of the considered peer.
     pub fn peer_alive(&mut self, ip: &IpAddr) -> Result<(), NetworkError> {
         let ip = ip.to_canonical();
-        self.peers
-            .get_mut(&ip)
-            .ok_or_else(|| {
-                NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::PeerInfoNotFoundError(ip),
-                )
-            })?
-            .last_alive = Some(MassaTime::now()?);
+        let peer = self.peers.get_mut(&ip).ok_or_else(|| {
+            NetworkError::PeerConnectionError(NetworkConnectionErrorType::PeerInfoNotFoundError(ip))
+        })?;
+        peer.last_alive = Some(MassaTime::now()?);
+        peer.consecutive_failures = 0;
         self.request_dump()
     }
 
-    /// Sets the peer status as failed.
+    /// Notes whether the peer accepts QUIC connections, as announced during the handshake.
+    /// Saved with the next dump.
+    ///
+    /// # Arguments
+    /// * ip : ip address of the considered peer.
+    /// * quic : whether the peer accepts QUIC connections
+    pub fn set_peer_quic(&mut self, ip: &IpAddr, quic: bool) -> Result<(), NetworkError> {
+        let ip = ip.to_canonical();
+        let peer = self.peers.get_mut(&ip).ok_or_else(|| {
+            NetworkError::PeerConnectionError(NetworkConnectionErrorType::PeerInfoNotFoundError(ip))
+        })?;
+        peer.quic = quic;
+        Ok(())
+    }
+
+    /// Sets the peer status as failed and increases its retry backoff.
     /// Requests a dump.
     ///
     /// # Argument
     /// * ip : ip address of the considered peer.
     pub fn peer_failed(&mut self, ip: &IpAddr) -> Result<(), NetworkError> {
         let ip = ip.to_canonical();
-        self.peers
-            .get_mut(&ip)
-            .ok_or_else(|| {
-                NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::PeerInfoNotFoundError(ip),
-                )
-            })?
-            .last_failure = Some(MassaTime::now()?);
+        let peer = self.peers.get_mut(&ip).ok_or_else(|| {
+            NetworkError::PeerConnectionError(NetworkConnectionErrorType::PeerInfoNotFoundError(ip))
+        })?;
+        peer.last_failure = Some(MassaTime::now()?);
+        peer.consecutive_failures = peer.consecutive_failures.saturating_add(1);
         self.request_dump()
     }
 
@@ -634,6 +688,7 @@ impl PeerInfoDatabase {
             })?;
             peer.active_out_connection_attempts -= 1;
             peer.advertised = true; // we just connected to it. Assume advertised.
+            peer.record_connection_success(MassaTime::now()?);
 
             if peer.banned {
                 peer.last_failure = Some(MassaTime::now()?);
@@ -679,6 +734,8 @@ impl PeerInfoDatabase {
             })?;
             peer.active_out_connection_attempts -= 1;
             peer.last_failure = Some(MassaTime::now()?);
+            peer.consecutive_failures = peer.consecutive_failures.saturating_add(1);
+            peer.record_connection_failure();
             let pt = peer.peer_type;
             if !peer.is_active() && peer.peer_type == PeerType::Standard {
                 self.update()?;
@@ -764,7 +821,7 @@ impl PeerInfoDatabase {
     // public getters //
     ////////////////////
 
-    /// Sorts peers by `( last_failure, rev(last_success) )`
+    /// Ranks the peers that are ready to be retried by decreasing score (see `PeerInfo::score`)
     /// and returns as many peers as there are available slots to attempt outgoing connections to.
     pub fn get_out_connection_candidate_ips(&self) -> Result<Vec<IpAddr>, NetworkError> {
         let mut connections = vec![];
@@ -852,14 +909,18 @@ impl PeerInfoDatabase {
             }
             p.is_peer_ready(self.wakeup_interval, now)
         };
-        let mut res: Vec<_> = self
-            .peers
-            .values()
-            .filter(f)
+        let mut res: Vec<_> = self.peers.values().filter(f).collect();
+        // best scores first, then most recently alive
+        res.sort_unstable_by(|a, b| {
+            b.score()
+                .total_cmp(&a.score())
+                .then_with(|| b.last_alive.cmp(&a.last_alive))
+        });
+        Ok(res
+            .into_iter()
             .take(available_slots)
-            .collect();
-        res.sort_unstable_by_key(|&p| (p.last_failure, std::cmp::Reverse(p.last_alive)));
-        Ok(res.into_iter().map(|p| p.ip).collect())
+            .map(|p| p.ip)
+            .collect())
     }
 
     fn get_peer_type(&self, ip: &IpAddr) -> Option<PeerType> {
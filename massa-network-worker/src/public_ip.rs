@@ -0,0 +1,107 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Detection of our routable IP from the addresses observed by our peers.
+//!
+//! During handshakes, each peer tells us the IP it sees for our connection.
+//! When enough distinct peers observe the same global IP, it is elected and advertised in our peer lists,
+//! so that nodes behind a NAT with a forwarded port become reachable without configuring `routable_ip`.
+
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+
+/// Number of most recent observations taken into account
+const MAX_OBSERVATIONS: usize = 100;
+
+/// Elects our routable IP from the addresses observed by our peers
+pub(crate) struct PublicIpVoter {
+    /// latest observed IP of each peer, oldest first
+    observations: VecDeque<(IpAddr, IpAddr)>,
+    /// number of distinct peers that must observe the same IP for it to be elected
+    min_votes: usize,
+    /// currently elected IP
+    elected: Option<IpAddr>,
+}
+
+impl PublicIpVoter {
+    /// Creates a new voter
+    ///
+    /// # Argument
+    /// * `min_votes`: number of distinct peers that must observe the same IP for it to be elected
+    pub fn new(min_votes: usize) -> Self {
+        PublicIpVoter {
+            observations: VecDeque::new(),
+            min_votes,
+            elected: None,
+        }
+    }
+
+    /// Records the IP observed for us by a peer.
+    /// Non-global observed IPs are ignored.
+    ///
+    /// # Returns
+    /// The newly elected IP if the election result changed
+    pub fn vote(&mut self, peer_ip: IpAddr, observed_ip: IpAddr) -> Option<IpAddr> {
+        let peer_ip = peer_ip.to_canonical();
+        let observed_ip = observed_ip.to_canonical();
+        if self.min_votes == 0 || !observed_ip.is_global() {
+            return None;
+        }
+
+        // only keep the latest observation of each peer
+        self.observations.retain(|(ip, _)| *ip != peer_ip);
+        self.observations.push_back((peer_ip, observed_ip));
+        if self.observations.len() > MAX_OBSERVATIONS {
+            self.observations.pop_front();
+        }
+
+        // count the votes
+        let mut votes: HashMap<IpAddr, usize> = HashMap::new();
+        for (_, ip) in self.observations.iter() {
+            *votes.entry(*ip).or_default() += 1;
+        }
+        let winner = votes
+            .into_iter()
+            .filter(|(_, count)| *count >= self.min_votes)
+            .max_by_key(|(ip, count)| (*count, Some(*ip) == self.elected))
+            .map(|(ip, _)| ip);
+        match winner {
+            Some(ip) if self.elected != Some(ip) => {
+                self.elected = Some(ip);
+                Some(ip)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_public_ip_vote() {
+    let observed: IpAddr = "169.202.0.1".parse().unwrap();
+    let other: IpAddr = "169.202.0.2".parse().unwrap();
+    let mut voter = PublicIpVoter::new(2);
+
+    // a single peer cannot elect an IP, even by voting twice
+    assert_eq!(voter.vote("169.202.0.11".parse().unwrap(), observed), None);
+    assert_eq!(voter.vote("169.202.0.11".parse().unwrap(), observed), None);
+    assert_eq!(
+        voter.vote("169.202.0.12".parse().unwrap(), observed),
+        Some(observed)
+    );
+
+    // local IPs are ignored
+    assert_eq!(
+        voter.vote(
+            "169.202.0.13".parse().unwrap(),
+            "192.168.0.1".parse().unwrap()
+        ),
+        None
+    );
+
+    // a tie keeps the elected IP, a majority changes it
+    assert_eq!(voter.vote("169.202.0.13".parse().unwrap(), other), None);
+    assert_eq!(voter.vote("169.202.0.14".parse().unwrap(), other), None);
+    assert_eq!(
+        voter.vote("169.202.0.15".parse().unwrap(), other),
+        Some(other)
+    );
+}
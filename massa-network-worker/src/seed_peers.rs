@@ -0,0 +1,162 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Fetches signed seed peer lists over HTTPS.
+//!
+//! A seed peer list is a JSON document `{"peers": [...], "signature": "..."}`
+//! where `signature` signs the hash of the peer IPs joined by newlines.
+//! It is used at startup to find peers without hard-coding their IPs in the config.
+
+use hyper::body::HttpBody;
+use massa_hash::Hash;
+use massa_network_exports::{NetworkConfig, NetworkError};
+use massa_signature::{PublicKey, Signature};
+use serde::Deserialize;
+use std::net::IpAddr;
+use tracing::{info, warn};
+
+/// Maximum size of a seed peer list body, far above the size of the peer lists a node keeps
+const MAX_SEED_PEERS_BODY_SIZE: usize = 1_048_576;
+
+/// Seed peer list as served by a seed URL
+#[derive(Deserialize)]
+struct SignedSeedPeers {
+    /// seed peer IPs
+    peers: Vec<IpAddr>,
+    /// signature of `seed_peers_hash(peers)`
+    signature: Signature,
+}
+
+/// Computes the hash signed by the publisher of a seed peer list
+pub(crate) fn seed_peers_hash(peers: &[IpAddr]) -> Hash {
+    let text = peers
+        .iter()
+        .map(|ip| ip.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Hash::compute_from(text.as_bytes())
+}
+
+/// Parses a seed peer list and checks its signature against `public_key`
+pub(crate) fn parse_seed_peers(
+    body: &[u8],
+    public_key: &PublicKey,
+) -> Result<Vec<IpAddr>, NetworkError> {
+    let seed_peers: SignedSeedPeers = serde_json::from_slice(body)?;
+    public_key.verify_signature(&seed_peers_hash(&seed_peers.peers), &seed_peers.signature)?;
+    Ok(seed_peers.peers)
+}
+
+/// Downloads a seed peer list from an HTTPS URL and checks its signature
+async fn fetch_seed_peers(url: &str, public_key: &PublicKey) -> Result<Vec<IpAddr>, NetworkError> {
+    let uri: hyper::Uri = url
+        .parse()
+        .map_err(|err| NetworkError::SeedPeersError(format!("invalid URL: {}", err)))?;
+    let connector = hyper_rustls::HttpsConnectorBuilder::new()
+        .with_native_roots()
+        .https_only()
+        .enable_http1()
+        .build();
+    let client: hyper::Client<_, hyper::Body> = hyper::Client::builder().build(connector);
+    let response = client
+        .get(uri)
+        .await
+        .map_err(|err| NetworkError::SeedPeersError(err.to_string()))?;
+    if !response.status().is_success() {
+        return Err(NetworkError::SeedPeersError(format!(
+            "unexpected HTTP status {}",
+            response.status()
+        )));
+    }
+    let body = read_body(response.into_body(), MAX_SEED_PEERS_BODY_SIZE).await?;
+    parse_seed_peers(&body, public_key)
+}
+
+/// Reads an HTTP body, failing as soon as it is known to exceed `max_size` bytes
+async fn read_body(mut body: hyper::Body, max_size: usize) -> Result<Vec<u8>, NetworkError> {
+    let too_large = || NetworkError::SeedPeersError(format!("body larger than {} bytes", max_size));
+    // the lower bound is the announced `Content-Length`, if any
+    if body.size_hint().lower() > max_size as u64 {
+        return Err(too_large());
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|err| NetworkError::SeedPeersError(err.to_string()))?;
+        if bytes.len().saturating_add(chunk.len()) > max_size {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+/// Fetches the seed peer lists configured in `seed_peer_lists`.
+/// Lists that cannot be downloaded in time or that are not properly signed are ignored with a warning.
+pub(crate) async fn fetch_all_seed_peers(cfg: &NetworkConfig) -> Vec<IpAddr> {
+    let mut peers = Vec::new();
+    for (url, public_key) in &cfg.seed_peer_lists {
+        match tokio::time::timeout(
+            cfg.connect_timeout.to_duration(),
+            fetch_seed_peers(url, public_key),
+        )
+        .await
+        {
+            Ok(Ok(seed_peers)) => {
+                info!("fetched {} seed peers from {}", seed_peers.len(), url);
+                peers.extend(seed_peers);
+            }
+            Ok(Err(err)) => warn!("could not fetch seed peers from {}: {}", url, err),
+            Err(_) => warn!("could not fetch seed peers from {}: timeout", url),
+        }
+    }
+    peers
+}
+
+#[test]
+fn test_seed_peers_signature() {
+    use massa_signature::KeyPair;
+    use serde_json::json;
+
+    let keypair = KeyPair::generate();
+    let peers: Vec<IpAddr> = vec![
+        "169.202.0.11".parse().unwrap(),
+        "2001:db8::1".parse().unwrap(),
+    ];
+    let signature = keypair.sign(&seed_peers_hash(&peers)).unwrap();
+
+    let body = json!({ "peers": peers, "signature": signature }).to_string();
+    assert_eq!(
+        parse_seed_peers(body.as_bytes(), &keypair.get_public_key()).unwrap(),
+        peers
+    );
+
+    // a list signed by another key is rejected
+    assert!(parse_seed_peers(body.as_bytes(), &KeyPair::generate().get_public_key()).is_err());
+
+    // a list modified after signing is rejected
+    let tampered_body = json!({ "peers": peers[..1], "signature": signature }).to_string();
+    assert!(parse_seed_peers(tampered_body.as_bytes(), &keypair.get_public_key()).is_err());
+}
+
+#[tokio::test]
+async fn test_seed_peers_body_size() {
+    let body = vec![b'a'; 100];
+    assert_eq!(
+        read_body(hyper::Body::from(body.clone()), 100)
+            .await
+            .unwrap(),
+        body
+    );
+    assert!(read_body(hyper::Body::from(body), 99).await.is_err());
+
+    // streamed bodies without a known size are cut once the limit is reached
+    let (mut sender, body) = hyper::Body::channel();
+    tokio::spawn(async move {
+        for _ in 0..2 {
+            let chunk = hyper::body::Bytes::from(vec![b'a'; 60]);
+            if sender.send_data(chunk).await.is_err() {
+                break;
+            }
+        }
+    });
+    assert!(read_body(body, 100).await.is_err());
+}
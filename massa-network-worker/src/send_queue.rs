@@ -0,0 +1,192 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Prioritized queue of the messages waiting to be written to a peer.
+//!
+//! Consensus messages (block headers, block asks and replies, endorsements) are written before
+//! operation messages, which are written before peer lists, so that a slow peer does not delay
+//! block propagation behind peer-list chatter.
+//!
+//! The serialized size of the queued messages is bounded per peer. Once the budget is exceeded,
+//! operation messages and peer lists are dropped. Consensus messages evict the queued messages of
+//! lower priority to fit, and the connection is closed if they still do not fit.
+
+use crate::messages::Message;
+use massa_time::MassaTime;
+use std::collections::VecDeque;
+
+/// Priority of a message, highest first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum SendLane {
+    /// block headers, block asks and replies, endorsements
+    Consensus = 0,
+    /// operations, operation announcements and asks
+    Operations = 1,
+    /// peer lists and peer list asks
+    PeerLists = 2,
+}
+
+/// Number of lanes
+const LANE_COUNT: usize = 3;
+
+impl SendLane {
+    /// Lane of a message sent by the writer task
+    pub(crate) fn of(msg: &Message) -> Self {
+        match msg {
+            Message::BlockHeader(_)
+            | Message::AskForBlocks(_)
+            | Message::ReplyForBlocks(_)
+            | Message::Endorsements(_) => SendLane::Consensus,
+            Message::OperationsAnnouncement(_)
+            | Message::AskForOperations(_)
+            | Message::Operations(_) => SendLane::Operations,
+            Message::HandshakeInitiation { .. }
+            | Message::HandshakeReply { .. }
+            | Message::AskPeerList
+            | Message::PeerList(_) => SendLane::PeerLists,
+        }
+    }
+}
+
+/// Serialized message waiting to be written
+pub(crate) struct QueuedMessage {
+    /// type of the message, for metrics and bandwidth stats
+    pub(crate) type_name: &'static str,
+    /// timeout of the write
+    pub(crate) write_timeout: MassaTime,
+    /// message serialized by `WriteBinder::serialize`
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// Result of `SendQueue::push`
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum PushResult {
+    /// the message was queued, after evicting this number of messages of lower priority
+    Queued(usize),
+    /// the message was dropped because the budget is exceeded
+    Dropped,
+    /// a consensus message does not fit in the budget: the connection should be closed
+    BudgetExceeded,
+}
+
+/// Messages waiting to be written to a peer, by lane
+pub(crate) struct SendQueue {
+    /// queued messages of each lane, oldest at the front
+    lanes: [VecDeque<QueuedMessage>; LANE_COUNT],
+    /// serialized size of the queued messages
+    outstanding_bytes: u64,
+    /// maximum serialized size of the queued messages
+    max_outstanding_bytes: u64,
+}
+
+impl SendQueue {
+    /// Creates an empty queue holding at most `max_outstanding_bytes` bytes of messages
+    pub(crate) fn new(max_outstanding_bytes: u64) -> Self {
+        SendQueue {
+            lanes: Default::default(),
+            outstanding_bytes: 0,
+            max_outstanding_bytes,
+        }
+    }
+
+    /// Returns true if no message is waiting to be written
+    pub(crate) fn is_empty(&self) -> bool {
+        self.lanes.iter().all(|lane| lane.is_empty())
+    }
+
+    /// Queues a message in a lane.
+    /// A message is always queued when the queue is empty, whatever its size.
+    pub(crate) fn push(&mut self, lane: SendLane, msg: QueuedMessage) -> PushResult {
+        let size = msg.bytes.len() as u64;
+        let mut evicted = 0;
+        if !self.is_empty()
+            && self.outstanding_bytes.saturating_add(size) > self.max_outstanding_bytes
+        {
+            if lane != SendLane::Consensus {
+                return PushResult::Dropped;
+            }
+            // make room by evicting the newest messages of lower priority
+            for lower_lane in self.lanes[SendLane::Operations as usize..].iter_mut().rev() {
+                while self.outstanding_bytes.saturating_add(size) > self.max_outstanding_bytes {
+                    let Some(evicted_msg) = lower_lane.pop_back() else {
+                        break;
+                    };
+                    self.outstanding_bytes -= evicted_msg.bytes.len() as u64;
+                    evicted += 1;
+                }
+            }
+            if self.outstanding_bytes.saturating_add(size) > self.max_outstanding_bytes {
+                return PushResult::BudgetExceeded;
+            }
+        }
+        self.outstanding_bytes += size;
+        self.lanes[lane as usize].push_back(msg);
+        PushResult::Queued(evicted)
+    }
+
+    /// Takes the oldest message of the highest priority lane
+    pub(crate) fn pop(&mut self) -> Option<QueuedMessage> {
+        let msg = self.lanes.iter_mut().find_map(|lane| lane.pop_front())?;
+        self.outstanding_bytes -= msg.bytes.len() as u64;
+        Some(msg)
+    }
+}
+
+#[test]
+fn test_send_queue() {
+    let msg = |size| QueuedMessage {
+        type_name: "test",
+        write_timeout: MassaTime::from_millis(1000),
+        bytes: vec![0; size],
+    };
+    let size = |msg: Option<QueuedMessage>| msg.unwrap().bytes.len();
+    let mut queue = SendQueue::new(100);
+
+    // consensus messages are popped first, then operations, then peer lists
+    assert_eq!(
+        queue.push(SendLane::PeerLists, msg(20)),
+        PushResult::Queued(0)
+    );
+    assert_eq!(
+        queue.push(SendLane::Operations, msg(30)),
+        PushResult::Queued(0)
+    );
+    assert_eq!(
+        queue.push(SendLane::Consensus, msg(40)),
+        PushResult::Queued(0)
+    );
+    // lower priority messages are dropped once the budget is exceeded
+    assert_eq!(
+        queue.push(SendLane::Operations, msg(20)),
+        PushResult::Dropped
+    );
+    assert_eq!(size(queue.pop()), 40);
+    assert_eq!(size(queue.pop()), 30);
+    assert_eq!(size(queue.pop()), 20);
+    assert!(queue.pop().is_none());
+
+    // a message larger than the budget is queued when the queue is empty
+    assert_eq!(
+        queue.push(SendLane::Operations, msg(150)),
+        PushResult::Queued(0)
+    );
+    assert!(queue.pop().is_some());
+
+    // consensus messages evict the lower priority ones, newest and lowest priority first
+    queue.push(SendLane::Operations, msg(30));
+    queue.push(SendLane::Operations, msg(31));
+    queue.push(SendLane::PeerLists, msg(32));
+    assert_eq!(
+        queue.push(SendLane::Consensus, msg(50)),
+        PushResult::Queued(2)
+    );
+    assert_eq!(size(queue.pop()), 50);
+    assert_eq!(size(queue.pop()), 30);
+    assert!(queue.is_empty());
+
+    // the connection is closed if consensus messages do not fit anymore
+    queue.push(SendLane::Consensus, msg(60));
+    assert_eq!(
+        queue.push(SendLane::Consensus, msg(60)),
+        PushResult::BudgetExceeded
+    );
+}
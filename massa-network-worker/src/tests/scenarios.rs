@@ -2,8 +2,10 @@
 
 // To start alone RUST_BACKTRACE=1 cargo test -- --nocapture --test-threads=1
 use super::tools;
+use crate::handshake_worker::{HandshakeReturnType, HandshakeWorker};
 use crate::messages::{Message, MessageDeserializer};
 use crate::node_worker::NodeWorker;
+use crate::start_network_controller;
 use crate::tests::tools::{get_dummy_block_id, get_transaction};
 use crate::NetworkError;
 use crate::NetworkEvent;
@@ -15,20 +17,27 @@ use enum_map::enum_map;
 use enum_map::EnumMap;
 use massa_hash::Hash;
 use massa_models::config::{
-    ENDORSEMENT_COUNT, MAX_ADVERTISE_LENGTH, MAX_ASK_BLOCKS_PER_MESSAGE,
-    MAX_DATASTORE_VALUE_LENGTH, MAX_ENDORSEMENTS_PER_MESSAGE, MAX_FUNCTION_NAME_LENGTH,
-    MAX_MESSAGE_SIZE, MAX_OPERATIONS_PER_BLOCK, MAX_OPERATIONS_PER_MESSAGE,
-    MAX_OPERATION_DATASTORE_ENTRY_COUNT, MAX_OPERATION_DATASTORE_KEY_LENGTH,
-    MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE, THREAD_COUNT,
+    ENDORSEMENT_COUNT, HANDSHAKE_RANDOMNESS_SIZE_BYTES, MAX_ADVERTISE_LENGTH,
+    MAX_ASK_BLOCKS_PER_MESSAGE, MAX_DATASTORE_VALUE_LENGTH, MAX_ENDORSEMENTS_PER_MESSAGE,
+    MAX_FUNCTION_NAME_LENGTH, MAX_MESSAGE_SIZE, MAX_OPERATIONS_PER_BLOCK,
+    MAX_OPERATIONS_PER_MESSAGE, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+    MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE,
+    THREAD_COUNT,
 };
 use massa_models::{
     block::BlockId,
     endorsement::{Endorsement, EndorsementSerializer},
     node::NodeId,
     slot::Slot,
+    version::Version,
     wrapped::WrappedContent,
 };
-use massa_network_exports::{settings::PeerTypeConnectionConfig, NodeCommand, NodeEvent};
+use massa_network_exports::settings::tests::default_testing_network_id;
+use massa_network_exports::test_exports::simulated_network::SimulatedNetwork;
+use massa_network_exports::{
+    settings::PeerTypeConnectionConfig, CompressionAlgorithm, EncryptionMode, NodeCommand,
+    NodeEvent, TransportType,
+};
 use massa_network_exports::{
     AskForBlocksInfo, BlockInfoReply, ConnectionClosureReason, ConnectionId, HandshakeErrorType,
     PeerInfo, PeerType,
@@ -37,6 +46,7 @@ use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use serial_test::serial;
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     time::{Duration, Instant},
@@ -108,12 +118,14 @@ async fn test_node_worker_shutdown() {
     let node_fn_handle = tokio::spawn(async move {
         NodeWorker::new(
             network_conf,
+            Default::default(),
             mock_node_id,
             reader,
             writer,
             node_worker_command_tx,
             node_command_rx,
             node_event_tx,
+            Default::default(),
         )
         .run_loop()
         .await
@@ -140,6 +152,189 @@ async fn test_node_worker_shutdown() {
     node_fn_handle.await.unwrap().unwrap();
 }
 
+/// Test that compressed messages are read back, and that a message compressed
+/// with an algorithm that was not advertised is rejected.
+#[tokio::test]
+#[serial]
+async fn test_binders_message_compression() {
+    let message_deserializer = || {
+        MessageDeserializer::new(
+            THREAD_COUNT,
+            ENDORSEMENT_COUNT,
+            MAX_ADVERTISE_LENGTH,
+            MAX_ASK_BLOCKS_PER_MESSAGE,
+            MAX_OPERATIONS_PER_BLOCK,
+            MAX_OPERATIONS_PER_MESSAGE,
+            MAX_ENDORSEMENTS_PER_MESSAGE,
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+            MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        )
+    };
+    let peer_list = vec![IpAddr::V4(Ipv4Addr::new(169, 202, 0, 11)); 1000];
+
+    for algorithm in [CompressionAlgorithm::Zstd, CompressionAlgorithm::Lz4] {
+        let (duplex_writer, duplex_reader) = tokio::io::duplex(MAX_MESSAGE_SIZE as usize);
+        let (_, write_half) = tokio::io::split(duplex_writer);
+        let (read_half, _) = tokio::io::split(duplex_reader);
+        let mut writer = WriteBinder::new(write_half, f64::INFINITY, MAX_MESSAGE_SIZE);
+        let mut reader = ReadBinder::new(
+            read_half,
+            f64::INFINITY,
+            MAX_MESSAGE_SIZE,
+            message_deserializer(),
+        );
+        writer.enable_compression(Some(algorithm), 0);
+
+        // the reader supports the algorithm
+        reader.enable_compression(algorithm.flag());
+        writer
+            .send(&Message::PeerList(peer_list.clone()))
+            .await
+            .unwrap();
+        match reader.next().await.unwrap() {
+            Some((_, Message::PeerList(received))) => assert_eq!(received, peer_list),
+            _ => panic!("unexpected message"),
+        }
+
+        // the reader does not support the algorithm
+        reader.enable_compression(0);
+        writer
+            .send(&Message::PeerList(peer_list.clone()))
+            .await
+            .unwrap();
+        assert!(reader.next().await.is_err());
+    }
+}
+
+/// Performs a handshake between two nodes using the given encryption modes
+async fn encryption_handshake(
+    mode_a: EncryptionMode,
+    mode_b: EncryptionMode,
+) -> (HandshakeReturnType, HandshakeReturnType) {
+    let (duplex_a, duplex_b) = tokio::io::duplex(MAX_MESSAGE_SIZE as usize);
+    let handshake = |duplex, mode| {
+        let (read_half, write_half) = tokio::io::split(duplex);
+        let keypair = KeyPair::generate();
+        HandshakeWorker::spawn(
+            read_half,
+            write_half,
+            NodeId::new(keypair.get_public_key()),
+            keypair,
+            MassaTime::from_millis(1_000),
+            Version::from_str("TEST.1.10").unwrap(),
+            ConnectionId(0),
+            f64::INFINITY,
+            f64::INFINITY,
+            Vec::new(),
+            0,
+            mode,
+            TransportType::Tcp.flag(),
+            default_testing_network_id(),
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+        )
+    };
+    let (res_a, res_b) = tokio::join!(handshake(duplex_a, mode_a), handshake(duplex_b, mode_b));
+    (res_a.unwrap().1, res_b.unwrap().1)
+}
+
+/// Test that messages are encrypted when both nodes offer encryption,
+/// and that a node requiring encryption refuses the peers not supporting it.
+#[tokio::test]
+#[serial]
+async fn test_handshake_encryption() {
+    let peer_list = vec![IpAddr::V4(Ipv4Addr::new(169, 202, 0, 11)); 10];
+    let (res_a, res_b) =
+        encryption_handshake(EncryptionMode::Required, EncryptionMode::Enabled).await;
+    let (_, mut reader_a, mut writer_a, _, transports) = res_a.expect("handshake failed");
+    let (_, mut reader_b, mut writer_b, _, _) = res_b.expect("handshake failed");
+    assert_eq!(transports, TransportType::Tcp.flag());
+    for (writer, reader) in [
+        (&mut writer_a, &mut reader_b),
+        (&mut writer_b, &mut reader_a),
+    ] {
+        writer
+            .send(&Message::PeerList(peer_list.clone()))
+            .await
+            .unwrap();
+        match reader.next().await.unwrap() {
+            Some((_, Message::PeerList(received))) => assert_eq!(received, peer_list),
+            _ => panic!("unexpected message"),
+        }
+    }
+
+    // messages are sent in clear to the peers not supporting encryption
+    let (res_a, res_b) =
+        encryption_handshake(EncryptionMode::Enabled, EncryptionMode::Disabled).await;
+    let (_, _, mut writer_a, _, _) = res_a.expect("handshake failed");
+    let (_, mut reader_b, _, _, _) = res_b.expect("handshake failed");
+    writer_a
+        .send(&Message::PeerList(peer_list.clone()))
+        .await
+        .unwrap();
+    match reader_b.next().await.unwrap() {
+        Some((_, Message::PeerList(received))) => assert_eq!(received, peer_list),
+        _ => panic!("unexpected message"),
+    }
+
+    let (res_a, _) = encryption_handshake(EncryptionMode::Required, EncryptionMode::Disabled).await;
+    match res_a {
+        Err(NetworkError::HandshakeError(HandshakeErrorType::EncryptionRequired)) => {}
+        _ => panic!("a peer not supporting encryption was accepted"),
+    }
+}
+
+/// Test that a peer that does not announce its network is refused.
+#[tokio::test]
+#[serial]
+async fn test_handshake_missing_network_id() {
+    let (duplex_a, duplex_b) = tokio::io::duplex(MAX_MESSAGE_SIZE as usize);
+    let (read_half, write_half) = tokio::io::split(duplex_a);
+    let keypair = KeyPair::generate();
+    let handshake = HandshakeWorker::spawn(
+        read_half,
+        write_half,
+        NodeId::new(keypair.get_public_key()),
+        keypair,
+        MassaTime::from_millis(1_000),
+        Version::from_str("TEST.1.10").unwrap(),
+        ConnectionId(0),
+        f64::INFINITY,
+        f64::INFINITY,
+        Vec::new(),
+        0,
+        EncryptionMode::Enabled,
+        TransportType::Tcp.flag(),
+        default_testing_network_id(),
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+    );
+
+    // the peer sends a handshake initiation without network id
+    let (_peer_read_half, peer_write_half) = tokio::io::split(duplex_b);
+    let mut peer_writer = WriteBinder::new(peer_write_half, f64::INFINITY, MAX_MESSAGE_SIZE);
+    peer_writer
+        .send(&Message::HandshakeInitiation {
+            public_key: KeyPair::generate().get_public_key(),
+            random_bytes: [0u8; HANDSHAKE_RANDOMNESS_SIZE_BYTES],
+            version: Version::from_str("TEST.1.10").unwrap(),
+            compression: 0,
+            network_id: None,
+            observed_ip: None,
+            transports: 0,
+            encryption_key: None,
+        })
+        .await
+        .unwrap();
+
+    match handshake.await.unwrap().1 {
+        Err(NetworkError::HandshakeError(HandshakeErrorType::MissingNetworkId)) => {}
+        _ => panic!("a peer without network id was accepted"),
+    }
+}
+
 /// Test that a node worker can send an operations message.
 #[tokio::test]
 #[serial]
@@ -185,12 +380,14 @@ async fn test_node_worker_operations_message() {
     let node_fn_handle = tokio::spawn(async move {
         NodeWorker::new(
             network_conf,
+            Default::default(),
             mock_node_id,
             reader,
             writer,
             node_worker_command_tx,
             node_command_rx,
             node_event_tx,
+            Default::default(),
         )
         .run_loop()
         .await
@@ -632,6 +829,12 @@ async fn test_advertised_and_wakeup_interval() {
         active_out_connections: 0,
         active_in_connections: 0,
         banned: false,
+        connection_successes: 0,
+        connection_failures: 0,
+        consecutive_failures: 0,
+        latency: None,
+        last_attempt: None,
+        quic: false,
     }]);
     let network_conf = NetworkConfig {
         wakeup_interval: MassaTime::from_millis(500),
@@ -767,6 +970,12 @@ async fn test_block_not_found() {
         active_out_connections: 0,
         active_in_connections: 0,
         banned: false,
+        connection_successes: 0,
+        connection_failures: 0,
+        consecutive_failures: 0,
+        latency: None,
+        last_attempt: None,
+        quic: false,
     }]);
     let network_conf = NetworkConfig {
         peer_types_config: default_testing_peer_type_enum_map(),
@@ -949,6 +1158,12 @@ async fn test_retry_connection_closed() {
         active_out_connections: 0,
         active_in_connections: 0,
         banned: false,
+        connection_successes: 0,
+        connection_failures: 0,
+        consecutive_failures: 0,
+        latency: None,
+        last_attempt: None,
+        quic: false,
     }]);
     let network_conf = NetworkConfig {
         peer_types_config: default_testing_peer_type_enum_map(),
@@ -1048,6 +1263,12 @@ async fn test_operation_messages() {
         active_out_connections: 0,
         active_in_connections: 0,
         banned: false,
+        connection_successes: 0,
+        connection_failures: 0,
+        consecutive_failures: 0,
+        latency: None,
+        last_attempt: None,
+        quic: false,
     }]);
     let network_conf = NetworkConfig {
         peer_types_config: default_testing_peer_type_enum_map(),
@@ -1163,6 +1384,12 @@ async fn test_endorsements_messages() {
         active_out_connections: 0,
         active_in_connections: 0,
         banned: false,
+        connection_successes: 0,
+        connection_failures: 0,
+        consecutive_failures: 0,
+        latency: None,
+        last_attempt: None,
+        quic: false,
     }]);
     let network_conf = NetworkConfig {
         peer_types_config: default_testing_peer_type_enum_map(),
@@ -1273,3 +1500,103 @@ async fn test_endorsements_messages() {
     )
     .await;
 }
+
+/// Starts three network controllers connected through a simulated network,
+/// checks that they connect to each other and exchange messages,
+/// then cuts a link and checks that the nodes see the connection closed.
+#[tokio::test]
+#[serial]
+async fn test_simulated_network() {
+    let bind_port: u16 = 50_000;
+    let addrs: Vec<SocketAddr> = (1..=3)
+        .map(|i| SocketAddr::new(IpAddr::V4(Ipv4Addr::new(169, 202, 1, i)), bind_port))
+        .collect();
+    let network = SimulatedNetwork::new();
+
+    let mut nodes = Vec::new();
+    let mut peers_files = Vec::new();
+    for addr in addrs.iter() {
+        // each node knows the other ones
+        let peers: Vec<PeerInfo> = addrs
+            .iter()
+            .filter(|peer_addr| *peer_addr != addr)
+            .map(|peer_addr| PeerInfo::new(peer_addr.ip(), true))
+            .collect();
+        let peers_file = tools::generate_peers_file(&peers);
+        let network_conf = NetworkConfig {
+            routable_ip: Some(addr.ip()),
+            wakeup_interval: MassaTime::from_millis(100),
+            ..NetworkConfig::scenarios_default(bind_port, peers_file.path())
+        };
+        let (command_sender, event_receiver, manager, _keypair, node_id) =
+            start_network_controller(
+                &network_conf,
+                Default::default(),
+                network.add_node(*addr),
+                None,
+                Version::from_str("TEST.1.10").unwrap(),
+            )
+            .await
+            .expect("could not start network controller");
+        nodes.push((command_sender, event_receiver, manager, node_id));
+        peers_files.push(peers_file);
+    }
+    let node_ids: Vec<NodeId> = nodes.iter().map(|(_, _, _, node_id)| *node_id).collect();
+
+    // each node connects to the two other ones
+    for (index, (_, event_receiver, _, _)) in nodes.iter_mut().enumerate() {
+        let mut expected: Vec<NodeId> = node_ids
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != index)
+            .map(|(_, node_id)| *node_id)
+            .collect();
+        while !expected.is_empty() {
+            let connected =
+                tools::wait_network_event(event_receiver, 5_000.into(), |evt| match evt {
+                    NetworkEvent::NewConnection(node_id) => Some(node_id),
+                    _ => None,
+                })
+                .await
+                .expect("nodes did not connect to each other");
+            expected.retain(|node_id| *node_id != connected);
+        }
+    }
+
+    // node 0 asks node 1 for a block
+    let block_id = get_dummy_block_id("simulated");
+    nodes[0]
+        .0
+        .ask_for_block_list(HashMap::from([(
+            node_ids[1],
+            vec![(block_id, AskForBlocksInfo::Info)],
+        )]))
+        .await
+        .unwrap();
+    tools::wait_network_event(&mut nodes[1].1, 1_000.into(), |evt| match evt {
+        NetworkEvent::AskedForBlocks { node, list }
+            if node == node_ids[0] && list.iter().any(|(id, _)| *id == block_id) =>
+        {
+            Some(())
+        }
+        _ => None,
+    })
+    .await
+    .expect("block request not received through the simulated network");
+
+    // cut the link between node 0 and node 1
+    network.cut_link(addrs[0].ip(), addrs[1].ip());
+    tools::wait_network_event(&mut nodes[0].1, 1_000.into(), |evt| match evt {
+        NetworkEvent::ConnectionClosed(node_id) if node_id == node_ids[1] => Some(()),
+        _ => None,
+    })
+    .await
+    .expect("connection not closed after the link was cut");
+
+    for (_, event_receiver, manager, _) in nodes {
+        manager
+            .stop(event_receiver)
+            .await
+            .expect("error while stopping network");
+    }
+}
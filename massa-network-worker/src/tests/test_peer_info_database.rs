@@ -784,6 +784,34 @@ async fn test_cleanup_peers() {
     assert!(peers.contains_key(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 43))));
 }
 
+#[test]
+fn test_peer_score_and_backoff() {
+    let wakeup_interval = MassaTime::from_millis(1000);
+    let now = MassaTime::from_millis(100_000);
+
+    // a reliable fast peer ranks above an unknown one, which ranks above an unreliable one
+    let mut reliable = default_peer_info_not_connected("169.202.0.11".parse().unwrap());
+    reliable.last_attempt = Some(MassaTime::from_millis(1000));
+    reliable.record_connection_success(MassaTime::from_millis(1100));
+    assert_eq!(reliable.latency, Some(MassaTime::from_millis(100)));
+    let unknown = default_peer_info_not_connected("169.202.0.12".parse().unwrap());
+    let mut unreliable = default_peer_info_not_connected("169.202.0.13".parse().unwrap());
+    unreliable.record_connection_failure();
+    unreliable.record_connection_failure();
+    assert!(reliable.score() > unknown.score());
+    assert!(unknown.score() > unreliable.score());
+
+    // the retry delay doubles with each consecutive failure
+    let mut failing = unreliable;
+    failing.last_failure = Some(now.saturating_sub(MassaTime::from_millis(3000)));
+    failing.consecutive_failures = 1;
+    assert!(failing.is_peer_ready(wakeup_interval, now));
+    failing.consecutive_failures = 2;
+    assert!(failing.is_peer_ready(wakeup_interval, now));
+    failing.consecutive_failures = 3;
+    assert!(!failing.is_peer_ready(wakeup_interval, now));
+}
+
 #[tokio::test]
 #[serial]
 async fn test() {
@@ -803,6 +831,12 @@ fn default_peer_info_connected(ip: IpAddr) -> PeerInfo {
         active_out_connections: 1,
         active_in_connections: 0,
         banned: false,
+        connection_successes: 0,
+        connection_failures: 0,
+        consecutive_failures: 0,
+        latency: None,
+        last_attempt: None,
+        quic: false,
     }
 }
 
@@ -817,6 +851,12 @@ fn default_peer_info_not_connected(ip: IpAddr) -> PeerInfo {
         active_out_connections: 0,
         active_in_connections: 0,
         banned: false,
+        connection_successes: 0,
+        connection_failures: 0,
+        consecutive_failures: 0,
+        latency: None,
+        last_attempt: None,
+        quic: false,
     }
 }
 
@@ -847,6 +887,12 @@ impl From<u32> for PeerInfoDatabase {
                 active_out_connections: 0,
                 active_in_connections: 0,
                 banned: ip[1] % 5 == 0,
+                connection_successes: 0,
+                connection_failures: 0,
+                consecutive_failures: 0,
+                latency: None,
+                last_attempt: None,
+                quic: false,
             };
             peers.insert(peer.ip, peer);
         }
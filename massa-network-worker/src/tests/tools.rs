@@ -19,16 +19,18 @@ use massa_models::{
     operation::{Operation, OperationSerializer, OperationType, WrappedOperation},
     version::Version,
 };
+use massa_network_exports::settings::tests::default_testing_network_id;
 use massa_network_exports::test_exports::mock_establisher::{self, MockEstablisherInterface};
 use massa_network_exports::{
-    ConnectionId, NetworkCommandSender, NetworkEventReceiver, NetworkManager, PeerInfo,
+    ConnectionId, EncryptionMode, NetworkCommandSender, NetworkEventReceiver, NetworkManager,
+    PeerInfo, TransportType,
 };
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use std::str::FromStr;
 use std::{
     future::Future,
-    net::{IpAddr, SocketAddr},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
     time::Duration,
 };
 use tempfile::NamedTempFile;
@@ -94,6 +96,12 @@ pub async fn full_connection_to_controller(
         connection_id,
         f64::INFINITY,
         f64::INFINITY,
+        Vec::new(),
+        0,
+        EncryptionMode::Disabled,
+        TransportType::Tcp.flag(),
+        default_testing_network_id(),
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
     )
     .await
     .expect("handshake creation failed")
@@ -153,6 +161,12 @@ pub async fn rejected_connection_to_controller(
         connection_id,
         f64::INFINITY,
         f64::INFINITY,
+        Vec::new(),
+        0,
+        EncryptionMode::Disabled,
+        TransportType::Tcp.flag(),
+        default_testing_network_id(),
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
     )
     .await
     .expect("handshake creation failed")
@@ -238,6 +252,12 @@ pub async fn full_connection_from_controller(
         connection_id,
         f64::INFINITY,
         f64::INFINITY,
+        Vec::new(),
+        0,
+        EncryptionMode::Disabled,
+        TransportType::Tcp.flag(),
+        default_testing_network_id(),
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
     )
     .await
     .expect("handshake creation failed")
@@ -369,6 +389,7 @@ pub async fn network_test<F, V>(
     let (network_event_sender, network_event_receiver, network_manager, _keypair, _node_id) =
         start_network_controller(
             &network_settings,
+            Default::default(),
             establisher,
             None,
             Version::from_str("TEST.1.10").unwrap(),
@@ -5,6 +5,7 @@
 #![warn(unused_crate_dependencies)]
 extern crate massa_logging;
 use crate::settings::SETTINGS;
+use crate::shutdown::{NodeManager, WORKER_STOP_TIMEOUT};
 
 use crossbeam_channel::{Receiver, TryRecvError};
 use dialoguer::Password;
@@ -12,21 +13,29 @@ use massa_api::{APIConfig, ApiServer, ApiV2, Private, Public, RpcServer, StopHan
 use massa_async_pool::AsyncPoolConfig;
 use massa_bootstrap::{get_state, start_bootstrap_server, BootstrapConfig, BootstrapManager};
 use massa_consensus_exports::events::ConsensusEvent;
-use massa_consensus_exports::{ConsensusChannels, ConsensusConfig, ConsensusManager};
+use massa_consensus_exports::{
+    ConsensusChannels, ConsensusConfig, ConsensusController, ConsensusManager,
+};
 use massa_consensus_worker::start_consensus_worker;
 use massa_executed_ops::ExecutedOpsConfig;
-use massa_execution_exports::{ExecutionConfig, ExecutionManager, GasCosts, StorageCostsConstants};
+use massa_execution_exports::{
+    ExecutionChannels, ExecutionConfig, ExecutionManager, GasCosts, StorageCostsConstants,
+};
 use massa_execution_worker::start_execution_worker;
 use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
 use massa_factory_worker::start_factory;
-use massa_final_state::{FinalState, FinalStateConfig};
+use massa_final_state::{FinalState, FinalStateConfig, TraceReplayOutcome};
+use massa_grpc::{GrpcConfig, MassaGrpcService};
 use massa_ledger_exports::LedgerConfig;
 use massa_ledger_worker::FinalLedger;
-use massa_logging::massa_trace;
+use massa_logging::{massa_trace, JsonLogLayer, LogFilterHandle};
+use massa_metrics::{start_metrics_server, MetricsConfig};
 use massa_models::address::Address;
+use massa_models::block::BlockId;
 use massa_models::config::constants::{
-    ASYNC_POOL_BOOTSTRAP_PART_SIZE, BLOCK_REWARD, BOOTSTRAP_RANDOMNESS_SIZE_BYTES, CHANNEL_SIZE,
-    DEFERRED_CREDITS_BOOTSTRAP_PART_SIZE, DELTA_F0, ENDORSEMENT_COUNT, END_TIMESTAMP,
+    ASYNC_POOL_BOOTSTRAP_PART_SIZE, BLOCK_REWARD, BOOTSTRAP_RANDOMNESS_SIZE_BYTES, CHAIN_ID,
+    CYCLE_INFO_BOOTSTRAP_PART_SIZE, DEFERRED_CREDITS_BOOTSTRAP_PART_SIZE,
+    DEFERRED_CREDITS_PART_SIZE_MESSAGE_BYTES, DELTA_F0, ENDORSEMENT_COUNT, END_TIMESTAMP,
     EXECUTED_OPS_BOOTSTRAP_PART_SIZE, GENESIS_KEY, GENESIS_TIMESTAMP, INITIAL_DRAW_SEED,
     LEDGER_COST_PER_BYTE, LEDGER_ENTRY_BASE_SIZE, LEDGER_ENTRY_DATASTORE_BASE_SIZE,
     LEDGER_PART_SIZE_MESSAGE_BYTES, MAX_ADVERTISE_LENGTH, MAX_ASK_BLOCKS_PER_MESSAGE,
@@ -36,20 +45,23 @@ use massa_models::config::constants::{
     MAX_DATASTORE_ENTRY_COUNT, MAX_DATASTORE_KEY_LENGTH, MAX_DATASTORE_VALUE_LENGTH,
     MAX_DEFERRED_CREDITS_LENGTH, MAX_ENDORSEMENTS_PER_MESSAGE, MAX_EXECUTED_OPS_CHANGES_LENGTH,
     MAX_EXECUTED_OPS_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_GAS_PER_BLOCK, MAX_LEDGER_CHANGES_COUNT,
-    MAX_MESSAGE_SIZE, MAX_OPERATIONS_PER_BLOCK, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
-    MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE,
-    MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, NETWORK_CONTROLLER_CHANNEL_SIZE,
-    NETWORK_EVENT_CHANNEL_SIZE, NETWORK_NODE_COMMAND_CHANNEL_SIZE, NETWORK_NODE_EVENT_CHANNEL_SIZE,
-    OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, POOL_CONTROLLER_CHANNEL_SIZE,
-    POS_MISS_RATE_DEACTIVATION_THRESHOLD, POS_SAVED_CYCLES, PROTOCOL_CONTROLLER_CHANNEL_SIZE,
-    PROTOCOL_EVENT_CHANNEL_SIZE, ROLL_PRICE, T0, THREAD_COUNT, VERSION,
+    MAX_LEDGER_VALUE_LENGTH, MAX_MESSAGE_SIZE, MAX_OPERATIONS_PER_BLOCK,
+    MAX_OPERATION_DATASTORE_ENTRY_COUNT, MAX_OPERATION_DATASTORE_KEY_LENGTH,
+    MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE, MAX_PRODUCTION_STATS_LENGTH,
+    MAX_ROLLS_COUNT_LENGTH, OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE,
+    POS_MISS_RATE_DEACTIVATION_THRESHOLD, POS_SAVED_CYCLES, ROLL_PRICE, T0, THREAD_COUNT, VERSION,
+};
+use massa_models::config::{
+    compute_genesis_blocks_hash, GenesisBuilder, CONSENSUS_BOOTSTRAP_PART_SIZE,
+};
+use massa_models::prehash::PreHashSet;
+use massa_network_exports::{
+    Establisher, MessageWriteTimeouts, NetworkConfig, NetworkId, NetworkManager,
 };
-use massa_models::config::CONSENSUS_BOOTSTRAP_PART_SIZE;
-use massa_network_exports::{Establisher, NetworkConfig, NetworkManager};
 use massa_network_worker::start_network_controller;
-use massa_pool_exports::{PoolConfig, PoolManager};
+use massa_pool_exports::{PoolConfig, PoolController, PoolManager};
 use massa_pool_worker::start_pool_controller;
-use massa_pos_exports::{PoSConfig, SelectorConfig, SelectorManager};
+use massa_pos_exports::{PoSConfig, SelectorConfig, SelectorController, SelectorManager};
 use massa_pos_worker::start_selector_worker;
 use massa_protocol_exports::{
     ProtocolCommand, ProtocolCommandSender, ProtocolConfig, ProtocolManager, ProtocolReceivers,
@@ -57,9 +69,11 @@ use massa_protocol_exports::{
 };
 use massa_protocol_worker::start_protocol_controller;
 use massa_storage::Storage;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SystemClock};
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
+use std::fs::OpenOptions;
+use std::io::LineWriter;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::thread::sleep;
@@ -69,42 +83,75 @@ use structopt::StructOpt;
 use tokio::signal;
 use tokio::sync::{broadcast, mpsc};
 use tracing::{error, info, warn};
-use tracing_subscriber::filter::{filter_fn, LevelFilter};
+use tracing_subscriber::filter::{filter_fn, LevelFilter, Targets};
+use tracing_subscriber::reload;
 mod settings;
+mod shutdown;
 
-async fn launch(
-    node_wallet: Arc<RwLock<Wallet>>,
-) -> (
-    Receiver<ConsensusEvent>,
-    Option<BootstrapManager>,
-    Box<dyn ConsensusManager>,
-    Box<dyn ExecutionManager>,
-    Box<dyn SelectorManager>,
-    Box<dyn PoolManager>,
-    ProtocolManager,
-    NetworkManager,
-    Box<dyn FactoryManager>,
-    mpsc::Receiver<()>,
-    StopHandle,
-    StopHandle,
-    StopHandle,
+/// Hands the objects loaded back from the storage disk backend over to the components using them:
+/// the blocks are registered to consensus along with their operations and endorsements,
+/// and the operations and endorsements are added to the pool.
+/// The objects that no component keeps are released.
+fn hand_over_loaded_objects(
+    mut loaded_storage: Storage,
+    consensus_controller: &dyn ConsensusController,
+    pool_controller: &mut dyn PoolController,
 ) {
-    info!("Node version : {}", *VERSION);
-    if let Some(end) = *END_TIMESTAMP {
-        if MassaTime::now().expect("could not get now time") > end {
-            panic!("This episode has come to an end, please get the latest testnet node version to continue");
-        }
+    let block_ids: Vec<BlockId> = loaded_storage.get_block_refs().iter().copied().collect();
+    for block_id in block_ids {
+        let (slot, op_ids, endorsement_ids) = {
+            let blocks = loaded_storage.read_blocks();
+            let block = blocks
+                .get(&block_id)
+                .expect("loaded block should be stored");
+            (
+                block.content.header.content.slot,
+                block.content.operations.iter().copied().collect(),
+                block
+                    .content
+                    .header
+                    .content
+                    .endorsements
+                    .iter()
+                    .map(|endorsement| endorsement.id)
+                    .collect(),
+            )
+        };
+        // operations and endorsements may be shared by several blocks: claim them instead of splitting them off
+        let mut block_storage = loaded_storage.clone_without_refs();
+        block_storage.claim_block_refs(&PreHashSet::from_iter([block_id]));
+        block_storage.claim_operation_refs(&op_ids);
+        block_storage.claim_endorsement_refs(&endorsement_ids);
+        consensus_controller.register_block(block_id, slot, block_storage, false);
     }
+    let op_ids = loaded_storage.get_op_refs().clone();
+    pool_controller.add_operations(loaded_storage.split_off(
+        &Default::default(),
+        &op_ids,
+        &Default::default(),
+    ));
+    let endorsement_ids = loaded_storage.get_endorsement_refs().clone();
+    pool_controller.add_endorsements(loaded_storage.split_off(
+        &Default::default(),
+        &Default::default(),
+        &endorsement_ids,
+    ));
+}
 
-    // Storage shared by multiple components.
-    let shared_storage: Storage = Storage::create_root();
-
-    // init final state
+/// Creates the final state, backed by a disk ledger at the given path, along with the selector worker
+fn create_final_state(
+    disk_ledger_path: PathBuf,
+) -> (
+    Arc<RwLock<FinalState>>,
+    Box<dyn SelectorManager>,
+    Box<dyn SelectorController>,
+) {
     let ledger_config = LedgerConfig {
         thread_count: THREAD_COUNT,
         initial_ledger_path: SETTINGS.ledger.initial_ledger_path.clone(),
-        disk_ledger_path: SETTINGS.ledger.disk_ledger_path.clone(),
+        disk_ledger_path,
         max_key_length: MAX_DATASTORE_KEY_LENGTH,
+        max_value_length: MAX_LEDGER_VALUE_LENGTH,
         max_ledger_part_size: LEDGER_PART_SIZE_MESSAGE_BYTES,
     };
     let async_pool_config = AsyncPoolConfig {
@@ -118,6 +165,8 @@ async fn launch(
         thread_count: THREAD_COUNT,
         cycle_history_length: POS_SAVED_CYCLES,
         credits_bootstrap_part_size: DEFERRED_CREDITS_BOOTSTRAP_PART_SIZE,
+        credits_bootstrap_part_size_bytes: DEFERRED_CREDITS_PART_SIZE_MESSAGE_BYTES,
+        cycle_info_bootstrap_part_size: CYCLE_INFO_BOOTSTRAP_PART_SIZE,
     };
     let executed_ops_config = ExecutedOpsConfig {
         thread_count: THREAD_COUNT,
@@ -133,22 +182,17 @@ async fn launch(
         periods_per_cycle: PERIODS_PER_CYCLE,
         initial_seed_string: INITIAL_DRAW_SEED.into(),
         initial_rolls_path: SETTINGS.selector.initial_rolls_path.clone(),
+        initial_deferred_credits_path: SETTINGS.selector.initial_deferred_credits_path.clone(),
+        initial_snapshot_path: SETTINGS.ledger.initial_snapshot_path.clone(),
     };
 
-    // Remove current disk ledger if there is one
-    // NOTE: this is temporary, since we cannot currently handle bootstrap from remaining ledger
-    if SETTINGS.ledger.disk_ledger_path.exists() {
-        std::fs::remove_dir_all(SETTINGS.ledger.disk_ledger_path.clone())
-            .expect("disk ledger delete failed");
-    }
-
     // Create final ledger
     let ledger = FinalLedger::new(ledger_config.clone());
 
     // launch selector worker
     let (selector_manager, selector_controller) = start_selector_worker(SelectorConfig {
         max_draw_cache: SETTINGS.selector.max_draw_cache,
-        channel_size: CHANNEL_SIZE,
+        channel_size: SETTINGS.channels.selector_channel_size,
         thread_count: THREAD_COUNT,
         endorsement_count: ENDORSEMENT_COUNT,
         periods_per_cycle: PERIODS_PER_CYCLE,
@@ -166,14 +210,67 @@ async fn launch(
         .expect("could not init final state"),
     ));
 
+    (final_state, selector_manager, selector_controller)
+}
+
+async fn launch(
+    node_wallet: Arc<RwLock<Wallet>>,
+    log_filter_handle: LogFilterHandle,
+) -> (
+    Receiver<ConsensusEvent>,
+    Option<BootstrapManager>,
+    Box<dyn ConsensusManager>,
+    Box<dyn ExecutionManager>,
+    Box<dyn SelectorManager>,
+    Box<dyn PoolManager>,
+    ProtocolManager,
+    NetworkManager,
+    Box<dyn FactoryManager>,
+    mpsc::Receiver<()>,
+    StopHandle,
+    StopHandle,
+    StopHandle,
+    Option<massa_grpc::StopHandle>,
+    Storage,
+) {
+    info!("Node version : {}", *VERSION);
+    let node_start_time = MassaTime::now().expect("could not get now time");
+    if let Some(end) = *END_TIMESTAMP {
+        if MassaTime::now().expect("could not get now time") > end {
+            panic!("This episode has come to an end, please get the latest testnet node version to continue");
+        }
+    }
+
+    // Storage shared by multiple components.
+    // The objects held at the last stop are loaded back from disk, to be handed over to the components.
+    let (shared_storage, loaded_storage) =
+        Storage::create_root_with_disk_backend(SETTINGS.storage.disk_storage_path.clone());
+
+    // Remove current disk ledger if there is one, unless it belongs to an interrupted bootstrap
+    // NOTE: this is temporary, since we cannot currently handle bootstrap from remaining ledger
+    if SETTINGS.ledger.disk_ledger_path.exists()
+        && !SETTINGS.bootstrap.bootstrap_cursor_path.exists()
+    {
+        std::fs::remove_dir_all(SETTINGS.ledger.disk_ledger_path.clone())
+            .expect("disk ledger delete failed");
+    }
+
+    // init final state
+    let (final_state, selector_manager, selector_controller) =
+        create_final_state(SETTINGS.ledger.disk_ledger_path.clone());
+
     // interrupt signal listener
     let stop_signal = signal::ctrl_c();
     tokio::pin!(stop_signal);
 
     let bootstrap_config: BootstrapConfig = BootstrapConfig {
         bootstrap_list: SETTINGS.bootstrap.bootstrap_list.clone(),
+        bootstrap_dns_list: SETTINGS.bootstrap.bootstrap_dns_list.clone(),
         bootstrap_whitelist_path: SETTINGS.bootstrap.bootstrap_whitelist_path.clone(),
         bootstrap_blacklist_path: SETTINGS.bootstrap.bootstrap_blacklist_path.clone(),
+        bootstrap_cursor_path: SETTINGS.bootstrap.bootstrap_cursor_path.clone(),
+        secondary_bootstrap_server_count: SETTINGS.bootstrap.secondary_bootstrap_server_count,
+        max_pending_bootstrap_parts: SETTINGS.bootstrap.max_pending_bootstrap_parts,
         bind: SETTINGS.bootstrap.bind,
         connect_timeout: SETTINGS.bootstrap.connect_timeout,
         read_timeout: SETTINGS.bootstrap.read_timeout,
@@ -241,18 +338,19 @@ async fn launch(
     let network_config: NetworkConfig = NetworkConfig {
         bind: SETTINGS.network.bind,
         routable_ip: SETTINGS.network.routable_ip,
+        routable_ip_detection_votes: SETTINGS.network.routable_ip_detection_votes,
         protocol_port: SETTINGS.network.protocol_port,
         connect_timeout: SETTINGS.network.connect_timeout,
         wakeup_interval: SETTINGS.network.wakeup_interval,
         initial_peers_file: SETTINGS.network.initial_peers_file.clone(),
         peers_file: SETTINGS.network.peers_file.clone(),
+        seed_peer_lists: SETTINGS.network.seed_peer_lists.clone(),
         keypair_file: SETTINGS.network.keypair_file.clone(),
         peer_types_config: SETTINGS.network.peer_types_config.clone(),
         max_in_connections_per_ip: SETTINGS.network.max_in_connections_per_ip,
         max_idle_peers: SETTINGS.network.max_idle_peers,
         max_banned_peers: SETTINGS.network.max_banned_peers,
         peers_file_dump_interval: SETTINGS.network.peers_file_dump_interval,
-        message_timeout: SETTINGS.network.message_timeout,
         ask_peer_list_interval: SETTINGS.network.ask_peer_list_interval,
         max_send_wait_node_event: SETTINGS.network.max_send_wait_node_event,
         max_send_wait_network_event: SETTINGS.network.max_send_wait_network_event,
@@ -262,6 +360,7 @@ async fn launch(
         max_operations_per_message: SETTINGS.network.max_operations_per_message,
         max_bytes_read: SETTINGS.network.max_bytes_read,
         max_bytes_write: SETTINGS.network.max_bytes_write,
+        max_node_outstanding_bytes: SETTINGS.network.max_node_outstanding_bytes,
         max_ask_blocks: MAX_ASK_BLOCKS_PER_MESSAGE,
         max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
         thread_count: THREAD_COUNT,
@@ -269,22 +368,42 @@ async fn launch(
         max_peer_advertise_length: MAX_ADVERTISE_LENGTH,
         max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE,
         max_message_size: MAX_MESSAGE_SIZE,
+        message_compression: SETTINGS.network.message_compression.clone(),
+        min_compressed_message_size: SETTINGS.network.min_compressed_message_size,
+        message_encryption: SETTINGS.network.message_encryption,
+        quic_enabled: SETTINGS.network.quic_enabled,
+        network_id: NetworkId {
+            chain_id: CHAIN_ID,
+            genesis_hash: compute_genesis_blocks_hash(
+                &GENESIS_KEY,
+                THREAD_COUNT,
+                *GENESIS_TIMESTAMP,
+            )
+            .expect("could not compute the genesis blocks hash"),
+        },
         max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
         max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
         max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
         max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
         max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
         max_parameters_size: MAX_PARAMETERS_SIZE,
-        controller_channel_size: NETWORK_CONTROLLER_CHANNEL_SIZE,
-        event_channel_size: NETWORK_EVENT_CHANNEL_SIZE,
-        node_command_channel_size: NETWORK_NODE_COMMAND_CHANNEL_SIZE,
-        node_event_channel_size: NETWORK_NODE_EVENT_CHANNEL_SIZE,
+        controller_channel_size: SETTINGS.channels.network_controller_channel_size,
+        event_channel_size: SETTINGS.channels.network_event_channel_size,
+        node_command_channel_size: SETTINGS.channels.network_node_command_channel_size,
+        node_event_channel_size: SETTINGS.channels.network_node_event_channel_size,
     };
 
     // launch network controller
+    let message_write_timeouts = MessageWriteTimeouts {
+        handshake: SETTINGS.protocol.handshake_message_timeout,
+        header: SETTINGS.protocol.header_message_timeout,
+        block: SETTINGS.protocol.block_message_timeout,
+        large: SETTINGS.protocol.large_message_timeout,
+    };
     let (network_command_sender, network_event_receiver, network_manager, private_key, node_id) =
         start_network_controller(
             &network_config,
+            message_write_timeouts,
             Establisher::new(),
             bootstrap_state.peers,
             *VERSION,
@@ -311,6 +430,9 @@ async fn launch(
     // launch execution module
     let execution_config = ExecutionConfig {
         max_final_events: SETTINGS.execution.max_final_events,
+        final_events_retention_periods: SETTINGS.execution.final_events_retention_periods,
+        max_address_history_slots: SETTINGS.execution.max_address_history_slots,
+        max_transfer_history_slots: SETTINGS.execution.max_transfer_history_slots,
         readonly_queue_length: SETTINGS.execution.readonly_queue_length,
         cursor_delay: SETTINGS.execution.cursor_delay,
         max_async_gas: MAX_ASYNC_GAS,
@@ -335,30 +457,54 @@ async fn launch(
             SETTINGS.execution.wasm_gas_costs_file.clone(),
         )
         .expect("Failed to load gas costs"),
+        trace_path: SETTINGS.execution.trace_path.clone(),
+        broadcast_enabled: SETTINGS.api.enable_ws || SETTINGS.grpc.enabled,
+        broadcast_slot_execution_output_capacity: SETTINGS
+            .execution
+            .broadcast_slot_execution_output_capacity,
+    };
+    let execution_channels = ExecutionChannels {
+        slot_execution_output_sender: broadcast::channel(
+            execution_config.broadcast_slot_execution_output_capacity,
+        )
+        .0,
+        execution_reorg_sender: broadcast::channel(
+            execution_config.broadcast_slot_execution_output_capacity,
+        )
+        .0,
     };
     let (execution_manager, execution_controller) = start_execution_worker(
         execution_config,
         final_state.clone(),
         selector_controller.clone(),
+        execution_channels.clone(),
     );
 
     // launch pool controller
     let pool_config = PoolConfig {
         thread_count: THREAD_COUNT,
         max_block_size: MAX_BLOCK_SIZE,
+        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
         max_block_gas: MAX_GAS_PER_BLOCK,
         roll_price: ROLL_PRICE,
         max_block_endorsement_count: ENDORSEMENT_COUNT,
         operation_validity_periods: OPERATION_VALIDITY_PERIODS,
         max_operation_pool_size_per_thread: SETTINGS.pool.max_pool_size_per_thread,
+        max_operations_per_address: SETTINGS.pool.max_operations_per_address,
+        operation_rebroadcast_periods: SETTINGS.pool.operation_rebroadcast_periods,
+        max_operation_rebroadcast_count: SETTINGS.pool.max_operation_rebroadcast_count,
         max_endorsements_pool_size_per_thread: SETTINGS.pool.max_pool_size_per_thread,
-        channels_size: POOL_CONTROLLER_CHANNEL_SIZE,
+        channels_size: SETTINGS.channels.pool_controller_channel_size,
     };
-    let (pool_manager, pool_controller) =
-        start_pool_controller(pool_config, &shared_storage, execution_controller.clone());
-
     let (protocol_command_sender, protocol_command_receiver) =
-        mpsc::channel::<ProtocolCommand>(PROTOCOL_CONTROLLER_CHANNEL_SIZE);
+        mpsc::channel::<ProtocolCommand>(SETTINGS.channels.protocol_controller_channel_size);
+
+    let (pool_manager, pool_controller) = start_pool_controller(
+        pool_config,
+        &shared_storage,
+        execution_controller.clone(),
+        ProtocolCommandSender(protocol_command_sender.clone()),
+    );
 
     let consensus_config = ConsensusConfig {
         genesis_timestamp: *GENESIS_TIMESTAMP,
@@ -380,16 +526,18 @@ async fn launch(
         block_db_prune_interval: SETTINGS.consensus.block_db_prune_interval,
         max_item_return_count: SETTINGS.consensus.max_item_return_count,
         max_gas_per_block: MAX_GAS_PER_BLOCK,
-        channel_size: CHANNEL_SIZE,
+        channel_size: SETTINGS.channels.consensus_channel_size,
+        max_commands_per_batch: SETTINGS.consensus.max_commands_per_batch,
         bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
-        broadcast_enabled: SETTINGS.api.enable_ws,
+        broadcast_enabled: SETTINGS.api.enable_ws || SETTINGS.grpc.enabled,
         broadcast_blocks_headers_capacity: SETTINGS.consensus.broadcast_blocks_headers_capacity,
         broadcast_blocks_capacity: SETTINGS.consensus.broadcast_blocks_capacity,
         broadcast_filled_blocks_capacity: SETTINGS.consensus.broadcast_filled_blocks_capacity,
+        check_graph_invariants: SETTINGS.consensus.check_graph_invariants,
     };
 
     let (consensus_event_sender, consensus_event_receiver) =
-        crossbeam_channel::bounded(CHANNEL_SIZE);
+        crossbeam_channel::bounded(SETTINGS.channels.consensus_channel_size);
     let consensus_channels = ConsensusChannels {
         execution_controller: execution_controller.clone(),
         selector_controller: selector_controller.clone(),
@@ -401,6 +549,7 @@ async fn launch(
         block_sender: broadcast::channel(consensus_config.broadcast_blocks_capacity).0,
         filled_block_sender: broadcast::channel(consensus_config.broadcast_filled_blocks_capacity)
             .0,
+        clock: Arc::new(SystemClock),
     };
 
     let (consensus_controller, consensus_manager) = start_consensus_worker(
@@ -409,10 +558,16 @@ async fn launch(
         bootstrap_state.graph,
         shared_storage.clone(),
     );
+    hand_over_loaded_objects(
+        loaded_storage,
+        consensus_controller.as_ref(),
+        pool_controller.clone().as_mut(),
+    );
 
     // launch protocol controller
     let protocol_config = ProtocolConfig {
         thread_count: THREAD_COUNT,
+        endorsement_count: ENDORSEMENT_COUNT,
         ask_block_timeout: SETTINGS.protocol.ask_block_timeout,
         max_known_blocks_size: SETTINGS.protocol.max_known_blocks_size,
         max_node_known_blocks_size: SETTINGS.protocol.max_node_known_blocks_size,
@@ -433,14 +588,21 @@ async fn launch(
         asked_operations_pruning_period: SETTINGS.protocol.asked_operations_pruning_period,
         operation_announcement_interval: SETTINGS.protocol.operation_announcement_interval,
         max_operations_per_message: SETTINGS.protocol.max_operations_per_message,
+        max_block_asks_per_second: SETTINGS.protocol.max_block_asks_per_second,
+        max_headers_per_second: SETTINGS.protocol.max_headers_per_second,
+        max_pending_header_validations: SETTINGS.protocol.max_pending_header_validations,
+        max_operations_per_announcement: SETTINGS.protocol.max_operations_per_announcement,
+        max_misbehavior_score: SETTINGS.protocol.max_misbehavior_score,
+        block_propagation_fanout: SETTINGS.protocol.block_propagation_fanout,
+        block_propagation_delay: SETTINGS.protocol.block_propagation_delay,
         max_serialized_operations_size_per_block: MAX_BLOCK_SIZE as usize,
-        controller_channel_size: PROTOCOL_CONTROLLER_CHANNEL_SIZE,
-        event_channel_size: PROTOCOL_EVENT_CHANNEL_SIZE,
+        controller_channel_size: SETTINGS.channels.protocol_controller_channel_size,
+        event_channel_size: SETTINGS.channels.protocol_event_channel_size,
         genesis_timestamp: *GENESIS_TIMESTAMP,
         t0: T0,
         max_operations_propagation_time: SETTINGS.protocol.max_operations_propagation_time,
         max_endorsements_propagation_time: SETTINGS.protocol.max_endorsements_propagation_time,
-        broadcast_enabled: SETTINGS.api.enable_ws,
+        broadcast_enabled: SETTINGS.api.enable_ws || SETTINGS.grpc.enabled,
         broadcast_operations_capacity: SETTINGS.protocol.broadcast_operations_capacity,
     };
 
@@ -471,17 +633,27 @@ async fn launch(
         genesis_timestamp: *GENESIS_TIMESTAMP,
         t0: T0,
         initial_delay: SETTINGS.factory.initial_delay,
+        block_preparation_time: SETTINGS.factory.block_preparation_time,
         max_block_size: MAX_BLOCK_SIZE as u64,
         max_block_gas: MAX_GAS_PER_BLOCK,
+        standby: SETTINGS.factory.standby,
+        standby_heartbeat_timeout: SETTINGS.factory.standby_heartbeat_timeout,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        roll_price: ROLL_PRICE,
+        operation_validity_periods: OPERATION_VALIDITY_PERIODS,
+        roll_compounding: SETTINGS.factory.roll_compounding,
+        roll_compounding_fee: SETTINGS.factory.roll_compounding_fee,
     };
     let factory_channels = FactoryChannels {
         selector: selector_controller.clone(),
         consensus: consensus_controller.clone(),
         pool: pool_controller.clone(),
+        execution: execution_controller.clone(),
         protocol: ProtocolCommandSender(protocol_command_sender.clone()),
         storage: shared_storage.clone(),
     };
-    let factory_manager = start_factory(factory_config, node_wallet.clone(), factory_channels);
+    let (factory_manager, factory_controller) =
+        start_factory(factory_config, node_wallet.clone(), factory_channels);
 
     // launch bootstrap server
     let bootstrap_manager = start_bootstrap_server(
@@ -496,12 +668,28 @@ async fn launch(
     .await
     .unwrap();
 
+    // load the token protecting the private API
+    let private_auth_token = SETTINGS.api.private_auth_token_path.as_ref().map(|path| {
+        std::fs::read_to_string(path)
+            .expect("could not read the private API token file")
+            .trim()
+            .to_string()
+    });
+    if private_auth_token.is_none() && !SETTINGS.api.bind_private.ip().is_loopback() {
+        warn!(
+            "the private API is bound to {} without authentication token, anyone reaching this address can manage the node",
+            SETTINGS.api.bind_private
+        );
+    }
+
     let api_config: APIConfig = APIConfig {
         bind_private: SETTINGS.api.bind_private,
+        private_auth_token,
         bind_public: SETTINGS.api.bind_public,
         bind_api: SETTINGS.api.bind_api,
         draw_lookahead_period_count: SETTINGS.api.draw_lookahead_period_count,
         max_arguments: SETTINGS.api.max_arguments,
+        max_simulated_cycles: SETTINGS.api.max_simulated_cycles,
         openrpc_spec_path: SETTINGS.api.openrpc_spec_path.clone(),
         bootstrap_whitelist_path: SETTINGS.bootstrap.bootstrap_whitelist_path.clone(),
         bootstrap_blacklist_path: SETTINGS.bootstrap.bootstrap_blacklist_path.clone(),
@@ -515,6 +703,9 @@ async fn launch(
         ping_interval: SETTINGS.api.ping_interval,
         enable_http: SETTINGS.api.enable_http,
         enable_ws: SETTINGS.api.enable_ws,
+        max_in_flight_requests: SETTINGS.api.max_in_flight_requests,
+        request_timeout: SETTINGS.api.request_timeout,
+        method_timeouts: SETTINGS.api.method_timeouts.clone(),
         max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
         max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
         max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
@@ -525,12 +716,47 @@ async fn launch(
         genesis_timestamp: *GENESIS_TIMESTAMP,
         t0: T0,
         periods_per_cycle: PERIODS_PER_CYCLE,
+        chain_id: CHAIN_ID,
+        node_start_time,
+    };
+
+    // spawn gRPC API
+    let grpc_handle = if SETTINGS.grpc.enabled {
+        let grpc_config = GrpcConfig {
+            enabled: SETTINGS.grpc.enabled,
+            bind: SETTINGS.grpc.bind,
+            max_decoding_message_size: SETTINGS.grpc.max_decoding_message_size,
+            max_encoding_message_size: SETTINGS.grpc.max_encoding_message_size,
+            concurrency_limit_per_connection: SETTINGS.grpc.concurrency_limit_per_connection,
+            timeout: SETTINGS.grpc.timeout,
+            max_arguments: SETTINGS.api.max_arguments,
+            thread_count: THREAD_COUNT,
+        };
+        let grpc_api = MassaGrpcService {
+            consensus_channels: consensus_channels.clone(),
+            execution_controller: execution_controller.clone(),
+            execution_channels: execution_channels.clone(),
+            protocol_senders: protocol_senders.clone(),
+            storage: shared_storage.clone(),
+            grpc_config: grpc_config.clone(),
+            version: *VERSION,
+        };
+        Some(
+            grpc_api
+                .serve(&grpc_config)
+                .await
+                .expect("failed to start gRPC API"),
+        )
+    } else {
+        None
     };
 
     // spawn Massa API
     let api = API::<ApiV2>::new(
         consensus_channels,
+        execution_channels,
         protocol_senders,
+        shared_storage.clone(),
         api_config.clone(),
         *VERSION,
     );
@@ -549,6 +775,9 @@ async fn launch(
         execution_controller.clone(),
         api_config.clone(),
         node_wallet,
+        factory_controller,
+        log_filter_handle,
+        final_state.clone(),
     );
     let api_private_handle = api_private
         .serve(&SETTINGS.api.bind_private, &api_config)
@@ -617,92 +846,93 @@ async fn launch(
         api_private_handle,
         api_public_handle,
         api_handle,
+        grpc_handle,
+        shared_storage.clone_without_refs(),
     )
 }
 
-struct Managers {
-    bootstrap_manager: Option<BootstrapManager>,
-    consensus_manager: Box<dyn ConsensusManager>,
-    execution_manager: Box<dyn ExecutionManager>,
-    selector_manager: Box<dyn SelectorManager>,
-    pool_manager: Box<dyn PoolManager>,
-    protocol_manager: ProtocolManager,
-    network_manager: NetworkManager,
-    factory_manager: Box<dyn FactoryManager>,
-}
-
-async fn stop(
-    _consensus_event_receiver: Receiver<ConsensusEvent>,
-    Managers {
-        bootstrap_manager,
-        mut execution_manager,
-        mut consensus_manager,
-        mut selector_manager,
-        mut pool_manager,
-        protocol_manager,
-        network_manager,
-        mut factory_manager,
-    }: Managers,
-    api_private_handle: StopHandle,
-    api_public_handle: StopHandle,
-    api_handle: StopHandle,
-) {
-    // stop bootstrap
-    if let Some(bootstrap_manager) = bootstrap_manager {
-        bootstrap_manager
-            .stop()
-            .await
-            .expect("bootstrap server shutdown failed")
-    }
-
-    // stop public API
-    api_public_handle.stop();
-
-    // stop private API
-    api_private_handle.stop();
-
-    // stop Massa API
-    api_handle.stop();
-
-    // stop factory
-    factory_manager.stop();
-
-    // stop protocol controller
-    let network_event_receiver = protocol_manager
-        .stop()
-        .await
-        .expect("protocol shutdown failed");
-
-    // stop consensus
-    consensus_manager.stop();
-
-    // stop pool
-    pool_manager.stop();
-
-    // stop execution controller
-    execution_manager.stop();
-
-    // stop selector controller
-    selector_manager.stop();
-
-    // stop pool controller
-    // TODO
-    //let protocol_pool_event_receiver = pool_manager.stop().await.expect("pool shutdown failed");
-
-    // stop network controller
-    network_manager
-        .stop(network_event_receiver)
-        .await
-        .expect("network shutdown failed");
-
-    // note that FinalLedger gets destroyed as soon as its Arc count goes to zero
-}
-
 #[derive(StructOpt)]
 struct Args {
     /// Wallet password
     #[structopt(short = "p", long = "pwd")]
     password: Option<String>,
+    /// Replay an execution trace (see the `trace_path` execution setting) on top of a snapshot and exit
+    #[structopt(long = "replay-trace", parse(from_os_str))]
+    replay_trace: Option<PathBuf>,
+    /// Final state snapshot the execution trace is replayed on top of
+    #[structopt(long = "replay-snapshot", parse(from_os_str))]
+    replay_snapshot: Option<PathBuf>,
+    /// Build the genesis from a TOML or JSON spec, write the configured initial ledger,
+    /// rolls and deferred credits files, and exit
+    #[structopt(long = "write-genesis", parse(from_os_str))]
+    write_genesis: Option<PathBuf>,
+}
+
+/// Builds the genesis described by a spec file and writes the initial files configured in the settings
+fn write_genesis(spec_path: &Path) -> anyhow::Result<()> {
+    let Some(initial_deferred_credits_path) = &SETTINGS.selector.initial_deferred_credits_path
+    else {
+        anyhow::bail!("--write-genesis requires the initial_deferred_credits_path setting");
+    };
+    let genesis = GenesisBuilder::from_file(THREAD_COUNT, spec_path)?.build(&GENESIS_KEY)?;
+    genesis.write_initial_files(
+        &SETTINGS.ledger.initial_ledger_path,
+        &SETTINGS.selector.initial_rolls_path,
+        initial_deferred_credits_path,
+    )?;
+    info!("initial files of genesis {} written", genesis.hash);
+    Ok(())
+}
+
+/// Replays an execution trace on top of a final state snapshot,
+/// failing at the first slot at which the final state hashes differ from the recorded ones
+fn replay_trace(snapshot_path: &Path, trace_path: &Path) -> anyhow::Result<()> {
+    // use a dedicated disk ledger so that the one of the node is left untouched
+    let disk_ledger_path =
+        std::env::temp_dir().join(format!("massa_replay_ledger_{}", process::id()));
+    let (final_state, mut selector_manager, _selector_controller) =
+        create_final_state(disk_ledger_path.clone());
+    let outcome = {
+        let mut final_state = final_state.write();
+        final_state
+            .import_snapshot(snapshot_path)
+            .and_then(|_| final_state.replay_trace(trace_path))
+    };
+    selector_manager.stop();
+    drop(final_state);
+    if let Err(err) = std::fs::remove_dir_all(&disk_ledger_path) {
+        warn!(
+            "could not remove the replay ledger {:?}: {}",
+            disk_ledger_path, err
+        );
+    }
+
+    match outcome? {
+        TraceReplayOutcome::Matched {
+            replayed_slots,
+            last_slot,
+        } => {
+            info!(
+                "execution trace replayed without divergence: {} slots replayed up to slot {}",
+                replayed_slots, last_slot
+            );
+            Ok(())
+        }
+        TraceReplayOutcome::Diverged {
+            slot,
+            components,
+            expected,
+            actual,
+        } => {
+            error!("recorded hashes at slot {}: {:?}", slot, expected);
+            error!("recomputed hashes at slot {}: {:?}", slot, actual);
+            anyhow::bail!(
+                "execution trace diverged at slot {}, differing components: {}",
+                slot,
+                components.join(", ")
+            )
+        }
+    }
 }
 
 /// Load wallet, asking for passwords if necessary
@@ -729,9 +959,61 @@ fn load_wallet(password: Option<String>, path: &Path) -> anyhow::Result<Arc<RwLo
     )?)))
 }
 
+/// Pins the current thread, and all the threads it spawns afterwards, to the given CPU cores
+#[cfg(target_os = "linux")]
+fn pin_to_cpu_cores(cores: &[usize]) -> anyhow::Result<()> {
+    // SAFETY: `cpu_set` is a plain bit set, only accessed through the libc helpers
+    unsafe {
+        let mut cpu_set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_ZERO(&mut cpu_set);
+        for core in cores {
+            if *core >= libc::CPU_SETSIZE as usize {
+                anyhow::bail!("invalid CPU core {}", core);
+            }
+            libc::CPU_SET(*core, &mut cpu_set);
+        }
+        if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &cpu_set) != 0 {
+            anyhow::bail!(
+                "could not pin the node to CPU cores {:?}: {}",
+                cores,
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Pins the current thread, and all the threads it spawns afterwards, to the given CPU cores
+#[cfg(not(target_os = "linux"))]
+fn pin_to_cpu_cores(_cores: &[usize]) -> anyhow::Result<()> {
+    anyhow::bail!("CPU pinning is only supported on Linux")
+}
+
 #[paw::main]
 fn main(args: Args) -> anyhow::Result<()> {
-    let tokio_rt = tokio::runtime::Builder::new_multi_thread()
+    SETTINGS.threads.check()?;
+
+    // pin before spawning any thread so that every node thread inherits the CPU affinity
+    if !SETTINGS.threads.cpu_cores.is_empty() {
+        pin_to_cpu_cores(&SETTINGS.threads.cpu_cores)?;
+    }
+
+    SETTINGS.channels.check()?;
+
+    // the global rayon pool is used for parallel signature verification
+    if SETTINGS.threads.signature_verification_threads > 0 {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(SETTINGS.threads.signature_verification_threads)
+            .thread_name(|id| format!("rayon-node-{}", id))
+            .build_global()?;
+    }
+
+    let mut tokio_rt_builder = tokio::runtime::Builder::new_multi_thread();
+    if SETTINGS.threads.async_worker_threads > 0 {
+        tokio_rt_builder.worker_threads(SETTINGS.threads.async_worker_threads);
+    }
+    let tokio_rt = tokio_rt_builder
+        .max_blocking_threads(SETTINGS.threads.max_blocking_threads)
         .thread_name_fn(|| {
             static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
             let id = ATOMIC_ID.fetch_add(1, Ordering::SeqCst);
@@ -746,37 +1028,97 @@ fn main(args: Args) -> anyhow::Result<()> {
 
 async fn run(args: Args) -> anyhow::Result<()> {
     use tracing_subscriber::prelude::*;
+    // the log filter can be changed at runtime through the private API
+    let level_filter = match SETTINGS.logging.level {
+        4 => LevelFilter::TRACE,
+        3 => LevelFilter::DEBUG,
+        2 => LevelFilter::INFO,
+        1 => LevelFilter::WARN,
+        _ => LevelFilter::ERROR,
+    };
+    let (log_filter, log_filter_reload_handle) =
+        reload::Layer::new(Targets::new().with_default(level_filter));
+    let log_filter_handle = LogFilterHandle::new(log_filter_reload_handle);
     // spawn the console server in the background, returning a `Layer`:
     let tracing_layer = tracing_subscriber::fmt::layer()
-        .with_filter(match SETTINGS.logging.level {
-            4 => LevelFilter::TRACE,
-            3 => LevelFilter::DEBUG,
-            2 => LevelFilter::INFO,
-            1 => LevelFilter::WARN,
-            _ => LevelFilter::ERROR,
-        })
+        .with_filter(log_filter)
         .with_filter(filter_fn(|metadata| {
             metadata.target().starts_with("massa") // ignore non-massa logs
         }));
+    // optionally export the logs as JSON lines, with the fields of their spans
+    let json_layer = match &SETTINGS.logging.json_log_path {
+        Some(path) => {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            Some(
+                JsonLogLayer::new(LineWriter::new(file))
+                    .with_filter(Targets::new().with_default(level_filter))
+                    .with_filter(filter_fn(|metadata| metadata.target().starts_with("massa"))),
+            )
+        }
+        None => None,
+    };
     // build a `Subscriber` by combining layers with a `tracing_subscriber::Registry`:
     tracing_subscriber::registry()
         // add the console layer to the subscriber or default layers...
         .with(tracing_layer)
+        .with(json_layer)
         .init();
 
     // Setup panic handlers,
     // and when a panic occurs,
-    // run default handler,
+    // log a crash report, run default handler,
     // and then shutdown.
     let default_panic = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |info| {
+        // report the crash in the logs, which are often the only output that is kept
+        let thread = std::thread::current();
+        let location = info
+            .location()
+            .map(|location| location.to_string())
+            .unwrap_or_default();
+        let payload = info
+            .payload()
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown panic payload");
+        error!(
+            thread = thread.name().unwrap_or("unnamed"),
+            location = location.as_str(),
+            version = %*VERSION,
+            "the node crashed: {}",
+            payload
+        );
         default_panic(info);
         std::process::exit(1);
     }));
 
+    // write the initial files of a genesis instead of running the node
+    if let Some(spec_path) = args.write_genesis {
+        return write_genesis(&spec_path);
+    }
+
+    // replay an execution trace instead of running the node
+    if let Some(trace_path) = args.replay_trace {
+        let Some(snapshot_path) = args.replay_snapshot else {
+            anyhow::bail!("--replay-trace requires --replay-snapshot");
+        };
+        return replay_trace(&snapshot_path, &trace_path);
+    }
+
     // load or create wallet, asking for password if necessary
     let node_wallet = load_wallet(args.password, &SETTINGS.factory.staking_wallet_path)?;
 
+    // serve the metrics, kept across restarts
+    let metrics_manager = if SETTINGS.metrics.enabled {
+        Some(start_metrics_server(MetricsConfig {
+            bind: SETTINGS.metrics.bind,
+            channel_size: SETTINGS.channels.metrics_channel_size,
+        })?)
+    } else {
+        None
+    };
+
     loop {
         let (
             consensus_event_receiver,
@@ -792,7 +1134,25 @@ async fn run(args: Args) -> anyhow::Result<()> {
             api_private_handle,
             api_public_handle,
             api_handle,
-        ) = launch(node_wallet.clone()).await;
+            grpc_handle,
+            storage,
+        ) = launch(node_wallet.clone(), log_filter_handle.clone()).await;
+        let node_manager = NodeManager {
+            consensus_event_receiver,
+            bootstrap_manager,
+            consensus_manager,
+            execution_manager,
+            selector_manager,
+            pool_manager,
+            protocol_manager,
+            network_manager,
+            factory_manager,
+            api_private_handle,
+            api_public_handle,
+            api_handle,
+            grpc_handle,
+            storage,
+        };
 
         // interrupt signal listener
         let (tx, rx) = crossbeam_channel::bounded(1);
@@ -804,7 +1164,7 @@ async fn run(args: Args) -> anyhow::Result<()> {
         // loop over messages
         let restart = loop {
             massa_trace!("massa-node.main.run.select", {});
-            match consensus_event_receiver.try_recv() {
+            match node_manager.consensus_event_receiver.try_recv() {
                 Ok(evt) => match evt {
                     ConsensusEvent::NeedSync => {
                         warn!("in response to a desynchronization, the node is going to bootstrap again");
@@ -843,30 +1203,34 @@ async fn run(args: Args) -> anyhow::Result<()> {
                 }
                 _ => {}
             }
+            // stop the node rather than leaving it running without one of its workers
+            if let Some(name) = node_manager.crashed_worker() {
+                error!("{} stopped unexpectedly, stopping the node", name);
+                break false;
+            }
             sleep(Duration::from_millis(100));
         };
-        stop(
-            consensus_event_receiver,
-            Managers {
-                bootstrap_manager,
-                consensus_manager,
-                execution_manager,
-                selector_manager,
-                pool_manager,
-                protocol_manager,
-                network_manager,
-                factory_manager,
-            },
-            api_private_handle,
-            api_public_handle,
-            api_handle,
-        )
-        .await;
+        // a second interrupt signal exits without waiting for the workers
+        let forced_exit_listener = tokio::spawn(async {
+            signal::ctrl_c().await.unwrap();
+            warn!("interrupt signal received while stopping, exiting now");
+            process::exit(1);
+        });
+        let failed = node_manager.stop(WORKER_STOP_TIMEOUT).await;
+        forced_exit_listener.abort();
+        if !failed.is_empty() {
+            anyhow::bail!("workers failed to stop: {}", failed.join(", "));
+        }
 
         if !restart {
             break;
         }
         interrupt_signal_listener.abort();
     }
+
+    // stop metrics server
+    if let Some(metrics_manager) = metrics_manager {
+        metrics_manager.stop();
+    }
     Ok(())
 }
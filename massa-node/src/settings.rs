@@ -1,16 +1,21 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 //! Build here the default node settings from the configuration file toml
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use enum_map::EnumMap;
+use massa_models::amount::Amount;
+use massa_models::api::RollCompoundingMode;
 use massa_models::config::build_massa_settings;
 use massa_signature::PublicKey;
 use massa_time::MassaTime;
 use serde::Deserialize;
 use std::net::{IpAddr, SocketAddr};
 
-use massa_network_exports::{settings::PeerTypeConnectionConfig, PeerType};
+use massa_network_exports::{
+    settings::PeerTypeConnectionConfig, CompressionAlgorithm, EncryptionMode, PeerType,
+};
 
 lazy_static::lazy_static! {
     pub static ref SETTINGS: Settings = build_massa_settings("massa-node", "MASSA_NODE");
@@ -19,23 +24,125 @@ lazy_static::lazy_static! {
 #[derive(Debug, Deserialize, Clone)]
 pub struct LoggingSettings {
     pub level: usize,
+    /// file to which the logs are also exported as JSON lines, with their spans
+    pub json_log_path: Option<PathBuf>,
+}
+
+/// Sizing of the thread pools and CPU pinning of the node
+#[derive(Debug, Deserialize, Clone)]
+pub struct ThreadsSettings {
+    /// number of threads verifying signatures in parallel (0: one per CPU core)
+    pub signature_verification_threads: usize,
+    /// number of threads running the asynchronous tasks (0: one per CPU core)
+    pub async_worker_threads: usize,
+    /// maximum number of threads running blocking tasks such as (de)serialization. Must be positive
+    pub max_blocking_threads: usize,
+    /// CPU cores on which all the node threads run (empty: no pinning)
+    pub cpu_cores: Vec<usize>,
+}
+
+impl ThreadsSettings {
+    /// Checks that the blocking pool can run at least one task
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.max_blocking_threads == 0 {
+            anyhow::bail!("threads.max_blocking_threads must be positive");
+        }
+        Ok(())
+    }
+}
+
+/// Capacities of the channels between the node workers.
+/// Smaller channels use less memory, larger ones absorb bursts of messages better.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ChannelsSettings {
+    /// consensus command and event channels
+    pub consensus_channel_size: usize,
+    /// selector command channel
+    pub selector_channel_size: usize,
+    /// pool command channel
+    pub pool_controller_channel_size: usize,
+    /// protocol command channel
+    pub protocol_controller_channel_size: usize,
+    /// protocol event channel
+    pub protocol_event_channel_size: usize,
+    /// network command channel
+    pub network_controller_channel_size: usize,
+    /// network event channel
+    pub network_event_channel_size: usize,
+    /// command channel of each connected node
+    pub network_node_command_channel_size: usize,
+    /// event channel shared by the connected nodes
+    pub network_node_event_channel_size: usize,
+    /// metrics collection channel
+    pub metrics_channel_size: usize,
+}
+
+impl ChannelsSettings {
+    /// Checks that all the capacities are positive
+    pub fn check(&self) -> anyhow::Result<()> {
+        let sizes = [
+            ("consensus_channel_size", self.consensus_channel_size),
+            ("selector_channel_size", self.selector_channel_size),
+            (
+                "pool_controller_channel_size",
+                self.pool_controller_channel_size,
+            ),
+            (
+                "protocol_controller_channel_size",
+                self.protocol_controller_channel_size,
+            ),
+            (
+                "protocol_event_channel_size",
+                self.protocol_event_channel_size,
+            ),
+            (
+                "network_controller_channel_size",
+                self.network_controller_channel_size,
+            ),
+            (
+                "network_event_channel_size",
+                self.network_event_channel_size,
+            ),
+            (
+                "network_node_command_channel_size",
+                self.network_node_command_channel_size,
+            ),
+            (
+                "network_node_event_channel_size",
+                self.network_node_event_channel_size,
+            ),
+            ("metrics_channel_size", self.metrics_channel_size),
+        ];
+        for (name, size) in sizes {
+            if size == 0 {
+                anyhow::bail!("channels.{} must be positive", name);
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct ExecutionSettings {
     pub max_final_events: usize,
+    pub final_events_retention_periods: u64,
+    pub max_address_history_slots: usize,
+    pub max_transfer_history_slots: usize,
     pub readonly_queue_length: usize,
     pub cursor_delay: MassaTime,
     pub stats_time_window_duration: MassaTime,
     pub max_read_only_gas: u64,
     pub abi_gas_costs_file: PathBuf,
     pub wasm_gas_costs_file: PathBuf,
+    pub trace_path: Option<PathBuf>,
+    pub broadcast_slot_execution_output_capacity: usize,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct SelectionSettings {
     pub max_draw_cache: usize,
     pub initial_rolls_path: PathBuf,
+    pub initial_deferred_credits_path: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -43,24 +150,31 @@ pub struct LedgerSettings {
     pub initial_ledger_path: PathBuf,
     pub disk_ledger_path: PathBuf,
     pub final_history_length: usize,
+    pub initial_snapshot_path: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StorageSettings {
+    pub disk_storage_path: PathBuf,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct NetworkSettings {
     pub bind: SocketAddr,
     pub routable_ip: Option<IpAddr>,
+    pub routable_ip_detection_votes: usize,
     pub protocol_port: u16,
     pub connect_timeout: MassaTime,
     pub wakeup_interval: MassaTime,
     pub initial_peers_file: PathBuf,
     pub peers_file: PathBuf,
+    pub seed_peer_lists: Vec<(String, PublicKey)>,
     pub keypair_file: PathBuf,
     pub peer_types_config: EnumMap<PeerType, PeerTypeConnectionConfig>,
     pub max_in_connections_per_ip: usize,
     pub max_idle_peers: usize,
     pub max_banned_peers: usize,
     pub peers_file_dump_interval: MassaTime,
-    pub message_timeout: MassaTime,
     pub ask_peer_list_interval: MassaTime,
     pub max_send_wait_node_event: MassaTime,
     pub max_send_wait_network_event: MassaTime,
@@ -70,14 +184,23 @@ pub struct NetworkSettings {
     pub max_operations_per_message: u32,
     pub max_bytes_read: f64,
     pub max_bytes_write: f64,
+    pub max_node_outstanding_bytes: u64,
+    pub message_compression: Vec<CompressionAlgorithm>,
+    pub min_compressed_message_size: u32,
+    pub message_encryption: EncryptionMode,
+    pub quic_enabled: bool,
 }
 
 /// Bootstrap configuration.
 #[derive(Debug, Deserialize, Clone)]
 pub struct BootstrapSettings {
     pub bootstrap_list: Vec<(SocketAddr, PublicKey)>,
+    pub bootstrap_dns_list: Vec<(String, PublicKey)>,
     pub bootstrap_whitelist_path: PathBuf,
     pub bootstrap_blacklist_path: PathBuf,
+    pub bootstrap_cursor_path: PathBuf,
+    pub secondary_bootstrap_server_count: usize,
+    pub max_pending_bootstrap_parts: usize,
     pub bind: Option<SocketAddr>,
     pub connect_timeout: MassaTime,
     pub read_timeout: MassaTime,
@@ -99,8 +222,18 @@ pub struct BootstrapSettings {
 pub struct FactorySettings {
     /// Initial delay
     pub initial_delay: MassaTime,
+    /// Time before a slot at which the block body is packed
+    pub block_preparation_time: MassaTime,
     /// Staking wallet file
     pub staking_wallet_path: PathBuf,
+    /// Start in standby mode, without producing blocks and endorsements
+    pub standby: bool,
+    /// Standby heartbeat timeout
+    pub standby_heartbeat_timeout: MassaTime,
+    /// Conversion of the deferred credits of the staking addresses into rolls
+    pub roll_compounding: RollCompoundingMode,
+    /// Fee of the roll purchases sent by the roll compounding
+    pub roll_compounding_fee: Amount,
 }
 
 /// Pool configuration, read from a file configuration
@@ -110,6 +243,9 @@ pub struct PoolSettings {
     pub max_operation_future_validity_start_periods: u64,
     pub max_endorsement_count: u64,
     pub max_item_return_count: usize,
+    pub max_operations_per_address: usize,
+    pub operation_rebroadcast_periods: u64,
+    pub max_operation_rebroadcast_count: u32,
 }
 
 /// API and server configuration, read from a file configuration.
@@ -117,9 +253,11 @@ pub struct PoolSettings {
 pub struct APISettings {
     pub draw_lookahead_period_count: u64,
     pub bind_private: SocketAddr,
+    pub private_auth_token_path: Option<PathBuf>,
     pub bind_public: SocketAddr,
     pub bind_api: SocketAddr,
     pub max_arguments: u64,
+    pub max_simulated_cycles: u64,
     pub openrpc_spec_path: PathBuf,
     pub max_request_body_size: u32,
     pub max_response_body_size: u32,
@@ -131,19 +269,47 @@ pub struct APISettings {
     pub ping_interval: MassaTime,
     pub enable_http: bool,
     pub enable_ws: bool,
+    pub max_in_flight_requests: usize,
+    pub request_timeout: MassaTime,
+    pub method_timeouts: HashMap<String, MassaTime>,
+}
+
+/// gRPC API settings
+#[derive(Debug, Deserialize, Clone)]
+pub struct GrpcSettings {
+    pub enabled: bool,
+    pub bind: SocketAddr,
+    pub max_decoding_message_size: usize,
+    pub max_encoding_message_size: usize,
+    pub concurrency_limit_per_connection: usize,
+    pub timeout: MassaTime,
+}
+
+/// Prometheus metrics settings
+#[derive(Debug, Deserialize, Clone)]
+pub struct MetricsSettings {
+    /// serve the metrics of the node
+    pub enabled: bool,
+    /// address on which the Prometheus endpoint listens
+    pub bind: SocketAddr,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Settings {
     pub logging: LoggingSettings,
+    pub threads: ThreadsSettings,
+    pub channels: ChannelsSettings,
+    pub metrics: MetricsSettings,
     pub protocol: ProtocolSettings,
     pub network: NetworkSettings,
     pub consensus: ConsensusSettings,
     pub api: APISettings,
+    pub grpc: GrpcSettings,
     pub bootstrap: BootstrapSettings,
     pub pool: PoolSettings,
     pub execution: ExecutionSettings,
     pub ledger: LedgerSettings,
+    pub storage: StorageSettings,
     pub selector: SelectionSettings,
     pub factory: FactorySettings,
 }
@@ -176,6 +342,9 @@ pub struct ConsensusSettings {
     pub broadcast_blocks_capacity: usize,
     /// filled blocks sender(channel) capacity
     pub broadcast_filled_blocks_capacity: usize,
+    pub check_graph_invariants: bool,
+    /// maximum number of queued block commands processed in a row before the consensus state is handed over to its readers
+    pub max_commands_per_batch: usize,
 }
 
 /// Protocol Configuration, read from toml user configuration file
@@ -201,6 +370,14 @@ pub struct ProtocolSettings {
     pub max_simultaneous_ask_blocks_per_node: usize,
     /// Max wait time for sending a Network or Node event.
     pub max_send_wait: MassaTime,
+    /// Write timeout for handshake-sized control messages (peer lists, asks)
+    pub handshake_message_timeout: MassaTime,
+    /// Write timeout for block headers
+    pub header_message_timeout: MassaTime,
+    /// Write timeout for block bodies
+    pub block_message_timeout: MassaTime,
+    /// Write timeout for operation and endorsement batches
+    pub large_message_timeout: MassaTime,
     /// Maximum number of batches in the memory buffer.
     /// Dismiss the new batches if overflow
     pub operation_batch_buffer_capacity: usize,
@@ -215,6 +392,20 @@ pub struct ProtocolSettings {
     pub operation_announcement_interval: MassaTime,
     /// Maximum of operations sent in one message.
     pub max_operations_per_message: u64,
+    /// Maximum number of block ids a node can ask us for per second
+    pub max_block_asks_per_second: u64,
+    /// Maximum number of block headers a node can send us per second
+    pub max_headers_per_second: u64,
+    /// Maximum number of received block headers being checked at the same time
+    pub max_pending_header_validations: usize,
+    /// Maximum number of operations in an announcement from a node
+    pub max_operations_per_announcement: usize,
+    /// Misbehavior score at which a node is banned
+    pub max_misbehavior_score: u64,
+    /// Number of randomly chosen nodes the header of an integrated block is sent to immediately
+    pub block_propagation_fanout: usize,
+    /// Delay after which the header of an integrated block is sent to the remaining nodes
+    pub block_propagation_delay: MassaTime,
     /// Time threshold after which operation are not propagated
     pub max_operations_propagation_time: MassaTime,
     /// Time threshold after which operation are not propagated
@@ -228,3 +419,12 @@ pub struct ProtocolSettings {
 fn test_load_node_config() {
     let _ = *SETTINGS;
 }
+
+#[cfg(test)]
+#[test]
+fn test_threads_settings_check() {
+    let mut threads = SETTINGS.threads.clone();
+    assert!(threads.check().is_ok());
+    threads.max_blocking_threads = 0;
+    assert!(threads.check().is_err());
+}
@@ -0,0 +1,209 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Orderly shutdown of the node workers.
+//!
+//! Workers are stopped one by one in dependency order, each within a timeout,
+//! so that a worker that does not stop cannot hang the node, and is reported.
+
+use crossbeam_channel::Receiver;
+use massa_api::StopHandle;
+use massa_bootstrap::BootstrapManager;
+use massa_consensus_exports::{events::ConsensusEvent, ConsensusManager};
+use massa_execution_exports::ExecutionManager;
+use massa_factory_exports::FactoryManager;
+use massa_network_exports::NetworkManager;
+use massa_pool_exports::PoolManager;
+use massa_pos_exports::SelectorManager;
+use massa_protocol_exports::ProtocolManager;
+use massa_storage::Storage;
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Maximum time given to each worker to stop
+pub const WORKER_STOP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Everything that needs to be stopped when the node stops or restarts
+pub struct NodeManager {
+    /// kept until consensus is stopped so that it can still send its events
+    pub consensus_event_receiver: Receiver<ConsensusEvent>,
+    pub bootstrap_manager: Option<BootstrapManager>,
+    pub consensus_manager: Box<dyn ConsensusManager>,
+    pub execution_manager: Box<dyn ExecutionManager>,
+    pub selector_manager: Box<dyn SelectorManager>,
+    pub pool_manager: Box<dyn PoolManager>,
+    pub protocol_manager: ProtocolManager,
+    pub network_manager: NetworkManager,
+    pub factory_manager: Box<dyn FactoryManager>,
+    pub api_private_handle: StopHandle,
+    pub api_public_handle: StopHandle,
+    pub api_handle: StopHandle,
+    pub grpc_handle: Option<massa_grpc::StopHandle>,
+    pub storage: Storage,
+}
+
+/// Waits for an asynchronous worker stop, at most `timeout`
+///
+/// # Returns
+/// The output of the stop, or the name of the worker if it failed to stop
+async fn stop_async<T, E: Display>(
+    name: &'static str,
+    timeout: Duration,
+    stop: impl Future<Output = Result<T, E>>,
+) -> Result<T, &'static str> {
+    match tokio::time::timeout(timeout, stop).await {
+        Ok(Ok(res)) => Ok(res),
+        Ok(Err(err)) => {
+            error!("{} failed to stop: {}", name, err);
+            Err(name)
+        }
+        Err(_) => {
+            error!("{} did not stop within {:?}", name, timeout);
+            Err(name)
+        }
+    }
+}
+
+/// Runs a blocking worker stop on a blocking thread and waits for it at most `timeout`.
+/// If the worker does not stop in time, its blocking thread is left behind.
+///
+/// # Returns
+/// The name of the worker if it failed to stop
+async fn stop_blocking(
+    name: &'static str,
+    timeout: Duration,
+    stop: impl FnOnce() + Send + 'static,
+) -> Result<(), &'static str> {
+    stop_async(name, timeout, tokio::task::spawn_blocking(stop)).await
+}
+
+impl NodeManager {
+    /// Returns the name of a worker whose task ended while the node is running, if any.
+    ///
+    /// The asynchronous workers log their error and end when they crash,
+    /// which would leave the node running without them.
+    /// A panic in any worker exits the node through the panic hook instead.
+    pub fn crashed_worker(&self) -> Option<&'static str> {
+        if self.protocol_manager.is_finished() {
+            return Some("protocol controller");
+        }
+        if self.network_manager.is_finished() {
+            return Some("network controller");
+        }
+        None
+    }
+
+    /// Stops the workers in dependency order: first the ones producing data for the others.
+    /// A worker failing to stop does not prevent the next ones from being stopped.
+    ///
+    /// # Returns
+    /// The names of the workers that failed to stop
+    pub async fn stop(self, timeout: Duration) -> Vec<&'static str> {
+        let NodeManager {
+            consensus_event_receiver,
+            bootstrap_manager,
+            mut consensus_manager,
+            mut execution_manager,
+            mut selector_manager,
+            mut pool_manager,
+            protocol_manager,
+            network_manager,
+            mut factory_manager,
+            api_private_handle,
+            api_public_handle,
+            api_handle,
+            grpc_handle,
+            storage,
+        } = self;
+        let mut failed = Vec::new();
+
+        // keep the objects referenced at this point on disk while the components release them
+        storage.close_disk_backend();
+
+        // stop bootstrap
+        if let Some(bootstrap_manager) = bootstrap_manager {
+            if let Err(name) =
+                stop_async("bootstrap server", timeout, bootstrap_manager.stop()).await
+            {
+                failed.push(name);
+            }
+        }
+
+        // stop APIs
+        api_public_handle.stop();
+        api_private_handle.stop();
+        api_handle.stop();
+        if let Some(grpc_handle) = grpc_handle {
+            grpc_handle.stop();
+        }
+
+        // stop factory
+        if let Err(name) = stop_blocking("factory", timeout, move || factory_manager.stop()).await {
+            failed.push(name);
+        }
+
+        // stop protocol controller
+        let network_event_receiver =
+            match stop_async("protocol controller", timeout, protocol_manager.stop()).await {
+                Ok(network_event_receiver) => Some(network_event_receiver),
+                Err(name) => {
+                    failed.push(name);
+                    None
+                }
+            };
+
+        // stop consensus
+        if let Err(name) =
+            stop_blocking("consensus", timeout, move || consensus_manager.stop()).await
+        {
+            failed.push(name);
+        }
+        drop(consensus_event_receiver);
+
+        // stop pool
+        if let Err(name) = stop_blocking("pool", timeout, move || pool_manager.stop()).await {
+            failed.push(name);
+        }
+
+        // stop execution controller
+        if let Err(name) =
+            stop_blocking("execution", timeout, move || execution_manager.stop()).await
+        {
+            failed.push(name);
+        }
+
+        // stop selector controller
+        if let Err(name) = stop_blocking("selector", timeout, move || selector_manager.stop()).await
+        {
+            failed.push(name);
+        }
+
+        // stop network controller, which needs the network events released by protocol
+        match network_event_receiver {
+            Some(network_event_receiver) => {
+                if let Err(name) = stop_async(
+                    "network controller",
+                    timeout,
+                    network_manager.stop(network_event_receiver),
+                )
+                .await
+                {
+                    failed.push(name);
+                }
+            }
+            None => {
+                error!(
+                    "network controller not stopped because protocol did not release its events"
+                );
+                failed.push("network controller");
+            }
+        }
+
+        if failed.is_empty() {
+            info!("all workers stopped");
+        }
+        // note that FinalLedger gets destroyed as soon as its Arc count goes to zero
+        failed
+    }
+}
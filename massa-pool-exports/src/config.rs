@@ -10,6 +10,8 @@ pub struct PoolConfig {
     pub thread_count: u8,
     /// maximal total block operations size
     pub max_block_size: u32,
+    /// maximal number of operations per block
+    pub max_operations_per_block: u32,
     /// maximal gas per block
     pub max_block_gas: u64,
     /// cost (in coins) of a single roll
@@ -18,6 +20,12 @@ pub struct PoolConfig {
     pub operation_validity_periods: u64,
     /// max operation pool size per thread (in number of operations)
     pub max_operation_pool_size_per_thread: usize,
+    /// max number of pending operations per sender address
+    pub max_operations_per_address: usize,
+    /// operations expiring within that many periods after the last final period are re-broadcast
+    pub operation_rebroadcast_periods: u64,
+    /// max number of times an operation nearing expiry is re-broadcast
+    pub max_operation_rebroadcast_count: u32,
     /// max endorsement pool size per thread (in number of endorsements)
     pub max_endorsements_pool_size_per_thread: usize,
     /// max number of endorsements per block
@@ -1,8 +1,8 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::config::{
-    ENDORSEMENT_COUNT, MAX_BLOCK_SIZE, MAX_GAS_PER_BLOCK, OPERATION_VALIDITY_PERIODS, ROLL_PRICE,
-    THREAD_COUNT,
+    ENDORSEMENT_COUNT, MAX_BLOCK_SIZE, MAX_GAS_PER_BLOCK, MAX_OPERATIONS_PER_BLOCK,
+    OPERATION_VALIDITY_PERIODS, ROLL_PRICE, THREAD_COUNT,
 };
 
 use crate::PoolConfig;
@@ -15,7 +15,11 @@ impl Default for PoolConfig {
             max_block_gas: MAX_GAS_PER_BLOCK,
             roll_price: ROLL_PRICE,
             max_block_size: MAX_BLOCK_SIZE,
+            max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
             max_operation_pool_size_per_thread: 1000,
+            max_operations_per_address: 1000,
+            operation_rebroadcast_periods: 2,
+            max_operation_rebroadcast_count: 2,
             max_endorsements_pool_size_per_thread: 1000,
             max_block_endorsement_count: ENDORSEMENT_COUNT,
             channels_size: 1024,
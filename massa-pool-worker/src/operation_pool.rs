@@ -7,6 +7,7 @@ use massa_models::{
     operation::OperationId,
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     slot::Slot,
+    stats::OperationStage,
 };
 use massa_pool_exports::PoolConfig;
 use massa_storage::Storage;
@@ -27,6 +28,9 @@ pub struct OperationPool {
     /// operations sorted by increasing expiration slot
     ops_per_expiration: BTreeSet<(Slot, OperationId)>,
 
+    /// number of pending operations per sender address
+    ops_count_per_address: PreHashMap<Address, usize>,
+
     /// storage instance
     pub(crate) storage: Storage,
 
@@ -47,6 +51,7 @@ impl OperationPool {
             operations: Default::default(),
             sorted_ops_per_thread: vec![Default::default(); config.thread_count as usize],
             ops_per_expiration: Default::default(),
+            ops_count_per_address: Default::default(),
             last_cs_final_periods: vec![0u64; config.thread_count as usize],
             config,
             storage: storage.clone_without_refs(),
@@ -65,7 +70,9 @@ impl OperationPool {
     }
 
     /// notify of new final slot
-    pub(crate) fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) {
+    ///
+    /// Returns a storage referencing the operations nearing expiry that need to be re-broadcast.
+    pub(crate) fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) -> Storage {
         // update internal final slot counter
         self.last_cs_final_periods = final_cs_periods.to_vec();
 
@@ -83,11 +90,71 @@ impl OperationPool {
             if !self.sorted_ops_per_thread[expire_slot.thread as usize].remove(&op_info.cursor) {
                 panic!("expected op presence in sorted list")
             }
+            release_address_count(&mut self.ops_count_per_address, &op_info.creator_address);
             removed_ops.insert(op_id);
         }
 
         // notify storage that pool has lost references to removed_ops
         self.storage.drop_operation_refs(&removed_ops);
+
+        self.get_ops_to_rebroadcast()
+    }
+
+    /// Gets the operations nearing expiry that were not re-broadcast too many times yet
+    /// and are not included in a block yet, and counts them as re-broadcast.
+    fn get_ops_to_rebroadcast(&mut self) -> Storage {
+        let mut rebroadcast_ops: PreHashSet<OperationId> = Default::default();
+        if self.config.max_operation_rebroadcast_count > 0 {
+            // gather the candidates per thread
+            let max_final_period = self
+                .last_cs_final_periods
+                .iter()
+                .max()
+                .copied()
+                .unwrap_or(0);
+            let mut candidates: Vec<PreHashSet<OperationId>> =
+                vec![Default::default(); self.config.thread_count as usize];
+            for (expire_slot, op_id) in self.ops_per_expiration.iter() {
+                if expire_slot.period
+                    > max_final_period.saturating_add(self.config.operation_rebroadcast_periods)
+                {
+                    break;
+                }
+                if expire_slot.period
+                    > self.last_cs_final_periods[expire_slot.thread as usize]
+                        .saturating_add(self.config.operation_rebroadcast_periods)
+                {
+                    continue;
+                }
+                let op_info = self
+                    .operations
+                    .get(op_id)
+                    .expect("expected op presence in operations list");
+                if op_info.rebroadcast_count < self.config.max_operation_rebroadcast_count {
+                    candidates[expire_slot.thread as usize].insert(*op_id);
+                }
+            }
+
+            // operations that were already executed are in a block and don't need to be re-broadcast
+            for (thread, thread_candidates) in candidates.into_iter().enumerate() {
+                if thread_candidates.is_empty() {
+                    continue;
+                }
+                for op_id in self
+                    .execution_controller
+                    .unexecuted_ops_among(&thread_candidates, thread as u8)
+                {
+                    if let Some(op_info) = self.operations.get_mut(&op_id) {
+                        op_info.rebroadcast_count += 1;
+                        rebroadcast_ops.insert(op_id);
+                    }
+                }
+            }
+        }
+
+        let mut res_storage = self.storage.clone_without_refs();
+        res_storage.claim_operation_refs(&rebroadcast_ops);
+        res_storage
     }
 
     /// Checks if an operation is relevant according to its thread and period validity range
@@ -123,7 +190,25 @@ impl OperationPool {
                 if !self.is_operation_relevant(&op_info) {
                     continue;
                 }
+                // an operation bigger than a block can never be included
+                if op_info.size > self.config.max_block_size as usize {
+                    continue;
+                }
+                // bound the number of pending operations of a single sender
+                if self
+                    .ops_count_per_address
+                    .get(&op_info.creator_address)
+                    .map_or(false, |count| {
+                        *count >= self.config.max_operations_per_address
+                    })
+                {
+                    continue;
+                }
                 if let Ok(op_info) = self.operations.try_insert(op_info.id, op_info) {
+                    *self
+                        .ops_count_per_address
+                        .entry(op_info.creator_address)
+                        .or_default() += 1;
                     if !self.sorted_ops_per_thread[op_info.thread as usize].insert(op_info.cursor) {
                         panic!("sorted ops should not contain the op at this point");
                     }
@@ -151,10 +236,15 @@ impl OperationPool {
                 if !self.ops_per_expiration.remove(&(end_slot, op_info.id)) {
                     panic!("the operation should be in self.ops_per_expiration at this point");
                 }
+                release_address_count(&mut self.ops_count_per_address, &op_info.creator_address);
                 removed.insert(op_info.id);
             }
         });
 
+        // measure the time the admitted operations took to reach the pool
+        ops_storage
+            .record_operation_stage(added.difference(&removed), OperationStage::PoolAdmission);
+
         // This will add the new ops to the storage without taking locks.
         // It just take the local references from `ops_storage` if they are not in `self.storage` yet.
         // If the objects are already in `self.storage` the references in ops_storage it will not add them to `self.storage` and
@@ -185,6 +275,11 @@ impl OperationPool {
 
         // iterate over pool operations in the right thread, from best to worst
         for cursor in self.sorted_ops_per_thread[slot.thread as usize].iter() {
+            // stop when the block is full
+            if op_ids.len() >= self.config.max_operations_per_block as usize {
+                break;
+            }
+
             let op_info = self
                 .operations
                 .get(&cursor.get_id())
@@ -263,3 +358,16 @@ impl OperationPool {
         (op_ids, res_storage)
     }
 }
+
+/// Decrements the number of pending operations of an address, forgetting it when it reaches zero
+fn release_address_count(
+    ops_count_per_address: &mut PreHashMap<Address, usize>,
+    address: &Address,
+) {
+    if let Some(count) = ops_count_per_address.get_mut(address) {
+        *count -= 1;
+        if *count == 0 {
+            ops_count_per_address.remove(address);
+        }
+    }
+}
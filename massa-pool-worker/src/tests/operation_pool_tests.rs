@@ -12,6 +12,14 @@
 //! Same as classic but we try to add irrelevant operation. (See the definition
 //! chapter below)
 //!
+//! # Add oversized operation
+//! Function: [`test_add_oversized_operation`]
+//! Same as classic but the operations do not fit in a block.
+//!
+//! # Add operations over the per-address cap
+//! Function: [`test_add_operations_over_address_cap`]
+//! Same as classic but a single sender has more pending operations than allowed.
+//!
 //! # Definition
 //! Relevant operation: Operation with a validity range corresponding to the
 //! latest period given his own thread. All operation which doesn't fit these
@@ -56,6 +64,35 @@ fn test_add_irrelevant_operation() {
     });
 }
 
+/// Test if adding operations bigger than a block make simply skip the add.
+#[test]
+fn test_add_oversized_operation() {
+    let pool_config = PoolConfig {
+        max_block_size: 10,
+        ..PoolConfig::default()
+    };
+    operation_pool_test(pool_config, |mut operation_pool, mut storage| {
+        storage.store_operations(create_some_operations(10, &KeyPair::generate(), 2));
+        operation_pool.add_operations(storage);
+        assert_eq!(operation_pool.storage.get_op_refs().len(), 0);
+    });
+}
+
+/// Test if the operations of a sender over the per-address cap are skipped.
+#[test]
+fn test_add_operations_over_address_cap() {
+    let pool_config = PoolConfig {
+        max_operations_per_address: 4,
+        ..PoolConfig::default()
+    };
+    operation_pool_test(pool_config, |mut operation_pool, mut storage| {
+        storage.store_operations(create_some_operations(10, &KeyPair::generate(), 2));
+        storage.store_operations(create_some_operations(2, &KeyPair::generate(), 2));
+        operation_pool.add_operations(storage);
+        assert_eq!(operation_pool.storage.get_op_refs().len(), 6);
+    });
+}
+
 fn get_transaction(expire_period: u64, fee: u64) -> WrappedOperation {
     let sender_keypair = KeyPair::generate();
 
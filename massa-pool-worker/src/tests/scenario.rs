@@ -11,6 +11,10 @@
 //! Function: [`test_get_operations_overflow`]
 //! Same as the previous test with a low limit of size to check if
 //! configurations are taken into account.
+//!
+//! # Get operations count overflow
+//! Function: [`test_get_operations_count_overflow`]
+//! Same as the previous test with a low limit of operations per block.
 
 use std::sync::mpsc::Receiver;
 use std::time::Duration;
@@ -19,6 +23,7 @@ use crate::tests::tools::create_some_operations;
 use crate::tests::tools::pool_test;
 use massa_execution_exports::test_exports::MockExecutionControllerMessage as ControllerMsg;
 use massa_models::address::Address;
+use massa_models::amount::Amount;
 use massa_models::operation::OperationId;
 use massa_models::prehash::PreHashSet;
 use massa_models::slot::Slot;
@@ -128,6 +133,27 @@ fn launch_basic_get_block_operation_execution_mock(
     });
 }
 
+/// Launch a mock for execution controller on call `get_block_operation` API,
+/// answering that no operation was executed and that every creator can pay for its operations.
+fn launch_funded_get_block_operation_execution_mock(recvr: Receiver<ControllerMsg>) {
+    std::thread::spawn(move || {
+        while let Ok(msg) = recvr.recv_timeout(Duration::from_millis(100)) {
+            match msg {
+                ControllerMsg::UnexecutedOpsAmong {
+                    ops, response_tx, ..
+                } => response_tx.send(ops).unwrap(),
+                ControllerMsg::GetFinalAndCandidateBalance {
+                    addresses,
+                    response_tx,
+                } => response_tx
+                    .send(vec![(Some(Amount::default()), None); addresses.len()])
+                    .unwrap(),
+                _ => panic!("unexpected controller request"),
+            }
+        }
+    });
+}
+
 /// # Test get block operation with overflow
 /// Try to get some operations stored in pool for a block, but pool's operations
 /// are bigger than the max block's size.
@@ -185,3 +211,47 @@ fn test_get_operations_overflow() {
         },
     );
 }
+
+/// # Test get operation with a limit of operations per block
+///
+/// ## Initialization
+/// Create 10 operations.
+/// Set `max_operations_per_block` to 5.
+/// Add 10 operations to pool.
+///
+/// Start mocked execution controller thread.
+///
+/// ## Expected result
+/// The block operation storage built for all threads is expected to have
+/// only 5 operations.
+#[test]
+fn test_get_operations_count_overflow() {
+    static OP_LEN: usize = 10;
+    static MAX_OP_LEN: usize = 5;
+    let keypair = KeyPair::generate();
+    let creator_address = Address::from_public_key(&keypair.get_public_key());
+    let operations = create_some_operations(OP_LEN, &keypair, 1);
+    let config = PoolConfig {
+        max_operations_per_block: MAX_OP_LEN as u32,
+        ..Default::default()
+    };
+    let creator_thread = creator_address.get_thread(config.thread_count);
+    pool_test(
+        config,
+        |mut pool_manager, mut pool_controller, execution_receiver, mut storage| {
+            storage.store_operations(operations);
+            pool_controller.add_operations(storage);
+
+            // start mock execution thread
+            launch_funded_get_block_operation_execution_mock(execution_receiver);
+
+            let block_operations_storage = pool_controller
+                .get_block_operations(&Slot::new(1, creator_thread))
+                .1;
+
+            pool_manager.stop();
+
+            assert_eq!(block_operations_storage.get_op_refs().len(), MAX_OP_LEN);
+        },
+    );
+}
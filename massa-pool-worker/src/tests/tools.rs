@@ -15,6 +15,7 @@ use massa_models::{
     wrapped::WrappedContent,
 };
 use massa_pool_exports::{PoolConfig, PoolController, PoolManager};
+use massa_protocol_exports::ProtocolCommandSender;
 use massa_signature::{KeyPair, PublicKey};
 use massa_storage::Storage;
 use std::str::FromStr;
@@ -63,8 +64,13 @@ where
     let storage: Storage = Storage::create_root();
 
     let (execution_controller, execution_receiver) = MockExecutionController::new_with_receiver();
-    let (pool_manager, pool_controller) =
-        start_pool_controller(cfg, &storage, execution_controller);
+    let (protocol_command_sender, _protocol_command_receiver) = tokio::sync::mpsc::channel(1024);
+    let (pool_manager, pool_controller) = start_pool_controller(
+        cfg,
+        &storage,
+        execution_controller,
+        ProtocolCommandSender(protocol_command_sender),
+    );
 
     test(pool_manager, pool_controller, execution_receiver, storage)
 }
@@ -36,6 +36,8 @@ pub struct OperationInfo {
     /// max amount that the op might spend from the sender's balance
     pub max_spending: Amount,
     pub validity_period_range: RangeInclusive<u64>,
+    /// number of times the op was re-broadcast because it was nearing expiry
+    pub rebroadcast_count: u32,
 }
 
 impl OperationInfo {
@@ -55,6 +57,7 @@ impl OperationInfo {
             thread: op.creator_address.get_thread(thread_count),
             validity_period_range: op.get_validity_range(operation_validity_periods),
             max_spending: op.get_max_spending(roll_price),
+            rebroadcast_count: 0,
         }
     }
 }
@@ -8,6 +8,7 @@ use crate::{controller_impl::PoolControllerImpl, endorsement_pool::EndorsementPo
 use massa_execution_exports::ExecutionController;
 use massa_pool_exports::PoolConfig;
 use massa_pool_exports::{PoolController, PoolManager};
+use massa_protocol_exports::ProtocolCommandSender;
 use massa_storage::Storage;
 use parking_lot::RwLock;
 use std::sync::mpsc::RecvError;
@@ -17,6 +18,7 @@ use std::{
     sync::Arc,
     thread::JoinHandle,
 };
+use tracing::warn;
 
 /// Endorsement pool write thread instance
 pub(crate) struct EndorsementPoolThread {
@@ -47,17 +49,25 @@ impl EndorsementPoolThread {
     /// Runs the thread
     fn run(self) {
         loop {
-            match self.receiver.recv() {
+            let endorsement_pool = match self.receiver.recv() {
                 Err(RecvError) => break,
                 Ok(Command::Stop) => break,
                 Ok(Command::AddItems(endorsements)) => {
-                    self.endorsement_pool.write().add_endorsements(endorsements)
+                    let mut endorsement_pool = self.endorsement_pool.write();
+                    endorsement_pool.add_endorsements(endorsements);
+                    endorsement_pool
                 }
-                Ok(Command::NotifyFinalCsPeriods(final_cs_periods)) => self
-                    .endorsement_pool
-                    .write()
-                    .notify_final_cs_periods(&final_cs_periods),
-            }
+                Ok(Command::NotifyFinalCsPeriods(final_cs_periods)) => {
+                    let mut endorsement_pool = self.endorsement_pool.write();
+                    endorsement_pool.notify_final_cs_periods(&final_cs_periods);
+                    endorsement_pool
+                }
+            };
+            massa_metrics::set_gauge(
+                "massa_pool_endorsements",
+                &[],
+                endorsement_pool.len() as f64,
+            );
         }
     }
 }
@@ -68,6 +78,8 @@ pub(crate) struct OperationPoolThread {
     receiver: Receiver<Command>,
     /// Shared reference to the operation pool
     operation_pool: Arc<RwLock<OperationPool>>,
+    /// Protocol command sender, to re-broadcast operations nearing expiry
+    protocol_command_sender: ProtocolCommandSender,
 }
 
 impl OperationPoolThread {
@@ -75,6 +87,7 @@ impl OperationPoolThread {
     pub(crate) fn spawn(
         receiver: Receiver<Command>,
         operation_pool: Arc<RwLock<OperationPool>>,
+        protocol_command_sender: ProtocolCommandSender,
     ) -> JoinHandle<()> {
         let thread_builder = thread::Builder::new().name("operation-pool".into());
         thread_builder
@@ -82,6 +95,7 @@ impl OperationPoolThread {
                 let this = Self {
                     receiver,
                     operation_pool,
+                    protocol_command_sender,
                 };
                 this.run()
             })
@@ -89,19 +103,35 @@ impl OperationPoolThread {
     }
 
     /// Run the thread.
-    fn run(self) {
+    fn run(mut self) {
         loop {
             match self.receiver.recv() {
                 Err(RecvError) => break,
                 Ok(Command::Stop) => break,
                 Ok(Command::AddItems(operations)) => {
-                    self.operation_pool.write().add_operations(operations)
+                    self.operation_pool.write().add_operations(operations);
                 }
-                Ok(Command::NotifyFinalCsPeriods(final_cs_periods)) => self
-                    .operation_pool
-                    .write()
-                    .notify_final_cs_periods(&final_cs_periods),
-            };
+                Ok(Command::NotifyFinalCsPeriods(final_cs_periods)) => {
+                    let rebroadcast_ops = self
+                        .operation_pool
+                        .write()
+                        .notify_final_cs_periods(&final_cs_periods);
+                    // re-broadcast outside of the pool lock as protocol may be busy
+                    if !rebroadcast_ops.get_op_refs().is_empty() {
+                        if let Err(err) = self
+                            .protocol_command_sender
+                            .propagate_operations(rebroadcast_ops)
+                        {
+                            warn!("could not re-broadcast operations nearing expiry: {}", err);
+                        }
+                    }
+                }
+            }
+            massa_metrics::set_gauge(
+                "massa_pool_operations",
+                &[],
+                self.operation_pool.read().len() as f64,
+            );
         }
     }
 }
@@ -112,6 +142,7 @@ pub fn start_pool_controller(
     config: PoolConfig,
     storage: &Storage,
     execution_controller: Box<dyn ExecutionController>,
+    protocol_command_sender: ProtocolCommandSender,
 ) -> (Box<dyn PoolManager>, Box<dyn PoolController>) {
     let (operations_input_sender, operations_input_receiver) = sync_channel(config.channels_size);
     let (endorsements_input_sender, endorsements_input_receiver) =
@@ -130,8 +161,11 @@ pub fn start_pool_controller(
         endorsements_input_sender: endorsements_input_sender.clone(),
     };
 
-    let operations_thread_handle =
-        OperationPoolThread::spawn(operations_input_receiver, operation_pool);
+    let operations_thread_handle = OperationPoolThread::spawn(
+        operations_input_receiver,
+        operation_pool,
+        protocol_command_sender,
+    );
     let endorsements_thread_handle =
         EndorsementPoolThread::spawn(endorsements_input_receiver, endorsement_pool);
 
@@ -9,6 +9,10 @@ pub struct PoSConfig {
     pub thread_count: u8,
     /// number of saved cycle
     pub cycle_history_length: usize,
-    /// maximum size of a deferred credits bootstrap part
+    /// maximum number of slots in a deferred credits bootstrap part
     pub credits_bootstrap_part_size: u64,
+    /// maximum size in bytes of the credits of a deferred credits bootstrap part
+    pub credits_bootstrap_part_size_bytes: u64,
+    /// maximum number of addresses in a cycle info bootstrap part
+    pub cycle_info_bootstrap_part_size: u64,
 }
@@ -23,6 +23,15 @@ pub struct Selection {
     pub producer: Address,
 }
 
+/// Number of times an address was drawn in a selection simulation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DrawCounts {
+    /// number of slots for which the address was drawn to produce the block
+    pub block_draws: u64,
+    /// number of endorsements the address was drawn to produce
+    pub endorsement_draws: u64,
+}
+
 /// interface that communicates with the selector worker thread
 pub trait SelectorController: Send + Sync {
     /// Waits for draws to reach at least a given cycle number.
@@ -63,6 +72,23 @@ pub trait SelectorController: Send + Sync {
     /// * `slot`: target slot of the selection
     fn get_producer(&self, slot: Slot) -> PosResult<Address>;
 
+    /// Simulate the draws of `cycle_count` cycles over a hypothetical roll distribution.
+    /// The simulation does not affect the draws of the selector.
+    ///
+    /// # Arguments
+    /// * `rolls`: simulated roll counts
+    /// * `seed`: seed of the simulation, each simulated cycle uses a seed derived from it
+    /// * `cycle_count`: number of simulated cycles
+    ///
+    /// # Returns
+    /// The draw counts of each address having rolls
+    fn simulate_draws(
+        &self,
+        rolls: BTreeMap<Address, u64>,
+        seed: Hash,
+        cycle_count: u64,
+    ) -> PosResult<BTreeMap<Address, DrawCounts>>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn SelectorController>`.
     fn clone_box(&self) -> Box<dyn SelectorController>;
@@ -85,7 +111,7 @@ impl Clone for Box<dyn SelectorController> {
 }
 
 /// Selector manager used to stop the selector thread
-pub trait SelectorManager {
+pub trait SelectorManager: Send {
     /// Stop the selector thread
     /// Note that we do not take self by value to consume it
     /// because it is not allowed to move out of Box<dyn SelectorManager>
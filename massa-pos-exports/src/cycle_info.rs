@@ -155,6 +155,19 @@ impl CycleInfo {
         }
     }
 
+    /// Hash identifying a complete cycle during the bootstrap process.
+    ///
+    /// Combines the cycle global hash and the final state hash snapshot,
+    /// which is not covered by the global hash.
+    pub fn get_bootstrap_hash(&self) -> Hash {
+        let mut hash_concat: Vec<u8> = Vec::new();
+        hash_concat.extend(self.cycle_global_hash.to_bytes());
+        if let Some(snapshot) = self.final_state_hash_snapshot {
+            hash_concat.extend(snapshot.to_bytes());
+        }
+        Hash::compute_from(&hash_concat)
+    }
+
     /// Apply every part of a `PoSChanges` to a cycle info, except for `deferred_credits`
     pub(crate) fn apply_changes(
         &mut self,
@@ -164,7 +177,7 @@ impl CycleInfo {
         thread_count: u8,
     ) -> bool {
         let hash_computer = CycleInfoHashComputer::new();
-        let slots_per_cycle = periods_per_cycle.saturating_mul(thread_count as u64);
+        let slots_per_cycle = Slot::slots_per_cycle(periods_per_cycle, thread_count);
         let mut hash_concat: Vec<u8> = Vec::new();
 
         // compute cycle hash and concat
@@ -232,6 +245,7 @@ impl CycleInfo {
 fn test_cycle_info_hash_computation() {
     use crate::DeferredCredits;
     use bitvec::prelude::*;
+    use massa_models::address::AddressCategory;
 
     // cycle and address
     let mut cycle_a = CycleInfo::new_with_hash(
@@ -241,7 +255,7 @@ fn test_cycle_info_hash_computation() {
         BitVec::default(),
         PreHashMap::default(),
     );
-    let addr = Address::from_bytes(&[0u8; 32]);
+    let addr = Address::new(AddressCategory::UserAddress, Hash::from_bytes(&[0u8; 32]));
 
     // add changes
     let mut roll_changes = PreHashMap::default();
@@ -449,6 +463,101 @@ impl Deserializer<CycleInfo> for CycleInfoDeserializer {
     }
 }
 
+/// Bootstrap streaming cursor of the cycle history.
+///
+/// A `CycleInfo` holding more addresses than the bootstrap part size is streamed in several parts,
+/// each one carrying the roll counts and production stats of a range of addresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleHistoryCursor {
+    /// cycle of the last streamed part
+    pub cycle: u64,
+    /// last address streamed for that cycle, `None` if the streamed parts of that cycle held no address
+    pub last_address: Option<Address>,
+}
+
+/// Serializer for `CycleHistoryCursor`
+#[derive(Clone)]
+pub struct CycleHistoryCursorSerializer {
+    u64_ser: U64VarIntSerializer,
+    opt_address_ser: OptionSerializer<Address, AddressSerializer>,
+}
+
+impl Default for CycleHistoryCursorSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CycleHistoryCursorSerializer {
+    /// Creates a new `CycleHistoryCursor` serializer
+    pub fn new() -> Self {
+        Self {
+            u64_ser: U64VarIntSerializer::new(),
+            opt_address_ser: OptionSerializer::new(AddressSerializer::new()),
+        }
+    }
+}
+
+impl Serializer<CycleHistoryCursor> for CycleHistoryCursorSerializer {
+    fn serialize(
+        &self,
+        value: &CycleHistoryCursor,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.u64_ser.serialize(&value.cycle, buffer)?;
+        self.opt_address_ser
+            .serialize(&value.last_address, buffer)?;
+        Ok(())
+    }
+}
+
+/// Deserializer for `CycleHistoryCursor`
+#[derive(Clone)]
+pub struct CycleHistoryCursorDeserializer {
+    u64_deser: U64VarIntDeserializer,
+    opt_address_deser: OptionDeserializer<Address, AddressDeserializer>,
+}
+
+impl Default for CycleHistoryCursorDeserializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CycleHistoryCursorDeserializer {
+    /// Creates a new `CycleHistoryCursor` deserializer
+    pub fn new() -> Self {
+        Self {
+            u64_deser: U64VarIntDeserializer::new(Included(u64::MIN), Included(u64::MAX)),
+            opt_address_deser: OptionDeserializer::new(AddressDeserializer::new()),
+        }
+    }
+}
+
+impl Deserializer<CycleHistoryCursor> for CycleHistoryCursorDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], CycleHistoryCursor, E> {
+        context(
+            "Failed CycleHistoryCursor deserialization",
+            tuple((
+                context("Failed cycle deserialization", |input| {
+                    self.u64_deser.deserialize(input)
+                }),
+                context("Failed last_address deserialization", |input| {
+                    self.opt_address_deser.deserialize(input)
+                }),
+            )),
+        )
+        .map(|(cycle, last_address)| CycleHistoryCursor {
+            cycle,
+            last_address,
+        })
+        .parse(buffer)
+    }
+}
+
 /// Block production statistics
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
 pub struct ProductionStats {
@@ -123,6 +123,16 @@ impl DeferredCredits {
         None
     }
 
+    /// Gets the sum of all the deferred credits
+    pub fn get_total_amount(&self) -> Amount {
+        self.credits
+            .values()
+            .flat_map(|slot_credits| slot_credits.values())
+            .fold(Amount::zero(), |total, amount| {
+                total.saturating_add(*amount)
+            })
+    }
+
     /// Insert/overwrite a deferred credit
     pub fn insert(&mut self, addr: Address, slot: Slot, amount: Amount) {
         let entry = self.credits.entry(slot).or_default();
@@ -316,3 +326,53 @@ impl Deserializer<PreHashMap<Address, Amount>> for CreditsDeserializer {
         .parse(buffer)
     }
 }
+
+#[test]
+fn test_deferred_credits_pruned_once_credited() {
+    use massa_models::address::AddressCategory;
+
+    let addr_a = Address::new(AddressCategory::UserAddress, Hash::compute_from(b"A"));
+    let addr_b = Address::new(AddressCategory::UserAddress, Hash::compute_from(b"B"));
+    // last slot of a period, first and second slots of the next one
+    let slot_last = Slot::new(4, 31);
+    let slot_first = Slot::new(5, 0);
+    let slot_second = Slot::new(5, 1);
+
+    let mut changes = DeferredCredits::default();
+    changes.insert(addr_a, slot_last, Amount::from_raw(10));
+    changes.insert(addr_a, slot_first, Amount::from_raw(20));
+    changes.insert(addr_b, slot_first, Amount::from_raw(30));
+    changes.insert(addr_b, slot_second, Amount::from_raw(40));
+    let mut final_credits = DeferredCredits::default();
+    final_credits.final_nested_extend(changes);
+    assert_eq!(
+        final_credits.get_total_amount(),
+        Amount::from_raw(10 + 20 + 30 + 40)
+    );
+
+    // the execution of `slot_first` sets the credits of that slot to zero
+    let mut executed = DeferredCredits::default();
+    executed.insert(addr_a, slot_first, Amount::zero());
+    executed.insert(addr_b, slot_first, Amount::zero());
+    final_credits.final_nested_extend(executed);
+    final_credits.remove_zeros();
+
+    assert!(!final_credits.credits.contains_key(&slot_first));
+    assert_eq!(
+        final_credits.get_address_deferred_credit_for_slot(&addr_a, &slot_last),
+        Some(Amount::from_raw(10))
+    );
+    assert_eq!(
+        final_credits.get_address_deferred_credit_for_slot(&addr_b, &slot_second),
+        Some(Amount::from_raw(40))
+    );
+    assert_eq!(final_credits.get_total_amount(), Amount::from_raw(10 + 40));
+
+    // the hash only depends on the remaining credits
+    let mut remaining = DeferredCredits::default();
+    remaining.insert(addr_a, slot_last, Amount::from_raw(10));
+    remaining.insert(addr_b, slot_second, Amount::from_raw(40));
+    let mut expected = DeferredCredits::default();
+    expected.final_nested_extend(remaining);
+    assert_eq!(final_credits.hash, expected.hash);
+}
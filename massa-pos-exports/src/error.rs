@@ -22,4 +22,6 @@ pub enum PosError {
     RollsFileLoadingError(String),
     /// Communication channel was down: {0}
     ChannelDown(String),
+    /// Unexpected cycle history part: received cycle {0} but expected cycle {1}
+    UnexpectedCycleHistoryPart(u64, u64),
 }
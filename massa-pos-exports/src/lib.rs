@@ -17,7 +17,7 @@ mod pos_final_state;
 mod settings;
 
 pub use config::PoSConfig;
-pub use controller_traits::{Selection, SelectorController, SelectorManager};
+pub use controller_traits::{DrawCounts, Selection, SelectorController, SelectorManager};
 pub use cycle_info::*;
 pub use deferred_credits::*;
 pub use error::*;
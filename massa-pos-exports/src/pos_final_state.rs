@@ -1,10 +1,18 @@
-use crate::{CycleInfo, PoSChanges, PosError, PosResult, ProductionStats, SelectorController};
-use crate::{DeferredCredits, PoSConfig};
+use crate::{CreditsSerializer, DeferredCredits, PoSConfig};
+use crate::{
+    CycleHistoryCursor, CycleInfo, PoSChanges, PosError, PosResult, ProductionStats,
+    SelectorController,
+};
 use bitvec::vec::BitVec;
 use massa_hash::Hash;
 use massa_models::error::ModelsError;
 use massa_models::streaming_step::StreamingStep;
-use massa_models::{address::Address, amount::Amount, prehash::PreHashMap, slot::Slot};
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    prehash::PreHashMap,
+    slot::{Slot, SlotSerializer},
+};
 use massa_serialization::{Serializer, U64VarIntSerializer};
 use std::collections::VecDeque;
 use std::{
@@ -69,9 +77,7 @@ impl PoSFinalState {
     /// This should be called only if bootstrap did not happen.
     pub fn create_initial_cycle(&mut self) {
         let mut rng_seed = BitVec::with_capacity(
-            self.config
-                .periods_per_cycle
-                .saturating_mul(self.config.thread_count as u64)
+            Slot::slots_per_cycle(self.config.periods_per_cycle, self.config.thread_count)
                 .try_into()
                 .unwrap(),
         );
@@ -156,12 +162,10 @@ impl PoSFinalState {
         slot: Slot,
         feed_selector: bool,
     ) -> PosResult<()> {
-        let slots_per_cycle: usize = self
-            .config
-            .periods_per_cycle
-            .saturating_mul(self.config.thread_count as u64)
-            .try_into()
-            .unwrap();
+        let slots_per_cycle: usize =
+            Slot::slots_per_cycle(self.config.periods_per_cycle, self.config.thread_count)
+                .try_into()
+                .unwrap();
 
         // compute the current cycle from the given slot
         let cycle = slot.get_cycle(self.config.periods_per_cycle);
@@ -307,6 +311,18 @@ impl PoSFinalState {
             .unwrap_or_default()
     }
 
+    /// Retrieves the total amount of rolls at the latest cycle
+    pub fn get_total_rolls(&self) -> u64 {
+        self.cycle_history
+            .back()
+            .map(|info| {
+                info.roll_counts
+                    .values()
+                    .fold(0u64, |total, count| total.saturating_add(*count))
+            })
+            .unwrap_or_default()
+    }
+
     /// Retrieves the amount of rolls a given address has at a given cycle
     pub fn get_address_active_rolls(&self, addr: &Address, cycle: u64) -> Option<u64> {
         // get lookback cycle index
@@ -363,68 +379,210 @@ impl PoSFinalState {
         Some(index)
     }
 
-    /// Gets a cycle of the Proof of Stake `cycle_history`. Used only in the bootstrap process.
+    /// Gets the bootstrap hashes of the complete cycles that would be streamed first by
+    /// `get_cycle_history_part`. Used only in the bootstrap process.
+    ///
+    /// # Returns
+    /// The cycle numbers and their `CycleInfo::get_bootstrap_hash`, in ascending cycle order
+    pub fn get_complete_cycle_hashes(&self) -> Vec<(u64, Hash)> {
+        let first_index = usize::from(self.cycle_history.len() >= self.config.cycle_history_length);
+        self.cycle_history
+            .iter()
+            .skip(first_index)
+            .take_while(|info| info.complete)
+            .map(|info| (info.cycle, info.get_bootstrap_hash()))
+            .collect()
+    }
+
+    /// Gets a part of a complete cycle of the Proof of Stake `cycle_history`.
+    /// Used only in the bootstrap process, by the secondary servers.
+    ///
+    /// The cycle is split like in `get_cycle_history_part`, a part holding at most
+    /// `cycle_info_bootstrap_part_size` addresses in ascending order.
+    ///
+    /// # Arguments:
+    /// `cursor`: the requested cycle and the last address received for it
+    ///
+    /// # Returns
+    /// The part holding the addresses after `cursor.last_address`, without any address once the
+    /// cycle was entirely streamed, or `None` if the cycle is not in the history or is not complete yet
+    pub fn get_complete_cycle_part(&self, cursor: CycleHistoryCursor) -> Option<CycleInfo> {
+        let index = self.get_cycle_index(cursor.cycle)?;
+        if !self.cycle_history.get(index)?.complete {
+            return None;
+        }
+        let addresses = self.get_cycle_addresses_after(index, cursor.last_address);
+        let part_size = self.config.cycle_info_bootstrap_part_size.max(1) as usize;
+        Some(self.get_cycle_part(index, &addresses[..addresses.len().min(part_size)]))
+    }
+
+    /// Gets a part of a cycle of the Proof of Stake `cycle_history`. Used only in the bootstrap process.
+    ///
+    /// A cycle is split into several parts of at most `cycle_info_bootstrap_part_size` addresses
+    /// (roll counts and production stats), streamed in ascending address order.
     ///
     /// # Arguments:
     /// `cursor`: indicates the bootstrap state after the previous payload
     ///
     /// # Returns
-    /// The PoS cycle and the updated cursor
+    /// The PoS cycle part and the updated cursor
     pub fn get_cycle_history_part(
         &self,
-        cursor: StreamingStep<u64>,
-    ) -> Result<(Option<CycleInfo>, StreamingStep<u64>), ModelsError> {
-        let cycle_index = match cursor {
+        cursor: StreamingStep<CycleHistoryCursor>,
+    ) -> Result<(Option<CycleInfo>, StreamingStep<CycleHistoryCursor>), ModelsError> {
+        let (cycle_index, last_address, addresses) = match cursor {
             StreamingStep::Started => {
-                usize::from(self.cycle_history.len() >= self.config.cycle_history_length)
+                let index =
+                    usize::from(self.cycle_history.len() >= self.config.cycle_history_length);
+                (index, None, self.get_cycle_addresses_after(index, None))
             }
-            StreamingStep::Ongoing(last_cycle) => {
-                if let Some(index) = self.get_cycle_index(last_cycle) {
-                    if index == self.cycle_history.len() - 1 {
-                        return Ok((None, StreamingStep::Finished(None)));
-                    }
-                    index.saturating_add(1)
+            StreamingStep::Ongoing(CycleHistoryCursor {
+                cycle,
+                last_address,
+            }) => {
+                let index = self
+                    .get_cycle_index(cycle)
+                    .ok_or(ModelsError::OutdatedBootstrapCursor)?;
+                let addresses = self.get_cycle_addresses_after(index, last_address);
+                if !addresses.is_empty() {
+                    // the cursor cycle still has addresses to stream
+                    (index, last_address, addresses)
+                } else if index == self.cycle_history.len() - 1 {
+                    return Ok((None, StreamingStep::Finished(None)));
                 } else {
-                    return Err(ModelsError::OutdatedBootstrapCursor);
+                    // the cursor cycle was entirely streamed, move to the next one
+                    let next_index = index.saturating_add(1);
+                    (
+                        next_index,
+                        None,
+                        self.get_cycle_addresses_after(next_index, None),
+                    )
                 }
             }
             StreamingStep::Finished(_) => return Ok((None, cursor)),
         };
+        let part_size = self.config.cycle_info_bootstrap_part_size.max(1) as usize;
+        let part_addresses = &addresses[..addresses.len().min(part_size)];
+        let part = self.get_cycle_part(cycle_index, part_addresses);
+        let cursor = CycleHistoryCursor {
+            cycle: part.cycle,
+            last_address: part_addresses.last().copied().or(last_address),
+        };
+        Ok((Some(part), StreamingStep::Ongoing(cursor)))
+    }
+
+    /// Builds the part of the cycle at `cycle_index` holding the roll counts and production stats
+    /// of `part_addresses`
+    fn get_cycle_part(&self, cycle_index: usize, part_addresses: &[Address]) -> CycleInfo {
         let cycle_info = self
             .cycle_history
             .get(cycle_index)
             .expect("a cycle should be available here");
-        Ok((
-            Some(cycle_info.clone()),
-            StreamingStep::Ongoing(cycle_info.cycle),
-        ))
+        let mut part = CycleInfo::new_with_hash(
+            cycle_info.cycle,
+            cycle_info.complete,
+            part_addresses
+                .iter()
+                .filter_map(|addr| {
+                    cycle_info
+                        .roll_counts
+                        .get(addr)
+                        .map(|count| (*addr, *count))
+                })
+                .collect(),
+            cycle_info.rng_seed.clone(),
+            part_addresses
+                .iter()
+                .filter_map(|addr| {
+                    cycle_info
+                        .production_stats
+                        .get(addr)
+                        .map(|stats| (*addr, *stats))
+                })
+                .collect(),
+        );
+        part.final_state_hash_snapshot = cycle_info.final_state_hash_snapshot;
+        part
+    }
+
+    /// Gets the ordered addresses having rolls or production stats in the cycle at `cycle_index`
+    /// that are strictly greater than `last_address`
+    fn get_cycle_addresses_after(
+        &self,
+        cycle_index: usize,
+        last_address: Option<Address>,
+    ) -> Vec<Address> {
+        let cycle_info = match self.cycle_history.get(cycle_index) {
+            Some(info) => info,
+            None => return Vec::new(),
+        };
+        let mut addresses: Vec<Address> = cycle_info
+            .roll_counts
+            .keys()
+            .chain(cycle_info.production_stats.keys())
+            .filter(|addr| last_address.map_or(true, |last| **addr > last))
+            .copied()
+            .collect();
+        addresses.sort_unstable();
+        addresses.dedup();
+        addresses
     }
 
     /// Gets a part of the Proof of Stake `deferred_credits`. Used only in the bootstrap process.
     ///
+    /// Credits are streamed from the newest slot to the oldest one, so that the credits which are
+    /// about to be settled are sent last. Credits at or before `min_slot` will be settled before
+    /// the bootstrap completes and are not streamed.
+    ///
+    /// A part holds at most `credits_bootstrap_part_size` slots and, unless it contains a single slot,
+    /// its serialized credits fit in `credits_bootstrap_part_size_bytes`.
+    ///
     /// # Arguments:
     /// `cursor`: indicates the bootstrap state after the previous payload
+    /// `min_slot`: slot up to which the credits are not streamed, usually the current final slot
     ///
     /// # Returns
     /// The PoS `deferred_credits` part and the updated cursor
     pub fn get_deferred_credits_part(
         &self,
         cursor: StreamingStep<Slot>,
+        min_slot: Slot,
     ) -> (DeferredCredits, StreamingStep<Slot>) {
         let mut credits_part = DeferredCredits::default();
-        let left_bound = match cursor {
+        let right_bound = match cursor {
             StreamingStep::Started => Unbounded,
-            StreamingStep::Ongoing(last_slot) => Excluded(last_slot),
+            StreamingStep::Ongoing(last_slot) if last_slot > min_slot => Excluded(last_slot),
+            StreamingStep::Ongoing(_) => return (credits_part, StreamingStep::Finished(None)),
             StreamingStep::Finished(_) => return (credits_part, cursor),
         };
+        let slot_serializer = SlotSerializer::new();
+        let credits_serializer = CreditsSerializer::new();
+        let mut part_size: u64 = 0;
         let mut credit_part_last_slot: Option<Slot> = None;
-        for (slot, credits) in self.deferred_credits.credits.range((left_bound, Unbounded)) {
-            if credits_part.credits.len() < self.config.credits_bootstrap_part_size as usize {
-                credits_part.credits.insert(*slot, credits.clone());
-                credit_part_last_slot = Some(*slot);
-            } else {
+        for (slot, credits) in self
+            .deferred_credits
+            .credits
+            .range((Excluded(min_slot), right_bound))
+            .rev()
+        {
+            if credits_part.credits.len() >= self.config.credits_bootstrap_part_size as usize {
                 break;
             }
+            // serialization can never fail in the following computations, unwrap is justified
+            let mut buffer = Vec::new();
+            slot_serializer.serialize(slot, &mut buffer).unwrap();
+            credits_serializer.serialize(credits, &mut buffer).unwrap();
+            let slot_size = buffer.len() as u64;
+            // always send at least one slot for the stream to move forward
+            if credit_part_last_slot.is_some()
+                && part_size.saturating_add(slot_size)
+                    > self.config.credits_bootstrap_part_size_bytes
+            {
+                break;
+            }
+            part_size = part_size.saturating_add(slot_size);
+            credits_part.credits.insert(*slot, credits.clone());
+            credit_part_last_slot = Some(*slot);
         }
         if let Some(last_slot) = credit_part_last_slot {
             (credits_part, StreamingStep::Ongoing(last_slot))
@@ -435,40 +593,70 @@ impl PoSFinalState {
 
     /// Sets a part of the Proof of Stake `cycle_history`. Used only in the bootstrap process.
     ///
+    /// A part of the last received cycle is merged into it, a part of the next cycle starts a new one.
+    ///
     /// # Arguments
-    /// `part`: a `CycleInfo` received from `get_pos_state_part` and used to update PoS final state
-    pub fn set_cycle_history_part(&mut self, part: Option<CycleInfo>) -> StreamingStep<u64> {
-        if let Some(cycle_info) = part {
+    /// `part`: a `CycleInfo` part received from `get_cycle_history_part` and used to update PoS final state
+    ///
+    /// # Errors
+    /// `PosError::UnexpectedCycleHistoryPart` if the part is neither from the last received cycle nor from the next one,
+    /// in which case the cycle history is left untouched
+    pub fn set_cycle_history_part(
+        &mut self,
+        part: Option<CycleInfo>,
+    ) -> PosResult<StreamingStep<CycleHistoryCursor>> {
+        let cycle_info = match part {
+            Some(info) => info,
+            None => return Ok(StreamingStep::Finished(None)),
+        };
+        let received_cycle = cycle_info.cycle;
+        if let Some(current) = self.cycle_history.back_mut() && current.cycle == received_cycle {
+            // continuation of the last received cycle
+            let mut roll_counts = std::mem::take(&mut current.roll_counts);
+            roll_counts.extend(cycle_info.roll_counts);
+            let mut production_stats = std::mem::take(&mut current.production_stats);
+            production_stats.extend(cycle_info.production_stats);
+            *current = CycleInfo::new_with_hash(
+                received_cycle,
+                cycle_info.complete,
+                roll_counts,
+                cycle_info.rng_seed,
+                production_stats,
+            );
+            current.final_state_hash_snapshot = cycle_info.final_state_hash_snapshot;
+        } else {
             let opt_next_cycle = self
                 .cycle_history
                 .back()
                 .map(|info| info.cycle.saturating_add(1));
-            let received_cycle = cycle_info.cycle;
             if let Some(next_cycle) = opt_next_cycle && received_cycle != next_cycle {
-                panic!(
-                    "PoS received cycle ({}) should be equal to the next expected cycle ({})",
-                    received_cycle, next_cycle
-                );
+                return Err(PosError::UnexpectedCycleHistoryPart(received_cycle, next_cycle));
             }
             self.cycle_history.push_back(cycle_info);
-            StreamingStep::Ongoing(received_cycle)
-        } else {
-            StreamingStep::Finished(None)
         }
+        let current = self
+            .cycle_history
+            .back()
+            .expect("cycle history should be non-empty");
+        let last_roll_address = current.roll_counts.keys().next_back();
+        let last_stats_address = current.production_stats.keys().max();
+        Ok(StreamingStep::Ongoing(CycleHistoryCursor {
+            cycle: received_cycle,
+            last_address: last_roll_address.max(last_stats_address).copied(),
+        }))
     }
 
     /// Sets a part of the Proof of Stake `deferred_credits`. Used only in the bootstrap process.
     ///
+    /// Parts are received from the newest slot to the oldest one,
+    /// the returned cursor is the oldest slot of the received part.
+    ///
     /// # Arguments
-    /// `part`: `DeferredCredits` from `get_pos_state_part` and used to update PoS final state
+    /// `part`: `DeferredCredits` from `get_deferred_credits_part` and used to update PoS final state
     pub fn set_deferred_credits_part(&mut self, part: DeferredCredits) -> StreamingStep<Slot> {
+        let opt_oldest_slot = part.credits.first_key_value().map(|(&slot, _)| slot);
         self.deferred_credits.final_nested_extend(part);
-        if let Some(slot) = self
-            .deferred_credits
-            .credits
-            .last_key_value()
-            .map(|(&slot, _)| slot)
-        {
+        if let Some(slot) = opt_oldest_slot {
             StreamingStep::Ongoing(slot)
         } else {
             StreamingStep::Finished(None)
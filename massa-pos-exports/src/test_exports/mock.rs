@@ -12,7 +12,7 @@ use std::{
 use massa_hash::Hash;
 use massa_models::{address::Address, api::IndexedSlot, slot::Slot};
 
-use crate::{PosResult, Selection, SelectorController};
+use crate::{DrawCounts, PosResult, Selection, SelectorController};
 
 /// All events that can be sent by the selector to your callbacks.
 #[derive(Debug)]
@@ -57,6 +57,17 @@ pub enum MockSelectorControllerMessage {
         /// Receiver to send the result to
         response_tx: mpsc::Sender<PosResult<Selection>>,
     },
+    /// Simulate the draws over a hypothetical roll distribution
+    SimulateDraws {
+        /// simulated roll counts
+        rolls: BTreeMap<Address, u64>,
+        /// seed of the simulation
+        seed: Hash,
+        /// number of simulated cycles
+        cycle_count: u64,
+        /// Receiver to send the result to
+        response_tx: mpsc::Sender<PosResult<BTreeMap<Address, DrawCounts>>>,
+    },
     /// Wait for draws
     WaitForDraws {
         /// Cycle to wait for
@@ -166,6 +177,25 @@ impl SelectorController for MockSelectorController {
         response_rx.recv().unwrap()
     }
 
+    fn simulate_draws(
+        &self,
+        rolls: BTreeMap<Address, u64>,
+        seed: Hash,
+        cycle_count: u64,
+    ) -> PosResult<BTreeMap<Address, DrawCounts>> {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.0
+            .lock()
+            .send(MockSelectorControllerMessage::SimulateDraws {
+                rolls,
+                seed,
+                cycle_count,
+                response_tx,
+            })
+            .unwrap();
+        response_rx.recv().unwrap()
+    }
+
     fn clone_box(&self) -> Box<dyn SelectorController> {
         Box::new(self.clone())
     }
@@ -5,10 +5,12 @@
 
 use std::collections::BTreeMap;
 
-use crate::{Command, DrawCachePtr};
+use crate::{draw::simulate_draws, Command, DrawCachePtr};
 use massa_hash::Hash;
 use massa_models::{address::Address, api::IndexedSlot, slot::Slot};
-use massa_pos_exports::{PosError, PosResult, Selection, SelectorController, SelectorManager};
+use massa_pos_exports::{
+    DrawCounts, PosError, PosResult, Selection, SelectorConfig, SelectorController, SelectorManager,
+};
 #[cfg(feature = "testing")]
 use std::collections::{HashMap, VecDeque};
 use std::sync::mpsc::SyncSender;
@@ -21,6 +23,8 @@ pub struct SelectorControllerImpl {
     pub(crate) periods_per_cycle: u64,
     /// thread count
     pub(crate) thread_count: u8,
+    /// selector configuration, used to simulate draws
+    pub(crate) selector_config: SelectorConfig,
     /// Cache storing the computed selections for each cycle.
     pub(crate) cache: DrawCachePtr,
     /// MPSC to send commands to the selector thread
@@ -146,6 +150,17 @@ impl SelectorController for SelectorControllerImpl {
         Ok((slot_producers, slot_endorsers))
     }
 
+    /// Simulate the draws of `cycle_count` cycles over a hypothetical roll distribution.
+    /// The draws are performed on the calling thread and do not touch the draw cache.
+    fn simulate_draws(
+        &self,
+        rolls: BTreeMap<Address, u64>,
+        seed: Hash,
+        cycle_count: u64,
+    ) -> PosResult<BTreeMap<Address, DrawCounts>> {
+        simulate_draws(&self.selector_config, rolls, seed, cycle_count)
+    }
+
     /// Returns a boxed clone of self.
     /// Allows cloning `Box<dyn SelectorController>`,
     /// see `massa-pos-exports/controller_traits.rs`
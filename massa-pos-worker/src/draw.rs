@@ -1,7 +1,7 @@
 use crate::CycleDraws;
 use massa_hash::Hash;
 use massa_models::{address::Address, slot::Slot};
-use massa_pos_exports::{PosError, PosResult, Selection, SelectorConfig};
+use massa_pos_exports::{DrawCounts, PosError, PosResult, Selection, SelectorConfig};
 use rand::{distributions::Distribution, SeedableRng};
 use rand_distr::WeightedAliasIndex;
 use rand_xoshiro::Xoshiro256PlusPlus;
@@ -53,7 +53,7 @@ pub(crate) fn perform_draws(
     let mut cycle_draws = CycleDraws {
         cycle,
         draws: HashMap::with_capacity(
-            (cfg.periods_per_cycle as usize) * (cfg.thread_count as usize),
+            Slot::slots_per_cycle(cfg.periods_per_cycle, cfg.thread_count) as usize,
         ),
     };
 
@@ -101,3 +101,45 @@ pub(crate) fn perform_draws(
 
     Ok(cycle_draws)
 }
+
+/// Simulates the draws of `cycle_count` cycles over a hypothetical roll distribution.
+///
+/// Simulated cycles are numbered from 1 so that no genesis draw is forced,
+/// and the seed of each cycle is the hash of the simulation `seed` followed by the cycle number.
+///
+/// # Parameters
+/// * `rolls`: simulated roll counts
+/// * `seed`: seed of the simulation
+/// * `cycle_count`: number of simulated cycles
+///
+/// # Result
+/// The draw counts of each address having rolls,
+/// or the errors of `perform_draws` (for example if nobody has rolls).
+pub(crate) fn simulate_draws(
+    cfg: &SelectorConfig,
+    rolls: BTreeMap<Address, u64>,
+    seed: Hash,
+    cycle_count: u64,
+) -> PosResult<BTreeMap<Address, DrawCounts>> {
+    let mut draw_counts: BTreeMap<Address, DrawCounts> = rolls
+        .iter()
+        .filter(|(_addr, roll_count)| **roll_count > 0)
+        .map(|(addr, _roll_count)| (*addr, DrawCounts::default()))
+        .collect();
+    for cycle in 1..=cycle_count {
+        let cycle_seed =
+            Hash::compute_from(&[seed.to_bytes().as_slice(), &cycle.to_be_bytes()].concat());
+        let cycle_draws = perform_draws(cfg, cycle, rolls.clone(), cycle_seed)?;
+        for selection in cycle_draws.draws.values() {
+            if let Some(counts) = draw_counts.get_mut(&selection.producer) {
+                counts.block_draws += 1;
+            }
+            for endorser in &selection.endorsements {
+                if let Some(counts) = draw_counts.get_mut(endorser) {
+                    counts.endorsement_draws += 1;
+                }
+            }
+        }
+    }
+    Ok(draw_counts)
+}
@@ -154,6 +154,7 @@ pub fn start_selector_worker(
         cache: cache.clone(),
         periods_per_cycle: selector_config.periods_per_cycle,
         thread_count: selector_config.thread_count,
+        selector_config: selector_config.clone(),
     };
 
     // launch the selector thread
@@ -49,6 +49,10 @@ pub enum ProtocolError {
     ContainerInconsistencyError(String),
     /// Invalid operation error: {0}
     InvalidOperationError(String),
+    /// Block too large: {0}
+    TooLarge(String),
+    /// Invalid header: {0}
+    InvalidHeader(String),
 }
 
 #[derive(Debug)]
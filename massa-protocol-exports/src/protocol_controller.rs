@@ -150,6 +150,12 @@ impl ProtocolManager {
         }
     }
 
+    /// Returns true if the protocol worker task ended.
+    /// Before `stop` is called, this only happens if the worker crashed.
+    pub fn is_finished(&self) -> bool {
+        self.join_handle.is_finished()
+    }
+
     /// Stop the protocol controller
     pub async fn stop(self) -> Result<NetworkEventReceiver, ProtocolError> {
         info!("stopping protocol controller...");
@@ -7,6 +7,8 @@ use serde::Deserialize;
 pub struct ProtocolConfig {
     /// running threads count
     pub thread_count: u8,
+    /// number of endorsements in a block
+    pub endorsement_count: u32,
     /// after `ask_block_timeout` milliseconds we try to ask a block to another node
     pub ask_block_timeout: MassaTime,
     /// max known blocks of current nodes we keep in memory (by node)
@@ -41,6 +43,20 @@ pub struct ProtocolConfig {
     pub operation_announcement_interval: MassaTime,
     /// Maximum of operations sent in one message.
     pub max_operations_per_message: u64,
+    /// Maximum number of block ids a node can ask us for per second
+    pub max_block_asks_per_second: u64,
+    /// Maximum number of block headers a node can send us per second
+    pub max_headers_per_second: u64,
+    /// Maximum number of received block headers being checked at the same time, the new ones are dropped if overflow
+    pub max_pending_header_validations: usize,
+    /// Maximum number of operations in an announcement from a node
+    pub max_operations_per_announcement: usize,
+    /// Misbehavior score at which a node is banned, the score of a node is incremented each time it exceeds a limit
+    pub max_misbehavior_score: u64,
+    /// Number of randomly chosen nodes the header of an integrated block is sent to immediately
+    pub block_propagation_fanout: usize,
+    /// Delay after which the header of an integrated block is sent to the remaining nodes that do not know it yet
+    pub block_propagation_delay: MassaTime,
     /// Maximum size in bytes of all serialized operations size in a block
     pub max_serialized_operations_size_per_block: usize,
     /// Controller channel size
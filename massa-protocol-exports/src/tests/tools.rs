@@ -216,7 +216,15 @@ pub fn create_protocol_config() -> ProtocolConfig {
         asked_operations_pruning_period: 500.into(),
         operation_announcement_interval: 150.into(),
         max_operations_per_message: 1024,
+        max_block_asks_per_second: 1024,
+        max_headers_per_second: 256,
+        max_pending_header_validations: 1024,
+        max_operations_per_announcement: 4096,
+        max_misbehavior_score: 10,
+        block_propagation_fanout: 100,
+        block_propagation_delay: 200.into(),
         thread_count: 32,
+        endorsement_count: 16,
         max_serialized_operations_size_per_block: 1024,
         controller_channel_size: 1024,
         event_channel_size: 1024,
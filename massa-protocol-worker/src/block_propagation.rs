@@ -0,0 +1,98 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Propagation of the headers of the blocks integrated by consensus.
+//!
+//! To avoid flooding the network when a block is integrated, its header is sent immediately
+//! only to a random subset of the nodes that do not know it yet. The other nodes receive it
+//! after a delay, unless they learnt about the block in the meantime from another peer.
+
+use massa_models::block::{BlockId, WrappedHeader};
+use massa_models::node::NodeId;
+use rand::seq::SliceRandom;
+use std::collections::VecDeque;
+use tokio::time::Instant;
+
+/// Header waiting for its delayed propagation
+#[derive(Debug, Clone)]
+pub(crate) struct DelayedHeader {
+    /// Instant at which the header is propagated to the remaining nodes
+    pub(crate) deadline: Instant,
+    /// Id of the block
+    pub(crate) block_id: BlockId,
+    /// Header to propagate
+    pub(crate) header: WrappedHeader,
+}
+
+/// Headers waiting for their delayed propagation, by increasing deadline
+#[derive(Debug, Default)]
+pub(crate) struct BlockPropagation {
+    /// Queue of the delayed headers
+    delayed: VecDeque<DelayedHeader>,
+}
+
+impl BlockPropagation {
+    /// Splits the nodes a header has to be sent to in two:
+    /// at most `fanout` randomly chosen nodes to send it to immediately, and the rest.
+    pub(crate) fn split_targets(
+        mut targets: Vec<NodeId>,
+        fanout: usize,
+    ) -> (Vec<NodeId>, Vec<NodeId>) {
+        if targets.len() <= fanout {
+            return (targets, Vec::new());
+        }
+        targets.shuffle(&mut rand::thread_rng());
+        let delayed = targets.split_off(fanout);
+        (targets, delayed)
+    }
+
+    /// Delays the propagation of `header` to the remaining nodes until `deadline`.
+    /// The deadlines must be pushed in increasing order.
+    pub(crate) fn delay(&mut self, block_id: BlockId, header: WrappedHeader, deadline: Instant) {
+        self.delayed.push_back(DelayedHeader {
+            deadline,
+            block_id,
+            header,
+        });
+    }
+
+    /// Returns the deadline of the next delayed header, if any
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.delayed.front().map(|delayed| delayed.deadline)
+    }
+
+    /// Removes and returns the delayed headers whose deadline is reached at `now`
+    pub(crate) fn pop_due(&mut self, now: Instant) -> Vec<DelayedHeader> {
+        let due_count = self
+            .delayed
+            .iter()
+            .take_while(|delayed| delayed.deadline <= now)
+            .count();
+        self.delayed.drain(..due_count).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+
+    #[test]
+    fn test_split_targets() {
+        let nodes: Vec<NodeId> = (0..10)
+            .map(|_| NodeId::new(KeyPair::generate().get_public_key()))
+            .collect();
+
+        let (immediate, delayed) = BlockPropagation::split_targets(nodes.clone(), 20);
+        assert_eq!(immediate, nodes);
+        assert!(delayed.is_empty());
+
+        let (immediate, delayed) = BlockPropagation::split_targets(nodes.clone(), 3);
+        assert_eq!(immediate.len(), 3);
+        assert_eq!(delayed.len(), 7);
+        let mut all: Vec<NodeId> = immediate.into_iter().chain(delayed).collect();
+        let mut expected = nodes;
+        all.sort();
+        expected.sort();
+        assert_eq!(all, expected);
+    }
+}
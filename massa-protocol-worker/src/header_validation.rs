@@ -0,0 +1,211 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Validation of the block headers received from the network.
+//!
+//! The checks that only depend on the header itself (format, slot bounds, endorsements, signature)
+//! run on the rayon thread pool, outside of the protocol loop. Only the headers passing them are
+//! noted by the protocol worker and forwarded to consensus, so that floods of invalid headers
+//! do not slow down the processing of the other events.
+
+use massa_models::{block::WrappedHeader, node::NodeId};
+use massa_protocol_exports::{ProtocolConfig, ProtocolError};
+use std::collections::HashSet;
+use tokio::sync::mpsc;
+
+/// Checks a header without relying on the state of the node:
+/// - the header is not a genesis one and its slot thread exists
+/// - its endorsements have distinct valid indices, its slot, and endorse its parent in its thread
+/// - its signature is valid
+pub(crate) fn check_header(
+    header: &WrappedHeader,
+    config: &ProtocolConfig,
+) -> Result<(), ProtocolError> {
+    let invalid = |reason: &str| Err(ProtocolError::InvalidHeader(reason.to_string()));
+
+    // refuse genesis blocks
+    if header.content.slot.period == 0 || header.content.parents.is_empty() {
+        return invalid("genesis header");
+    }
+    if header.content.slot.thread >= config.thread_count {
+        return invalid("slot thread out of bounds");
+    }
+    let Some(parent_in_thread) = header
+        .content
+        .parents
+        .get(header.content.slot.thread as usize)
+    else {
+        return invalid("no parent in the header thread");
+    };
+
+    if header.content.endorsements.len() > config.endorsement_count as usize {
+        return invalid("too many endorsements");
+    }
+    let mut used_endorsement_indices: HashSet<u32> =
+        HashSet::with_capacity(header.content.endorsements.len());
+    for endorsement in header.content.endorsements.iter() {
+        if endorsement.content.index >= config.endorsement_count
+            || !used_endorsement_indices.insert(endorsement.content.index)
+        {
+            return invalid("endorsement index out of bounds or reused");
+        }
+        if endorsement.content.slot != header.content.slot {
+            return invalid("endorsement slot differs from header slot");
+        }
+        if endorsement.content.endorsed_block != *parent_in_thread {
+            return invalid("endorsement does not endorse the parent in the header thread");
+        }
+    }
+
+    header
+        .verify_signature()
+        .map_err(|_| ProtocolError::WrongSignature)
+}
+
+/// Header received from a node, along with the result of its checks
+pub(crate) struct ValidatedHeader {
+    /// node that sent the header
+    pub(crate) source_node_id: NodeId,
+    /// checked header
+    pub(crate) header: WrappedHeader,
+    /// result of `check_header`
+    pub(crate) result: Result<(), ProtocolError>,
+}
+
+/// Checks the received headers on the rayon thread pool
+pub(crate) struct HeaderValidationPool {
+    /// protocol configuration
+    config: ProtocolConfig,
+    /// sends the checked headers back to the protocol worker
+    result_tx: mpsc::UnboundedSender<ValidatedHeader>,
+    /// receives the checked headers
+    result_rx: mpsc::UnboundedReceiver<ValidatedHeader>,
+    /// number of headers being checked
+    pending: usize,
+}
+
+impl HeaderValidationPool {
+    /// Creates a pool checking at most `config.max_pending_header_validations` headers at once
+    pub(crate) fn new(config: ProtocolConfig) -> Self {
+        let (result_tx, result_rx) = mpsc::unbounded_channel();
+        HeaderValidationPool {
+            config,
+            result_tx,
+            result_rx,
+            pending: 0,
+        }
+    }
+
+    /// Starts checking a header received from a node.
+    /// Returns false if too many headers are already being checked: the header is dropped.
+    pub(crate) fn submit(&mut self, header: WrappedHeader, source_node_id: NodeId) -> bool {
+        if self.pending >= self.config.max_pending_header_validations {
+            return false;
+        }
+        self.pending += 1;
+        let config = self.config;
+        let result_tx = self.result_tx.clone();
+        rayon::spawn(move || {
+            let result = check_header(&header, &config);
+            // the receiver lives as long as the pool
+            let _ = result_tx.send(ValidatedHeader {
+                source_node_id,
+                header,
+                result,
+            });
+        });
+        true
+    }
+
+    /// Waits for the next checked header
+    pub(crate) async fn recv(&mut self) -> ValidatedHeader {
+        let validated = self
+            .result_rx
+            .recv()
+            .await
+            .expect("header validation channel closed while the pool holds a sender");
+        self.pending -= 1;
+        validated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_models::block::{BlockHeader, BlockHeaderSerializer, BlockId};
+    use massa_models::endorsement::{Endorsement, EndorsementSerializer};
+    use massa_models::slot::Slot;
+    use massa_models::wrapped::WrappedContent;
+    use massa_protocol_exports::tests::tools::create_protocol_config;
+    use massa_signature::KeyPair;
+
+    fn header(config: &ProtocolConfig, endorsement_indices: &[u32]) -> WrappedHeader {
+        let keypair = KeyPair::generate();
+        let slot = Slot::new(1, 0);
+        let parents: Vec<BlockId> = (0..config.thread_count)
+            .map(|thread| BlockId(massa_hash::Hash::compute_from(&[thread])))
+            .collect();
+        let endorsements = endorsement_indices
+            .iter()
+            .map(|index| {
+                Endorsement::new_wrapped(
+                    Endorsement {
+                        slot,
+                        index: *index,
+                        endorsed_block: parents[0],
+                    },
+                    EndorsementSerializer::new(),
+                    &keypair,
+                )
+                .unwrap()
+            })
+            .collect();
+        BlockHeader::new_wrapped(
+            BlockHeader {
+                slot,
+                parents,
+                operation_merkle_root: massa_hash::Hash::compute_from(&[]),
+                endorsements,
+            },
+            BlockHeaderSerializer::new(),
+            &keypair,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_header() {
+        let config = create_protocol_config();
+        assert!(check_header(&header(&config, &[0, 1]), &config).is_ok());
+        assert!(matches!(
+            check_header(&header(&config, &[1, 1]), &config),
+            Err(ProtocolError::InvalidHeader(_))
+        ));
+        assert!(matches!(
+            check_header(&header(&config, &[config.endorsement_count]), &config),
+            Err(ProtocolError::InvalidHeader(_))
+        ));
+
+        let mut wrong_signature = header(&config, &[]);
+        wrong_signature.signature = header(&config, &[]).signature;
+        assert!(matches!(
+            check_header(&wrong_signature, &config),
+            Err(ProtocolError::WrongSignature)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_header_validation_pool() {
+        let mut config = create_protocol_config();
+        config.max_pending_header_validations = 1;
+        let mut pool = HeaderValidationPool::new(config);
+        let node_id = NodeId::new(KeyPair::generate().get_public_key());
+
+        assert!(pool.submit(header(&config, &[]), node_id));
+        // the pool is full until the result is received
+        assert!(!pool.submit(header(&config, &[]), node_id));
+        let validated = pool.recv().await;
+        assert_eq!(validated.source_node_id, node_id);
+        assert!(validated.result.is_ok());
+        assert!(pool.submit(header(&config, &[]), node_id));
+    }
+}
@@ -14,8 +14,10 @@
 pub mod protocol_worker;
 pub mod worker_operations_impl;
 pub use protocol_worker::start_protocol_controller;
+mod block_propagation;
 mod cache;
 mod checked_operations;
+mod header_validation;
 mod node_info;
 mod protocol_network;
 mod sig_verifier;
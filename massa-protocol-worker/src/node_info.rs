@@ -5,11 +5,16 @@
 //! # Operations
 //! Same as for wanted/known blocks, we remember here in cache which node asked
 //! for operations and which operations he seem to already know.
+//!
+//! # Rate limiting
+//! We count the blocks a node asks us for and the headers it sends us per second,
+//! and keep a misbehavior score incremented each time the node exceeds a limit.
 
 use massa_models::operation::OperationPrefixId;
 use massa_models::prehash::{CapacityAllocator, PreHashMap};
 use massa_models::{block::BlockId, endorsement::EndorsementId};
 use massa_protocol_exports::ProtocolConfig;
+use std::time::Duration;
 use tokio::time::Instant;
 
 use crate::cache::LinearHashCacheSet;
@@ -19,7 +24,8 @@ use crate::cache::LinearHashCacheSet;
 #[derive(Debug, Clone)]
 pub(crate) struct NodeInfo {
     /// The blocks the node "knows about",
-    /// defined as the one the node propagated headers to us for.
+    /// defined as the one the node propagated headers to us for,
+    /// asked us about or we sent headers to.
     pub(crate) known_blocks: PreHashMap<BlockId, (bool, Instant)>,
     /// Blocks we asked that node for
     pub asked_blocks: PreHashMap<BlockId, Instant>,
@@ -29,6 +35,41 @@ pub(crate) struct NodeInfo {
     known_operations: LinearHashCacheSet<OperationPrefixId>,
     /// all known endorsements
     known_endorsements: LinearHashCacheSet<EndorsementId>,
+    /// block ids the node asked us for during the current second
+    block_asks: RateCounter,
+    /// headers the node sent us during the current second
+    headers: RateCounter,
+    /// incremented each time the node exceeds a limit
+    pub misbehavior_score: u64,
+}
+
+/// Counts events over one second windows
+#[derive(Debug, Clone)]
+struct RateCounter {
+    /// start of the current window
+    window_start: Instant,
+    /// number of events in the current window
+    count: u64,
+}
+
+impl RateCounter {
+    /// Creates a counter whose first window starts now
+    fn new() -> Self {
+        RateCounter {
+            window_start: Instant::now(),
+            count: 0,
+        }
+    }
+
+    /// Adds `count` events happening at `now`, and returns the number of events of the current window
+    fn add(&mut self, count: u64, now: Instant) -> u64 {
+        if now.saturating_duration_since(self.window_start) >= Duration::from_secs(1) {
+            self.window_start = now;
+            self.count = 0;
+        }
+        self.count = self.count.saturating_add(count);
+        self.count
+    }
 }
 
 impl NodeInfo {
@@ -42,6 +83,9 @@ impl NodeInfo {
             known_endorsements: LinearHashCacheSet::new(
                 pool_settings.max_node_known_endorsements_size,
             ),
+            block_asks: RateCounter::new(),
+            headers: RateCounter::new(),
+            misbehavior_score: 0,
         }
     }
 
@@ -105,4 +149,16 @@ impl NodeInfo {
     pub fn knows_op(&self, op: &OperationPrefixId) -> bool {
         self.known_operations.contains(op)
     }
+
+    /// Notes that the node asked us for `count` blocks.
+    /// Returns false if the node exceeded `max_block_asks_per_second`.
+    pub fn note_block_asks(&mut self, count: usize, max_block_asks_per_second: u64) -> bool {
+        self.block_asks.add(count as u64, Instant::now()) <= max_block_asks_per_second
+    }
+
+    /// Notes that the node sent us a header.
+    /// Returns false if the node exceeded `max_headers_per_second`.
+    pub fn note_header(&mut self, max_headers_per_second: u64) -> bool {
+        self.headers.add(1, Instant::now()) <= max_headers_per_second
+    }
 }
@@ -4,6 +4,7 @@
 
 use std::collections::hash_map::Entry;
 
+use crate::header_validation::ValidatedHeader;
 use crate::node_info::NodeInfo;
 use crate::protocol_worker::ProtocolWorker;
 use massa_hash::{Hash, HASH_SIZE_BYTES};
@@ -22,7 +23,7 @@ use massa_serialization::Serializer;
 use massa_storage::Storage;
 use std::pin::Pin;
 use tokio::time::{Instant, Sleep};
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 // static tracing messages
 static NEW_CONN: &str = "protocol.protocol_worker.on_network_event.new_connection";
@@ -86,6 +87,11 @@ impl ProtocolWorker {
                 list,
             } => {
                 massa_trace!(ASKED_BLOCKS, { "node": from_node_id, "hashlist": list});
+                if let Some(node_info) = self.active_nodes.get_mut(&from_node_id) && !node_info.note_block_asks(list.len(), self.config.max_block_asks_per_second) {
+                    return self
+                        .note_misbehavior(&from_node_id, "too many block asks")
+                        .await;
+                }
                 self.on_asked_for_blocks_received(from_node_id, list)
                     .await?;
             }
@@ -94,22 +100,27 @@ impl ProtocolWorker {
                 header,
             } => {
                 massa_trace!(BLOCK_HEADER, { "node": source_node_id, "header": header});
-                if let Some((block_id, is_new)) =
-                    self.note_header_from_node(&header, &source_node_id).await?
-                {
-                    if is_new {
-                        self.consensus_controller
-                            .register_block_header(block_id, header);
-                    }
-                    self.update_ask_block(block_ask_timer).await?;
-                } else {
-                    warn!(
-                        "node {} sent us critically incorrect header, \
-                        which may be an attack attempt by the remote node \
-                        or a loss of sync between us and the remote node",
-                        source_node_id,
+                if let Some(node_info) = self.active_nodes.get_mut(&source_node_id) && !node_info.note_header(self.config.max_headers_per_second) {
+                    return self
+                        .note_misbehavior(&source_node_id, "too many headers")
+                        .await;
+                }
+                // headers that were already checked do not need to go through the validation pool
+                if self.checked_headers.contains_key(&header.id) {
+                    self.on_header_validated(
+                        ValidatedHeader {
+                            source_node_id,
+                            header,
+                            result: Ok(()),
+                        },
+                        block_ask_timer,
+                    )
+                    .await?;
+                } else if !self.header_validation.submit(header, source_node_id) {
+                    debug!(
+                        "too many headers being checked, dropping a header from node {}",
+                        source_node_id
                     );
-                    let _ = self.ban_node(&source_node_id).await;
                 }
             }
             NetworkEvent::ReceivedOperations { node, operations } => {
@@ -137,6 +148,11 @@ impl ProtocolWorker {
                 operation_prefix_ids,
             } => {
                 massa_trace!(OPS_BATCH, { "node": node, "operation_ids": operation_prefix_ids});
+                if operation_prefix_ids.len() > self.config.max_operations_per_announcement {
+                    return self
+                        .note_misbehavior(&node, "too many operations in an announcement")
+                        .await;
+                }
                 self.on_operations_announcements_received(operation_prefix_ids, node)
                     .await?;
             }
@@ -181,18 +197,18 @@ impl ProtocolWorker {
                     continue;
                 }
             };
+            // Mark the node as knowing the block:
+            // it either already has the header or gets it with the reply.
+            node_info.insert_known_blocks(
+                &[*hash],
+                true,
+                Instant::now(),
+                self.config.max_node_known_blocks_size,
+            );
             let block_info = match info_wanted {
                 AskForBlocksInfo::Header => BlockInfoReply::Header(header),
                 AskForBlocksInfo::Info => BlockInfoReply::Info(operations_ids),
                 AskForBlocksInfo::Operations(op_ids) => {
-                    // Mark the node as having the block.
-                    node_info.insert_known_blocks(
-                        &[*hash],
-                        true,
-                        Instant::now(),
-                        self.config.max_node_known_blocks_size,
-                    );
-
                     // Send only the missing operations that are in storage.
                     let needed_ops = {
                         let operations = self.storage.read_operations();
@@ -275,6 +291,46 @@ impl ProtocolWorker {
         Ok(())
     }
 
+    /// Note a header received from a node once its stateless checks are done,
+    /// and register it to consensus if it is new.
+    ///
+    /// # Ban
+    /// Ban the source node if the header is invalid.
+    pub(crate) async fn on_header_validated(
+        &mut self,
+        ValidatedHeader {
+            source_node_id,
+            header,
+            result,
+        }: ValidatedHeader,
+        block_ask_timer: &mut Pin<&mut Sleep>,
+    ) -> Result<(), ProtocolError> {
+        let noted = match result {
+            Ok(()) => self.note_checked_header(&header, &source_node_id).await?,
+            Err(err) => {
+                massa_trace!("protocol.protocol_worker.check_header.err", { "header": header, "err": format!("{}", err)});
+                None
+            }
+        };
+        if let Some((block_id, is_new)) = noted {
+            // consensus already knows the blocks it acknowledged, registering them again is useless
+            if is_new && !self.consensus_controller.are_blocks_known(&[block_id])[0] {
+                self.consensus_controller
+                    .register_block_header(block_id, header);
+            }
+            self.update_ask_block(block_ask_timer).await?;
+        } else {
+            warn!(
+                "node {} sent us critically incorrect header, \
+                which may be an attack attempt by the remote node \
+                or a loss of sync between us and the remote node",
+                source_node_id,
+            );
+            let _ = self.ban_node(&source_node_id).await;
+        }
+        Ok(())
+    }
+
     /// On block information received, manage when we get a list of operations.
     /// Ask for the missing operations that are not in the `checked_operations` cache variable.
     ///
@@ -365,7 +421,13 @@ impl ProtocolWorker {
                 .extend(known_operations.iter().copied());
 
             if info.operations_size > self.config.max_serialized_operations_size_per_block {
-                warn!("Node id {} sent us a operation list for block id {} but the operations we already have in our records exceed max size.", from_node_id, block_id);
+                let err = ProtocolError::TooLarge(format!(
+                    "the known operations of block {} weigh {} bytes, maximum authorized {} bytes",
+                    block_id,
+                    info.operations_size,
+                    self.config.max_serialized_operations_size_per_block
+                ));
+                warn!("Node id {} sent us a operation list for block id {} but the operations we already have in our records exceed max size: {}", from_node_id, block_id, err);
                 let _ = self.ban_node(&from_node_id).await;
                 return Ok(());
             }
@@ -465,7 +527,13 @@ impl ProtocolWorker {
                         .sum()
                 };
                 if full_op_size > self.config.max_serialized_operations_size_per_block {
-                    warn!("Node id {} sent us full operations for block id {} but they exceed max size.", from_node_id, block_id);
+                    let err = ProtocolError::TooLarge(format!(
+                        "the operations of block {} weigh {} bytes, maximum authorized {} bytes",
+                        block_id,
+                        full_op_size,
+                        self.config.max_serialized_operations_size_per_block
+                    ));
+                    warn!("Node id {} sent us full operations for block id {} but they exceed max size: {}", from_node_id, block_id, err);
                     let _ = self.ban_node(&from_node_id).await;
                     self.block_wishlist.remove(&block_id);
                     self.consensus_controller
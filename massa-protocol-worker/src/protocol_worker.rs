@@ -1,7 +1,9 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::block_propagation::BlockPropagation;
 use crate::cache::{LinearHashCacheMap, LinearHashCacheSet};
 use crate::checked_operations::CheckedOperations;
+use crate::header_validation::{check_header, HeaderValidationPool};
 use crate::sig_verifier::verify_sigs_batch;
 use crate::{node_info::NodeInfo, worker_operations_impl::OperationBatchBuffer};
 
@@ -28,7 +30,7 @@ use massa_protocol_exports::{
 };
 use massa_storage::Storage;
 use massa_time::{MassaTime, TimeError};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::mem;
 use std::pin::Pin;
 use tokio::{
@@ -154,6 +156,10 @@ pub struct ProtocolWorker {
     pub(crate) storage: Storage,
     /// Operations to announce at the next interval.
     operations_to_announce: Vec<OperationId>,
+    /// Headers of integrated blocks waiting to be propagated to the remaining nodes.
+    block_propagation: BlockPropagation,
+    /// Received headers being checked outside of the worker loop.
+    pub(crate) header_validation: HeaderValidationPool,
 }
 
 /// channels used by the protocol worker
@@ -214,6 +220,8 @@ impl ProtocolWorker {
             operations_to_announce: Vec::with_capacity(
                 config.operation_announcement_buffer_capacity,
             ),
+            block_propagation: Default::default(),
+            header_validation: HeaderValidationPool::new(config),
         }
     }
 
@@ -239,6 +247,8 @@ impl ProtocolWorker {
         let operation_announcement_interval =
             sleep(self.config.operation_announcement_interval.into());
         tokio::pin!(operation_announcement_interval);
+        let block_propagation_timer = sleep(self.config.block_propagation_delay.into());
+        tokio::pin!(block_propagation_timer);
         loop {
             massa_trace!("protocol.protocol_worker.run_loop.begin", {});
             /*
@@ -264,7 +274,8 @@ impl ProtocolWorker {
                 Some(cmd) = self.controller_command_rx.recv() => {
                     self.process_command(cmd, &mut
                         block_ask_timer,
-                        &mut operation_announcement_interval).await?;
+                        &mut operation_announcement_interval,
+                        &mut block_propagation_timer).await?;
                 }
 
                 // listen to network controller events
@@ -273,12 +284,24 @@ impl ProtocolWorker {
                     self.on_network_event(evt?, &mut block_ask_timer, &mut operation_announcement_interval).await?;
                 }
 
+                // headers checked by the validation pool
+                validated = self.header_validation.recv() => {
+                    massa_trace!("protocol.protocol_worker.run_loop.header_validated", {});
+                    self.on_header_validated(validated, &mut block_ask_timer).await?;
+                }
+
                 // block ask timer
                 _ = &mut block_ask_timer => {
                     massa_trace!("protocol.protocol_worker.run_loop.block_ask_timer", { });
                     self.update_ask_block(&mut block_ask_timer).await?;
                 }
 
+                // delayed block header propagation
+                _ = &mut block_propagation_timer => {
+                    massa_trace!("protocol.protocol_worker.run_loop.block_propagation_timer", { });
+                    self.propagate_delayed_headers(&mut block_propagation_timer).await?;
+                }
+
                 // Operation announcement interval.
                 _ = &mut operation_announcement_interval => {
                     // Announce operations.
@@ -408,11 +431,84 @@ impl ProtocolWorker {
         }
     }
 
+    /// Returns the active nodes the header of `block_id` has to be propagated to:
+    /// the ones that are not known to know the block.
+    /// This excludes the node that sent us the block.
+    fn header_propagation_targets(&self, block_id: &BlockId) -> Vec<NodeId> {
+        self.active_nodes
+            .iter()
+            .filter(|(node_id, node_info)| {
+                let knows_block = node_info
+                    .get_known_block(block_id)
+                    .map_or(false, |(knows, _)| *knows);
+                if knows_block {
+                    massa_trace!("protocol.protocol_worker.process_command.integrated_block.do_not_send", { "node": node_id, "block_id": block_id });
+                    return false;
+                }
+                true
+            })
+            .map(|(node_id, _)| *node_id)
+            .collect()
+    }
+
+    /// Sends `header` to `node_ids`, and notes that they know it.
+    async fn send_header(
+        &mut self,
+        block_id: &BlockId,
+        header: &WrappedHeader,
+        node_ids: Vec<NodeId>,
+    ) -> Result<(), ProtocolError> {
+        for node_id in node_ids {
+            let Some(node_info) = self.active_nodes.get_mut(&node_id) else {
+                continue;
+            };
+            massa_trace!("protocol.protocol_worker.process_command.integrated_block.send_header", { "node": node_id, "block_id": block_id});
+            self.network_command_sender
+                .send_block_header(node_id, header.clone())
+                .await
+                .map_err(|_| {
+                    ProtocolError::ChannelError(
+                        "send block header network command send failed".into(),
+                    )
+                })?;
+            node_info.insert_known_blocks(
+                &[*block_id],
+                true,
+                Instant::now(),
+                self.config.max_node_known_blocks_size,
+            );
+        }
+        Ok(())
+    }
+
+    /// Propagates the delayed headers whose deadline is reached
+    /// to the nodes that still do not know them, and resets the timer to the next deadline.
+    async fn propagate_delayed_headers(
+        &mut self,
+        timer: &mut Pin<&mut Sleep>,
+    ) -> Result<(), ProtocolError> {
+        let now = Instant::now();
+        for delayed in self.block_propagation.pop_due(now) {
+            let targets = self.header_propagation_targets(&delayed.block_id);
+            self.send_header(&delayed.block_id, &delayed.header, targets)
+                .await?;
+        }
+        let next_tick = match self.block_propagation.next_deadline() {
+            Some(deadline) => deadline,
+            None => now
+                .checked_add(self.config.block_propagation_delay.into())
+                .expect("time overflow"),
+        };
+        timer.set(sleep_until(next_tick));
+        Ok(())
+    }
+
     async fn process_command(
         &mut self,
         cmd: ProtocolCommand,
         block_timer: &mut Pin<&mut Sleep>,
         op_timer: &mut Pin<&mut Sleep>,
+        propagation_timer: &mut Pin<&mut Sleep>,
     ) -> Result<(), ProtocolError> {
         match cmd {
             ProtocolCommand::IntegratedBlock { block_id, storage } => {
@@ -432,23 +528,22 @@ impl ProtocolWorker {
                             ))
                         })?
                 };
-                for (node_id, node_info) in self.active_nodes.iter_mut() {
-                    // node that isn't asking for that block
-                    let cond = node_info.get_known_block(&block_id);
-                    // if we don't know if that node knows that hash or if we know it doesn't
-                    if !cond.map_or_else(|| false, |v| v.0) {
-                        massa_trace!("protocol.protocol_worker.process_command.integrated_block.send_header", { "node": node_id, "block_id": block_id});
-                        self.network_command_sender
-                            .send_block_header(*node_id, header.clone())
-                            .await
-                            .map_err(|_| {
-                                ProtocolError::ChannelError(
-                                    "send block header network command send failed".into(),
-                                )
-                            })?;
-                    } else {
-                        massa_trace!("protocol.protocol_worker.process_command.integrated_block.do_not_send", { "node": node_id, "block_id": block_id });
+                let (immediate_targets, delayed_targets) = BlockPropagation::split_targets(
+                    self.header_propagation_targets(&block_id),
+                    self.config.block_propagation_fanout,
+                );
+                self.send_header(&block_id, &header, immediate_targets)
+                    .await?;
+                if !delayed_targets.is_empty() {
+                    // the remaining nodes are selected again when the delay expires,
+                    // to skip the ones that got the header from other peers in the meantime
+                    let deadline = Instant::now()
+                        .checked_add(self.config.block_propagation_delay.into())
+                        .expect("time overflow");
+                    if self.block_propagation.next_deadline().is_none() {
+                        propagation_timer.set(sleep_until(deadline));
                     }
+                    self.block_propagation.delay(block_id, header, deadline);
                 }
                 massa_trace!(
                     "protocol.protocol_worker.process_command.integrated_block.end",
@@ -760,6 +855,31 @@ impl ProtocolWorker {
         Ok(())
     }
 
+    /// Increments the misbehavior score of a node that exceeded a limit,
+    /// and bans it once the score reaches `max_misbehavior_score`.
+    pub(crate) async fn note_misbehavior(
+        &mut self,
+        node_id: &NodeId,
+        reason: &str,
+    ) -> Result<(), ProtocolError> {
+        let score = match self.active_nodes.get_mut(node_id) {
+            Some(node_info) => {
+                node_info.misbehavior_score = node_info.misbehavior_score.saturating_add(1);
+                node_info.misbehavior_score
+            }
+            None => return Ok(()),
+        };
+        massa_trace!("protocol.protocol_worker.note_misbehavior", { "node": node_id, "reason": reason, "score": score });
+        if score >= self.config.max_misbehavior_score {
+            warn!(
+                "node {} exceeded the protocol limits too many times ({}), banning it",
+                node_id, reason
+            );
+            self.ban_node(node_id).await?;
+        }
+        Ok(())
+    }
+
     /// Perform checks on a header,
     /// and if valid update the node's view of the world.
     ///
@@ -767,16 +887,7 @@ impl ProtocolWorker {
     ///
     /// Does not ban the source node if the header is invalid.
     ///
-    /// Checks performed on Header:
-    /// - Not genesis.
-    /// - Can compute a `BlockId`.
-    /// - Valid signature.
-    /// - Absence of duplicate endorsements.
-    ///
-    /// Checks performed on endorsements:
-    /// - Unique indices.
-    /// - Slot matches that of the block.
-    /// - Block matches that of the block.
+    /// The stateless checks of `check_header` are skipped for the headers already checked.
     pub(crate) async fn note_header_from_node(
         &mut self,
         header: &WrappedHeader,
@@ -784,21 +895,26 @@ impl ProtocolWorker {
     ) -> Result<Option<(BlockId, bool)>, ProtocolError> {
         massa_trace!("protocol.protocol_worker.note_header_from_node", { "node": source_node_id, "header": header });
 
-        // check header integrity
-        massa_trace!("protocol.protocol_worker.check_header.start", {
-            "header": header
-        });
-
-        // refuse genesis blocks
-        if header.content.slot.period == 0 || header.content.parents.is_empty() {
-            // genesis
-            massa_trace!("protocol.protocol_worker.check_header.err_is_genesis", {
-                "header": header
-            });
-            return Ok(None);
+        if !self.checked_headers.contains_key(&header.id) {
+            if let Err(err) = check_header(header, &self.config) {
+                massa_trace!("protocol.protocol_worker.check_header.err", { "header": header, "err": format!("{}", err)});
+                return Ok(None);
+            }
         }
+        self.note_checked_header(header, source_node_id).await
+    }
 
-        // compute ID
+    /// Update the node's view of the world with a header that passed `check_header`
+    /// or was already checked.
+    ///
+    /// Returns a boolean representing whether the header is new.
+    ///
+    /// Does not ban the source node if the endorsements of the header are invalid.
+    pub(crate) async fn note_checked_header(
+        &mut self,
+        header: &WrappedHeader,
+        source_node_id: &NodeId,
+    ) -> Result<Option<(BlockId, bool)>, ProtocolError> {
         let block_id = header.id;
 
         // check if this header was already verified
@@ -835,35 +951,6 @@ impl ProtocolWorker {
             return Ok(None);
         };
 
-        // check header signature
-        if let Err(err) = header.verify_signature() {
-            massa_trace!("protocol.protocol_worker.check_header.err_signature", { "header": header, "err": format!("{}", err)});
-            return Ok(None);
-        };
-
-        // check endorsement in header integrity
-        let mut used_endorsement_indices: HashSet<u32> =
-            HashSet::with_capacity(header.content.endorsements.len());
-        for endorsement in header.content.endorsements.iter() {
-            // check index reuse
-            if !used_endorsement_indices.insert(endorsement.content.index) {
-                massa_trace!("protocol.protocol_worker.check_header.err_endorsement_index_reused", { "header": header, "endorsement": endorsement});
-                return Ok(None);
-            }
-            // check slot
-            if endorsement.content.slot != header.content.slot {
-                massa_trace!("protocol.protocol_worker.check_header.err_endorsement_invalid_slot", { "header": header, "endorsement": endorsement});
-                return Ok(None);
-            }
-            // check endorsed block
-            if endorsement.content.endorsed_block
-                != header.content.parents[header.content.slot.thread as usize]
-            {
-                massa_trace!("protocol.protocol_worker.check_header.err_endorsement_invalid_endorsed_block", { "header": header, "endorsement": endorsement});
-                return Ok(None);
-            }
-        }
-
         self.checked_headers.insert(block_id, header.clone());
 
         if let Some(node_info) = self.active_nodes.get_mut(source_node_id) {
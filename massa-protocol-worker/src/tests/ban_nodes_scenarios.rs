@@ -7,7 +7,7 @@ use massa_models::operation::OperationId;
 use massa_models::prehash::PreHashSet;
 use massa_models::wrapped::Id;
 use massa_models::{block::BlockId, slot::Slot};
-use massa_network_exports::{BlockInfoReply, NetworkCommand};
+use massa_network_exports::{AskForBlocksInfo, BlockInfoReply, NetworkCommand};
 use massa_pool_exports::test_exports::MockPoolControllerMessage;
 use massa_protocol_exports::tests::tools;
 use massa_signature::KeyPair;
@@ -652,3 +652,51 @@ async fn test_protocol_removes_banned_node_on_disconnection() {
     )
     .await;
 }
+
+#[tokio::test]
+#[serial]
+async fn test_protocol_bans_node_exceeding_block_asks_rate() {
+    let mut protocol_config = *tools::PROTOCOL_CONFIG;
+    protocol_config.max_block_asks_per_second = 1;
+    protocol_config.max_misbehavior_score = 2;
+    protocol_test(
+        &protocol_config,
+        async move |mut network_controller,
+                    protocol_command_sender,
+                    protocol_manager,
+                    protocol_consensus_event_receiver,
+                    protocol_pool_event_receiver| {
+            let mut nodes = tools::create_and_connect_nodes(1, &mut network_controller).await;
+            let asking_node = nodes.pop().expect("Failed to get node info.");
+
+            // Ask for more blocks than allowed, twice.
+            let list = vec![
+                (
+                    BlockId::new(Hash::compute_from("block_1".as_bytes())),
+                    AskForBlocksInfo::Info,
+                ),
+                (
+                    BlockId::new(Hash::compute_from("block_2".as_bytes())),
+                    AskForBlocksInfo::Info,
+                ),
+            ];
+            network_controller
+                .send_ask_for_block(asking_node.id, list.clone())
+                .await;
+            network_controller
+                .send_ask_for_block(asking_node.id, list)
+                .await;
+
+            // The node is banned once its misbehavior score reaches the limit.
+            tools::assert_banned_nodes(vec![asking_node.id], &mut network_controller).await;
+            (
+                network_controller,
+                protocol_command_sender,
+                protocol_manager,
+                protocol_consensus_event_receiver,
+                protocol_pool_event_receiver,
+            )
+        },
+    )
+    .await;
+}
@@ -212,7 +212,7 @@ async fn test_protocol_sends_blocks_when_asked_for() {
 
 #[tokio::test]
 #[serial]
-async fn test_protocol_propagates_block_to_all_nodes_including_those_who_asked_for_info() {
+async fn test_protocol_propagates_block_header_to_all_nodes_except_those_who_asked_for_info() {
     let protocol_config = &tools::PROTOCOL_CONFIG;
     protocol_test_with_storage(
         protocol_config,
@@ -283,12 +283,11 @@ async fn test_protocol_propagates_block_to_all_nodes_including_those_who_asked_f
 
             // 6. Check that protocol propagates the header to the right nodes.
             // node_a created the block and should receive nothing
-            // node_b asked for the info and should receive the header
+            // node_b asked for the info, it already has the header and should only receive the info
             // node_c did nothing, it should receive the header
             // node_d was disconnected, so nothing should be send to it
             let mut expected_headers = HashSet::new();
             expected_headers.insert(node_c.id);
-            expected_headers.insert(node_b.id);
 
             let mut expected_info = HashSet::new();
             expected_info.insert(node_b.id);
@@ -566,3 +565,76 @@ async fn test_protocol_block_not_found() {
     )
     .await;
 }
+
+#[tokio::test]
+#[serial]
+async fn test_protocol_propagates_block_header_to_remaining_nodes_after_delay() {
+    let mut protocol_config = *tools::PROTOCOL_CONFIG;
+    protocol_config.block_propagation_fanout = 1;
+    protocol_config.block_propagation_delay = MassaTime::from_millis(200);
+    protocol_test_with_storage(
+        &protocol_config,
+        async move |mut network_controller,
+                    mut protocol_command_sender,
+                    protocol_manager,
+                    protocol_consensus_event_receiver,
+                    protocol_pool_event_receiver,
+                    mut storage| {
+            let nodes = create_and_connect_nodes(3, &mut network_controller).await;
+
+            // the block is integrated without having been received from any node
+            let ref_block = create_block(&nodes[0].keypair);
+            let ref_hash = ref_block.id;
+            storage.store_block(ref_block);
+            protocol_command_sender = tokio::task::spawn_blocking(move || {
+                protocol_command_sender
+                    .integrated_block(ref_hash, storage.clone())
+                    .unwrap();
+                protocol_command_sender
+            })
+            .await
+            .unwrap();
+
+            // one node gets the header immediately, the two others after the delay
+            let mut expected_headers: HashSet<_> = nodes.iter().map(|node| node.id).collect();
+            let start = std::time::Instant::now();
+            let mut delays = Vec::new();
+            while !expected_headers.is_empty() {
+                match network_controller
+                    .wait_command(1000.into(), |cmd| match cmd {
+                        cmd @ NetworkCommand::SendBlockHeader { .. } => Some(cmd),
+                        _ => None,
+                    })
+                    .await
+                {
+                    Some(NetworkCommand::SendBlockHeader { node, header }) => {
+                        assert!(expected_headers.remove(&node));
+                        assert_eq!(header.id, ref_hash);
+                        delays.push(start.elapsed());
+                    }
+                    _ => panic!("header not propagated to all nodes"),
+                };
+            }
+            assert!(delays[0] < std::time::Duration::from_millis(200));
+            assert!(delays[1] >= std::time::Duration::from_millis(150));
+
+            // each node receives the header only once
+            assert!(network_controller
+                .wait_command(500.into(), |cmd| match cmd {
+                    cmd @ NetworkCommand::SendBlockHeader { .. } => Some(cmd),
+                    _ => None,
+                })
+                .await
+                .is_none());
+
+            (
+                network_controller,
+                protocol_command_sender,
+                protocol_manager,
+                protocol_consensus_event_receiver,
+                protocol_pool_event_receiver,
+            )
+        },
+    )
+    .await;
+}
@@ -20,4 +20,6 @@ pub struct HttpConfig {
     pub max_log_length: u32,
     /// custom headers to pass with every request.
     pub headers: Vec<(String, String)>,
+    /// token sent as `Authorization: Bearer` header with every request to the private API.
+    pub private_auth_token: Option<String>,
 }
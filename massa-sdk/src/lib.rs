@@ -10,23 +10,31 @@ use jsonrpsee::http_client::HttpClient;
 use jsonrpsee::rpc_params;
 use jsonrpsee::ws_client::{HeaderMap, HeaderValue};
 use massa_models::api::{
-    AddressInfo, BlockInfo, BlockSummary, DatastoreEntryInput, DatastoreEntryOutput,
-    EndorsementInfo, EventFilter, NodeStatus, OperationInfo, OperationInput,
-    ReadOnlyBytecodeExecution, ReadOnlyCall, TimeInterval,
+    AddressHistoryRequest, AddressInfo, BlockInfo, BlockSummary, DatastoreEntryInput,
+    DatastoreEntryOutput, EndorsementInfo, EventFilter, LedgerEntryProofInput, NodeStatus,
+    OperationInfo, OperationInput, ReadOnlyBytecodeExecution, ReadOnlyCall, RollCompoundingMode,
+    SelectionSimulation, SimulatedDraws, SnapshotRequest, StakersPage, StakersRequest,
+    TimeInterval, TransfersPage, TransfersRequest,
 };
 use massa_models::clique::Clique;
 use massa_models::composite::PubkeySig;
+use massa_models::config::CompactConfig;
 use massa_models::execution::ExecuteReadOnlyResponse;
+use massa_models::ledger_proof::FinalLedgerEntryProof;
 use massa_models::node::NodeId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::{
-    address::Address, block::BlockId, endorsement::EndorsementId, operation::OperationId,
+    address::{Address, AddressHistoryEntry},
+    block::BlockId,
+    endorsement::EndorsementId,
+    operation::OperationId,
 };
 
 use jsonrpsee::{core::Error as JsonRpseeError, core::RpcResult, http_client::HttpClientBuilder};
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
+use std::sync::Mutex;
 
 mod config;
 pub use config::HttpConfig;
@@ -51,9 +59,15 @@ impl Client {
         let private_socket_addr = SocketAddr::new(ip, private_port);
         let public_url = format!("http://{}", public_socket_addr);
         let private_url = format!("http://{}", private_socket_addr);
+        let mut private_http_config = http_config.clone();
+        if let Some(token) = &http_config.private_auth_token {
+            private_http_config
+                .headers
+                .push(("Authorization".to_string(), format!("Bearer {}", token)));
+        }
         Client {
             public: RpcClient::from_url(&public_url, http_config).await,
-            private: RpcClient::from_url(&private_url, http_config).await,
+            private: RpcClient::from_url(&private_url, &private_http_config).await,
         }
     }
 }
@@ -61,6 +75,8 @@ impl Client {
 /// TODO add ws client
 pub struct RpcClient {
     http_client: HttpClient,
+    /// node configuration, cached once fetched as it does not change while the node runs
+    config: Mutex<Option<CompactConfig>>,
 }
 
 impl RpcClient {
@@ -99,7 +115,10 @@ impl RpcClient {
             .set_headers(headers)
             .build(url)
         {
-            Ok(http_client) => RpcClient { http_client },
+            Ok(http_client) => RpcClient {
+                http_client,
+                config: Mutex::new(None),
+            },
             Err(_) => panic!("unable to connect to Node."),
         }
     }
@@ -140,6 +159,77 @@ impl RpcClient {
             .await
     }
 
+    /// Resume block and endorsement production on a node in standby mode.
+    /// No confirmation to expect.
+    pub async fn node_staking_activate(&self) -> RpcResult<()> {
+        self.http_client
+            .request("node_staking_activate", rpc_params![])
+            .await
+    }
+
+    /// Put the node in standby mode: it keeps in sync but withholds block and endorsement production.
+    /// No confirmation to expect.
+    pub async fn node_staking_standby(&self) -> RpcResult<()> {
+        self.http_client
+            .request("node_staking_standby", rpc_params![])
+            .await
+    }
+
+    /// Notify a node in standby mode that the primary node is alive.
+    /// No confirmation to expect.
+    pub async fn node_staking_heartbeat(&self) -> RpcResult<()> {
+        self.http_client
+            .request("node_staking_heartbeat", rpc_params![])
+            .await
+    }
+
+    /// Replace the log filter by comma-separated `target=level` directives, e.g. `massa_bootstrap=debug,info`.
+    /// No confirmation to expect.
+    pub async fn node_set_log_filter(&self, directives: String) -> RpcResult<()> {
+        self.http_client
+            .request("node_set_log_filter", rpc_params![directives])
+            .await
+    }
+
+    /// Log one `massa_trace` event out of the given rate, or none if the rate is 0.
+    /// No confirmation to expect.
+    pub async fn node_set_trace_sampling(&self, rate: u64) -> RpcResult<()> {
+        self.http_client
+            .request("node_set_trace_sampling", rpc_params![rate])
+            .await
+    }
+
+    /// Set how the deferred credits paid to the staking addresses are converted into rolls at the start of each cycle.
+    /// No confirmation to expect.
+    pub async fn node_set_roll_compounding(&self, mode: RollCompoundingMode) -> RpcResult<()> {
+        self.http_client
+            .request("node_set_roll_compounding", rpc_params![mode])
+            .await
+    }
+
+    /// Write a snapshot of the final state to the given file of the node machine at the given final slot.
+    /// The snapshot is written once the slot is final.
+    pub async fn node_export_snapshot(&self, request: SnapshotRequest) -> RpcResult<()> {
+        self.http_client
+            .request("node_export_snapshot", rpc_params![request])
+            .await
+    }
+
+    /// Write the peer database to the given file of the node machine.
+    pub async fn node_export_peers(&self, path: String) -> RpcResult<()> {
+        self.http_client
+            .request("node_export_peers", rpc_params![path])
+            .await
+    }
+
+    /// Merge the peers of the given file of the node machine with the peer database.
+    /// Returns the number of previously unknown peers.
+    pub async fn node_import_peers(&self, path: String) -> RpcResult<usize> {
+        self.http_client
+            .request("node_import_peers", rpc_params![path])
+            .await
+    }
+
     /// Bans given ip address(es)
     /// No confirmation to expect.
     pub async fn node_ban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
@@ -251,7 +341,20 @@ impl RpcClient {
 
     /// summary of the current state: time, last final blocks (hash, thread, slot, timestamp), clique count, connected nodes count
     pub async fn get_status(&self) -> RpcResult<NodeStatus> {
-        self.http_client.request("get_status", rpc_params![]).await
+        let status: NodeStatus = self
+            .http_client
+            .request("get_status", rpc_params![])
+            .await?;
+        *self.config.lock().expect("config lock poisoned") = Some(status.config);
+        Ok(status)
+    }
+
+    /// node configuration, fetched with `get_status` on the first call only
+    pub async fn get_config(&self) -> RpcResult<CompactConfig> {
+        if let Some(config) = *self.config.lock().expect("config lock poisoned") {
+            return Ok(config);
+        }
+        Ok(self.get_status().await?.config)
     }
 
     pub(crate) async fn _get_cliques(&self) -> RpcResult<Vec<Clique>> {
@@ -265,6 +368,43 @@ impl RpcClient {
         self.http_client.request("get_stakers", rpc_params![]).await
     }
 
+    /// Returns a page of the stakers of a cycle, sorted by decreasing active roll count.
+    pub async fn get_cycle_stakers(&self, request: StakersRequest) -> RpcResult<StakersPage> {
+        self.http_client
+            .request("get_cycle_stakers", rpc_params![request])
+            .await
+    }
+
+    /// Returns the balance and roll changes of an address in the recent final slots.
+    pub async fn get_address_history(
+        &self,
+        request: AddressHistoryRequest,
+    ) -> RpcResult<Vec<AddressHistoryEntry>> {
+        self.http_client
+            .request("get_address_history", rpc_params![request])
+            .await
+    }
+
+    /// Returns a page of the coins sent and received by an address in the recent final slots.
+    pub async fn get_transactions_by_address(
+        &self,
+        request: TransfersRequest,
+    ) -> RpcResult<TransfersPage> {
+        self.http_client
+            .request("get_transactions_by_address", rpc_params![request])
+            .await
+    }
+
+    /// Runs the selector over a hypothetical roll distribution and seed for a number of cycles.
+    pub async fn simulate_selections(
+        &self,
+        simulation: SelectionSimulation,
+    ) -> RpcResult<Vec<SimulatedDraws>> {
+        self.http_client
+            .request("simulate_selections", rpc_params![simulation])
+            .await
+    }
+
     /// Returns operation(s) information associated to a given list of operation(s) ID(s).
     pub async fn get_operations(
         &self,
@@ -330,6 +470,16 @@ impl RpcClient {
             .await
     }
 
+    /// Get proofs of final ledger entries against the latest final state hash
+    pub async fn get_ledger_entry_proof(
+        &self,
+        input: Vec<LedgerEntryProofInput>,
+    ) -> RpcResult<Vec<FinalLedgerEntryProof>> {
+        self.http_client
+            .request("get_ledger_entry_proof", rpc_params![input])
+            .await
+    }
+
     // User (interaction with the node)
 
     /// Adds operations to pool. Returns operations that were ok and sent to pool.
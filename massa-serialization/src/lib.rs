@@ -272,6 +272,15 @@ where
     }
 }
 
+impl<T, ST> Clone for OptionSerializer<T, ST>
+where
+    ST: Serializer<T> + Clone,
+{
+    fn clone(&self) -> Self {
+        OptionSerializer::new(self.data_serializer.clone())
+    }
+}
+
 impl<T, ST> Serializer<Option<T>> for OptionSerializer<T, ST>
 where
     ST: Serializer<T>,
@@ -309,6 +318,16 @@ where
     }
 }
 
+impl<T, DT> Clone for OptionDeserializer<T, DT>
+where
+    T: Clone,
+    DT: Deserializer<T> + Clone,
+{
+    fn clone(&self) -> Self {
+        OptionDeserializer::new(self.data_deserializer.clone())
+    }
+}
+
 impl<T, DT> Deserializer<Option<T>> for OptionDeserializer<T, DT>
 where
     T: Clone,
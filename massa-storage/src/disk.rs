@@ -0,0 +1,229 @@
+//! Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Disk backend of the storage.
+//!
+//! Every object held by at least one `Storage` instance is written to a `RocksDB` column family,
+//! and deleted from it when its last reference is dropped.
+//! Once closed, the DB is not modified anymore, so the objects still referenced at that point can be loaded back on restart.
+
+use massa_models::{
+    block::{BlockDeserializer, BlockId, WrappedBlock},
+    config::{
+        ENDORSEMENT_COUNT, MAX_DATASTORE_VALUE_LENGTH, MAX_FUNCTION_NAME_LENGTH,
+        MAX_OPERATIONS_PER_BLOCK, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        MAX_PARAMETERS_SIZE, THREAD_COUNT,
+    },
+    endorsement::{EndorsementDeserializer, EndorsementId, WrappedEndorsement},
+    operation::{OperationDeserializer, OperationId, WrappedOperation},
+    wrapped::{Id, Wrapped, WrappedContent, WrappedDeserializer, WrappedSerializer},
+};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
+use rocksdb::{ColumnFamilyDescriptor, IteratorMode, Options, WriteBatch, DB};
+use std::fmt::{Debug, Display};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const BLOCKS_CF: &str = "blocks";
+const OPERATIONS_CF: &str = "operations";
+const ENDORSEMENTS_CF: &str = "endorsements";
+const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
+const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
+const CF_ERROR: &str = "critical: rocksdb column family operation failed";
+const SER_ERROR: &str = "critical: stored object serialization failed";
+const DESER_ERROR: &str = "critical: stored object deserialization failed";
+
+/// Disk storage DB module
+///
+/// Contains a `RocksDB` DB instance
+pub(crate) struct StorageDB {
+    db: DB,
+    /// the changes are not persisted anymore
+    closed: AtomicBool,
+}
+
+impl Debug for StorageDB {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#?}", self.db)
+    }
+}
+
+impl StorageDB {
+    /// Create and initialize a new `StorageDB`.
+    ///
+    /// # Arguments
+    /// * path: path to the desired disk storage db directory
+    pub fn new(path: PathBuf) -> Self {
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+
+        let db = DB::open_cf_descriptors(
+            &db_opts,
+            path,
+            vec![
+                ColumnFamilyDescriptor::new(BLOCKS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(OPERATIONS_CF, Options::default()),
+                ColumnFamilyDescriptor::new(ENDORSEMENTS_CF, Options::default()),
+            ],
+        )
+        .expect(OPEN_ERROR);
+
+        StorageDB {
+            db,
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Stops persisting the changes: the content of the DB stays as it is now
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+    }
+
+    /// Writes objects in the given column family, indexed by their id
+    fn put_objects<'a, T, U, I>(&self, cf_name: &str, objects: I)
+    where
+        T: Display + WrappedContent + 'a,
+        U: Id + 'a,
+        I: IntoIterator<Item = (&'a [u8], &'a Wrapped<T, U>)>,
+    {
+        if self.closed.load(Ordering::Relaxed) {
+            return;
+        }
+        let handle = self.db.cf_handle(cf_name).expect(CF_ERROR);
+        let serializer = WrappedSerializer::new();
+        let mut batch = WriteBatch::default();
+        for (key, object) in objects {
+            let mut bytes = Vec::new();
+            serializer.serialize(object, &mut bytes).expect(SER_ERROR);
+            batch.put_cf(handle, key, bytes);
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Deletes objects from the given column family
+    fn delete_objects<'a, I>(&self, cf_name: &str, keys: I)
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        if self.closed.load(Ordering::Relaxed) {
+            return;
+        }
+        let handle = self.db.cf_handle(cf_name).expect(CF_ERROR);
+        let mut batch = WriteBatch::default();
+        for key in keys {
+            batch.delete_cf(handle, key);
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Reads every object of the given column family
+    fn read_objects<T, U, DT>(
+        &self,
+        cf_name: &str,
+        deserializer: &WrappedDeserializer<T, DT>,
+    ) -> Vec<Wrapped<T, U>>
+    where
+        T: Display + WrappedContent,
+        U: Id,
+        DT: Deserializer<T>,
+    {
+        let handle = self.db.cf_handle(cf_name).expect(CF_ERROR);
+        self.db
+            .iterator_cf(handle, IteratorMode::Start)
+            .flatten()
+            .map(|(_key, value)| {
+                let (_rest, object) = deserializer
+                    .deserialize::<DeserializeError>(&value)
+                    .expect(DESER_ERROR);
+                object
+            })
+            .collect()
+    }
+
+    /// Persists blocks
+    pub fn put_blocks(&self, blocks: &[WrappedBlock]) {
+        self.put_objects(
+            BLOCKS_CF,
+            blocks
+                .iter()
+                .map(|block| (block.id.to_bytes().as_slice(), block)),
+        );
+    }
+
+    /// Deletes persisted blocks
+    pub fn delete_blocks(&self, ids: &[BlockId]) {
+        self.delete_objects(BLOCKS_CF, ids.iter().map(|id| id.to_bytes().as_slice()));
+    }
+
+    /// Persists operations
+    pub fn put_operations(&self, operations: &[WrappedOperation]) {
+        self.put_objects(
+            OPERATIONS_CF,
+            operations
+                .iter()
+                .map(|op| (op.id.to_bytes().as_slice(), op)),
+        );
+    }
+
+    /// Deletes persisted operations
+    pub fn delete_operations(&self, ids: &[OperationId]) {
+        self.delete_objects(OPERATIONS_CF, ids.iter().map(|id| id.to_bytes().as_slice()));
+    }
+
+    /// Persists endorsements
+    pub fn put_endorsements(&self, endorsements: &[WrappedEndorsement]) {
+        self.put_objects(
+            ENDORSEMENTS_CF,
+            endorsements
+                .iter()
+                .map(|endo| (endo.id.to_bytes().as_slice(), endo)),
+        );
+    }
+
+    /// Deletes persisted endorsements
+    pub fn delete_endorsements(&self, ids: &[EndorsementId]) {
+        self.delete_objects(
+            ENDORSEMENTS_CF,
+            ids.iter().map(|id| id.to_bytes().as_slice()),
+        );
+    }
+
+    /// Reads every persisted block
+    pub fn read_blocks(&self) -> Vec<WrappedBlock> {
+        self.read_objects(
+            BLOCKS_CF,
+            &WrappedDeserializer::new(BlockDeserializer::new(
+                THREAD_COUNT,
+                MAX_OPERATIONS_PER_BLOCK,
+                ENDORSEMENT_COUNT,
+            )),
+        )
+    }
+
+    /// Reads every persisted operation
+    pub fn read_operations(&self) -> Vec<WrappedOperation> {
+        self.read_objects(
+            OPERATIONS_CF,
+            &WrappedDeserializer::new(OperationDeserializer::new(
+                MAX_DATASTORE_VALUE_LENGTH,
+                MAX_FUNCTION_NAME_LENGTH,
+                MAX_PARAMETERS_SIZE,
+                MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+                MAX_OPERATION_DATASTORE_KEY_LENGTH,
+                MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+            )),
+        )
+    }
+
+    /// Reads every persisted endorsement
+    pub fn read_endorsements(&self) -> Vec<WrappedEndorsement> {
+        self.read_objects(
+            ENDORSEMENTS_CF,
+            &WrappedDeserializer::new(EndorsementDeserializer::new(
+                THREAD_COUNT,
+                ENDORSEMENT_COUNT,
+            )),
+        )
+    }
+}
@@ -5,12 +5,15 @@
 //!
 //! The `Storage` structure also has lists of object references held by the current instance of `Storage`.
 //! When no instance of `Storage` claims a reference to a given object anymore, that object is automatically removed from storage.
+//!
+//! The objects can also be persisted on disk, see `Storage::create_root_with_disk_backend`.
 
 #![warn(missing_docs)]
 #![feature(hash_drain_filter)]
 #![feature(map_try_insert)]
 
 mod block_indexes;
+mod disk;
 mod endorsement_indexes;
 mod operation_indexes;
 
@@ -18,18 +21,22 @@ mod operation_indexes;
 mod tests;
 
 use block_indexes::BlockIndexes;
+use disk::StorageDB;
 use endorsement_indexes::EndorsementIndexes;
 use massa_models::prehash::{CapacityAllocator, PreHashMap, PreHashSet, PreHashed};
+use massa_models::stats::OperationStage;
 use massa_models::wrapped::Id;
 use massa_models::{
     block::{BlockId, WrappedBlock},
     endorsement::{EndorsementId, WrappedEndorsement},
     operation::{OperationId, WrappedOperation},
 };
+use massa_time::MassaTime;
 use operation_indexes::OperationIndexes;
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::fmt::Debug;
 use std::hash::Hash;
+use std::path::PathBuf;
 use std::{collections::hash_map, sync::Arc};
 
 /// A storage system for objects (blocks, operations...), shared by various components.
@@ -54,6 +61,9 @@ pub struct Storage {
     local_used_ops: PreHashSet<OperationId>,
     /// locally used endorsement references
     local_used_endorsements: PreHashSet<EndorsementId>,
+
+    /// optional disk backend persisting the stored objects
+    disk: Option<Arc<StorageDB>>,
 }
 
 impl Debug for Storage {
@@ -108,6 +118,69 @@ impl Storage {
             local_used_blocks: Default::default(),
             local_used_ops: Default::default(),
             local_used_endorsements: Default::default(),
+            disk: None,
+        }
+    }
+
+    /// Creates a new root `Storage` instance persisting its objects in a `RocksDB` database at `path`.
+    /// Same usage restrictions as `create_root`.
+    ///
+    /// The objects that were still referenced when the disk backend was last closed are loaded back.
+    ///
+    /// # Returns
+    /// The root instance, holding no reference, and an instance holding a reference to each loaded object,
+    /// to be handed over to the components using them.
+    pub fn create_root_with_disk_backend(path: PathBuf) -> (Storage, Storage) {
+        let disk = StorageDB::new(path);
+        let blocks = disk.read_blocks();
+        let operations = disk.read_operations();
+        let endorsements = disk.read_endorsements();
+        let mut root = Storage::create_root();
+        root.disk = Some(Arc::new(disk));
+        let mut loaded = root.clone_without_refs();
+
+        // the loaded objects are already persisted: index them without writing them back
+        let block_ids: PreHashSet<BlockId> = blocks.iter().map(|block| block.id).collect();
+        let op_ids: PreHashSet<OperationId> = operations.iter().map(|op| op.id).collect();
+        let endorsement_ids: PreHashSet<EndorsementId> =
+            endorsements.iter().map(|endo| endo.id).collect();
+        {
+            let mut block_store = loaded.blocks.write();
+            blocks
+                .into_iter()
+                .for_each(|block| block_store.insert(block));
+            let mut op_store = loaded.operations.write();
+            operations.into_iter().for_each(|op| op_store.insert(op));
+            let mut endo_store = loaded.endorsements.write();
+            endorsements
+                .into_iter()
+                .for_each(|endo| endo_store.insert(endo));
+        }
+        Storage::internal_claim_refs(
+            &block_ids,
+            &mut loaded.block_owners.write(),
+            &mut loaded.local_used_blocks,
+        );
+        Storage::internal_claim_refs(
+            &op_ids,
+            &mut loaded.operation_owners.write(),
+            &mut loaded.local_used_ops,
+        );
+        Storage::internal_claim_refs(
+            &endorsement_ids,
+            &mut loaded.endorsement_owners.write(),
+            &mut loaded.local_used_endorsements,
+        );
+        (root, loaded)
+    }
+
+    /// Stops persisting the changes to the disk backend, if any.
+    ///
+    /// Called on node shutdown before the components release their references,
+    /// so that the objects they hold are loaded back on the next start.
+    pub fn close_disk_backend(&self) {
+        if let Some(disk) = &self.disk {
+            disk.close();
         }
     }
 
@@ -120,6 +193,7 @@ impl Storage {
             operation_owners: self.operation_owners.clone(),
             block_owners: self.block_owners.clone(),
             endorsement_owners: self.endorsement_owners.clone(),
+            disk: self.disk.clone(),
 
             // do not clone local ref lists
             local_used_ops: Default::default(),
@@ -269,6 +343,9 @@ impl Storage {
         }
         // if there are orphaned objects, remove them from storage
         if !orphaned_ids.is_empty() {
+            if let Some(disk) = &self.disk {
+                disk.delete_blocks(&orphaned_ids);
+            }
             let mut blocks = self.blocks.write();
             for b_id in orphaned_ids {
                 blocks.remove(&b_id);
@@ -280,6 +357,10 @@ impl Storage {
     /// Note that this also claims a local reference to the block
     pub fn store_block(&mut self, block: WrappedBlock) {
         let id = block.id;
+        // persist the block before locking the indexes, to not block their readers on disk writes
+        if let Some(disk) = &self.disk {
+            disk.put_blocks(std::slice::from_ref(&block));
+        }
         let mut owners = self.block_owners.write();
         let mut blocks = self.blocks.write();
         blocks.insert(block);
@@ -353,6 +434,9 @@ impl Storage {
         }
         // if there are orphaned objects, remove them from storage
         if !orphaned_ids.is_empty() {
+            if let Some(disk) = &self.disk {
+                disk.delete_operations(&orphaned_ids);
+            }
             let mut ops = self.operations.write();
             for id in orphaned_ids {
                 ops.remove(&id);
@@ -366,6 +450,9 @@ impl Storage {
         if operations.is_empty() {
             return;
         }
+        if let Some(disk) = &self.disk {
+            disk.put_operations(&operations);
+        }
         let mut owners = self.operation_owners.write();
         let mut op_store = self.operations.write();
         let ids: PreHashSet<OperationId> = operations.iter().map(|op| op.id).collect();
@@ -375,6 +462,19 @@ impl Storage {
         Storage::internal_claim_refs(&ids, &mut owners, &mut self.local_used_ops);
     }
 
+    /// Records that operations reached a stage, to measure the latency since their reception.
+    /// Operations that are not stored, or that already reached that stage, are ignored.
+    pub fn record_operation_stage<'a, I>(&self, ids: I, stage: OperationStage)
+    where
+        I: IntoIterator<Item = &'a OperationId>,
+    {
+        let now = MassaTime::now().expect("could not get current time");
+        let mut ops = self.operations.write();
+        for id in ids {
+            ops.record_stage(id, stage, now);
+        }
+    }
+
     /// Gets a read reference to the operations index
     pub fn read_operations(&self) -> RwLockReadGuard<OperationIndexes> {
         self.operations.read()
@@ -451,6 +551,9 @@ impl Storage {
         }
         // if there are orphaned objects, remove them from storage
         if !orphaned_ids.is_empty() {
+            if let Some(disk) = &self.disk {
+                disk.delete_endorsements(&orphaned_ids);
+            }
             let mut endos = self.endorsements.write();
             for id in orphaned_ids {
                 endos.remove(&id);
@@ -464,6 +567,9 @@ impl Storage {
         if endorsements.is_empty() {
             return;
         }
+        if let Some(disk) = &self.disk {
+            disk.put_endorsements(&endorsements);
+        }
         let mut owners = self.endorsement_owners.write();
         let mut endo_store = self.endorsements.write();
         let ids: PreHashSet<EndorsementId> = endorsements.iter().map(|op| op.id).collect();
@@ -4,7 +4,9 @@ use massa_models::{
     address::Address,
     operation::{OperationId, OperationPrefixId, WrappedOperation},
     prehash::{PreHashMap, PreHashSet},
+    stats::{OperationLatencyStats, OperationStage},
 };
+use massa_time::MassaTime;
 
 /// Container for all operations and different indexes.
 /// Note: The structure can evolve and store more indexes.
@@ -16,6 +18,10 @@ pub struct OperationIndexes {
     index_by_creator: PreHashMap<Address, PreHashSet<OperationId>>,
     /// Structure indexing operations by ID prefix
     index_by_prefix: PreHashMap<OperationPrefixId, PreHashSet<OperationId>>,
+    /// Reception time of the operations, and stages for which their latency was already recorded
+    receptions: PreHashMap<OperationId, (MassaTime, Vec<OperationStage>)>,
+    /// Latencies from operation reception to each stage
+    latency_stats: OperationLatencyStats,
 }
 
 impl OperationIndexes {
@@ -24,6 +30,10 @@ impl OperationIndexes {
     /// * `operation`: the operation to insert
     pub(crate) fn insert(&mut self, operation: WrappedOperation) {
         if let Ok(o) = self.operations.try_insert(operation.id, operation) {
+            // record the reception time
+            if let Ok(now) = MassaTime::now() {
+                self.receptions.insert(o.id, (now, Vec::new()));
+            }
             // update creator index
             self.index_by_creator
                 .entry(o.creator_address)
@@ -42,6 +52,7 @@ impl OperationIndexes {
     /// * `operation_id`: the operation id to remove
     pub(crate) fn remove(&mut self, operation_id: &OperationId) -> Option<WrappedOperation> {
         if let Some(o) = self.operations.remove(operation_id) {
+            self.receptions.remove(operation_id);
             // update creator index
             if let hash_map::Entry::Occupied(mut occ) =
                 self.index_by_creator.entry(o.creator_address)
@@ -95,4 +106,28 @@ impl OperationIndexes {
     ) -> Option<&PreHashSet<OperationId>> {
         self.index_by_prefix.get(prefix)
     }
+
+    /// Records the time it took for an operation to reach `stage` since its reception.
+    /// Only the first time an operation reaches a stage is recorded.
+    pub(crate) fn record_stage(&mut self, id: &OperationId, stage: OperationStage, now: MassaTime) {
+        let (Some(op), Some((received_at, recorded_stages))) =
+            (self.operations.get(id), self.receptions.get_mut(id))
+        else {
+            return;
+        };
+        if recorded_stages.contains(&stage) {
+            return;
+        }
+        recorded_stages.push(stage);
+        self.latency_stats.record(
+            stage,
+            op.content.op.type_name(),
+            now.saturating_sub(*received_at),
+        );
+    }
+
+    /// Gets the latencies from operation reception to each stage
+    pub fn get_latency_stats(&self) -> &OperationLatencyStats {
+        &self.latency_stats
+    }
 }
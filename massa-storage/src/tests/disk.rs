@@ -0,0 +1,45 @@
+use crate::Storage;
+use massa_factory_exports::test_exports::create_empty_block;
+use massa_models::{prehash::PreHashSet, slot::Slot};
+use massa_signature::KeyPair;
+use tempfile::TempDir;
+
+#[test]
+/// Store blocks, close the disk backend and load them back in a new root.
+fn test_disk_backend_reload() {
+    let disk_path = TempDir::new().expect("cannot create temp directory");
+    let kept_block = create_empty_block(&KeyPair::generate(), &Slot::new(0, 0));
+    let dropped_block = create_empty_block(&KeyPair::generate(), &Slot::new(0, 1));
+    {
+        let (mut storage, _loaded) =
+            Storage::create_root_with_disk_backend(disk_path.path().to_path_buf());
+        storage.store_block(kept_block.clone());
+        storage.store_block(dropped_block.clone());
+
+        // a block released before closing is removed from disk
+        let mut ids = PreHashSet::default();
+        ids.insert(dropped_block.id);
+        storage.drop_block_refs(&ids);
+
+        // the blocks still referenced when closing stay on disk
+        storage.close_disk_backend();
+    }
+
+    // the loaded blocks are referenced by the returned instance only, not by the root
+    let (root, loaded) = Storage::create_root_with_disk_backend(disk_path.path().to_path_buf());
+    assert!(root.get_block_refs().is_empty());
+    assert!(loaded.get_block_refs().contains(&kept_block.id));
+    assert!(!loaded.get_block_refs().contains(&dropped_block.id));
+    {
+        let blocks = root.read_blocks();
+        let stored_block = blocks.get(&kept_block.id).unwrap();
+        assert_eq!(stored_block.serialized_data, kept_block.serialized_data);
+    }
+
+    // once handed over, the blocks are released with the component references
+    let mut component_storage = root.clone_without_refs();
+    component_storage.extend(loaded);
+    assert!(root.read_blocks().get(&kept_block.id).is_some());
+    drop(component_storage);
+    assert!(root.read_blocks().get(&kept_block.id).is_none());
+}
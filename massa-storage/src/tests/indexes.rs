@@ -1,6 +1,13 @@
 use crate::Storage;
 use massa_factory_exports::test_exports::create_empty_block;
-use massa_models::{address::Address, slot::Slot};
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    operation::{Operation, OperationSerializer, OperationType},
+    slot::Slot,
+    stats::OperationStage,
+    wrapped::WrappedContent,
+};
 use massa_signature::KeyPair;
 
 #[test]
@@ -46,3 +53,29 @@ fn test_block_fail_find() {
         .get_blocks_created_by(&Address::from_public_key(&keypair2.get_public_key()))
         .is_none());
 }
+
+#[test]
+fn test_operation_latency_stats() {
+    let mut storage = Storage::create_root();
+    let op = Operation::new_wrapped(
+        Operation {
+            fee: Amount::zero(),
+            expire_period: 10,
+            op: OperationType::RollBuy { roll_count: 1 },
+        },
+        OperationSerializer::new(),
+        &KeyPair::generate(),
+    )
+    .unwrap();
+
+    storage.store_operations(vec![op.clone()]);
+    // only the first time a stage is reached is recorded
+    storage.record_operation_stage(&[op.id], OperationStage::PoolAdmission);
+    storage.record_operation_stage(&[op.id], OperationStage::PoolAdmission);
+
+    let ops = storage.read_operations();
+    let stats = ops.get_latency_stats();
+    assert_eq!(stats.pool_admission["RollBuy"].count, 1);
+    assert!(stats.block_inclusion.is_empty());
+    assert!(stats.final_execution.is_empty());
+}
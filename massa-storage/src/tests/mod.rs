@@ -1,3 +1,4 @@
 mod basic;
+mod disk;
 mod indexes;
 mod references;
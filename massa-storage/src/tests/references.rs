@@ -1,6 +1,12 @@
 use crate::Storage;
 use massa_factory_exports::test_exports::create_empty_block;
-use massa_models::{prehash::PreHashSet, slot::Slot};
+use massa_models::{
+    amount::Amount,
+    operation::{Operation, OperationSerializer, OperationType, WrappedOperation},
+    prehash::PreHashSet,
+    slot::Slot,
+    wrapped::WrappedContent,
+};
 use massa_signature::KeyPair;
 
 #[test]
@@ -74,3 +80,73 @@ fn test_retrieve_all_ref_dropped_automatically() {
         assert!(blocks.get(&block.id).is_none());
     };
 }
+
+fn create_operation() -> WrappedOperation {
+    Operation::new_wrapped(
+        Operation {
+            fee: Amount::zero(),
+            expire_period: 10,
+            op: OperationType::RollBuy { roll_count: 1 },
+        },
+        OperationSerializer::new(),
+        &KeyPair::generate(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_shared_operation_kept_until_last_owner_drops() {
+    let mut storage = Storage::create_root();
+    let op = create_operation();
+    let ids: PreHashSet<_> = vec![op.id].into_iter().collect();
+
+    storage.store_operations(vec![op.clone()]);
+    let mut storage2 = storage.clone_without_refs();
+    assert_eq!(storage2.claim_operation_refs(&ids), ids);
+
+    // the first owner releases the operation, it is still held by the second one
+    storage.drop_operation_refs(&ids);
+    assert!(storage.get_op_refs().is_empty());
+    assert!(storage.read_operations().contains(&op.id));
+
+    // dropping a ref that is not owned locally is ignored
+    storage.drop_operation_refs(&ids);
+    assert!(storage.read_operations().contains(&op.id));
+
+    storage2.drop_operation_refs(&ids);
+    assert!(!storage.read_operations().contains(&op.id));
+
+    // a pruned object can not be claimed anymore
+    assert!(storage2.claim_operation_refs(&ids).is_empty());
+}
+
+#[test]
+fn test_split_off_and_extend() {
+    let mut storage = Storage::create_root();
+    let block = create_empty_block(&KeyPair::generate(), &Slot::new(0, 0));
+    let op = create_operation();
+    let block_ids: PreHashSet<_> = vec![block.id].into_iter().collect();
+    let op_ids: PreHashSet<_> = vec![op.id].into_iter().collect();
+
+    storage.store_block(block.clone());
+    storage.store_operations(vec![op.clone()]);
+
+    // move the block ref to another instance without changing the owner count
+    let mut block_storage = storage.split_off(&block_ids, &Default::default(), &Default::default());
+    assert!(storage.get_block_refs().is_empty());
+    assert_eq!(block_storage.get_block_refs(), &block_ids);
+    assert_eq!(storage.get_op_refs(), &op_ids);
+
+    // hand the op ref over to the block storage
+    block_storage.extend(storage);
+    assert_eq!(block_storage.get_op_refs(), &op_ids);
+    assert!(block_storage.read_operations().contains(&op.id));
+
+    drop(block_storage.clone());
+    assert!(block_storage.read_blocks().contains(&block.id));
+
+    let pruning_storage = block_storage.clone_without_refs();
+    drop(block_storage);
+    assert!(!pruning_storage.read_blocks().contains(&block.id));
+    assert!(!pruning_storage.read_operations().contains(&op.id));
+}
@@ -0,0 +1,108 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Sources of the current time.
+//!
+//! The workers waiting for the slots read the time through a `MassaClock` so that tests can
+//! replace the system clock with a `MockClock` and advance the time instantly.
+
+use crate::{MassaTime, TimeError};
+#[cfg(any(test, feature = "testing"))]
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use std::time::Duration;
+
+/// Source of the current UNIX timestamp
+pub trait MassaClock: Send + Sync {
+    /// Returns the current UNIX timestamp
+    fn now(&self) -> Result<MassaTime, TimeError>;
+
+    /// Returns the real duration to wait before checking again whether `deadline` is reached.
+    /// A zero duration means the deadline is reached.
+    fn duration_until(&self, deadline: MassaTime) -> Result<Duration, TimeError>;
+}
+
+/// Clock reading the system time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl MassaClock for SystemClock {
+    fn now(&self) -> Result<MassaTime, TimeError> {
+        MassaTime::now()
+    }
+
+    fn duration_until(&self, deadline: MassaTime) -> Result<Duration, TimeError> {
+        Ok(deadline.saturating_sub(MassaTime::now()?).to_duration())
+    }
+}
+
+/// Clock whose time only changes when the test sets or advances it
+#[cfg(any(test, feature = "testing"))]
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    time: Arc<AtomicU64>,
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl MockClock {
+    /// Real duration the workers wait before checking again a deadline that is not reached yet
+    pub const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+    /// Creates a clock starting at `time`
+    pub fn new(time: MassaTime) -> Self {
+        MockClock {
+            time: Arc::new(AtomicU64::new(time.to_millis())),
+        }
+    }
+
+    /// Sets the time of the clock
+    pub fn set(&self, time: MassaTime) {
+        self.time.store(time.to_millis(), Ordering::SeqCst);
+    }
+
+    /// Advances the time of the clock by `duration`
+    pub fn advance(&self, duration: MassaTime) {
+        self.time.fetch_add(duration.to_millis(), Ordering::SeqCst);
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl MassaClock for MockClock {
+    fn now(&self) -> Result<MassaTime, TimeError> {
+        Ok(MassaTime::from_millis(self.time.load(Ordering::SeqCst)))
+    }
+
+    fn duration_until(&self, deadline: MassaTime) -> Result<Duration, TimeError> {
+        if deadline <= self.now()? {
+            Ok(Duration::ZERO)
+        } else {
+            Ok(Self::POLL_INTERVAL)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock() {
+        let clock = MockClock::new(MassaTime::from_millis(1_000));
+        assert_eq!(clock.now().unwrap(), MassaTime::from_millis(1_000));
+        assert_eq!(
+            clock.duration_until(MassaTime::from_millis(1_500)).unwrap(),
+            MockClock::POLL_INTERVAL
+        );
+
+        clock.clone().advance(MassaTime::from_millis(500));
+        assert_eq!(clock.now().unwrap(), MassaTime::from_millis(1_500));
+        assert_eq!(
+            clock.duration_until(MassaTime::from_millis(1_500)).unwrap(),
+            Duration::ZERO
+        );
+
+        clock.set(MassaTime::from_millis(10));
+        assert_eq!(clock.now().unwrap(), MassaTime::from_millis(10));
+    }
+}
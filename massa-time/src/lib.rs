@@ -4,7 +4,11 @@
 #![warn(unused_crate_dependencies)]
 #![feature(bound_map)]
 
+mod clock;
 mod error;
+#[cfg(any(test, feature = "testing"))]
+pub use clock::MockClock;
+pub use clock::{MassaClock, SystemClock};
 pub use error::TimeError;
 use massa_serialization::{Deserializer, Serializer, U64VarIntDeserializer, U64VarIntSerializer};
 use nom::error::{context, ContextError, ParseError};
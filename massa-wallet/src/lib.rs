@@ -6,10 +6,12 @@
 #![feature(map_try_insert)]
 
 pub use error::WalletError;
+pub use operation_builder::{to_operation_input, OperationBuilder};
 
 use massa_cipher::{decrypt, encrypt};
 use massa_hash::Hash;
 use massa_models::address::Address;
+use massa_models::amount::Amount;
 use massa_models::composite::PubkeySig;
 use massa_models::operation::{Operation, OperationSerializer, WrappedOperation};
 use massa_models::prehash::{PreHashMap, PreHashSet};
@@ -19,6 +21,7 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 mod error;
+mod operation_builder;
 
 /// Contains the keypairs created in the wallet.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -91,6 +94,13 @@ impl Wallet {
         Ok(addrs)
     }
 
+    /// Generates a new keypair, adds it to the wallet and returns its address.
+    /// The wallet file is updated.
+    pub fn generate_keypair(&mut self) -> Result<Address, WalletError> {
+        let addrs = self.add_keypairs(vec![KeyPair::generate()])?;
+        Ok(addrs[0])
+    }
+
     /// Removes wallet entries given a list of addresses. Missing entries are ignored.
     /// The wallet file is updated.
     pub fn remove_addresses(&mut self, addresses: &Vec<Address>) -> Result<(), WalletError> {
@@ -148,6 +158,16 @@ impl Wallet {
             .ok_or_else(|| WalletError::MissingKeyError(address))?;
         Ok(Operation::new_wrapped(content, OperationSerializer::new(), sender_keypair).unwrap())
     }
+
+    /// Returns a builder of operations sent by the given address of the wallet
+    pub fn operation_builder(
+        &self,
+        sender: Address,
+        fee: Amount,
+        expire_period: u64,
+    ) -> OperationBuilder<'_> {
+        OperationBuilder::new(self, sender, fee, expire_period)
+    }
 }
 
 impl std::fmt::Display for Wallet {
@@ -0,0 +1,93 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Helpers to build and sign operations with the keys of the wallet
+
+use crate::{Wallet, WalletError};
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::api::OperationInput;
+use massa_models::operation::{Operation, OperationType, WrappedOperation};
+
+/// Builds operations sent by an address of the wallet, with a common fee and expiration period.
+///
+/// The resulting operations are signed and can be turned into an [`OperationInput`]
+/// with [`to_operation_input`] to be submitted to the node API.
+pub struct OperationBuilder<'a> {
+    wallet: &'a Wallet,
+    sender: Address,
+    fee: Amount,
+    expire_period: u64,
+}
+
+impl<'a> OperationBuilder<'a> {
+    /// Creates a builder for operations sent by `sender`
+    pub fn new(wallet: &'a Wallet, sender: Address, fee: Amount, expire_period: u64) -> Self {
+        OperationBuilder {
+            wallet,
+            sender,
+            fee,
+            expire_period,
+        }
+    }
+
+    /// Signs an operation of the given type
+    pub fn build(&self, op: OperationType) -> Result<WrappedOperation, WalletError> {
+        self.wallet.create_operation(
+            Operation {
+                fee: self.fee,
+                expire_period: self.expire_period,
+                op,
+            },
+            self.sender,
+        )
+    }
+
+    /// Signs a transfer of `amount` coins to `recipient_address`
+    pub fn transaction(
+        &self,
+        recipient_address: Address,
+        amount: Amount,
+    ) -> Result<WrappedOperation, WalletError> {
+        self.build(OperationType::Transaction {
+            recipient_address,
+            amount,
+        })
+    }
+
+    /// Signs the purchase of `roll_count` rolls
+    pub fn roll_buy(&self, roll_count: u64) -> Result<WrappedOperation, WalletError> {
+        self.build(OperationType::RollBuy { roll_count })
+    }
+
+    /// Signs the sale of `roll_count` rolls
+    pub fn roll_sell(&self, roll_count: u64) -> Result<WrappedOperation, WalletError> {
+        self.build(OperationType::RollSell { roll_count })
+    }
+
+    /// Signs a call to the function `target_func` of the smart contract at `target_addr`
+    pub fn call_sc(
+        &self,
+        target_addr: Address,
+        target_func: String,
+        param: Vec<u8>,
+        max_gas: u64,
+        coins: Amount,
+    ) -> Result<WrappedOperation, WalletError> {
+        self.build(OperationType::CallSC {
+            target_addr,
+            target_func,
+            param,
+            max_gas,
+            coins,
+        })
+    }
+}
+
+/// Converts a signed operation into the input expected by the `send_operations` API endpoint
+pub fn to_operation_input(op: WrappedOperation) -> OperationInput {
+    OperationInput {
+        creator_public_key: op.creator_public_key,
+        serialized_content: op.serialized_data,
+        signature: op.signature,
+    }
+}